@@ -0,0 +1,108 @@
+//! JSON event output for `--json`.
+//!
+//! Every event is a single line on stdout with an `event` field:
+//! - `action_started`: `category`, `action`, `title`, `steps`
+//! - `step_started`: `index` (zero-based), `total`, `description`
+//! - `output`: `stream` (`stdout` or `stderr`), `text`
+//! - `download_progress`: `downloaded`, `total` (0 if unknown), `speed` (bytes/s)
+//! - `step_finished`: `index`, `success`, `exit_code` (null if unknown)
+//! - `finished`: `success`, `message`
+//! - `error`: `message`, for failures before the action starts
+
+use crate::core::download::DownloadState;
+use crate::ui::task_runner::headless::Reporter;
+use crate::ui::task_runner::{Command, CommandResult};
+use std::io::Write;
+use xero_core::json::string;
+
+/// Write one event line. Fields are `(name, already encoded value)`.
+fn emit(event: &str, fields: &[(&str, String)]) {
+    let mut line = format!("{{\"event\":{}", string(event));
+    for (name, value) in fields {
+        line.push_str(&format!(",{}:{}", string(name), value));
+    }
+    line.push('}');
+
+    let mut stdout = std::io::stdout().lock();
+    let _ = writeln!(stdout, "{}", line);
+    let _ = stdout.flush();
+}
+
+pub fn action_started(category: &str, action: &str, title: &str, steps: usize) {
+    emit(
+        "action_started",
+        &[
+            ("category", string(category)),
+            ("action", string(action)),
+            ("title", string(title)),
+            ("steps", steps.to_string()),
+        ],
+    );
+}
+
+pub fn error(message: &str) {
+    emit("error", &[("message", string(message))]);
+}
+
+/// Reports headless runner progress as JSON events.
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn step_started(&mut self, index: usize, total: usize, command: &Command) {
+        emit(
+            "step_started",
+            &[
+                ("index", index.to_string()),
+                ("total", total.to_string()),
+                ("description", string(&command.description)),
+            ],
+        );
+    }
+
+    fn output(&mut self, text: &str, stderr: bool) {
+        let stream = if stderr { "stderr" } else { "stdout" };
+        emit(
+            "output",
+            &[("stream", string(stream)), ("text", string(text))],
+        );
+    }
+
+    fn download_progress(&mut self, state: &DownloadState) {
+        emit(
+            "download_progress",
+            &[
+                ("downloaded", state.downloaded.to_string()),
+                ("total", state.total.to_string()),
+                ("speed", format!("{:.0}", state.speed)),
+            ],
+        );
+    }
+
+    fn step_finished(&mut self, index: usize, result: &CommandResult) {
+        let (success, exit_code) = match result {
+            CommandResult::Success => (true, Some(0)),
+            CommandResult::Failure { exit_code } => (false, *exit_code),
+        };
+        emit(
+            "step_finished",
+            &[
+                ("index", index.to_string()),
+                ("success", success.to_string()),
+                (
+                    "exit_code",
+                    exit_code.map_or_else(|| "null".to_string(), |c| c.to_string()),
+                ),
+            ],
+        );
+    }
+
+    fn finished(&mut self, success: bool, message: &str) {
+        emit(
+            "finished",
+            &[
+                ("success", success.to_string()),
+                ("message", string(message)),
+            ],
+        );
+    }
+}
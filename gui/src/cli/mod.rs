@@ -0,0 +1,202 @@
+//! Command line interface for running toolkit actions without the GUI.
+//!
+//! Invoked as `xero-toolkit-cli <category> <action>` (a symlink to the main
+//! binary) or `xero-toolkit --cli <category> <action>`. Actions run through
+//! the task runner's headless backend, so they execute exactly the steps
+//! the matching GUI button would.
+//!
+//! With `--json`, progress is written to stdout as one JSON event per line
+//! (see [`json`]) for scripts and configuration management tools.
+
+mod json;
+
+use crate::actions;
+use crate::config;
+use crate::core::download::{format_bytes, format_speed, DownloadState};
+use crate::core::task_runner::{restart_needed, Restart};
+use crate::ui::task_runner::headless::{run_headless, Reporter};
+use crate::ui::task_runner::{Command, CommandResult};
+use std::io::Write;
+
+/// Name of the CLI symlink.
+const CLI_NAME: &str = "xero-toolkit-cli";
+
+/// Whether the process was started in CLI mode.
+pub fn is_cli_invocation(args: &[String]) -> bool {
+    let invoked_as_cli = args
+        .first()
+        .and_then(|arg0| std::path::Path::new(arg0).file_name())
+        .is_some_and(|name| name == CLI_NAME);
+    invoked_as_cli || args.get(1).is_some_and(|a| a == "--cli")
+}
+
+fn print_usage() {
+    println!("Usage: {} [--json] <category> <action>", CLI_NAME);
+    println!("       {} list", CLI_NAME);
+    println!();
+    println!("Runs a toolkit action without the GUI. Use `list` to see all actions.");
+    println!();
+    println!("Options:");
+    println!("  --json    Print progress as JSON events, one per line");
+}
+
+fn print_actions() {
+    let mut category = "";
+    for action in actions::ACTIONS {
+        if action.category != category {
+            category = action.category;
+            println!("{}:", category);
+        }
+        let installed = if action.is_installed() == Some(true) {
+            " (installed)"
+        } else {
+            ""
+        };
+        println!("  {:<16} {}{}", action.id, action.description, installed);
+    }
+}
+
+fn report_error(json: bool, message: &str) {
+    if json {
+        json::error(message);
+    } else {
+        eprintln!("{}", message);
+    }
+}
+
+/// Run the CLI and return the process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let json = args.iter().skip(1).any(|a| a == "--json");
+    let args: Vec<&str> = args
+        .iter()
+        .skip(1)
+        .map(String::as_str)
+        .filter(|a| *a != "--cli" && *a != "--json")
+        .collect();
+
+    match args.as_slice() {
+        [] | ["help"] | ["-h"] | ["--help"] => {
+            print_usage();
+            0
+        }
+        ["list"] => {
+            print_actions();
+            0
+        }
+        [category, id] => {
+            let Some(action) = actions::find(category, id) else {
+                report_error(json, &format!("Unknown action: {} {}", category, id));
+                if !json {
+                    eprintln!("Run `{} list` to see available actions.", CLI_NAME);
+                }
+                return 2;
+            };
+
+            if let Err(e) = config::env::init() {
+                report_error(
+                    json,
+                    &format!("Failed to initialize environment variables: {}", e),
+                );
+                return 1;
+            }
+
+            if let Some(message) = action.requirements_error() {
+                report_error(json, &message);
+                return 1;
+            }
+
+            let commands = action.commands_with_hooks();
+            let restart = restart_needed(commands.commands());
+            let success = if json {
+                json::action_started(action.category, action.id, action.title, commands.len());
+                run_headless(commands, &mut json::JsonReporter)
+            } else {
+                println!(":: {}", action.title);
+                run_headless(commands, &mut TextReporter::default())
+            };
+            match restart {
+                Some(Restart::Relogin) if success && !json => {
+                    println!("==> Log out and back in for the changes to take effect.")
+                }
+                Some(Restart::Reboot) if success && !json => {
+                    println!("==> Reboot for the changes to take effect.")
+                }
+                _ => {}
+            }
+            if success {
+                0
+            } else {
+                1
+            }
+        }
+        _ => {
+            print_usage();
+            2
+        }
+    }
+}
+
+/// Prints step progress and command output to the terminal.
+#[derive(Default)]
+struct TextReporter {
+    /// A download progress line is on screen and needs a newline.
+    progress_shown: bool,
+}
+
+impl TextReporter {
+    fn end_progress(&mut self) {
+        if self.progress_shown {
+            eprintln!();
+            self.progress_shown = false;
+        }
+    }
+}
+
+impl Reporter for TextReporter {
+    fn step_started(&mut self, index: usize, total: usize, command: &Command) {
+        println!("==> [{}/{}] {}", index + 1, total, command.description);
+    }
+
+    fn output(&mut self, text: &str, stderr: bool) {
+        self.end_progress();
+        if stderr {
+            eprint!("{}", text);
+        } else {
+            print!("{}", text);
+        }
+    }
+
+    fn download_progress(&mut self, state: &DownloadState) {
+        if state.total > 0 {
+            eprint!(
+                "\r    {} / {} ({})    ",
+                format_bytes(state.downloaded),
+                format_bytes(state.total),
+                format_speed(state.speed)
+            );
+        } else {
+            eprint!("\r    {}    ", format_bytes(state.downloaded));
+        }
+        let _ = std::io::stderr().flush();
+        self.progress_shown = true;
+    }
+
+    fn step_finished(&mut self, _index: usize, result: &CommandResult) {
+        self.end_progress();
+        if let CommandResult::Failure { exit_code } = result {
+            match exit_code {
+                Some(code) => eprintln!("[Exit code: {}]", code),
+                None => eprintln!("[Exit code: unknown]"),
+            }
+        }
+    }
+
+    fn finished(&mut self, success: bool, message: &str) {
+        self.end_progress();
+        if success {
+            println!("==> {}", message);
+        } else {
+            eprintln!("==> {}", message);
+        }
+    }
+}
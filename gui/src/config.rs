@@ -1,11 +1,9 @@
 //! Centralized configuration and constants for the application.
+//!
+//! Settings that don't need GTK live in `xero_core::config` and are
+//! re-exported here along with the GUI's own.
 
-/// Application information constants.
-pub mod app_info {
-    pub const NAME: &str = "xero-toolkit";
-    pub const ID: &str = "xyz.xerolinux.xero-toolkit";
-    pub const VERSION: &str = env!("CARGO_PKG_VERSION");
-}
+pub use xero_core::config::*;
 
 /// Sidebar configuration.
 pub mod sidebar {
@@ -13,120 +11,27 @@ pub mod sidebar {
     pub const MAX_WIDTH: i32 = 400;
 }
 
-/// External links.
-pub mod links {
-    pub const DISCORD: &str = "https://discord.gg/2pdhYusbKV";
-    pub const YOUTUBE: &str = "https://www.youtube.com/@MurderFromMars";
-    pub const GITHUB: &str = "https://github.com/MurderFromMars";
-    pub const TOOLKIT_REPO: &str = "https://github.com/MurderFromMars/CyberXero-Toolkit.git";
+/// Background agent in the system tray.
+pub mod tray {
+    /// Delay before the first update check after the agent starts.
+    pub const FIRST_CHECK_DELAY_SECS: u32 = 5 * 60;
+    /// Interval between update checks.
+    pub const CHECK_INTERVAL_SECS: u32 = 6 * 60 * 60;
 }
 
-/// Binary paths for system executables.
-pub mod paths {
-    use std::path::PathBuf;
-
-    /// Path to the xero-authd daemon binary.
-    pub const DAEMON: &str = "/opt/xero-toolkit/xero-authd";
-
-    /// Path to the xero-auth client binary.
-    pub const CLIENT: &str = "/opt/xero-toolkit/xero-auth";
-
-    /// Path to the sources directory (contains scripts and systemd).
-    #[allow(dead_code)]
-    pub const SOURCES: &str = "/opt/xero-toolkit/sources";
-
-    /// Path to the scripts directory.
-    pub const SCRIPTS: &str = "/opt/xero-toolkit/sources/scripts";
-
-    /// Path to the systemd units directory.
-    pub const SYSTEMD: &str = "/opt/xero-toolkit/sources/systemd";
-
-    /// Path to the desktop file in system applications.
-    pub const DESKTOP_FILE: &str = "/usr/share/applications/xero-toolkit.desktop";
-
-    /// Path to the system-wide autostart desktop file.
-    pub const SYSTEM_AUTOSTART: &str = "/etc/xdg/autostart/xero-toolkit.desktop";
-
-    /// Get the daemon path as a PathBuf.
-    pub fn daemon() -> PathBuf {
-        PathBuf::from(DAEMON)
-    }
-
-    /// Get the client path as a PathBuf.
-    pub fn client() -> PathBuf {
-        PathBuf::from(CLIENT)
-    }
-
-    /// Get the sources path as a PathBuf.
-    #[allow(dead_code)]
-    pub fn sources() -> PathBuf {
-        PathBuf::from(SOURCES)
-    }
-
-    /// Get the scripts path as a PathBuf.
-    pub fn scripts() -> PathBuf {
-        PathBuf::from(SCRIPTS)
-    }
-
-    /// Get the systemd units path as a PathBuf.
-    pub fn systemd() -> PathBuf {
-        PathBuf::from(SYSTEMD)
-    }
-
-    /// Get the desktop file path as a PathBuf.
-    pub fn desktop_file() -> PathBuf {
-        PathBuf::from(DESKTOP_FILE)
-    }
-
-    /// Get the system autostart path as a PathBuf.
-    pub fn system_autostart() -> PathBuf {
-        PathBuf::from(SYSTEM_AUTOSTART)
-    }
-}
-
-/// Cached environment variables read at startup.
-pub mod env {
-    use std::sync::OnceLock;
-
-    static ENV: OnceLock<Env> = OnceLock::new();
-
-    /// Cached environment variables.
-    pub struct Env {
-        pub user: String,
-        pub home: String,
-    }
-
-    impl Env {
-        fn new() -> anyhow::Result<Self> {
-            Ok(Self {
-                user: std::env::var("USER")
-                    .map_err(|_| anyhow::anyhow!("USER environment variable is not set"))?,
-                home: std::env::var("HOME")
-                    .map_err(|_| anyhow::anyhow!("HOME environment variable is not set"))?,
-            })
-        }
-    }
-
-    /// Initialize environment variables. Must be called at application startup.
-    /// Returns an error if required environment variables (USER, HOME) are not set.
-    pub fn init() -> anyhow::Result<()> {
-        ENV.set(Env::new()?)
-            .map_err(|_| anyhow::anyhow!("Environment variables already initialized"))?;
-        Ok(())
-    }
-
-    /// Get the cached environment variables.
-    /// Panics if not initialized (call `init()` at application startup).
-    pub fn get() -> &'static Env {
-        ENV.get()
-            .expect("Environment variables not initialized. Call config::env::init() at startup.")
-    }
+/// Interactive terminal dialogs.
+pub mod terminal {
+    /// Lines of output kept for scrolling back.
+    pub const SCROLLBACK_LINES: i64 = 10_000;
 }
 
 /// Debug environment variables for seasonal effects.
 pub mod seasonal_debug {
     pub const ENABLE_SNOW: &str = "XERO_TOOLKIT_ENABLE_SNOW";
     pub const ENABLE_HALLOWEEN: &str = "XERO_TOOLKIT_ENABLE_HALLOWEEN";
+    pub const ENABLE_FIREWORKS: &str = "XERO_TOOLKIT_ENABLE_FIREWORKS";
+    pub const ENABLE_PETALS: &str = "XERO_TOOLKIT_ENABLE_PETALS";
+    pub const ENABLE_CONFETTI: &str = "XERO_TOOLKIT_ENABLE_CONFETTI";
 
     /// Check if an environment variable is set to enable an effect.
     /// Returns `Some(true)` if enabled, `Some(false)` if explicitly disabled, `None` if not set.
@@ -167,9 +72,13 @@ pub mod resources {
         pub const DOWNLOAD: &str = "/xyz/xerolinux/xero-toolkit/ui/dialogs/download_dialog.ui";
         pub const DOWNLOAD_SETUP: &str =
             "/xyz/xerolinux/xero-toolkit/ui/dialogs/download_setup_dialog.ui";
+        pub const FIRST_RUN: &str = "/xyz/xerolinux/xero-toolkit/ui/dialogs/first_run_dialog.ui";
         pub const SCHEDULER_SELECTION: &str =
             "/xyz/xerolinux/xero-toolkit/ui/dialogs/scheduler_selection_dialog.ui";
         pub const SELECTION: &str = "/xyz/xerolinux/xero-toolkit/ui/dialogs/selection_dialog.ui";
+        pub const PREFERENCES: &str =
+            "/xyz/xerolinux/xero-toolkit/ui/dialogs/preferences_dialog.ui";
+        pub const SHORTCUTS: &str = "/xyz/xerolinux/xero-toolkit/ui/dialogs/shortcuts_dialog.ui";
         pub const TASK_LIST: &str = "/xyz/xerolinux/xero-toolkit/ui/dialogs/task_list_dialog.ui";
         pub const TERMINAL: &str = "/xyz/xerolinux/xero-toolkit/ui/dialogs/terminal_dialog.ui";
         pub const WARNING: &str = "/xyz/xerolinux/xero-toolkit/ui/dialogs/warning_dialog.ui";
@@ -177,18 +86,31 @@ pub mod resources {
 
     /// Page/tab UI resources.
     pub mod tabs {
+        pub const BACKUP: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/backup.ui";
         pub const BIOMETRICS: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/biometrics.ui";
         pub const CONTAINERS_VMS: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/containers_vms.ui";
+        pub const CUSTOM: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/custom.ui";
         pub const CUSTOMIZATION: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/customization.ui";
+        pub const DEV_TOOLS: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/dev_tools.ui";
+        pub const DISKS: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/disks.ui";
         pub const DRIVERS: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/drivers.ui";
         pub const GAMESCOPE: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/gamescope.ui";
         pub const GAMING_TOOLS: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/gaming_tools.ui";
         pub const KERNEL_SCHEDULERS: &str =
             "/xyz/xerolinux/xero-toolkit/ui/tabs/kernel_schedulers.ui";
+        pub const LOCAL_AI: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/local_ai.ui";
         pub const MAIN_PAGE: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/main_page.ui";
         pub const MULTIMEDIA_TOOLS: &str =
             "/xyz/xerolinux/xero-toolkit/ui/tabs/multimedia_tools.ui";
+        pub const NETWORK_TOOLS: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/network_tools.ui";
+        pub const POWER_MANAGEMENT: &str =
+            "/xyz/xerolinux/xero-toolkit/ui/tabs/power_management.ui";
+        pub const SCHEDULED_MAINTENANCE: &str =
+            "/xyz/xerolinux/xero-toolkit/ui/tabs/scheduled_maintenance.ui";
         pub const SERVICING_SYSTEM_TWEAKS: &str =
             "/xyz/xerolinux/xero-toolkit/ui/tabs/servicing_system_tweaks.ui";
+        pub const SYSTEM_SETTINGS: &str =
+            "/xyz/xerolinux/xero-toolkit/ui/tabs/system_settings.ui";
+        pub const UNINSTALL: &str = "/xyz/xerolinux/xero-toolkit/ui/tabs/uninstall.ui";
     }
 }
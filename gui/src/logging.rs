@@ -0,0 +1,62 @@
+//! Logging to stdout, keeping the most recent lines for error reports.
+
+use log::{LevelFilter, Log, Metadata, Record};
+use simple_logger::SimpleLogger;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Number of log lines kept in memory.
+const HISTORY_LINES: usize = 200;
+
+static HISTORY: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Prints through `simple_logger` and records each line in [`HISTORY`].
+struct Logger {
+    inner: SimpleLogger,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        self.inner.log(record);
+
+        let line = format!(
+            "{:<5} [{}] {}",
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Ok(mut history) = HISTORY.lock() {
+            if history.len() == HISTORY_LINES {
+                history.pop_front();
+            }
+            history.push_back(line);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Install the logger, printing records up to `level`.
+pub fn init(level: LevelFilter) {
+    let inner = SimpleLogger::new().with_level(level);
+    log::set_max_level(inner.max_level());
+    log::set_boxed_logger(Box::new(Logger { inner })).expect("logger already initialized");
+}
+
+/// The last `count` log lines, oldest first.
+pub fn recent(count: usize) -> Vec<String> {
+    let Ok(history) = HISTORY.lock() else {
+        return Vec::new();
+    };
+    let skip = history.len().saturating_sub(count);
+    history.iter().skip(skip).cloned().collect()
+}
@@ -2,14 +2,33 @@
 
 use adw::prelude::*;
 use adw::Application;
+use gtk4::{gio, glib};
 use log::info;
 
+mod cli;
 mod config;
-mod core;
+mod logging;
 mod ui;
 
+use xero_core as core;
+use xero_core::{actions, i18n};
+
 fn main() {
-    simple_logger::SimpleLogger::new().init().unwrap();
+    let args: Vec<String> = std::env::args().collect();
+    if cli::is_cli_invocation(&args) {
+        // Keep the terminal for command output; the logger writes to stdout,
+        // which must carry nothing but events in JSON mode.
+        let level = if args.iter().any(|a| a == "--json") {
+            log::LevelFilter::Off
+        } else {
+            log::LevelFilter::Warn
+        };
+        logging::init(level);
+        std::process::exit(cli::run(&args));
+    }
+
+    logging::init(log::LevelFilter::Trace);
+    i18n::init();
 
     info!(
         "Starting {} v{}",
@@ -20,9 +39,28 @@ fn main() {
 
     let app = Application::builder()
         .application_id(config::app_info::ID)
+        .flags(gio::ApplicationFlags::HANDLES_COMMAND_LINE)
         .build();
 
+    app.add_main_option(
+        "page",
+        glib::Char::from(b'p'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "Open the given page, e.g. gaming_tools",
+        Some("PAGE"),
+    );
+    app.add_main_option(
+        "background",
+        glib::Char::from(b'b'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Start in the system tray without opening the window",
+        None,
+    );
+
     app.connect_activate(ui::setup_application_ui);
+    app.connect_command_line(ui::handle_command_line);
 
     app.run();
 }
@@ -0,0 +1,116 @@
+//! Recent activity on the main page.
+//!
+//! Lists the newest sequences from `core::history` with their outcome, a
+//! button to open the saved output log and one to run the same commands
+//! again. The list is rebuilt whenever the task runner finishes.
+
+use crate::core::history::{self, Entry, Outcome};
+use crate::ui::task_runner::{self, CommandSequence};
+use crate::ui::utils::extract_widget;
+use adw::prelude::*;
+use gtk4::{glib, ApplicationWindow, Builder, ListBox};
+use log::{info, warn};
+use std::cell::RefCell;
+
+/// Number of entries shown on the main page.
+const RECENT_COUNT: usize = 5;
+
+thread_local! {
+    /// Rebuilds the Recent Activity section.
+    static REFRESH: RefCell<Option<Box<dyn Fn()>>> = const { RefCell::new(None) };
+}
+
+/// Rebuild the Recent Activity section, if the main page is loaded.
+pub fn refresh() {
+    REFRESH.with(|refresh| {
+        if let Some(refresh) = refresh.borrow().as_ref() {
+            refresh();
+        }
+    });
+}
+
+/// Set up the Recent Activity section of the main page.
+pub fn setup_section(page_builder: &Builder, window: &ApplicationWindow) {
+    let section = extract_widget::<gtk4::Box>(page_builder, "activity_section");
+    let list = extract_widget::<ListBox>(page_builder, "activity_list");
+
+    let window = window.clone();
+    let refresh = move || populate(&section, &list, &window);
+    refresh();
+    REFRESH.with(|r| *r.borrow_mut() = Some(Box::new(refresh)));
+}
+
+fn populate(section: &gtk4::Box, list: &ListBox, window: &ApplicationWindow) {
+    list.remove_all();
+
+    let entries = history::recent(RECENT_COUNT);
+    section.set_visible(!entries.is_empty());
+
+    for entry in entries {
+        list.append(&entry_row(entry, window));
+    }
+}
+
+fn subtitle(entry: &Entry) -> String {
+    let when = glib::DateTime::from_unix_local(entry.timestamp)
+        .and_then(|time| time.format("%b %e, %H:%M"))
+        .map(|time| time.to_string())
+        .unwrap_or_default();
+    let outcome = match entry.outcome {
+        Outcome::Success => crate::i18n::gettext("Succeeded"),
+        Outcome::Failed => crate::i18n::gettext("Failed"),
+        Outcome::Cancelled => crate::i18n::gettext("Cancelled"),
+    };
+    format!("{} · {}", when, outcome)
+}
+
+fn entry_row(entry: Entry, window: &ApplicationWindow) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(crate::i18n::gettext(&entry.title))
+        .subtitle(subtitle(&entry))
+        .use_markup(false)
+        .build();
+
+    let icon = match entry.outcome {
+        Outcome::Success => "circle-check-symbolic",
+        Outcome::Failed => "triangle-exclamation-symbolic",
+        Outcome::Cancelled => "circle-xmark",
+    };
+    row.add_prefix(&gtk4::Image::from_icon_name(icon));
+
+    let log_button = gtk4::Button::builder()
+        .label(crate::i18n::gettext("View log"))
+        .valign(gtk4::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .sensitive(history::has_log(&entry))
+        .build();
+    if let Some(log) = entry.log.clone() {
+        log_button.connect_clicked(move |_| {
+            info!("Opening task log {}", log.display());
+            if let Err(e) = crate::core::package::open_url(&log.to_string_lossy()) {
+                warn!("Failed to open {}: {}", log.display(), e);
+            }
+        });
+    }
+    row.add_suffix(&log_button);
+
+    let run_button = gtk4::Button::builder()
+        .label(crate::i18n::gettext("Run again"))
+        .valign(gtk4::Align::Center)
+        .sensitive(!entry.commands.is_empty())
+        .build();
+    let window = window.clone();
+    run_button.connect_clicked(move |_| {
+        info!("Running '{}' again", entry.title);
+        let commands = entry
+            .commands
+            .iter()
+            .cloned()
+            .fold(CommandSequence::new(), CommandSequence::then)
+            .build();
+        task_runner::run(window.upcast_ref(), commands, &entry.title);
+    });
+    row.add_suffix(&run_button);
+
+    row
+}
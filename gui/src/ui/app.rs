@@ -9,11 +9,24 @@ use crate::ui::utils::extract_widget;
 use adw::prelude::*;
 use adw::Application;
 use gtk4::glib;
-use gtk4::{gio, ApplicationWindow, Builder, CssProvider, Stack};
+use gtk4::{gio, ApplicationWindow, Builder, Stack};
 use log::{error, info, warn};
+use std::cell::RefCell;
+
+thread_local! {
+    /// Context of the main window, once it's been built.
+    static CONTEXT: RefCell<Option<AppContext>> = const { RefCell::new(None) };
+}
 
 /// Initialize and set up main application UI.
 pub fn setup_application_ui(app: &Application) {
+    // Activating again (another launch, D-Bus activation) raises the
+    // existing window instead of building a second one
+    if let Some(window) = app.active_window() {
+        window.present();
+        return;
+    }
+
     info!("Initializing application components");
 
     setup_resources_and_theme();
@@ -27,10 +40,19 @@ pub fn setup_application_ui(app: &Application) {
     if let Err(e) = config::env::init() {
         error!("Failed to initialize environment variables: {}", e);
         window.present();
-        crate::ui::dialogs::error::show_error(&window, &format!("Failed to initialize environment variables: {}\n\nRequired environment variables (USER, HOME) are not set.", e));
+        crate::ui::dialogs::error::show_error(
+            &window,
+            &crate::i18n::gettext_f(
+                "Failed to initialize environment variables: {error}\n\nRequired environment variables (USER, HOME) are not set.",
+                &[("error", &e.to_string())],
+            ),
+        );
         return;
     }
 
+    // Read installed packages while the window is built, for page buttons
+    crate::ui::install_state::prefetch();
+
     // Extract tabs_container first for stack creation
     let tabs_container = extract_widget(&builder, "tabs_container");
 
@@ -44,6 +66,9 @@ pub fn setup_application_ui(app: &Application) {
     if let Some(first_page) = navigation::PAGES.first() {
         ctx.navigate_to_page(first_page.id);
     }
+    crate::ui::search::setup_search(&builder, &ctx);
+    crate::ui::shortcuts::setup(app, &builder, &window, &ctx);
+    CONTEXT.with(|c| *c.borrow_mut() = Some(ctx));
 
     // Apply seasonal effects (snow for December, Halloween for October, etc.)
     crate::ui::seasonal::apply_seasonal_effects(&window);
@@ -53,6 +78,14 @@ pub fn setup_application_ui(app: &Application) {
     // appears empty at a small size before the WM tiles it.
     window.present();
 
+    // Let other programs list and trigger actions over the session bus
+    crate::ui::dbus::setup_service(&window);
+
+    setup_background_toggle(app, &builder);
+    if config::user::load().run_in_background {
+        crate::ui::tray::start(app);
+    }
+
     // Perform system checks off the main thread so they don't block
     // window rendering. Results are sent back via an async channel.
     let (sender, receiver) = async_channel::bounded::<(core::system_check::DependencyCheckResult, bool)>(1);
@@ -61,11 +94,7 @@ pub fn setup_application_ui(app: &Application) {
         info!("Checking system dependencies (background thread)");
 
         let deps = core::system_check::check_dependencies();
-        let aur_ok = if !deps.has_missing_dependencies() {
-            core::aur::init()
-        } else {
-            false
-        };
+        let aur_ok = core::aur::init();
 
         let _ = sender.send_blocking((deps, aur_ok));
     });
@@ -73,21 +102,92 @@ pub fn setup_application_ui(app: &Application) {
     let window_clone = window.clone();
     glib::MainContext::default().spawn_local(async move {
         if let Ok((dep_result, aur_ok)) = receiver.recv().await {
-            if dep_result.has_missing_dependencies() {
-                warn!("Dependency check failed - missing dependencies");
-                core::system_check::show_dependency_error_dialog(&window_clone, &dep_result);
+            if aur_ok {
+                info!("AUR helper initialized successfully");
+            }
+            if crate::ui::dialogs::first_run::should_show() {
+                crate::ui::dialogs::first_run::show_first_run_dialog(&window_clone, &dep_result);
+                return;
+            } else if dep_result.has_missing_dependencies() {
+                warn!("Missing dependencies - affected actions are disabled");
+                crate::ui::dialogs::dependency_error::show_dependency_error_dialog(&window_clone, &dep_result);
             } else {
-                if aur_ok {
-                    info!("AUR helper initialized successfully");
-                }
                 info!("All dependency checks passed");
             }
+            // A task cut short when the toolkit last stopped
+            crate::ui::task_runner::offer_resume(window_clone.upcast_ref());
         }
     });
 
     info!("Xero Toolkit application startup complete");
 }
 
+/// Handle a command line passed to the primary instance.
+///
+/// Brings up the window, then opens the page named by `--page <id>` or by a
+/// `xero-toolkit://page/<id>` argument, so docs and welcome apps can link
+/// straight to a page. `--background` starts the tray agent instead of
+/// opening the window.
+pub fn handle_command_line(
+    app: &Application,
+    command_line: &gio::ApplicationCommandLine,
+) -> glib::ExitCode {
+    if command_line.options_dict().contains("background") {
+        if app.active_window().is_none() {
+            info!("Starting in the background");
+            crate::ui::tray::start(app);
+        }
+        return glib::ExitCode::SUCCESS;
+    }
+
+    let page = command_line
+        .options_dict()
+        .lookup::<String>("page")
+        .ok()
+        .flatten();
+    let link = command_line
+        .arguments()
+        .iter()
+        .skip(1)
+        .filter_map(|arg| arg.to_str())
+        .find(|arg| arg.contains("://"))
+        .map(String::from);
+
+    // The window still opens on a bad page or link, but the caller learns
+    // it wasn't understood
+    let mut status = glib::ExitCode::SUCCESS;
+    let page = match (page, link) {
+        (Some(page), _) if navigation::is_page(&page) => Some(page),
+        (Some(page), _) => {
+            eprintln!("Unknown page: {}", page);
+            status = glib::ExitCode::FAILURE;
+            None
+        }
+        (None, Some(link)) => {
+            let page = navigation::page_from_uri(&link).map(String::from);
+            if page.is_none() {
+                eprintln!("Unsupported link: {}", link);
+                status = glib::ExitCode::FAILURE;
+            }
+            page
+        }
+        (None, None) => None,
+    };
+
+    app.activate();
+
+    if let Some(page) = page {
+        info!("Opening page '{}' from command line", page);
+        CONTEXT.with(|c| {
+            if let Some(ctx) = c.borrow().as_ref() {
+                ctx.navigate_to_page(&page);
+            }
+        });
+    }
+
+    status
+}
+
 /// Set up resources and theme.
 fn setup_resources_and_theme() {
     info!("Setting up resources and theme");
@@ -104,13 +204,7 @@ fn setup_resources_and_theme() {
         theme.add_resource_path(config::resources::ICONS);
         info!("Icon theme paths configured");
 
-        let css_provider = CssProvider::new();
-        css_provider.load_from_resource(config::resources::CSS);
-        gtk4::style_context_add_provider_for_display(
-            &display,
-            &css_provider,
-            gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
-        );
+        crate::ui::theme::setup();
         info!("UI theme and styling loaded successfully");
     } else {
         warn!("No default display found - UI theming may not work properly");
@@ -124,6 +218,16 @@ fn create_main_window(app: &Application, builder: &Builder) -> ApplicationWindow
     window.set_application(Some(app));
     info!("Setting window icon to xero-toolkit");
     window.set_icon_name(Some("xero-toolkit"));
+
+    // With the tray agent running, closing only hides the window so it
+    // can be brought back from the tray
+    window.connect_close_request(|window| {
+        if crate::ui::tray::is_running() {
+            window.set_visible(false);
+            return glib::Propagation::Stop;
+        }
+        glib::Propagation::Proceed
+    });
     info!("Main application window created from UI resource");
 
     window
@@ -135,15 +239,15 @@ fn setup_ui_components(builder: &Builder, stack: Stack, window: &ApplicationWind
     let main_split_view = extract_widget(builder, "main_split_view");
     let sidebar_toggle = extract_widget(builder, "sidebar_toggle_button");
 
+    crate::ui::toast::setup(builder);
+    crate::ui::elevation::setup(builder);
+
     // Set up autostart toggle in sidebar
     setup_autostart_toggle(builder);
 
     // Set up about button
     setup_about_button(builder, window);
 
-    // Set up seasonal effects toggle
-    setup_seasonal_effects_toggle(builder, window);
-
     info!("All UI components successfully initialized from UI builder");
 
     let ui = UiComponents::new(stack, tabs_container, main_split_view, sidebar_toggle);
@@ -184,6 +288,33 @@ fn setup_autostart_toggle(builder: &Builder) {
     });
 }
 
+/// Set up the background agent toggle switch in the sidebar.
+fn setup_background_toggle(app: &Application, builder: &Builder) {
+    let switch = extract_widget::<gtk4::Switch>(builder, "switch_background");
+    switch.set_active(config::user::load().run_in_background);
+
+    let app = app.clone();
+    switch.connect_state_set(move |_switch, state| {
+        info!("Background agent toggle changed to: {}", state);
+
+        if let Err(e) = config::user::update(|s| s.run_in_background = state) {
+            warn!("Failed to save background setting: {}", e);
+            return glib::Propagation::Stop;
+        }
+        if state {
+            crate::ui::tray::start(&app);
+        } else {
+            crate::ui::tray::stop();
+        }
+        // The autostart entry starts in the tray only while this is on
+        if let Err(e) = core::autostart::refresh() {
+            warn!("Failed to update autostart entry: {}", e);
+        }
+
+        glib::Propagation::Proceed
+    });
+}
+
 /// Set up the about button in the header bar.
 fn setup_about_button(builder: &Builder, window: &ApplicationWindow) {
     use crate::ui::dialogs::about;
@@ -195,25 +326,3 @@ fn setup_about_button(builder: &Builder, window: &ApplicationWindow) {
         about::show_about_dialog(window_clone.upcast_ref());
     });
 }
-
-/// Set up the seasonal effects toggle button in the header bar.
-fn setup_seasonal_effects_toggle(builder: &Builder, _window: &ApplicationWindow) {
-    use crate::ui::seasonal;
-
-    let toggle = extract_widget::<gtk4::ToggleButton>(builder, "seasonal_effects_toggle");
-
-    // Show/hide button based on whether any effect is active
-    let has_active = seasonal::has_active_effect();
-    toggle.set_visible(has_active);
-    toggle.set_active(seasonal::are_effects_enabled());
-
-    // Connect toggle action
-    toggle.connect_toggled(move |btn| {
-        let enabled = btn.is_active();
-        seasonal::set_effects_enabled(enabled);
-        info!(
-            "Seasonal effects {}",
-            if enabled { "enabled" } else { "disabled" }
-        );
-    });
-}
@@ -0,0 +1,165 @@
+//! Batch mode on pages with many install buttons.
+//!
+//! With a page's Batch Mode toggle on, clicking one of its buttons queues
+//! the sequence it would run instead of running it, and marks the button
+//! as picked; clicking it again drops it. Run Selected combines the queue
+//! with `core::batch`, so the packages go in one transaction, and runs it
+//! as a single sequence.
+
+use crate::core::batch;
+use crate::i18n::gettext;
+use crate::ui::task_runner::{self, CommandSequence};
+use crate::ui::utils::try_extract_widget;
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button, Label, ToggleButton};
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// CSS class marking a picked button.
+const PICKED_CLASS: &str = "batch-picked";
+
+struct Queued {
+    button: Button,
+    title: String,
+    commands: CommandSequence,
+}
+
+struct Batch {
+    summary: Label,
+    run: Button,
+    queue: RefCell<Vec<Queued>>,
+    /// Page button clicked last, which the next queued sequence belongs to.
+    clicked: RefCell<Option<Button>>,
+}
+
+thread_local! {
+    /// The page whose Batch Mode is on.
+    static ACTIVE: RefCell<Option<Rc<Batch>>> = const { RefCell::new(None) };
+}
+
+/// Set up Batch Mode on a page. Call it before the page connects its own
+/// button handlers, so a picked button can be dropped without running.
+pub fn setup(page_builder: &Builder, window: &ApplicationWindow) {
+    let (Some(toggle), Some(summary), Some(run)) = (
+        try_extract_widget::<ToggleButton>(page_builder, "batch_toggle"),
+        try_extract_widget::<Label>(page_builder, "batch_summary"),
+        try_extract_widget::<Button>(page_builder, "batch_run"),
+    ) else {
+        return;
+    };
+
+    let batch = Rc::new(Batch {
+        summary,
+        run: run.clone(),
+        queue: RefCell::new(Vec::new()),
+        clicked: RefCell::new(None),
+    });
+
+    let buttons = page_builder.objects().into_iter().filter_map(|object| {
+        let button = object.downcast::<Button>().ok()?;
+        button
+            .buildable_id()
+            .is_some_and(|id| id.starts_with("btn_"))
+            .then_some(button)
+    });
+    for button in buttons {
+        let batch = batch.clone();
+        button.connect_clicked(move |button| {
+            if !is_active(&batch) {
+                return;
+            }
+            let picked = batch
+                .queue
+                .borrow()
+                .iter()
+                .position(|q| q.button == *button);
+            match picked {
+                Some(index) => {
+                    let queued = batch.queue.borrow_mut().remove(index);
+                    info!("Batch: dropped '{}'", queued.title);
+                    button.remove_css_class(PICKED_CLASS);
+                    batch.update();
+                    button.stop_signal_emission_by_name("clicked");
+                }
+                None => *batch.clicked.borrow_mut() = Some(button.clone()),
+            }
+        });
+    }
+
+    let batch_clone = batch.clone();
+    toggle.connect_toggled(move |toggle| {
+        batch_clone.clear();
+        ACTIVE.with(|active| {
+            *active.borrow_mut() = toggle.is_active().then(|| batch_clone.clone());
+        });
+        batch_clone.summary.set_visible(toggle.is_active());
+        batch_clone.run.set_visible(toggle.is_active());
+        batch_clone.update();
+    });
+
+    let window = window.clone();
+    run.connect_clicked(move |_| {
+        let queue = batch.queue.take();
+        toggle.set_active(false);
+        if queue.is_empty() {
+            return;
+        }
+
+        let titles: Vec<String> = queue.iter().map(|q| q.title.clone()).collect();
+        info!("Batch: running {}", titles.join(", "));
+        let parts = queue.into_iter().map(|q| q.commands).collect();
+        task_runner::run(
+            window.upcast_ref(),
+            batch::combine(parts),
+            &titles.join(" + "),
+        );
+    });
+}
+
+fn is_active(batch: &Rc<Batch>) -> bool {
+    ACTIVE.with(|active| {
+        active
+            .borrow()
+            .as_ref()
+            .is_some_and(|a| Rc::ptr_eq(a, batch))
+    })
+}
+
+impl Batch {
+    /// Drop the queue and unmark its buttons.
+    fn clear(&self) {
+        for queued in self.queue.take() {
+            queued.button.remove_css_class(PICKED_CLASS);
+        }
+        self.clicked.take();
+    }
+
+    fn update(&self) {
+        let count = self.queue.borrow().len();
+        self.summary
+            .set_label(&format!("{}: {}", gettext("Selected"), count));
+        self.run.set_sensitive(count > 0);
+    }
+}
+
+/// Queue `commands` when Batch Mode is on and they come from a click on
+/// the page, or hand them back to run now.
+pub fn offer(commands: CommandSequence, title: &str) -> Option<CommandSequence> {
+    let Some(batch) = ACTIVE.with(|active| active.borrow().clone()) else {
+        return Some(commands);
+    };
+    let Some(button) = batch.clicked.take() else {
+        return Some(commands);
+    };
+
+    info!("Batch: queued '{}'", title);
+    button.add_css_class(PICKED_CLASS);
+    batch.queue.borrow_mut().push(Queued {
+        button,
+        title: title.to_string(),
+        commands,
+    });
+    batch.update();
+    None
+}
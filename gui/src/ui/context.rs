@@ -18,9 +18,11 @@ impl AppContext {
         Self { ui }
     }
 
-    /// Navigate to a specific page in the stack.
-    pub fn navigate_to_page(&self, page_name: &str) {
-        self.ui.stack.set_visible_child_name(page_name);
+    /// Navigate to a specific page, loading it if it hasn't been visited.
+    ///
+    /// Returns `false` if there is no such page.
+    pub fn navigate_to_page(&self, page_name: &str) -> bool {
+        crate::ui::navigation::open_page(&self.ui.tabs_container, page_name)
     }
 }
 
@@ -28,8 +30,6 @@ impl AppContext {
 #[derive(Clone)]
 pub struct UiComponents {
     pub stack: Stack,
-    #[allow(dead_code)]
-    // Stored for potential future use, currently only used during initialization
     pub tabs_container: GtkBox,
     pub main_split_view: adw::OverlaySplitView,
     pub sidebar_toggle: ToggleButton,
@@ -69,11 +69,11 @@ impl UiComponents {
         self.main_split_view
             .connect_show_sidebar_notify(move |split_view| {
                 let tooltip = if split_view.shows_sidebar() {
-                    "Hide sidebar"
+                    crate::i18n::gettext("Hide sidebar")
                 } else {
-                    "Show sidebar"
+                    crate::i18n::gettext("Show sidebar")
                 };
-                toggle.set_tooltip_text(Some(tooltip));
+                toggle.set_tooltip_text(Some(&tooltip));
             });
     }
 }
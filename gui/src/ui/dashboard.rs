@@ -0,0 +1,259 @@
+//! Dashboard cards on the main page.
+//!
+//! Each card shows one number about the system and opens the page or
+//! action that deals with it. The cards are read again whenever a task
+//! finishes; pending updates come last, since checking them takes a while.
+
+use crate::core::status::{self, Snapshot};
+use crate::core::{history, updates};
+use crate::i18n::{gettext, n_};
+use crate::ui::dialogs::terminal;
+use crate::ui::install_state;
+use crate::ui::pages::{scheduled_maintenance, servicing};
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{extract_widget, spawn_blocking};
+use adw::prelude::*;
+use gtk4::{glib, ApplicationWindow, Builder, Button, FlowBox, Label};
+use log::{info, warn};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A clickable card with a big value over a caption.
+struct Card {
+    button: Button,
+    value: Label,
+}
+
+impl Card {
+    fn new(flow_box: &FlowBox, caption: &str) -> Self {
+        let value = Label::builder()
+            .label("…")
+            .css_classes(vec!["title-1".to_string()])
+            .build();
+        let caption = Label::builder()
+            .label(gettext(caption))
+            .css_classes(vec!["caption".to_string(), "dim-label".to_string()])
+            .build();
+        let content = gtk4::Box::builder()
+            .orientation(gtk4::Orientation::Vertical)
+            .spacing(4)
+            .margin_top(12)
+            .margin_bottom(12)
+            .margin_start(12)
+            .margin_end(12)
+            .build();
+        content.append(&value);
+        content.append(&caption);
+
+        let button = Button::builder()
+            .child(&content)
+            .width_request(150)
+            .css_classes(vec!["card".to_string()])
+            .build();
+        flow_box.append(&button);
+        Self { button, value }
+    }
+
+    /// Show `value`, marked as good news or as needing attention.
+    fn set(&self, value: &str, attention: bool) {
+        self.value.set_label(value);
+        if attention {
+            self.value.add_css_class("warning");
+        } else {
+            self.value.remove_css_class("warning");
+        }
+    }
+}
+
+struct Dashboard {
+    updates: Card,
+    orphans: Card,
+    free_space: Card,
+    maintenance: Card,
+    failed: Card,
+    modified: Card,
+    snapshot: RefCell<Snapshot>,
+}
+
+/// Set up the dashboard cards of the main page.
+pub fn setup_section(page_builder: &Builder, window: &ApplicationWindow) {
+    let flow_box = extract_widget::<FlowBox>(page_builder, "dashboard_box");
+
+    let dashboard = Rc::new(Dashboard {
+        updates: Card::new(&flow_box, n_("Pending Updates")),
+        orphans: Card::new(&flow_box, n_("Orphaned Packages")),
+        free_space: Card::new(&flow_box, n_("Free on /")),
+        maintenance: Card::new(&flow_box, n_("Last Maintenance")),
+        failed: Card::new(&flow_box, n_("Failed Services")),
+        modified: Card::new(&flow_box, n_("Modified Files")),
+        snapshot: RefCell::new(Snapshot::default()),
+    });
+
+    let window_clone = window.clone();
+    dashboard.updates.button.connect_clicked(move |_| {
+        info!("Dashboard: updates clicked");
+        terminal::show_terminal_dialog(
+            window_clone.upcast_ref(),
+            &gettext("System Update"),
+            "/usr/local/bin/upd",
+            &[],
+        );
+    });
+
+    let window_clone = window.clone();
+    dashboard.orphans.button.connect_clicked(move |_| {
+        info!("Dashboard: orphans clicked");
+        servicing::review_orphans(&window_clone);
+    });
+
+    let window_clone = window.clone();
+    dashboard.free_space.button.connect_clicked(move |_| {
+        open_page(&window_clone, "disks");
+    });
+
+    let window_clone = window.clone();
+    dashboard.maintenance.button.connect_clicked(move |_| {
+        open_page(&window_clone, "scheduled_maintenance");
+    });
+
+    let window_clone = window.clone();
+    let dashboard_clone = dashboard.clone();
+    dashboard.failed.button.connect_clicked(move |_| {
+        info!("Dashboard: failed services clicked");
+        let units = dashboard_clone.snapshot.borrow().failed_units.clone();
+        show_failed_units(&window_clone, units);
+    });
+
+    let window_clone = window.clone();
+    dashboard.modified.button.connect_clicked(move |_| {
+        open_page(&window_clone, "uninstall");
+    });
+
+    install_state::connect_refresh(move || load(&dashboard));
+}
+
+fn open_page(window: &ApplicationWindow, page: &str) {
+    info!("Dashboard: opening {}", page);
+    if let Err(e) = WidgetExt::activate_action(window, "win.open-page", Some(&page.to_variant())) {
+        warn!("Failed to open {}: {}", page, e);
+    }
+}
+
+fn load(dashboard: &Rc<Dashboard>) {
+    let timers = scheduled_maintenance::timer_names();
+    let dashboard_clone = dashboard.clone();
+    spawn_blocking(
+        move || Snapshot::read(&timers),
+        move |snapshot| {
+            *dashboard_clone.snapshot.borrow_mut() = snapshot;
+            render(&dashboard_clone);
+        },
+    );
+
+    let dashboard = dashboard.clone();
+    spawn_blocking(updates::check, move |summary| {
+        dashboard
+            .updates
+            .set(&summary.packages.to_string(), summary.packages > 0);
+        dashboard.updates.button.set_tooltip_text(Some(
+            &summary
+                .message()
+                .unwrap_or_else(|| gettext("Everything is up to date")),
+        ));
+    });
+}
+
+fn render(dashboard: &Dashboard) {
+    let snapshot = dashboard.snapshot.borrow();
+
+    dashboard
+        .orphans
+        .set(&snapshot.orphans.to_string(), snapshot.orphans > 0);
+
+    match snapshot.root_space {
+        Some((free, total)) => {
+            // Under a tenth left is when things start failing to install
+            dashboard
+                .free_space
+                .set(&glib::format_size(free), free < total / 10);
+        }
+        None => dashboard.free_space.set("?", false),
+    }
+
+    match snapshot.last_maintenance {
+        Some(timestamp) => dashboard
+            .maintenance
+            .set(&status::days_ago(timestamp, history::now()), false),
+        None => dashboard.maintenance.set(&gettext("Never"), true),
+    }
+
+    let failed = snapshot.failed_units.len();
+    dashboard.failed.set(&failed.to_string(), failed > 0);
+
+    let modified = &snapshot.modified_files;
+    dashboard
+        .modified
+        .set(&modified.len().to_string(), !modified.is_empty());
+    let tooltip = if modified.is_empty() {
+        gettext("Files the toolkit installed outside of pacman are unchanged")
+    } else {
+        modified.join("\n")
+    };
+    dashboard.modified.button.set_tooltip_text(Some(&tooltip));
+}
+
+/// List the failed units, with a way to read their logs.
+fn show_failed_units(window: &ApplicationWindow, units: Vec<String>) {
+    if units.is_empty() {
+        crate::ui::toast::show(&gettext("No services have failed since boot."));
+        return;
+    }
+
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    for unit in &units {
+        list.append(
+            &adw::ActionRow::builder()
+                .title(unit.as_str())
+                .use_markup(false)
+                .build(),
+        );
+    }
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Failed Services"))
+        .body(gettext(
+            "These services failed since the system started. Their logs usually say why.",
+        ))
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("close", gettext("Close").as_str()),
+        ("logs", gettext("Show Logs").as_str()),
+    ]);
+    dialog.set_response_appearance("logs", adw::ResponseAppearance::Suggested);
+    dialog.set_close_response("close");
+
+    let window_clone = window.clone();
+    dialog.connect_response(Some("logs"), move |_, _| {
+        let mut args = vec!["-b", "--no-pager", "-n", "50"];
+        for unit in &units {
+            args.extend(["-u", unit.as_str()]);
+        }
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("journalctl")
+                    .args(&args)
+                    .description("Reading the logs of the failed services...")
+                    .build(),
+            )
+            .build();
+        task_runner::run(window_clone.upcast_ref(), commands, "Failed Services");
+    });
+
+    dialog.present(Some(window));
+}
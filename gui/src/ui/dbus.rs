@@ -0,0 +1,147 @@
+//! Session bus service exposing toolkit actions to other programs.
+//!
+//! Owns `xyz.xerolinux.Toolkit` and serves `/xyz/xerolinux/Toolkit` with:
+//! - `ListActions() -> a(ssss)`: category, id, title and description
+//! - `IsInstalled(category, id) -> (bb)`: whether the action can tell, and
+//!   whether it's already set up, answered after probing off the main thread
+//! - `RunAction(category, id)`: raises the window and runs the action in the
+//!   task runner, the same as clicking its button
+//!
+//! Actions are looked up in the shared registry, so KRunner plugins, Plasma
+//! widgets and other XeroLinux tools address them like the CLI does.
+
+use crate::actions;
+use crate::ui::task_runner;
+use crate::ui::utils::spawn_blocking;
+use gtk4::prelude::*;
+use gtk4::{gio, glib, ApplicationWindow};
+use log::{error, info, warn};
+
+const BUS_NAME: &str = "xyz.xerolinux.Toolkit";
+const OBJECT_PATH: &str = "/xyz/xerolinux/Toolkit";
+const INTERFACE: &str = "xyz.xerolinux.Toolkit";
+
+const INTERFACE_XML: &str = r#"
+<node>
+  <interface name="xyz.xerolinux.Toolkit">
+    <method name="ListActions">
+      <arg type="a(ssss)" name="actions" direction="out"/>
+    </method>
+    <method name="IsInstalled">
+      <arg type="s" name="category" direction="in"/>
+      <arg type="s" name="id" direction="in"/>
+      <arg type="b" name="known" direction="out"/>
+      <arg type="b" name="installed" direction="out"/>
+    </method>
+    <method name="RunAction">
+      <arg type="s" name="category" direction="in"/>
+      <arg type="s" name="id" direction="in"/>
+    </method>
+  </interface>
+</node>
+"#;
+
+const ERROR_UNKNOWN_ACTION: &str = "xyz.xerolinux.Toolkit.Error.UnknownAction";
+const ERROR_BUSY: &str = "xyz.xerolinux.Toolkit.Error.Busy";
+
+/// Own the bus name and export the toolkit object for `window`.
+pub fn setup_service(window: &ApplicationWindow) {
+    let window = window.clone();
+    gio::bus_own_name(
+        gio::BusType::Session,
+        BUS_NAME,
+        gio::BusNameOwnerFlags::NONE,
+        move |connection, _| register(&connection, &window),
+        |_, name| info!("Acquired D-Bus name {}", name),
+        |_, name| warn!("Lost or could not acquire D-Bus name {}", name),
+    );
+}
+
+fn register(connection: &gio::DBusConnection, window: &ApplicationWindow) {
+    let interface = match gio::DBusNodeInfo::for_xml(INTERFACE_XML) {
+        Ok(node) => node.lookup_interface(INTERFACE),
+        Err(e) => {
+            error!("Invalid D-Bus interface definition: {}", e);
+            return;
+        }
+    };
+    let Some(interface) = interface else {
+        error!("D-Bus interface {} not found in definition", INTERFACE);
+        return;
+    };
+
+    let window = window.clone();
+    let result = connection
+        .register_object(OBJECT_PATH, &interface)
+        .method_call(move |_, _, _, _, method, params, invocation| {
+            handle_method(&window, method, &params, invocation);
+        })
+        .build();
+
+    if let Err(e) = result {
+        error!("Failed to register D-Bus object {}: {}", OBJECT_PATH, e);
+    }
+}
+
+fn lookup(params: &glib::Variant) -> Option<&'static actions::Action> {
+    let (category, id) = params.get::<(String, String)>()?;
+    actions::find(&category, &id)
+}
+
+fn handle_method(
+    window: &ApplicationWindow,
+    method: &str,
+    params: &glib::Variant,
+    invocation: gio::DBusMethodInvocation,
+) {
+    match method {
+        "ListActions" => {
+            let list: Vec<(String, String, String, String)> = actions::ACTIONS
+                .iter()
+                .map(|a| {
+                    (
+                        a.category.to_string(),
+                        a.id.to_string(),
+                        a.title.to_string(),
+                        a.description.to_string(),
+                    )
+                })
+                .collect();
+            invocation.return_value(Some(&(list,).to_variant()));
+        }
+        "IsInstalled" => {
+            let Some(action) = lookup(params) else {
+                invocation.return_dbus_error(ERROR_UNKNOWN_ACTION, "No such action");
+                return;
+            };
+            // Probes query pacman or flatpak, so the reply is sent once
+            // they're done instead of holding up the main loop.
+            spawn_blocking(
+                move || action.is_installed(),
+                move |state| {
+                    let reply = (state.is_some(), state.unwrap_or(false));
+                    invocation.return_value(Some(&reply.to_variant()));
+                },
+            );
+        }
+        "RunAction" => {
+            let Some(action) = lookup(params) else {
+                invocation.return_dbus_error(ERROR_UNKNOWN_ACTION, "No such action");
+                return;
+            };
+            if task_runner::is_running() {
+                invocation.return_dbus_error(ERROR_BUSY, "Another action is already running");
+                return;
+            }
+
+            info!("D-Bus: Running action {} {}", action.category, action.id);
+            window.present();
+            crate::ui::pages::run_action(window, action);
+            invocation.return_value(None);
+        }
+        _ => invocation.return_dbus_error(
+            "org.freedesktop.DBus.Error.UnknownMethod",
+            &format!("Unknown method {}", method),
+        ),
+    }
+}
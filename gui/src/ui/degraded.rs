@@ -0,0 +1,82 @@
+//! Degraded mode when optional dependencies are missing.
+//!
+//! Without flatpak or an AUR helper the toolkit still starts. Pages whose
+//! buttons need a missing tool get a banner naming it, with a button that
+//! installs it in the task runner, and registered actions that can't run
+//! are disabled.
+
+use crate::actions::{self, Requirement};
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::task_runner::{self, CommandSequence};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder};
+use log::info;
+
+/// Tell the user what's missing for `page_id` and disable the registered
+/// actions on it that can't run.
+pub fn apply_to_page(
+    page_id: &str,
+    requires: &[Requirement],
+    page_builder: &Builder,
+    container: &gtk4::Box,
+    window: &ApplicationWindow,
+) {
+    let missing: Vec<Requirement> = requires.iter().copied().filter(|r| !r.is_met()).collect();
+    if missing.is_empty() {
+        return;
+    }
+    info!("Page '{}' is missing {:?}", page_id, missing);
+
+    for action in actions::ACTIONS.iter().filter(|a| a.page == page_id) {
+        let Some(message) = action.requirements_error() else {
+            continue;
+        };
+        if let Some(widget) = page_builder.object::<gtk4::Widget>(action.widget) {
+            widget.set_sensitive(false);
+            widget.set_tooltip_text(Some(&message));
+        }
+    }
+
+    container.prepend(&banner(&missing, window));
+}
+
+fn banner(missing: &[Requirement], window: &ApplicationWindow) -> adw::Banner {
+    let names: Vec<String> = missing.iter().map(|r| gettext(r.describe())).collect();
+    let names = match names.split_last() {
+        Some((last, rest)) if !rest.is_empty() => gettext_f(
+            "{list} and {last}",
+            &[("list", &rest.join(", ")), ("last", last)],
+        ),
+        _ => names.concat(),
+    };
+    let banner = adw::Banner::builder()
+        .title(gettext_f(
+            "Some actions on this page need {names}, which isn't installed",
+            &[("names", &names)],
+        ))
+        .button_label(gettext("Install"))
+        .revealed(true)
+        .build();
+
+    let missing = missing.to_vec();
+    let window = window.clone();
+    banner.connect_button_clicked(move |banner| {
+        if task_runner::is_running() {
+            return;
+        }
+        let commands = missing
+            .iter()
+            .fold(CommandSequence::new(), |sequence, requirement| {
+                sequence.append(requirement.install_commands())
+            })
+            .build();
+        task_runner::run(
+            window.upcast_ref(),
+            commands,
+            "Install Missing Dependencies",
+        );
+        banner.set_revealed(false);
+    });
+
+    banner
+}
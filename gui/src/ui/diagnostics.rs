@@ -0,0 +1,35 @@
+//! Problems found while building the UI.
+//!
+//! Page setup looks widgets up with `utils::try_extract_widget`. When a .ui
+//! file and the code drift apart, the missing id is recorded here instead
+//! of panicking, so the page loses one action rather than the app going
+//! down. The report is shown in error details.
+
+use std::cell::RefCell;
+
+thread_local! {
+    static MISSING_WIDGETS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Note that no widget has the id `name`.
+pub fn record_missing_widget(name: &str) {
+    MISSING_WIDGETS.with(|missing| {
+        let mut missing = missing.borrow_mut();
+        if !missing.iter().any(|id| id == name) {
+            missing.push(name.to_string());
+        }
+    });
+}
+
+/// Number of missing widgets recorded so far.
+pub fn missing_widget_count() -> usize {
+    MISSING_WIDGETS.with(|missing| missing.borrow().len())
+}
+
+/// Everything recorded so far, or `None` if nothing went wrong.
+pub fn report() -> Option<String> {
+    MISSING_WIDGETS.with(|missing| {
+        let missing = missing.borrow();
+        (!missing.is_empty()).then(|| format!("Missing widgets: {}", missing.join(", ")))
+    })
+}
@@ -0,0 +1,90 @@
+//! Dialog shown at startup when dependencies are missing.
+
+use crate::actions::aur;
+use crate::core::system_check::{self, DependencyCheckResult};
+use crate::ui::utils::extract_widget;
+use gtk4::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button, Label};
+use log::{info, warn};
+
+/// Show dependency error dialog listing what's missing.
+///
+/// The app keeps running with the affected actions disabled. When the AUR
+/// helper is missing, offers to build paru in a terminal first.
+pub fn show_dependency_error_dialog(
+    main_window: &ApplicationWindow,
+    check_result: &DependencyCheckResult,
+) {
+    warn!("Showing dependency error dialog");
+
+    // Load error dialog from UI file
+    let builder = Builder::from_resource(crate::config::resources::dialogs::DEPENDENCY_ERROR);
+
+    let error_window: gtk4::Window = extract_widget(&builder, "dependency_error_window");
+
+    let missing_deps_label: Label = extract_widget(&builder, "missing_deps_label");
+
+    let install_hint_label: Label = extract_widget(&builder, "install_hint_label");
+
+    let install_aur_button: Button = extract_widget(&builder, "install_aur_button");
+
+    let continue_button: Button = extract_widget(&builder, "continue_button");
+
+    let show_result = {
+        let missing_deps_label = missing_deps_label.clone();
+        let install_hint_label = install_hint_label.clone();
+        let install_aur_button = install_aur_button.clone();
+        move |result: &DependencyCheckResult| {
+            missing_deps_label.set_label(&result.format_missing_list());
+            install_hint_label.set_label(&result.generate_install_hint());
+            install_aur_button.set_visible(result.aur_helper_missing);
+        }
+    };
+    show_result(check_result);
+
+    error_window.set_transient_for(Some(main_window));
+
+    let error_window_clone = error_window.clone();
+    install_aur_button.connect_clicked(move |button| {
+        info!("Installing paru from the dependency error dialog");
+        button.set_sensitive(false);
+
+        let script = aur::install_paru().to_shell_script(aur::INSTALL_PARU_TITLE);
+        let error_window = error_window_clone.clone();
+        let button = button.clone();
+        let show_result = show_result.clone();
+        super::terminal::show_terminal_dialog_then(
+            &error_window_clone,
+            aur::INSTALL_PARU_TITLE,
+            "bash",
+            &["-c", &script],
+            move |exit| {
+                if !exit.success() {
+                    warn!("paru installation failed: {:?}", exit);
+                    button.set_sensitive(true);
+                    return;
+                }
+
+                let result = system_check::check_dependencies();
+                if result.has_missing_dependencies() {
+                    show_result(&result);
+                    button.set_sensitive(true);
+                    return;
+                }
+                if crate::core::aur::init() {
+                    info!("AUR helper initialized successfully");
+                }
+                info!("Dependencies installed, continuing");
+                error_window.close();
+            },
+        );
+    });
+
+    let error_window_clone = error_window.clone();
+    continue_button.connect_clicked(move |_| {
+        warn!("Continuing with missing dependencies, affected actions are disabled");
+        error_window_clone.close();
+    });
+
+    error_window.present();
+}
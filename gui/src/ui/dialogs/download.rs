@@ -4,6 +4,7 @@ use crate::core::download::{
     download_file, fetch_arch_iso_info, format_bytes, format_speed, format_time_remaining,
     DownloadState,
 };
+use crate::i18n::{gettext, gettext_f};
 use crate::ui::utils::extract_widget;
 use gtk4::glib;
 use gtk4::prelude::*;
@@ -66,9 +67,9 @@ pub fn show_download_dialog(parent: &Window) {
                             .strip_prefix("archlinux-")
                             .and_then(|s| s.split('-').next())
                         {
-                            format!("Version: {}", date_part)
+                            gettext_f("Version: {version}", &[("version", date_part)])
                         } else {
-                            "Latest Version".to_string()
+                            gettext("Latest Version")
                         };
                         version_label_clone.set_text(&version);
 
@@ -99,7 +100,7 @@ pub fn show_download_dialog(parent: &Window) {
                         // Show error state
                         fetching_spinner_clone.remove_css_class("spinning");
                         fetching_spinner_clone.set_icon_name(Some("circle-xmark"));
-                        version_label_clone.set_text("Failed to fetch version");
+                        version_label_clone.set_text(&gettext("Failed to fetch version"));
                         version_label_clone.remove_css_class("accent");
                         version_label_clone.add_css_class("error");
                     }
@@ -113,7 +114,7 @@ pub fn show_download_dialog(parent: &Window) {
                 // Show error state
                 fetching_spinner_clone.remove_css_class("spinning");
                 fetching_spinner_clone.set_icon_name(Some("circle-xmark"));
-                version_label_clone.set_text("Failed to fetch version");
+                version_label_clone.set_text(&gettext("Failed to fetch version"));
                 version_label_clone.remove_css_class("accent");
                 version_label_clone.add_css_class("error");
 
@@ -222,9 +223,9 @@ fn start_download(parent: &Window, iso_name: String, download_url: String, save_
         pause_flag_clone.store(!is_paused, Ordering::Relaxed);
 
         if is_paused {
-            pause_button_clone.set_label("Pause");
+            pause_button_clone.set_label(&gettext("Pause"));
         } else {
-            pause_button_clone.set_label("Resume");
+            pause_button_clone.set_label(&gettext("Resume"));
         }
     });
 
@@ -276,7 +277,7 @@ fn start_download(parent: &Window, iso_name: String, download_url: String, save_
                     // Update time remaining - only show if download is not complete
                     if state.downloaded >= state.total && state.total > 0 {
                         // Download is complete, show completion status
-                        time_remaining_label.set_text("Completed");
+                        time_remaining_label.set_text(&gettext("Completed"));
                         time_remaining_label.add_css_class("success");
                     } else {
                         let time_remaining = if state.speed > 0.0 {
@@ -299,11 +300,11 @@ fn start_download(parent: &Window, iso_name: String, download_url: String, save_
                     speed_label_clone.set_text("-");
                     speed_label_clone.remove_css_class("success");
 
-                    time_remaining_label_clone.set_text("Completed");
+                    time_remaining_label_clone.set_text(&gettext("Completed"));
                     time_remaining_label_clone.add_css_class("success");
 
                     pause_button_clone.set_sensitive(false);
-                    cancel_button_clone.set_label("Close");
+                    cancel_button_clone.set_label(&gettext("Close"));
                     cancel_button_clone.add_css_class("suggested-action");
 
                     return glib::ControlFlow::Break;
@@ -311,7 +312,7 @@ fn start_download(parent: &Window, iso_name: String, download_url: String, save_
                 DownloadMessage::Error(e) => {
                     error!("Download failed: {}", e);
                     if !e.contains("cancelled") {
-                        show_error_dialog(&parent_clone, "Download Failed", &e);
+                        show_error_dialog(&parent_clone, &gettext("Download Failed"), &e);
                     }
                     window_clone.close();
                     return glib::ControlFlow::Break;
@@ -366,7 +367,7 @@ fn show_error_dialog(parent: &Window, title: &str, message: &str) {
     use adw::prelude::*;
 
     let dialog = adw::AlertDialog::new(Some(title), Some(message));
-    dialog.add_response("ok", "OK");
+    dialog.add_response("ok", &gettext("OK"));
     dialog.set_default_response(Some("ok"));
     dialog.present(Some(parent));
 }
@@ -1,15 +1,73 @@
 //! Shared dialog helpers used across the UI pages.
 
+use crate::core::{package, report};
+use crate::i18n::gettext;
 use adw::prelude::*;
 use adw::AlertDialog;
 use gtk4::ApplicationWindow;
+use log::error;
 
 /// Show an error message dialog transient for the provided window.
 pub fn show_error(window: &ApplicationWindow, message: &str) {
+    show_error_report(window, message, None);
+}
+
+/// Like [`show_error`], naming the action that failed in issue reports.
+pub fn show_action_error(window: &ApplicationWindow, message: &str, action_id: &str) {
+    show_error_report(window, message, Some(action_id));
+}
+
+/// Error dialog with expandable details and a "Report Issue" button that
+/// opens a prefilled GitHub issue.
+fn show_error_report(window: &ApplicationWindow, message: &str, action_id: Option<&str>) {
+    let logs = crate::logging::recent(report::LOG_LINES);
+
+    let details = gtk4::TextView::builder()
+        .editable(false)
+        .monospace(true)
+        .wrap_mode(gtk4::WrapMode::WordChar)
+        .top_margin(6)
+        .bottom_margin(6)
+        .left_margin(6)
+        .right_margin(6)
+        .build();
+    let mut text = message.to_string();
+    if let Some(diagnostics) = crate::ui::diagnostics::report() {
+        text.push_str("\n\n");
+        text.push_str(&diagnostics);
+    }
+    text.push_str("\n\n");
+    text.push_str(&logs.join("\n"));
+    details.buffer().set_text(&text);
+
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .min_content_height(180)
+        .child(&details)
+        .build();
+    scrolled.add_css_class("card");
+    let expander = gtk4::Expander::builder()
+        .label(gettext("Details"))
+        .child(&scrolled)
+        .build();
+
     let dialog = AlertDialog::builder()
-        .heading("Error")
+        .heading(gettext("Error"))
         .body(message)
+        .extra_child(&expander)
         .build();
+    dialog.add_responses(&[
+        ("report", gettext("Report Issue").as_str()),
+        ("close", gettext("Close").as_str()),
+    ]);
+    dialog.set_default_response(Some("close"));
+    dialog.set_close_response("close");
+
+    let url = report::issue_url(message, action_id, &logs);
+    dialog.connect_response(Some("report"), move |_, _| {
+        if let Err(e) = package::open_url(&url) {
+            error!("Failed to open issue URL: {}", e);
+        }
+    });
 
     dialog.present(Some(window));
 }
@@ -0,0 +1,311 @@
+//! First-run setup wizard.
+//!
+//! Shown once, in place of the dependency error dialog, on the first launch.
+//! Walks through the dependency check, Flathub, the AUR helper, mirrors,
+//! autostart and favorites. Choices are saved to `config::user` and the
+//! system changes run together in the task runner when the wizard finishes.
+
+use crate::actions::{self, Action};
+use crate::config;
+use crate::core::system_check::DependencyCheckResult;
+use crate::core::{self, aur};
+use crate::ui::navigation::PAGES;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::extract_widget;
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button, StringList, Window};
+use log::{info, warn};
+use std::rc::Rc;
+
+const FLATHUB_REPO: &str = "https://dl.flathub.org/repo/flathub.flatpakrepo";
+
+/// Whether the wizard still has to be shown.
+pub fn should_show() -> bool {
+    !config::user::load().setup_done
+}
+
+/// Widgets holding the user's choices.
+struct Choices {
+    flathub: adw::SwitchRow,
+    aur_helper: adw::ComboRow,
+    install_paru: adw::SwitchRow,
+    mirror_country: adw::ComboRow,
+    rank_mirrors: adw::SwitchRow,
+    autostart: adw::SwitchRow,
+    background: adw::SwitchRow,
+    favorites: Vec<(&'static Action, gtk4::CheckButton)>,
+}
+
+/// Show the first-run wizard.
+pub fn show_first_run_dialog(window: &ApplicationWindow, deps: &DependencyCheckResult) {
+    info!("Showing first-run setup");
+    let builder = Builder::from_resource(config::resources::dialogs::FIRST_RUN);
+
+    let dialog: Window = extract_widget(&builder, "first_run_window");
+    let carousel: adw::Carousel = extract_widget(&builder, "carousel");
+    let back_button: Button = extract_widget(&builder, "back_button");
+    let next_button: Button = extract_widget(&builder, "next_button");
+    let skip_button: Button = extract_widget(&builder, "skip_button");
+
+    dialog.set_transient_for(Some(window));
+
+    let settings = config::user::load();
+    fill_system_check(&builder, deps);
+
+    let flathub: adw::SwitchRow = extract_widget(&builder, "flathub_row");
+    if !deps.flatpak_missing && core::package::has_flatpak_remote("flathub") {
+        flathub.set_active(false);
+        flathub.set_sensitive(false);
+        flathub.set_subtitle(&crate::i18n::gettext("Already set up"));
+    } else {
+        flathub.set_active(true);
+        if deps.flatpak_missing {
+            flathub.set_subtitle(&crate::i18n::gettext("Flatpak will be installed first"));
+        }
+    }
+
+    // "Automatic" followed by the helpers in detection order
+    let aur_helper: adw::ComboRow = extract_widget(&builder, "aur_helper_row");
+    let automatic = crate::i18n::gettext("Automatic");
+    let mut helper_names = vec![automatic.as_str()];
+    helper_names.extend(aur::Helper::ALL.iter().map(|h| h.name()));
+    aur_helper.set_model(Some(&StringList::new(&helper_names)));
+    let selected_helper = settings
+        .aur_helper
+        .as_deref()
+        .and_then(aur::Helper::from_name)
+        .and_then(|h| aur::Helper::ALL.iter().position(|&x| x == h))
+        .map_or(0, |i| i + 1);
+    aur_helper.set_selected(selected_helper as u32);
+
+    let install_paru: adw::SwitchRow = extract_widget(&builder, "install_paru_row");
+    install_paru.set_visible(deps.aur_helper_missing);
+    install_paru.set_active(deps.aur_helper_missing);
+
+    let mirror_country: adw::ComboRow = extract_widget(&builder, "mirror_country_row");
+    let mut country_names = vec![automatic.as_str()];
+    country_names.extend(
+        actions::servicing::MIRROR_COUNTRIES
+            .iter()
+            .map(|(_, name)| *name),
+    );
+    mirror_country.set_model(Some(&StringList::new(&country_names)));
+    let selected_country = settings
+        .mirror_country
+        .as_deref()
+        .and_then(|code| {
+            actions::servicing::MIRROR_COUNTRIES
+                .iter()
+                .position(|(c, _)| *c == code)
+        })
+        .map_or(0, |i| i + 1);
+    mirror_country.set_selected(selected_country as u32);
+
+    let autostart: adw::SwitchRow = extract_widget(&builder, "autostart_row");
+    autostart.set_active(core::autostart::is_enabled());
+    let background: adw::SwitchRow = extract_widget(&builder, "background_row");
+    background.set_active(settings.run_in_background);
+
+    let choices = Rc::new(Choices {
+        flathub,
+        aur_helper,
+        install_paru,
+        mirror_country,
+        rank_mirrors: extract_widget(&builder, "rank_mirrors_row"),
+        autostart,
+        background,
+        favorites: fill_favorites(&builder),
+    });
+
+    // Back/Next move through the carousel; Next finishes on the last page
+    let update_buttons = {
+        let back_button = back_button.clone();
+        let next_button = next_button.clone();
+        move |carousel: &adw::Carousel, index: u32| {
+            back_button.set_sensitive(index > 0);
+            let is_last = index + 1 >= carousel.n_pages();
+            let label = if is_last { "Finish" } else { "Next" };
+            next_button.set_label(&crate::i18n::gettext(label));
+        }
+    };
+    carousel.connect_page_changed(update_buttons);
+
+    let carousel_clone = carousel.clone();
+    back_button.connect_clicked(move |_| {
+        let index = carousel_clone.position().round() as u32;
+        if index > 0 {
+            carousel_clone.scroll_to(&carousel_clone.nth_page(index - 1), true);
+        }
+    });
+
+    let carousel_clone = carousel.clone();
+    let dialog_clone = dialog.clone();
+    let window_clone = window.clone();
+    next_button.connect_clicked(move |_| {
+        let index = carousel_clone.position().round() as u32;
+        if index + 1 < carousel_clone.n_pages() {
+            carousel_clone.scroll_to(&carousel_clone.nth_page(index + 1), true);
+            return;
+        }
+        finish(&window_clone, &choices);
+        dialog_clone.close();
+    });
+
+    let dialog_clone = dialog.clone();
+    skip_button.connect_clicked(move |_| {
+        info!("First-run setup skipped");
+        mark_done();
+        dialog_clone.close();
+    });
+
+    dialog.present();
+}
+
+fn fill_system_check(builder: &Builder, deps: &DependencyCheckResult) {
+    let flatpak_row: adw::ActionRow = extract_widget(builder, "flatpak_row");
+    let aur_row: adw::ActionRow = extract_widget(builder, "aur_row");
+
+    let status = |row: &adw::ActionRow, missing: bool, found: &str| {
+        let (subtitle, icon) = if missing {
+            (
+                crate::i18n::gettext("Not installed"),
+                "triangle-exclamation-symbolic",
+            )
+        } else {
+            (found.to_string(), "circle-check-symbolic")
+        };
+        row.set_subtitle(&subtitle);
+        row.add_suffix(&gtk4::Image::from_icon_name(icon));
+    };
+
+    let installed = crate::i18n::gettext("Installed");
+    status(&flatpak_row, deps.flatpak_missing, &installed);
+    let helper =
+        aur::detect().map(|h| crate::i18n::gettext_f("Using {helper}", &[("helper", h.name())]));
+    status(
+        &aur_row,
+        deps.aur_helper_missing,
+        helper.as_deref().unwrap_or(&installed),
+    );
+}
+
+/// One group per page with registered actions, with a check button per action.
+fn fill_favorites(builder: &Builder) -> Vec<(&'static Action, gtk4::CheckButton)> {
+    let page: adw::PreferencesPage = extract_widget(builder, "favorites_page");
+    let mut checks = Vec::new();
+
+    for page_config in PAGES {
+        let page_actions: Vec<&'static Action> = actions::ACTIONS
+            .iter()
+            .copied()
+            .filter(|a| a.page == page_config.id)
+            .collect();
+        if page_actions.is_empty() {
+            continue;
+        }
+
+        let group = adw::PreferencesGroup::builder()
+            .title(crate::i18n::gettext(page_config.title))
+            .build();
+        for action in page_actions {
+            let check = gtk4::CheckButton::builder()
+                .active(crate::ui::favorites::is_favorite(action))
+                .valign(gtk4::Align::Center)
+                .build();
+            let row = adw::ActionRow::builder()
+                .title(crate::i18n::gettext(action.title))
+                .subtitle(crate::i18n::gettext(action.description))
+                .activatable_widget(&check)
+                .build();
+            row.add_prefix(&check);
+            group.add(&row);
+            checks.push((action, check));
+        }
+        page.add(&group);
+    }
+
+    checks
+}
+
+fn mark_done() {
+    if let Err(e) = config::user::update(|s| s.setup_done = true) {
+        warn!("Failed to save first-run state: {}", e);
+    }
+}
+
+/// Save the choices and run the system changes they need.
+fn finish(window: &ApplicationWindow, choices: &Choices) {
+    info!("Finishing first-run setup");
+
+    let aur_helper = match choices.aur_helper.selected() {
+        0 => None,
+        i => aur::Helper::ALL
+            .get(i as usize - 1)
+            .map(|h| h.name().to_string()),
+    };
+    let mirror_country = match choices.mirror_country.selected() {
+        0 => None,
+        i => actions::servicing::MIRROR_COUNTRIES
+            .get(i as usize - 1)
+            .map(|(code, _)| code.to_string()),
+    };
+    let run_in_background = choices.background.is_active();
+
+    if let Err(e) = config::user::update(|s| {
+        s.aur_helper = aur_helper;
+        s.mirror_country = mirror_country;
+        s.run_in_background = run_in_background;
+        s.setup_done = true;
+    }) {
+        warn!("Failed to save first-run choices: {}", e);
+    }
+
+    let pins: Vec<(&Action, bool)> = choices
+        .favorites
+        .iter()
+        .map(|(action, check)| (*action, check.is_active()))
+        .collect();
+    if let Err(e) = crate::ui::favorites::set_pinned(&pins) {
+        warn!("Failed to save favorites: {}", e);
+    }
+
+    // Written after the background setting so the entry starts in the tray
+    let autostart = if choices.autostart.is_active() {
+        core::autostart::enable()
+    } else {
+        core::autostart::disable()
+    };
+    if let Err(e) = autostart {
+        warn!("Failed to update autostart: {}", e);
+    }
+    if run_in_background {
+        if let Some(app) = window.application().and_downcast::<adw::Application>() {
+            crate::ui::tray::start(&app);
+        }
+    }
+
+    let mut commands = CommandSequence::new();
+    if choices.install_paru.is_visible() && choices.install_paru.is_active() {
+        commands = commands.append(actions::aur::install_paru());
+    }
+    if choices.flathub.is_sensitive() && choices.flathub.is_active() {
+        if !core::system_check::flatpak_available() {
+            commands = commands.append(actions::Requirement::Flatpak.install_commands());
+        }
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("flatpak")
+                .args(&["remote-add", "--if-not-exists", "flathub", FLATHUB_REPO])
+                .description("Adding the Flathub repository...")
+                .build(),
+        );
+    }
+    if choices.rank_mirrors.is_active() {
+        commands = commands.append(actions::servicing::UPDATE_MIRRORS.commands_with_hooks());
+    }
+
+    if !commands.is_empty() {
+        task_runner::run(window.upcast_ref(), commands.build(), "First-Run Setup");
+    }
+}
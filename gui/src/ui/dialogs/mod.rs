@@ -2,14 +2,20 @@
 //!
 //! This module contains all dialog-related UI components:
 //! - `about`: About dialog with creator information
-//! - `error`: Simple error message dialogs
+//! - `dependency_error`: Missing dependency dialog shown at startup
+//! - `error`: Error dialogs with details and issue reporting
+//! - `first_run`: Setup wizard shown on the first launch
+//! - `preferences`: Preferences dialog
 //! - `selection`: Multi-choice selection dialogs
 //! - `download`: ISO download dialogs
 //! - `terminal`: Interactive terminal dialogs
 
 pub mod about;
+pub mod dependency_error;
 pub mod download;
 pub mod error;
+pub mod first_run;
+pub mod preferences;
 pub mod selection;
 pub mod terminal;
 pub mod warning;
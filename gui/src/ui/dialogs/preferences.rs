@@ -0,0 +1,339 @@
+//! Preferences dialog.
+//!
+//! Settings are saved to `config::user` and applied as soon as they change.
+
+use crate::config;
+use crate::config::user::{
+    self, Accent, AppSource, ColorScheme, EffectMode, NotifierService, PrivilegeBackend,
+    TerminalEmulator,
+};
+use crate::core::{history, notifier};
+use crate::i18n::{gettext, n_};
+use crate::ui::utils::{extract_widget, spawn_blocking};
+use crate::ui::{seasonal, theme};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, StringList};
+use log::{info, warn};
+
+/// Color schemes in the order they're listed.
+const COLOR_SCHEMES: [(ColorScheme, &str); 3] = [
+    (ColorScheme::System, n_("Follow system")),
+    (ColorScheme::Light, n_("Light")),
+    (ColorScheme::Dark, n_("Dark")),
+];
+
+/// Accent colors in the order they're listed.
+const ACCENTS: [(Accent, &str); 10] = [
+    (Accent::System, n_("Follow system")),
+    (Accent::Blue, n_("Blue")),
+    (Accent::Teal, n_("Teal")),
+    (Accent::Green, n_("Green")),
+    (Accent::Yellow, n_("Yellow")),
+    (Accent::Orange, n_("Orange")),
+    (Accent::Red, n_("Red")),
+    (Accent::Pink, n_("Pink")),
+    (Accent::Purple, n_("Purple")),
+    (Accent::Slate, n_("Slate")),
+];
+
+/// Effect modes in the order they're listed in the effect rows.
+const EFFECT_MODES: [(EffectMode, &str); 3] = [
+    (EffectMode::Automatic, n_("Automatic")),
+    (EffectMode::Always, n_("Always")),
+    (EffectMode::Off, n_("Off")),
+];
+
+/// App sources in the order they're listed; `None` keeps each app's usual
+/// source.
+const APP_SOURCES: [(Option<AppSource>, &str); 3] = [
+    (None, n_("Each app's default")),
+    (Some(AppSource::Native), n_("Native packages")),
+    (Some(AppSource::Flatpak), n_("Flatpak")),
+];
+
+/// Terminals in the order they're listed; `None` is the built-in one.
+const TERMINALS: [(Option<TerminalEmulator>, &str); 6] = [
+    (None, n_("Built-in")),
+    (Some(TerminalEmulator::Konsole), n_("Konsole")),
+    (Some(TerminalEmulator::Alacritty), n_("Alacritty")),
+    (Some(TerminalEmulator::Kitty), n_("kitty")),
+    (Some(TerminalEmulator::GnomeTerminal), n_("GNOME Terminal")),
+    (Some(TerminalEmulator::Foot), n_("foot")),
+];
+
+/// Privilege backends in the order they're listed.
+const PRIVILEGE_BACKENDS: [(PrivilegeBackend, &str); 3] = [
+    (PrivilegeBackend::Session, n_("xero-auth session")),
+    (PrivilegeBackend::Pkexec, "pkexec"),
+    (PrivilegeBackend::Sudo, "sudo"),
+];
+
+/// Notification services in the order they're listed.
+const NOTIFIER_SERVICES: [(NotifierService, &str); 4] = [
+    (NotifierService::Off, n_("Off")),
+    (NotifierService::Ntfy, "ntfy"),
+    (NotifierService::Gotify, "Gotify"),
+    (NotifierService::Webhook, n_("Webhook")),
+];
+
+/// Show the preferences dialog.
+pub fn show_preferences_dialog(window: &ApplicationWindow) {
+    info!("Showing preferences");
+    let builder = Builder::from_resource(config::resources::dialogs::PREFERENCES);
+    let dialog = extract_widget::<adw::PreferencesDialog>(&builder, "preferences_dialog");
+
+    setup_style(&builder);
+    setup_seasonal(&builder, window);
+    setup_app_source(&builder);
+    setup_terminal(&builder);
+    setup_privilege(&builder);
+    setup_notifier(&builder, &dialog);
+
+    dialog.present(Some(window));
+}
+
+/// Translated labels of `options`, for a combo row.
+fn option_labels<T>(options: &[(T, &str)]) -> StringList {
+    let labels: Vec<String> = options.iter().map(|(_, label)| gettext(*label)).collect();
+    let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+    StringList::new(&labels)
+}
+
+/// Position of `value` in `options`, for a combo row.
+fn option_position<T: PartialEq>(options: &[(T, &str)], value: &T) -> u32 {
+    options.iter().position(|(v, _)| v == value).unwrap_or(0) as u32
+}
+
+fn setup_style(builder: &Builder) {
+    let color_scheme_row = extract_widget::<adw::ComboRow>(builder, "color_scheme_row");
+    let accent_row = extract_widget::<adw::ComboRow>(builder, "accent_row");
+    let custom_css_row = extract_widget::<adw::SwitchRow>(builder, "custom_css_row");
+
+    let appearance = user::load().appearance;
+    color_scheme_row.set_model(Some(&option_labels(&COLOR_SCHEMES)));
+    color_scheme_row.set_selected(option_position(&COLOR_SCHEMES, &appearance.color_scheme));
+    accent_row.set_model(Some(&option_labels(&ACCENTS)));
+    accent_row.set_selected(option_position(&ACCENTS, &appearance.accent));
+    custom_css_row.set_active(appearance.custom_css);
+
+    color_scheme_row.connect_selected_notify(|row| {
+        if let Some((scheme, _)) = COLOR_SCHEMES.get(row.selected() as usize) {
+            theme::update_settings(|a| a.color_scheme = *scheme);
+        }
+    });
+    accent_row.connect_selected_notify(|row| {
+        if let Some((accent, _)) = ACCENTS.get(row.selected() as usize) {
+            theme::update_settings(|a| a.accent = *accent);
+        }
+    });
+    custom_css_row.connect_active_notify(|row| {
+        let enabled = row.is_active();
+        theme::update_settings(|a| a.custom_css = enabled);
+    });
+}
+
+fn setup_seasonal(builder: &Builder, window: &ApplicationWindow) {
+    let group = extract_widget::<adw::PreferencesGroup>(builder, "seasonal_group");
+    let animation_group =
+        extract_widget::<adw::PreferencesGroup>(builder, "seasonal_animation_group");
+    let enabled_row = extract_widget::<adw::SwitchRow>(builder, "seasonal_enabled_row");
+    let density_row = extract_widget::<adw::SpinRow>(builder, "seasonal_density_row");
+    let fps_row = extract_widget::<adw::SpinRow>(builder, "seasonal_fps_row");
+
+    let settings = seasonal::settings();
+    enabled_row.set_active(settings.enabled);
+    density_row.set_value(f64::from(settings.density));
+    fps_row.set_value(f64::from(settings.max_fps));
+
+    let mut effect_rows = Vec::new();
+    for effect in seasonal::effects() {
+        let row = adw::ComboRow::builder()
+            .title(gettext(effect.name()))
+            .model(&option_labels(&EFFECT_MODES))
+            .build();
+        row.set_selected(option_position(&EFFECT_MODES, &settings.mode(effect.id())));
+
+        let id = effect.id();
+        let window = window.clone();
+        row.connect_selected_notify(move |row| {
+            let Some((mode, _)) = EFFECT_MODES.get(row.selected() as usize) else {
+                return;
+            };
+            info!("Seasonal effect '{}' set to {:?}", id, mode);
+            seasonal::update_settings(&window, |s| {
+                s.effects.insert(id.to_string(), *mode);
+            });
+        });
+        group.add(&row);
+        effect_rows.push(row);
+    }
+
+    // Effect rows and animation settings only matter while effects are on
+    let set_sensitive = move |enabled: bool| {
+        for row in &effect_rows {
+            row.set_sensitive(enabled);
+        }
+        animation_group.set_sensitive(enabled);
+    };
+    set_sensitive(settings.enabled);
+
+    let window_clone = window.clone();
+    enabled_row.connect_active_notify(move |row| {
+        let enabled = row.is_active();
+        info!(
+            "Seasonal effects {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+        set_sensitive(enabled);
+        seasonal::update_settings(&window_clone, |s| s.enabled = enabled);
+    });
+
+    let window_clone = window.clone();
+    density_row.connect_value_notify(move |row| {
+        let density = row.value() as u32;
+        seasonal::update_settings(&window_clone, |s| s.density = density);
+    });
+
+    let window_clone = window.clone();
+    fps_row.connect_value_notify(move |row| {
+        let max_fps = row.value() as u32;
+        seasonal::update_settings(&window_clone, |s| s.max_fps = max_fps);
+    });
+}
+
+fn setup_app_source(builder: &Builder) {
+    let row = extract_widget::<adw::ComboRow>(builder, "app_source_row");
+    row.set_model(Some(&option_labels(&APP_SOURCES)));
+    row.set_selected(option_position(&APP_SOURCES, &user::load().app_source));
+
+    row.connect_selected_notify(|row| {
+        let Some((source, _)) = APP_SOURCES.get(row.selected() as usize) else {
+            return;
+        };
+        if let Err(e) = user::update(|s| s.app_source = *source) {
+            warn!("Failed to save app source: {}", e);
+        }
+    });
+}
+
+fn setup_terminal(builder: &Builder) {
+    let row = extract_widget::<adw::ComboRow>(builder, "terminal_row");
+    row.set_model(Some(&option_labels(&TERMINALS)));
+    row.set_selected(option_position(&TERMINALS, &user::load().terminal));
+
+    row.connect_selected_notify(|row| {
+        let Some((terminal, _)) = TERMINALS.get(row.selected() as usize) else {
+            return;
+        };
+        if let Err(e) = user::update(|s| s.terminal = *terminal) {
+            warn!("Failed to save terminal: {}", e);
+        }
+    });
+}
+
+fn setup_privilege(builder: &Builder) {
+    let row = extract_widget::<adw::ComboRow>(builder, "privilege_row");
+    row.set_model(Some(&option_labels(&PRIVILEGE_BACKENDS)));
+    row.set_selected(option_position(
+        &PRIVILEGE_BACKENDS,
+        &user::load().privilege,
+    ));
+
+    row.connect_selected_notify(|row| {
+        let Some((backend, _)) = PRIVILEGE_BACKENDS.get(row.selected() as usize) else {
+            return;
+        };
+        if let Err(e) = user::update(|s| s.privilege = *backend) {
+            warn!("Failed to save privilege backend: {}", e);
+        }
+    });
+}
+
+/// Load the settings, apply `change` to the notifier and save them.
+fn update_notifier(change: impl FnOnce(&mut user::NotifierSettings)) {
+    if let Err(e) = user::update(|s| change(&mut s.notifier)) {
+        warn!("Failed to save notification settings: {}", e);
+    }
+}
+
+fn setup_notifier(builder: &Builder, dialog: &adw::PreferencesDialog) {
+    let service_row = extract_widget::<adw::ComboRow>(builder, "notifier_service_row");
+    let url_row = extract_widget::<adw::EntryRow>(builder, "notifier_url_row");
+    let token_row = extract_widget::<adw::PasswordEntryRow>(builder, "notifier_token_row");
+    let minutes_row = extract_widget::<adw::SpinRow>(builder, "notifier_minutes_row");
+    let test_row = extract_widget::<adw::ActionRow>(builder, "notifier_test_row");
+    let test_button = extract_widget::<gtk4::Button>(builder, "notifier_test_button");
+
+    let settings = user::load().notifier;
+    service_row.set_model(Some(&option_labels(&NOTIFIER_SERVICES)));
+    service_row.set_selected(option_position(&NOTIFIER_SERVICES, &settings.service));
+    url_row.set_text(&settings.url);
+    token_row.set_text(&settings.token);
+    minutes_row.set_value(f64::from(settings.min_minutes));
+
+    // The rows only matter with a service, and the token only for Gotify
+    let set_sensitive = {
+        let rows: Vec<gtk4::Widget> = vec![
+            url_row.clone().upcast(),
+            minutes_row.clone().upcast(),
+            test_row.clone().upcast(),
+        ];
+        let token_row = token_row.clone();
+        move |service: NotifierService| {
+            for row in &rows {
+                row.set_sensitive(service != NotifierService::Off);
+            }
+            token_row.set_visible(service == NotifierService::Gotify);
+        }
+    };
+    set_sensitive(settings.service);
+
+    service_row.connect_selected_notify(move |row| {
+        if let Some((service, _)) = NOTIFIER_SERVICES.get(row.selected() as usize) {
+            info!("Task notifications set to {:?}", service);
+            set_sensitive(*service);
+            update_notifier(|n| n.service = *service);
+        }
+    });
+    url_row.connect_apply(|row| {
+        let url = row.text().trim().to_string();
+        update_notifier(|n| n.url = url);
+    });
+    token_row.connect_apply(|row| {
+        let token = row.text().trim().to_string();
+        update_notifier(|n| n.token = token);
+    });
+    minutes_row.connect_value_notify(|row| {
+        let minutes = row.value() as u32;
+        update_notifier(|n| n.min_minutes = minutes);
+    });
+
+    let dialog = dialog.clone();
+    test_button.connect_clicked(move |button| {
+        info!("Sending a test notification");
+        // Include text that hasn't been applied yet
+        let mut settings = user::load().notifier;
+        settings.url = url_row.text().trim().to_string();
+        settings.token = token_row.text().trim().to_string();
+
+        button.set_sensitive(false);
+        let button = button.clone();
+        let dialog = dialog.clone();
+        let title = gettext("Test notification");
+        spawn_blocking(
+            move || notifier::send(&settings, &title, history::Outcome::Success),
+            move |result| {
+                button.set_sensitive(true);
+                match result {
+                    Ok(()) => {
+                        dialog.add_toast(adw::Toast::new(&gettext("Test notification sent")));
+                    }
+                    Err(e) => {
+                        warn!("Test notification failed: {:#}", e);
+                        dialog.add_toast(adw::Toast::new(&format!("{:#}", e)));
+                    }
+                }
+            },
+        );
+    });
+}
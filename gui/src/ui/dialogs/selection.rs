@@ -2,14 +2,22 @@
 //!
 //! This module provides a reusable dialog window for presenting users with
 //! multiple options to select from, with customizable title, description, and actions.
+//! Options can be listed under group headers, carry a "Recommended" badge and
+//! use markup with links in their descriptions. Long lists get a filter entry.
 
+use crate::core::package;
 use crate::ui::utils::extract_widget;
 use gtk4::prelude::*;
-use gtk4::{Box as GtkBox, Builder, Button, CheckButton, Label, Separator, Window};
-use log::info;
+use gtk4::{
+    glib, Box as GtkBox, Builder, Button, CheckButton, Label, ListBox, SearchEntry, Window,
+};
+use log::{error, info};
 use std::cell::RefCell;
 use std::rc::Rc;
 
+/// Option lists longer than this get a filter entry.
+const FILTER_THRESHOLD: usize = 8;
+
 /// Represents a selectable option in the dialog
 #[derive(Clone, Debug)]
 pub struct SelectionOption {
@@ -17,6 +25,13 @@ pub struct SelectionOption {
     pub label: String,
     pub description: String,
     pub installed: bool,
+    /// Header the option is listed under
+    pub group: Option<String>,
+    /// Whether the description is Pango markup, e.g. with `<a href>` links
+    pub markup: bool,
+    pub recommended: bool,
+    /// Checked when the dialog opens, but can be unchecked
+    pub selected: bool,
 }
 
 impl SelectionOption {
@@ -27,8 +42,51 @@ impl SelectionOption {
             label: label.to_string(),
             description: description.to_string(),
             installed,
+            group: None,
+            markup: false,
+            recommended: false,
+            selected: false,
         }
     }
+
+    /// List the option under a group header
+    pub fn in_group(mut self, group: &str) -> Self {
+        self.group = Some(group.to_string());
+        self
+    }
+
+    /// Treat the description as Pango markup, so it can contain links
+    #[allow(dead_code)]
+    // No page links out of its options yet
+    pub fn markup(mut self) -> Self {
+        self.markup = true;
+        self
+    }
+
+    /// Show a "Recommended" badge next to the label
+    pub fn recommended(mut self) -> Self {
+        self.recommended = true;
+        self
+    }
+
+    /// Check the option when the dialog opens
+    pub fn selected(mut self, selected: bool) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Whether the option matches a lowercase filter query
+    fn matches(&self, query: &str) -> bool {
+        query.is_empty()
+            || [
+                self.id.as_str(),
+                &self.label,
+                &self.description,
+                self.group.as_deref().unwrap_or_default(),
+            ]
+            .iter()
+            .any(|field| field.to_lowercase().contains(query))
+    }
 }
 
 /// Selection type for the dialog
@@ -57,7 +115,7 @@ impl SelectionDialogConfig {
             title: title.to_string(),
             description: description.to_string(),
             options: Vec::new(),
-            confirm_label: "Install".to_string(),
+            confirm_label: crate::i18n::gettext("Install"),
             selection_type: SelectionType::Multi,
             selection_required: true,
         }
@@ -88,6 +146,71 @@ impl SelectionDialogConfig {
     }
 }
 
+/// Options grouped by header, in order of each group's first option
+fn group_options(options: &[SelectionOption]) -> Vec<(Option<String>, Vec<SelectionOption>)> {
+    let mut groups: Vec<(Option<String>, Vec<SelectionOption>)> = Vec::new();
+    for option in options {
+        match groups.iter_mut().find(|(group, _)| *group == option.group) {
+            Some((_, members)) => members.push(option.clone()),
+            None => groups.push((option.group.clone(), vec![option.clone()])),
+        }
+    }
+    groups
+}
+
+/// Row with the option's check or radio button, label, badge and description
+fn option_row(option: &SelectionOption, button: &CheckButton) -> GtkBox {
+    // Horizontal box: checkbox/radio on left, text on right
+    let option_row = GtkBox::new(gtk4::Orientation::Horizontal, 12);
+    option_row.set_margin_start(12);
+    option_row.set_margin_end(12);
+    option_row.set_margin_top(8);
+    option_row.set_margin_bottom(8);
+
+    // Vertical box for title and description
+    let text_box = GtkBox::new(gtk4::Orientation::Vertical, 4);
+    text_box.set_hexpand(true);
+
+    let title_box = GtkBox::new(gtk4::Orientation::Horizontal, 8);
+    let title_label = Label::new(Some(&option.label));
+    title_label.set_halign(gtk4::Align::Start);
+    title_label.set_wrap(true);
+    if option.installed {
+        title_label.set_css_classes(&["dim"]);
+    }
+    title_box.append(&title_label);
+
+    if option.recommended {
+        let badge = Label::new(Some(&crate::i18n::gettext("Recommended")));
+        badge.set_css_classes(&["recommended-badge"]);
+        badge.set_valign(gtk4::Align::Center);
+        title_box.append(&badge);
+    }
+
+    let desc_label = Label::new(None);
+    if option.markup {
+        desc_label.set_markup(&option.description);
+        desc_label.connect_activate_link(|_, uri| {
+            if let Err(e) = package::open_url(uri) {
+                error!("Failed to open URL {}: {}", uri, e);
+            }
+            glib::Propagation::Stop
+        });
+    } else {
+        desc_label.set_label(&option.description);
+    }
+    desc_label.set_css_classes(&["dim", "caption"]);
+    desc_label.set_halign(gtk4::Align::Start);
+    desc_label.set_wrap(true);
+
+    text_box.append(&title_box);
+    text_box.append(&desc_label);
+
+    option_row.append(button);
+    option_row.append(&text_box);
+    option_row
+}
+
 /// Show a selection dialog and call the callback with selected option IDs
 pub fn show_selection_dialog<F>(parent: &Window, config: SelectionDialogConfig, on_confirm: F)
 where
@@ -116,101 +239,78 @@ where
     description_label.set_label(&config.description);
     confirm_button.set_label(&config.confirm_label);
 
-    let checkboxes: Rc<RefCell<Vec<(String, CheckButton)>>> = Rc::new(RefCell::new(Vec::new()));
-    let radio_buttons: Rc<RefCell<Vec<(String, CheckButton)>>> = Rc::new(RefCell::new(Vec::new()));
+    let buttons: Rc<RefCell<Vec<(String, CheckButton)>>> = Rc::new(RefCell::new(Vec::new()));
     let selection_type = config.selection_type;
     let selection_required = config.selection_required;
 
     let mut first_radio: Option<CheckButton> = None;
+    let query = Rc::new(RefCell::new(String::new()));
+    let mut sections: Vec<(Option<Label>, ListBox, Rc<Vec<SelectionOption>>)> = Vec::new();
+
+    for (group, options) in group_options(&config.options) {
+        let header = group.map(|title| {
+            let header = Label::new(Some(&title));
+            header.set_halign(gtk4::Align::Start);
+            header.set_css_classes(&["selection-group-title"]);
+            options_container.append(&header);
+            header
+        });
 
-    for (i, option) in config.options.iter().enumerate() {
-        // Horizontal box: checkbox/radio on left, text on right
-        let option_row = GtkBox::new(gtk4::Orientation::Horizontal, 12);
-        option_row.set_margin_start(12);
-        option_row.set_margin_end(12);
-        option_row.set_margin_top(8);
-        option_row.set_margin_bottom(8);
-
-        // Create checkbox or radio button based on selection type
-        match selection_type {
-            SelectionType::Multi => {
-                let checkbox = CheckButton::new();
-                checkbox.set_active(option.installed);
-                checkbox.set_sensitive(!option.installed);
-                checkboxes
-                    .borrow_mut()
-                    .push((option.id.clone(), checkbox.clone()));
-
-                // Vertical box for title and description
-                let text_box = GtkBox::new(gtk4::Orientation::Vertical, 4);
-                text_box.set_hexpand(true);
-
-                let title_label = Label::new(Some(&option.label));
-                title_label.set_halign(gtk4::Align::Start);
-                title_label.set_wrap(true);
-                if option.installed {
-                    title_label.set_css_classes(&["dim"]);
+        let list = ListBox::builder()
+            .selection_mode(gtk4::SelectionMode::None)
+            .show_separators(true)
+            .build();
+
+        for option in &options {
+            // Checkbox, or radio button joined to the first one
+            let button = CheckButton::new();
+            if selection_type == SelectionType::Single {
+                match &first_radio {
+                    Some(first) => button.set_group(Some(first)),
+                    None => first_radio = Some(button.clone()),
                 }
-
-                let desc_label = Label::new(Some(&option.description));
-                desc_label.set_css_classes(&["dim", "caption"]);
-                desc_label.set_halign(gtk4::Align::Start);
-                desc_label.set_wrap(true);
-
-                text_box.append(&title_label);
-                text_box.append(&desc_label);
-
-                option_row.append(&checkbox);
-                option_row.append(&text_box);
             }
-            SelectionType::Single => {
-                let radio = if let Some(ref first) = first_radio {
-                    let radio = CheckButton::new();
-                    radio.set_group(Some(first));
-                    radio
-                } else {
-                    let radio = CheckButton::new();
-                    first_radio = Some(radio.clone());
-                    radio
-                };
-                radio.set_active(option.installed);
-                radio.set_sensitive(!option.installed);
-                radio_buttons
-                    .borrow_mut()
-                    .push((option.id.clone(), radio.clone()));
-
-                // Vertical box for title and description
-                let text_box = GtkBox::new(gtk4::Orientation::Vertical, 4);
-                text_box.set_hexpand(true);
-
-                let title_label = Label::new(Some(&option.label));
-                title_label.set_halign(gtk4::Align::Start);
-                title_label.set_wrap(true);
-                if option.installed {
-                    title_label.set_css_classes(&["dim"]);
-                }
+            button.set_active(option.installed || option.selected);
+            button.set_sensitive(!option.installed);
+            buttons
+                .borrow_mut()
+                .push((option.id.clone(), button.clone()));
+
+            let row = gtk4::ListBoxRow::builder()
+                .activatable(false)
+                .child(&option_row(option, &button))
+                .build();
+            list.append(&row);
+        }
 
-                let desc_label = Label::new(Some(&option.description));
-                desc_label.set_css_classes(&["dim", "caption"]);
-                desc_label.set_halign(gtk4::Align::Start);
-                desc_label.set_wrap(true);
+        let options = Rc::new(options);
+        let options_clone = options.clone();
+        let query_clone = query.clone();
+        list.set_filter_func(move |row| {
+            options_clone
+                .get(row.index() as usize)
+                .is_some_and(|option| option.matches(&query_clone.borrow()))
+        });
 
-                text_box.append(&title_label);
-                text_box.append(&desc_label);
+        options_container.append(&list);
+        sections.push((header, list, options));
+    }
 
-                option_row.append(&radio);
-                option_row.append(&text_box);
+    // Filter long lists by label, description and group
+    let filter_entry: SearchEntry = extract_widget(&builder, "filter_entry");
+    filter_entry.set_visible(config.options.len() > FILTER_THRESHOLD);
+    filter_entry.connect_search_changed(move |entry| {
+        *query.borrow_mut() = entry.text().trim().to_lowercase();
+        let query = query.borrow();
+        for (header, list, options) in &sections {
+            list.invalidate_filter();
+            let any = options.iter().any(|option| option.matches(&query));
+            list.set_visible(any);
+            if let Some(header) = header {
+                header.set_visible(any);
             }
         }
-
-        options_container.append(&option_row);
-
-        // Add separator between options (not after the last one)
-        if i < config.options.len() - 1 {
-            let sep = Separator::new(gtk4::Orientation::Horizontal);
-            options_container.append(&sep);
-        }
-    }
+    });
 
     // Set initial state of confirm button based on selection_required
     if selection_required {
@@ -227,20 +327,13 @@ where
     // Update confirm button sensitivity based on selection
     let update_confirm_button = {
         let confirm_button_clone = confirm_button.clone();
-        let checkboxes_clone = checkboxes.clone();
-        let radio_buttons_clone = radio_buttons.clone();
+        let buttons_clone = buttons.clone();
 
         move || {
-            let has_selection = match selection_type {
-                SelectionType::Multi => checkboxes_clone
-                    .borrow()
-                    .iter()
-                    .any(|(_, checkbox)| checkbox.is_active()),
-                SelectionType::Single => radio_buttons_clone
-                    .borrow()
-                    .iter()
-                    .any(|(_, radio)| radio.is_active()),
-            };
+            let has_selection = buttons_clone
+                .borrow()
+                .iter()
+                .any(|(_, button)| button.is_active());
 
             if selection_required {
                 confirm_button_clone.set_sensitive(has_selection);
@@ -251,42 +344,23 @@ where
     };
 
     // Connect selection change handlers
-    let connect_toggle_handler = |button: &CheckButton| {
+    for (_, button) in buttons.borrow().iter() {
         let update = update_confirm_button.clone();
         button.connect_toggled(move |_| {
             update();
         });
-    };
-
-    for (_, checkbox) in checkboxes.borrow().iter() {
-        connect_toggle_handler(checkbox);
-    }
-
-    for (_, radio) in radio_buttons.borrow().iter() {
-        connect_toggle_handler(radio);
     }
 
     // Confirm button - collect selected options and call callback
     let dialog_clone = dialog.clone();
-    let checkboxes_clone = checkboxes.clone();
-    let radio_buttons_clone = radio_buttons.clone();
+    let buttons_clone = buttons.clone();
     confirm_button.connect_clicked(move |_| {
-        let collect_selected = |(id, button): &(String, CheckButton)| {
-            (button.is_active() && button.is_sensitive()).then_some(id.clone())
-        };
-
-        let selected: Vec<String> = match selection_type {
-            SelectionType::Multi => checkboxes_clone
-                .borrow()
-                .iter()
-                .filter_map(collect_selected)
-                .collect(),
-            SelectionType::Single => radio_buttons_clone
-                .borrow()
-                .iter()
-                .filter_map(collect_selected)
-                .collect(),
-        };
+        let selected: Vec<String> = buttons_clone
+            .borrow()
+            .iter()
+            .filter(|(_, button)| button.is_active() && button.is_sensitive())
+            .map(|(id, _)| id.clone())
+            .collect();
 
         info!(
             "Selection dialog confirmed with {} selections",
@@ -301,3 +375,33 @@ where
     // Show the dialog
     dialog.present();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_options_keeps_first_appearance_order() {
+        let options = vec![
+            SelectionOption::new("a", "A", "", false).in_group("Drivers"),
+            SelectionOption::new("b", "B", "", false),
+            SelectionOption::new("c", "C", "", false).in_group("Drivers"),
+        ];
+        let groups = group_options(&options);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].0.as_deref(), Some("Drivers"));
+        assert_eq!(groups[0].1.len(), 2);
+        assert_eq!(groups[1].0, None);
+    }
+
+    #[test]
+    fn test_matches() {
+        let option = SelectionOption::new("linux-zen", "Zen Kernel", "Tuned for desktops", false)
+            .in_group("Kernels");
+        assert!(option.matches(""));
+        assert!(option.matches("zen"));
+        assert!(option.matches("desktop"));
+        assert!(option.matches("kernels"));
+        assert!(!option.matches("lts"));
+    }
+}
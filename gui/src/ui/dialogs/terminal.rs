@@ -1,16 +1,68 @@
 //! Interactive terminal dialog for running shell commands.
+//!
+//! Commands run in an embedded VTE terminal, so interactive installers
+//! behave the same whichever terminal emulator the user has installed.
+//! Ctrl+Shift+C and Ctrl+Shift+V copy and paste, like in most terminals.
+//! When a terminal emulator is chosen in Preferences, commands open in it
+//! instead, falling back to another installed one and then to the
+//! embedded terminal.
 
-use crate::ui::utils::extract_widget;
-use gtk4::gdk::RGBA;
+use crate::config;
+use crate::config::user;
+use crate::core::terminal as emulator;
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::utils::{extract_widget, spawn_blocking};
+use gtk4::gdk::{self, RGBA};
 use gtk4::prelude::*;
-use gtk4::{Builder, Button, Window};
-use log::{error, info};
+use gtk4::{glib, Builder, Button, Window};
+use log::{error, info, warn};
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::str::FromStr;
 use vte4::prelude::*;
 use vte4::Terminal;
 
+/// How the command in a terminal dialog ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TerminalExit {
+    /// Exited with this code.
+    Exited(i32),
+    /// Killed by this signal.
+    Signaled(i32),
+    /// Couldn't be started.
+    SpawnFailed,
+}
+
+impl TerminalExit {
+    /// Decode the wait status VTE reports in `child-exited`.
+    fn from_wait_status(status: i32) -> Self {
+        if status & 0x7f == 0 {
+            Self::Exited((status >> 8) & 0xff)
+        } else {
+            Self::Signaled(status & 0x7f)
+        }
+    }
+
+    pub fn success(&self) -> bool {
+        *self == Self::Exited(0)
+    }
+
+    fn message(&self) -> String {
+        match self {
+            Self::Exited(0) => gettext("Command exited with code 0 (success)"),
+            Self::Exited(code) => gettext_f(
+                "Command exited with code {code} (error)",
+                &[("code", &code.to_string())],
+            ),
+            Self::Signaled(signal) => gettext_f(
+                "Command was terminated by signal {signal}",
+                &[("signal", &signal.to_string())],
+            ),
+            Self::SpawnFailed => gettext("Command could not be started"),
+        }
+    }
+}
+
 fn update_terminal_style(terminal: &Terminal) {
     let style_manager = adw::StyleManager::default();
     let is_dark = style_manager.is_dark();
@@ -46,12 +98,32 @@ fn update_terminal_style(terminal: &Terminal) {
 
 /// Shows an interactive terminal window for the given command.
 pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &[&str]) {
+    show_terminal_dialog_then(parent, title, command, args, |_| {});
+}
+
+/// Like [`show_terminal_dialog`], calling `on_exit` with how the command
+/// ended once it exits. The window stays open so the output can be read.
+pub fn show_terminal_dialog_then<F>(
+    parent: &Window,
+    title: &str,
+    command: &str,
+    args: &[&str],
+    on_exit: F,
+) where
+    F: FnOnce(TerminalExit) + 'static,
+{
+    let Some(on_exit) = run_in_emulator(title, command, args, on_exit) else {
+        return;
+    };
+
     // Load the UI
-    let builder = Builder::from_resource(crate::config::resources::dialogs::TERMINAL);
+    let builder = Builder::from_resource(config::resources::dialogs::TERMINAL);
 
     let window: adw::Window = extract_widget(&builder, "terminal_window");
     let terminal: Terminal = extract_widget(&builder, "terminal");
     let close_button: Button = extract_widget(&builder, "close_button");
+    let copy_button: Button = extract_widget(&builder, "copy_button");
+    let paste_button: Button = extract_widget(&builder, "paste_button");
 
     window.set_transient_for(Some(parent));
     window.set_title(Some(title));
@@ -59,6 +131,7 @@ pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &
     // Set a nice monospace font
     let font_desc = gtk4::pango::FontDescription::from_string("Monospace 11");
     terminal.set_font(Some(&font_desc));
+    terminal.set_scrollback_lines(config::terminal::SCROLLBACK_LINES);
 
     // Setup theming
     update_terminal_style(&terminal);
@@ -86,6 +159,8 @@ pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &
         window_clone.close();
     });
 
+    setup_clipboard(&terminal, &copy_button, &paste_button);
+
     // Spawn the command
     let mut argv = vec![command.to_string()];
     argv.extend(args.iter().map(|s| s.to_string()));
@@ -93,15 +168,18 @@ pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &
 
     info!("Terminal: Spawning {:?} in interactive window", argv_refs);
 
+    let on_exit = Rc::new(RefCell::new(Some(on_exit)));
+
     let close_button_clone = close_button.clone();
     let close_button_error = close_button.clone();
     let terminal_error = terminal.clone();
+    let on_exit_error = on_exit.clone();
     terminal.spawn_async(
         vte4::PtyFlags::DEFAULT,
         None,
         &argv_refs,
         &[],
-        gtk4::glib::SpawnFlags::SEARCH_PATH,
+        glib::SpawnFlags::SEARCH_PATH,
         || {}, // child setup
         -1,
         None::<&gtk4::gio::Cancellable>,
@@ -114,6 +192,10 @@ pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &
                 // Enable close button and make it blue on error
                 close_button_error.add_css_class("suggested-action");
                 close_button_error.set_sensitive(true);
+
+                if let Some(on_exit) = on_exit_error.borrow_mut().take() {
+                    on_exit(TerminalExit::SpawnFailed);
+                }
             }
         },
     );
@@ -121,19 +203,128 @@ pub fn show_terminal_dialog(parent: &Window, title: &str, command: &str, args: &
     // Enable close button and show exit status when child exits
     let terminal_exit = terminal.clone();
     terminal.connect_child_exited(move |_, status| {
-        // Print exit message to terminal with improved formatting
-        let exit_code = status;
-        let status_text = if exit_code == 0 { "success" } else { "error" };
+        let exit = TerminalExit::from_wait_status(status);
+        info!("Terminal: {}", exit.message());
+
+        // Print exit message to terminal
         let message = format!(
-            "\r\n[Process completed] Command exited with code {} ({})\r\n",
-            exit_code, status_text
+            "\r\n[{}] {}\r\n",
+            gettext("Process completed"),
+            exit.message()
         );
         terminal_exit.feed(message.as_bytes());
 
         // Enable close button and ensure it's blue
         close_button_clone.add_css_class("suggested-action");
         close_button_clone.set_sensitive(true);
+
+        if let Some(on_exit) = on_exit.borrow_mut().take() {
+            on_exit(exit);
+        }
     });
 
     window.present();
 }
+
+/// Run the command in the terminal emulator chosen in Preferences, or one
+/// installed in its place. Hands `on_exit` back when there's none to run it
+/// in, for the embedded terminal.
+fn run_in_emulator<F>(title: &str, command: &str, args: &[&str], on_exit: F) -> Option<F>
+where
+    F: FnOnce(TerminalExit) + 'static,
+{
+    let Some(emulator) = user::load().terminal.and_then(emulator::pick) else {
+        return Some(on_exit);
+    };
+
+    let status_file = emulator::status_file();
+    let argv = emulator.argv(title, &emulator::wrapped(command, args, &status_file));
+    info!("Terminal: Spawning {:?} in {}", argv, emulator.name());
+
+    let mut child = match std::process::Command::new(&argv[0])
+        .args(&argv[1..])
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            warn!(
+                "Failed to start {}, using the built-in terminal: {}",
+                emulator.name(),
+                e
+            );
+            return Some(on_exit);
+        }
+    };
+    spawn_blocking(
+        move || {
+            let _ = child.wait();
+            emulator::read_status(&status_file)
+        },
+        move |status| {
+            let exit = status.map_or(TerminalExit::SpawnFailed, TerminalExit::Exited);
+            info!("Terminal: {}", exit.message());
+            on_exit(exit);
+        },
+    );
+    None
+}
+
+/// Copy and paste through the header bar buttons and Ctrl+Shift+C/V.
+fn setup_clipboard(terminal: &Terminal, copy_button: &Button, paste_button: &Button) {
+    let copy_button_clone = copy_button.clone();
+    terminal.connect_selection_changed(move |terminal| {
+        copy_button_clone.set_sensitive(terminal.has_selection());
+    });
+
+    let terminal_clone = terminal.clone();
+    copy_button.connect_clicked(move |_| {
+        terminal_clone.copy_clipboard_format(vte4::Format::Text);
+    });
+
+    let terminal_clone = terminal.clone();
+    paste_button.connect_clicked(move |_| {
+        terminal_clone.paste_clipboard();
+        terminal_clone.grab_focus();
+    });
+
+    // Capture phase, before VTE sends the keys to the command
+    let key_controller = gtk4::EventControllerKey::new();
+    key_controller.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let terminal_clone = terminal.clone();
+    key_controller.connect_key_pressed(move |_, key, _, modifiers| {
+        let shortcut = gdk::ModifierType::CONTROL_MASK | gdk::ModifierType::SHIFT_MASK;
+        if !modifiers.contains(shortcut) {
+            return glib::Propagation::Proceed;
+        }
+        match key.to_lower() {
+            gdk::Key::c => {
+                terminal_clone.copy_clipboard_format(vte4::Format::Text);
+                glib::Propagation::Stop
+            }
+            gdk::Key::v => {
+                terminal_clone.paste_clipboard();
+                glib::Propagation::Stop
+            }
+            _ => glib::Propagation::Proceed,
+        }
+    });
+    terminal.add_controller(key_controller);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_wait_status() {
+        assert_eq!(TerminalExit::from_wait_status(0), TerminalExit::Exited(0));
+        assert_eq!(
+            TerminalExit::from_wait_status(1 << 8),
+            TerminalExit::Exited(1)
+        );
+        assert_eq!(TerminalExit::from_wait_status(9), TerminalExit::Signaled(9));
+        assert!(TerminalExit::Exited(0).success());
+        assert!(!TerminalExit::Exited(127).success());
+        assert!(!TerminalExit::SpawnFailed.success());
+    }
+}
@@ -1,8 +1,10 @@
 //! Warning confirmation dialog for experimental features.
 
+use crate::core::snapshot::Tool;
+use crate::ui::task_runner::Command;
 use crate::ui::utils::extract_widget;
 use gtk4::prelude::*;
-use gtk4::{Builder, Button, Label, Window};
+use gtk4::{Builder, Button, CheckButton, Label, Window};
 use log::info;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -12,6 +14,39 @@ use std::rc::Rc;
 pub fn show_warning_confirmation<F>(parent: &Window, heading: &str, message: &str, on_confirm: F)
 where
     F: FnOnce() + 'static,
+{
+    present(parent, heading, message, None, move |_| on_confirm());
+}
+
+/// Like [`show_warning_confirmation`], for actions that are hard to undo.
+/// When Snapper or Timeshift is set up, the dialog offers to take a
+/// snapshot first, and `on_confirm` gets the step taking it, to run
+/// before the action's own steps.
+pub fn show_destructive_confirmation<F>(
+    parent: &Window,
+    heading: &str,
+    message: &str,
+    on_confirm: F,
+) where
+    F: FnOnce(Option<Command>) + 'static,
+{
+    let tool = Tool::detect();
+    let comment = format!("Before {}", heading);
+    present(parent, heading, message, tool, move |snapshot| {
+        on_confirm(tool.filter(|_| snapshot).map(|t| t.create(&comment)));
+    });
+}
+
+/// Show the dialog, with the snapshot option when `snapshot_tool` is set.
+/// `on_confirm` gets whether the option was ticked.
+fn present<F>(
+    parent: &Window,
+    heading: &str,
+    message: &str,
+    snapshot_tool: Option<Tool>,
+    on_confirm: F,
+) where
+    F: FnOnce(bool) + 'static,
 {
     info!("Showing warning confirmation dialog: {}", heading);
 
@@ -27,6 +62,7 @@ where
     // Get UI elements
     let heading_label: Label = extract_widget(&builder, "dialog_heading");
     let warning_message: Label = extract_widget(&builder, "warning_message");
+    let snapshot_check: CheckButton = extract_widget(&builder, "snapshot_check");
     let cancel_button: Button = extract_widget(&builder, "cancel_button");
     let continue_button: Button = extract_widget(&builder, "continue_button");
 
@@ -36,6 +72,15 @@ where
     // Set message with Pango markup
     warning_message.set_markup(message);
 
+    if let Some(tool) = snapshot_tool {
+        snapshot_check.set_tooltip_text(Some(&crate::i18n::gettext_f(
+            "Take a {tool} snapshot to roll back to if something goes wrong",
+            &[("tool", tool.name())],
+        )));
+        snapshot_check.set_active(true);
+        snapshot_check.set_visible(true);
+    }
+
     // Setup callbacks
     let dialog_clone = dialog.clone();
     cancel_button.connect_clicked(move |_| {
@@ -49,7 +94,7 @@ where
     continue_button.connect_clicked(move |_| {
         info!("Warning dialog confirmed");
         if let Some(on_confirm) = on_confirm_rc.borrow_mut().take() {
-            on_confirm();
+            on_confirm(snapshot_check.is_visible() && snapshot_check.is_active());
         }
         dialog_clone.close();
     });
@@ -0,0 +1,106 @@
+//! Header bar indicator for an active root session.
+//!
+//! With the session privilege backend, the xero-auth daemon keeps root for
+//! the length of a sequence. A shield shows in the header bar while it
+//! runs. Its tooltip tells how many commands ran and when the session
+//! times out, and its popover can end the session right away.
+
+use crate::core::daemon;
+use crate::core::task_runner::stop_daemon_if_needed;
+use crate::ui::utils::{extract_widget, spawn_blocking};
+use gtk4::prelude::*;
+use gtk4::{glib, Builder, Label, MenuButton};
+use log::info;
+use std::cell::RefCell;
+use std::time::Duration;
+use xero_auth::SessionStatus;
+
+/// How often the status is refreshed while a session is active.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Clone)]
+struct Indicator {
+    button: MenuButton,
+    status: Label,
+}
+
+thread_local! {
+    /// Shield button in the main window's header bar.
+    static INDICATOR: RefCell<Option<Indicator>> = const { RefCell::new(None) };
+    /// Timer refreshing the status while the shield shows.
+    static POLL: RefCell<Option<glib::SourceId>> = const { RefCell::new(None) };
+}
+
+/// Remember the main window's indicator and wire its drop button.
+pub fn setup(builder: &Builder) {
+    let indicator = Indicator {
+        button: extract_widget(builder, "elevated_indicator"),
+        status: extract_widget(builder, "elevated_status_label"),
+    };
+    let drop_button = extract_widget::<gtk4::Button>(builder, "drop_privileges_button");
+
+    let button = indicator.button.clone();
+    drop_button.connect_clicked(move |_| {
+        button.popdown();
+        drop_privileges();
+    });
+
+    INDICATOR.with(|i| *i.borrow_mut() = Some(indicator));
+}
+
+/// Show or hide the indicator, as the task runner starts or stops the
+/// daemon.
+pub fn set_active(active: bool) {
+    let Some(indicator) = INDICATOR.with(|i| i.borrow().clone()) else {
+        return;
+    };
+    indicator.button.set_visible(active);
+
+    POLL.with(|poll| {
+        if let Some(source) = poll.borrow_mut().take() {
+            source.remove();
+        }
+        if active {
+            refresh();
+            let source = glib::timeout_add_local(POLL_INTERVAL, || {
+                refresh();
+                glib::ControlFlow::Continue
+            });
+            *poll.borrow_mut() = Some(source);
+        }
+    });
+}
+
+/// Ask the daemon for its status, hiding the shield once it's gone.
+fn refresh() {
+    spawn_blocking(daemon::session_status, |status| match status {
+        Some(status) => show_status(&status),
+        None => set_active(false),
+    });
+}
+
+fn show_status(status: &SessionStatus) {
+    let Some(indicator) = INDICATOR.with(|i| i.borrow().clone()) else {
+        return;
+    };
+    let remaining = status.remaining.as_secs();
+    let text = crate::i18n::gettext_f(
+        "Elevated session active\nCommands run: {count}\nTimes out in {time} when idle",
+        &[
+            ("count", &status.commands_run.to_string()),
+            ("time", &format!("{}:{:02}", remaining / 60, remaining % 60)),
+        ],
+    );
+    indicator.button.set_tooltip_text(Some(&text));
+    indicator.status.set_text(&text);
+}
+
+/// End the root session now. Privileged steps of a running sequence fail
+/// after this.
+fn drop_privileges() {
+    info!("Dropping the elevated session");
+    spawn_blocking(stop_daemon_if_needed, |()| {
+        set_active(false);
+        crate::ui::toast::show(&crate::i18n::gettext("Root session ended"));
+    });
+}
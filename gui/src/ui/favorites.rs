@@ -0,0 +1,189 @@
+//! Favorite actions pinned to the main page.
+//!
+//! Right-clicking the button of a registered action offers to pin or unpin
+//! it. Pins are saved in `config::user` as `category/id` and shown as
+//! buttons in the Favorites section of the main page.
+
+use crate::actions::{self, Action};
+use crate::config;
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::utils::extract_widget;
+use adw::prelude::*;
+use gtk4::{glib, ApplicationWindow, Builder, FlowBox};
+use log::{info, warn};
+use std::cell::RefCell;
+
+thread_local! {
+    /// Rebuilds the Favorites section after the pins change.
+    static REFRESH: RefCell<Option<Box<dyn Fn()>>> = const { RefCell::new(None) };
+}
+
+fn key(action: &Action) -> String {
+    format!("{}/{}", action.category, action.id)
+}
+
+/// Pinned actions, in the order they were pinned.
+pub fn favorites() -> Vec<&'static Action> {
+    config::user::load()
+        .favorites
+        .iter()
+        .filter_map(|key| {
+            let (category, id) = key.split_once('/')?;
+            actions::find(category, id)
+        })
+        .collect()
+}
+
+/// Whether `action` is pinned.
+pub fn is_favorite(action: &Action) -> bool {
+    config::user::load().favorites.contains(&key(action))
+}
+
+fn toggle(action: &Action) -> anyhow::Result<()> {
+    let key = key(action);
+    config::user::update(|settings| {
+        if let Some(pos) = settings.favorites.iter().position(|k| *k == key) {
+            settings.favorites.remove(pos);
+        } else {
+            settings.favorites.push(key);
+        }
+    })?;
+
+    refresh();
+    Ok(())
+}
+
+/// Pin or unpin several actions at once, keeping the order of existing pins.
+pub fn set_pinned(changes: &[(&Action, bool)]) -> anyhow::Result<()> {
+    config::user::update(|settings| {
+        for (action, pinned) in changes {
+            let key = key(action);
+            let present = settings.favorites.contains(&key);
+            if *pinned && !present {
+                settings.favorites.push(key);
+            } else if !*pinned && present {
+                settings.favorites.retain(|k| *k != key);
+            }
+        }
+    })?;
+
+    refresh();
+    Ok(())
+}
+
+fn refresh() {
+    REFRESH.with(|refresh| {
+        if let Some(refresh) = refresh.borrow().as_ref() {
+            refresh();
+        }
+    });
+}
+
+/// Set up the Favorites section of the main page.
+pub fn setup_section(page_builder: &Builder, window: &ApplicationWindow) {
+    let section = extract_widget::<gtk4::Box>(page_builder, "favorites_section");
+    let flow_box = extract_widget::<FlowBox>(page_builder, "favorites_box");
+
+    let window = window.clone();
+    let refresh = move || populate(&section, &flow_box, &window);
+    refresh();
+    REFRESH.with(|r| *r.borrow_mut() = Some(Box::new(refresh)));
+}
+
+fn populate(section: &gtk4::Box, flow_box: &FlowBox, window: &ApplicationWindow) {
+    flow_box.remove_all();
+
+    let favorites = favorites();
+    section.set_visible(!favorites.is_empty());
+
+    for action in favorites {
+        let button = gtk4::Button::builder()
+            .label(gettext(action.title))
+            .tooltip_text(gettext(action.description))
+            .width_request(200)
+            .height_request(50)
+            .css_classes(vec!["pill".to_string()])
+            .build();
+
+        let window_clone = window.clone();
+        button.connect_clicked(move |_| {
+            info!("Favorites: {} clicked", action.title);
+            crate::ui::pages::run_action(&window_clone, action);
+        });
+        attach_context_menu(action, button.upcast_ref(), window);
+        flow_box.append(&button);
+    }
+}
+
+/// Offer pin/unpin on right-click for the registered actions on a page.
+pub fn attach_page_menus(page_id: &str, page_builder: &Builder, window: &ApplicationWindow) {
+    for action in actions::ACTIONS.iter().filter(|a| a.page == page_id) {
+        match page_builder.object::<gtk4::Widget>(action.widget) {
+            Some(widget) => attach_context_menu(action, &widget, window),
+            None => warn!("Widget '{}' not found on page '{}'", action.widget, page_id),
+        }
+    }
+}
+
+fn attach_context_menu(action: &'static Action, widget: &gtk4::Widget, window: &ApplicationWindow) {
+    let gesture = gtk4::GestureClick::builder()
+        .button(gtk4::gdk::BUTTON_SECONDARY)
+        .build();
+
+    let widget_weak = widget.downgrade();
+    let window = window.clone();
+    gesture.connect_pressed(move |gesture, _, _, _| {
+        gesture.set_state(gtk4::EventSequenceState::Claimed);
+        let Some(widget) = widget_weak.upgrade() else {
+            return;
+        };
+
+        let (label, icon) = if is_favorite(action) {
+            (gettext("Remove from Favorites"), "circle-xmark")
+        } else {
+            (gettext("Add to Favorites"), "star-symbolic")
+        };
+        let item = gtk4::Button::builder()
+            .child(
+                &adw::ButtonContent::builder()
+                    .label(&label)
+                    .icon_name(icon)
+                    .build(),
+            )
+            .css_classes(vec!["flat".to_string()])
+            .build();
+
+        let popover = gtk4::Popover::builder().child(&item).build();
+        popover.set_parent(&widget);
+        // Unparent once closed, outside the signal handler
+        popover.connect_closed(|popover| {
+            let popover = popover.clone();
+            glib::idle_add_local_once(move || popover.unparent());
+        });
+
+        let popover_clone = popover.clone();
+        let window = window.clone();
+        item.connect_clicked(move |_| {
+            popover_clone.popdown();
+            // Toggling may rebuild the Favorites section, so wait until the
+            // popover has been unparented from the button
+            let window = window.clone();
+            glib::idle_add_local_once(move || {
+                if let Err(e) = toggle(action) {
+                    show_error(
+                        &window,
+                        &gettext_f(
+                            "Failed to save favorites: {error}",
+                            &[("error", &format!("{:#}", e))],
+                        ),
+                    );
+                }
+            });
+        });
+
+        popover.popup();
+    });
+
+    widget.add_controller(gesture);
+}
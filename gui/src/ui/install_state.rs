@@ -0,0 +1,249 @@
+//! Shared install state for page buttons.
+//!
+//! Pages bind a [`Probe`] to a callback that updates their buttons. All
+//! probes run together on a worker thread against one [`Snapshot`] of the
+//! installed packages and Flatpak apps, so a page costs a lookup rather
+//! than a `pacman -Q` per button. The snapshot is prefetched at startup and
+//! read again after every task runner sequence and from the Refresh button
+//! (F5). Callbacks only run when their probe's result changes.
+
+use crate::core;
+use gtk4::glib;
+use gtk4::prelude::*;
+use log::debug;
+use std::cell::{Cell, RefCell};
+use std::collections::HashSet;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Style class for widgets still waiting for their first result.
+const PENDING_CLASS: &str = "install-state-pending";
+
+/// How to tell whether something is installed.
+#[derive(Clone, Copy, Debug)]
+pub enum Probe {
+    /// A pacman package.
+    Package(&'static str),
+    /// Any of several pacman packages, e.g. `-bin` and `-git` variants.
+    AnyPackage(&'static [&'static str]),
+    /// A Flatpak app id.
+    Flatpak(&'static str),
+    /// A file or directory.
+    Path(&'static str),
+    /// Any other check. Runs on the worker thread.
+    Custom(fn() -> bool),
+}
+
+impl Probe {
+    fn check(&self, snapshot: &Snapshot) -> bool {
+        match self {
+            Probe::Package(name) => snapshot.packages.contains(*name),
+            Probe::AnyPackage(names) => names.iter().any(|n| snapshot.packages.contains(*n)),
+            Probe::Flatpak(id) => snapshot.flatpaks.contains(*id),
+            Probe::Path(path) => std::path::Path::new(path).exists(),
+            Probe::Custom(check) => check(),
+        }
+    }
+}
+
+/// Installed packages and Flatpak apps, read once per refresh.
+#[derive(Default)]
+struct Snapshot {
+    packages: HashSet<String>,
+    flatpaks: HashSet<String>,
+}
+
+impl Snapshot {
+    fn read() -> Self {
+        Self {
+            packages: core::package::installed_packages(),
+            flatpaks: core::package::installed_flatpaks(),
+        }
+    }
+}
+
+struct Binding {
+    probe: Probe,
+    on_change: Rc<dyn Fn(bool)>,
+    last: Option<bool>,
+}
+
+/// Bound probes and the listeners notified after each refresh.
+#[derive(Default)]
+struct InstallStateRegistry {
+    bindings: Vec<Binding>,
+    listeners: Vec<Rc<dyn Fn()>>,
+    /// Snapshot from the last pass, reused for probes bound since.
+    snapshot: Option<Arc<Snapshot>>,
+    /// The system may have changed since the snapshot was read.
+    stale: bool,
+    /// A pass is running on the worker thread.
+    running: bool,
+    /// Another pass was requested while one was running.
+    pending: bool,
+    /// A pass is queued on the main loop.
+    scheduled: bool,
+}
+
+thread_local! {
+    static REGISTRY: RefCell<InstallStateRegistry> = RefCell::new(InstallStateRegistry::default());
+}
+
+/// Call `on_change` with whether `probe` finds its target installed, once
+/// known and again whenever it changes.
+pub fn bind(probe: Probe, on_change: impl Fn(bool) + 'static) {
+    REGISTRY.with(|registry| {
+        registry.borrow_mut().bindings.push(Binding {
+            probe,
+            on_change: Rc::new(on_change),
+            last: None,
+        })
+    });
+    schedule_pass();
+}
+
+/// Like [`bind`], dimming `widget` and ignoring clicks on it until the
+/// first result is in.
+pub fn bind_widget(
+    widget: &impl IsA<gtk4::Widget>,
+    probe: Probe,
+    on_change: impl Fn(bool) + 'static,
+) {
+    let widget = widget.clone().upcast::<gtk4::Widget>();
+    widget.add_css_class(PENDING_CLASS);
+    widget.set_can_target(false);
+
+    let first = Cell::new(true);
+    bind(probe, move |installed| {
+        if first.replace(false) {
+            widget.remove_css_class(PENDING_CLASS);
+            widget.set_can_target(true);
+        }
+        on_change(installed);
+    });
+}
+
+/// Call `listener` after every refresh, for state that isn't a single probe.
+pub fn connect_refresh(listener: impl Fn() + 'static) {
+    REGISTRY.with(|registry| registry.borrow_mut().listeners.push(Rc::new(listener)));
+    schedule_pass();
+}
+
+/// Read the installed packages ahead of the pages that need them.
+pub fn prefetch() {
+    schedule_pass();
+}
+
+/// Read the installed packages again and re-run all probes off the main
+/// thread, e.g. after a task changed the system.
+pub fn refresh() {
+    REGISTRY.with(|registry| registry.borrow_mut().stale = true);
+    run_pass();
+}
+
+/// Check `probe` right away, from the snapshot when there is one. For
+/// one-off checks, such as marking installed options in a dialog.
+pub fn is_installed(probe: Probe) -> bool {
+    let snapshot = REGISTRY.with(|registry| {
+        let registry = registry.borrow();
+        registry.snapshot.clone().filter(|_| !registry.stale)
+    });
+    match snapshot {
+        Some(snapshot) => probe.check(&snapshot),
+        None => match probe {
+            Probe::Package(name) => core::is_package_installed(name),
+            Probe::AnyPackage(names) => names.iter().any(|n| core::is_package_installed(n)),
+            Probe::Flatpak(id) => core::is_flatpak_installed(id),
+            _ => probe.check(&Snapshot::default()),
+        },
+    }
+}
+
+/// Run a pass from the main loop, once for any number of calls before it runs.
+fn schedule_pass() {
+    let already =
+        REGISTRY.with(|registry| std::mem::replace(&mut registry.borrow_mut().scheduled, true));
+    if !already {
+        glib::idle_add_local_once(|| {
+            REGISTRY.with(|registry| registry.borrow_mut().scheduled = false);
+            run_pass();
+        });
+    }
+}
+
+/// Run all probes on a worker thread, reading a new snapshot if needed.
+/// A pass requested while one is running starts again once it's done.
+fn run_pass() {
+    let work = REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        if registry.running {
+            registry.pending = true;
+            return None;
+        }
+        registry.running = true;
+        let snapshot = if registry.stale {
+            None
+        } else {
+            registry.snapshot.clone()
+        };
+        registry.stale = false;
+        let probes: Vec<Probe> = registry.bindings.iter().map(|b| b.probe).collect();
+        Some((snapshot, probes))
+    });
+    let Some((snapshot, probes)) = work else {
+        return;
+    };
+    debug!(
+        "Checking {} install states ({} snapshot)",
+        probes.len(),
+        if snapshot.is_some() { "cached" } else { "new" }
+    );
+
+    let (sender, receiver) = async_channel::bounded(1);
+    std::thread::spawn(move || {
+        let snapshot = snapshot.unwrap_or_else(|| Arc::new(Snapshot::read()));
+        let states: Vec<bool> = probes.iter().map(|p| p.check(&snapshot)).collect();
+        let _ = sender.send_blocking((snapshot, states));
+    });
+
+    glib::MainContext::default().spawn_local(async move {
+        match receiver.recv().await {
+            Ok((snapshot, states)) => apply(Some(snapshot), &states),
+            Err(_) => apply(None, &[]),
+        }
+    });
+}
+
+fn apply(snapshot: Option<Arc<Snapshot>>, states: &[bool]) {
+    // Callbacks may bind more probes, so they run outside the borrow
+    let (changed, listeners, pending) = REGISTRY.with(|registry| {
+        let mut registry = registry.borrow_mut();
+        // Keep the snapshot unless a refresh was requested while reading it
+        if snapshot.is_some() && !registry.stale {
+            registry.snapshot = snapshot;
+        }
+        let changed: Vec<_> = registry
+            .bindings
+            .iter_mut()
+            .zip(states)
+            .filter(|(binding, state)| binding.last != Some(**state))
+            .map(|(binding, &state)| {
+                binding.last = Some(state);
+                (binding.on_change.clone(), state)
+            })
+            .collect();
+        registry.running = false;
+        let pending = std::mem::take(&mut registry.pending);
+        (changed, registry.listeners.clone(), pending)
+    });
+
+    for (on_change, state) in changed {
+        on_change(state);
+    }
+    for listener in listeners {
+        listener();
+    }
+    if pending {
+        run_pass();
+    }
+}
@@ -1,21 +1,51 @@
 //! User interface components and functionality.
 //!
 //! This module contains all UI-related components organized by functionality:
+//! - `activity`: Recent task runner activity on the main page
 //! - `app`: Application setup and initialization
+//! - `batch`: Batch Mode, queueing several actions into one sequence
 //! - `context`: Application state and UI components
+//! - `dashboard`: Status cards on the main page
 //! - `navigation`: Tab navigation and sidebar management
+//! - `dbus`: Session bus service exposing actions to other programs
+//! - `degraded`: Banners and disabled actions when dependencies are missing
+//! - `diagnostics`: Widgets missing from .ui files, for error reports
 //! - `dialogs`: Dialog windows (error, selection, download)
+//! - `elevation`: Header bar indicator for an active root session
+//! - `favorites`: Actions pinned to the main page
+//! - `install_state`: Shared install state that page buttons bind to
 //! - `task_runner`: Command execution with progress UI
+//! - `toast`: Toasts for short informational results
+//! - `tray`: Background agent with a tray icon and update notifications
 //! - `pages`: Page-specific button handlers
+//! - `relevance`: Actions hidden or annotated for the hardware they need
+//! - `search`: Header bar search across pages and actions
+//! - `shortcuts`: Keyboard shortcuts and the shortcuts dialog
+//! - `theme`: Color scheme, accent color and the toolkit stylesheet
 
+pub mod activity;
 pub mod app;
+pub mod batch;
 pub mod context;
+pub mod dashboard;
+pub mod dbus;
+pub mod degraded;
+pub mod diagnostics;
 pub mod dialogs;
+pub mod elevation;
+pub mod favorites;
+pub mod install_state;
 pub mod navigation;
 pub mod pages;
+pub mod relevance;
+pub mod search;
 pub mod seasonal;
+pub mod shortcuts;
 pub mod task_runner;
+pub mod theme;
+pub mod toast;
+pub mod tray;
 pub mod utils;
 
 // Re-export the main entry point
-pub use app::setup_application_ui;
+pub use app::{handle_command_line, setup_application_ui};
@@ -5,6 +5,8 @@
 //! Only the initial (first) page is loaded eagerly. This avoids parsing
 //! 10 UI files and spawning dozens of subprocess checks at startup.
 
+use crate::actions::Requirement;
+use crate::i18n::{gettext, n_};
 use crate::ui::pages;
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, Box as GtkBox, Builder, Button, Image, Label, Orientation, Stack};
@@ -19,6 +21,9 @@ pub struct PageConfig {
     pub title: &'static str,
     pub icon: &'static str,
     pub ui_resource: &'static str,
+    /// Tools some of the page's buttons need. If any is missing, the page
+    /// shows a banner offering to install it.
+    pub requires: &'static [Requirement],
     pub setup_handler: Option<fn(&Builder, &Builder, &ApplicationWindow)>,
 }
 
@@ -27,79 +32,170 @@ pub struct PageConfig {
 pub const PAGES: &[PageConfig] = &[
     PageConfig {
         id: "main_page",
-        title: "Main Page",
+        title: n_("Main Page"),
         icon: "house-symbolic",
         ui_resource: crate::config::resources::tabs::MAIN_PAGE,
+        requires: &[Requirement::AurHelper, Requirement::Flatpak],
         setup_handler: Some(pages::main_page::setup_handlers),
     },
     PageConfig {
         id: "drivers",
-        title: "Drivers",
+        title: n_("Drivers"),
         icon: "gear-symbolic",
         ui_resource: crate::config::resources::tabs::DRIVERS,
+        requires: &[Requirement::AurHelper],
         setup_handler: Some(pages::drivers::setup_handlers),
     },
     PageConfig {
         id: "customization",
-        title: "Customization",
+        title: n_("Customization"),
         icon: "brush-symbolic",
         ui_resource: crate::config::resources::tabs::CUSTOMIZATION,
+        requires: &[Requirement::AurHelper, Requirement::Flatpak],
         setup_handler: Some(pages::customization::setup_handlers),
     },
     PageConfig {
         id: "gaming_tools",
-        title: "Gaming Tools",
+        title: n_("Gaming Tools"),
         icon: "gamepad-symbolic",
         ui_resource: crate::config::resources::tabs::GAMING_TOOLS,
+        requires: &[Requirement::AurHelper, Requirement::Flatpak],
         setup_handler: Some(pages::gaming_tools::setup_handlers),
     },
     PageConfig {
         id: "gamescope",
-        title: "Gamescope",
+        title: n_("Gamescope"),
         icon: "steam-symbolic",
         ui_resource: crate::config::resources::tabs::GAMESCOPE,
+        requires: &[],
         setup_handler: Some(pages::gamescope::setup_handlers),
     },
     PageConfig {
         id: "containers_vms",
-        title: "Containers/VMs",
+        title: n_("Containers/VMs"),
         icon: "box-symbolic",
         ui_resource: crate::config::resources::tabs::CONTAINERS_VMS,
+        requires: &[Requirement::AurHelper, Requirement::Flatpak],
         setup_handler: Some(pages::containers_vms::setup_handlers),
     },
+    PageConfig {
+        id: "dev_tools",
+        title: n_("Dev Tools"),
+        icon: "code-symbolic",
+        ui_resource: crate::config::resources::tabs::DEV_TOOLS,
+        requires: &[Requirement::AurHelper, Requirement::Flatpak],
+        setup_handler: Some(pages::dev_tools::setup_handlers),
+    },
+    PageConfig {
+        id: "local_ai",
+        title: n_("Local AI"),
+        icon: "sparkles-symbolic",
+        ui_resource: crate::config::resources::tabs::LOCAL_AI,
+        requires: &[Requirement::AurHelper],
+        setup_handler: Some(pages::local_ai::setup_handlers),
+    },
     PageConfig {
         id: "multimedia_tools",
-        title: "Multimedia Tools",
+        title: n_("Multimedia Tools"),
         icon: "play-symbolic",
         ui_resource: crate::config::resources::tabs::MULTIMEDIA_TOOLS,
+        requires: &[Requirement::AurHelper, Requirement::Flatpak],
         setup_handler: Some(pages::multimedia_tools::setup_handlers),
     },
     PageConfig {
         id: "kernel_schedulers",
-        title: "Kernel & Schedulers",
+        title: n_("Kernel & Schedulers"),
         icon: "hammer-symbolic",
         ui_resource: crate::config::resources::tabs::KERNEL_SCHEDULERS,
+        requires: &[Requirement::AurHelper],
         setup_handler: Some(pages::kernel_schedulers::setup_handlers),
     },
+    PageConfig {
+        id: "power_management",
+        title: n_("Power Management"),
+        icon: "gauge-symbolic",
+        ui_resource: crate::config::resources::tabs::POWER_MANAGEMENT,
+        requires: &[Requirement::AurHelper],
+        setup_handler: Some(pages::power_management::setup_handlers),
+    },
     PageConfig {
         id: "servicing_system_tweaks",
-        title: "Servicing/System tweaks",
+        title: n_("Servicing/System tweaks"),
         icon: "toolbox-symbolic",
         ui_resource: crate::config::resources::tabs::SERVICING_SYSTEM_TWEAKS,
+        requires: &[Requirement::AurHelper],
         setup_handler: Some(pages::servicing::setup_handlers),
     },
+    PageConfig {
+        id: "scheduled_maintenance",
+        title: n_("Scheduled Maintenance"),
+        icon: "arrows-rotate-symbolic",
+        ui_resource: crate::config::resources::tabs::SCHEDULED_MAINTENANCE,
+        requires: &[Requirement::AurHelper],
+        setup_handler: Some(pages::scheduled_maintenance::setup_handlers),
+    },
+    PageConfig {
+        id: "backup",
+        title: n_("Backup"),
+        icon: "drive-harddisk-symbolic",
+        ui_resource: crate::config::resources::tabs::BACKUP,
+        requires: &[],
+        setup_handler: Some(pages::backup::setup_handlers),
+    },
+    PageConfig {
+        id: "disks",
+        title: n_("Disks"),
+        icon: "drive-multidisk-symbolic",
+        ui_resource: crate::config::resources::tabs::DISKS,
+        requires: &[],
+        setup_handler: Some(pages::disks::setup_handlers),
+    },
+    PageConfig {
+        id: "system_settings",
+        title: n_("System Settings"),
+        icon: "globe-symbolic",
+        ui_resource: crate::config::resources::tabs::SYSTEM_SETTINGS,
+        requires: &[],
+        setup_handler: Some(pages::system_settings::setup_handlers),
+    },
+    PageConfig {
+        id: "network_tools",
+        title: n_("Network Tools"),
+        icon: "network-workgroup-symbolic",
+        ui_resource: crate::config::resources::tabs::NETWORK_TOOLS,
+        requires: &[],
+        setup_handler: Some(pages::network_tools::setup_handlers),
+    },
     PageConfig {
         id: "biometrics",
-        title: "Biometrics",
+        title: n_("Biometrics"),
         icon: "xfprintd-gui",
         ui_resource: crate::config::resources::tabs::BIOMETRICS,
+        requires: &[Requirement::AurHelper],
         setup_handler: Some(pages::biometrics::setup_handlers),
     },
+    PageConfig {
+        id: "uninstall",
+        title: n_("Uninstall Manager"),
+        icon: "user-trash-symbolic",
+        ui_resource: crate::config::resources::tabs::UNINSTALL,
+        requires: &[],
+        setup_handler: Some(pages::uninstall::setup_handlers),
+    },
+    PageConfig {
+        id: "custom",
+        title: n_("Custom Actions"),
+        icon: "terminal-symbolic",
+        ui_resource: crate::config::resources::tabs::CUSTOM,
+        requires: &[],
+        setup_handler: Some(pages::custom::setup_handlers),
+    },
 ];
 
 /// Everything needed to lazily load a page on first visit.
 struct PendingPage {
     ui_resource: &'static str,
+    requires: &'static [Requirement],
     setup_fn: Option<fn(&Builder, &Builder, &ApplicationWindow)>,
     /// The empty container sitting in the stack — we'll populate it on first visit.
     container: GtkBox,
@@ -124,7 +220,7 @@ impl Tab {
         let image = Image::from_icon_name(icon_name);
         image.set_pixel_size(18);
 
-        let label_widget = Label::new(Some(label));
+        let label_widget = Label::new(Some(&gettext(label)));
         label_widget.set_xalign(0.0);
 
         content_box.append(&image);
@@ -136,6 +232,8 @@ impl Tab {
             .build();
 
         button.set_child(Some(&content_box));
+        // Lets `open_page` find the tab for a page id
+        button.set_widget_name(page_name);
 
         Tab {
             page_name: page_name.to_string(),
@@ -181,11 +279,26 @@ fn load_pending_page(page_id: &str, pending: PendingPage, main_builder: &Builder
         Some(page_widget) => {
             pending.container.append(&page_widget);
 
+            let window: ApplicationWindow =
+                crate::ui::utils::extract_widget(main_builder, "app_window");
+            let missing_before = crate::ui::diagnostics::missing_widget_count();
             if let Some(setup_fn) = pending.setup_fn {
-                let window: ApplicationWindow =
-                    crate::ui::utils::extract_widget(main_builder, "app_window");
                 setup_fn(&page_builder, main_builder, &window);
             }
+            if crate::ui::diagnostics::missing_widget_count() > missing_before {
+                crate::ui::toast::show(&crate::i18n::gettext(
+                    "Some actions on this page are unavailable",
+                ));
+            }
+            crate::ui::favorites::attach_page_menus(page_id, &page_builder, &window);
+            crate::ui::degraded::apply_to_page(
+                page_id,
+                pending.requires,
+                &page_builder,
+                &pending.container,
+                &window,
+            );
+            crate::ui::relevance::apply_to_page(page_id, &page_builder);
         }
         None => {
             warn!(
@@ -193,7 +306,7 @@ fn load_pending_page(page_id: &str, pending: PendingPage, main_builder: &Builder
                 widget_id, pending.ui_resource
             );
             let label = Label::builder()
-                .label(format!("Page content not available"))
+                .label(gettext("Page content not available"))
                 .build();
             pending.container.append(&label);
         }
@@ -226,11 +339,20 @@ pub fn create_stack_and_tabs(tabs_container: &GtkBox, main_builder: &Builder) ->
                 page_builder.object::<gtk4::Widget>(&format!("page_{}", page_config.id))
             {
                 container.append(&page_widget);
+                let window: ApplicationWindow =
+                    crate::ui::utils::extract_widget(main_builder, "app_window");
                 if let Some(setup_fn) = page_config.setup_handler {
-                    let window: ApplicationWindow =
-                        crate::ui::utils::extract_widget(main_builder, "app_window");
                     setup_fn(&page_builder, main_builder, &window);
                 }
+                crate::ui::favorites::attach_page_menus(page_config.id, &page_builder, &window);
+                crate::ui::degraded::apply_to_page(
+                    page_config.id,
+                    page_config.requires,
+                    &page_builder,
+                    &container,
+                    &window,
+                );
+                crate::ui::relevance::apply_to_page(page_config.id, &page_builder);
             }
             info!("Loaded page {} (eagerly)", page_config.id);
         } else {
@@ -239,6 +361,7 @@ pub fn create_stack_and_tabs(tabs_container: &GtkBox, main_builder: &Builder) ->
                 page_config.id.to_string(),
                 PendingPage {
                     ui_resource: page_config.ui_resource,
+                    requires: page_config.requires,
                     setup_fn: page_config.setup_handler,
                     container: container.clone(),
                 },
@@ -246,7 +369,11 @@ pub fn create_stack_and_tabs(tabs_container: &GtkBox, main_builder: &Builder) ->
             info!("Registered page {} (lazy)", page_config.id);
         }
 
-        stack.add_titled(&container, Some(page_config.id), page_config.title);
+        stack.add_titled(
+            &container,
+            Some(page_config.id),
+            &gettext(page_config.title),
+        );
     }
 
     // Add the dynamic stack to the right container
@@ -259,9 +386,13 @@ pub fn create_stack_and_tabs(tabs_container: &GtkBox, main_builder: &Builder) ->
     // Set up navigation tabs
     let mut first_button: Option<Button> = None;
 
-    for page_config in PAGES {
+    for (index, page_config) in PAGES.iter().enumerate() {
         let tab = Tab::new(page_config.title, page_config.id, page_config.icon);
         tab.connect(&stack, tabs_container, &pending, main_builder);
+        if let Some(accel) = crate::ui::shortcuts::page_accel(index) {
+            tab.button
+                .set_tooltip_text(crate::ui::shortcuts::accel_label(&accel).as_deref());
+        }
 
         if first_button.is_none() {
             first_button = Some(tab.button.clone());
@@ -277,6 +408,38 @@ pub fn create_stack_and_tabs(tabs_container: &GtkBox, main_builder: &Builder) ->
     stack
 }
 
+/// Prefix of deep links that open a page, e.g. `xero-toolkit://page/drivers`.
+const PAGE_URI_PREFIX: &str = "xero-toolkit://page/";
+
+/// Whether `page_id` names a page in [`PAGES`].
+pub fn is_page(page_id: &str) -> bool {
+    PAGES.iter().any(|p| p.id == page_id)
+}
+
+/// Page id from a `xero-toolkit://page/<id>` deep link, if it names a page.
+pub fn page_from_uri(uri: &str) -> Option<&str> {
+    let page_id = uri.strip_prefix(PAGE_URI_PREFIX)?.trim_end_matches('/');
+    is_page(page_id).then_some(page_id)
+}
+
+/// Switch to a page as if its tab was clicked, loading it on first visit.
+///
+/// Returns `false` if no tab exists for `page_id`.
+pub fn open_page(tabs_container: &GtkBox, page_id: &str) -> bool {
+    let mut child = tabs_container.first_child();
+
+    while let Some(widget) = child {
+        if let Ok(button) = widget.clone().downcast::<Button>() {
+            if button.widget_name() == page_id {
+                button.emit_clicked();
+                return true;
+            }
+        }
+        child = widget.next_sibling();
+    }
+    false
+}
+
 /// Update which tab is marked as active.
 fn update_active_tab(tabs_container: &GtkBox, clicked_button: &Button) {
     let mut child = tabs_container.first_child();
@@ -292,3 +455,22 @@ fn update_active_tab(tabs_container: &GtkBox, clicked_button: &Button) {
         child = widget.next_sibling();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_page_uris() {
+        assert_eq!(
+            page_from_uri("xero-toolkit://page/containers_vms"),
+            Some("containers_vms")
+        );
+        assert_eq!(
+            page_from_uri("xero-toolkit://page/drivers/"),
+            Some("drivers")
+        );
+        assert_eq!(page_from_uri("xero-toolkit://page/nonexistent"), None);
+        assert_eq!(page_from_uri("https://xerolinux.xyz/page/drivers"), None);
+    }
+}
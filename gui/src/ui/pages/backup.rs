@@ -0,0 +1,915 @@
+//! Backup page handlers.
+//!
+//! Handles:
+//! - Installing restic or borgmatic and creating an encrypted repository on
+//!   a local disk, an SSH server or an rclone remote
+//! - Picking the folders that are backed up
+//! - A systemd user timer for scheduled backups
+//! - Listing snapshots and restoring one into a folder
+//! - Exporting the toolkit setup to a profile and replaying an imported one
+
+use crate::core::backup::{self, Config, Destination, Schedule, Snapshot, Tool};
+use crate::core::profile::{self, Profile, Step};
+use crate::i18n::{gettext, gettext_f, ngettext_f};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{spawn_blocking, try_extract_widget};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Saved setup and timer state, read off the main thread.
+#[derive(Default)]
+struct State {
+    config: Option<Config>,
+    timer_enabled: bool,
+}
+
+impl State {
+    fn read() -> Self {
+        Self {
+            config: Config::load(),
+            timer_enabled: backup::timer_enabled(),
+        }
+    }
+}
+
+struct BackupPage {
+    window: ApplicationWindow,
+    repo_row: adw::ActionRow,
+    setup_button: Button,
+    paths_group: adw::PreferencesGroup,
+    schedule_group: adw::PreferencesGroup,
+    schedule_row: adw::ComboRow,
+    snapshots_group: adw::PreferencesGroup,
+    /// Rows of [`Config::paths`], replaced on every render.
+    path_rows: RefCell<Vec<adw::ActionRow>>,
+    state: RefCell<State>,
+}
+
+/// Set up all handlers for the backup page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let groups = (
+        try_extract_widget::<adw::ActionRow>(page_builder, "backup_repo_row"),
+        try_extract_widget::<adw::PreferencesGroup>(page_builder, "backup_paths_group"),
+        try_extract_widget::<adw::PreferencesGroup>(page_builder, "backup_schedule_group"),
+        try_extract_widget::<adw::ComboRow>(page_builder, "backup_schedule_row"),
+        try_extract_widget::<adw::PreferencesGroup>(page_builder, "backup_snapshots_group"),
+    );
+    let buttons = (
+        try_extract_widget::<Button>(page_builder, "btn_backup_setup"),
+        try_extract_widget::<Button>(page_builder, "btn_backup_add"),
+        try_extract_widget::<Button>(page_builder, "btn_backup_schedule"),
+        try_extract_widget::<Button>(page_builder, "btn_backup_now"),
+        try_extract_widget::<Button>(page_builder, "btn_backup_browse"),
+        try_extract_widget::<Button>(page_builder, "btn_profile_export"),
+        try_extract_widget::<Button>(page_builder, "btn_profile_import"),
+    );
+    let (
+        (
+            Some(repo_row),
+            Some(paths_group),
+            Some(schedule_group),
+            Some(schedule_row),
+            Some(snapshots_group),
+        ),
+        (
+            Some(setup_button),
+            Some(add_button),
+            Some(schedule_button),
+            Some(now_button),
+            Some(browse_button),
+            Some(export_button),
+            Some(import_button),
+        ),
+    ) = (groups, buttons)
+    else {
+        return;
+    };
+
+    let page = Rc::new(BackupPage {
+        window: window.clone(),
+        repo_row,
+        setup_button,
+        paths_group,
+        schedule_group,
+        schedule_row,
+        snapshots_group,
+        path_rows: RefCell::new(Vec::new()),
+        state: RefCell::new(State::default()),
+    });
+
+    let page_clone = page.clone();
+    page.setup_button.connect_clicked(move |_| {
+        info!("Backup setup clicked");
+        show_tools(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    add_button.connect_clicked(move |_| {
+        info!("Add backup folders clicked");
+        add_folders(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    schedule_button.connect_clicked(move |_| {
+        info!("Apply backup schedule clicked");
+        apply_schedule(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    now_button.connect_clicked(move |_| {
+        info!("Back up now clicked");
+        back_up_now(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    browse_button.connect_clicked(move |_| {
+        info!("Browse snapshots clicked");
+        browse(&page_clone);
+    });
+
+    let window_clone = window.clone();
+    export_button.connect_clicked(move |_| {
+        info!("Export setup clicked");
+        export_profile(&window_clone);
+    });
+
+    let window_clone = window.clone();
+    import_button.connect_clicked(move |_| {
+        info!("Import setup clicked");
+        import_profile(&window_clone);
+    });
+
+    // The setup and timer change when a task finishes
+    let page_clone = page.clone();
+    install_state::connect_refresh(move || load(&page_clone));
+}
+
+/// Read the state off the main thread, then show it.
+fn load(page: &Rc<BackupPage>) {
+    let page = page.clone();
+    spawn_blocking(State::read, move |state| {
+        *page.state.borrow_mut() = state;
+        render(&page);
+    });
+}
+
+fn render(page: &Rc<BackupPage>) {
+    let state = page.state.borrow();
+    let config = state.config.as_ref();
+
+    match config {
+        Some(config) => page.repo_row.set_subtitle(&gettext_f(
+            "{tool} at {repository}",
+            &[
+                ("tool", config.tool.name()),
+                (
+                    "repository",
+                    &gtk4::glib::markup_escape_text(&config.repository),
+                ),
+            ],
+        )),
+        None => page.repo_row.set_subtitle(&gettext("Not set up")),
+    }
+    page.setup_button.set_label(&if config.is_some() {
+        gettext("Change")
+    } else {
+        gettext("Set Up")
+    });
+    page.paths_group.set_sensitive(config.is_some());
+    page.schedule_group.set_sensitive(config.is_some());
+    page.snapshots_group.set_sensitive(config.is_some());
+
+    let schedule = config.map(|c| c.schedule).unwrap_or_default();
+    if let Some(index) = Schedule::ALL.iter().position(|s| *s == schedule) {
+        page.schedule_row.set_selected(index as u32);
+    }
+    page.schedule_row
+        .set_subtitle(&match (schedule, state.timer_enabled) {
+            (Schedule::Off, _) => String::new(),
+            (_, true) => gettext("Timer enabled"),
+            (_, false) => gettext("Timer not enabled, press Apply"),
+        });
+
+    for row in page.path_rows.borrow_mut().drain(..) {
+        page.paths_group.remove(&row);
+    }
+    let paths = config.map(|c| c.paths.clone()).unwrap_or_default();
+    if paths.is_empty() {
+        let row = adw::ActionRow::builder()
+            .title(gettext("No folders yet"))
+            .build();
+        page.paths_group.add(&row);
+        page.path_rows.borrow_mut().push(row);
+    }
+    for path in paths {
+        // Paths come from the file system, not markup
+        let row = adw::ActionRow::builder()
+            .title(path.as_str())
+            .use_markup(false)
+            .build();
+        let remove_button = Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text(gettext("Remove"))
+            .valign(gtk4::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+        let page_clone = page.clone();
+        remove_button.connect_clicked(move |_| {
+            update_paths(&page_clone, |paths| paths.retain(|p| *p != path));
+        });
+        row.add_suffix(&remove_button);
+        page.paths_group.add(&row);
+        page.path_rows.borrow_mut().push(row);
+    }
+}
+
+// ─── Repository setup ───────────────────────────────────────────────────────
+
+/// Step one: pick restic or borgmatic.
+fn show_tools(page: &Rc<BackupPage>) {
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Backup Setup"),
+        &gettext(
+            "Pick the backup program. Both encrypt and deduplicate, so only \
+             changes take up space after the first backup.",
+        ),
+    )
+    .selection_type(SelectionType::Single)
+    .selection_required(true)
+    .confirm_label(&gettext("Next"));
+    for tool in Tool::ALL {
+        config = config.add_option(SelectionOption::new(
+            tool.id(),
+            tool.name(),
+            &gettext(tool.description()),
+            false,
+        ));
+    }
+
+    let page = page.clone();
+    let window = page.window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        if let Some(tool) = selected.first().and_then(|id| Tool::from_id(id)) {
+            show_destinations(&page, tool);
+        }
+    });
+}
+
+/// Step two: pick where the repository goes.
+fn show_destinations(page: &Rc<BackupPage>, tool: Tool) {
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Backup Destination"),
+        &gettext_f(
+            "Pick where {tool} keeps the backups.",
+            &[("tool", tool.name())],
+        ),
+    )
+    .selection_type(SelectionType::Single)
+    .selection_required(true)
+    .confirm_label(&gettext("Next"));
+    for destination in tool.destinations() {
+        config = config.add_option(SelectionOption::new(
+            destination.id(),
+            &gettext(destination.name()),
+            &gettext(destination.description()),
+            false,
+        ));
+    }
+
+    let page = page.clone();
+    let window = page.window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        if let Some(destination) = selected.first().and_then(|id| Destination::from_id(id)) {
+            show_repository_form(&page, tool, destination);
+        }
+    });
+}
+
+/// Step three: the location and passphrase.
+fn show_repository_form(page: &Rc<BackupPage>, tool: Tool, destination: Destination) {
+    let location_row = adw::EntryRow::builder().title(gettext("Location")).build();
+    let passphrase_row = adw::PasswordEntryRow::builder()
+        .title(gettext("Passphrase"))
+        .build();
+    let confirm_row = adw::PasswordEntryRow::builder()
+        .title(gettext("Confirm Passphrase"))
+        .build();
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    list.append(&location_row);
+    list.append(&passphrase_row);
+    list.append(&confirm_row);
+
+    if destination == Destination::Local {
+        let folder_button = Button::builder()
+            .icon_name("folder-open-symbolic")
+            .tooltip_text(gettext("Choose Folder"))
+            .valign(gtk4::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+        let window = page.window.clone();
+        let row = location_row.clone();
+        folder_button.connect_clicked(move |_| {
+            let dialog = gtk4::FileDialog::new();
+            dialog.set_title(&gettext("Select the Backup Folder"));
+            let window = window.clone();
+            let row = row.clone();
+            gtk4::glib::spawn_future_local(async move {
+                let Ok(folder) = dialog.select_folder_future(Some(&window)).await else {
+                    // User cancelled
+                    return;
+                };
+                if let Some(path) = folder.path() {
+                    row.set_text(&path.to_string_lossy());
+                }
+            });
+        });
+        location_row.add_suffix(&folder_button);
+    }
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Backup Repository"))
+        .body(gettext_f(
+            "{destination}, such as {example}. An existing {tool} repository there \
+             is used as it is, with its passphrase.",
+            &[
+                ("destination", &gettext(destination.description())),
+                ("example", destination.placeholder()),
+                ("tool", tool.name()),
+            ],
+        ))
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("cancel", gettext("Cancel").as_str()),
+        ("create", gettext("Set Up").as_str()),
+    ]);
+    dialog.set_response_appearance("create", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("create"));
+    dialog.set_close_response("cancel");
+
+    let page_clone = page.clone();
+    dialog.connect_response(Some("create"), move |_, _| {
+        let Some(repository) = destination.repository(tool, &location_row.text()) else {
+            show_error(
+                &page_clone.window,
+                &gettext_f(
+                    "Enter a location like {example}.",
+                    &[("example", destination.placeholder())],
+                ),
+            );
+            return;
+        };
+        let passphrase = passphrase_row.text().to_string();
+        if passphrase.is_empty() || passphrase != confirm_row.text() {
+            show_error(&page_clone.window, &gettext("The passphrases don't match."));
+            return;
+        }
+
+        let previous = page_clone.state.borrow().config.clone();
+        let config = Config {
+            tool,
+            repository,
+            paths: previous
+                .as_ref()
+                .map(|c| c.paths.clone())
+                .unwrap_or_else(backup::default_paths),
+            schedule: previous.map(|c| c.schedule).unwrap_or_default(),
+        };
+        set_up_repository(&page_clone, config, passphrase);
+    });
+
+    dialog.present(Some(&page.window));
+}
+
+/// Save the setup, then install the tool, store the passphrase and create
+/// the repository.
+fn set_up_repository(page: &Rc<BackupPage>, config: Config, passphrase: String) {
+    info!(
+        "Setting up {} backups at {}",
+        config.tool.name(),
+        config.repository
+    );
+
+    let window = page.window.clone();
+    spawn_blocking(
+        move || -> anyhow::Result<(Config, std::path::PathBuf)> {
+            config.save()?;
+            config.write_units()?;
+            let staged = backup::stage_passphrase(&passphrase)?;
+            Ok((config, staged))
+        },
+        move |result| {
+            let (config, staged) = match result {
+                Ok(result) => result,
+                Err(e) => {
+                    show_error(&window, &format!("{:#}", e));
+                    return;
+                }
+            };
+            let script = backup::script_path().to_string_lossy().to_string();
+            let staged = staged.to_string_lossy().to_string();
+
+            let mut install = vec!["-S", "--noconfirm", "--needed"];
+            install.extend(config.tool.packages());
+            let commands = CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("pacman")
+                        .args(&install)
+                        .description(&format!("Installing {}...", config.tool.name()))
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("sh")
+                        .args(&["-c", backup::STORE_PASSPHRASE_SCRIPT, "sh", &staged])
+                        .description("Storing the passphrase in the keyring...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("sh")
+                        .args(&[&script, "init"])
+                        .description("Creating the repository...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("systemctl")
+                        .args(&["--user", "daemon-reload"])
+                        .description("Reloading systemd...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("echo")
+                        .args(&["Pick the folders and a schedule, then run a first backup."])
+                        .description("Backups set up")
+                        .build(),
+                )
+                .build();
+            task_runner::run(window.upcast_ref(), commands, "Backup Setup");
+        },
+    );
+}
+
+// ─── Folders and schedule ───────────────────────────────────────────────────
+
+/// Change the backed up folders and save them right away.
+fn update_paths(page: &Rc<BackupPage>, change: impl FnOnce(&mut Vec<String>)) {
+    let Some(mut config) = page.state.borrow().config.clone() else {
+        return;
+    };
+    change(&mut config.paths);
+
+    let page = page.clone();
+    spawn_blocking(
+        move || config.save(),
+        move |result| match result {
+            Ok(()) => load(&page),
+            Err(e) => show_error(&page.window, &format!("{:#}", e)),
+        },
+    );
+}
+
+fn add_folders(page: &Rc<BackupPage>) {
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title(&gettext("Select Folders to Back Up"));
+
+    let page = page.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(folders) = dialog
+            .select_multiple_folders_future(Some(&page.window))
+            .await
+        else {
+            // User cancelled
+            return;
+        };
+        let added: Vec<String> = folders
+            .iter::<gtk4::gio::File>()
+            .filter_map(|folder| folder.ok()?.path())
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        update_paths(&page, |paths| {
+            for path in added {
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        });
+    });
+}
+
+fn apply_schedule(page: &Rc<BackupPage>) {
+    let state = page.state.borrow();
+    let Some(mut config) = state.config.clone() else {
+        return;
+    };
+    let Some(schedule) = Schedule::ALL
+        .get(page.schedule_row.selected() as usize)
+        .copied()
+    else {
+        return;
+    };
+    config.schedule = schedule;
+    let was_enabled = state.timer_enabled;
+
+    let window = page.window.clone();
+    spawn_blocking(
+        move || {
+            config.save()?;
+            config.write_units()
+        },
+        move |result| {
+            if let Err(e) = result {
+                show_error(&window, &format!("{:#}", e));
+                return;
+            }
+
+            let mut commands = CommandSequence::new();
+            if schedule == Schedule::Off {
+                if was_enabled {
+                    commands = commands.then(
+                        Command::builder()
+                            .normal()
+                            .program("systemctl")
+                            .args(&["--user", "disable", "--now", backup::TIMER_NAME])
+                            .description("Disabling the backup timer...")
+                            .build(),
+                    );
+                }
+                commands = commands.then(
+                    Command::builder()
+                        .normal()
+                        .program("rm")
+                        .args(&["-f", &backup::timer_path().to_string_lossy()])
+                        .description("Removing the backup timer...")
+                        .build(),
+                );
+            }
+            commands = commands.then(
+                Command::builder()
+                    .normal()
+                    .program("systemctl")
+                    .args(&["--user", "daemon-reload"])
+                    .description("Reloading systemd...")
+                    .build(),
+            );
+            if schedule != Schedule::Off {
+                commands = commands.then(
+                    Command::builder()
+                        .normal()
+                        .program("systemctl")
+                        .args(&["--user", "enable", "--now", backup::TIMER_NAME])
+                        .description("Enabling the backup timer...")
+                        .build(),
+                );
+            }
+            task_runner::run(window.upcast_ref(), commands.build(), "Backup Schedule");
+        },
+    );
+}
+
+// ─── Snapshots ──────────────────────────────────────────────────────────────
+
+fn back_up_now(page: &BackupPage) {
+    let script = backup::script_path().to_string_lossy().to_string();
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&[&script, "backup"])
+                .description("Backing up...")
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Backup");
+}
+
+/// List the snapshots, which may reach a remote, then show them.
+fn browse(page: &BackupPage) {
+    let Some(config) = page.state.borrow().config.clone() else {
+        return;
+    };
+
+    let window = page.window.clone();
+    spawn_blocking(
+        move || config.snapshots(),
+        move |result| match result {
+            Ok(snapshots) => show_snapshots(&window, snapshots),
+            Err(e) => show_error(
+                &window,
+                &gettext_f(
+                    "Couldn't list the snapshots: {error}",
+                    &[("error", &format!("{:#}", e))],
+                ),
+            ),
+        },
+    );
+}
+
+fn show_snapshots(window: &ApplicationWindow, snapshots: Vec<Snapshot>) {
+    let group = adw::PreferencesGroup::new();
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .min_content_height(360)
+        .child(&group)
+        .build();
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Snapshots"))
+        .body(gettext(
+            "Restoring a snapshot copies its files into a folder you pick, \
+             under their full paths, so nothing in place is overwritten.",
+        ))
+        .extra_child(&scrolled)
+        .build();
+    dialog.add_responses(&[("close", gettext("Close").as_str())]);
+    dialog.set_close_response("close");
+
+    if snapshots.is_empty() {
+        group.add(
+            &adw::ActionRow::builder()
+                .title(gettext("No snapshots yet"))
+                .build(),
+        );
+    }
+    for snapshot in snapshots {
+        let row = adw::ActionRow::builder()
+            .title(snapshot.time.as_str())
+            .subtitle(snapshot.id.as_str())
+            .use_markup(false)
+            .build();
+        let restore_button = Button::builder()
+            .label(gettext("Restore"))
+            .valign(gtk4::Align::Center)
+            .build();
+        let dialog_clone = dialog.clone();
+        let window_clone = window.clone();
+        restore_button.connect_clicked(move |_| {
+            dialog_clone.close();
+            pick_restore_target(&window_clone, snapshot.clone());
+        });
+        row.add_suffix(&restore_button);
+        group.add(&row);
+    }
+
+    dialog.present(Some(window));
+}
+
+fn pick_restore_target(window: &ApplicationWindow, snapshot: Snapshot) {
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title(&gettext("Restore Into"));
+
+    let window = window.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(folder) = dialog.select_folder_future(Some(&window)).await else {
+            // User cancelled
+            return;
+        };
+        let Some(target) = folder.path() else {
+            return;
+        };
+        let target = target.to_string_lossy().to_string();
+
+        let window_clone = window.clone();
+        show_warning_confirmation(
+            window.upcast_ref(),
+            &gettext("Restore Snapshot"),
+            &gettext_f(
+                "The files of the snapshot from <b>{time}</b> will be restored into \
+                 <span foreground=\"cyan\" weight=\"bold\">{target}</span>.",
+                &[
+                    ("time", &gtk4::glib::markup_escape_text(&snapshot.time)),
+                    ("target", &gtk4::glib::markup_escape_text(&target)),
+                ],
+            ),
+            move || {
+                let script = backup::script_path().to_string_lossy().to_string();
+                let commands = CommandSequence::new()
+                    .then(
+                        Command::builder()
+                            .normal()
+                            .program("sh")
+                            .args(&[&script, "restore", &snapshot.id, &target])
+                            .description(&format!("Restoring {}...", snapshot.id))
+                            .build(),
+                    )
+                    .build();
+                task_runner::run(window_clone.upcast_ref(), commands, "Backup Restore");
+            },
+        );
+    });
+}
+
+// ─── Toolkit setup ──────────────────────────────────────────────────────────
+
+/// Selection option for the step at `index` of a profile.
+fn step_option(step: &Step, index: usize, installed: bool) -> SelectionOption {
+    let (description, group) = match &step.action {
+        Some(_) => (
+            gettext("Toolkit action, set up with this version's steps"),
+            gettext("Actions"),
+        ),
+        None => (
+            gettext("Finished task, its commands run again as they were"),
+            gettext("Tasks"),
+        ),
+    };
+    SelectionOption::new(
+        &index.to_string(),
+        &gettext(&step.title),
+        &description,
+        installed,
+    )
+    .in_group(&group)
+}
+
+/// Keep the steps whose option ids are in `selected`.
+fn selected_steps(steps: &[Step], selected: &[String]) -> Vec<Step> {
+    steps
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| selected.contains(&index.to_string()))
+        .map(|(_, step)| step.clone())
+        .collect()
+}
+
+/// Pick what to save from the installed actions and the task history.
+fn export_profile(window: &ApplicationWindow) {
+    let window = window.clone();
+    spawn_blocking(Profile::current, move |profile| {
+        if profile.steps.is_empty() {
+            show_error(
+                &window,
+                &gettext(
+                    "There is nothing to export yet. Installed actions and tasks \
+                     that finished show up here.",
+                ),
+            );
+            return;
+        }
+
+        let mut config = SelectionDialogConfig::new(
+            &gettext("Export My Setup"),
+            &gettext(
+                "Pick what to set up again from the profile. Tasks that used \
+                 files prepared on this computer are left out.",
+            ),
+        )
+        .selection_type(SelectionType::Multi)
+        .selection_required(true)
+        .confirm_label(&gettext("Export"));
+        for (index, step) in profile.steps.iter().enumerate() {
+            config = config.add_option(step_option(step, index, false));
+        }
+
+        let window_clone = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let profile = Profile {
+                steps: selected_steps(&profile.steps, &selected),
+                ..profile.clone()
+            };
+            save_profile(&window_clone, profile);
+        });
+    });
+}
+
+fn save_profile(window: &ApplicationWindow, profile: Profile) {
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title(&gettext("Export My Setup"));
+    dialog.set_initial_name(Some(profile::FILE_NAME));
+
+    let window = window.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(file) = dialog.save_future(Some(&window)).await else {
+            // User cancelled
+            return;
+        };
+        let Some(path) = file.path() else {
+            return;
+        };
+        match profile.save(&path) {
+            Ok(()) => {
+                info!("Exported setup to {}", path.display());
+                crate::ui::toast::show(&gettext_f(
+                    "Setup saved to {path}",
+                    &[("path", &path.display().to_string())],
+                ));
+            }
+            Err(e) => show_error(&window, &format!("{:#}", e)),
+        }
+    });
+}
+
+/// Open a profile, pick its steps and run them as one sequence.
+fn import_profile(window: &ApplicationWindow) {
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title(&gettext("Import a Setup"));
+
+    let window = window.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(file) = dialog.open_future(Some(&window)).await else {
+            // User cancelled
+            return;
+        };
+        let Some(path) = file.path() else {
+            return;
+        };
+
+        // The install probes of the actions block
+        let window = window.clone();
+        spawn_blocking(
+            move || {
+                Profile::load(&path).map(|profile| {
+                    let installed: Vec<bool> = profile
+                        .steps
+                        .iter()
+                        .map(|step| {
+                            step.registered_action()
+                                .and_then(|action| action.is_installed())
+                                == Some(true)
+                        })
+                        .collect();
+                    (profile, installed)
+                })
+            },
+            move |result| match result {
+                Ok((profile, installed)) => show_import(&window, profile, &installed),
+                Err(e) => show_error(&window, &format!("{:#}", e)),
+            },
+        );
+    });
+}
+
+/// Step list of an imported profile. Actions already set up here are shown
+/// checked and skipped, and actions this version doesn't know are left out.
+fn show_import(window: &ApplicationWindow, profile: Profile, installed: &[bool]) {
+    let unknown = profile.steps.iter().filter(|step| !step.is_known()).count();
+    let mut description = if profile.hostname.is_empty() {
+        gettext("Steps saved from another computer. The chosen ones run one after another.")
+    } else {
+        gettext_f(
+            "Steps saved from {hostname}. The chosen ones run one after another.",
+            &[("hostname", &profile.hostname)],
+        )
+    };
+    if unknown > 0 {
+        description.push(' ');
+        description.push_str(&ngettext_f(
+            "{count} action from a different toolkit version is left out.",
+            "{count} actions from a different toolkit version are left out.",
+            unknown as u32,
+            &[("count", &unknown.to_string())],
+        ));
+    }
+
+    let mut config = SelectionDialogConfig::new(&gettext("Import a Setup"), &description)
+        .selection_type(SelectionType::Multi)
+        .selection_required(true)
+        .confirm_label(&gettext("Set Up"));
+    let mut available = 0;
+    for (index, step) in profile.steps.iter().enumerate() {
+        if step.is_known() {
+            config = config.add_option(step_option(step, index, installed[index]));
+            available += 1;
+        }
+    }
+    if available == 0 {
+        show_error(
+            window,
+            &gettext("The profile has no steps this version can run."),
+        );
+        return;
+    }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let commands = selected_steps(&profile.steps, &selected)
+            .iter()
+            .filter_map(Step::commands)
+            .fold(CommandSequence::new(), CommandSequence::append)
+            .build();
+        if !commands.is_empty() {
+            task_runner::run(window_clone.upcast_ref(), commands, profile::RESTORE_TITLE);
+        }
+    });
+}
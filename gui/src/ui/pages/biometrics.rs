@@ -4,9 +4,11 @@
 //! - Fingerprint reader setup (xfprintd-gui - jailbroken edition from source)
 //! - Howdy facial recognition setup (xero-howdy-qt - build from source)
 
-use crate::core;
+use crate::actions;
+use crate::i18n::gettext;
+use crate::ui::install_state::{self, Probe};
 use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
+use crate::ui::utils::extract_action_widgets;
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, Builder};
 use log::{error, info};
@@ -25,39 +27,30 @@ fn update_button_state(
     is_installed: bool,
 ) {
     if is_installed {
-        setup_button.set_label("Launch App");
+        setup_button.set_label(&gettext("Launch App"));
         setup_button.add_css_class("suggested-action");
         uninstall_button.set_visible(true);
     } else {
-        setup_button.set_label("Install");
+        setup_button.set_label(&gettext("Install"));
         setup_button.remove_css_class("suggested-action");
         uninstall_button.set_visible(false);
     }
 }
 
-/// Check if howdy is installed (either howdy-bin or howdy-git)
-fn is_howdy_installed() -> bool {
-    core::is_package_installed("howdy-bin") || core::is_package_installed("howdy-git")
-}
-
 fn setup_fingerprint(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_fingerprint_setup =
-        extract_widget::<gtk4::Button>(page_builder, "btn_fingerprint_setup");
-    let btn_fingerprint_uninstall =
-        extract_widget::<gtk4::Button>(page_builder, "btn_fingerprint_uninstall");
-
-    // Initial check - check if binary exists instead of package
-    let is_installed = std::path::Path::new("/usr/bin/xfprintd-gui").exists();
-    update_button_state(&btn_fingerprint_setup, &btn_fingerprint_uninstall, is_installed);
-
-    // Update on window focus (e.g. after installation completes)
+    let ids = ["btn_fingerprint_setup", "btn_fingerprint_uninstall"];
+    let Some([btn_fingerprint_setup, btn_fingerprint_uninstall]) =
+        extract_action_widgets::<gtk4::Button, 2>(page_builder, ids)
+    else {
+        return;
+    };
+
+    // Check if binary exists instead of package; refreshed after installation
     let btn_setup_clone = btn_fingerprint_setup.clone();
     let btn_uninstall_clone = btn_fingerprint_uninstall.clone();
-    window.connect_is_active_notify(move |window| {
-        if window.is_active() {
-            let is_installed = std::path::Path::new("/usr/bin/xfprintd-gui").exists();
-            update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
-        }
+    let probe = Probe::Path(actions::biometrics::FINGERPRINT_BINARY);
+    install_state::bind_widget(&btn_fingerprint_setup, probe, move |is_installed| {
+        update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
     });
 
     // Setup/Launch button handler
@@ -66,7 +59,7 @@ fn setup_fingerprint(page_builder: &Builder, window: &ApplicationWindow) {
         info!("Biometrics: Fingerprint setup button clicked");
 
         // Check again at click time - check if binary exists instead of package
-        if std::path::Path::new("/usr/bin/xfprintd-gui").exists() {
+        if std::path::Path::new(actions::biometrics::FINGERPRINT_BINARY).exists() {
             info!("Launching xfprintd-gui...");
             if let Err(e) = StdCommand::new("xfprintd-gui")
                 .stdin(Stdio::null())
@@ -77,52 +70,7 @@ fn setup_fingerprint(page_builder: &Builder, window: &ApplicationWindow) {
                 error!("Failed to launch xfprintd-gui: {}", e);
             }
         } else {
-            // Build and install xfprintd-gui from jailbroken fork
-            let commands = CommandSequence::new()
-                .then(
-                    Command::builder()
-                        .aur()
-                        .args(&["-S", "--noconfirm", "--needed", "rust", "cargo", "gtk4", "libadwaita", "glib2", "pkgconf", "polkit", "fprintd", "base-devel"])
-                        .description("Installing build dependencies...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .normal()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "rm -rf /tmp/xfprintd-jailbreak && git clone https://github.com/MurderFromMars/xfprintd-gui.git /tmp/xfprintd-jailbreak",
-                        ])
-                        .description("Cloning XFPrintD GUI Jailbroken Edition...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .normal()
-                        .program("bash")
-                        .args(&[
-                            "-c",
-                            "cd /tmp/xfprintd-jailbreak && bash /tmp/xfprintd-jailbreak/install.sh",
-                        ])
-                        .description("Building and installing Fingerprint GUI (Jailbroken Edition)...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .normal()
-                        .program("rm")
-                        .args(&["-rf", "/tmp/xfprintd-jailbreak"])
-                        .description("Cleaning up build directory...")
-                        .build(),
-                )
-                .build();
-
-            task_runner::run(
-                window_clone.upcast_ref(),
-                commands,
-                "Install XFPrintD GUI (Jailbroken Edition)",
-            );
+            super::run_action(&window_clone, &actions::biometrics::FINGERPRINT);
         }
     });
 
@@ -184,21 +132,19 @@ fn setup_fingerprint(page_builder: &Builder, window: &ApplicationWindow) {
 }
 
 fn setup_howdy(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_howdy_setup = extract_widget::<gtk4::Button>(page_builder, "btn_howdy_setup");
-    let btn_howdy_uninstall = extract_widget::<gtk4::Button>(page_builder, "btn_howdy_uninstall");
-
-    // Initial check - check if binary exists instead of package
-    let is_installed = std::path::Path::new("/usr/bin/xero-howdy-qt").exists();
-    update_button_state(&btn_howdy_setup, &btn_howdy_uninstall, is_installed);
-
-    // Update on window focus (e.g. after installation completes)
+    let ids = ["btn_howdy_setup", "btn_howdy_uninstall"];
+    let Some([btn_howdy_setup, btn_howdy_uninstall]) =
+        extract_action_widgets::<gtk4::Button, 2>(page_builder, ids)
+    else {
+        return;
+    };
+
+    // Check if binary exists instead of package; refreshed after installation
     let btn_setup_clone = btn_howdy_setup.clone();
     let btn_uninstall_clone = btn_howdy_uninstall.clone();
-    window.connect_is_active_notify(move |window| {
-        if window.is_active() {
-            let is_installed = std::path::Path::new("/usr/bin/xero-howdy-qt").exists();
-            update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
-        }
+    let probe = Probe::Path(actions::biometrics::HOWDY_BINARY);
+    install_state::bind_widget(&btn_howdy_setup, probe, move |is_installed| {
+        update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
     });
 
     // Setup/Launch button handler
@@ -207,7 +153,7 @@ fn setup_howdy(page_builder: &Builder, window: &ApplicationWindow) {
         info!("Biometrics: Howdy setup button clicked");
 
         // Check again at click time - check if binary exists instead of package
-        if std::path::Path::new("/usr/bin/xero-howdy-qt").exists() {
+        if std::path::Path::new(actions::biometrics::HOWDY_BINARY).exists() {
             info!("Launching xero-howdy-qt...");
             if let Err(e) = StdCommand::new("xero-howdy-qt")
                 .stdin(Stdio::null())
@@ -218,77 +164,7 @@ fn setup_howdy(page_builder: &Builder, window: &ApplicationWindow) {
                 error!("Failed to launch xero-howdy-qt: {}", e);
             }
         } else {
-            // Build and install Howdy Qt from source
-            let mut commands = CommandSequence::new();
-
-            // First, install build dependencies from AUR helper
-            commands = commands.then(
-                Command::builder()
-                    .aur()
-                    .args(&["-S", "--noconfirm", "--needed", "rust", "cargo", "clang", "qt6-base", "qt6-declarative"])
-                    .description("Installing build dependencies...")
-                    .build(),
-            );
-
-            // Then install Howdy if not already installed
-            if !is_howdy_installed() {
-                info!("Installing howdy-git from AUR");
-                commands = commands.then(
-                    Command::builder()
-                        .aur()
-                        .args(&["-S", "--noconfirm", "--needed", "howdy-git"])
-                        .description("Installing Howdy from AUR...")
-                        .build(),
-                );
-            } else {
-                info!("Howdy already installed, skipping Howdy installation");
-            }
-
-            commands = commands
-                .then(
-                    Command::builder()
-                        .normal()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "rm -rf /tmp/xero-howdy-qt && git clone https://github.com/XeroLinuxDev/xero-howdy-qt.git /tmp/xero-howdy-qt",
-                        ])
-                        .description("Cloning Howdy Qt repository...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .normal()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "cd /tmp/xero-howdy-qt && cargo build --release",
-                        ])
-                        .description("Building Howdy Qt (this may take a few minutes)...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .privileged()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "install -Dm755 /tmp/xero-howdy-qt/target/release/xero-howdy-qt /usr/bin/xero-howdy-qt",
-                        ])
-                        .description("Installing Howdy Qt to system...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .normal()
-                        .program("rm")
-                        .args(&["-rf", "/tmp/xero-howdy-qt"])
-                        .description("Cleaning up build directory...")
-                        .build(),
-                )
-                .build();
-
-            task_runner::run(window_clone.upcast_ref(), commands, "Install Howdy Qt (Build from Source)");
+            super::run_action(&window_clone, &actions::biometrics::HOWDY);
         }
     });
 
@@ -5,20 +5,39 @@
 //! with any Arch-based distribution.
 //!
 //! Handles install + uninstall for:
-//! - Docker
-//! - Podman (with optional Podman Desktop flatpak)
+//! - Docker (plus Compose stack templates)
+//! - Podman (with optional Podman Desktop flatpak, rootless setup and quadlets)
+//! - Incus (storage pool matched to the filesystem)
 //! - VirtualBox (kernel-aware host modules / dkms)
 //! - DistroBox (with BoxBuddy flatpak)
 //! - KVM / QEMU / virt-manager (with conflict resolution & nested virt)
+//! - GPU passthrough (VFIO) readiness check and setup
+//! - Windows 11 VM quick create (TPM, Secure Boot, virtio drivers)
+//! - Quickemu (quickget guests, listed and launched from the page)
+//! - VM guest utilities (agents for the hypervisor this system runs in)
 //! - iOS iPA Sideloader (Plume Impactor flatpak)
 
+use crate::actions;
+use crate::config::user::AppSource;
 use crate::core;
+use crate::core::apps;
+use crate::core::cmdline;
+use crate::core::compose::{self, Stack};
+use crate::core::podman;
+use crate::core::quickemu::{self, Guest};
+use crate::core::vfio::{self, Readiness};
+use crate::core::virt;
+use crate::core::vm::{self, Win11Vm};
+use crate::i18n::{gettext, gettext_f, n_};
+use crate::ui::dialogs::error::show_error;
 use crate::ui::dialogs::selection::{
     show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
 };
-use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
-use gtk4::prelude::*;
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state::{self, Probe};
+use crate::ui::task_runner::{self, Command, CommandSequence, Restart};
+use crate::ui::utils::{extract_action_widgets, spawn_blocking, try_extract_widget};
+use adw::prelude::*;
 use gtk4::{ApplicationWindow, Builder, Button};
 use log::info;
 
@@ -60,76 +79,51 @@ fn removable_packages(candidates: &[&str]) -> Vec<String> {
         .collect()
 }
 
-/// Check all install states off the main thread, then update all button pairs
-/// at once. Called on initial page load and on window refocus — never blocks
-/// the GTK main loop.
-fn async_refresh_states(
-    docker: (Button, Button),
-    podman: (Button, Button),
-    vbox: (Button, Button),
-    distrobox: (Button, Button),
-    kvm: (Button, Button),
-    ipa: (Button, Button),
-) {
-    let (tx, rx) = async_channel::bounded::<(bool, bool, bool, bool, bool, bool)>(1);
-
-    std::thread::spawn(move || {
-        let _ = tx.send_blocking((
-            core::is_package_installed("docker"),
-            core::is_package_installed("podman"),
-            core::is_package_installed("virtualbox"),
-            core::is_package_installed("distrobox"),
-            core::is_package_installed("virt-manager"),
-            core::is_flatpak_installed("dev.khcrysalis.PlumeImpactor"),
-        ));
-    });
-
-    gtk4::glib::MainContext::default().spawn_local(async move {
-        if let Ok((d, p, v, db, k, ipa_ok)) = rx.recv().await {
-            update_button_state(&docker.0, &docker.1, d, "Docker");
-            update_button_state(&podman.0, &podman.1, p, "Podman");
-            update_button_state(&vbox.0, &vbox.1, v, "Virtual Box");
-            update_button_state(&distrobox.0, &distrobox.1, db, "DistroBox");
-            update_button_state(&kvm.0, &kvm.1, k, "Qemu Virtual Manager");
-            update_button_state(&ipa.0, &ipa.1, ipa_ok, "iOS iPA Sideloader");
-        }
-    });
-}
-
 // ─── Page entry point ───────────────────────────────────────────────────────
 
 /// Set up all button handlers for the containers/VMs page.
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    // First, so picked buttons can be dropped before their handlers run
+    crate::ui::batch::setup(page_builder, window);
     let docker_btns = setup_docker(page_builder, window);
+    setup_compose_stacks(page_builder, window);
     let podman_btns = setup_podman(page_builder, window);
+    let incus_btns = setup_incus(page_builder, window);
     let vbox_btns = setup_vbox(page_builder, window);
     let distrobox_btns = setup_distrobox(page_builder, window);
     let kvm_btns = setup_kvm(page_builder, window);
+    setup_gpu_passthrough(page_builder, window);
+    setup_win11_vm(page_builder, window);
+    let quickemu_btns = setup_quickemu(page_builder, window);
+    setup_vm_guest_tools(page_builder, window);
     let ipa_btns = setup_ipa_sideloader(page_builder, window);
 
-    // Single async pass to set initial button states — no main-thread blocking.
-    async_refresh_states(
-        docker_btns.clone(),
-        podman_btns.clone(),
-        vbox_btns.clone(),
-        distrobox_btns.clone(),
-        kvm_btns.clone(),
-        ipa_btns.clone(),
+    // Button states follow the shared install state, refreshed after tasks.
+    let bind = |probe: Probe, buttons: Option<(Button, Button)>, label: &'static str| {
+        let Some((install, uninstall)) = buttons else {
+            return;
+        };
+        let install_clone = install.clone();
+        install_state::bind_widget(&install, probe, move |installed| {
+            update_button_state(&install_clone, &uninstall, installed, label)
+        });
+    };
+    bind(Probe::Package("docker"), docker_btns, "Docker");
+    bind(Probe::Package("podman"), podman_btns, "Podman");
+    bind(Probe::Package("incus"), incus_btns, "Incus");
+    bind(Probe::Package("virtualbox"), vbox_btns, "Virtual Box");
+    bind(Probe::Package("distrobox"), distrobox_btns, "DistroBox");
+    bind(
+        Probe::Package("virt-manager"),
+        kvm_btns,
+        "Qemu Virtual Manager",
+    );
+    bind(Probe::Package("quickemu"), quickemu_btns, "Quickemu");
+    bind(
+        Probe::Flatpak(actions::containers::PLUME_FLATPAK),
+        ipa_btns,
+        "iOS iPA Sideloader",
     );
-
-    // Refresh states whenever the user returns focus to the window.
-    window.connect_is_active_notify(move |w| {
-        if w.is_active() {
-            async_refresh_states(
-                docker_btns.clone(),
-                podman_btns.clone(),
-                vbox_btns.clone(),
-                distrobox_btns.clone(),
-                kvm_btns.clone(),
-                ipa_btns.clone(),
-            );
-        }
-    });
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -139,55 +133,16 @@ pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &
 /// Core packages for a working Docker setup.
 const DOCKER_PACKAGES: &[&str] = &["docker", "docker-compose", "docker-buildx"];
 
-fn setup_docker(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
-    let btn_install = extract_widget::<Button>(builder, "btn_docker");
-    let btn_uninstall = extract_widget::<Button>(builder, "btn_docker_uninstall");
+fn setup_docker(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_docker", "btn_docker_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
 
     // ── Install ──────────────────────────────────────────────────────────
     let window_clone = window.clone();
     btn_install.connect_clicked(move |_| {
         info!("Docker install button clicked");
 
-        let user = crate::config::env::get().user.clone();
-
-        let commands = CommandSequence::new()
-            .then(
-                Command::builder()
-                    .aur()
-                    .args(&[
-                        "-S", "--noconfirm", "--needed",
-                        "docker", "docker-compose", "docker-buildx",
-                    ])
-                    .description("Installing Docker engine and tools...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("systemctl")
-                    .args(&["enable", "--now", "docker.service"])
-                    .description("Enabling Docker service...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("groupadd")
-                    .args(&["-f", "docker"])
-                    .description("Ensuring docker group exists...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("usermod")
-                    .args(&["-aG", "docker", &user])
-                    .description("Adding your user to docker group...")
-                    .build(),
-            )
-            .build();
-
-        task_runner::run(window_clone.upcast_ref(), commands, "Docker Setup");
+        super::run_action(&window_clone, &actions::containers::DOCKER);
     });
 
     // ── Uninstall ────────────────────────────────────────────────────────
@@ -240,7 +195,142 @@ fn setup_docker(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
         task_runner::run(window_clone.upcast_ref(), commands.build(), "Docker Uninstall");
     });
 
-    (btn_install, btn_uninstall)
+    Some((btn_install, btn_uninstall))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Docker Compose stacks
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_compose_stacks(builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn_deploy) = try_extract_widget::<Button>(builder, "btn_compose_stack") else {
+        return;
+    };
+
+    let window_clone = window.clone();
+    btn_deploy.connect_clicked(move |_| {
+        info!("Deploy stack button clicked");
+
+        if !core::is_package_installed("docker") {
+            show_error(
+                &window_clone,
+                &gettext(
+                    "Compose stacks run on Docker. Install Docker first, then deploy a stack.",
+                ),
+            );
+            return;
+        }
+
+        let mut config = SelectionDialogConfig::new(
+            &gettext("Deploy Stack"),
+            &gettext("Pick a stack to run with Docker Compose. Its files go in ~/containers."),
+        )
+        .selection_type(SelectionType::Single)
+        .selection_required(true)
+        .confirm_label(&gettext("Next"));
+        for stack in compose::STACKS {
+            config = config.add_option(SelectionOption::new(
+                stack.id,
+                stack.name,
+                &gettext(stack.description),
+                false,
+            ));
+        }
+
+        let window_for_closure = window_clone.clone();
+        show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
+            if let Some(stack) = selected.first().and_then(|id| compose::find(id)) {
+                show_stack_form(&window_for_closure, stack);
+            }
+        });
+    });
+}
+
+/// Let the user adjust the stack's ports and folders, then deploy it.
+pub fn show_stack_form(window: &ApplicationWindow, stack: &'static Stack) {
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    let entries: Vec<adw::EntryRow> = stack
+        .fields
+        .iter()
+        .map(|field| {
+            let row = adw::EntryRow::builder()
+                .title(gettext(field.label))
+                .text(stack.default_value(field))
+                .build();
+            list.append(&row);
+            row
+        })
+        .collect();
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(format!("{} {}", gettext("Deploy"), stack.name))
+        .body(format!(
+            "{} {}",
+            gettext("The compose file and settings are written to"),
+            stack.dir().display()
+        ))
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("cancel", gettext("Cancel").as_str()),
+        ("deploy", gettext("Deploy").as_str()),
+    ]);
+    dialog.set_response_appearance("deploy", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("deploy"));
+    dialog.set_close_response("cancel");
+
+    let window_clone = window.clone();
+    dialog.connect_response(Some("deploy"), move |_, _| {
+        let values: Vec<String> = entries.iter().map(|e| e.text().to_string()).collect();
+        match stack.write(&values) {
+            Ok(values) => task_runner::run(
+                window_clone.upcast_ref(),
+                stack_commands(stack, &values),
+                &gettext_f("Deploy {stack}", &[("stack", stack.name)]),
+            ),
+            Err(e) => show_error(&window_clone, &e.to_string()),
+        }
+    });
+
+    dialog.present(Some(window));
+}
+
+/// Pull and start a written stack, then show its container status. Runs
+/// privileged since the user may not have picked up the docker group yet.
+fn stack_commands(stack: &Stack, values: &[(&str, String)]) -> CommandSequence {
+    let compose_file = stack.dir().join("docker-compose.yml");
+    let compose_file = compose_file.to_string_lossy();
+    let compose = |args: &[&str], description: &str| {
+        let mut full = vec!["compose", "-f", compose_file.as_ref()];
+        full.extend(args);
+        Command::builder()
+            .privileged()
+            .program("docker")
+            .args(&full)
+            .description(description)
+            .build()
+    };
+
+    CommandSequence::new()
+        .then(compose(&["pull"], "Downloading container images..."))
+        .then(compose(&["up", "-d"], "Starting the stack..."))
+        .then(compose(&["ps"], "Checking container status..."))
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[&format!(
+                    "{} is available at {}",
+                    stack.name,
+                    stack.url(values)
+                )])
+                .description("Stack deployed")
+                .build(),
+        )
+        .build()
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -248,30 +338,71 @@ fn setup_docker(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
 // ═══════════════════════════════════════════════════════════════════════════════
 
 const PODMAN_PACKAGES: &[&str] = &["podman", "podman-docker"];
-const PODMAN_DESKTOP_FLATPAK: &str = "io.podman_desktop.PodmanDesktop";
 
-fn setup_podman(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
-    let btn_install = extract_widget::<Button>(builder, "btn_podman");
-    let btn_uninstall = extract_widget::<Button>(builder, "btn_podman_uninstall");
+fn setup_podman(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_podman", "btn_podman_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Rootless & quadlets (existing installs) ──────────────────────────
+    if let Some(btn_rootless) = try_extract_widget::<Button>(builder, "btn_podman_rootless") {
+        let btn_rootless_clone = btn_rootless.clone();
+        install_state::bind_widget(&btn_rootless, Probe::Package("podman"), move |installed| {
+            btn_rootless_clone.set_visible(installed)
+        });
+
+        let window_clone = window.clone();
+        btn_rootless.connect_clicked(move |button| {
+            info!("Podman rootless button clicked");
+
+            button.set_sensitive(false);
+            let button = button.clone();
+            let window = window_clone.clone();
+            let user = crate::config::env::get().user.clone();
+            let work = move || (podman::rootless_ready(&user), podman::user_socket_enabled());
+            spawn_blocking(work, move |(ready, socket_enabled)| {
+                button.set_sensitive(true);
+                show_podman_tools(&window, ready, socket_enabled);
+            });
+        });
+    }
 
     // ── Install ──────────────────────────────────────────────────────────
     let window_clone = window.clone();
     btn_install.connect_clicked(move |_| {
         info!("Podman install button clicked");
 
-        let config = SelectionDialogConfig::new(
-            "Podman Installation",
-            "Podman will be installed. Optionally include the Podman Desktop GUI.",
+        let desktop = apps::PODMAN_DESKTOP.installed();
+        let preferred = apps::PODMAN_DESKTOP.preferred();
+        let mut config = SelectionDialogConfig::new(
+            &gettext("Podman Installation"),
+            &gettext(
+                "Podman will be installed. Optionally include the Podman Desktop GUI, \
+                 as a native package or from Flathub.",
+            ),
         )
         .selection_type(SelectionType::Single)
         .selection_required(false)
-        .add_option(SelectionOption::new(
-            "podman_desktop",
-            "Podman Desktop",
-            "Graphical interface for managing containers",
-            core::is_flatpak_installed(PODMAN_DESKTOP_FLATPAK),
-        ))
-        .confirm_label("Install");
+        .confirm_label(&gettext("Install"));
+        for (id, source, description) in [
+            (
+                "podman_desktop_native",
+                AppSource::Native,
+                gettext("Graphical interface for managing containers, from the Arch repos"),
+            ),
+            (
+                "podman_desktop_flatpak",
+                AppSource::Flatpak,
+                gettext("Graphical interface for managing containers, from Flathub"),
+            ),
+        ] {
+            let label = format!("Podman Desktop ({})", gettext(source.name()));
+            let option = SelectionOption::new(id, &label, &description, desktop == Some(source));
+            config = config.add_option(if source == preferred {
+                option.recommended()
+            } else {
+                option
+            });
+        }
 
         let window_for_closure = window_clone.clone();
         show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
@@ -290,17 +421,13 @@ fn setup_podman(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
                         .args(&["enable", "--now", "podman.socket"])
                         .description("Enabling Podman socket...")
                         .build(),
-                );
+                )
+                .append(podman_rootless_commands(&crate::config::env::get().user));
 
-            if selected.iter().any(|s| s == "podman_desktop") {
-                commands = commands.then(
-                    Command::builder()
-                        .normal()
-                        .program("flatpak")
-                        .args(&["install", "-y", "flathub", PODMAN_DESKTOP_FLATPAK])
-                        .description("Installing Podman Desktop GUI...")
-                        .build(),
-                );
+            if selected.iter().any(|s| s == "podman_desktop_native") {
+                commands = commands.then(apps::PODMAN_DESKTOP.install(AppSource::Native));
+            } else if selected.iter().any(|s| s == "podman_desktop_flatpak") {
+                commands = commands.then(apps::PODMAN_DESKTOP.install(AppSource::Flatpak));
             }
 
             if !commands.is_empty() {
@@ -336,15 +463,8 @@ fn setup_podman(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
                     .build(),
             );
 
-        if core::is_flatpak_installed(PODMAN_DESKTOP_FLATPAK) {
-            commands = commands.then(
-                Command::builder()
-                    .normal()
-                    .program("flatpak")
-                    .args(&["uninstall", "-y", PODMAN_DESKTOP_FLATPAK])
-                    .description("Removing Podman Desktop GUI...")
-                    .build(),
-            );
+        if let Some(source) = apps::PODMAN_DESKTOP.installed() {
+            commands = commands.then(apps::PODMAN_DESKTOP.remove(source));
         }
 
         let pkgs = removable_packages(PODMAN_PACKAGES);
@@ -368,99 +488,324 @@ fn setup_podman(builder: &Builder, window: &ApplicationWindow) -> (Button, Butto
         );
     });
 
-    (btn_install, btn_uninstall)
+    Some((btn_install, btn_uninstall))
 }
 
-// ═══════════════════════════════════════════════════════════════════════════════
-//  VirtualBox
-// ═══════════════════════════════════════════════════════════════════════════════
+/// Subordinate IDs for `user` and a storage migration, so rootless
+/// containers can map more than one UID.
+fn podman_rootless_commands(user: &str) -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "grep -q \"^$1:\" /etc/subuid || usermod --add-subuids \"$2\" \"$1\"; \
+                     grep -q \"^$1:\" /etc/subgid || usermod --add-subgids \"$2\" \"$1\"",
+                    "sh",
+                    user,
+                    podman::SUBID_RANGE,
+                ])
+                .description("Adding subordinate IDs for rootless containers...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("podman")
+                .args(&["system", "migrate"])
+                .description("Migrating Podman storage to the new IDs...")
+                .build(),
+        )
+}
 
-/// All possible VirtualBox host modules variants — used during uninstall
-/// to clean up whichever one was installed.
-const VBOX_HOST_VARIANTS: &[&str] = &[
-    "virtualbox-host-modules-arch",
-    "virtualbox-host-modules-lts",
-    "virtualbox-host-dkms",
-];
+/// Rootless status, the user API socket toggle and the quadlet generator.
+fn show_podman_tools(window: &ApplicationWindow, ready: bool, socket_enabled: bool) {
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Rootless Podman"))
+        .body(gettext(
+            "Run containers as your user, and keep them running as systemd services.",
+        ))
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[("close", gettext("Close").as_str())]);
+    dialog.set_close_response("close");
 
-/// Detect which host modules packages are needed for VirtualBox based on
-/// the running kernel (`uname -r`):
-///
-/// | Kernel suffix | Packages                                             |
-/// |---------------|------------------------------------------------------|
-/// | `-arch`       | `virtualbox-host-modules-arch` (prebuilt)            |
-/// | `-lts`        | `virtualbox-host-modules-lts`  (prebuilt)            |
-/// | anything else | `virtualbox-host-dkms` + matching kernel headers     |
-///
-/// For dkms, the kernel headers package is derived from the version string
-/// (e.g. `6.12.8-zen1-1-zen` → `linux-zen-headers`). If the headers
-/// package can't be located the install proceeds without it and dkms will
-/// prompt the user if needed.
-fn detect_vbox_host_packages() -> Vec<String> {
-    let uname = std::process::Command::new("uname")
-        .arg("-r")
-        .output()
-        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
-        .unwrap_or_default();
-
-    if uname.contains("-arch") {
-        vec!["virtualbox-host-modules-arch".to_string()]
-    } else if uname.contains("-lts") {
-        vec!["virtualbox-host-modules-lts".to_string()]
+    let detail = if ready {
+        gettext("Subordinate UIDs and GIDs are set up")
     } else {
-        // Custom kernel (zen, cachyos, hardened, etc.) — needs dkms + headers.
-        let mut pkgs = vec!["virtualbox-host-dkms".to_string()];
-
-        if let Some(suffix) = uname.rsplit('-').next() {
-            if !suffix.is_empty() && suffix.chars().all(|c| c.is_alphanumeric()) {
-                let headers = format!("linux-{}-headers", suffix);
-                if core::is_package_in_repos(&headers)
-                    || core::is_package_installed(&format!("linux-{}", suffix))
-                {
-                    pkgs.push(headers);
+        gettext("Your user has no subordinate UID/GID ranges")
+    };
+    let rootless_row = check_row(&gettext("Rootless prerequisites"), &detail, ready);
+    if !ready {
+        let fix = Button::builder()
+            .label(gettext("Fix"))
+            .valign(gtk4::Align::Center)
+            .build();
+        let window_clone = window.clone();
+        let dialog_clone = dialog.clone();
+        fix.connect_clicked(move |_| {
+            dialog_clone.close();
+            let user = crate::config::env::get().user.clone();
+            task_runner::run(
+                window_clone.upcast_ref(),
+                podman_rootless_commands(&user).build(),
+                "Podman Rootless Setup",
+            );
+        });
+        rootless_row.add_suffix(&fix);
+    }
+    list.append(&rootless_row);
+
+    let socket_row = adw::SwitchRow::builder()
+        .title(gettext("User API socket"))
+        .subtitle(gettext("Docker-compatible API for tools running as you"))
+        .active(socket_enabled)
+        .build();
+    let window_clone = window.clone();
+    socket_row.connect_active_notify(move |row| {
+        let enabled = row.is_active();
+        row.set_sensitive(false);
+        let row = row.clone();
+        let window = window_clone.clone();
+        spawn_blocking(
+            move || podman::set_user_socket(enabled),
+            move |result| {
+                row.set_sensitive(true);
+                if let Err(e) = result {
+                    show_error(&window, &e.to_string());
                 }
-            }
-        }
+            },
+        );
+    });
+    list.append(&socket_row);
+
+    let quadlet_row = adw::ActionRow::builder()
+        .title(gettext("Quadlet generator"))
+        .subtitle(gettext("Turn a container into a systemd user service"))
+        .build();
+    let generate = Button::builder()
+        .label(gettext("Generate…"))
+        .valign(gtk4::Align::Center)
+        .build();
+    let window_clone = window.clone();
+    let dialog_clone = dialog.clone();
+    generate.connect_clicked(move |_| {
+        dialog_clone.close();
+        let window = window_clone.clone();
+        spawn_blocking(podman::containers, move |names| {
+            show_quadlet_picker(&window, names);
+        });
+    });
+    quadlet_row.add_suffix(&generate);
+    list.append(&quadlet_row);
+
+    dialog.present(Some(window));
+}
+
+/// Pick a container, read its settings and confirm replacing it.
+fn show_quadlet_picker(window: &ApplicationWindow, names: Vec<String>) {
+    if names.is_empty() {
+        show_error(
+            window,
+            &gettext(
+                "No Podman containers found. Create one with podman run, then generate its quadlet.",
+            ),
+        );
+        return;
+    }
 
-        pkgs
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Generate Quadlet"),
+        &gettext(
+            "The container's image, ports, volumes and environment are written to \
+             ~/.config/containers/systemd/ as a user service.",
+        ),
+    )
+    .selection_type(SelectionType::Single)
+    .selection_required(true)
+    .confirm_label(&gettext("Next"));
+    for name in &names {
+        let exists = podman::quadlet_path(name).exists();
+        let description = if exists {
+            gettext("Already has a quadlet")
+        } else {
+            gettext("Podman container")
+        };
+        config = config.add_option(SelectionOption::new(name, name, &description, exists));
     }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let Some(name) = selected.first().cloned() else {
+            return;
+        };
+        let window = window_clone.clone();
+        spawn_blocking(
+            move || podman::inspect(&name),
+            move |result| match result {
+                Ok(container) => confirm_quadlet(&window, container),
+                Err(e) => show_error(&window, &e.to_string()),
+            },
+        );
+    });
+}
+
+/// Systemd recreates the container from the quadlet, so the original has to
+/// go first.
+fn confirm_quadlet(window: &ApplicationWindow, container: podman::Container) {
+    let message = gettext_f(
+        "The <b>{name}</b> container is removed and recreated by systemd as \
+         <b>{service}</b>, starting at login.\n\n\
+         Volumes and bind mounts are kept. Changes made inside the container \
+         itself are lost.",
+        &[("name", &container.name), ("service", &container.service())],
+    );
+
+    let window_clone = window.clone();
+    show_warning_confirmation(
+        window.upcast_ref(),
+        &gettext("Generate Quadlet"),
+        &message,
+        move || {
+            let path = match container.write_quadlet() {
+                Ok(path) => path.display().to_string(),
+                Err(e) => {
+                    show_error(&window_clone, &e.to_string());
+                    return;
+                }
+            };
+            let service = container.service();
+            let commands = CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("podman")
+                        .args(&["rm", "--force", &container.name])
+                        .description("Removing the original container...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("systemctl")
+                        .args(&["--user", "daemon-reload"])
+                        .description("Generating the user service...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("systemctl")
+                        .args(&["--user", "start", &service])
+                        .description("Starting the container as a service...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("echo")
+                        .args(&[&format!("Quadlet written to {}", path)])
+                        .description("Quadlet created")
+                        .build(),
+                )
+                .build();
+            task_runner::run(window_clone.upcast_ref(), commands, "Podman Quadlet");
+        },
+    );
 }
 
-fn setup_vbox(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
-    let btn_install = extract_widget::<Button>(builder, "btn_vbox");
-    let btn_uninstall = extract_widget::<Button>(builder, "btn_vbox_uninstall");
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Incus
+// ═══════════════════════════════════════════════════════════════════════════════
+
+const INCUS_PACKAGES: &[&str] = &["incus"];
+
+fn setup_incus(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_incus", "btn_incus_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
 
     // ── Install ──────────────────────────────────────────────────────────
-    //
-    // Packages are listed explicitly instead of using `virtualbox-meta`
-    // (XeroLinux-specific) to avoid provider-conflict errors when
-    // --noconfirm auto-selects from multiple repos.
     let window_clone = window.clone();
     btn_install.connect_clicked(move |_| {
-        info!("VirtualBox install button clicked");
+        info!("Incus install button clicked");
 
-        let host_pkgs = detect_vbox_host_packages();
-        info!("Detected VBox host packages: {:?}", host_pkgs);
+        super::run_action(&window_clone, &actions::containers::INCUS);
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    //
+    // Instances and storage pools under /var/lib/incus are left in place,
+    // the same as Docker's images and volumes.
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Incus uninstall button clicked");
 
-        let mut install_args: Vec<&str> = vec![
-            "-S", "--noconfirm", "--needed",
-            "virtualbox",
-            "virtualbox-guest-iso",
-        ];
-        let host_refs: Vec<&str> = host_pkgs.iter().map(|s| s.as_str()).collect();
-        install_args.extend_from_slice(&host_refs);
+        let user = crate::config::env::get().user.clone();
+        let pkgs = removable_packages(INCUS_PACKAGES);
 
-        let commands = CommandSequence::new()
+        let mut commands = CommandSequence::new()
             .then(
                 Command::builder()
-                    .aur()
-                    .args(&install_args)
-                    .description("Installing VirtualBox...")
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["disable", "--now", "incus.service", "incus.socket"])
+                    .description("Stopping Incus services...")
                     .build(),
             )
-            .build();
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("gpasswd")
+                    .args(&["-d", &user, "incus-admin"])
+                    .description("Removing your user from incus-admin group...")
+                    .build(),
+            );
+
+        if !pkgs.is_empty() {
+            let mut args = vec!["-Rns".to_string(), "--noconfirm".to_string()];
+            args.extend(pkgs);
+            let refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&refs)
+                    .description("Removing Incus packages and dependencies...")
+                    .build(),
+            );
+        }
+
+        task_runner::run(
+            window_clone.upcast_ref(),
+            commands.build(),
+            "Incus Uninstall",
+        );
+    });
+
+    Some((btn_install, btn_uninstall))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  VirtualBox
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_vbox(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_vbox", "btn_vbox_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
 
-        task_runner::run(window_clone.upcast_ref(), commands, "VirtualBox Setup");
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("VirtualBox install button clicked");
+
+        super::run_action(&window_clone, &actions::containers::VIRTUALBOX);
     });
 
     // ── Uninstall ────────────────────────────────────────────────────────
@@ -472,7 +817,7 @@ fn setup_vbox(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
         info!("VirtualBox uninstall button clicked");
 
         let mut candidates: Vec<&str> = vec!["virtualbox", "virtualbox-guest-iso"];
-        candidates.extend_from_slice(VBOX_HOST_VARIANTS);
+        candidates.extend_from_slice(actions::containers::VBOX_HOST_VARIANTS);
 
         let pkgs = removable_packages(&candidates);
         if pkgs.is_empty() {
@@ -500,43 +845,23 @@ fn setup_vbox(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
         );
     });
 
-    (btn_install, btn_uninstall)
+    Some((btn_install, btn_uninstall))
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
 //  DistroBox
 // ═══════════════════════════════════════════════════════════════════════════════
 
-const BOXBUDDY_FLATPAK: &str = "io.github.dvlv.boxbuddyrs";
-
-fn setup_distrobox(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
-    let btn_install = extract_widget::<Button>(builder, "btn_distrobox");
-    let btn_uninstall = extract_widget::<Button>(builder, "btn_distrobox_uninstall");
+fn setup_distrobox(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_distrobox", "btn_distrobox_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
 
     // ── Install ──────────────────────────────────────────────────────────
     let window_clone = window.clone();
     btn_install.connect_clicked(move |_| {
         info!("DistroBox install button clicked");
 
-        let commands = CommandSequence::new()
-            .then(
-                Command::builder()
-                    .aur()
-                    .args(&["-S", "--noconfirm", "--needed", "distrobox"])
-                    .description("Installing DistroBox...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .normal()
-                    .program("flatpak")
-                    .args(&["install", "-y", BOXBUDDY_FLATPAK])
-                    .description("Installing BoxBuddy GUI...")
-                    .build(),
-            )
-            .build();
-
-        task_runner::run(window_clone.upcast_ref(), commands, "DistroBox Setup");
+        super::run_action(&window_clone, &actions::containers::DISTROBOX);
     });
 
     // ── Uninstall ────────────────────────────────────────────────────────
@@ -546,12 +871,12 @@ fn setup_distrobox(builder: &Builder, window: &ApplicationWindow) -> (Button, Bu
 
         let mut commands = CommandSequence::new();
 
-        if core::is_flatpak_installed(BOXBUDDY_FLATPAK) {
+        if core::is_flatpak_installed(actions::containers::BOXBUDDY_FLATPAK) {
             commands = commands.then(
                 Command::builder()
                     .normal()
                     .program("flatpak")
-                    .args(&["uninstall", "-y", BOXBUDDY_FLATPAK])
+                    .args(&["uninstall", "-y", actions::containers::BOXBUDDY_FLATPAK])
                     .description("Removing BoxBuddy GUI...")
                     .build(),
             );
@@ -575,152 +900,49 @@ fn setup_distrobox(builder: &Builder, window: &ApplicationWindow) -> (Button, Bu
         );
     });
 
-    (btn_install, btn_uninstall)
+    Some((btn_install, btn_uninstall))
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
 //  KVM / QEMU / virt-manager
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// Explicit package list replacing `virt-manager-meta` (XeroLinux-specific).
-///
-/// | Package        | Purpose                                        |
-/// |----------------|------------------------------------------------|
-/// | qemu-desktop   | QEMU emulator (desktop profile, audio+display) |
-/// | libvirt        | Virtualization API daemon                      |
-/// | virt-manager   | GTK GUI for managing VMs                       |
-/// | virt-viewer    | Remote VM display client (SPICE/VNC)           |
-/// | edk2-ovmf      | UEFI firmware for VMs                          |
-/// | dnsmasq        | NAT/DHCP networking for libvirt                |
-/// | iptables-nft   | Firewall backend for libvirt networking        |
-/// | openbsd-netcat | Network utility (replaces gnu-netcat)          |
-/// | swtpm          | Software TPM 2.0 (needed for Windows 11 VMs)  |
-const KVM_PACKAGES: &[&str] = &[
-    "qemu-desktop",
-    "libvirt",
-    "virt-manager",
-    "virt-viewer",
-    "edk2-ovmf",
-    "dnsmasq",
-    "iptables-nft",
-    "openbsd-netcat",
-    "swtpm",
-];
-
-/// Detect CPU vendor and return the correct modprobe option for nested
-/// virtualisation. Intel → `kvm-intel`, AMD → `kvm-amd`.
-fn detect_kvm_nested_conf() -> (&'static str, &'static str) {
-    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
-
-    if cpuinfo.contains("GenuineIntel") {
-        ("kvm-intel", "options kvm-intel nested=1")
-    } else {
-        // AMD or fallback — kvm-amd also covers most other x86 cases
-        ("kvm-amd", "options kvm-amd nested=1")
-    }
-}
+fn setup_kvm(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_kvm", "btn_kvm_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Fix defaults (existing installs) ─────────────────────────────────
+    if let Some(btn_defaults) = try_extract_widget::<Button>(builder, "btn_kvm_defaults") {
+        let btn_defaults_clone = btn_defaults.clone();
+        install_state::bind_widget(
+            &btn_defaults,
+            Probe::Package("virt-manager"),
+            move |installed| btn_defaults_clone.set_visible(installed),
+        );
+
+        let window_clone = window.clone();
+        btn_defaults.connect_clicked(move |_| {
+            info!("Fix libvirt defaults button clicked");
 
-fn setup_kvm(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
-    let btn_install = extract_widget::<Button>(builder, "btn_kvm");
-    let btn_uninstall = extract_widget::<Button>(builder, "btn_kvm_uninstall");
+            super::run_action(&window_clone, &actions::containers::LIBVIRT_DEFAULTS);
+        });
+    }
 
     // ── Install ──────────────────────────────────────────────────────────
     let window_clone = window.clone();
     btn_install.connect_clicked(move |_| {
         info!("KVM install button clicked");
 
-        let user = crate::config::env::get().user.clone();
-        let (kvm_module, kvm_option) = detect_kvm_nested_conf();
-        let conf_path = format!("/etc/modprobe.d/{}.conf", kvm_module);
-        let write_cmd = format!("echo '{}' > {}", kvm_option, conf_path);
+        super::run_action(&window_clone, &actions::containers::KVM);
+    });
 
-        let mut commands = CommandSequence::new();
-
-        // Resolve iptables / netcat conflicts safely.
-        // iptables (legacy) conflicts with iptables-nft; gnu-netcat conflicts
-        // with openbsd-netcat. Only act when the conflicting variant is present,
-        // exit 0 regardless so the sequence continues.
-        commands = commands.then(
-            Command::builder()
-                .privileged()
-                .program("sh")
-                .args(&[
-                    "-c",
-                    "pacman -Qi iptables &>/dev/null && \
-                     ! pacman -Qi iptables-nft &>/dev/null && \
-                     pacman -Rdd --noconfirm iptables || true; \
-                     pacman -Qi gnu-netcat &>/dev/null && \
-                     pacman -Rdd --noconfirm gnu-netcat || true",
-                ])
-                .description("Resolving package conflicts if needed...")
-                .build(),
-        );
-
-        // Install all packages explicitly (no meta-package).
-        commands = commands.then(
-            Command::builder()
-                .aur()
-                .args(&[
-                    "-S", "--noconfirm", "--needed",
-                    "qemu-desktop",
-                    "libvirt",
-                    "virt-manager",
-                    "virt-viewer",
-                    "edk2-ovmf",
-                    "dnsmasq",
-                    "iptables-nft",
-                    "openbsd-netcat",
-                    "swtpm",
-                ])
-                .description("Installing virtualization packages...")
-                .build(),
-        );
-
-        // Add user to libvirt group for unprivileged VM management.
-        commands = commands
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("usermod")
-                    .args(&["-aG", "libvirt", &user])
-                    .description("Adding your user to libvirt group...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("sh")
-                    .args(&["-c", &write_cmd])
-                    .description("Enabling nested virtualization...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("systemctl")
-                    .args(&["enable", "--now", "libvirtd.service"])
-                    .description("Enabling libvirtd service...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("systemctl")
-                    .args(&["restart", "libvirtd.service"])
-                    .description("Restarting libvirtd service...")
-                    .build(),
-            );
-
-        task_runner::run(window_clone.upcast_ref(), commands.build(), "KVM / QEMU Setup");
-    });
-
-    // ── Uninstall ────────────────────────────────────────────────────────
-    let window_clone = window.clone();
-    btn_uninstall.connect_clicked(move |_| {
-        info!("KVM uninstall button clicked");
+    // ── Uninstall ────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("KVM uninstall button clicked");
 
         let user = crate::config::env::get().user.clone();
-        let pkgs = removable_packages(KVM_PACKAGES);
+        let pkgs = removable_packages(actions::containers::KVM_PACKAGES);
 
         let mut commands = CommandSequence::new()
             .then(
@@ -780,36 +1002,821 @@ fn setup_kvm(builder: &Builder, window: &ApplicationWindow) -> (Button, Button)
         );
     });
 
-    (btn_install, btn_uninstall)
+    Some((btn_install, btn_uninstall))
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
-//  iOS iPA Sideloader (Plume Impactor)
+//  GPU passthrough (VFIO)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_gpu_passthrough(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_gpu_passthrough") else {
+        return;
+    };
+
+    let window_clone = window.clone();
+    button.connect_clicked(move |button| {
+        info!("GPU passthrough button clicked");
+
+        // lspci and the kernel log are read off the main thread
+        button.set_sensitive(false);
+        let button = button.clone();
+        let window = window_clone.clone();
+        spawn_blocking(vfio::check, move |readiness| {
+            button.set_sensitive(true);
+            show_passthrough_report(&window, readiness);
+        });
+    });
+}
+
+/// Row showing whether one requirement is met.
+fn check_row(title: &str, detail: &str, ok: bool) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(title)
+        .subtitle(detail)
+        .build();
+    let (icon, class) = if ok {
+        ("circle-check-symbolic", "success")
+    } else {
+        ("dialog-warning-symbolic", "warning")
+    };
+    let image = gtk4::Image::from_icon_name(icon);
+    image.add_css_class(class);
+    row.add_prefix(&image);
+    row
+}
+
+/// Step one: report what's in place, then offer to configure the rest.
+fn show_passthrough_report(window: &ApplicationWindow, readiness: Readiness) {
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+
+    list.append(&check_row(
+        &gettext("CPU Virtualization"),
+        &if readiness.virtualization {
+            gettext("VT-x / AMD-V available")
+        } else {
+            gettext("Enable VT-x or AMD-V (SVM) in the firmware settings")
+        },
+        readiness.virtualization,
+    ));
+
+    let params = vfio::iommu_params(readiness.cpu).join(" ");
+    list.append(&check_row(
+        &gettext("IOMMU Kernel Parameters"),
+        &if readiness.iommu_param {
+            params
+        } else {
+            format!("{} {}", gettext("Not set:"), params)
+        },
+        readiness.iommu_param,
+    ));
+
+    let iommu_detail = match (&readiness.iommu_log, readiness.iommu_active()) {
+        (Some(line), true) => line.clone(),
+        (None, true) => format!("{} {}", readiness.groups.len(), gettext("IOMMU groups")),
+        (_, false) => gettext("No IOMMU groups. Enable VT-d / AMD-Vi in the firmware settings"),
+    };
+    list.append(&check_row(
+        &gettext("IOMMU"),
+        &iommu_detail,
+        readiness.iommu_active(),
+    ));
+
+    if readiness.iommu_active() {
+        let mut gpus = readiness.gpu_groups().peekable();
+        if gpus.peek().is_none() {
+            list.append(&check_row(
+                &gettext("GPU"),
+                &gettext("No GPU found in the IOMMU groups"),
+                false,
+            ));
+        }
+        for group in gpus {
+            let Some(gpu) = group.gpu() else {
+                continue;
+            };
+            let others: Vec<&str> = group
+                .devices
+                .iter()
+                .filter(|d| d.address != gpu.address)
+                .map(|d| d.description.as_str())
+                .collect();
+            let detail = if group.is_isolated() {
+                format!(
+                    "{} {}, {}",
+                    gettext("IOMMU group"),
+                    group.id,
+                    gettext("isolated")
+                )
+            } else {
+                format!(
+                    "{} {} {} {}",
+                    gettext("IOMMU group"),
+                    group.id,
+                    gettext("is shared with"),
+                    others.join(", ")
+                )
+            };
+            let title = if gpu.description.is_empty() {
+                &gpu.ids
+            } else {
+                &gpu.description
+            };
+            list.append(&check_row(title, &detail, group.is_isolated()));
+        }
+    }
+
+    list.append(&check_row(
+        &gettext("User Groups"),
+        &if readiness.missing_user_groups.is_empty() {
+            vfio::USER_GROUPS.join(", ")
+        } else {
+            format!(
+                "{} {}",
+                gettext("Not a member of:"),
+                readiness.missing_user_groups.join(", ")
+            )
+        },
+        readiness.missing_user_groups.is_empty(),
+    ));
+
+    if readiness.single_gpu() {
+        list.append(&check_row(
+            &gettext("Libvirt Hooks"),
+            &if readiness.hooks_installed {
+                gettext("Installed")
+            } else {
+                gettext("Needed to free your only GPU while the VM runs")
+            },
+            readiness.hooks_installed,
+        ));
+    }
+
+    let body = match readiness.target().and_then(|g| g.gpu()) {
+        Some(gpu) if readiness.single_gpu() => format!(
+            "{} {}\n{}",
+            gettext("Passthrough GPU:"),
+            gpu.description,
+            gettext(
+                "This is your only GPU. The desktop stops while the VM runs \
+                 and comes back when it shuts down."
+            )
+        ),
+        Some(gpu) => format!("{} {}", gettext("Passthrough GPU:"), gpu.description),
+        None => gettext("No GPU can be passed through yet."),
+    };
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(if readiness.is_ready() {
+            gettext("Ready for GPU Passthrough")
+        } else {
+            gettext("Not Ready for GPU Passthrough")
+        })
+        .body(body)
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("close", gettext("Close").as_str()),
+        ("configure", gettext("Configure").as_str()),
+    ]);
+    dialog.set_response_appearance("configure", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some(if readiness.is_ready() {
+        "close"
+    } else {
+        "configure"
+    }));
+    dialog.set_close_response("close");
+
+    let window_clone = window.clone();
+    dialog.connect_response(Some("configure"), move |_, _| {
+        show_passthrough_steps(&window_clone, &readiness);
+    });
+
+    dialog.present(Some(window));
+}
+
+/// Step two: pick which missing pieces to set up.
+fn show_passthrough_steps(window: &ApplicationWindow, readiness: &Readiness) {
+    let bootloader = cmdline::detect();
+    let params = vfio::iommu_params(readiness.cpu);
+    let target = readiness.target().cloned();
+
+    let mut description = gettext("Select the steps to apply.");
+    if bootloader.is_none() {
+        description.push(' ');
+        description.push_str(&gettext(
+            "Your bootloader isn't supported, add the IOMMU kernel parameters by hand.",
+        ));
+    }
+
+    let mut config = SelectionDialogConfig::new(&gettext("GPU Passthrough Setup"), &description)
+        .selection_type(SelectionType::Multi)
+        .selection_required(true)
+        .confirm_label(&gettext("Apply"));
+
+    if let Some(bootloader) = bootloader {
+        config = config.add_option(SelectionOption::new(
+            "iommu",
+            &gettext("IOMMU Kernel Parameters"),
+            &gettext_f(
+                "Add {params} through {bootloader}",
+                &[
+                    ("params", &params.join(" ")),
+                    ("bootloader", bootloader.name()),
+                ],
+            ),
+            readiness.iommu_param,
+        ));
+    }
+    if let (Some(group), false) = (&target, readiness.single_gpu()) {
+        config = config.add_option(SelectionOption::new(
+            "vfio_ids",
+            &gettext("Reserve GPU at Boot"),
+            &gettext_f(
+                "Bind {gpu} to vfio-pci at boot and rebuild the initramfs",
+                &[(
+                    "gpu",
+                    group
+                        .gpu()
+                        .map(|g| g.description.as_str())
+                        .unwrap_or_default(),
+                )],
+            ),
+            std::path::Path::new("/etc/modprobe.d/vfio.conf").exists(),
+        ));
+    }
+    config = config.add_option(SelectionOption::new(
+        "groups",
+        &gettext("User Groups"),
+        &gettext("Add your user to the libvirt, kvm and input groups"),
+        readiness.missing_user_groups.is_empty(),
+    ));
+    if target.is_some() {
+        let option = SelectionOption::new(
+            "hooks",
+            &gettext("Libvirt Hooks"),
+            &gettext("Detach the GPU from the host while a passthrough VM runs"),
+            readiness.hooks_installed,
+        );
+        config = config.add_option(if readiness.single_gpu() {
+            option.recommended()
+        } else {
+            option
+        });
+    }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let selected = |id: &str| selected.iter().any(|s| s == id);
+        let mut commands = CommandSequence::new();
+
+        if let (true, Some(bootloader)) = (selected("iommu"), bootloader) {
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &bootloader.add_params_script(&params)])
+                    .description("Adding IOMMU kernel parameters...")
+                    .requires(Restart::Reboot)
+                    .build(),
+            );
+        }
+        if let (true, Some(group)) = (selected("vfio_ids"), &target) {
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        &format!(
+                            "set -e; cat > /etc/modprobe.d/vfio.conf << 'EOF'\n{conf}EOF\n\
+                             grep -q '^MODULES=(.*vfio_pci' /etc/mkinitcpio.conf || \
+                             sed -i 's/^MODULES=(/MODULES=({modules} /' /etc/mkinitcpio.conf\n\
+                             mkinitcpio -P",
+                            conf = group.modprobe_conf(),
+                            modules = vfio::INITRAMFS_MODULES.join(" ")
+                        ),
+                    ])
+                    .description("Reserving the GPU for vfio-pci...")
+                    .requires(Restart::Reboot)
+                    .build(),
+            );
+        }
+        if selected("groups") {
+            let user = crate::config::env::get().user.clone();
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        &format!(
+                            "for g in {}; do \
+                             getent group \"$g\" > /dev/null && usermod -aG \"$g\" \"$1\"; \
+                             done; true",
+                            vfio::USER_GROUPS.join(" ")
+                        ),
+                        "sh",
+                        &user,
+                    ])
+                    .description("Adding your user to the virtualization groups...")
+                    .requires(Restart::Relogin)
+                    .build(),
+            );
+        }
+        if let (true, Some(group)) = (selected("hooks"), &target) {
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        &format!(
+                            "set -e; mkdir -p /etc/libvirt/hooks\n\
+                             if [ -f {qemu} ] && ! grep -q 'Xero Toolkit' {qemu}; then cp {qemu} {qemu}.bak; fi\n\
+                             cat > {qemu} << 'EOF'\n{qemu_hook}EOF\n\
+                             cat > {startup} << 'EOF'\n{startup_hook}EOF\n\
+                             cat > {teardown} << 'EOF'\n{teardown_hook}EOF\n\
+                             chmod +x {qemu} {startup} {teardown}\n\
+                             systemctl try-restart libvirtd.service",
+                            qemu = vfio::QEMU_HOOK_PATH,
+                            startup = vfio::STARTUP_HOOK_PATH,
+                            teardown = vfio::TEARDOWN_HOOK_PATH,
+                            qemu_hook = vfio::QEMU_HOOK,
+                            startup_hook = group.startup_hook(),
+                            teardown_hook = group.teardown_hook(),
+                        ),
+                    ])
+                    .description("Installing libvirt passthrough hooks...")
+                    .build(),
+            );
+        }
+
+        let next = if selected("iommu") || selected("vfio_ids") {
+            "Reboot, then run GPU Passthrough again to check that everything is ready."
+        } else {
+            "Log out and back in, then run GPU Passthrough again to check that everything is ready."
+        };
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[next])
+                .description("GPU passthrough configured")
+                .build(),
+        );
+
+        task_runner::run(
+            window_clone.upcast_ref(),
+            commands.build(),
+            "GPU Passthrough Setup",
+        );
+    });
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Windows 11 VM
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_win11_vm(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_win11_vm") else {
+        return;
+    };
+
+    let window_clone = window.clone();
+    button.connect_clicked(move |_| {
+        info!("Windows 11 VM button clicked");
+
+        if !core::is_package_installed("virt-install") {
+            show_error(
+                &window_clone,
+                &gettext("Windows 11 VMs run on KVM. Set up Qemu Virtual Manager first."),
+            );
+            return;
+        }
+
+        let filter = gtk4::FileFilter::new();
+        filter.set_name(Some(&gettext("Disc images")));
+        filter.add_pattern("*.iso");
+        filter.add_pattern("*.ISO");
+        let filters = gtk4::gio::ListStore::new::<gtk4::FileFilter>();
+        filters.append(&filter);
+
+        let dialog = gtk4::FileDialog::new();
+        dialog.set_title(&gettext("Select the Windows 11 ISO"));
+        dialog.set_filters(Some(&filters));
+
+        let window = window_clone.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(file) = dialog.open_future(Some(&window)).await else {
+                // User cancelled
+                return;
+            };
+            let Some(iso) = file.path() else {
+                return;
+            };
+            let defaults = Win11Vm::with_defaults(iso, vm::host_memory_mib(), vm::host_cpus());
+            show_win11_form(&window, defaults);
+        });
+    });
+}
+
+/// Let the user adjust name and sizes, then create the VM.
+fn show_win11_form(window: &ApplicationWindow, defaults: Win11Vm) {
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+
+    let name_row = adw::EntryRow::builder()
+        .title(gettext("Name"))
+        .text(defaults.name.as_str())
+        .build();
+    list.append(&name_row);
+
+    let spin_row = |title: &str, min: f64, max: f64, step: f64, value: u32| {
+        let row = adw::SpinRow::with_range(min, max, step);
+        row.set_title(&gettext(title));
+        row.set_value(value as f64);
+        list.append(&row);
+        row
+    };
+    let max_memory = vm::host_memory_mib().max(defaults.memory_mib as u64) as f64;
+    let memory = defaults.memory_mib;
+    let memory_row = spin_row(n_("Memory (MiB)"), 4096.0, max_memory, 1024.0, memory);
+    let max_cpus = vm::host_cpus().max(defaults.vcpus as usize) as f64;
+    let cpus_row = spin_row(n_("CPU Cores"), 2.0, max_cpus, 1.0, defaults.vcpus);
+    let disk_row = spin_row(n_("Disk Size (GB)"), 64.0, 4096.0, 16.0, defaults.disk_gb);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("New Windows 11 VM"))
+        .body(format!(
+            "{} {}\n{}",
+            gettext("Installing from"),
+            defaults.iso.display(),
+            gettext("The VM gets a TPM, Secure Boot and the virtio drivers.")
+        ))
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("cancel", gettext("Cancel").as_str()),
+        ("create", gettext("Create").as_str()),
+    ]);
+    dialog.set_response_appearance("create", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("create"));
+    dialog.set_close_response("cancel");
+
+    let window_clone = window.clone();
+    dialog.connect_response(Some("create"), move |_, _| {
+        let vm = Win11Vm {
+            name: name_row.text().trim().to_string(),
+            memory_mib: memory_row.value() as u32,
+            vcpus: cpus_row.value() as u32,
+            disk_gb: disk_row.value() as u32,
+            ..defaults.clone()
+        };
+        if let Err(e) = vm.validate() {
+            show_error(&window_clone, &e.to_string());
+            return;
+        }
+        task_runner::run(
+            window_clone.upcast_ref(),
+            win11_commands(&vm),
+            "Windows 11 VM",
+        );
+    });
+
+    dialog.present(Some(window));
+}
+
+/// Fetch the drivers, put the ISOs where QEMU can read them, create the VM
+/// and open its console.
+fn win11_commands(vm: &Win11Vm) -> CommandSequence {
+    let virtio_iso = Win11Vm::virtio_iso().display().to_string();
+    let pool_iso = vm.pool_iso().display().to_string();
+    let mut commands = CommandSequence::new();
+
+    if !Win11Vm::virtio_iso().exists() {
+        commands = commands
+            .then(
+                Command::builder()
+                    .download(vm::VIRTIO_WIN_URL, "/tmp/virtio-win.iso")
+                    .description("Downloading virtio-win drivers...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        "install -Dm644 /tmp/virtio-win.iso \"$1\" && rm -f /tmp/virtio-win.iso",
+                        "sh",
+                        &virtio_iso,
+                    ])
+                    .description("Adding virtio-win drivers to the storage pool...")
+                    .build(),
+            );
+    }
+
+    if vm.pool_iso() != vm.iso {
+        // A reflink makes this instant on btrfs
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "cp --reflink=auto \"$1\" \"$2\" && chmod 644 \"$2\"",
+                    "sh",
+                    &vm.iso.to_string_lossy(),
+                    &pool_iso,
+                ])
+                .description("Copying the Windows ISO to the storage pool...")
+                .build(),
+        );
+    }
+
+    let args = vm.virt_install_args();
+    let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    let hint = "When setup finds no drives, choose Load driver and open \
+                viostor\\w11\\amd64 on the virtio-win CD.";
+    commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("virt-install")
+                .args(&args)
+                .description("Creating the virtual machine...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "setsid virt-viewer --connect qemu:///system --wait \"$1\" > /dev/null 2>&1 &",
+                    "sh",
+                    &vm.name,
+                ])
+                .description("Opening the VM console...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[hint])
+                .description("Windows setup started")
+                .build(),
+        )
+        .build()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Quickemu
 // ═══════════════════════════════════════════════════════════════════════════════
 
-const PLUME_FLATPAK: &str = "dev.khcrysalis.PlumeImpactor";
+fn setup_quickemu(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_quickemu", "btn_quickemu_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
 
-fn setup_ipa_sideloader(builder: &Builder, window: &ApplicationWindow) -> (Button, Button) {
-    let btn_install = extract_widget::<Button>(builder, "btn_ipa_sideloader");
-    let btn_uninstall = extract_widget::<Button>(builder, "btn_ipa_sideloader_uninstall");
+    // ── VMs (existing installs) ──────────────────────────────────────────
+    if let Some(btn_vms) = try_extract_widget::<Button>(builder, "btn_quickemu_vms") {
+        let btn_vms_clone = btn_vms.clone();
+        install_state::bind_widget(&btn_vms, Probe::Package("quickemu"), move |installed| {
+            btn_vms_clone.set_visible(installed)
+        });
+
+        let window_clone = window.clone();
+        btn_vms.connect_clicked(move |_| {
+            info!("Quickemu VMs button clicked");
+            show_quick_vms(&window_clone);
+        });
+    }
 
     // ── Install ──────────────────────────────────────────────────────────
     let window_clone = window.clone();
     btn_install.connect_clicked(move |_| {
-        info!("iOS iPA Sideloader install button clicked");
+        info!("Quickemu install button clicked");
+
+        super::run_action(&window_clone, &actions::containers::QUICKEMU);
+    });
 
+    // ── Uninstall ────────────────────────────────────────────────────────
+    //
+    // VMs in ~/VMs are left in place.
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Quickemu uninstall button clicked");
+
+        let pkgs = removable_packages(quickemu::PACKAGES);
+        if pkgs.is_empty() {
+            return;
+        }
+        let mut args = vec!["-Rns".to_string(), "--noconfirm".to_string()];
+        args.extend(pkgs);
+        let refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
         let commands = CommandSequence::new()
             .then(
                 Command::builder()
-                    .normal()
-                    .program("flatpak")
-                    .args(&["install", "-y", "flathub", PLUME_FLATPAK])
-                    .description("Installing Plume Impactor from Flathub...")
+                    .privileged()
+                    .program("pacman")
+                    .args(&refs)
+                    .description("Removing Quickemu packages and dependencies...")
                     .build(),
             )
             .build();
 
-        task_runner::run(window_clone.upcast_ref(), commands, "iOS iPA Sideloader Setup");
+        task_runner::run(window_clone.upcast_ref(), commands, "Quickemu Uninstall");
+    });
+
+    Some((btn_install, btn_uninstall))
+}
+
+/// List the VMs in ~/VMs with a launch button each, and offer to create one.
+fn show_quick_vms(window: &ApplicationWindow) {
+    let dir = quickemu::vms_dir();
+    let vms = quickemu::list_vms(&dir);
+
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Quick VMs"))
+        .body(format!("{} {}", gettext("VMs are kept in"), dir.display()))
+        .extra_child(&list)
+        .build();
+
+    if vms.is_empty() {
+        list.append(
+            &adw::ActionRow::builder()
+                .title(gettext("No VMs yet"))
+                .subtitle(gettext("Create one to download and set it up"))
+                .build(),
+        );
+    }
+    for vm in vms {
+        let row = adw::ActionRow::builder()
+            .title(vm.name.as_str())
+            .subtitle(vm.guest_os.as_str())
+            .build();
+        let launch = Button::builder()
+            .icon_name("media-playback-start-symbolic")
+            .tooltip_text(gettext("Launch"))
+            .valign(gtk4::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+
+        let window_clone = window.clone();
+        let dialog_clone = dialog.clone();
+        launch.connect_clicked(move |_| match vm.launch() {
+            Ok(()) => {
+                dialog_clone.close();
+            }
+            Err(e) => show_error(&window_clone, &e.to_string()),
+        });
+        row.add_suffix(&launch);
+        list.append(&row);
+    }
+
+    dialog.add_responses(&[
+        ("close", gettext("Close").as_str()),
+        ("create", gettext("Create VM").as_str()),
+    ]);
+    dialog.set_response_appearance("create", adw::ResponseAppearance::Suggested);
+    dialog.set_close_response("close");
+
+    let window_clone = window.clone();
+    dialog.connect_response(Some("create"), move |_, _| {
+        show_quick_create(&window_clone);
+    });
+
+    dialog.present(Some(window));
+}
+
+/// Pick a guest, then download it with quickget and start it.
+fn show_quick_create(window: &ApplicationWindow) {
+    let dir = quickemu::vms_dir();
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Create VM"),
+        &gettext("quickget downloads the OS and writes a ready-to-run VM to ~/VMs."),
+    )
+    .selection_type(SelectionType::Single)
+    .selection_required(true)
+    .confirm_label(&gettext("Create"));
+    for guest in quickemu::GUESTS {
+        let exists = dir.join(guest.conf_name()).exists();
+        config = config.add_option(SelectionOption::new(
+            guest.id,
+            guest.name,
+            &gettext(guest.description),
+            exists,
+        ));
+    }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        if let Some(guest) = selected.first().and_then(|id| quickemu::find(id)) {
+            task_runner::run(
+                window_clone.upcast_ref(),
+                quickget_commands(guest),
+                guest.name,
+            );
+        }
+    });
+}
+
+fn quickget_commands(guest: &Guest) -> CommandSequence {
+    let dir = quickemu::vms_dir().display().to_string();
+    let conf = guest.conf_name();
+    let mut quickget = vec![
+        "-c",
+        "mkdir -p \"$1\" && cd \"$1\" && shift && quickget \"$@\"",
+        "sh",
+        &dir,
+    ];
+    quickget.extend(guest.quickget);
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&quickget)
+                .description("Downloading the OS and creating the VM...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "cd \"$1\" && setsid quickemu --vm \"$2\" > /dev/null 2>&1 &",
+                    "sh",
+                    &dir,
+                    &conf,
+                ])
+                .description("Starting the VM...")
+                .build(),
+        )
+        .build()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  VM guest utilities
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_vm_guest_tools(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_vm_guest_tools") else {
+        return;
+    };
+
+    let window_clone = window.clone();
+    button.connect_clicked(move |button| {
+        info!("VM guest utilities button clicked");
+
+        button.set_sensitive(false);
+        let button = button.clone();
+        let window = window_clone.clone();
+        spawn_blocking(virt::detect, move |hypervisor| {
+            button.set_sensitive(true);
+            match hypervisor {
+                Some(hypervisor) => {
+                    info!("Running under {}", hypervisor.name());
+                    super::run_action(&window, &actions::containers::VM_GUEST_TOOLS);
+                }
+                None => show_error(
+                    &window,
+                    "This system isn't running in a VirtualBox, KVM, VMware or Hyper-V \
+                     virtual machine, so there are no guest utilities to install.",
+                ),
+            }
+        });
+    });
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  iOS iPA Sideloader (Plume Impactor)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_ipa_sideloader(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_ipa_sideloader", "btn_ipa_sideloader_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("iOS iPA Sideloader install button clicked");
+
+        super::run_action(&window_clone, &actions::containers::IPA_SIDELOADER);
     });
 
     // ── Uninstall ────────────────────────────────────────────────────────
@@ -822,7 +1829,7 @@ fn setup_ipa_sideloader(builder: &Builder, window: &ApplicationWindow) -> (Butto
                 Command::builder()
                     .normal()
                     .program("flatpak")
-                    .args(&["uninstall", "-y", PLUME_FLATPAK])
+                    .args(&["uninstall", "-y", actions::containers::PLUME_FLATPAK])
                     .description("Removing Plume Impactor...")
                     .build(),
             )
@@ -835,5 +1842,5 @@ fn setup_ipa_sideloader(builder: &Builder, window: &ApplicationWindow) -> (Butto
         );
     });
 
-    (btn_install, btn_uninstall)
+    Some((btn_install, btn_uninstall))
 }
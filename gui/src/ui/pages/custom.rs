@@ -0,0 +1,159 @@
+//! Custom actions page handlers.
+//!
+//! Lists the user-defined actions from `~/.config/xero-toolkit/actions.d/`
+//! and runs them through the task runner. Definitions are re-read when the
+//! Reload button is pressed.
+
+use crate::actions::custom::{self, CustomAction};
+use crate::actions::Requirement;
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::task_runner;
+use crate::ui::utils::try_extract_widget;
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder};
+use log::{info, warn};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Icon for actions that don't set one.
+const DEFAULT_ICON: &str = "terminal-symbolic";
+
+/// Set up all handlers for the custom actions page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let Some(group) =
+        try_extract_widget::<adw::PreferencesGroup>(page_builder, "group_custom_actions")
+    else {
+        return;
+    };
+    let rows: Rc<RefCell<Vec<adw::ActionRow>>> = Rc::new(RefCell::new(Vec::new()));
+
+    populate(&group, &rows, window);
+
+    let btn_reload = try_extract_widget::<gtk4::Button>(page_builder, "btn_custom_reload");
+    if let Some(btn_reload) = btn_reload {
+        let window_clone = window.clone();
+        btn_reload.connect_clicked(move |_| {
+            info!("Custom: Reload button clicked");
+            populate(&group, &rows, &window_clone);
+        });
+    }
+
+    let Some(btn_open_folder) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_custom_open_folder")
+    else {
+        return;
+    };
+    let window_clone = window.clone();
+    btn_open_folder.connect_clicked(move |_| {
+        info!("Custom: Open Folder button clicked");
+        let dir = custom::actions_dir();
+        if let Err(e) = std::fs::create_dir_all(&dir) {
+            show_error(
+                &window_clone,
+                &gettext_f(
+                    "Failed to create {dir}: {error}",
+                    &[
+                        ("dir", &dir.display().to_string()),
+                        ("error", &e.to_string()),
+                    ],
+                ),
+            );
+            return;
+        }
+        let _ = std::process::Command::new("xdg-open").arg(&dir).spawn();
+    });
+}
+
+/// Replace the group's rows with the current definitions.
+fn populate(
+    group: &adw::PreferencesGroup,
+    rows: &Rc<RefCell<Vec<adw::ActionRow>>>,
+    window: &ApplicationWindow,
+) {
+    for row in rows.borrow_mut().drain(..) {
+        group.remove(&row);
+    }
+
+    let loaded = custom::load();
+    info!("Loaded {} custom action file(s)", loaded.len());
+
+    let mut new_rows = Vec::new();
+    if loaded.is_empty() {
+        new_rows.push(empty_row());
+    }
+    for result in loaded {
+        match result {
+            Ok(action) => new_rows.push(action_row(Rc::new(action), window)),
+            Err(e) => {
+                warn!("{:#}", e);
+                new_rows.push(error_row(&e));
+            }
+        }
+    }
+
+    for row in &new_rows {
+        group.add(row);
+    }
+    *rows.borrow_mut() = new_rows;
+}
+
+fn action_row(action: Rc<CustomAction>, window: &ApplicationWindow) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(action.title.as_str())
+        .subtitle(action.description.as_str())
+        .use_markup(false)
+        .build();
+    row.add_prefix(&gtk4::Image::from_icon_name(
+        action.icon.as_deref().unwrap_or(DEFAULT_ICON),
+    ));
+
+    let button = gtk4::Button::builder()
+        .label(gettext("Run"))
+        .valign(gtk4::Align::Center)
+        .build();
+    let window = window.clone();
+    button.connect_clicked(move |_| {
+        info!("Custom: Running action '{}'", action.id);
+        if action.needs_aur_helper() && !Requirement::AurHelper.is_met() {
+            super::offer_aur_helper_install(&window, &action.title);
+            return;
+        }
+        task_runner::run(window.upcast_ref(), action.commands(), &action.title);
+    });
+    row.add_suffix(&button);
+    row.set_activatable_widget(Some(&button));
+    row
+}
+
+fn error_row(error: &anyhow::Error) -> adw::ActionRow {
+    let reason = error
+        .chain()
+        .skip(1)
+        .map(|cause| cause.to_string())
+        .collect::<Vec<_>>()
+        .join(": ");
+    let row = adw::ActionRow::builder()
+        .title(error.to_string())
+        .subtitle(reason.trim())
+        .use_markup(false)
+        .build();
+    row.add_prefix(&gtk4::Image::from_icon_name(
+        "triangle-exclamation-symbolic",
+    ));
+    row.add_css_class("error");
+    row
+}
+
+fn empty_row() -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(gettext("No custom actions"))
+        .subtitle(gettext_f(
+            "Add .toml files to {dir} and press Reload",
+            &[("dir", &custom::actions_dir().display().to_string())],
+        ))
+        .use_markup(false)
+        .build();
+    row.add_css_class("dim-label");
+    row
+}
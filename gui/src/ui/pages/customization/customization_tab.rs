@@ -0,0 +1,1524 @@
+//! Customization tab button handlers.
+//!
+//! Handles:
+//! - CyberXero Theme installation
+//! - Shell Setup wizard (framework, prompt, plugins) with revert
+//! - Save Desktop tool
+//! - Icon and cursor themes with live apply
+//! - GRUB theme picker with revert
+//! - Plymouth Manager
+//! - Update Layan Theme
+//! - Decky Loader management (install/update/uninstall/wipe)
+//! - Rice switcher with saved Plasma profiles
+//! - Config/Rice reset
+//! - GNOME Tweaks, Extension Manager, adw-gtk3 and Dash to Dock on GNOME
+//!
+//! Plasma-only actions are hidden on other desktops.
+
+use crate::actions;
+use crate::core::desktop::Desktop;
+use crate::core::icon_themes::{self, IconTheme, Kind};
+use crate::core::reset::{self, Scope};
+use crate::core::shell::{self, Framework, Prompt, Setup};
+use crate::core::{self, decky, grub, rice, snapshot};
+use crate::i18n::{gettext, gettext_f, n_};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
+use crate::ui::dialogs::terminal;
+use crate::ui::pages::run_action;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{spawn_blocking, try_extract_widget};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::{info, warn};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Set up all button handlers for the customization tab.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    setup_cyberxero_theme(page_builder, window);
+    setup_shell_setup(page_builder, window);
+    setup_save_desktop(page_builder, window);
+    setup_icon_themes(page_builder, window);
+    setup_grub_theme(page_builder, window);
+    setup_plymouth_manager(page_builder, window);
+    setup_layan_patch(page_builder, window);
+    setup_decky_loader(page_builder, window);
+    setup_rice_switcher(page_builder, window);
+    setup_config_reset(page_builder, window);
+    setup_gnome_tweaks(page_builder, window);
+    setup_extension_manager(page_builder, window);
+    setup_adw_gtk3(page_builder, window);
+    setup_dash_to_dock(page_builder, window);
+    setup_desktop_visibility(page_builder);
+}
+
+/// Show only the actions that work on the running desktop. Plasma ones
+/// would silently do nothing anywhere else.
+fn setup_desktop_visibility(builder: &Builder) {
+    let desktop = Desktop::detect();
+    info!("Customization actions for desktop {:?}", desktop);
+
+    for id in ["cyberxero_row", "btn_layan_patch", "btn_rice_switcher"] {
+        if let Some(widget) = try_extract_widget::<gtk4::Widget>(builder, id) {
+            widget.set_visible(desktop == Desktop::Plasma);
+        }
+    }
+    if let Some(row) = try_extract_widget::<gtk4::Widget>(builder, "gnome_row") {
+        row.set_visible(desktop == Desktop::Gnome);
+    }
+}
+
+fn setup_cyberxero_theme(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_cyberxero_theme") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("CyberXero Theme button clicked");
+
+        let window_clone = window.clone();
+        crate::ui::dialogs::warning::show_warning_confirmation(
+            window.upcast_ref(),
+            &gettext("Apply CyberXero Theme"),
+            &gettext(
+                "This will install the <span foreground=\"cyan\" weight=\"bold\">CyberXero Dynamic Tiling Theme</span>.\n\n\
+                 • Existing Plasma configs will be <span foreground=\"cyan\" weight=\"bold\">backed up</span> automatically\n\
+                 • KWin effects will be compiled from source\n\
+                 • Plasmashell will be <span foreground=\"red\" weight=\"bold\">restarted</span> during installation\n\n\
+                 This process may take several minutes.",
+            ),
+            move || {
+                terminal::show_terminal_dialog(
+                    window_clone.upcast_ref(),
+                    &gettext("CyberXero Theme Installation"),
+                    "/usr/local/bin/cyberxero-theme",
+                    &[],
+                );
+            },
+        );
+    });
+}
+
+fn setup_shell_setup(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_shell_setup") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Shell Setup button clicked");
+        show_shell_frameworks(&window);
+    });
+}
+
+/// Step one: pick a framework, or revert an earlier setup.
+fn show_shell_frameworks(window: &ApplicationWindow) {
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Shell Setup"),
+        &gettext(
+            "Pick how your shell is set up. Your .zshrc and login shell are backed \
+             up the first time, so they can be restored from here.",
+        ),
+    )
+    .selection_type(SelectionType::Single)
+    .selection_required(true)
+    .confirm_label(&gettext("Next"));
+    for framework in Framework::ALL.into_iter().filter(Framework::is_available) {
+        config = config.add_option(SelectionOption::new(
+            framework.id(),
+            framework.name(),
+            &gettext(framework.description()),
+            false,
+        ));
+    }
+    if shell::has_plugins() {
+        config = config.add_option(SelectionOption::new(
+            "update",
+            &gettext("Update Plugins Only"),
+            &gettext("Pull the latest Oh My Zsh, zinit and fish plugins, keeping the setup"),
+            false,
+        ));
+    }
+    if shell::backup_dir().is_dir() {
+        config = config.add_option(SelectionOption::new(
+            "revert",
+            &gettext("Revert Shell Setup"),
+            &gettext("Restore the original .zshrc and login shell"),
+            false,
+        ));
+    }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let Some(id) = selected.first() else {
+            return;
+        };
+        if id == "revert" {
+            revert_shell_setup(&window_clone);
+        } else if id == "update" {
+            update_shell_plugins(&window_clone);
+        } else if let Some(framework) = Framework::from_id(id) {
+            show_shell_prompts(&window_clone, framework);
+        }
+    });
+}
+
+/// Step two: pick the prompt, skipped when the framework has only one.
+fn show_shell_prompts(window: &ApplicationWindow, framework: Framework) {
+    let prompts = framework.prompts();
+    if let [prompt] = prompts[..] {
+        show_shell_plugins(window, framework, prompt);
+        return;
+    }
+
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Shell Prompt"),
+        &gettext_f(
+            "Pick the prompt for {framework}.",
+            &[("framework", framework.name())],
+        ),
+    )
+    .selection_type(SelectionType::Single)
+    .selection_required(true)
+    .confirm_label(&gettext("Next"));
+    for prompt in prompts {
+        config = config.add_option(SelectionOption::new(
+            prompt.id(),
+            prompt.name(),
+            &gettext(prompt.description()),
+            false,
+        ));
+    }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        if let Some(prompt) = selected.first().and_then(|id| Prompt::from_id(id)) {
+            show_shell_plugins(&window_clone, framework, prompt);
+        }
+    });
+}
+
+/// Step three: pick the plugins, then run the setup.
+fn show_shell_plugins(window: &ApplicationWindow, framework: Framework, prompt: Prompt) {
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Shell Plugins"),
+        &gettext_f(
+            "Pick the plugins to load with {framework}.",
+            &[("framework", framework.name())],
+        ),
+    )
+    .selection_type(SelectionType::Multi)
+    .confirm_label(&gettext("Set Up"));
+    for plugin in framework.plugins() {
+        let option =
+            SelectionOption::new(plugin.id, plugin.name, &gettext(plugin.description), false);
+        config = config.add_option(if plugin.recommended {
+            option.recommended()
+        } else {
+            option
+        });
+    }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let plugins = framework
+            .plugins()
+            .iter()
+            .filter(|p| selected.iter().any(|id| id == p.id))
+            .collect();
+        run_shell_setup(
+            &window_clone,
+            &Setup {
+                framework,
+                prompt,
+                plugins,
+            },
+        );
+    });
+}
+
+fn run_shell_setup(window: &ApplicationWindow, setup: &Setup) {
+    info!(
+        "Setting up {} with {}",
+        setup.framework.name(),
+        setup.prompt.name()
+    );
+
+    let user = crate::config::env::get().user.clone();
+    let backup = shell::backup_dir().to_string_lossy().to_string();
+    let config_path = setup.framework.config_path().to_string_lossy().to_string();
+    let shell_path = setup.framework.shell();
+    let (repo, aur) = setup.packages();
+
+    let mut install = vec!["-S", "--noconfirm", "--needed"];
+    install.extend(&repo);
+    let mut commands = CommandSequence::new().then(
+        Command::builder()
+            .privileged()
+            .program("pacman")
+            .args(&install)
+            .description("Installing the shell, prompt and plugins...")
+            .build(),
+    );
+    if !aur.is_empty() {
+        let mut install = vec!["-S", "--noconfirm", "--needed"];
+        install.extend(&aur);
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&install)
+                .description(&format!("Installing {}...", setup.prompt.name()))
+                .build(),
+        );
+    }
+    commands = commands.then(
+        Command::builder()
+            .normal()
+            .program("sh")
+            .args(&["-c", shell::BACKUP_SCRIPT, "sh", &backup])
+            .description("Backing up the current shell setup...")
+            .build(),
+    );
+    if setup.framework == Framework::OhMyZsh {
+        let script = match shell::oh_my_zsh_script() {
+            Ok(script) => script,
+            Err(e) => {
+                show_error(window, &e.to_string());
+                return;
+            }
+        };
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", &script])
+                .description("Installing or updating Oh My Zsh framework...")
+                .build(),
+        );
+    }
+    commands = commands.then(
+        Command::builder()
+            .normal()
+            .program("sh")
+            .args(&[
+                "-c",
+                shell::WRITE_CONFIG_SCRIPT,
+                "sh",
+                &config_path,
+                &setup.config(),
+            ])
+            .description(&format!("Writing {}...", config_path))
+            .build(),
+    );
+    let fisher = setup.fisher_plugins();
+    if !fisher.is_empty() {
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("fish")
+                .args(&["-c", &format!("fisher install {}", fisher.join(" "))])
+                .description("Installing fish plugins...")
+                .build(),
+        );
+    }
+    commands = commands
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", shell::KONSOLE_PROFILE_SCRIPT, "sh", shell_path])
+                .description("Updating Konsole profile...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("chsh")
+                .args(&[&user, "-s", shell_path])
+                .description(&format!("Setting {} as default shell...", shell_path))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[if setup.prompt == Prompt::Powerlevel10k {
+                    "Open a new terminal and run p10k configure to style the prompt."
+                } else {
+                    "Open a new terminal to use the new shell setup."
+                }])
+                .description("Shell setup complete")
+                .build(),
+        );
+
+    task_runner::run(window.upcast_ref(), commands.build(), "Shell Setup");
+}
+
+/// Update the framework and plugins in place, leaving the config alone.
+fn update_shell_plugins(window: &ApplicationWindow) {
+    info!("Updating shell plugins");
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", shell::UPDATE_SCRIPT])
+                .description("Updating shell plugins...")
+                .build(),
+        )
+        .build();
+
+    task_runner::run(window.upcast_ref(), commands, "Shell Plugins Update");
+}
+
+/// Put the backed up `.zshrc` and login shell back. Installed packages stay.
+fn revert_shell_setup(window: &ApplicationWindow) {
+    let shell_path = shell::previous_shell().unwrap_or_else(|| "/bin/bash".to_string());
+    let backup = shell::backup_dir().to_string_lossy().to_string();
+    let user = crate::config::env::get().user.clone();
+
+    let window_clone = window.clone();
+    crate::ui::dialogs::warning::show_warning_confirmation(
+        window.upcast_ref(),
+        &gettext("Revert Shell Setup"),
+        &gettext_f(
+            "The original <span foreground=\"cyan\" weight=\"bold\">.zshrc</span> \
+             is restored and <b>{shell}</b> becomes the login shell again.\n\n\
+             Installed packages and plugins are kept.",
+            &[("shell", &shell_path)],
+        ),
+        move || {
+            let commands = CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("chsh")
+                        .args(&[&user, "-s", &shell_path])
+                        .description(&format!("Setting {} as default shell...", shell_path))
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("sh")
+                        .args(&["-c", shell::KONSOLE_PROFILE_SCRIPT, "sh", &shell_path])
+                        .description("Updating Konsole profile...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("sh")
+                        .args(&["-c", shell::REVERT_SCRIPT, "sh", &backup])
+                        .description("Restoring the original .zshrc...")
+                        .build(),
+                )
+                .build();
+
+            task_runner::run(window_clone.upcast_ref(), commands, "Shell Setup Revert");
+        },
+    );
+}
+
+fn setup_save_desktop(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_save_desktop") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Save Desktop Tool button clicked");
+
+        run_action(&window, &actions::customization::SAVE_DESKTOP);
+    });
+}
+
+fn setup_icon_themes(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_icon_themes") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Icons & Cursors button clicked");
+
+        let window = window.clone();
+        spawn_blocking(
+            || {
+                let installed: Vec<bool> = icon_themes::THEMES
+                    .iter()
+                    .map(|t| core::is_package_installed(t.package))
+                    .collect();
+                (installed, Kind::Icons.current(), Kind::Cursors.current())
+            },
+            move |(installed, icons, cursors)| {
+                show_icon_themes(&window, &installed, icons, cursors);
+            },
+        );
+    });
+}
+
+/// List the icon and cursor themes with a variant picker each. Installing
+/// a theme applies it right away.
+fn show_icon_themes(
+    window: &ApplicationWindow,
+    installed: &[bool],
+    icons: Option<String>,
+    cursors: Option<String>,
+) {
+    let icons_group = adw::PreferencesGroup::builder()
+        .title(gettext("Icon Themes"))
+        .build();
+    let cursors_group = adw::PreferencesGroup::builder()
+        .title(gettext("Cursor Themes"))
+        .build();
+    let content = gtk4::Box::builder()
+        .orientation(gtk4::Orientation::Vertical)
+        .spacing(18)
+        .build();
+    content.append(&icons_group);
+    content.append(&cursors_group);
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .min_content_height(360)
+        .child(&content)
+        .build();
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Icons & Cursors"))
+        .body(gettext(
+            "Themes are applied right away to Plasma and GTK apps. Some apps \
+             only pick up a new cursor once restarted.",
+        ))
+        .extra_child(&scrolled)
+        .build();
+
+    for (theme, &installed) in icon_themes::THEMES.iter().zip(installed) {
+        let (group, current) = match theme.kind {
+            Kind::Icons => (&icons_group, icons.as_deref()),
+            Kind::Cursors => (&cursors_group, cursors.as_deref()),
+        };
+        let in_use = current.filter(|name| theme.provides(name));
+
+        let row = adw::ActionRow::builder().title(theme.name).build();
+        row.set_subtitle(&match (in_use, installed) {
+            (Some(name), _) => gettext_f("In use: {theme}", &[("theme", name)]),
+            (None, true) => gettext("Installed"),
+            (None, false) if theme.aur => {
+                gettext_f("{package} from the AUR", &[("package", theme.package)])
+            }
+            (None, false) => theme.package.to_string(),
+        });
+
+        let labels: Vec<&str> = theme.variants.iter().map(|(_, label)| *label).collect();
+        let variant = gtk4::DropDown::from_strings(&labels);
+        variant.set_valign(gtk4::Align::Center);
+        variant.set_visible(theme.variants.len() > 1);
+        if let Some(index) =
+            in_use.and_then(|name| theme.variants.iter().position(|(v, _)| *v == name))
+        {
+            variant.set_selected(index as u32);
+        }
+        row.add_suffix(&variant);
+
+        let apply_button = Button::builder()
+            .label(if installed {
+                gettext("Apply")
+            } else {
+                gettext("Install")
+            })
+            .valign(gtk4::Align::Center)
+            .build();
+        let dialog_clone = dialog.clone();
+        let window_clone = window.clone();
+        apply_button.connect_clicked(move |_| {
+            dialog_clone.close();
+            let (name, _) = theme
+                .variants
+                .get(variant.selected() as usize)
+                .unwrap_or(&theme.variants[0]);
+            install_icon_theme(&window_clone, theme, name, installed);
+        });
+        row.add_suffix(&apply_button);
+
+        if installed && theme.removable {
+            let remove_button = Button::builder()
+                .icon_name("user-trash-symbolic")
+                .tooltip_text(gettext("Uninstall"))
+                .valign(gtk4::Align::Center)
+                .css_classes(vec!["flat".to_string()])
+                .build();
+            let dialog_clone = dialog.clone();
+            let window_clone = window.clone();
+            let in_use = in_use.is_some();
+            remove_button.connect_clicked(move |_| {
+                dialog_clone.close();
+                remove_icon_theme(&window_clone, theme, in_use);
+            });
+            row.add_suffix(&remove_button);
+        }
+
+        group.add(&row);
+    }
+
+    dialog.add_response("close", &gettext("Close"));
+    dialog.set_close_response("close");
+    dialog.present(Some(window));
+}
+
+fn apply_icon_theme_command(kind: Kind, name: &str) -> Command {
+    Command::builder()
+        .normal()
+        .program("sh")
+        .args(&["-c", kind.apply_script(), "sh", name])
+        .description(&format!("Applying {}...", name))
+        .build()
+}
+
+fn install_icon_theme(window: &ApplicationWindow, theme: &IconTheme, name: &str, installed: bool) {
+    info!("Applying {} theme {}", theme.name, name);
+
+    let mut commands = CommandSequence::new();
+    if !installed {
+        let builder = if theme.aur {
+            Command::builder().aur()
+        } else {
+            Command::builder().privileged().program("pacman")
+        };
+        commands = commands.then(
+            builder
+                .args(&["-S", "--noconfirm", "--needed", theme.package])
+                .description(&format!("Installing {}...", theme.name))
+                .build(),
+        );
+    }
+    commands = commands.then(apply_icon_theme_command(theme.kind, name));
+
+    task_runner::run(window.upcast_ref(), commands.build(), "Icons & Cursors");
+}
+
+/// Remove a theme package. A theme in use is swapped for the stock one
+/// first, so apps aren't left without icons or cursors.
+fn remove_icon_theme(window: &ApplicationWindow, theme: &'static IconTheme, in_use: bool) {
+    let window_clone = window.clone();
+    crate::ui::dialogs::warning::show_warning_confirmation(
+        window.upcast_ref(),
+        &gettext("Uninstall Theme"),
+        &if in_use {
+            gettext_f(
+                "<b>{package}</b> will be removed. It's in use, so the stock Breeze \
+                 theme is applied first.",
+                &[("package", theme.package)],
+            )
+        } else {
+            gettext_f(
+                "<b>{package}</b> will be removed.",
+                &[("package", theme.package)],
+            )
+        },
+        move || {
+            let mut commands = CommandSequence::new();
+            if in_use {
+                commands = commands.then(apply_icon_theme_command(
+                    theme.kind,
+                    theme.kind.default_theme(),
+                ));
+            }
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&["-Rns", "--noconfirm", theme.package])
+                    .description(&format!("Removing {}...", theme.name))
+                    .build(),
+            );
+            task_runner::run(
+                window_clone.upcast_ref(),
+                commands.build(),
+                "Icons & Cursors",
+            );
+        },
+    );
+}
+
+fn setup_grub_theme(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_grub_theme") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("GRUB Theme button clicked");
+
+        if !crate::core::microcode::uses_grub() {
+            show_error(
+                &window,
+                &gettext("GRUB isn't the bootloader on this system."),
+            );
+            return;
+        }
+
+        let repo = grub::themes_repo();
+        if repo.is_dir() {
+            load_grub_themes(&window);
+            return;
+        }
+
+        let window_inner = window.clone();
+        fetch_then(
+            &window,
+            GRUB_THEMES_ARCHIVE,
+            GRUB_THEMES_DEST,
+            None,
+            move || {
+                let window = window_inner.clone();
+                spawn_blocking(
+                    move || grub::extract_themes(Path::new(GRUB_THEMES_DEST), &repo),
+                    move |result| match result {
+                        Ok(()) => load_grub_themes(&window),
+                        Err(e) => show_error(&window, &format!("{:#}", e)),
+                    },
+                );
+            },
+        );
+    });
+}
+
+/// Read the themes and the current and previous selection off the main
+/// thread, then show the picker.
+fn load_grub_themes(window: &ApplicationWindow) {
+    let window = window.clone();
+    spawn_blocking(
+        || {
+            (
+                grub::list_themes(&grub::themes_repo()),
+                grub::current_theme(),
+                grub::previous_theme(),
+            )
+        },
+        move |(themes, current, previous)| {
+            if themes.is_empty() {
+                show_error(&window, &gettext("No GRUB themes found in ~/xero-grubs."));
+                return;
+            }
+            show_grub_themes(&window, themes, current, previous);
+        },
+    );
+}
+
+/// List the themes with their thumbnails. Picking one applies it; the
+/// revert response restores the theme from before the last change.
+fn show_grub_themes(
+    window: &ApplicationWindow,
+    themes: Vec<grub::Theme>,
+    current: Option<String>,
+    previous: Option<Option<String>>,
+) {
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .min_content_height(360)
+        .child(&list)
+        .build();
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("GRUB Theme"))
+        .body(gettext(
+            "Pick a theme for the boot menu. The previous one is remembered \
+             and can be restored from here.",
+        ))
+        .extra_child(&scrolled)
+        .build();
+
+    for theme in themes {
+        let is_current = current.as_deref() == Some(theme.name.as_str());
+        let row = adw::ActionRow::builder().title(theme.name.as_str()).build();
+        if is_current {
+            row.set_subtitle(&gettext("Current"));
+        }
+
+        let thumbnail = gtk4::Picture::builder()
+            .content_fit(gtk4::ContentFit::Cover)
+            .width_request(112)
+            .height_request(63)
+            .margin_top(6)
+            .margin_bottom(6)
+            .build();
+        if let Some(preview) = &theme.preview {
+            thumbnail.set_filename(Some(preview));
+        }
+        row.add_prefix(&thumbnail);
+
+        let apply_button = Button::builder()
+            .label(gettext("Apply"))
+            .valign(gtk4::Align::Center)
+            .sensitive(!is_current)
+            .build();
+        let dialog_clone = dialog.clone();
+        let window_clone = window.clone();
+        apply_button.connect_clicked(move |_| {
+            dialog_clone.close();
+            apply_grub_theme(&window_clone, &theme);
+        });
+        row.add_suffix(&apply_button);
+
+        list.append(&row);
+    }
+
+    dialog.add_response("close", &gettext("Close"));
+    if let Some(previous) = &previous {
+        let label = match previous {
+            Some(name) => gettext_f("Revert to {theme}", &[("theme", name)]),
+            None => gettext("Revert to No Theme"),
+        };
+        dialog.add_response("revert", &label);
+    }
+    dialog.set_close_response("close");
+
+    let window_clone = window.clone();
+    dialog.connect_response(Some("revert"), move |_, _| {
+        info!("Reverting GRUB theme");
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &grub::revert_script()])
+                    .description("Restoring the previous GRUB theme...")
+                    .build(),
+            )
+            .build();
+        task_runner::run(window_clone.upcast_ref(), commands, "GRUB Theme Revert");
+    });
+
+    dialog.present(Some(window));
+}
+
+fn apply_grub_theme(window: &ApplicationWindow, theme: &grub::Theme) {
+    info!("Applying GRUB theme {}", theme.name);
+
+    let contents = match std::fs::read_to_string(grub::DEFAULT_GRUB) {
+        Ok(contents) => contents,
+        Err(e) => {
+            show_error(
+                window,
+                &gettext_f(
+                    "Failed to read {file}: {error}",
+                    &[("file", grub::DEFAULT_GRUB), ("error", &e.to_string())],
+                ),
+            );
+            return;
+        }
+    };
+    let updated = grub::with_theme(&contents, theme);
+    let dir = theme.dir.to_string_lossy().to_string();
+
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    &grub::apply_script(),
+                    "sh",
+                    &dir,
+                    &theme.name,
+                    &updated,
+                ])
+                .description(&format!("Installing the {} GRUB theme...", theme.name))
+                .build(),
+        )
+        .build();
+    task_runner::run(window.upcast_ref(), commands, "GRUB Theme");
+}
+
+/// Snapshot of the GRUB themes repository, downloaded when no local copy exists.
+const GRUB_THEMES_ARCHIVE: &str =
+    "https://github.com/xerolinux/xero-grubs/archive/refs/heads/main.tar.gz";
+const GRUB_THEMES_DEST: &str = "/tmp/xero-grubs.tar.gz";
+
+/// Download `url` to `dest` with the native downloader on a worker thread,
+/// then run `then` on the main thread. Failures are shown to the user and
+/// `then` is not called.
+fn fetch_then<F>(window: &ApplicationWindow, url: &str, dest: &str, sha256: Option<&str>, then: F)
+where
+    F: FnOnce() + 'static,
+{
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+
+    let (tx, rx) = async_channel::bounded::<Result<(), String>>(1);
+    let url = url.to_string();
+    let dest = std::path::PathBuf::from(dest);
+    let sha256 = sha256.map(str::to_string);
+    std::thread::spawn(move || {
+        let result = crate::core::download::fetch_blocking(
+            &url,
+            &dest,
+            |_| {},
+            Arc::new(AtomicBool::new(false)),
+        )
+        .and_then(|()| match &sha256 {
+            Some(hash) => crate::core::verify::check_sha256(&dest, hash),
+            None => Ok(()),
+        })
+        .map_err(|e| format!("{:#}", e));
+        let _ = tx.send_blocking(result);
+    });
+
+    let window = window.clone();
+    gtk4::glib::MainContext::default().spawn_local(async move {
+        match rx.recv().await {
+            Ok(Ok(())) => then(),
+            Ok(Err(e)) => show_error(&window, &format!("{}\n{}", gettext("Download failed:"), e)),
+            Err(_) => {}
+        }
+    });
+}
+
+fn setup_plymouth_manager(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_plymouth_manager") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Plymouth Manager button clicked");
+
+        terminal::show_terminal_dialog(
+            window.upcast_ref(),
+            &gettext("Plymouth Manager"),
+            "/usr/local/bin/xpm",
+            &[],
+        );
+    });
+}
+
+fn setup_layan_patch(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_layan_patch") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Update Layan Theme button clicked");
+
+        run_action(&window, &actions::customization::LAYAN);
+    });
+}
+
+/// Look up the newest Decky Loader on `channel` and install it.
+fn install_decky(window: &ApplicationWindow, channel: decky::Channel, title: &'static str) {
+    let window = window.clone();
+    spawn_blocking(
+        move || decky::latest(channel),
+        move |release| match release {
+            Ok(release) => {
+                info!("Installing Decky Loader {}", release.tag);
+                task_runner::run(
+                    window.upcast_ref(),
+                    decky::install(&release, channel),
+                    title,
+                );
+            }
+            Err(e) => show_error(&window, &e.to_string()),
+        },
+    );
+}
+
+fn setup_decky_loader(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_decky_loader") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Decky Loader button clicked");
+
+        let window_clone = window.clone();
+        let is_installed = decky::is_installed();
+
+        let description = if is_installed {
+            match decky::installed_version() {
+                Some(version) => gettext_f(
+                    "Decky Loader {version} is currently installed, select an action",
+                    &[("version", &version)],
+                ),
+                None => gettext("Decky Loader is currently installed, select an action"),
+            }
+        } else {
+            gettext("Setup Decky loader, select a branch to install")
+        };
+
+        let mut config = SelectionDialogConfig::new("Decky Loader", &description)
+            .selection_type(SelectionType::Single)
+            .confirm_label(&gettext("Continue"));
+
+        if is_installed {
+            config = config
+                .add_option(SelectionOption::new(
+                    "update_release",
+                    &gettext("Update to Latest Release"),
+                    &gettext("Recommended for stable Steam client"),
+                    false,
+                ))
+                .add_option(SelectionOption::new(
+                    "update_prerelease",
+                    &gettext("Update to Latest Pre-Release"),
+                    &gettext("Recommended for beta Steam client"),
+                    false,
+                ))
+                .add_option(SelectionOption::new(
+                    "uninstall",
+                    &gettext("Uninstall Decky Loader"),
+                    &gettext("Remove Decky Loader but keep plugins and config intact"),
+                    false,
+                ))
+                .add_option(SelectionOption::new(
+                    "wipe",
+                    &gettext("Wipe Decky Loader"),
+                    &gettext("Completely remove Decky Loader including all plugins and config"),
+                    false,
+                ));
+        } else {
+            config = config
+                .add_option(SelectionOption::new(
+                    "install_release",
+                    &gettext("Install Latest Release"),
+                    &gettext("Recommended for stable SteamOS"),
+                    false,
+                ))
+                .add_option(SelectionOption::new(
+                    "install_prerelease",
+                    &gettext("Install Latest Pre-Release"),
+                    &gettext("Recommended for beta/preview SteamOS"),
+                    false,
+                ));
+        }
+
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let Some(action) = selected.first() else {
+                return;
+            };
+
+            match action.as_str() {
+                "install_release" | "update_release" => {
+                    install_decky(
+                        &window_clone,
+                        decky::Channel::Release,
+                        n_("Decky Loader — Install Release"),
+                    );
+                }
+                "install_prerelease" | "update_prerelease" => {
+                    install_decky(
+                        &window_clone,
+                        decky::Channel::Prerelease,
+                        n_("Decky Loader — Install Pre-Release"),
+                    );
+                }
+                "uninstall" => {
+                    let window_inner = window_clone.clone();
+                    crate::ui::dialogs::warning::show_warning_confirmation(
+                        window_clone.upcast_ref(),
+                        &gettext("Uninstall Decky Loader"),
+                        &gettext(
+                            "This will <span foreground=\"red\" weight=\"bold\">remove</span> Decky Loader services.\n\n\
+                             Your plugins and configuration in <span foreground=\"cyan\" weight=\"bold\">~/homebrew</span> will be <span foreground=\"cyan\" weight=\"bold\">kept intact</span>.\n\n\
+                             CEF remote debugging will be disabled.",
+                        ),
+                        move || {
+                            task_runner::run(
+                                window_inner.upcast_ref(),
+                                decky::remove(false),
+                                "Uninstall Decky Loader",
+                            );
+                        },
+                    );
+                }
+                "wipe" => {
+                    let window_inner = window_clone.clone();
+                    crate::ui::dialogs::warning::show_destructive_confirmation(
+                        window_clone.upcast_ref(),
+                        &gettext("Wipe Decky Loader"),
+                        &gettext(
+                            "<span foreground=\"red\" weight=\"bold\">WARNING: This is a destructive action!</span>\n\n\
+                             This will <span foreground=\"red\" weight=\"bold\">completely remove</span> Decky Loader <span foreground=\"red\" weight=\"bold\">including all plugins and configuration</span>.\n\n\
+                             The entire <span foreground=\"cyan\" weight=\"bold\">~/homebrew</span> folder will be deleted.\n\
+                             CEF remote debugging will be disabled.\n\n\
+                             This action <span foreground=\"red\" weight=\"bold\">cannot be undone</span>.",
+                        ),
+                        move |snapshot_step| {
+                            task_runner::run(
+                                window_inner.upcast_ref(),
+                                snapshot::before(snapshot_step, decky::remove(true)),
+                                "Wipe Decky Loader",
+                            );
+                        },
+                    );
+                }
+                _ => {}
+            }
+        });
+    });
+}
+
+fn setup_rice_switcher(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_rice_switcher") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Rice Switcher button clicked");
+
+        let window = window.clone();
+        spawn_blocking(
+            || {
+                let available: Vec<bool> = rice::THEMES.iter().map(|t| t.available()).collect();
+                (available, rice::list_profiles())
+            },
+            move |(available, profiles)| show_rice_switcher(&window, &available, profiles),
+        );
+    });
+}
+
+/// List the curated global themes and the saved profiles. Applying a theme
+/// saves the current look as a profile first, so every change can be undone.
+fn show_rice_switcher(window: &ApplicationWindow, available: &[bool], profiles: Vec<String>) {
+    let themes_group = adw::PreferencesGroup::builder()
+        .title(gettext("Global Themes"))
+        .build();
+    let profiles_group = adw::PreferencesGroup::builder()
+        .title(gettext("Saved Profiles"))
+        .description(gettext("Plasma, KWin and Konsole settings"))
+        .build();
+    let content = gtk4::Box::builder()
+        .orientation(gtk4::Orientation::Vertical)
+        .spacing(18)
+        .build();
+    content.append(&themes_group);
+    content.append(&profiles_group);
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .min_content_height(360)
+        .child(&content)
+        .build();
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Rice Switcher"))
+        .body(gettext(
+            "Switch the Plasma look. The current one is saved as a profile \
+             first and can be restored from here.",
+        ))
+        .extra_child(&scrolled)
+        .build();
+
+    for (theme, &available) in rice::THEMES.iter().zip(available) {
+        let row = adw::ActionRow::builder()
+            .title(theme.name)
+            .subtitle(if available {
+                gettext(theme.description)
+            } else {
+                gettext("Not installed")
+            })
+            .build();
+        let apply_button = Button::builder()
+            .label(gettext("Apply"))
+            .valign(gtk4::Align::Center)
+            .sensitive(available)
+            .build();
+        let dialog_clone = dialog.clone();
+        let window_clone = window.clone();
+        apply_button.connect_clicked(move |_| {
+            dialog_clone.close();
+            let window = window_clone.clone();
+            show_save_profile(
+                &window_clone,
+                &gettext_f("Before {theme}", &[("theme", theme.name)]),
+                &gettext_f("Save and Apply {theme}", &[("theme", theme.name)]),
+                move || apply_global_theme(&window, theme),
+            );
+        });
+        row.add_suffix(&apply_button);
+        themes_group.add(&row);
+    }
+
+    if profiles.is_empty() {
+        profiles_group.add(
+            &adw::ActionRow::builder()
+                .title(gettext("No saved profiles yet"))
+                .build(),
+        );
+    }
+    for name in profiles {
+        // Profile names are typed by the user, not markup
+        let row = adw::ActionRow::builder()
+            .title(name.as_str())
+            .use_markup(false)
+            .build();
+
+        let restore_button = Button::builder()
+            .label(gettext("Restore"))
+            .valign(gtk4::Align::Center)
+            .build();
+        let dialog_clone = dialog.clone();
+        let window_clone = window.clone();
+        let profile = name.clone();
+        restore_button.connect_clicked(move |_| {
+            dialog_clone.close();
+            restore_profile(&window_clone, &profile);
+        });
+        row.add_suffix(&restore_button);
+
+        let delete_button = Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text(gettext("Delete"))
+            .valign(gtk4::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+        let window_clone = window.clone();
+        let group = profiles_group.clone();
+        let row_clone = row.clone();
+        delete_button.connect_clicked(move |_| {
+            let window = window_clone.clone();
+            let group = group.clone();
+            let row = row_clone.clone();
+            let name = name.clone();
+            crate::ui::dialogs::warning::show_warning_confirmation(
+                window_clone.upcast_ref(),
+                &gettext("Delete Profile"),
+                &gettext_f(
+                    "The saved profile <b>{profile}</b> will be <span foreground=\"red\" weight=\"bold\">deleted</span>.",
+                    &[("profile", &gtk4::glib::markup_escape_text(&name))],
+                ),
+                move || {
+                    spawn_blocking(
+                        move || rice::delete_profile(&name),
+                        move |result| match result {
+                            Ok(()) => group.remove(&row),
+                            Err(e) => show_error(&window, &format!("{:#}", e)),
+                        },
+                    );
+                },
+            );
+        });
+        row.add_suffix(&delete_button);
+
+        profiles_group.add(&row);
+    }
+
+    dialog.add_responses(&[
+        ("close", gettext("Close").as_str()),
+        ("save", gettext("Save Current Look").as_str()),
+    ]);
+    dialog.set_close_response("close");
+
+    let window_clone = window.clone();
+    dialog.connect_response(Some("save"), move |_, _| {
+        show_save_profile(&window_clone, "", &gettext("Save"), || {});
+    });
+
+    dialog.present(Some(window));
+}
+
+/// Ask for a profile name and save the current look under it, then run
+/// `then`. Nothing else happens if saving fails.
+fn show_save_profile<F>(
+    window: &ApplicationWindow,
+    default_name: &str,
+    confirm_label: &str,
+    then: F,
+) where
+    F: FnOnce() + 'static,
+{
+    let name_row = adw::EntryRow::builder()
+        .title(gettext("Profile Name"))
+        .text(default_name)
+        .build();
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    list.append(&name_row);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Save Current Look"))
+        .body(gettext(
+            "Plasma, KWin and Konsole settings are saved under this name. \
+             A profile with the same name is replaced.",
+        ))
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("cancel", gettext("Cancel").as_str()),
+        ("save", confirm_label),
+    ]);
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+
+    let window_clone = window.clone();
+    let then = Rc::new(RefCell::new(Some(then)));
+    dialog.connect_response(Some("save"), move |_, _| {
+        let name = name_row.text().trim().to_string();
+        if let Err(e) = rice::validate_name(&name) {
+            show_error(&window_clone, &e.to_string());
+            return;
+        }
+        let Some(then) = then.borrow_mut().take() else {
+            return;
+        };
+        let window = window_clone.clone();
+        spawn_blocking(
+            move || rice::save_profile(&name),
+            move |result| match result {
+                Ok(_) => then(),
+                Err(e) => show_error(&window, &format!("{:#}", e)),
+            },
+        );
+    });
+
+    dialog.present(Some(window));
+}
+
+fn apply_global_theme(window: &ApplicationWindow, theme: &rice::GlobalTheme) {
+    info!("Applying global theme {}", theme.name);
+
+    match theme.apply {
+        rice::Apply::Script(script) => {
+            terminal::show_terminal_dialog(
+                window.upcast_ref(),
+                &format!("{} Theme Installation", theme.name),
+                script,
+                &[],
+            );
+        }
+        rice::Apply::LookAndFeel(id) => {
+            let commands = CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("plasma-apply-lookandfeel")
+                        .args(&["--apply", id])
+                        .description(&format!("Applying the {} global theme...", theme.name))
+                        .build(),
+                )
+                .build();
+            task_runner::run(window.upcast_ref(), commands, "Rice Switcher");
+        }
+    }
+}
+
+fn restore_profile(window: &ApplicationWindow, name: &str) {
+    let dir = rice::profiles_dir()
+        .join(name)
+        .to_string_lossy()
+        .to_string();
+    let description = format!("Restoring the {} profile...", name);
+    let window_clone = window.clone();
+    crate::ui::dialogs::warning::show_warning_confirmation(
+        window.upcast_ref(),
+        &gettext("Restore Profile"),
+        &gettext_f(
+            "The current Plasma, KWin and Konsole settings will be replaced with \
+             the <b>{profile}</b> profile.\n\n\
+             Plasmashell will be <span foreground=\"red\" weight=\"bold\">restarted</span>.",
+            &[("profile", &gtk4::glib::markup_escape_text(name))],
+        ),
+        move || {
+            info!("Restoring rice profile {}", dir);
+            let commands = CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("sh")
+                        .args(&["-c", rice::RESTORE_SCRIPT, "sh", &dir])
+                        .description(&description)
+                        .build(),
+                )
+                .build();
+            task_runner::run(window_clone.upcast_ref(), commands, "Rice Switcher");
+        },
+    );
+}
+
+fn setup_config_reset(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_config_reset") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Config/Rice Reset button clicked");
+
+        let mut config = SelectionDialogConfig::new(
+            &gettext("Config/Rice Reset"),
+            &gettext(
+                "Pick what to reset to the defaults in /etc/skel. The files it \
+                 overwrites are listed next.",
+            ),
+        )
+        .selection_type(SelectionType::Multi)
+        .selection_required(true)
+        .confirm_label(&gettext("Next"));
+        for scope in Scope::ALL {
+            config = config.add_option(SelectionOption::new(
+                scope.id(),
+                &gettext(scope.name()),
+                &gettext(scope.description()),
+                false,
+            ));
+        }
+
+        let window_clone = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let scopes: Vec<Scope> = selected
+                .iter()
+                .filter_map(|id| Scope::from_id(id))
+                .collect();
+            let window = window_clone.clone();
+            spawn_blocking(
+                move || {
+                    let plan = reset::plan(&scopes);
+                    (scopes, plan)
+                },
+                move |(scopes, plan)| confirm_config_reset(&window, scopes, plan),
+            );
+        });
+    });
+}
+
+/// Files listed by name in the confirmation; the rest are counted.
+const RESET_LISTED_FILES: usize = 12;
+
+fn confirm_config_reset(window: &ApplicationWindow, scopes: Vec<Scope>, plan: reset::Plan) {
+    if plan.entries.is_empty() {
+        show_error(
+            window,
+            &gettext("/etc/skel has no defaults for what was picked."),
+        );
+        return;
+    }
+
+    let overwritten = if plan.overwritten.is_empty() {
+        gettext("No existing files are overwritten.")
+    } else {
+        let mut list: Vec<String> = plan
+            .overwritten
+            .iter()
+            .take(RESET_LISTED_FILES)
+            .map(|f| format!("~/{}", gtk4::glib::markup_escape_text(f)))
+            .collect();
+        if plan.overwritten.len() > RESET_LISTED_FILES {
+            list.push(gettext_f(
+                "and {count} more",
+                &[(
+                    "count",
+                    &(plan.overwritten.len() - RESET_LISTED_FILES).to_string(),
+                )],
+            ));
+        }
+        gettext_f(
+            "These files are <span foreground=\"red\" weight=\"bold\">overwritten</span> \
+             and backed up to <span foreground=\"cyan\" weight=\"bold\">~/.reset-backup-…</span> first:\n{files}",
+            &[("files", &list.join("\n"))],
+        )
+    };
+    let plasma = scopes.contains(&Scope::Plasma) || scopes.contains(&Scope::Everything);
+    let mut message = format!(
+        "{}\n\n{}",
+        overwritten,
+        gettext("Changes take effect after logging out and back in.")
+    );
+    if plasma {
+        message.push_str("\n\n");
+        message.push_str(&gettext(
+            "Plasma settings are also saved as the <b>Before Reset</b> profile \
+             in the Rice Switcher.",
+        ));
+    }
+
+    let window_clone = window.clone();
+    crate::ui::dialogs::warning::show_destructive_confirmation(
+        window.upcast_ref(),
+        &gettext("Config/Rice Reset"),
+        &message,
+        move |snapshot_step| {
+            let window = window_clone.clone();
+            spawn_blocking(
+                move || plasma.then(|| rice::save_profile("Before Reset")),
+                move |result| {
+                    if let Some(Err(e)) = result {
+                        warn!("Failed to save the Before Reset profile: {:#}", e);
+                    }
+                    task_runner::run(
+                        window.upcast_ref(),
+                        snapshot::before(snapshot_step, reset::commands(&plan)),
+                        "Config/Rice Reset",
+                    );
+                },
+            );
+        },
+    );
+}
+
+fn setup_gnome_tweaks(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_gnome_tweaks") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("GNOME Tweaks button clicked");
+
+        run_action(&window, &actions::customization::GNOME_TWEAKS);
+    });
+}
+
+fn setup_extension_manager(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_extension_manager") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Extension Manager button clicked");
+
+        run_action(&window, &actions::customization::EXTENSION_MANAGER);
+    });
+}
+
+fn setup_adw_gtk3(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_adw_gtk3") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("adw-gtk3 Theme button clicked");
+
+        run_action(&window, &actions::customization::ADW_GTK3);
+    });
+}
+
+fn setup_dash_to_dock(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_dash_to_dock") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Dash to Dock button clicked");
+
+        run_action(&window, &actions::customization::DASH_TO_DOCK);
+    });
+}
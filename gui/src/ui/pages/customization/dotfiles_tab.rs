@@ -0,0 +1,353 @@
+//! Dotfiles tab.
+//!
+//! Handles:
+//! - Setting up a bare git repository in ~/.dotfiles
+//! - Picking which config paths are tracked
+//! - Committing and pushing to a user-provided remote
+//! - Restoring from the remote on a fresh install
+
+use crate::core::dotfiles::{self, Status};
+use crate::i18n::{gettext, gettext_f, n_};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{spawn_blocking, try_extract_widget};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Repository state and which paths exist, read off the main thread.
+#[derive(Default)]
+struct State {
+    status: Status,
+    present: Vec<bool>,
+}
+
+impl State {
+    fn read() -> Self {
+        Self {
+            status: Status::read(),
+            present: dotfiles::present(),
+        }
+    }
+}
+
+struct DotfilesTab {
+    window: ApplicationWindow,
+    status_row: adw::ActionRow,
+    init_button: Button,
+    remote_row: adw::EntryRow,
+    restore_row: adw::ActionRow,
+    paths_group: adw::PreferencesGroup,
+    save_button: Button,
+    /// One row per entry of [`dotfiles::CANDIDATES`].
+    path_rows: Vec<adw::SwitchRow>,
+    state: RefCell<State>,
+}
+
+/// Set up the dotfiles tab.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let rows = (
+        try_extract_widget::<adw::ActionRow>(page_builder, "dotfiles_status_row"),
+        try_extract_widget::<adw::EntryRow>(page_builder, "dotfiles_remote_row"),
+        try_extract_widget::<adw::ActionRow>(page_builder, "dotfiles_restore_row"),
+        try_extract_widget::<adw::PreferencesGroup>(page_builder, "dotfiles_paths_group"),
+    );
+    let buttons = (
+        try_extract_widget::<Button>(page_builder, "btn_dotfiles_init"),
+        try_extract_widget::<Button>(page_builder, "btn_dotfiles_restore"),
+        try_extract_widget::<Button>(page_builder, "btn_dotfiles_save"),
+    );
+    let (
+        (Some(status_row), Some(remote_row), Some(restore_row), Some(paths_group)),
+        (Some(init_button), Some(restore_button), Some(save_button)),
+    ) = (rows, buttons)
+    else {
+        return;
+    };
+
+    let path_rows = dotfiles::CANDIDATES
+        .iter()
+        .map(|(path, name)| {
+            let row = adw::SwitchRow::builder()
+                .title(gettext(*name))
+                .subtitle(format!("~/{}", path))
+                .build();
+            paths_group.add(&row);
+            row
+        })
+        .collect();
+
+    let tab = Rc::new(DotfilesTab {
+        window: window.clone(),
+        status_row,
+        init_button,
+        remote_row,
+        restore_row,
+        paths_group,
+        save_button,
+        path_rows,
+        state: RefCell::new(State::default()),
+    });
+
+    let tab_clone = tab.clone();
+    tab.init_button.connect_clicked(move |_| {
+        info!("Set up dotfiles clicked");
+        init_repo(&tab_clone);
+    });
+
+    let tab_clone = tab.clone();
+    tab.remote_row.connect_apply(move |_| {
+        info!("Set dotfiles remote clicked");
+        set_remote(&tab_clone);
+    });
+
+    let tab_clone = tab.clone();
+    restore_button.connect_clicked(move |_| {
+        info!("Restore dotfiles clicked");
+        restore(&tab_clone);
+    });
+
+    let tab_clone = tab.clone();
+    tab.save_button.connect_clicked(move |_| {
+        info!("Commit dotfiles clicked");
+        save(&tab_clone);
+    });
+
+    // The repository changes when a task finishes
+    let tab_clone = tab.clone();
+    install_state::connect_refresh(move || load(&tab_clone));
+}
+
+/// Read the state off the main thread, then show it.
+fn load(tab: &Rc<DotfilesTab>) {
+    let tab = tab.clone();
+    spawn_blocking(State::read, move |state| {
+        *tab.state.borrow_mut() = state;
+        render(&tab);
+    });
+}
+
+fn render(tab: &DotfilesTab) {
+    let state = tab.state.borrow();
+    let status = &state.status;
+
+    if status.initialized {
+        let last_commit = match &status.last_commit {
+            Some(when) => gettext_f("last commit {when}", &[("when", when)]),
+            None => gettext("no commits yet"),
+        };
+        tab.status_row.set_subtitle(&gettext_f(
+            "{tracked} files tracked, {changed} changed, {last_commit}",
+            &[
+                ("tracked", &status.files.len().to_string()),
+                ("changed", &status.changed.to_string()),
+                ("last_commit", &last_commit),
+            ],
+        ));
+    } else {
+        tab.status_row.set_subtitle(&gettext("Not set up"));
+    }
+    tab.init_button.set_visible(!status.initialized);
+    tab.restore_row.set_visible(!status.initialized);
+
+    // Before setup the URL is only read by Restore
+    tab.remote_row.set_show_apply_button(status.initialized);
+    if let Some(remote) = &status.remote {
+        if tab.remote_row.text() != remote.as_str() {
+            tab.remote_row.set_text(remote);
+        }
+    }
+
+    tab.paths_group.set_sensitive(status.initialized);
+    tab.save_button.set_label(&if status.remote.is_some() {
+        gettext("Commit and Push")
+    } else {
+        gettext("Commit")
+    });
+    for (((path, _), row), &present) in dotfiles::CANDIDATES
+        .iter()
+        .zip(&tab.path_rows)
+        .zip(&state.present)
+    {
+        let tracked = dotfiles::is_tracked(&status.files, path);
+        row.set_active(tracked);
+        // Tracked paths stay switchable, so a deleted one can be dropped
+        row.set_sensitive(present || tracked);
+        row.set_subtitle(&if present {
+            format!("~/{}", path)
+        } else {
+            format!("~/{}, not found", path)
+        });
+    }
+}
+
+/// `git` with the bare repository and home folder set, running `args`.
+fn git_command(args: &[&str], description: &str) -> Command {
+    let repo_args = dotfiles::git_args();
+    let mut all_args: Vec<&str> = repo_args.iter().map(String::as_str).collect();
+    all_args.extend_from_slice(args);
+    Command::builder()
+        .normal()
+        .program("git")
+        .args(&all_args)
+        .description(description)
+        .build()
+}
+
+fn init_repo(tab: &DotfilesTab) {
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", &dotfiles::init_script()])
+                .description("Creating the dotfiles repository...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(
+                    &["Pick the paths to track, then commit them. The 'dotfiles' \
+                     alias runs git on the repository from a new terminal."],
+                )
+                .description("Dotfiles repository ready")
+                .build(),
+        )
+        .build();
+    task_runner::run(tab.window.upcast_ref(), commands, "Dotfiles Setup");
+}
+
+/// The URL typed in the remote row, or an error shown to the user.
+fn remote_url(tab: &DotfilesTab) -> Option<String> {
+    let url = tab.remote_row.text().trim().to_string();
+    if dotfiles::is_valid_remote(&url) {
+        Some(url)
+    } else {
+        show_error(
+            &tab.window,
+            "Enter a git remote URL, such as https://github.com/user/dotfiles.git \
+             or git@github.com:user/dotfiles.git.",
+        );
+        None
+    }
+}
+
+fn set_remote(tab: &DotfilesTab) {
+    let Some(url) = remote_url(tab) else {
+        return;
+    };
+
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", &dotfiles::remote_script(), "sh", &url])
+                .description("Setting the dotfiles remote...")
+                .build(),
+        )
+        .build();
+    task_runner::run(tab.window.upcast_ref(), commands, "Dotfiles Remote");
+}
+
+fn restore(tab: &DotfilesTab) {
+    let Some(url) = remote_url(tab) else {
+        return;
+    };
+
+    let window = tab.window.clone();
+    show_warning_confirmation(
+        tab.window.upcast_ref(),
+        &gettext("Restore Dotfiles"),
+        &gettext_f(
+            "The files in <b>{url}</b> will be checked out into your home folder.\n\n\
+             Existing files they replace are <span foreground=\"cyan\" weight=\"bold\">moved</span> \
+             to ~/.dotfiles-backup first.",
+            &[("url", &gtk4::glib::markup_escape_text(&url))],
+        ),
+        move || {
+            let commands = CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("sh")
+                        .args(&["-c", &dotfiles::restore_script(), "sh", &url])
+                        .description("Restoring dotfiles from the remote...")
+                        .build(),
+                )
+                .build();
+            task_runner::run(window.upcast_ref(), commands, "Dotfiles Restore");
+        },
+    );
+}
+
+/// Track the switched-on paths, untrack the switched-off ones, commit and
+/// push.
+fn save(tab: &DotfilesTab) {
+    let state = tab.state.borrow();
+    let status = &state.status;
+    if !status.identity {
+        show_error(
+            &tab.window,
+            "Git doesn't know your name and email yet. Set them up in \
+             Dev Tools, Git Setup first.",
+        );
+        return;
+    }
+
+    let mut add = Vec::new();
+    let mut untrack = Vec::new();
+    for (((path, _), row), &present) in dotfiles::CANDIDATES
+        .iter()
+        .zip(&tab.path_rows)
+        .zip(&state.present)
+    {
+        let tracked = dotfiles::is_tracked(&status.files, path);
+        if row.is_active() && present {
+            add.push(*path);
+        } else if !row.is_active() && tracked {
+            untrack.push(*path);
+        }
+    }
+    if add.is_empty() && untrack.is_empty() && status.files.is_empty() {
+        show_error(
+            &tab.window,
+            &gettext("Switch on at least one path to track."),
+        );
+        return;
+    }
+
+    let mut commands = CommandSequence::new();
+    if !untrack.is_empty() {
+        let mut args = vec!["rm", "-r", "--cached", "--quiet", "--"];
+        args.extend(&untrack);
+        commands = commands.then(git_command(&args, n_("Untracking switched-off paths...")));
+    }
+    if !add.is_empty() {
+        let mut args = vec!["add", "--"];
+        args.extend(&add);
+        commands = commands.then(git_command(&args, n_("Adding tracked paths...")));
+    }
+    commands = commands.then(
+        Command::builder()
+            .normal()
+            .program("sh")
+            .args(&["-c", &dotfiles::commit_script()])
+            .description("Committing changes...")
+            .build(),
+    );
+    if status.remote.is_some() {
+        commands = commands.then(git_command(
+            &["push", "-u", "origin", "HEAD"],
+            n_("Pushing to the remote..."),
+        ));
+    }
+
+    task_runner::run(tab.window.upcast_ref(), commands.build(), "Dotfiles");
+}
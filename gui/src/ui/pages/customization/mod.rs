@@ -0,0 +1,21 @@
+//! Customization page with subtabs for the desktop look and dotfiles.
+//!
+//! This module provides a unified page with two subtabs:
+//! - Customization: themes, shell setup, Decky Loader and the rice switcher
+//! - Dotfiles: config files tracked in a git repository and pushed to a remote
+
+pub mod customization_tab;
+pub mod dotfiles_tab;
+
+use gtk4::{ApplicationWindow, Builder};
+use log::info;
+
+/// Set up all handlers for the customization page with subtabs.
+pub fn setup_handlers(page_builder: &Builder, main_builder: &Builder, window: &ApplicationWindow) {
+    info!("Setting up Customization page with subtabs");
+
+    customization_tab::setup_handlers(page_builder, main_builder, window);
+    dotfiles_tab::setup_handlers(page_builder, main_builder, window);
+
+    info!("Customization page handlers initialized");
+}
@@ -0,0 +1,548 @@
+//! Dev tools page button handlers.
+//!
+//! Handles install + uninstall for:
+//! - Code editors (Code - OSS from the repos, VS Code from the AUR, or
+//!   VSCodium / VS Code from Flathub)
+//! - Language toolchains (rustup, GCC/Clang, Python with pipx, Node.js via fnm)
+//! - Git identity and credential helper
+//! - Dev containers (devcontainer CLI on Docker, plus the VS Code extension)
+
+use crate::actions;
+use crate::core;
+use crate::core::devtools::{self, GitConfig, Source};
+use crate::i18n::{gettext, n_};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
+use crate::ui::install_state::{self, Probe};
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{extract_action_widgets, spawn_blocking};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::info;
+
+// ─── Shared helpers ─────────────────────────────────────────────────────────
+
+/// Update install / uninstall button pair based on installation status.
+fn update_button_state(
+    install_button: &Button,
+    uninstall_button: &Button,
+    is_installed: bool,
+    default_label: &str,
+) {
+    let label = gettext(default_label);
+    if is_installed {
+        install_button.set_label(&format!("{} ✓", label));
+        install_button.set_sensitive(false);
+        install_button.remove_css_class("suggested-action");
+        install_button.add_css_class("dim-label");
+        uninstall_button.set_visible(true);
+    } else {
+        install_button.set_label(&label);
+        install_button.set_sensitive(true);
+        install_button.add_css_class("suggested-action");
+        install_button.remove_css_class("dim-label");
+        uninstall_button.set_visible(false);
+    }
+}
+
+/// `pacman -Rns` step for the installed packages among `candidates`, if any.
+fn remove_packages_command(candidates: &[&str], description: &str) -> Option<Command> {
+    let pkgs: Vec<&str> = candidates
+        .iter()
+        .copied()
+        .filter(|pkg| core::is_package_installed(pkg))
+        .collect();
+    if pkgs.is_empty() {
+        return None;
+    }
+    let mut args = vec!["-Rns", "--noconfirm"];
+    args.extend(pkgs);
+    Some(
+        Command::builder()
+            .privileged()
+            .program("pacman")
+            .args(&args)
+            .description(description)
+            .build(),
+    )
+}
+
+// ─── Page entry point ───────────────────────────────────────────────────────
+
+/// Set up all button handlers for the dev tools page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let editor_btns = setup_code_editor(page_builder, window);
+    let toolchain_btns = setup_toolchains(page_builder, window);
+    let git_btns = setup_git(page_builder, window);
+    let devcontainer_btns = setup_devcontainers(page_builder, window);
+
+    let bind = |probe: Probe, buttons: Option<(Button, Button)>, label: &'static str| {
+        let Some((install, uninstall)) = buttons else {
+            return;
+        };
+        let install_clone = install.clone();
+        install_state::bind_widget(&install, probe, move |installed| {
+            update_button_state(&install_clone, &uninstall, installed, label)
+        });
+    };
+    bind(
+        Probe::Custom(|| {
+            devtools::EDITOR_PACKAGES
+                .iter()
+                .any(|pkg| core::is_package_installed(pkg))
+                || devtools::EDITOR_FLATPAKS
+                    .iter()
+                    .any(|id| core::is_flatpak_installed(id))
+        }),
+        editor_btns,
+        n_("Code Editor"),
+    );
+    bind(
+        Probe::AnyPackage(devtools::TOOLCHAIN_PACKAGES),
+        toolchain_btns,
+        n_("Toolchains"),
+    );
+    bind(
+        Probe::Custom(devtools::git_configured),
+        git_btns,
+        n_("Git Setup"),
+    );
+    bind(
+        Probe::Package("devcontainer-cli"),
+        devcontainer_btns,
+        n_("Dev Containers"),
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Code editor
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_code_editor(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_code_editor", "btn_code_editor_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Code editor install button clicked");
+
+        let mut config = SelectionDialogConfig::new(
+            &gettext("Code Editor"),
+            &gettext(
+                "Pick a build. They share the same editor, the difference is the \
+                 extension marketplace and how it's packaged.",
+            ),
+        )
+        .selection_type(SelectionType::Single)
+        .selection_required(true)
+        .confirm_label(&gettext("Install"));
+        for editor in devtools::EDITORS {
+            let installed = match editor.source {
+                Source::Repo(pkg) | Source::Aur(pkg) => core::is_package_installed(pkg),
+                Source::Flatpak(id) => core::is_flatpak_installed(id),
+            };
+            config = config.add_option(SelectionOption::new(
+                editor.id,
+                editor.name,
+                &gettext(editor.description),
+                installed,
+            ));
+        }
+
+        let window_for_closure = window_clone.clone();
+        show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
+            let Some(editor) = selected.first().and_then(|id| devtools::find_editor(id)) else {
+                return;
+            };
+            let builder = match editor.source {
+                Source::Repo(_) => Command::builder().privileged().program("pacman"),
+                Source::Aur(_) => Command::builder().aur(),
+                Source::Flatpak(_) => Command::builder().normal().program("flatpak"),
+            };
+            let args = match editor.source {
+                Source::Repo(pkg) | Source::Aur(pkg) => ["-S", "--noconfirm", "--needed", pkg],
+                Source::Flatpak(id) => ["install", "-y", "flathub", id],
+            };
+            let commands = CommandSequence::new()
+                .then(
+                    builder
+                        .args(&args)
+                        .description(&format!("Installing {}...", editor.name))
+                        .build(),
+                )
+                .build();
+            task_runner::run(window_for_closure.upcast_ref(), commands, editor.name);
+        });
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    //
+    // Removes every installed build. Settings in ~/.config stay.
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Code editor uninstall button clicked");
+
+        let mut commands = CommandSequence::new();
+        if let Some(remove) = remove_packages_command(
+            devtools::EDITOR_PACKAGES,
+            n_("Removing code editor packages..."),
+        ) {
+            commands = commands.then(remove);
+        }
+        for id in devtools::EDITOR_FLATPAKS {
+            if core::is_flatpak_installed(id) {
+                commands = commands.then(
+                    Command::builder()
+                        .normal()
+                        .program("flatpak")
+                        .args(&["uninstall", "-y", id])
+                        .description(&format!("Removing {}...", id))
+                        .build(),
+                );
+            }
+        }
+
+        if !commands.is_empty() {
+            task_runner::run(
+                window_clone.upcast_ref(),
+                commands.build(),
+                "Code Editor Uninstall",
+            );
+        }
+    });
+
+    Some((btn_install, btn_uninstall))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Toolchains
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Installs the LTS release and adds fnm's hook to the shells in use, so
+/// `node` is on `PATH` in new terminals.
+const FNM_SETUP: &str = r#"set -e
+fnm install --lts
+for rc in "$HOME/.bashrc" "$HOME/.zshrc"; do
+    if [ -f "$rc" ] && ! grep -q 'fnm env' "$rc"; then
+        printf '\neval "$(fnm env --use-on-cd)"\n' >> "$rc"
+    fi
+done
+if [ -d "$HOME/.config/fish" ]; then
+    mkdir -p "$HOME/.config/fish/conf.d"
+    echo 'fnm env --use-on-cd --shell fish | source' > "$HOME/.config/fish/conf.d/fnm.fish"
+fi
+"#;
+
+fn setup_toolchains(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_toolchains", "btn_toolchains_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Toolchains install button clicked");
+
+        let mut config = SelectionDialogConfig::new(
+            &gettext("Toolchains"),
+            &gettext(
+                "Select the languages to set up. Each is installed from the Arch \
+                 repos and configured for your user.",
+            ),
+        )
+        .selection_type(SelectionType::Multi)
+        .selection_required(true)
+        .confirm_label(&gettext("Install"));
+        for toolchain in devtools::TOOLCHAINS {
+            config = config.add_option(SelectionOption::new(
+                toolchain.id,
+                toolchain.name,
+                &gettext(toolchain.description),
+                core::is_package_installed(toolchain.marker),
+            ));
+        }
+
+        let window_for_closure = window_clone.clone();
+        show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
+            let toolchains: Vec<_> = selected
+                .iter()
+                .filter_map(|id| devtools::find_toolchain(id))
+                .collect();
+            if toolchains.is_empty() {
+                return;
+            }
+
+            let mut install = vec!["-S", "--noconfirm", "--needed"];
+            for toolchain in &toolchains {
+                install.extend(toolchain.packages);
+            }
+            let mut commands = CommandSequence::new().then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&install)
+                    .description("Installing toolchain packages...")
+                    .build(),
+            );
+
+            for toolchain in &toolchains {
+                let setup = match toolchain.id {
+                    "rust" => Command::builder()
+                        .normal()
+                        .program("rustup")
+                        .args(&["default", "stable"])
+                        .description("Installing the stable Rust toolchain..."),
+                    "python" => Command::builder()
+                        .normal()
+                        .program("pipx")
+                        .args(&["ensurepath"])
+                        .description("Adding pipx apps to PATH..."),
+                    "node" => Command::builder()
+                        .normal()
+                        .program("sh")
+                        .args(&["-c", FNM_SETUP])
+                        .description("Installing Node.js LTS with fnm..."),
+                    _ => continue,
+                };
+                commands = commands.then(setup.build());
+            }
+
+            task_runner::run(
+                window_for_closure.upcast_ref(),
+                commands.build(),
+                "Toolchains Setup",
+            );
+        });
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    //
+    // ~/.rustup, ~/.cargo, pipx apps and fnm's Node versions are left in
+    // place, the same as the editor settings.
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Toolchains uninstall button clicked");
+
+        let Some(remove) = remove_packages_command(
+            devtools::TOOLCHAIN_PACKAGES,
+            n_("Removing toolchain packages and dependencies..."),
+        ) else {
+            return;
+        };
+        task_runner::run(
+            window_clone.upcast_ref(),
+            CommandSequence::new().then(remove).build(),
+            "Toolchains Uninstall",
+        );
+    });
+
+    Some((btn_install, btn_uninstall))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Git
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_git(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_git_setup", "btn_git_setup_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Set up ───────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Git setup button clicked");
+
+        let window = window_clone.clone();
+        spawn_blocking(GitConfig::read, move |current| {
+            show_git_form(&window, current);
+        });
+    });
+
+    // ── Reset ────────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Git reset button clicked");
+
+        // --unset fails for keys that aren't set, which isn't an error here
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        "for key in user.name user.email credential.helper; do \
+                         git config --global --unset-all \"$key\" || true; done",
+                    ])
+                    .description("Removing git identity and credential helper...")
+                    .build(),
+            )
+            .build();
+        task_runner::run(window_clone.upcast_ref(), commands, "Git Reset");
+    });
+
+    Some((btn_install, btn_uninstall))
+}
+
+/// Ask for name, email and credential helper, prefilled from ~/.gitconfig.
+fn show_git_form(window: &ApplicationWindow, current: GitConfig) {
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+
+    let name_row = adw::EntryRow::builder()
+        .title(gettext("Name"))
+        .text(current.name.as_str())
+        .build();
+    let email_row = adw::EntryRow::builder()
+        .title(gettext("Email"))
+        .text(current.email.as_str())
+        .build();
+    list.append(&name_row);
+    list.append(&email_row);
+
+    let helpers: Vec<String> = devtools::CREDENTIAL_HELPERS
+        .iter()
+        .map(|(_, description)| gettext(*description))
+        .collect();
+    let helper_names: Vec<&str> = helpers.iter().map(|s| s.as_str()).collect();
+    let selected = devtools::CREDENTIAL_HELPERS
+        .iter()
+        .position(|(helper, _)| *helper == current.credential_helper)
+        .unwrap_or(devtools::CREDENTIAL_HELPERS.len() - 1);
+    let helper_row = adw::ComboRow::builder()
+        .title(gettext("Remember passwords in"))
+        .model(&gtk4::StringList::new(&helper_names))
+        .selected(selected as u32)
+        .build();
+    list.append(&helper_row);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Git Setup"))
+        .body(gettext(
+            "Commits are signed with this name and email. New repositories \
+             start on a main branch.",
+        ))
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("cancel", gettext("Cancel").as_str()),
+        ("save", gettext("Save").as_str()),
+    ]);
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+
+    let window_clone = window.clone();
+    dialog.connect_response(Some("save"), move |_, _| {
+        let helper = devtools::CREDENTIAL_HELPERS
+            .get(helper_row.selected() as usize)
+            .map_or("", |(helper, _)| *helper);
+        let config = GitConfig {
+            name: name_row.text().to_string(),
+            email: email_row.text().to_string(),
+            credential_helper: helper.to_string(),
+        };
+        if let Err(e) = config.validate() {
+            show_error(&window_clone, &e.to_string());
+            return;
+        }
+        task_runner::run(
+            window_clone.upcast_ref(),
+            git_commands(&config),
+            "Git Setup",
+        );
+    });
+
+    dialog.present(Some(window));
+}
+
+fn git_commands(config: &GitConfig) -> CommandSequence {
+    let mut commands = CommandSequence::new();
+
+    let mut missing = Vec::new();
+    if !core::is_package_installed("git") {
+        missing.push("git");
+    }
+    // git-credential-libsecret ships with git but links libsecret
+    if config.credential_helper == "libsecret" && !core::is_package_installed("libsecret") {
+        missing.push("libsecret");
+    }
+    if !missing.is_empty() {
+        let mut args = vec!["-S", "--noconfirm", "--needed"];
+        args.extend(missing);
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&args)
+                .description("Installing git...")
+                .build(),
+        );
+    }
+
+    for args in config.commands() {
+        let args: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("git")
+                .args(&args)
+                .description(&format!("Setting {}...", args[2]))
+                .build(),
+        );
+    }
+    commands.build()
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Dev containers
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_devcontainers(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_devcontainers", "btn_devcontainers_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Dev containers install button clicked");
+
+        if !core::is_package_installed("docker") {
+            show_error(
+                &window_clone,
+                &gettext(
+                    "Dev containers run on Docker. Install Docker from the Containers/VMs page first.",
+                ),
+            );
+            return;
+        }
+
+        super::run_action(&window_clone, &actions::dev_tools::DEVCONTAINERS);
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    //
+    // The VS Code extension is left installed.
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Dev containers uninstall button clicked");
+
+        let Some(remove) = remove_packages_command(
+            &["devcontainer-cli"],
+            n_("Removing the devcontainer CLI..."),
+        ) else {
+            return;
+        };
+        task_runner::run(
+            window_clone.upcast_ref(),
+            CommandSequence::new().then(remove).build(),
+            "Dev Containers Uninstall",
+        );
+    });
+
+    Some((btn_install, btn_uninstall))
+}
@@ -0,0 +1,460 @@
+//! Disks page handlers.
+//!
+//! Handles:
+//! - Listing disks with their filesystems and how full they are
+//! - SMART health checks with smartctl
+//! - Mounting and unmounting through udisks
+//! - Mounting a partition at boot through a guided fstab entry
+//! - Opening a partition editor for anything destructive
+
+use crate::core;
+use crate::core::desktop::Desktop;
+use crate::core::drives::{self, Disk, Partition};
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::install_state;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{spawn_blocking, try_extract_widget};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::{error, info};
+use std::cell::RefCell;
+use std::path::Path;
+use std::process::Stdio;
+use std::rc::Rc;
+
+/// Disks and fstab, read off the main thread.
+#[derive(Default)]
+struct State {
+    disks: Vec<Disk>,
+    /// UUIDs of the partitions fstab mounts.
+    in_fstab: Vec<String>,
+}
+
+impl State {
+    fn read() -> Self {
+        let disks = drives::disks();
+        let in_fstab = disks
+            .iter()
+            .flat_map(|d| &d.partitions)
+            .filter(|p| !p.uuid.is_empty() && drives::in_fstab(&p.uuid))
+            .map(|p| p.uuid.clone())
+            .collect();
+        Self { disks, in_fstab }
+    }
+}
+
+struct DisksPage {
+    window: ApplicationWindow,
+    disks_box: gtk4::Box,
+    /// Groups of the disks, replaced on every render.
+    groups: RefCell<Vec<adw::PreferencesGroup>>,
+    state: RefCell<State>,
+}
+
+/// Set up all handlers for the disks page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let (Some(disks_box), Some(editor_row), Some(editor_button)) = (
+        try_extract_widget::<gtk4::Box>(page_builder, "disks_box"),
+        try_extract_widget::<adw::ActionRow>(page_builder, "partition_editor_row"),
+        try_extract_widget::<Button>(page_builder, "btn_partition_editor"),
+    ) else {
+        return;
+    };
+
+    let page = Rc::new(DisksPage {
+        window: window.clone(),
+        disks_box,
+        groups: RefCell::new(Vec::new()),
+        state: RefCell::new(State::default()),
+    });
+
+    let editor = PartitionEditor::for_desktop();
+    editor_row.set_title(&gettext_f(
+        "Partitioning and Formatting in {editor}",
+        &[("editor", editor.name)],
+    ));
+    let page_clone = page.clone();
+    editor_button.connect_clicked(move |_| {
+        info!("Open partition editor clicked");
+        open_partition_editor(&page_clone, &editor);
+    });
+
+    // Mounts and fstab change when a task finishes
+    let page_clone = page.clone();
+    install_state::connect_refresh(move || load(&page_clone));
+}
+
+/// Read the state off the main thread, then show it.
+fn load(page: &Rc<DisksPage>) {
+    let page = page.clone();
+    spawn_blocking(State::read, move |state| {
+        *page.state.borrow_mut() = state;
+        render(&page);
+    });
+}
+
+fn render(page: &Rc<DisksPage>) {
+    for group in page.groups.borrow_mut().drain(..) {
+        page.disks_box.remove(&group);
+    }
+
+    let state = page.state.borrow();
+    for disk in &state.disks {
+        let group = disk_group(page, disk, &state.in_fstab);
+        page.disks_box.append(&group);
+        page.groups.borrow_mut().push(group);
+    }
+}
+
+/// How a disk is connected, as people know it.
+fn transport_name(transport: &str) -> String {
+    match transport {
+        "nvme" => "NVMe".to_string(),
+        "" => gettext("Internal"),
+        other => other.to_ascii_uppercase(),
+    }
+}
+
+fn disk_group(page: &Rc<DisksPage>, disk: &Disk, in_fstab: &[String]) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::builder()
+        .title(gtk4::glib::markup_escape_text(disk.display_name()).as_str())
+        .description(format!(
+            "{} · {} · {}",
+            disk.device,
+            disk.size,
+            if disk.removable {
+                gettext_f(
+                    "{transport}, removable",
+                    &[("transport", &transport_name(&disk.transport))],
+                )
+            } else {
+                transport_name(&disk.transport)
+            }
+        ))
+        .build();
+
+    let health_button = Button::builder()
+        .label(gettext("Check Health"))
+        .valign(gtk4::Align::Center)
+        .build();
+    let page_clone = page.clone();
+    let device = disk.device.clone();
+    health_button.connect_clicked(move |_| {
+        info!("Check health of {} clicked", device);
+        check_health(&page_clone, &device);
+    });
+    group.set_header_suffix(Some(&health_button));
+
+    if disk.partitions.is_empty() {
+        let row = adw::ActionRow::builder()
+            .title(gettext("No filesystems"))
+            .build();
+        group.add(&row);
+    }
+    for partition in &disk.partitions {
+        let fstab = in_fstab.contains(&partition.uuid);
+        group.add(&partition_row(page, disk, partition, fstab));
+    }
+    group
+}
+
+fn partition_row(
+    page: &Rc<DisksPage>,
+    disk: &Disk,
+    partition: &Partition,
+    in_fstab: bool,
+) -> adw::ActionRow {
+    let mut details = vec![partition.fstype.clone(), partition.size.clone()];
+    if !partition.label.is_empty() {
+        details.insert(0, partition.device.clone());
+    }
+    match &partition.mountpoint {
+        Some(mountpoint) if partition.avail.is_empty() => details.push(gettext_f(
+            "mounted at {mountpoint}",
+            &[("mountpoint", mountpoint)],
+        )),
+        Some(mountpoint) => details.push(gettext_f(
+            "mounted at {mountpoint}, {avail} free",
+            &[("mountpoint", mountpoint), ("avail", &partition.avail)],
+        )),
+        None if partition.is_mountable() => details.push(gettext("not mounted")),
+        None => {}
+    }
+    if partition.used_percent.is_some_and(|used| used >= 90) {
+        details.push(gettext("almost full"));
+    }
+    if in_fstab {
+        details.push(gettext("in fstab"));
+    }
+
+    // Labels come from the drive, not markup
+    let row = adw::ActionRow::builder()
+        .title(partition.display_name())
+        .subtitle(details.join(" · "))
+        .use_markup(false)
+        .build();
+
+    if let Some(used) = partition.used_percent {
+        let bar = gtk4::LevelBar::builder()
+            .min_value(0.0)
+            .max_value(100.0)
+            .value(f64::from(used))
+            .width_request(120)
+            .valign(gtk4::Align::Center)
+            .tooltip_text(gettext_f(
+                // xgettext:no-c-format
+                "{percent}% used",
+                &[("percent", &used.to_string())],
+            ))
+            .build();
+        // The default offsets colour a nearly empty drive as a warning
+        for offset in ["low", "high", "full"] {
+            bar.remove_offset_value(Some(offset));
+        }
+        row.add_suffix(&bar);
+    }
+
+    if partition.is_system() || !partition.is_mountable() {
+        return row;
+    }
+
+    let mounted = partition.mountpoint.is_some();
+    let mount_button = Button::builder()
+        .label(if mounted {
+            gettext("Unmount")
+        } else {
+            gettext("Mount")
+        })
+        .valign(gtk4::Align::Center)
+        .build();
+    let page_clone = page.clone();
+    let partition_clone = partition.clone();
+    mount_button.connect_clicked(move |_| {
+        toggle_mount(&page_clone, &partition_clone);
+    });
+    row.add_suffix(&mount_button);
+
+    if !in_fstab && !partition.uuid.is_empty() {
+        let fstab_button = Button::builder()
+            .icon_name("document-edit-symbolic")
+            .tooltip_text(gettext("Mount at Boot"))
+            .valign(gtk4::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+        let page_clone = page.clone();
+        let partition = partition.clone();
+        let removable = disk.removable;
+        fstab_button.connect_clicked(move |_| {
+            info!("Mount at boot clicked for {}", partition.device);
+            show_fstab_form(&page_clone, &partition, removable);
+        });
+        row.add_suffix(&fstab_button);
+    }
+
+    row
+}
+
+// ─── Actions ────────────────────────────────────────────────────────────────
+
+/// Mount or unmount through udisks, which asks for a password itself when
+/// polkit wants one.
+fn toggle_mount(page: &DisksPage, partition: &Partition) {
+    let (verb, description) = if partition.mountpoint.is_some() {
+        ("unmount", "Unmounting")
+    } else {
+        ("mount", "Mounting")
+    };
+    info!("{} {}", description, partition.device);
+
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("udisksctl")
+                .args(&[verb, "-b", &partition.device])
+                .description(&format!("{} {}...", description, partition.display_name()))
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Disks");
+}
+
+fn check_health(page: &DisksPage, device: &str) {
+    let mut commands = CommandSequence::new();
+    if !core::is_package_installed("smartmontools") {
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "smartmontools"])
+                .description("Installing smartmontools...")
+                .build(),
+        );
+    }
+    let commands = commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", drives::SMART_SCRIPT, "sh", device])
+                .description(&format!("Reading the health of {}...", device))
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Drive Health");
+}
+
+/// Ask where to mount the partition on every boot.
+fn show_fstab_form(page: &Rc<DisksPage>, partition: &Partition, removable: bool) {
+    let dir_row = adw::EntryRow::builder()
+        .title(gettext("Mount Point"))
+        .text(partition.mount_dir().to_string_lossy().as_ref())
+        .build();
+    let automount_row = adw::SwitchRow::builder()
+        .title(gettext("Mount When Opened"))
+        .subtitle(gettext("For drives that aren't always connected"))
+        .active(removable)
+        .build();
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    list.append(&dir_row);
+    list.append(&automount_row);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Mount at Boot"))
+        .body(format!(
+            "{} ({}) gets an fstab entry by its UUID, so it's mounted at the same \
+             place on every boot. The system still starts when it's missing, and \
+             the current fstab is kept as /etc/fstab.bak.",
+            partition.display_name(),
+            partition.fstype
+        ))
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("cancel", gettext("Cancel").as_str()),
+        ("add", gettext("Add Entry").as_str()),
+    ]);
+    dialog.set_response_appearance("add", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("add"));
+    dialog.set_close_response("cancel");
+
+    let page_clone = page.clone();
+    let partition = partition.clone();
+    dialog.connect_response(Some("add"), move |_, _| {
+        let dir = dir_row.text().trim().to_string();
+        if !drives::is_valid_mount_point(&dir) {
+            show_error(
+                &page_clone.window,
+                &gettext(
+                    "Pick a folder like /mnt/data, using only letters, digits, -, _ and dots.",
+                ),
+            );
+            return;
+        }
+        add_fstab_entry(
+            &page_clone,
+            &partition,
+            Path::new(&dir),
+            automount_row.is_active(),
+        );
+    });
+
+    dialog.present(Some(&page.window));
+}
+
+fn add_fstab_entry(page: &DisksPage, partition: &Partition, dir: &Path, automount: bool) {
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    let entry = partition.fstab_entry(dir, uid, gid, automount);
+    info!("Adding fstab entry: {}", entry);
+
+    let mut commands = CommandSequence::new();
+    if partition.is_ntfs() {
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "ntfs-3g"])
+                .description("Installing NTFS tools...")
+                .build(),
+        );
+    }
+    let commands = commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", &partition.fstab_mount_script(&entry, dir)])
+                .description(&format!(
+                    "Mounting {} at {}...",
+                    partition.display_name(),
+                    dir.display()
+                ))
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Mount at Boot");
+}
+
+/// The partition editor that fits the desktop.
+struct PartitionEditor {
+    program: &'static str,
+    package: &'static str,
+    name: &'static str,
+}
+
+impl PartitionEditor {
+    fn for_desktop() -> Self {
+        match Desktop::detect() {
+            Desktop::Plasma => Self {
+                program: "partitionmanager",
+                package: "partitionmanager",
+                name: "KDE Partition Manager",
+            },
+            _ => Self {
+                program: "gnome-disks",
+                package: "gnome-disk-utility",
+                name: "GNOME Disks",
+            },
+        }
+    }
+}
+
+/// Launch the editor, installing it first when it's missing.
+fn open_partition_editor(page: &DisksPage, editor: &PartitionEditor) {
+    if Path::new("/usr/bin").join(editor.program).exists() {
+        info!("Launching {}...", editor.name);
+        if let Err(e) = std::process::Command::new(editor.program)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            error!("Failed to launch {}: {}", editor.name, e);
+        }
+        return;
+    }
+
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", editor.package])
+                .description(&format!("Installing {}...", editor.name))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[&format!("Click Open again to start {}.", editor.name)])
+                .description(&format!("{} is installed", editor.name))
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Partition Editor");
+}
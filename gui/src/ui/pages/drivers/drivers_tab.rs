@@ -0,0 +1,265 @@
+//! Drivers tab button handlers.
+//!
+//! Handles:
+//! - Nvidia legacy drivers, AMD ROCm and NVIDIA CUDA
+//! - Tailscale VPN
+//! - ASUS ROG laptop tools
+//! - OpenRazer drivers
+//! - Zenergy and Cooler Control daemon tools
+
+use crate::actions;
+use crate::i18n::gettext;
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state::{self, Probe};
+use crate::ui::pages::run_action;
+use crate::ui::task_runner::{self, Command, CommandSequence, Restart};
+use crate::ui::utils::try_extract_widget;
+use gtk4::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::info;
+
+/// Set up all button handlers for the drivers tab.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    setup_tailscale(page_builder, window);
+    setup_asus_rog(page_builder, window);
+    setup_openrazer(page_builder, window);
+    setup_cooler_control(page_builder, window);
+    setup_zenergy(page_builder, window);
+    setup_nvidia_legacy(page_builder, window);
+    setup_rocm(page_builder, window);
+    setup_cuda(page_builder, window);
+}
+
+fn setup_tailscale(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_tailscale") else {
+        return;
+    };
+    // Its setup script only runs once this release pins it
+    if let Some(message) = actions::drivers::TAILSCALE_VPN.requirements_error() {
+        button.set_sensitive(false);
+        button.set_tooltip_text(Some(&message));
+        return;
+    }
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Tailscale VPN button clicked");
+        run_action(&window, &actions::drivers::TAILSCALE_VPN);
+    });
+}
+
+fn setup_asus_rog(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_asus_rog") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("ASUS ROG Tools button clicked");
+        run_action(&window, &actions::drivers::ASUS_ROG);
+    });
+}
+
+fn setup_openrazer(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_openrazer") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("OpenRazer Drivers button clicked");
+
+        // Show selection dialog for optional frontends
+        let window_clone = window.clone();
+        let config = SelectionDialogConfig::new(
+            &gettext("OpenRazer Drivers & Frontend"),
+            &gettext("OpenRazer drivers will be installed. Optionally select a frontend application for managing your Razer devices."),
+        )
+        .selection_type(SelectionType::Multi)
+        .selection_required(false)
+        .add_option(SelectionOption::new(
+            "polychromatic",
+            "Polychromatic",
+            &gettext("Graphical frontend for managing Razer devices (GTK-based)"),
+            install_state::is_installed(Probe::Package("polychromatic")),
+        ))
+        .add_option(SelectionOption::new(
+            "razergenie",
+            "RazerGenie",
+            &gettext("Graphical frontend for managing Razer devices (Qt-based)"),
+            install_state::is_installed(Probe::Package("razergenie")),
+        ))
+        .confirm_label(&gettext("Install"));
+
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let commands = build_openrazer_commands(&selected);
+            task_runner::run(
+                window_clone.upcast_ref(),
+                commands,
+                "Install OpenRazer Drivers (Reboot Required)",
+            );
+        });
+    });
+}
+
+fn setup_cooler_control(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_cooler_control") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Cooler Control button clicked");
+        run_action(&window, &actions::drivers::COOLER_CONTROL);
+    });
+}
+
+/// Build commands for OpenRazer installation.
+fn build_openrazer_commands(selected_frontends: &[String]) -> CommandSequence {
+    let user = crate::config::env::get().user.clone();
+    let mut commands = CommandSequence::new();
+
+    // Always install openrazer-meta-git
+    commands = commands.then(
+        Command::builder()
+            .aur()
+            .args(&["-S", "--noconfirm", "--needed", "openrazer-meta-git"])
+            .description("Installing OpenRazer drivers...")
+            .requires(Restart::Reboot)
+            .build(),
+    );
+
+    // Add user to plugdev group
+    commands = commands.then(
+        Command::builder()
+            .privileged()
+            .program("usermod")
+            .args(&["-aG", "plugdev", &user])
+            .description("Adding user to plugdev group...")
+            .requires(Restart::Relogin)
+            .build(),
+    );
+
+    // Optionally install selected frontends
+    if selected_frontends.iter().any(|s| s == "polychromatic") {
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "polychromatic"])
+                .description("Installing Polychromatic frontend...")
+                .build(),
+        );
+    }
+
+    if selected_frontends.iter().any(|s| s == "razergenie") {
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "razergenie"])
+                .description("Installing RazerGenie frontend...")
+                .build(),
+        );
+    }
+
+    commands
+}
+
+fn setup_zenergy(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_zenergy") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Zenergy Driver button clicked");
+        run_action(&window, &actions::drivers::ZENERGY);
+    });
+}
+
+fn setup_nvidia_legacy(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_nvidia_legacy") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Nvidia Legacy Drivers button clicked");
+
+        let window_clone = window.clone();
+        show_warning_confirmation(
+            window.upcast_ref(),
+            &gettext("Nvidia Legacy Drivers"),
+            &gettext("This is only intended for <span foreground=\"red\" weight=\"bold\">GTX900/1000</span> Series Legacy GPUs\n\
+            For <span foreground=\"cyan\" weight=\"bold\">RTX/Turing+</span> GPUs download the <span foreground=\"green\" weight=\"bold\">nVidia</span> ISO instead.\n\n\
+            <span foreground=\"red\" weight=\"bold\">No Support/Help</span> will be provided for those Legacy GPUs !"),
+            move || {
+                run_action(&window_clone, &actions::drivers::NVIDIA_LEGACY);
+            },
+        );
+    });
+}
+
+fn setup_rocm(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_rocm") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("AMD ROCm button clicked");
+        run_action(&window, &actions::drivers::ROCM);
+    });
+}
+
+fn setup_cuda(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_cuda") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("NVIDIA CUDA button clicked");
+
+        // Show selection dialog for CUDA version
+        let window_clone = window.clone();
+        let config = SelectionDialogConfig::new(
+            &gettext("NVIDIA CUDA Toolkit"),
+            &gettext("Select the CUDA version to install. The latest version is recommended for most users."),
+        )
+        .selection_type(SelectionType::Single)
+        .selection_required(true)
+        .add_option(SelectionOption::new(
+            "cuda",
+            &gettext("CUDA (Latest)"),
+            &gettext("Install the latest CUDA toolkit from official repositories"),
+            install_state::is_installed(Probe::Package("cuda")),
+        ))
+        .add_option(SelectionOption::new(
+            "cuda-12.9",
+            "CUDA 12.9",
+            &gettext("Install CUDA Toolkit version 12.9 specifically"),
+            install_state::is_installed(Probe::Package("cuda-12.9")),
+        ))
+        .confirm_label(&gettext("Install"));
+
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            if let Some(package) = selected.first() {
+                let description = format!("Installing {}...", package);
+                let commands = CommandSequence::new()
+                    .then(
+                        Command::builder()
+                            .aur()
+                            .args(&["-S", "--noconfirm", "--needed", package])
+                            .description(&description)
+                            .build(),
+                    )
+                    .build();
+
+                task_runner::run(window_clone.upcast_ref(), commands, "Install NVIDIA CUDA");
+            }
+        });
+    });
+}
@@ -0,0 +1,21 @@
+//! Drivers page with subtabs for driver installers and hybrid graphics.
+//!
+//! This module provides a unified page with two subtabs:
+//! - Drivers: GPU compute stacks, legacy NVIDIA drivers and hardware tools
+//! - Hybrid Graphics: GPU mode switching and PRIME offload on Optimus laptops
+
+pub mod drivers_tab;
+pub mod prime_tab;
+
+use gtk4::{ApplicationWindow, Builder};
+use log::info;
+
+/// Set up all handlers for the drivers page with subtabs.
+pub fn setup_handlers(page_builder: &Builder, main_builder: &Builder, window: &ApplicationWindow) {
+    info!("Setting up Drivers page with subtabs");
+
+    drivers_tab::setup_handlers(page_builder, main_builder, window);
+    prime_tab::setup_handlers(page_builder, main_builder, window);
+
+    info!("Drivers page handlers initialized");
+}
@@ -0,0 +1,569 @@
+//! Hybrid graphics tab.
+//!
+//! Handles:
+//! - Detecting Optimus setups and the installed switcher
+//! - Installing or removing envycontrol or supergfxctl
+//! - Switching between integrated, hybrid and dedicated modes, with a
+//!   reboot prompt and a rollback script that also works from a TTY
+//! - prime-run and the PRIME offload environment
+
+use crate::core;
+use crate::core::hardware::{self, GpuVendor};
+use crate::core::prime::{self, Mode, Switcher};
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{spawn_blocking, try_extract_widget};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// GPU and switcher state, read off the main thread.
+#[derive(Default)]
+struct State {
+    gpus: Vec<GpuVendor>,
+    switcher: Option<Switcher>,
+    mode: Option<Mode>,
+    supported: Vec<Mode>,
+    rollback: Option<(Switcher, Mode)>,
+    prime_run: bool,
+}
+
+impl State {
+    fn read() -> Self {
+        let switcher = prime::installed_switcher();
+        Self {
+            gpus: hardware::gpus(),
+            switcher,
+            mode: switcher.and_then(|s| s.current_mode()),
+            supported: switcher.map(|s| s.supported_modes()).unwrap_or_default(),
+            rollback: prime::rollback_target(),
+            prime_run: core::is_package_installed("nvidia-prime"),
+        }
+    }
+}
+
+struct PrimeTab {
+    window: ApplicationWindow,
+    gpus_row: adw::ActionRow,
+    switcher_row: adw::ActionRow,
+    install_button: Button,
+    remove_button: Button,
+    mode_group: adw::PreferencesGroup,
+    mode_row: adw::ComboRow,
+    rollback_row: adw::ActionRow,
+    rollback_button: Button,
+    offload_group: adw::PreferencesGroup,
+    offload_row: adw::ActionRow,
+    state: RefCell<State>,
+}
+
+/// Set up the hybrid graphics tab.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let rows = (
+        try_extract_widget::<adw::ActionRow>(page_builder, "prime_gpus_row"),
+        try_extract_widget::<adw::ActionRow>(page_builder, "prime_switcher_row"),
+        try_extract_widget::<adw::ComboRow>(page_builder, "prime_mode_row"),
+        try_extract_widget::<adw::ActionRow>(page_builder, "prime_rollback_row"),
+        try_extract_widget::<adw::ActionRow>(page_builder, "prime_offload_row"),
+    );
+    let buttons = (
+        try_extract_widget::<Button>(page_builder, "btn_prime_switcher_install"),
+        try_extract_widget::<Button>(page_builder, "btn_prime_switcher_remove"),
+        try_extract_widget::<Button>(page_builder, "btn_prime_apply"),
+        try_extract_widget::<Button>(page_builder, "btn_prime_rollback"),
+        try_extract_widget::<Button>(page_builder, "btn_prime_offload"),
+    );
+    let groups = (
+        try_extract_widget::<adw::PreferencesGroup>(page_builder, "prime_mode_group"),
+        try_extract_widget::<adw::PreferencesGroup>(page_builder, "prime_offload_group"),
+    );
+    let (
+        (Some(gpus_row), Some(switcher_row), Some(mode_row), Some(rollback_row), Some(offload_row)),
+        (
+            Some(install_button),
+            Some(remove_button),
+            Some(apply_button),
+            Some(rollback_button),
+            Some(offload_button),
+        ),
+        (Some(mode_group), Some(offload_group)),
+    ) = (rows, buttons, groups)
+    else {
+        return;
+    };
+
+    let tab = Rc::new(PrimeTab {
+        window: window.clone(),
+        gpus_row,
+        switcher_row,
+        install_button,
+        remove_button,
+        mode_group,
+        mode_row,
+        rollback_row,
+        rollback_button,
+        offload_group,
+        offload_row,
+        state: RefCell::new(State::default()),
+    });
+
+    let tab_clone = tab.clone();
+    tab.install_button.connect_clicked(move |_| {
+        info!("Install GPU switcher clicked");
+        install_switcher(&tab_clone);
+    });
+
+    let tab_clone = tab.clone();
+    tab.remove_button.connect_clicked(move |_| {
+        info!("Remove GPU switcher clicked");
+        remove_switcher(&tab_clone);
+    });
+
+    let tab_clone = tab.clone();
+    apply_button.connect_clicked(move |_| {
+        info!("Apply GPU mode clicked");
+        apply_mode(&tab_clone);
+    });
+
+    let tab_clone = tab.clone();
+    tab.rollback_button.connect_clicked(move |_| {
+        info!("Revert GPU mode clicked");
+        rollback(&tab_clone);
+    });
+
+    let tab_clone = tab.clone();
+    offload_button.connect_clicked(move |_| {
+        info!("PRIME offload setup clicked");
+        setup_offload(&tab_clone);
+    });
+
+    // The switcher and mode change when a task finishes
+    let tab_clone = tab.clone();
+    install_state::connect_refresh(move || load(&tab_clone));
+}
+
+/// Read the state off the main thread, then show it.
+fn load(tab: &Rc<PrimeTab>) {
+    let tab = tab.clone();
+    spawn_blocking(State::read, move |state| {
+        *tab.state.borrow_mut() = state;
+        render(&tab);
+    });
+}
+
+fn render(tab: &PrimeTab) {
+    let state = tab.state.borrow();
+    let hybrid = prime::is_hybrid(&state.gpus);
+
+    let names: Vec<&str> = state.gpus.iter().map(|g| g.name()).collect();
+    tab.gpus_row
+        .set_subtitle(&match (names.is_empty(), hybrid) {
+            (true, _) => gettext("No GPU found"),
+            (false, true) => format!("{} (Optimus)", names.join(" + ")),
+            (false, false) => gettext_f(
+                "{gpus}, not a hybrid setup",
+                &[("gpus", &names.join(" + "))],
+            ),
+        });
+
+    match state.switcher {
+        Some(switcher) => tab.switcher_row.set_subtitle(&gettext_f(
+            "{switcher} installed",
+            &[("switcher", switcher.name())],
+        )),
+        None => tab.switcher_row.set_subtitle(&gettext("None installed")),
+    }
+    tab.install_button.set_visible(state.switcher.is_none());
+    tab.install_button.set_sensitive(hybrid);
+    tab.remove_button.set_visible(state.switcher.is_some());
+
+    tab.mode_group.set_sensitive(state.switcher.is_some());
+    match state.mode {
+        Some(mode) => {
+            if let Some(index) = Mode::ALL.iter().position(|m| *m == mode) {
+                tab.mode_row.set_selected(index as u32);
+            }
+            tab.mode_row
+                .set_subtitle(&gettext_f("Currently {mode}", &[("mode", mode.name())]));
+        }
+        None => tab.mode_row.set_subtitle(""),
+    }
+
+    match state.rollback {
+        Some((_, mode)) => tab.rollback_row.set_subtitle(&gettext_f(
+            "Back to {mode} mode, then reboot",
+            &[("mode", mode.name())],
+        )),
+        None => tab.rollback_row.set_subtitle(&gettext("Nothing to undo")),
+    }
+    tab.rollback_button.set_sensitive(state.rollback.is_some());
+
+    tab.offload_group.set_sensitive(hybrid);
+    tab.offload_row.set_subtitle(&if state.prime_run {
+        gettext("prime-run installed")
+    } else {
+        gettext("Not set up")
+    });
+}
+
+// ─── Switcher ───────────────────────────────────────────────────────────────
+
+fn install_switcher(tab: &Rc<PrimeTab>) {
+    let mut config = SelectionDialogConfig::new(
+        &gettext("GPU Switcher"),
+        &gettext(
+            "Pick the tool that switches GPU modes. ASUS laptops are best served \
+             by supergfxctl, others by EnvyControl.",
+        ),
+    )
+    .selection_type(SelectionType::Single)
+    .selection_required(true)
+    .confirm_label(&gettext("Install"));
+    for switcher in Switcher::ALL {
+        config = config.add_option(SelectionOption::new(
+            switcher.id(),
+            switcher.name(),
+            &gettext(switcher.description()),
+            switcher.installed(),
+        ));
+    }
+
+    let window = tab.window.clone();
+    show_selection_dialog(tab.window.upcast_ref(), config, move |selected| {
+        let Some(switcher) = selected.first().and_then(|id| Switcher::from_id(id)) else {
+            return;
+        };
+
+        let mut commands = CommandSequence::new();
+        for other in Switcher::ALL {
+            if other != switcher && other.installed() {
+                commands = commands.append(removal_commands(other));
+            }
+        }
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", switcher.id()])
+                .description(&format!("Installing {}...", switcher.name()))
+                .build(),
+        );
+        if let Some(service) = switcher.service() {
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["enable", "--now", service])
+                    .description(&format!("Enabling {}...", service))
+                    .build(),
+            );
+        }
+
+        task_runner::run(window.upcast_ref(), commands.build(), "GPU Switcher Setup");
+    });
+}
+
+/// Undo the switcher's configuration, then remove it. The rollback script
+/// goes too, it calls the removed tool.
+fn removal_commands(switcher: Switcher) -> CommandSequence {
+    let mut commands = CommandSequence::new();
+    match switcher {
+        Switcher::EnvyControl => {
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("envycontrol")
+                    .args(&["--reset"])
+                    .description("Restoring the default GPU configuration...")
+                    .build(),
+            );
+        }
+        Switcher::Supergfxctl => {
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["disable", "--now", "supergfxd.service"])
+                    .description("Stopping supergfxd...")
+                    .build(),
+            );
+        }
+    }
+    commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-Rns", "--noconfirm", switcher.id()])
+                .description(&format!("Removing {}...", switcher.name()))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("rm")
+                .args(&["-f", prime::ROLLBACK_SCRIPT])
+                .description("Removing the rollback script...")
+                .build(),
+        )
+}
+
+fn remove_switcher(tab: &Rc<PrimeTab>) {
+    let Some(switcher) = tab.state.borrow().switcher else {
+        return;
+    };
+
+    let window = tab.window.clone();
+    show_warning_confirmation(
+        tab.window.upcast_ref(),
+        &gettext("Remove GPU Switcher"),
+        &gettext_f(
+            "<b>{switcher}</b> will be removed and the GPU configuration reset to the \
+             default hybrid mode after a reboot.",
+            &[("switcher", switcher.name())],
+        ),
+        move || {
+            task_runner::run(
+                window.upcast_ref(),
+                removal_commands(switcher).build(),
+                "GPU Switcher Removal",
+            );
+        },
+    );
+}
+
+// ─── Mode ───────────────────────────────────────────────────────────────────
+
+fn apply_mode(tab: &Rc<PrimeTab>) {
+    let state = tab.state.borrow();
+    let Some(switcher) = state.switcher else {
+        return;
+    };
+    let Some(target) = Mode::ALL.get(tab.mode_row.selected() as usize).copied() else {
+        return;
+    };
+
+    if state.mode == Some(target) {
+        show_error(
+            &tab.window,
+            &gettext_f(
+                "The GPU is already in {mode} mode.",
+                &[("mode", target.name())],
+            ),
+        );
+        return;
+    }
+    if !state.supported.contains(&target) {
+        show_error(
+            &tab.window,
+            &gettext_f(
+                "{switcher} doesn't support {mode} mode on this laptop.",
+                &[("switcher", switcher.name()), ("mode", target.name())],
+            ),
+        );
+        return;
+    }
+
+    show_switch_dialog(&tab.window, switcher, state.mode, target);
+}
+
+/// Explain the reboot and the way back, then switch now or with a reboot.
+fn show_switch_dialog(
+    window: &ApplicationWindow,
+    switcher: Switcher,
+    previous: Option<Mode>,
+    target: Mode,
+) {
+    let body = match previous {
+        Some(previous) => gettext_f(
+            "The switch takes effect after a reboot. If the desktop doesn't \
+             come up, press Ctrl+Alt+F3, log in and run\n\n\
+             sudo {script}\n\n\
+             to return to {mode} mode.",
+            &[
+                ("script", prime::ROLLBACK_SCRIPT),
+                ("mode", previous.name()),
+            ],
+        ),
+        None => gettext(
+            "The switch takes effect after a reboot. The current mode \
+             couldn't be read, so there's no automatic way back.",
+        ),
+    };
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext_f(
+            "Switch to {mode} Mode",
+            &[("mode", target.name())],
+        ))
+        .body(body)
+        .build();
+    dialog.add_responses(&[
+        ("cancel", gettext("Cancel").as_str()),
+        ("later", gettext("Reboot Later").as_str()),
+        ("reboot", gettext("Switch and Reboot").as_str()),
+    ]);
+    dialog.set_response_appearance("reboot", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("reboot"));
+    dialog.set_close_response("cancel");
+
+    let window_clone = window.clone();
+    dialog.connect_response(None, move |_, response| {
+        let reboot = match response {
+            "reboot" => true,
+            "later" => false,
+            _ => return,
+        };
+        task_runner::run(
+            window_clone.upcast_ref(),
+            switch_commands(switcher, previous, target, reboot),
+            "GPU Mode Switch",
+        );
+    });
+
+    dialog.present(Some(window));
+}
+
+fn switch_commands(
+    switcher: Switcher,
+    previous: Option<Mode>,
+    target: Mode,
+    reboot: bool,
+) -> CommandSequence {
+    let mut commands = CommandSequence::new();
+
+    if let Some(previous) = previous {
+        let script = prime::rollback_script(switcher, previous);
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    &format!(
+                        "cat > {path} << 'EOF'\n{script}EOF\nchmod 755 {path}",
+                        path = prime::ROLLBACK_SCRIPT,
+                        script = script
+                    ),
+                ])
+                .description("Saving the current mode for rollback...")
+                .build(),
+        );
+    }
+
+    let builder = if switcher.needs_root() {
+        Command::builder().privileged()
+    } else {
+        Command::builder().normal()
+    };
+    commands = commands.then(
+        builder
+            .program(switcher.id())
+            .args(&switcher.switch_args(target))
+            .description(&format!("Switching to {} mode...", target.name()))
+            .build(),
+    );
+
+    let last = if reboot {
+        Command::builder()
+            .normal()
+            .program("reboot")
+            .description("Rebooting system...")
+            .build()
+    } else {
+        Command::builder()
+            .normal()
+            .program("echo")
+            .args(&[&format!(
+                "Reboot to finish switching to {} mode.",
+                target.name()
+            )])
+            .description("GPU mode switched")
+            .build()
+    };
+    commands.then(last).build()
+}
+
+fn rollback(tab: &Rc<PrimeTab>) {
+    let Some((_, mode)) = tab.state.borrow().rollback else {
+        return;
+    };
+
+    let window = tab.window.clone();
+    show_warning_confirmation(
+        tab.window.upcast_ref(),
+        &gettext("Revert GPU Mode"),
+        &gettext_f(
+            "Switch back to <b>{mode}</b> mode? The system reboots right after.",
+            &[("mode", mode.name())],
+        ),
+        move || {
+            let commands = CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("sh")
+                        .args(&[prime::ROLLBACK_SCRIPT])
+                        .description("Restoring the previous GPU mode...")
+                        .build(),
+                )
+                .build();
+            task_runner::run(window.upcast_ref(), commands, "GPU Mode Rollback");
+        },
+    );
+}
+
+// ─── PRIME offload ──────────────────────────────────────────────────────────
+
+/// Writes `$1` to the file `$2`, creating its folder.
+const WRITE_FILE: &str = r#"mkdir -p "$(dirname "$2")" && printf '%s' "$1" > "$2""#;
+
+/// Install prime-run and save the offload variables for launchers that
+/// take an environment file.
+fn setup_offload(tab: &Rc<PrimeTab>) {
+    let path = prime::offload_env_path().to_string_lossy().to_string();
+    let env = prime::offload_env();
+
+    let mut commands = CommandSequence::new();
+    if !tab.state.borrow().prime_run {
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "nvidia-prime"])
+                .description("Installing prime-run...")
+                .build(),
+        );
+    }
+    commands = commands
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", WRITE_FILE, "sh", &env, &path])
+                .description("Saving the offload environment...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[&format!(
+                    "Start programs on the NVIDIA GPU with prime-run <program>. \
+                     In Steam, set a game's launch options to prime-run %command%. \
+                     Other launchers can load {}.",
+                    path
+                )])
+                .description("PRIME offload ready")
+                .build(),
+        );
+
+    task_runner::run(tab.window.upcast_ref(), commands.build(), "PRIME Offload");
+}
@@ -2,7 +2,7 @@
 //!
 //! Handles the logic for the Gamescope command generator.
 
-use crate::ui::utils::extract_widget;
+use crate::ui::utils::try_extract_widget;
 use adw::prelude::*;
 use adw::{ComboRow, EntryRow};
 use gtk4::{ApplicationWindow, Builder, Button, StringObject, Switch};
@@ -15,7 +15,11 @@ pub fn setup_handlers(
     _main_builder: &Builder,
     _window: &ApplicationWindow,
 ) {
-    let widgets = Rc::new(extract_all_widgets(page_builder));
+    // The generator needs every field, so a missing one skips the page
+    let Some(widgets) = extract_all_widgets(page_builder) else {
+        return;
+    };
+    let widgets = Rc::new(widgets);
 
     connect_widget_signals(&widgets);
     setup_copy_button(page_builder, &widgets);
@@ -25,47 +29,50 @@ pub fn setup_handlers(
 }
 
 /// Extract all widgets from the UI builder.
-fn extract_all_widgets(builder: &Builder) -> GamescopeWidgets {
-    GamescopeWidgets {
+fn extract_all_widgets(builder: &Builder) -> Option<GamescopeWidgets> {
+    Some(GamescopeWidgets {
         // Output (Visual)
-        entry_output_width: extract_widget(builder, "entry_output_width"),
-        entry_output_height: extract_widget(builder, "entry_output_height"),
-        entry_max_scale: extract_widget(builder, "entry_max_scale"),
+        entry_output_width: try_extract_widget(builder, "entry_output_width")?,
+        entry_output_height: try_extract_widget(builder, "entry_output_height")?,
+        entry_max_scale: try_extract_widget(builder, "entry_max_scale")?,
 
         // Nested (Game)
-        entry_nested_width: extract_widget(builder, "entry_nested_width"),
-        entry_nested_height: extract_widget(builder, "entry_nested_height"),
-        entry_nested_refresh: extract_widget(builder, "entry_nested_refresh"),
+        entry_nested_width: try_extract_widget(builder, "entry_nested_width")?,
+        entry_nested_height: try_extract_widget(builder, "entry_nested_height")?,
+        entry_nested_refresh: try_extract_widget(builder, "entry_nested_refresh")?,
 
         // Scaler / Filter
-        combo_scaler: extract_widget(builder, "combo_scaler"),
-        combo_filter: extract_widget(builder, "combo_filter"),
-        entry_fsr_sharpness: extract_widget(builder, "entry_fsr_sharpness"),
+        combo_scaler: try_extract_widget(builder, "combo_scaler")?,
+        combo_filter: try_extract_widget(builder, "combo_filter")?,
+        entry_fsr_sharpness: try_extract_widget(builder, "entry_fsr_sharpness")?,
 
         // Flags
-        check_fullscreen: extract_widget(builder, "check_fullscreen"),
-        check_grab: extract_widget(builder, "check_grab"),
-        check_force_grab_cursor: extract_widget(builder, "check_force_grab_cursor"),
-        check_adaptive_sync: extract_widget(builder, "check_adaptive_sync"),
-        check_immediate_flips: extract_widget(builder, "check_immediate_flips"),
-        check_expose_wayland: extract_widget(builder, "check_expose_wayland"),
-        check_force_windows_fullscreen: extract_widget(builder, "check_force_windows_fullscreen"),
+        check_fullscreen: try_extract_widget(builder, "check_fullscreen")?,
+        check_grab: try_extract_widget(builder, "check_grab")?,
+        check_force_grab_cursor: try_extract_widget(builder, "check_force_grab_cursor")?,
+        check_adaptive_sync: try_extract_widget(builder, "check_adaptive_sync")?,
+        check_immediate_flips: try_extract_widget(builder, "check_immediate_flips")?,
+        check_expose_wayland: try_extract_widget(builder, "check_expose_wayland")?,
+        check_force_windows_fullscreen: try_extract_widget(
+            builder,
+            "check_force_windows_fullscreen",
+        )?,
 
         // Backend / HDR / Misc
-        combo_backend: extract_widget(builder, "combo_backend"),
-        check_hdr_enabled: extract_widget(builder, "check_hdr_enabled"),
-        entry_cursor_path: extract_widget(builder, "entry_cursor_path"),
-        entry_framerate_limit: extract_widget(builder, "entry_framerate_limit"),
+        combo_backend: try_extract_widget(builder, "combo_backend")?,
+        check_hdr_enabled: try_extract_widget(builder, "check_hdr_enabled")?,
+        entry_cursor_path: try_extract_widget(builder, "entry_cursor_path")?,
+        entry_framerate_limit: try_extract_widget(builder, "entry_framerate_limit")?,
 
         // Debug & Extra
-        check_debug_layers: extract_widget(builder, "check_debug_layers"),
-        check_mangoapp: extract_widget(builder, "check_mangoapp"),
-        check_realtime: extract_widget(builder, "check_realtime"),
-        entry_extra_flags: extract_widget(builder, "entry_extra_flags"),
+        check_debug_layers: try_extract_widget(builder, "check_debug_layers")?,
+        check_mangoapp: try_extract_widget(builder, "check_mangoapp")?,
+        check_realtime: try_extract_widget(builder, "check_realtime")?,
+        entry_extra_flags: try_extract_widget(builder, "entry_extra_flags")?,
 
         // Output
-        text_command_output: extract_widget(builder, "text_command_output"),
-    }
+        text_command_output: try_extract_widget(builder, "text_command_output")?,
+    })
 }
 
 /// Connect all widget signals to regenerate the command on changes.
@@ -127,7 +134,9 @@ fn connect_combo_signal(widgets: &Rc<GamescopeWidgets>, combo: &ComboRow) {
 
 /// Set up the copy button to copy the command to clipboard.
 fn setup_copy_button(builder: &Builder, widgets: &Rc<GamescopeWidgets>) {
-    let btn_copy_command = extract_widget::<Button>(builder, "btn_copy_command");
+    let Some(btn_copy_command) = try_extract_widget::<Button>(builder, "btn_copy_command") else {
+        return;
+    };
     let text_output = widgets.text_command_output.clone();
     btn_copy_command.connect_clicked(move |_| {
         let text = text_output.text();
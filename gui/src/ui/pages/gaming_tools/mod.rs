@@ -0,0 +1,24 @@
+//! Gaming Tools page with subtabs for the tool installers and Proton-GE.
+//!
+//! This module provides a unified page with two subtabs:
+//! - Tools: Gaming suite, GPU, launcher, controller and Falcond installers
+//! - Proton-GE: Install and remove Proton-GE builds for Steam
+
+pub mod proton_tab;
+pub mod tools_tab;
+
+use gtk4::{ApplicationWindow, Builder};
+use log::info;
+
+/// Set up all handlers for the gaming tools page with subtabs.
+pub fn setup_handlers(page_builder: &Builder, main_builder: &Builder, window: &ApplicationWindow) {
+    info!("Setting up Gaming Tools page with subtabs");
+
+    // First, so picked buttons can be dropped before their handlers run
+    crate::ui::batch::setup(page_builder, window);
+
+    tools_tab::setup_handlers(page_builder, main_builder, window);
+    proton_tab::setup_handlers(page_builder, main_builder, window);
+
+    info!("Gaming Tools page handlers initialized");
+}
@@ -0,0 +1,305 @@
+//! Proton-GE version manager tab.
+//!
+//! Handles:
+//! - Listing recent GE-Proton releases from GitHub
+//! - Downloading, verifying and extracting releases for Steam
+//! - Removing installed builds, one at a time or all but the newest
+
+use crate::core::proton::{self, Release};
+use crate::i18n::{gettext, gettext_f, ngettext_f};
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{spawn_blocking, try_extract_widget};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button, Label, ListBox};
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Releases listed in the available group.
+const RELEASE_LIMIT: usize = 15;
+
+/// Release list from GitHub.
+enum Releases {
+    Loading,
+    Unavailable,
+    Loaded(Vec<Release>),
+}
+
+struct ProtonTab {
+    window: ApplicationWindow,
+    installed_list: ListBox,
+    available_list: ListBox,
+    count_label: Label,
+    refresh_button: Button,
+    remove_old_button: Button,
+    releases: RefCell<Releases>,
+}
+
+/// Set up the Proton-GE tab.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let widgets = (
+        try_extract_widget::<ListBox>(page_builder, "proton_installed_list"),
+        try_extract_widget::<ListBox>(page_builder, "proton_available_list"),
+        try_extract_widget::<Label>(page_builder, "proton_installed_count_label"),
+        try_extract_widget::<Button>(page_builder, "btn_refresh_proton"),
+        try_extract_widget::<Button>(page_builder, "btn_proton_remove_old"),
+    );
+    let (
+        Some(installed_list),
+        Some(available_list),
+        Some(count_label),
+        Some(refresh_button),
+        Some(remove_old_button),
+    ) = widgets
+    else {
+        return;
+    };
+
+    if let Some(row) = try_extract_widget::<adw::ActionRow>(page_builder, "proton_location_row") {
+        row.set_subtitle(&proton::compat_dir().display().to_string());
+    }
+
+    let tab = Rc::new(ProtonTab {
+        window: window.clone(),
+        installed_list,
+        available_list,
+        count_label,
+        refresh_button,
+        remove_old_button,
+        releases: RefCell::new(Releases::Loading),
+    });
+
+    let tab_clone = tab.clone();
+    tab.refresh_button.connect_clicked(move |_| {
+        info!("Refresh Proton-GE releases clicked");
+        fetch_releases(&tab_clone);
+    });
+
+    let tab_clone = tab.clone();
+    tab.remove_old_button.connect_clicked(move |_| {
+        remove_older(&tab_clone);
+    });
+
+    // Installed builds change when a task finishes; the release list doesn't
+    let tab_clone = tab.clone();
+    install_state::connect_refresh(move || render(&tab_clone));
+
+    fetch_releases(&tab);
+}
+
+/// Fetch the release list off the main thread, then show it.
+fn fetch_releases(tab: &Rc<ProtonTab>) {
+    *tab.releases.borrow_mut() = Releases::Loading;
+    tab.refresh_button.set_sensitive(false);
+    render(tab);
+
+    let tab = tab.clone();
+    spawn_blocking(
+        || proton::available(RELEASE_LIMIT),
+        move |releases| {
+            *tab.releases.borrow_mut() = match releases {
+                Some(releases) => Releases::Loaded(releases),
+                None => Releases::Unavailable,
+            };
+            tab.refresh_button.set_sensitive(true);
+            render(&tab);
+        },
+    );
+}
+
+/// Fill both lists from the installed builds and the fetched releases.
+fn render(tab: &Rc<ProtonTab>) {
+    let installed = proton::installed();
+    tab.count_label.set_text(&ngettext_f(
+        "{count} installed",
+        "{count} installed",
+        installed.len() as u32,
+        &[("count", &installed.len().to_string())],
+    ));
+    tab.remove_old_button.set_sensitive(installed.len() > 1);
+
+    tab.installed_list.remove_all();
+    for (i, name) in installed.iter().enumerate() {
+        let row = adw::ActionRow::builder().title(name).build();
+        if i == 0 {
+            row.set_subtitle(&gettext("Newest"));
+        }
+
+        let remove_button = Button::builder()
+            .icon_name("trash-symbolic")
+            .tooltip_text(gettext("Remove"))
+            .valign(gtk4::Align::Center)
+            .css_classes(vec!["flat".to_string(), "destructive-action".to_string()])
+            .build();
+        let tab_clone = tab.clone();
+        let name = name.clone();
+        remove_button.connect_clicked(move |_| remove(&tab_clone, &name));
+        row.add_suffix(&remove_button);
+
+        tab.installed_list.append(&row);
+    }
+    if installed.is_empty() {
+        tab.installed_list.append(&placeholder_row(&gettext(
+            "No Proton-GE versions installed",
+        )));
+    }
+
+    tab.available_list.remove_all();
+    match &*tab.releases.borrow() {
+        Releases::Loading => {
+            tab.available_list
+                .append(&placeholder_row(&gettext("Checking for releases...")));
+        }
+        Releases::Unavailable => {
+            tab.available_list
+                .append(&placeholder_row(&gettext("Couldn't reach GitHub")));
+        }
+        Releases::Loaded(releases) => {
+            for (i, release) in releases.iter().enumerate() {
+                tab.available_list
+                    .append(&release_row(tab, release, i == 0, &installed));
+            }
+        }
+    }
+}
+
+fn placeholder_row(text: &str) -> adw::ActionRow {
+    let row = adw::ActionRow::builder().title(text).build();
+    row.add_css_class("dim-label");
+    row
+}
+
+fn release_row(
+    tab: &Rc<ProtonTab>,
+    release: &Release,
+    latest: bool,
+    installed: &[String],
+) -> adw::ActionRow {
+    let row = adw::ActionRow::builder().title(&release.tag).build();
+    if latest {
+        row.set_subtitle(&gettext("Latest"));
+    }
+
+    if installed.contains(&release.tag) {
+        let label = Label::new(Some(&gettext("Installed")));
+        label.add_css_class("dim-label");
+        row.add_suffix(&label);
+    } else {
+        let install_button = Button::builder()
+            .icon_name("download-symbolic")
+            .tooltip_text(gettext("Install"))
+            .valign(gtk4::Align::Center)
+            .css_classes(vec!["flat".to_string(), "suggested-action".to_string()])
+            .build();
+        let tab_clone = tab.clone();
+        let release = release.clone();
+        install_button.connect_clicked(move |_| install(&tab_clone, &release));
+        row.add_suffix(&install_button);
+    }
+    row
+}
+
+/// Download, verify and extract a release.
+fn install(tab: &ProtonTab, release: &Release) {
+    info!("Installing {}", release.tag);
+    let dir = proton::download_dir();
+
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .download(
+                    &release.archive_url(),
+                    &dir.join(release.archive_name()).display().to_string(),
+                )
+                .description(&format!("Downloading {}...", release.tag))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .download(
+                    &release.checksum_url(),
+                    &dir.join(release.checksum_name()).display().to_string(),
+                )
+                .description("Downloading checksum...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", &proton::install_script(release)])
+                .description(&format!("Verifying and installing {}...", release.tag))
+                .build(),
+        )
+        .build();
+
+    task_runner::run(tab.window.upcast_ref(), commands, "Install Proton-GE");
+}
+
+/// Remove one installed build after confirmation.
+fn remove(tab: &Rc<ProtonTab>, name: &str) {
+    let name = name.to_string();
+    let tab_clone = tab.clone();
+    show_warning_confirmation(
+        tab.window.upcast_ref(),
+        &gettext("Remove Proton-GE"),
+        &gettext_f(
+            "Remove <b>{version}</b>?\n\nGames set to use it will fall back to Steam's default Proton.",
+            &[("version", &name)],
+        ),
+        move || run_removal(&tab_clone, &[name]),
+    );
+}
+
+/// Remove every installed build but the newest after confirmation.
+fn remove_older(tab: &Rc<ProtonTab>) {
+    let installed = proton::installed();
+    let Some((newest, older)) = installed.split_first() else {
+        return;
+    };
+    if older.is_empty() {
+        return;
+    }
+
+    let older = older.to_vec();
+    let tab_clone = tab.clone();
+    show_warning_confirmation(
+        tab.window.upcast_ref(),
+        &gettext("Remove Older Versions"),
+        &ngettext_f(
+            "Remove {count} older Proton-GE version and keep <b>{newest}</b>?\n\n\
+             Games set to use a removed version will fall back to Steam's default Proton.",
+            "Remove {count} older Proton-GE versions and keep <b>{newest}</b>?\n\n\
+             Games set to use a removed version will fall back to Steam's default Proton.",
+            older.len() as u32,
+            &[("count", &older.len().to_string()), ("newest", newest)],
+        ),
+        move || run_removal(&tab_clone, &older),
+    );
+}
+
+fn run_removal(tab: &ProtonTab, names: &[String]) {
+    let mut commands = CommandSequence::new();
+    for name in names {
+        let Some(path) = proton::installed_path(name) else {
+            continue;
+        };
+        info!("Removing {}", path.display());
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("rm")
+                .args(&["-rf", "--", &path.display().to_string()])
+                .description(&format!("Removing {}...", name))
+                .build(),
+        );
+    }
+
+    task_runner::run(
+        tab.window.upcast_ref(),
+        commands.build(),
+        "Remove Proton-GE",
+    );
+}
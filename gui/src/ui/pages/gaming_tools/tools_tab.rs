@@ -0,0 +1,735 @@
+//! Gaming tools tab button handlers.
+//!
+//! Handles:
+//! - Gaming suite installation (CachyOS meta or curated package list fallback)
+//! - LACT GPU overclocking
+//! - Game launchers (Bottles), from the AUR or Flathub
+//! - Controller drivers and udev rules, per controller family
+//! - Falcond gaming utility
+//! - GameMode setup and self-test
+//! - Game streaming (Sunshine host, Moonlight client)
+//! - Steam libraries on NTFS drives
+//! - vkBasalt with a sharpening config
+//! - Gamescope session for the display manager
+
+use crate::actions;
+use crate::config::user::AppSource;
+use crate::core::apps;
+use crate::core::drives::{self, Partition};
+use crate::i18n::{gettext, gettext_f, n_};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
+use crate::ui::install_state::{self, Probe};
+use crate::ui::pages::run_action;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{extract_action_widgets, spawn_blocking, try_extract_widget};
+use gtk4::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::info;
+
+/// Set up all button handlers for the tools tab.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    setup_gaming_meta(page_builder, window);
+    setup_lact_oc(page_builder, window);
+    setup_bottles(page_builder, window);
+    setup_controller(page_builder, window);
+    setup_falcond(page_builder, window);
+    setup_gamemode(page_builder, window);
+    setup_sunshine(page_builder, window);
+    setup_moonlight(page_builder, window);
+    setup_steam_library(page_builder, window);
+    setup_vkbasalt(page_builder, window);
+    setup_gamescope_session(page_builder, window);
+}
+
+fn setup_gaming_meta(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_gaming_meta") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Gaming Suite button clicked");
+        run_action(&window, &actions::gaming::GAMING_SUITE);
+    });
+}
+
+fn setup_lact_oc(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_lact_oc") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("LACT OC button clicked");
+        run_action(&window, &actions::gaming::LACT);
+    });
+}
+
+fn setup_bottles(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_bottles") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Bottles button clicked");
+
+        let config = SelectionDialogConfig::new(
+            &gettext("Bottles Installation"),
+            &gettext("Bottles runs Windows software in managed Wine prefixes."),
+        )
+        .selection_type(SelectionType::Multi)
+        .selection_required(false)
+        .add_option(
+            SelectionOption::new(
+                "flatpak",
+                &gettext("Use the Flatpak"),
+                &gettext(
+                    "Install Bottles from Flathub, with the gamescope and MangoHud \
+                     Vulkan layers, instead of the AUR",
+                ),
+                false,
+            )
+            .selected(apps::BOTTLES.preferred() == AppSource::Flatpak)
+            .in_group(&gettext("Source")),
+        )
+        .confirm_label(&gettext("Install"));
+
+        let window_clone = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected| {
+            let flatpak = selected.iter().any(|s| s == "flatpak");
+            task_runner::run(
+                window_clone.upcast_ref(),
+                bottles_commands(flatpak),
+                "Bottles Installation",
+            );
+        });
+    });
+}
+
+/// Bottles from the AUR, or from Flathub with its Vulkan layers.
+fn bottles_commands(flatpak: bool) -> CommandSequence {
+    if !flatpak {
+        return CommandSequence::new()
+            .then(apps::BOTTLES.install(AppSource::Native))
+            .build();
+    }
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&[
+                    "install",
+                    "-y",
+                    apps::BOTTLES.flatpak,
+                    "org.freedesktop.Platform.VulkanLayer.gamescope/x86_64/25.08",
+                    "org.freedesktop.Platform.VulkanLayer.MangoHud/x86_64/25.08",
+                ])
+                .description("Installing Bottles and Vulkan layers...")
+                .build(),
+        )
+        .build()
+}
+
+/// Driver support for one family of controllers.
+struct ControllerSupport {
+    id: &'static str,
+    label: &'static str,
+    description: &'static str,
+    /// AUR packages with the drivers, firmware and udev rules.
+    packages: &'static [&'static str],
+    /// USB vendor id, used to check the controller shows up afterwards.
+    vendor: &'static str,
+}
+
+const CONTROLLERS: &[ControllerSupport] = &[
+    ControllerSupport {
+        id: "xbox",
+        label: "Xbox",
+        description: n_("xone driver and dongle firmware for Xbox One and Series controllers"),
+        packages: &["xone-dkms-git", "xone-dongle-firmware"],
+        vendor: "045e",
+    },
+    ControllerSupport {
+        id: "dualsense",
+        label: "DualSense",
+        description: n_("dualsensectl for lightbar, battery and trigger settings, plus udev rules"),
+        packages: &["dualsensectl-git", "game-devices-udev"],
+        vendor: "054c",
+    },
+    ControllerSupport {
+        id: "8bitdo",
+        label: "8BitDo",
+        description: n_("udev rules so 8BitDo controllers work in Steam and games"),
+        packages: &["game-devices-udev"],
+        vendor: "2dc8",
+    },
+    ControllerSupport {
+        id: "switch",
+        label: "Switch Pro",
+        description: n_("joycond for Switch Pro controllers and Joy-Con pairs, plus udev rules"),
+        packages: &["joycond-git", "game-devices-udev"],
+        vendor: "057e",
+    },
+];
+
+/// Every package any controller option installs, for the uninstall button.
+const CONTROLLER_PACKAGES: &[&str] = &[
+    "xone-dkms-git",
+    "xone-dongle-firmware",
+    "dualsensectl-git",
+    "game-devices-udev",
+    "joycond-git",
+];
+
+impl ControllerSupport {
+    /// Whether all of this option's packages are installed. Blocks on pacman.
+    fn is_installed(&self) -> bool {
+        self.packages
+            .iter()
+            .all(|pkg| crate::core::is_package_installed(pkg))
+    }
+}
+
+fn setup_controller(builder: &Builder, window: &ApplicationWindow) {
+    let ids = ["btn_controller", "btn_controller_uninstall"];
+    let Some([btn_install, btn_uninstall]) = extract_action_widgets::<Button, 2>(builder, ids)
+    else {
+        return;
+    };
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |button| {
+        info!("Controller Tools button clicked");
+
+        // Package lookups run off the main thread; the button waits meanwhile
+        button.set_sensitive(false);
+        let button = button.clone();
+        let window = window_clone.clone();
+        spawn_blocking(installed_controllers, move |installed| {
+            button.set_sensitive(true);
+            show_controller_install_dialog(&window, installed);
+        });
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |button| {
+        info!("Controller Tools uninstall button clicked");
+
+        button.set_sensitive(false);
+        let button = button.clone();
+        let window = window_clone.clone();
+        spawn_blocking(installed_controllers, move |installed| {
+            button.set_sensitive(true);
+            show_controller_uninstall_dialog(&window, installed);
+        });
+    });
+
+    let btn_uninstall_clone = btn_uninstall.clone();
+    install_state::bind_widget(
+        &btn_uninstall,
+        Probe::AnyPackage(CONTROLLER_PACKAGES),
+        move |installed| btn_uninstall_clone.set_visible(installed),
+    );
+}
+
+/// Ids of the controller options that are fully installed.
+fn installed_controllers() -> Vec<&'static str> {
+    CONTROLLERS
+        .iter()
+        .filter(|c| c.is_installed())
+        .map(|c| c.id)
+        .collect()
+}
+
+fn selected_controllers(selected: &[String]) -> Vec<&'static ControllerSupport> {
+    CONTROLLERS
+        .iter()
+        .filter(|c| selected.iter().any(|s| s == c.id))
+        .collect()
+}
+
+fn show_controller_install_dialog(window: &ApplicationWindow, installed: Vec<&'static str>) {
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Controller Tools"),
+        &gettext(
+            "Choose the controllers you use. Only their drivers and udev rules are installed.",
+        ),
+    )
+    .selection_type(SelectionType::Multi)
+    .selection_required(true)
+    .confirm_label(&gettext("Install"));
+
+    for controller in CONTROLLERS {
+        config = config.add_option(SelectionOption::new(
+            controller.id,
+            controller.label,
+            &gettext(controller.description),
+            installed.contains(&controller.id),
+        ));
+    }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let controllers: Vec<_> = selected_controllers(&selected)
+            .into_iter()
+            .filter(|c| !installed.contains(&c.id))
+            .collect();
+        if controllers.is_empty() {
+            return;
+        }
+
+        let mut packages: Vec<&str> = controllers
+            .iter()
+            .flat_map(|c| c.packages.iter().copied())
+            .collect();
+        packages.sort_unstable();
+        packages.dedup();
+
+        let mut args = vec!["-S", "--noconfirm", "--needed"];
+        args.extend(packages);
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&args)
+                    .description("Installing controller drivers and udev rules...")
+                    .build(),
+            )
+            .then(reload_udev_command())
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("sh")
+                    .args(&["-c", &controller_detection_script(&controllers)])
+                    .description("Checking for connected controllers...")
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(
+            window_clone.upcast_ref(),
+            commands,
+            "Controller Tools Installation",
+        );
+    });
+}
+
+fn show_controller_uninstall_dialog(window: &ApplicationWindow, installed: Vec<&'static str>) {
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Remove Controller Support"),
+        &gettext("Choose the controllers to remove drivers and udev rules for."),
+    )
+    .selection_type(SelectionType::Multi)
+    .selection_required(true)
+    .confirm_label(&gettext("Remove"));
+
+    for controller in CONTROLLERS.iter().filter(|c| installed.contains(&c.id)) {
+        config = config.add_option(SelectionOption::new(
+            controller.id,
+            controller.label,
+            &gettext(controller.description),
+            false,
+        ));
+    }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let removed = selected_controllers(&selected);
+
+        // Packages shared with a controller that stays are kept
+        let kept: Vec<&str> = CONTROLLERS
+            .iter()
+            .filter(|c| installed.contains(&c.id) && !selected.iter().any(|s| s == c.id))
+            .flat_map(|c| c.packages.iter().copied())
+            .collect();
+        let mut packages: Vec<&str> = removed
+            .iter()
+            .flat_map(|c| c.packages.iter().copied())
+            .filter(|pkg| !kept.contains(pkg))
+            .collect();
+        packages.sort_unstable();
+        packages.dedup();
+        if packages.is_empty() {
+            return;
+        }
+
+        let mut args = vec!["-Rns", "--noconfirm"];
+        args.extend(packages);
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&args)
+                    .description("Removing controller drivers and udev rules...")
+                    .build(),
+            )
+            .then(reload_udev_command())
+            .build();
+
+        task_runner::run(
+            window_clone.upcast_ref(),
+            commands,
+            "Controller Tools Uninstall",
+        );
+    });
+}
+
+fn reload_udev_command() -> Command {
+    Command::builder()
+        .privileged()
+        .program("sh")
+        .args(&["-c", "udevadm control --reload-rules && udevadm trigger"])
+        .description("Reloading udev rules...")
+        .build()
+}
+
+/// Script reporting which of `controllers` the kernel sees as input devices,
+/// over USB or Bluetooth. A controller that isn't plugged in isn't an error.
+fn controller_detection_script(controllers: &[&ControllerSupport]) -> String {
+    controllers
+        .iter()
+        .map(|c| {
+            format!(
+                "if grep -qi 'Vendor={vendor}' /proc/bus/input/devices; \
+                 then echo '{label}: detected'; \
+                 else echo '{label}: not connected, plug it in or pair it to check'; fi",
+                vendor = c.vendor,
+                label = c.label
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn setup_falcond(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_falcond") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Falcond button clicked");
+        run_action(&window, &actions::gaming::FALCOND);
+    });
+}
+
+fn setup_gamemode(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_gamemode") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("GameMode button clicked");
+        run_action(&window, &actions::gaming::GAMEMODE);
+    });
+}
+
+fn setup_sunshine(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_sunshine") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Sunshine button clicked");
+        run_action(&window, &actions::gaming::SUNSHINE);
+    });
+}
+
+fn setup_moonlight(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_moonlight") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Moonlight button clicked");
+        run_action(&window, &actions::gaming::MOONLIGHT);
+    });
+}
+
+fn setup_steam_library(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_steam_library") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |button| {
+        info!("Steam Library Drive button clicked");
+
+        // lsblk runs off the main thread; the button waits meanwhile
+        button.set_sensitive(false);
+        let button = button.clone();
+        let window = window.clone();
+        spawn_blocking(drives::ntfs_partitions, move |partitions| {
+            button.set_sensitive(true);
+            show_steam_library_dialog(&window, partitions);
+        });
+    });
+}
+
+fn show_steam_library_dialog(window: &ApplicationWindow, partitions: Vec<Partition>) {
+    if partitions.is_empty() {
+        show_error(
+            window,
+            &gettext(
+                "No NTFS drives found. Connect the drive that holds your Windows Steam library and try again.",
+            ),
+        );
+        return;
+    }
+
+    let mut config = SelectionDialogConfig::new(
+        &gettext("Steam Library Drive"),
+        &gettext(
+            "Mount an NTFS drive on boot with the ntfs3 driver and set it up for Proton. \
+             Proton prefixes are kept on your Linux drive, since Windows filesystems can't hold them.",
+        ),
+    )
+    .selection_type(SelectionType::Single)
+    .selection_required(true)
+    .confirm_label(&gettext("Set Up"));
+
+    for partition in &partitions {
+        let description = gettext_f(
+            "{device} · {size} · mounts at {dir}",
+            &[
+                ("device", &partition.device),
+                ("size", &partition.size),
+                ("dir", &partition.target_dir().display().to_string()),
+            ],
+        );
+        config = config.add_option(SelectionOption::new(
+            &partition.uuid,
+            partition.display_name(),
+            &description,
+            drives::in_fstab(&partition.uuid),
+        ));
+    }
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected| {
+        let Some(partition) = selected
+            .first()
+            .and_then(|uuid| partitions.iter().find(|p| &p.uuid == uuid))
+        else {
+            return;
+        };
+        task_runner::run(
+            window_clone.upcast_ref(),
+            steam_library_commands(partition).build(),
+            "Steam Library Drive",
+        );
+    });
+}
+
+/// Finds the Steam library under the mount point `$1` in Steam's
+/// libraryfolders.vdf, or picks `$1/SteamLibrary` for a new one, and points
+/// its `compatdata` at the home drive, where Proton can create its prefixes.
+/// Prefixes already in the home drive are kept aside instead of overwritten.
+const STEAM_LIBRARY_SCRIPT: &str = r#"set -e
+mount="$1"
+home_compat="$HOME/.local/share/Steam/steamapps/compatdata"
+library=$(cat "$HOME/.local/share/Steam/steamapps/libraryfolders.vdf" \
+    "$HOME/.local/share/Steam/config/libraryfolders.vdf" 2>/dev/null \
+    | sed -n 's/^[[:space:]]*"path"[[:space:]]*"\(.*\)"[[:space:]]*$/\1/p' \
+    | while IFS= read -r path; do
+        case "$path" in
+            "$mount" | "$mount"/*) echo "$path"; break ;;
+        esac
+    done)
+known=${library:+yes}
+library=${library:-"$mount/SteamLibrary"}
+lib="$library/steamapps"
+mkdir -p "$lib" "$home_compat"
+if [ -d "$lib/compatdata" ] && [ ! -L "$lib/compatdata" ]; then
+    for prefix in "$lib/compatdata"/*; do
+        [ -e "$prefix" ] || continue
+        [ -e "$home_compat/${prefix##*/}" ] || mv "$prefix" "$home_compat/"
+    done
+    if ! rmdir "$lib/compatdata" 2>/dev/null; then
+        kept="$lib/compatdata.$(date +%Y%m%d%H%M%S)"
+        mv "$lib/compatdata" "$kept"
+        echo "Prefixes that already exist on the home drive were left in $kept"
+    fi
+fi
+ln -sfn "$home_compat" "$lib/compatdata"
+if [ -z "$known" ]; then
+    echo "Add $library in Steam under Settings > Storage."
+fi
+"#;
+
+/// Mount `partition` on boot and set up its Steam library.
+fn steam_library_commands(partition: &Partition) -> CommandSequence {
+    let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+    let mount = partition.target_dir().display().to_string();
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "ntfs-3g"])
+                .description("Installing NTFS tools...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", &partition.ntfs_mount_script(uid, gid)])
+                .description(&format!(
+                    "Mounting {} at {}...",
+                    partition.display_name(),
+                    mount
+                ))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", STEAM_LIBRARY_SCRIPT, "sh", &mount])
+                .description("Linking Proton prefixes to your home drive...")
+                .build(),
+        )
+}
+
+fn setup_vkbasalt(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_vkbasalt") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("vkBasalt button clicked");
+        run_action(&window, &actions::gaming::VKBASALT);
+    });
+}
+
+/// Launcher for the gamescope session. Reads the user's settings snippet,
+/// then runs Steam's Big Picture mode inside gamescope on the bare display.
+const GAMESCOPE_SESSION_SCRIPT: &str = r#"#!/bin/sh
+# Installed by xero-toolkit
+conf="$HOME/.config/gamescope-session.conf"
+[ -f "$conf" ] && . "$conf"
+set -- -e -f -W "${WIDTH:-1920}" -H "${HEIGHT:-1080}" -r "${REFRESH:-60}"
+[ "${VRR:-0}" = 1 ] && set -- "$@" --adaptive-sync
+exec gamescope "$@" $EXTRA_ARGS -- steam -gamepadui -steamos3
+"#;
+
+/// Session entry SDDM lists among the Wayland sessions.
+const GAMESCOPE_SESSION_DESKTOP: &str = "[Desktop Entry]
+Name=Steam (Gamescope)
+Comment=Steam Big Picture in a gamescope session
+Exec=/usr/local/bin/gamescope-session-steam
+Type=Application
+DesktopNames=gamescope
+";
+
+fn setup_gamescope_session(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_gamescope_session") else {
+        return;
+    };
+    let window = window.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Gamescope Session button clicked");
+
+        let (width, height, refresh) = current_mode(&window);
+        let settings = format!(
+            "# Gamescope session settings, read at login\n\
+             WIDTH={}\nHEIGHT={}\nREFRESH={}\n\
+             # 1 to enable variable refresh rate\nVRR=0\n\
+             # Any other gamescope flags, e.g. --hdr-enabled\nEXTRA_ARGS=\n",
+            width, height, refresh
+        );
+        let settings_script = format!(
+            "conf=\"$HOME/.config/gamescope-session.conf\"; \
+             if [ -f \"$conf\" ]; then echo \"Keeping existing $conf\"; \
+             else mkdir -p \"$(dirname \"$conf\")\" && cat > \"$conf\" << 'EOF'\n{}EOF\n\
+             echo \"Wrote $conf, edit it to change resolution, refresh rate or VRR\"; fi",
+            settings
+        );
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&["-S", "--noconfirm", "--needed", "gamescope", "steam"])
+                    .description("Installing gamescope and Steam...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        &format!(
+                            "cat > /usr/local/bin/gamescope-session-steam << 'EOF'\n{}EOF\n\
+                             chmod 755 /usr/local/bin/gamescope-session-steam",
+                            GAMESCOPE_SESSION_SCRIPT
+                        ),
+                    ])
+                    .description("Installing gamescope session launcher...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        &format!(
+                            "mkdir -p /usr/share/wayland-sessions && \
+                             cat > /usr/share/wayland-sessions/gamescope-steam.desktop << 'EOF'\n{}EOF",
+                            GAMESCOPE_SESSION_DESKTOP
+                        ),
+                    ])
+                    .description("Adding Steam (Gamescope) to the login screen...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("sh")
+                    .args(&["-c", &settings_script])
+                    .description("Writing gamescope session settings...")
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window.upcast_ref(), commands, "Gamescope Session Setup");
+    });
+}
+
+/// Resolution and refresh rate of the monitor showing `window`, to prefill
+/// the session settings. Falls back to 1080p60.
+fn current_mode(window: &ApplicationWindow) -> (i32, i32, i32) {
+    let monitor = window.surface().and_then(|surface| {
+        gtk4::gdk::Display::default().and_then(|d| d.monitor_at_surface(&surface))
+    });
+    let Some(monitor) = monitor else {
+        return (1920, 1080, 60);
+    };
+
+    let geometry = monitor.geometry();
+    let scale = monitor.scale();
+    (
+        (geometry.width() as f64 * scale).round() as i32,
+        (geometry.height() as f64 * scale).round() as i32,
+        (monitor.refresh_rate() as f64 / 1000.0).round() as i32,
+    )
+}
@@ -4,10 +4,17 @@
 //! - Linux kernel installation and removal
 //! - Kernel headers management
 //! - Kernel listing and status
+//! - NVIDIA module check before kernels are installed or removed
+//! - CPU microcode check and fix
 
+use crate::actions;
+use crate::core::microcode::{self, MicrocodeStatus};
+use crate::core::nvidia::{self, Advice};
+use crate::i18n::{gettext, gettext_f, ngettext_f};
 use crate::ui::dialogs::warning::show_warning_confirmation;
-use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
+use crate::ui::pages::run_action;
+use crate::ui::task_runner::{self, Command, CommandSequence, Restart};
+use crate::ui::utils::{extract_widget, spawn_blocking};
 use gtk4::glib;
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, Box as GtkBox, Builder, Button, Image, Label, ListBox, Orientation};
@@ -18,6 +25,63 @@ use std::process::{Command as StdCommand, Stdio};
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     setup_kernel_lists(page_builder, window);
     setup_refresh_button(page_builder, window);
+    setup_microcode(page_builder, window);
+}
+
+/// Set up the microcode status row and its fix button.
+fn setup_microcode(builder: &Builder, window: &ApplicationWindow) {
+    let button = extract_widget::<Button>(builder, "btn_fix_microcode");
+    let window = window.clone();
+    let builder_clone = builder.clone();
+
+    button.connect_clicked(move |_| {
+        info!("Fix microcode button clicked");
+
+        run_action(&window, &actions::kernel::FIX_MICROCODE);
+
+        let builder_refresh = builder_clone.clone();
+        glib::timeout_add_seconds_local(2, move || {
+            if !task_runner::is_running() {
+                refresh_microcode_status(&builder_refresh);
+                glib::ControlFlow::Break
+            } else {
+                glib::ControlFlow::Continue
+            }
+        });
+    });
+
+    refresh_microcode_status(builder);
+}
+
+/// Check microcode state off the main thread and update the status row.
+fn refresh_microcode_status(builder: &Builder) {
+    let label = extract_widget::<Label>(builder, "microcode_status_label");
+    let button = extract_widget::<Button>(builder, "btn_fix_microcode");
+
+    let (tx, rx) = async_channel::bounded::<MicrocodeStatus>(1);
+    std::thread::spawn(move || {
+        let _ = tx.send_blocking(microcode::check());
+    });
+
+    glib::MainContext::default().spawn_local(async move {
+        let Ok(status) = rx.recv().await else {
+            return;
+        };
+
+        let text = match status.vendor {
+            None => gettext("Unknown CPU vendor"),
+            Some(v) if !status.installed => {
+                gettext_f("{package} missing", &[("package", v.package())])
+            }
+            Some(v) if !status.loaded => {
+                gettext_f("{package} not loaded at boot", &[("package", v.package())])
+            }
+            Some(v) => gettext_f("{package} loaded", &[("package", v.package())]),
+        };
+
+        label.set_text(&text);
+        button.set_visible(status.needs_fix());
+    });
 }
 
 /// Initialize and populate kernel lists.
@@ -311,7 +375,7 @@ fn populate_installed_list(builder: &Builder, kernels: &[String], window: &Appli
     }
 
     if kernels.is_empty() {
-        let label = Label::new(Some("No kernels installed"));
+        let label = Label::new(Some(&gettext("No kernels installed")));
         label.add_css_class("dim-label");
         label.set_margin_start(12);
         label.set_margin_end(12);
@@ -370,7 +434,7 @@ fn populate_available_list(
     }
 
     if added == 0 {
-        let label = Label::new(Some("All available kernels are installed"));
+        let label = Label::new(Some(&gettext("All available kernels are installed")));
         label.add_css_class("dim-label");
         label.set_margin_start(12);
         label.set_margin_end(12);
@@ -385,98 +449,191 @@ fn update_status_labels(builder: &Builder, available: &[String], installed: &[St
     let installed_count = extract_widget::<Label>(builder, "installed_count_label");
     let available_count = extract_widget::<Label>(builder, "available_count_label");
 
-    installed_count.set_text(&format!("{} installed", installed.len()));
-
-    let not_installed = available.iter().filter(|k| !installed.contains(k)).count();
-    available_count.set_text(&format!("{} available", not_installed));
+    let count = installed.len() as u32;
+    installed_count.set_text(&ngettext_f(
+        "{count} installed",
+        "{count} installed",
+        count,
+        &[("count", &count.to_string())],
+    ));
+
+    let not_installed = available.iter().filter(|k| !installed.contains(k)).count() as u32;
+    available_count.set_text(&ngettext_f(
+        "{count} available",
+        "{count} available",
+        not_installed,
+        &[("count", &not_installed.to_string())],
+    ));
 }
 
-/// Install a kernel with its headers.
+/// Install a kernel with its headers, and the NVIDIA module it needs.
 fn install_kernel(kernel_name: &str, window: &ApplicationWindow, builder: &Builder) {
-    let headers = format!("{}-headers", kernel_name);
     let kernel_name = kernel_name.to_string();
+    let window = window.clone();
+    let builder = builder.clone();
+    let lookup = kernel_name.clone();
+    // The NVIDIA module check asks pacman, off the main thread
+    spawn_blocking(
+        move || nvidia::for_install(&lookup),
+        move |advice| confirm_install(kernel_name, advice, &window, &builder),
+    );
+}
+
+fn confirm_install(
+    kernel_name: String,
+    advice: Advice,
+    window: &ApplicationWindow,
+    builder: &Builder,
+) {
+    let headers = format!("{}-headers", kernel_name);
+    let mut message = gettext_f(
+        "Install <b>{kernel}</b> and <b>{headers}</b>?\n\n\
+        This will download and install the kernel and its headers.",
+        &[("kernel", &kernel_name), ("headers", &headers)],
+    );
+    let mut packages = vec![kernel_name.clone(), headers];
+    let mut commands = CommandSequence::new();
+    match advice {
+        Advice::Add(module) => {
+            message.push_str("\n\n");
+            message.push_str(&gettext_f(
+                "Your NVIDIA driver is prebuilt for each kernel, so \
+                <b>{module}</b> is installed with it.",
+                &[("module", &module)],
+            ));
+            packages.push(module);
+        }
+        Advice::SwitchToDkms { remove, install } => {
+            message.push_str("\n\n");
+            message.push_str(&gettext_f(
+                "<span foreground=\"red\" weight=\"bold\">NVIDIA:</span> \
+                Your driver is prebuilt for specific kernels, and there is none \
+                for {kernel}. Booting it would end at a black screen, so <b>{remove}</b> \
+                will be replaced with <b>{install}</b>, built for every kernel.",
+                &[
+                    ("kernel", &kernel_name),
+                    ("remove", &remove.join(", ")),
+                    ("install", install),
+                ],
+            ));
+            let mut args = vec!["-Rdd", "--noconfirm"];
+            args.extend(remove.iter().map(String::as_str));
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&args)
+                    .description("Removing prebuilt NVIDIA modules...")
+                    .build(),
+            );
+            packages.push(install.to_string());
+        }
+        Advice::Nothing | Advice::Remove(_) => {}
+    }
+
     let window_clone = window.clone();
     let builder_clone = builder.clone();
-
     show_warning_confirmation(
         window.upcast_ref(),
-        "Confirm Installation",
-        &format!(
-            "Install <b>{}</b> and <b>{}</b>?\n\n\
-            This will download and install the kernel and its headers.",
-            kernel_name, headers
-        ),
+        &gettext("Confirm Installation"),
+        &message,
         move || {
-            info!("Installing {} and {}", kernel_name, headers);
+            info!("Installing {}", packages.join(", "));
 
-            let commands = CommandSequence::new()
+            let mut args = vec!["-S", "--noconfirm", "--needed"];
+            args.extend(packages.iter().map(String::as_str));
+            let commands = commands
                 .then(
                     Command::builder()
                         .aur()
-                        .args(&["-S", "--noconfirm", "--needed", &kernel_name, &headers])
-                        .description(&format!("Installing {} and {}...", kernel_name, headers))
+                        .args(&args)
+                        .description(&format!("Installing {}...", packages.join(", ")))
+                        .requires(Restart::Reboot)
                         .build(),
                 )
                 .build();
 
             // Run installation
             task_runner::run(window_clone.upcast_ref(), commands, "Install Kernel");
-
-            // Schedule refresh after dialog closes
-            glib::timeout_add_seconds_local(2, move || {
-                if !task_runner::is_running() {
-                    scan_and_populate_kernels(&builder_clone, &window_clone, None);
-                    glib::ControlFlow::Break
-                } else {
-                    glib::ControlFlow::Continue
-                }
-            });
+            refresh_when_done(&builder_clone, &window_clone);
         },
     );
 }
 
-/// Remove a kernel with its headers.
+/// Remove a kernel with its headers, and its prebuilt NVIDIA module.
 fn remove_kernel(kernel_name: &str, window: &ApplicationWindow, builder: &Builder) {
-    let headers = format!("{}-headers", kernel_name);
     let kernel_name = kernel_name.to_string();
+    let window = window.clone();
+    let builder = builder.clone();
+    let lookup = kernel_name.clone();
+    spawn_blocking(
+        move || nvidia::for_removal(&lookup),
+        move |advice| confirm_removal(kernel_name, advice, &window, &builder),
+    );
+}
+
+fn confirm_removal(
+    kernel_name: String,
+    advice: Advice,
+    window: &ApplicationWindow,
+    builder: &Builder,
+) {
+    let headers = format!("{}-headers", kernel_name);
+    let mut message = gettext_f(
+        "Remove <b>{kernel}</b> and <b>{headers}</b>?\n\n\
+        <span foreground=\"red\" weight=\"bold\">Warning:</span> \
+        This will uninstall the kernel and its headers.\n\
+        Make sure you have at least one other kernel installed.",
+        &[("kernel", &kernel_name), ("headers", &headers)],
+    );
+    let mut packages = vec![kernel_name, headers];
+    if let Advice::Remove(module) = advice {
+        message.push_str("\n\n");
+        message.push_str(&gettext_f(
+            "Its NVIDIA module, <b>{module}</b>, is removed with it.",
+            &[("module", &module)],
+        ));
+        packages.push(module);
+    }
+
     let window_clone = window.clone();
     let builder_clone = builder.clone();
-
     show_warning_confirmation(
         window.upcast_ref(),
-        "Confirm Removal",
-        &format!(
-            "Remove <b>{}</b> and <b>{}</b>?\n\n\
-            <span foreground=\"red\" weight=\"bold\">Warning:</span> \
-            This will uninstall the kernel and its headers.\n\
-            Make sure you have at least one other kernel installed.",
-            kernel_name, headers
-        ),
+        &gettext("Confirm Removal"),
+        &message,
         move || {
-            info!("Removing {} and {}", kernel_name, headers);
+            info!("Removing {}", packages.join(", "));
 
+            let mut args = vec!["-R", "--noconfirm"];
+            args.extend(packages.iter().map(String::as_str));
             let commands = CommandSequence::new()
                 .then(
                     Command::builder()
                         .aur()
-                        .args(&["-R", "--noconfirm", &kernel_name, &headers])
-                        .description(&format!("Removing {} and {}...", kernel_name, headers))
+                        .args(&args)
+                        .description(&format!("Removing {}...", packages.join(", ")))
                         .build(),
                 )
                 .build();
 
             // Run removal
             task_runner::run(window_clone.upcast_ref(), commands, "Remove Kernel");
-
-            // Schedule refresh after dialog closes
-            glib::timeout_add_seconds_local(2, move || {
-                if !task_runner::is_running() {
-                    scan_and_populate_kernels(&builder_clone, &window_clone, None);
-                    glib::ControlFlow::Break
-                } else {
-                    glib::ControlFlow::Continue
-                }
-            });
+            refresh_when_done(&builder_clone, &window_clone);
         },
     );
 }
+
+/// Rescan the kernels once the task runner dialog closes.
+fn refresh_when_done(builder: &Builder, window: &ApplicationWindow) {
+    let builder = builder.clone();
+    let window = window.clone();
+    glib::timeout_add_seconds_local(2, move || {
+        if !task_runner::is_running() {
+            scan_and_populate_kernels(&builder, &window, None);
+            glib::ControlFlow::Break
+        } else {
+            glib::ControlFlow::Continue
+        }
+    });
+}
@@ -2,6 +2,7 @@
 //!
 //! Manages sched-ext BPF CPU schedulers via scxctl.
 
+use crate::i18n::{gettext, gettext_f, n_};
 use crate::ui::dialogs::warning::show_warning_confirmation;
 use crate::ui::task_runner::{self, Command, CommandSequence};
 use crate::ui::utils::{
@@ -47,7 +48,7 @@ pub fn setup_handlers(builder: &Builder, _main_builder: &Builder, window: &Appli
 }
 
 fn init_kernel_support(builder: &Builder, state: &Rc<RefCell<State>>) {
-    let version = run_command("uname", &["-r"]).unwrap_or_else(|| "Unknown".to_string());
+    let version = run_command("uname", &["-r"]).unwrap_or_else(|| gettext("Unknown"));
     let supported = path_exists(SCHED_EXT_PATH);
 
     state.borrow_mut().kernel_supported = supported;
@@ -63,15 +64,18 @@ fn init_kernel_support(builder: &Builder, state: &Rc<RefCell<State>>) {
     } else {
         icon.set_icon_name(Some("circle-xmark"));
         icon.add_css_class("error");
-        label.set_text(&format!("{} (no sched-ext)", version));
+        label.set_text(&gettext_f(
+            "{version} (no sched-ext)",
+            &[("version", &version)],
+        ));
         label.add_css_class("warning");
     }
 
     // Hidden label for compatibility
-    extract_widget::<Label>(builder, "kernel_support_label").set_text(if supported {
-        "Supported"
+    extract_widget::<Label>(builder, "kernel_support_label").set_text(&if supported {
+        gettext("Supported")
     } else {
-        "Not supported"
+        gettext("Not supported")
     });
 }
 
@@ -145,9 +149,9 @@ fn setup_buttons(builder: &Builder, window: &ApplicationWindow, state: &Rc<RefCe
             w.upcast_ref(),
             commands,
             if cmd == "switch" {
-                "Switch Scheduler"
+                n_("Switch Scheduler")
             } else {
-                "Start Scheduler"
+                n_("Start Scheduler")
             },
         );
     });
@@ -158,8 +162,8 @@ fn setup_buttons(builder: &Builder, window: &ApplicationWindow, state: &Rc<RefCe
         let wc = w.clone();
         show_warning_confirmation(
             w.upcast_ref(),
-            "Stop Scheduler",
-            "Stop the current scheduler and fall back to EEVDF?",
+            &gettext("Stop Scheduler"),
+            &gettext("Stop the current scheduler and fall back to EEVDF?"),
             move || {
                 task_runner::run(
                     wc.upcast_ref(),
@@ -453,7 +457,7 @@ fn update_status_labels(builder: &Builder, is_active: bool, name: &str, mode: &s
         active_label.remove_css_class("dim-label");
         active_label.add_css_class("accent");
     } else {
-        active_label.set_text("EEVDF (Default)");
+        active_label.set_text(&gettext("EEVDF (Default)"));
         active_label.remove_css_class("accent");
         active_label.add_css_class("dim-label");
     }
@@ -495,7 +499,7 @@ fn get_status() -> (bool, String, String) {
                         .nth(1)
                         .and_then(|s| s.split(" mode").next())
                         .map(|s| s.trim().to_string())
-                        .unwrap_or_else(|| "N/A".to_string());
+                        .unwrap_or_else(|| gettext("N/A"));
                     return (true, name, mode);
                 }
             }
@@ -519,11 +523,17 @@ fn show_scheduler_selector(
 
     // Categories
     let categories = vec![
-        ("Gaming", vec!["scx_rusty", "scx_lavd", "scx_bpfland"]),
-        ("Desktop", vec!["scx_cosmos", "scx_flash"]),
-        ("Servers", vec!["scx_layered", "scx_flatcg", "scx_tickless"]),
-        ("Low Latency", vec!["scx_nest"]),
-        ("Testing", vec!["scx_simple", "scx_chaos", "scx_userland"]),
+        (n_("Gaming"), vec!["scx_rusty", "scx_lavd", "scx_bpfland"]),
+        (n_("Desktop"), vec!["scx_cosmos", "scx_flash"]),
+        (
+            n_("Servers"),
+            vec!["scx_layered", "scx_flatcg", "scx_tickless"],
+        ),
+        (n_("Low Latency"), vec!["scx_nest"]),
+        (
+            n_("Testing"),
+            vec!["scx_simple", "scx_chaos", "scx_userland"],
+        ),
     ];
 
     // Set for tracking used schedulers
@@ -534,7 +544,7 @@ fn show_scheduler_selector(
 
     for (cat_name, items) in categories {
         let group = adw::PreferencesGroup::new();
-        group.set_title(cat_name);
+        group.set_title(&gettext(cat_name));
 
         let mut has_items = false;
 
@@ -585,7 +595,7 @@ fn show_scheduler_selector(
 
     if !others.is_empty() {
         let group = adw::PreferencesGroup::new();
-        group.set_title("Other");
+        group.set_title(&gettext("Other"));
         for item in others {
             let row = adw::ActionRow::new();
             row.set_title(&humanize_name(item));
@@ -0,0 +1,340 @@
+//! Local AI page button handlers.
+//!
+//! Handles install + uninstall for:
+//! - Ollama, in the CUDA, ROCm or Vulkan build matching the GPU
+//! - Open WebUI, deployed as a Docker Compose stack in front of Ollama
+//! - whisper.cpp speech to text from the AUR
+//! - Stable Diffusion web UI (AUTOMATIC1111), cloned into the home folder
+//!
+//! Every install checks free disk space first and warns when it's short.
+
+use crate::actions;
+use crate::core;
+use crate::core::{ai, compose, hardware};
+use crate::i18n::{gettext, gettext_f, n_};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state::{self, Probe};
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{extract_action_widgets, spawn_blocking, try_extract_widget};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::info;
+use std::path::{Path, PathBuf};
+
+// ─── Shared helpers ─────────────────────────────────────────────────────────
+
+/// Update install / uninstall button pair based on installation status.
+fn update_button_state(
+    install_button: &Button,
+    uninstall_button: &Button,
+    is_installed: bool,
+    default_label: &str,
+) {
+    if is_installed {
+        install_button.set_label(&format!("{} ✓", default_label));
+        install_button.set_sensitive(false);
+        install_button.remove_css_class("suggested-action");
+        install_button.add_css_class("dim-label");
+        uninstall_button.set_visible(true);
+    } else {
+        install_button.set_label(default_label);
+        install_button.set_sensitive(true);
+        install_button.add_css_class("suggested-action");
+        install_button.remove_css_class("dim-label");
+        uninstall_button.set_visible(false);
+    }
+}
+
+/// `pacman -Rns` step for the installed packages among `candidates`, if any.
+fn remove_packages_command(candidates: &[&str], description: &str) -> Option<Command> {
+    let pkgs: Vec<&str> = candidates
+        .iter()
+        .copied()
+        .filter(|pkg| core::is_package_installed(pkg))
+        .collect();
+    if pkgs.is_empty() {
+        return None;
+    }
+    let mut args = vec!["-Rns", "--noconfirm"];
+    args.extend(pkgs);
+    Some(
+        Command::builder()
+            .privileged()
+            .program("pacman")
+            .args(&args)
+            .description(description)
+            .build(),
+    )
+}
+
+/// Run `proceed` straight away when the drive holding `path` has
+/// `required_gb` free, otherwise only after the user accepts a warning.
+fn with_space_check<F>(
+    window: &ApplicationWindow,
+    name: &str,
+    path: &Path,
+    required_gb: u64,
+    proceed: F,
+) where
+    F: FnOnce() + 'static,
+{
+    let warning =
+        ai::free_gb(path).and_then(|free| ai::space_warning(name, path, free, required_gb));
+    match warning {
+        Some(message) => show_warning_confirmation(
+            window.upcast_ref(),
+            &gettext("Low Disk Space"),
+            &message,
+            proceed,
+        ),
+        None => proceed(),
+    }
+}
+
+// ─── Page entry point ───────────────────────────────────────────────────────
+
+/// Set up all button handlers for the local AI page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let ollama_btns = setup_ollama(page_builder, window);
+    setup_open_webui(page_builder, window);
+    let whisper_btns = setup_whisper(page_builder, window);
+    let sd_webui_btns = setup_sd_webui(page_builder, window);
+
+    let bind = |probe: Probe, buttons: Option<(Button, Button)>, label: &'static str| {
+        let Some((install, uninstall)) = buttons else {
+            return;
+        };
+        let install_clone = install.clone();
+        install_state::bind_widget(&install, probe, move |installed| {
+            update_button_state(&install_clone, &uninstall, installed, label)
+        });
+    };
+    bind(
+        Probe::AnyPackage(ai::OLLAMA_PACKAGES),
+        ollama_btns,
+        "Ollama",
+    );
+    bind(
+        Probe::AnyPackage(ai::WHISPER_PACKAGES),
+        whisper_btns,
+        "whisper.cpp",
+    );
+    bind(
+        Probe::Custom(|| ai::sd_webui_dir().join("webui.sh").exists()),
+        sd_webui_btns,
+        "Stable Diffusion",
+    );
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Ollama
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_ollama(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_ollama", "btn_ollama_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Ollama install button clicked");
+
+        let package = ai::ollama_package(&hardware::gpus());
+        let window = window_clone.clone();
+        with_space_check(
+            &window_clone,
+            "Ollama",
+            Path::new("/var/lib"),
+            ai::ollama_required_gb(package),
+            move || super::run_action(&window, &actions::local_ai::OLLAMA),
+        );
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    //
+    // Downloaded models in /var/lib/ollama are left in place.
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Ollama uninstall button clicked");
+
+        let Some(remove) =
+            remove_packages_command(ai::OLLAMA_PACKAGES, n_("Removing Ollama packages..."))
+        else {
+            return;
+        };
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("systemctl")
+                    .args(&["disable", "--now", "ollama.service"])
+                    .description("Stopping the Ollama service...")
+                    .build(),
+            )
+            .then(remove)
+            .build();
+        task_runner::run(window_clone.upcast_ref(), commands, "Ollama Uninstall");
+    });
+
+    Some((btn_install, btn_uninstall))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Open WebUI
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_open_webui(builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn_deploy) = try_extract_widget::<Button>(builder, "btn_open_webui") else {
+        return;
+    };
+
+    // The stack is removed like any other, from the Docker Compose stacks
+    // on the Containers/VMs page.
+    let window_clone = window.clone();
+    btn_deploy.connect_clicked(move |_| {
+        info!("Open WebUI button clicked");
+
+        if !core::is_package_installed("docker") {
+            show_error(
+                &window_clone,
+                &gettext(
+                    "Open WebUI runs on Docker. Install Docker from the Containers/VMs page first.",
+                ),
+            );
+            return;
+        }
+        if !ai::OLLAMA_PACKAGES
+            .iter()
+            .any(|pkg| core::is_package_installed(pkg))
+        {
+            show_error(
+                &window_clone,
+                &gettext("Open WebUI chats with models served by Ollama. Install Ollama first."),
+            );
+            return;
+        }
+        let Some(stack) = compose::find("open-webui") else {
+            return;
+        };
+
+        let window = window_clone.clone();
+        with_space_check(
+            &window_clone,
+            "Open WebUI",
+            Path::new("/var/lib/docker"),
+            ai::OPEN_WEBUI_REQUIRED_GB,
+            move || super::containers_vms::show_stack_form(&window, stack),
+        );
+    });
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  whisper.cpp
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_whisper(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_whisper", "btn_whisper_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("whisper.cpp install button clicked");
+
+        let window = window_clone.clone();
+        with_space_check(
+            &window_clone,
+            "whisper.cpp",
+            Path::new("/usr"),
+            ai::WHISPER_REQUIRED_GB,
+            move || super::run_action(&window, &actions::local_ai::WHISPER),
+        );
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("whisper.cpp uninstall button clicked");
+
+        let Some(remove) =
+            remove_packages_command(ai::WHISPER_PACKAGES, n_("Removing whisper.cpp..."))
+        else {
+            return;
+        };
+        task_runner::run(
+            window_clone.upcast_ref(),
+            CommandSequence::new().then(remove).build(),
+            "whisper.cpp Uninstall",
+        );
+    });
+
+    Some((btn_install, btn_uninstall))
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+//  Stable Diffusion web UI
+// ═══════════════════════════════════════════════════════════════════════════════
+
+fn setup_sd_webui(builder: &Builder, window: &ApplicationWindow) -> Option<(Button, Button)> {
+    let ids = ["btn_sd_webui", "btn_sd_webui_uninstall"];
+    let [btn_install, btn_uninstall]: [Button; 2] = extract_action_widgets(builder, ids)?;
+
+    // ── Install ──────────────────────────────────────────────────────────
+    let window_clone = window.clone();
+    btn_install.connect_clicked(move |_| {
+        info!("Stable Diffusion install button clicked");
+
+        let window = window_clone.clone();
+        with_space_check(
+            &window_clone,
+            "Stable Diffusion",
+            &ai::sd_webui_dir(),
+            ai::SD_WEBUI_REQUIRED_GB,
+            move || super::run_action(&window, &actions::local_ai::SD_WEBUI),
+        );
+    });
+
+    // ── Uninstall ────────────────────────────────────────────────────────
+    //
+    // The folder also holds the Python environment, downloaded models and
+    // generated images, so removing it is confirmed first.
+    let window_clone = window.clone();
+    btn_uninstall.connect_clicked(move |_| {
+        info!("Stable Diffusion uninstall button clicked");
+
+        let dir: PathBuf = ai::sd_webui_dir();
+        let message = gettext_f(
+            "This deletes <b>{dir}</b>, including downloaded models and \
+             generated images in it.",
+            &[("dir", &dir.display().to_string())],
+        );
+        let window = window_clone.clone();
+        show_warning_confirmation(
+            window_clone.upcast_ref(),
+            &gettext("Remove Stable Diffusion"),
+            &message,
+            move || {
+                let window = window.clone();
+                spawn_blocking(
+                    move || std::fs::remove_dir_all(&dir),
+                    move |result| match result {
+                        Ok(()) => {
+                            info!("Removed the Stable Diffusion web UI");
+                            install_state::refresh();
+                        }
+                        Err(e) => show_error(
+                            &window,
+                            &gettext_f(
+                                "Failed to remove the web UI folder: {error}",
+                                &[("error", &e.to_string())],
+                            ),
+                        ),
+                    },
+                );
+            },
+        );
+    });
+
+    Some((btn_install, btn_uninstall))
+}
@@ -1,6 +1,7 @@
 //! Main page button handlers.
 //!
 //! Handles:
+//! - Dashboard cards with the system's status
 //! - System update
 //! - Package manager GUI installation
 //! - Download Arch ISO
@@ -8,30 +9,37 @@
 
 use crate::config;
 use crate::core;
+use crate::i18n::gettext;
 use crate::ui::dialogs::download::show_download_dialog;
 use crate::ui::dialogs::selection::{
     show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
 };
 use crate::ui::dialogs::terminal;
 use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state::{self, Probe};
 use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
+use crate::ui::utils::try_extract_widget;
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, Builder, Button};
 use log::info;
 
 /// Set up all button handlers for the main page.
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    crate::ui::dashboard::setup_section(page_builder, window);
     setup_update_system(page_builder, window);
     setup_pkg_manager(page_builder, window);
     setup_download_arch_iso(page_builder, window);
     setup_install_nix(page_builder, window);
     setup_external_links(page_builder);
+    crate::ui::favorites::setup_section(page_builder, window);
+    crate::ui::activity::setup_section(page_builder, window);
 }
 
 /// Setup system update button.
 fn setup_update_system(builder: &Builder, window: &ApplicationWindow) {
-    let button = extract_widget::<Button>(builder, "btn_update_system");
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_update_system") else {
+        return;
+    };
     let window = window.clone();
 
     button.connect_clicked(move |_| {
@@ -40,7 +48,7 @@ fn setup_update_system(builder: &Builder, window: &ApplicationWindow) {
         // Use terminal dialog for interactive system update
         terminal::show_terminal_dialog(
             window.upcast_ref(),
-            "System Update",
+            &gettext("System Update"),
             "/usr/local/bin/upd",
             &[],
         );
@@ -49,7 +57,9 @@ fn setup_update_system(builder: &Builder, window: &ApplicationWindow) {
 
 /// Setup package manager GUI button.
 fn setup_pkg_manager(builder: &Builder, window: &ApplicationWindow) {
-    let button = extract_widget::<Button>(builder, "btn_pkg_manager");
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_pkg_manager") else {
+        return;
+    };
     let window = window.clone();
 
     button.connect_clicked(move |_| {
@@ -57,48 +67,48 @@ fn setup_pkg_manager(builder: &Builder, window: &ApplicationWindow) {
 
         // Check which package managers are already installed
         let config = SelectionDialogConfig::new(
-            "Package Manager GUI Applications",
-            "Select which package manager GUIs to install. Multiple selections allowed.",
+            &gettext("Package Manager GUI Applications"),
+            &gettext("Select which package manager GUIs to install. Multiple selections allowed."),
         )
         .selection_type(SelectionType::Multi)
         .selection_required(true)
         .add_option(SelectionOption::new(
             "octopi",
             "Octopi",
-            "Powerful Pacman GUI with AUR support",
-            core::is_package_installed("octopi"),
+            &gettext("Powerful Pacman GUI with AUR support"),
+            install_state::is_installed(Probe::Package("octopi")),
         ))
         .add_option(SelectionOption::new(
             "pacseek",
             "PacSeek",
-            "Terminal UI package manager with search",
-            core::is_package_installed("pacseek"),
+            &gettext("Terminal UI package manager with search"),
+            install_state::is_installed(Probe::Package("pacseek")),
         ))
         .add_option(SelectionOption::new(
             "bauh",
             "Bauh",
-            "Manage Pacman, AUR, Flatpak, Snap packages",
-            core::is_package_installed("bauh"),
+            &gettext("Manage Pacman, AUR, Flatpak, Snap packages"),
+            install_state::is_installed(Probe::Package("bauh")),
         ))
         .add_option(SelectionOption::new(
             "warehouse",
             "Warehouse",
-            "Flatpak package manager (Flatpak)",
-            core::is_flatpak_installed("io.github.flattool.Warehouse"),
+            &gettext("Flatpak package manager (Flatpak)"),
+            install_state::is_installed(Probe::Flatpak("io.github.flattool.Warehouse")),
         ))
         .add_option(SelectionOption::new(
             "flatseal",
             "Flatseal",
-            "Flatpak permissions manager (Flatpak)",
-            core::is_flatpak_installed("com.github.tchx84.Flatseal"),
+            &gettext("Flatpak permissions manager (Flatpak)"),
+            install_state::is_installed(Probe::Flatpak("com.github.tchx84.Flatseal")),
         ))
         .add_option(SelectionOption::new(
             "bazaar",
             "Bazaar",
-            "Browse and install Flatpak apps (Flatpak)",
-            core::is_flatpak_installed("io.github.kolunmi.Bazaar"),
+            &gettext("Browse and install Flatpak apps (Flatpak)"),
+            install_state::is_installed(Probe::Flatpak("io.github.kolunmi.Bazaar")),
         ))
-        .confirm_label("Install");
+        .confirm_label(&gettext("Install"));
 
         let window_for_closure = window.clone();
         show_selection_dialog(window.upcast_ref(), config, move |selected| {
@@ -187,7 +197,9 @@ fn build_pkg_manager_commands(selected: &[String]) -> CommandSequence {
 
 /// Setup download Arch ISO button.
 fn setup_download_arch_iso(builder: &Builder, window: &ApplicationWindow) {
-    let button = extract_widget::<Button>(builder, "btn_download_arch_iso");
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_download_arch_iso") else {
+        return;
+    };
     let window = window.clone();
 
     button.connect_clicked(move |_| {
@@ -199,7 +211,9 @@ fn setup_download_arch_iso(builder: &Builder, window: &ApplicationWindow) {
 
 /// Setup Nix package manager installation button.
 fn setup_install_nix(builder: &Builder, window: &ApplicationWindow) {
-    let button = extract_widget::<Button>(builder, "btn_install_nix");
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_install_nix") else {
+        return;
+    };
     let window = window.clone();
 
     button.connect_clicked(move |_| {
@@ -209,36 +223,36 @@ fn setup_install_nix(builder: &Builder, window: &ApplicationWindow) {
         let window_clone = window.clone();
         show_warning_confirmation(
             window.upcast_ref(),
-            "Experimental Feature Warning",
-            "Nix Package Manager is an <span foreground=\"red\" weight=\"bold\">EXPERIMENTAL</span> feature.\n\n\
+            &gettext("Experimental Feature Warning"),
+            &gettext("Nix Package Manager is an <span foreground=\"red\" weight=\"bold\">EXPERIMENTAL</span> feature.\n\n\
             This is intended for <span foreground=\"red\" weight=\"bold\">EXPERIENCED USERS ONLY</span>.\n\
             <span foreground=\"red\" weight=\"bold\">Do NOT enable</span> unless you know what you are doing.\n\
             <span foreground=\"red\" weight=\"bold\">NO SUPPORT</span> will be provided for Nix-related issues.\n\n\
-            Proceed at your own risk.",
+            Proceed at your own risk."),
             move || {
                 info!("User confirmed Nix installation after warning");
 
                 // Show selection dialog to choose installation type
                 let window_for_selection = window_clone.clone();
                 let config = SelectionDialogConfig::new(
-                    "Nix Installation Type",
-                    "Choose the installation type for Nix Package Manager. Multi-user is recommended for most users.",
+                    &gettext("Nix Installation Type"),
+                    &gettext("Choose the installation type for Nix Package Manager. Multi-user is recommended for most users."),
                 )
                 .selection_type(SelectionType::Single)
                 .selection_required(true)
                 .add_option(SelectionOption::new(
                     "multi-user",
-                    "Multi-user Installation (Recommended)",
-                    "Better build isolation, security, and sharing between users. Requires systemd and sudo.",
+                    &gettext("Multi-user Installation (Recommended)"),
+                    &gettext("Better build isolation, security, and sharing between users. Requires systemd and sudo."),
                     false,
                 ))
                 .add_option(SelectionOption::new(
                     "single-user",
-                    "Single-user Installation",
-                    "Simpler installation owned by your user. Easier to uninstall.",
+                    &gettext("Single-user Installation"),
+                    &gettext("Simpler installation owned by your user. Easier to uninstall."),
                     false,
                 ))
-                .confirm_label("Continue");
+                .confirm_label(&gettext("Continue"));
 
                 show_selection_dialog(window_clone.upcast_ref(), config, move |selected| {
                     if selected.is_empty() {
@@ -257,7 +271,7 @@ fn setup_install_nix(builder: &Builder, window: &ApplicationWindow) {
 
                     terminal::show_terminal_dialog(
                         window_for_selection.upcast_ref(),
-            "Install Nix Package Manager",
+            &gettext("Install Nix Package Manager"),
                         "sh",
                         &["-c", install_command],
                     );
@@ -269,22 +283,25 @@ fn setup_install_nix(builder: &Builder, window: &ApplicationWindow) {
 
 /// Setup external link buttons.
 fn setup_external_links(builder: &Builder) {
-    let btn_discord = extract_widget::<Button>(builder, "link_discord");
-    btn_discord.connect_clicked(|_| {
-        info!("Discord link clicked");
-        let _ = core::package::open_url(config::links::DISCORD);
-    });
+    if let Some(btn_discord) = try_extract_widget::<Button>(builder, "link_discord") {
+        btn_discord.connect_clicked(|_| {
+            info!("Discord link clicked");
+            let _ = core::package::open_url(config::links::DISCORD);
+        });
+    }
 
-    let btn_youtube = extract_widget::<Button>(builder, "link_youtube");
-    btn_youtube.connect_clicked(|_| {
-        info!("YouTube link clicked");
-        let _ = core::package::open_url(config::links::YOUTUBE);
-    });
+    if let Some(btn_youtube) = try_extract_widget::<Button>(builder, "link_youtube") {
+        btn_youtube.connect_clicked(|_| {
+            info!("YouTube link clicked");
+            let _ = core::package::open_url(config::links::YOUTUBE);
+        });
+    }
 
-    let btn_github = extract_widget::<Button>(builder, "link_github");
-    btn_github.connect_clicked(|_| {
-        info!("GitHub link clicked");
-        let _ = core::package::open_url(config::links::GITHUB);
-    });
+    if let Some(btn_github) = try_extract_widget::<Button>(builder, "link_github") {
+        btn_github.connect_clicked(|_| {
+            info!("GitHub link clicked");
+            let _ = core::package::open_url(config::links::GITHUB);
+        });
+    }
 
 }
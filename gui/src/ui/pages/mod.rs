@@ -2,23 +2,134 @@
 //!
 //! This module organizes button handlers by page:
 //! - `main_page`: System update, package managers
-//! - `drivers`: GPU drivers, Tailscale, ASUS ROG tools
-//! - `gaming_tools`: Steam, controllers, game launchers
+//! - `drivers`: GPU drivers, Tailscale, ASUS ROG tools, hybrid graphics
+//! - `gaming_tools`: Steam, controllers, game launchers, Proton-GE
 //! - `gamescope`: Gamescope command generator
 //! - `containers_vms`: Docker, Podman, VirtualBox, KVM
+//! - `dev_tools`: Code editors, toolchains, git setup, dev containers
+//! - `local_ai`: Ollama, Open WebUI, whisper.cpp, Stable Diffusion
 //! - `multimedia_tools`: OBS, Jellyfin
-//! - `customization`: ZSH, themes, wallpapers
+//! - `customization`: ZSH, themes, wallpapers, dotfiles
 //! - `kernel_schedulers`: Kernel Manager and SCX Scheduler (with subtabs)
+//! - `power_management`: Power daemons and battery charge threshold
 //! - `servicing`: System fixes and maintenance
+//! - `scheduled_maintenance`: Recurring maintenance jobs as systemd timers
+//! - `backup`: Encrypted restic or borgmatic backups with a schedule
+//! - `disks`: Drives, usage, SMART health, mounting and fstab entries
+//! - `system_settings`: Hostname, timezone, NTP and locale
+//! - `network_tools`: Encrypted DNS, Tailscale, VPN imports and file sharing
 //! - `biometrics`: Fingerprint and facial recognition setup
+//! - `uninstall`: Everything the toolkit installed, with one-click removal
+//! - `custom`: User-defined actions from drop-in TOML files
 
+pub mod backup;
 pub mod biometrics;
 pub mod containers_vms;
+pub mod custom;
 pub mod customization;
+pub mod dev_tools;
+pub mod disks;
 pub mod drivers;
 pub mod gamescope;
 pub mod gaming_tools;
 pub mod kernel_schedulers;
+pub mod local_ai;
 pub mod main_page;
 pub mod multimedia_tools;
+pub mod network_tools;
+pub mod power_management;
+pub mod scheduled_maintenance;
 pub mod servicing;
+pub mod system_settings;
+pub mod uninstall;
+
+use crate::actions::{self, Action, Requirement};
+use crate::core::snapshot;
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::dialogs::error::show_action_error;
+use crate::ui::dialogs::warning::{show_destructive_confirmation, show_warning_confirmation};
+use crate::ui::task_runner;
+use crate::ui::utils::spawn_blocking;
+use gtk4::prelude::*;
+use gtk4::{glib, ApplicationWindow};
+
+/// Run a registered action in the task runner, or explain which of its
+/// requirements are missing. Destructive actions are confirmed first. The
+/// commands are built off the main thread, since many check pacman.
+pub fn run_action(window: &ApplicationWindow, action: &'static Action) {
+    let missing = action.missing_requirements();
+    if missing.contains(&Requirement::AurHelper) {
+        offer_aur_helper_install(window, action.title);
+        return;
+    }
+    if let Some(message) = action.requirements_error() {
+        show_action_error(window, &message, action.id);
+        return;
+    }
+    if !action.destructive {
+        let window = window.clone();
+        spawn_blocking(
+            move || action.commands_with_hooks(),
+            move |commands| {
+                task_runner::run(window.upcast_ref(), commands, action.title);
+            },
+        );
+        return;
+    }
+
+    let message = gettext_f(
+        "<b>{title}</b>: {description}.\n\nThis can't be undone from the toolkit.",
+        &[
+            ("title", &glib::markup_escape_text(&gettext(action.title))),
+            (
+                "description",
+                &glib::markup_escape_text(&gettext(action.description)),
+            ),
+        ],
+    );
+    let window_clone = window.clone();
+    show_destructive_confirmation(
+        window.upcast_ref(),
+        &gettext(action.title),
+        &message,
+        move |snapshot_step| {
+            spawn_blocking(
+                move || action.commands_with_hooks(),
+                move |commands| {
+                    task_runner::run(
+                        window_clone.upcast_ref(),
+                        snapshot::before(snapshot_step, commands),
+                        action.title,
+                    );
+                },
+            );
+        },
+    );
+}
+
+/// Explain that `title` needs an AUR helper and offer to install paru.
+pub fn offer_aur_helper_install(window: &ApplicationWindow, title: &str) {
+    let message = gettext_f(
+        "<b>{title}</b> needs an AUR helper ({helpers}), and none is installed.\n\n\
+         The toolkit can build and install <b>paru</b> from the AUR now. \
+         Run the action again once it's done.",
+        &[
+            ("title", &glib::markup_escape_text(&gettext(title))),
+            ("helpers", &crate::core::aur::supported_list()),
+        ],
+    );
+
+    let window_clone = window.clone();
+    show_warning_confirmation(
+        window.upcast_ref(),
+        &gettext("AUR Helper Required"),
+        &message,
+        move || {
+            task_runner::run(
+                window_clone.upcast_ref(),
+                actions::aur::install_paru(),
+                actions::aur::INSTALL_PARU_TITLE,
+            );
+        },
+    );
+}
@@ -2,18 +2,29 @@
 //!
 //! Handles:
 //! - OBS-Studio with plugins and V4L2
+//! - OBS streaming stack with GPU encoders, virtual camera and DroidCam
 //! - Kdenlive video editor
 //! - Jellyfin server installation
 //! - GPU Screen Recorder GTK (repo-first, AUR fallback)
 //! - Streaming service web app installer
+//! - Codec install and hardware video acceleration check
+//! - DaVinci Resolve from the official installer, with GPU compute packages
+//! - Mic noise suppression (PipeWire filters or NoiseTorch) and virtual camera
 
+use crate::actions;
+use crate::config::user::AppSource;
 use crate::core;
+use crate::core::apps;
+use crate::core::hardware::{self, GpuVendor};
+use crate::core::hwaccel;
+use crate::i18n::{gettext, gettext_f};
 use crate::ui::dialogs::selection::{
     show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
 };
+use crate::ui::install_state::{self, Probe};
 use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::extract_widget;
-use gtk4::prelude::*;
+use crate::ui::utils::{spawn_blocking, try_extract_widget};
+use adw::prelude::*;
 use gtk4::{ApplicationWindow, Builder};
 use log::info;
 
@@ -96,14 +107,22 @@ const STREAMING_SERVICES: &[(&str, &str)] = &[
 /// Set up all button handlers for the multimedia tools page
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     setup_obs_studio_aio(page_builder, window);
+    setup_obs_stack(page_builder, window);
     setup_kdenlive(page_builder, window);
     setup_jellyfin(page_builder, window);
     setup_gpu_screen_recorder(page_builder, window);
     setup_streaming_services(page_builder, window);
+    setup_codec_doctor(page_builder, window);
+    setup_davinci_resolve(page_builder, window);
+    setup_noise_suppression(page_builder, window);
 }
 
 fn setup_obs_studio_aio(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_obs_studio_aio = extract_widget::<gtk4::Button>(page_builder, "btn_obs_studio_aio");
+    let Some(btn_obs_studio_aio) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_obs_studio_aio")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_obs_studio_aio.connect_clicked(move |_| {
         info!("Multimedia tools: OBS-Studio AiO button clicked");
@@ -134,48 +153,48 @@ fn setup_obs_studio_aio(page_builder: &Builder, window: &ApplicationWindow) {
                     core::is_flatpak_installed("com.obsproject.Studio.Plugin.BackgroundRemoval");
 
                 let config = SelectionDialogConfig::new(
-                    "OBS-Studio & Plugins Installation",
-                    "OBS-Studio will be installed. Optionally select plugins to install.",
+                    &gettext("OBS-Studio & Plugins Installation"),
+                    &gettext("OBS-Studio will be installed. Optionally select plugins to install."),
                 )
                 .selection_type(SelectionType::Multi)
                 .selection_required(false)
                 .add_option(SelectionOption::new(
                     "wayland_hotkeys",
-                    "Wayland Hotkeys Plugin",
-                    "Enable hotkey support for OBS on Wayland",
+                    &gettext("Wayland Hotkeys Plugin"),
+                    &gettext("Enable hotkey support for OBS on Wayland"),
                     wayland_hotkeys_installed,
                 ))
                 .add_option(SelectionOption::new(
                     "graphics_capture",
-                    "Graphics Capture Plugins",
+                    &gettext("Graphics Capture Plugins"),
                     "VkCapture, GStreamer, GStreamer VA-API",
                     graphics_capture_installed,
                 ))
                 .add_option(SelectionOption::new(
                     "transitions_effects",
-                    "Transitions & Effects",
+                    &gettext("Transitions & Effects"),
                     "Move Transition, Transition Table, Scale to Sound",
                     transitions_effects_installed,
                 ))
                 .add_option(SelectionOption::new(
                     "streaming_tools",
-                    "Streaming & Recording Tools",
+                    &gettext("Streaming & Recording Tools"),
                     "WebSocket API, Scene Switcher, DroidCam",
                     streaming_tools_installed,
                 ))
                 .add_option(SelectionOption::new(
                     "audio_video_tools",
-                    "Audio & Video Tools",
+                    &gettext("Audio & Video Tools"),
                     "Waveform, Vertical Canvas, Background Removal",
                     audio_video_tools_installed,
                 ))
                 .add_option(SelectionOption::new(
                     "v4l2",
-                    "V4L2loopback Virtual Camera",
-                    "Enable OBS virtual camera functionality",
+                    &gettext("V4L2loopback Virtual Camera"),
+                    &gettext("Enable OBS virtual camera functionality"),
                     v4l2_installed,
                 ))
-                .confirm_label("Install");
+                .confirm_label(&gettext("Install"));
 
                 let window_for_closure = window.clone();
                 show_selection_dialog(window_ref, config, move |selected_ids| {
@@ -255,26 +274,7 @@ fn setup_obs_studio_aio(page_builder: &Builder, window: &ApplicationWindow) {
                             .build());
                     }
                     if selected_ids.iter().any(|s| s == "v4l2") {
-                        commands = commands.then(Command::builder()
-                            .aur()
-                            .args(&["-S", "--noconfirm", "--needed", "v4l2loopback-dkms", "v4l2loopback-utils"])
-                            .description("Installing V4L2 loopback modules...")
-                            .build());
-                        commands = commands.then(Command::builder()
-                            .privileged()
-                            .program("sh")
-                            .args(&["-c", "echo 'v4l2loopback' > /etc/modules-load.d/v4l2loopback.conf"])
-                            .description("Enabling V4L2 loopback module at boot...")
-                            .build());
-                        commands = commands.then(Command::builder()
-                            .privileged()
-                            .program("sh")
-                            .args(&[
-                                "-c",
-                                "echo 'options v4l2loopback exclusive_caps=1 card_label=\"OBS Virtual Camera\"' > /etc/modprobe.d/v4l2loopback.conf",
-                            ])
-                            .description("Configuring virtual camera options...")
-                            .build());
+                        commands = v4l2loopback_commands(commands);
                     }
 
                     task_runner::run(window_for_closure.upcast_ref(), commands.build(), "OBS-Studio Setup");
@@ -282,94 +282,230 @@ fn setup_obs_studio_aio(page_builder: &Builder, window: &ApplicationWindow) {
     });
 }
 
-fn setup_kdenlive(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_kdenlive = extract_widget::<gtk4::Button>(page_builder, "btn_kdenlive");
+/// Install v4l2loopback and load it at boot, set up for the OBS virtual camera.
+fn v4l2loopback_commands(commands: CommandSequence) -> CommandSequence {
+    commands
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "v4l2loopback-dkms",
+                    "v4l2loopback-utils",
+                ])
+                .description("Installing V4L2 loopback modules...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "echo 'v4l2loopback' > /etc/modules-load.d/v4l2loopback.conf",
+                ])
+                .description("Enabling V4L2 loopback module at boot...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "echo 'options v4l2loopback exclusive_caps=1 card_label=\"OBS Virtual Camera\"' > /etc/modprobe.d/v4l2loopback.conf",
+                ])
+                .description("Configuring virtual camera options...")
+                .build(),
+        )
+}
+
+fn setup_obs_stack(page_builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn_obs_stack) = try_extract_widget::<gtk4::Button>(page_builder, "btn_obs_stack")
+    else {
+        return;
+    };
     let window = window.clone();
-    btn_kdenlive.connect_clicked(move |_| {
-        info!("Multimedia tools: Kdenlive button clicked");
-        let commands = CommandSequence::new()
-            .then(
+    btn_obs_stack.connect_clicked(move |_| {
+        info!("Multimedia tools: OBS Streaming Stack button clicked");
+
+        let gpus = hardware::gpus();
+        let encoders = if gpus.is_empty() {
+            gettext("No supported GPU found, OBS will encode on the CPU.")
+        } else {
+            let names: Vec<&str> = gpus.iter().map(|g| g.name()).collect();
+            gettext_f(
+                "Hardware encoding is set up for your {gpu} GPU.",
+                &[("gpu", &names.join(" + "))],
+            )
+        };
+
+        let config = SelectionDialogConfig::new(
+            &gettext("OBS Streaming Stack"),
+            &format!(
+                "{} {}",
+                gettext("OBS-Studio will be installed with hardware encoding."),
+                encoders
+            ),
+        )
+        .selection_type(SelectionType::Multi)
+        .selection_required(false)
+        .add_option(
+            SelectionOption::new(
+                "flatpak",
+                &gettext("Use the Flatpak"),
+                &gettext("Install OBS-Studio from Flathub instead of the Arch repos"),
+                false,
+            )
+            .selected(apps::OBS.preferred() == AppSource::Flatpak)
+            .in_group(&gettext("Source")),
+        )
+        .add_option(
+            SelectionOption::new(
+                "v4l2",
+                &gettext("Virtual Camera"),
+                &gettext("v4l2loopback module so OBS can act as a webcam"),
+                install_state::is_installed(Probe::Package("v4l2loopback-dkms")),
+            )
+            .in_group(&gettext("Extras"))
+            .recommended(),
+        )
+        .add_option(
+            SelectionOption::new(
+                "droidcam",
+                "DroidCam",
+                &gettext("Use an Android or iOS phone as a camera"),
+                install_state::is_installed(Probe::Package("droidcam")),
+            )
+            .in_group(&gettext("Extras")),
+        )
+        .confirm_label(&gettext("Install"));
+
+        let window_for_closure = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected_ids| {
+            let flatpak = selected_ids.iter().any(|s| s == "flatpak");
+            let mut commands = obs_stack_commands(flatpak, &gpus);
+
+            if selected_ids.iter().any(|s| s == "v4l2") {
+                commands = v4l2loopback_commands(commands);
+            }
+            if selected_ids.iter().any(|s| s == "droidcam") {
+                commands = commands.then(
+                    Command::builder()
+                        .aur()
+                        .args(&["-S", "--noconfirm", "--needed", "droidcam"])
+                        .description("Installing DroidCam client...")
+                        .build(),
+                );
+            }
+
+            task_runner::run(
+                window_for_closure.upcast_ref(),
+                commands.build(),
+                "OBS Streaming Stack",
+            );
+        });
+    });
+}
+
+/// OBS-Studio from the repos or Flathub, plus the VA-API pieces for AMD and
+/// Intel GPUs. NVENC needs nothing extra: the repo build uses the NVIDIA
+/// driver's encoder directly and Flatpak pulls the matching GL extension.
+fn obs_stack_commands(flatpak: bool, gpus: &[GpuVendor]) -> CommandSequence {
+    let vaapi_gpus: Vec<GpuVendor> = gpus
+        .iter()
+        .copied()
+        .filter(|g| *g != GpuVendor::Nvidia)
+        .collect();
+    let mut commands = CommandSequence::new();
+
+    if flatpak {
+        let mut args = vec!["install", "-y", "flathub", "com.obsproject.Studio"];
+        if !vaapi_gpus.is_empty() {
+            args.extend([
+                "com.obsproject.Studio.Plugin.Gstreamer",
+                "com.obsproject.Studio.Plugin.GStreamerVaapi",
+            ]);
+        }
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&args)
+                .description("Installing OBS-Studio Flatpak and encoder plugins...")
+                .build(),
+        );
+    } else {
+        let mut args = vec!["-S", "--noconfirm", "--needed", "obs-studio"];
+        args.extend(vaapi_gpus.iter().map(|g| g.vaapi_package()));
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&args)
+                .description("Installing OBS-Studio and VA-API drivers...")
+                .build(),
+        );
+
+        if !vaapi_gpus.is_empty() {
+            commands = commands.then(
                 Command::builder()
                     .aur()
-                    .args(&["-S", "--noconfirm", "--needed", "kdenlive"])
-                    .description("Installing Kdenlive...")
+                    .args(&["-S", "--noconfirm", "--needed", "obs-vaapi"])
+                    .description("Installing OBS VA-API encoder plugin...")
                     .build(),
-            )
-            .build();
+            );
+        }
+    }
+
+    commands
+}
 
-        task_runner::run(window.upcast_ref(), commands, "Kdenlive Installation");
+fn setup_kdenlive(page_builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn_kdenlive) = try_extract_widget::<gtk4::Button>(page_builder, "btn_kdenlive")
+    else {
+        return;
+    };
+    let window = window.clone();
+    btn_kdenlive.connect_clicked(move |_| {
+        info!("Multimedia tools: Kdenlive button clicked");
+        super::run_action(&window, &actions::multimedia::KDENLIVE);
     });
 }
 
 fn setup_jellyfin(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_jellyfin = extract_widget::<gtk4::Button>(page_builder, "btn_jellyfin");
+    let Some(btn_jellyfin) = try_extract_widget::<gtk4::Button>(page_builder, "btn_jellyfin")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_jellyfin.connect_clicked(move |_| {
         info!("Multimedia tools: Jellyfin button clicked");
-        let commands = CommandSequence::new()
-            .then(
-                Command::builder()
-                    .aur()
-                    .args(&[
-                        "-S",
-                        "--noconfirm",
-                        "--needed",
-                        "jellyfin-server",
-                        "jellyfin-web",
-                        "jellyfin-ffmpeg",
-                    ])
-                    .description("Installing Jellyfin server and components...")
-                    .build(),
-            )
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("systemctl")
-                    .args(&["enable", "--now", "jellyfin.service"])
-                    .description("Starting Jellyfin service...")
-                    .build(),
-            )
-            .build();
-
-        task_runner::run(window.upcast_ref(), commands, "Jellyfin Server Setup");
+        super::run_action(&window, &actions::multimedia::JELLYFIN);
     });
 }
 
 fn setup_gpu_screen_recorder(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_gpu_screen_recorder =
-        extract_widget::<gtk4::Button>(page_builder, "btn_gpu_screen_recorder");
+    let Some(btn_gpu_screen_recorder) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_gpu_screen_recorder")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_gpu_screen_recorder.connect_clicked(move |_| {
         info!("Multimedia tools: GPU Screen Recorder button clicked");
-
-        // Check official repos first; fall back to AUR if unavailable.
-        let in_repos = core::is_package_in_repos("gpu-screen-recorder-gtk");
-
-        let install_cmd = if in_repos {
-            info!("gpu-screen-recorder-gtk found in official repos – installing via pacman");
-            Command::builder()
-                .privileged()
-                .program("pacman")
-                .args(&["-S", "--noconfirm", "--needed", "gpu-screen-recorder-gtk"])
-                .description("Installing GPU Screen Recorder GTK from official repos...")
-                .build()
-        } else {
-            info!("gpu-screen-recorder-gtk not in official repos – installing via AUR");
-            Command::builder()
-                .aur()
-                .args(&["-S", "--noconfirm", "--needed", "gpu-screen-recorder-gtk"])
-                .description("Installing GPU Screen Recorder GTK from AUR...")
-                .build()
-        };
-
-        let commands = CommandSequence::new().then(install_cmd).build();
-
-        task_runner::run(window.upcast_ref(), commands, "GPU Screen Recorder Setup");
+        super::run_action(&window, &actions::multimedia::GPU_SCREEN_RECORDER);
     });
 }
 
 fn setup_streaming_services(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_streaming = extract_widget::<gtk4::Button>(page_builder, "btn_streaming_services");
+    let Some(btn_streaming) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_streaming_services")
+    else {
+        return;
+    };
     let window = window.clone();
 
     btn_streaming.connect_clicked(move |_| {
@@ -389,21 +525,25 @@ fn setup_streaming_services(page_builder: &Builder, window: &ApplicationWindow)
         };
 
         let dialog_desc = if is_steamos {
-            "Select services to add as fullscreen Chrome kiosk web apps.\n\
-             Flatpak Google Chrome will be installed if needed.\n\
-             Handheld device detected — selected apps will be added to Steam."
+            gettext(
+                "Select services to add as fullscreen Chrome kiosk web apps.\n\
+                 Flatpak Google Chrome will be installed if needed.\n\
+                 Handheld device detected — selected apps will be added to Steam.",
+            )
         } else {
-            "Select services to add as fullscreen Chrome kiosk web apps.\n\
-             Flatpak Google Chrome will be installed if needed."
+            gettext(
+                "Select services to add as fullscreen Chrome kiosk web apps.\n\
+                 Flatpak Google Chrome will be installed if needed.",
+            )
         };
 
         let mut config = SelectionDialogConfig::new(
-            "Streaming Service Web Apps",
-            dialog_desc,
+            &gettext("Streaming Service Web Apps"),
+            &dialog_desc,
         )
         .selection_type(SelectionType::Multi)
         .selection_required(true)
-        .confirm_label("Add Selected");
+        .confirm_label(&gettext("Add Selected"));
 
         for (name, _url) in STREAMING_SERVICES {
             let desktop_path = format!("{}/{}.desktop", apps_dir, name);
@@ -552,3 +692,486 @@ fn setup_streaming_services(page_builder: &Builder, window: &ApplicationWindow)
         });
     });
 }
+
+fn setup_codec_doctor(page_builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn_codec_doctor) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_codec_doctor")
+    else {
+        return;
+    };
+    let window = window.clone();
+    btn_codec_doctor.connect_clicked(move |button| {
+        info!("Multimedia tools: Codec Doctor button clicked");
+
+        // vainfo and friends can take a moment; the button waits meanwhile
+        button.set_sensitive(false);
+        let button = button.clone();
+        let window = window.clone();
+        spawn_blocking(hwaccel::check, move |report| {
+            button.set_sensitive(true);
+            show_codec_report(&window, report);
+        });
+    });
+}
+
+/// Show which acceleration APIs work, offering to install the codecs and
+/// any missing VA-API driver.
+fn show_codec_report(window: &ApplicationWindow, report: hwaccel::Report) {
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    for status in &report.apis {
+        let row = adw::ActionRow::builder()
+            .title(status.api.name())
+            .subtitle(&status.detail)
+            .build();
+        let (icon, class) = if status.working {
+            ("circle-check-symbolic", "success")
+        } else {
+            ("dialog-warning-symbolic", "warning")
+        };
+        let image = gtk4::Image::from_icon_name(icon);
+        image.add_css_class(class);
+        row.add_prefix(&image);
+        list.append(&row);
+    }
+
+    let gpus = if report.gpus.is_empty() {
+        gettext("No supported GPU found.")
+    } else {
+        let names: Vec<&str> = report.gpus.iter().map(|g| g.name()).collect();
+        format!("{} {}", gettext("Detected GPUs:"), names.join(", "))
+    };
+    let body = if report.missing_packages.is_empty() {
+        gpus
+    } else {
+        format!(
+            "{}\n{} {}",
+            gpus,
+            gettext("Recommended driver packages:"),
+            report.missing_packages.join(", ")
+        )
+    };
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Hardware Acceleration"))
+        .body(body)
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("close", gettext("Close").as_str()),
+        ("install", gettext("Install Codecs").as_str()),
+    ]);
+    dialog.set_response_appearance("install", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("install"));
+    dialog.set_close_response("close");
+
+    let window_clone = window.clone();
+    dialog.connect_response(Some("install"), move |_, _| {
+        let mut args = vec!["-S", "--noconfirm", "--needed"];
+        args.extend(hwaccel::CODEC_PACKAGES);
+        args.extend(&report.missing_packages);
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&args)
+                    .description("Installing codecs and video acceleration drivers...")
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window_clone.upcast_ref(), commands, "Codec Installation");
+    });
+
+    dialog.present(Some(window));
+}
+
+/// Libraries the Resolve installer and binaries expect, beyond a desktop.
+const RESOLVE_PACKAGES: &[&str] = &[
+    "unzip",
+    "fuse2",
+    "glu",
+    "libxcrypt-compat",
+    "xmlsec",
+    "apr-util",
+    "ocl-icd",
+    "ffmpeg",
+];
+
+/// Converts footage Resolve's free version can't decode on Linux (H.264,
+/// H.265, AAC) to DNxHR HQ with PCM audio.
+const RESOLVE_IMPORT_SCRIPT: &str = r#"#!/bin/sh
+# Installed by xero-toolkit
+# Usage: resolve-import FILE... (writes FILE_dnxhr.mov next to each)
+for f in "$@"; do
+    ffmpeg -hide_banner -i "$f" -c:v dnxhd -profile:v dnxhr_hq -pix_fmt yuv422p \
+        -c:a pcm_s16le "${f%.*}_dnxhr.mov" || exit 1
+done
+"#;
+
+/// Converts a Resolve export to H.264/AAC for sharing, since the free
+/// version can't encode either on Linux.
+const RESOLVE_EXPORT_SCRIPT: &str = r#"#!/bin/sh
+# Installed by xero-toolkit
+# Usage: resolve-export FILE... (writes FILE_h264.mp4 next to each)
+for f in "$@"; do
+    ffmpeg -hide_banner -i "$f" -c:v libx264 -crf 18 -preset slow -pix_fmt yuv420p \
+        -c:a aac -b:a 320k "${f%.*}_h264.mp4" || exit 1
+done
+"#;
+
+/// GPU compute runtime Resolve uses for each vendor: OpenCL through ROCm on
+/// AMD, CUDA through the NVIDIA driver plus its OpenCL, and Intel's OpenCL.
+fn resolve_gpu_packages(gpu: GpuVendor) -> &'static [&'static str] {
+    match gpu {
+        GpuVendor::Amd => &["rocm-opencl-runtime", "rocm-hip-runtime"],
+        GpuVendor::Nvidia => &["nvidia-utils", "opencl-nvidia"],
+        GpuVendor::Intel => &["intel-compute-runtime"],
+    }
+}
+
+fn setup_davinci_resolve(page_builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn_davinci_resolve) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_davinci_resolve")
+    else {
+        return;
+    };
+    let window = window.clone();
+    btn_davinci_resolve.connect_clicked(move |_| {
+        info!("Multimedia tools: DaVinci Resolve button clicked");
+
+        let filter = gtk4::FileFilter::new();
+        filter.set_name(Some(&gettext("DaVinci Resolve installer")));
+        filter.add_pattern("DaVinci_Resolve*.zip");
+        filter.add_pattern("DaVinci_Resolve*.run");
+        let filters = gtk4::gio::ListStore::new::<gtk4::FileFilter>();
+        filters.append(&filter);
+
+        let dialog = gtk4::FileDialog::new();
+        dialog.set_title(&gettext("Select the DaVinci Resolve Linux download"));
+        dialog.set_filters(Some(&filters));
+
+        let window = window.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let Ok(file) = dialog.open_future(Some(&window)).await else {
+                // User cancelled
+                return;
+            };
+            let Some(path) = file.path() else {
+                return;
+            };
+
+            let commands = resolve_commands(&path.to_string_lossy(), &hardware::gpus());
+            task_runner::run(
+                window.upcast_ref(),
+                commands.build(),
+                "DaVinci Resolve Setup",
+            );
+        });
+    });
+}
+
+/// Install Resolve from the official download at `installer`, a `.zip` as
+/// downloaded or the `.run` inside it.
+fn resolve_commands(installer: &str, gpus: &[GpuVendor]) -> CommandSequence {
+    let work_dir = dirs::cache_dir()
+        .unwrap_or_else(|| "/tmp".into())
+        .join("xero-toolkit/resolve")
+        .display()
+        .to_string();
+
+    let mut packages: Vec<&str> = RESOLVE_PACKAGES.to_vec();
+    for gpu in gpus {
+        packages.extend(resolve_gpu_packages(*gpu));
+    }
+    let mut args = vec!["-S", "--noconfirm", "--needed"];
+    args.extend(packages);
+
+    // Paths go in as positional parameters so they never need quoting
+    let extract_script = "set -e; rm -rf \"$2\"; mkdir -p \"$2\"; \
+        case \"$1\" in *.zip) unzip -o -q \"$1\" -d \"$2\" ;; *) cp \"$1\" \"$2\"/ ;; esac; \
+        chmod +x \"$2\"/DaVinci_Resolve_*.run";
+    let install_script = "cd \"$1\" && SKIP_PACKAGE_CHECK=1 ./DaVinci_Resolve_*.run -i -y";
+    // Resolve bundles an old GLib that breaks against the system's libraries
+    let glib_fix_script = "rm -f /opt/resolve/libs/libglib-2.0.so* \
+        /opt/resolve/libs/libgio-2.0.so* /opt/resolve/libs/libgmodule-2.0.so*";
+    let helpers_script = format!(
+        "cat > /usr/local/bin/resolve-import << 'EOF'\n{}EOF\n\
+         cat > /usr/local/bin/resolve-export << 'EOF'\n{}EOF\n\
+         chmod 755 /usr/local/bin/resolve-import /usr/local/bin/resolve-export",
+        RESOLVE_IMPORT_SCRIPT, RESOLVE_EXPORT_SCRIPT
+    );
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&args)
+                .description("Installing Resolve dependencies and GPU compute runtime...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", extract_script, "sh", installer, &work_dir])
+                .description("Unpacking the DaVinci Resolve installer...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", install_script, "sh", &work_dir])
+                .description("Running the DaVinci Resolve installer...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", glib_fix_script])
+                .description("Using the system GLib libraries...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", &helpers_script])
+                .description("Installing resolve-import and resolve-export helpers...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("rm")
+                .args(&["-rf", &work_dir])
+                .description("Cleaning up the installer...")
+                .build(),
+        )
+}
+
+/// Per-user PipeWire config directory the filter configs go in.
+const PIPEWIRE_CONF_DIR: &str = "$HOME/.config/pipewire/pipewire.conf.d";
+
+/// PipeWire filter chain adding a "Noise Canceling source" built on the
+/// RNNoise LADSPA plugin.
+const RNNOISE_CONF: &str = r#"context.modules = [
+    {   name = libpipewire-module-filter-chain
+        args = {
+            node.description = "Noise Canceling source"
+            media.name = "Noise Canceling source"
+            filter.graph = {
+                nodes = [
+                    {
+                        type = ladspa
+                        name = rnnoise
+                        plugin = /usr/lib/ladspa/librnnoise_ladspa.so
+                        label = noise_suppressor_mono
+                        control = {
+                            "VAD Threshold (%)" = 50.0
+                            "VAD Grace Period (ms)" = 200
+                            "Retroactive VAD Grace (ms)" = 0
+                        }
+                    }
+                ]
+            }
+            capture.props = {
+                node.name = "capture.rnnoise_source"
+                node.passive = true
+                audio.rate = 48000
+            }
+            playback.props = {
+                node.name = "rnnoise_source"
+                media.class = Audio/Source
+                audio.rate = 48000
+            }
+        }
+    }
+]
+"#;
+
+/// PipeWire echo cancellation with the WebRTC canceller, adding an "Echo
+/// Cancelling source" and sink pair.
+const ECHO_CANCEL_CONF: &str = r#"context.modules = [
+    {   name = libpipewire-module-echo-cancel
+        args = {
+            library.name = aec/libspa-aec-webrtc
+            node.latency = 1024/48000
+            source.props = {
+                node.name = "echo_cancel_source"
+                node.description = "Echo Cancelling source"
+            }
+            sink.props = {
+                node.name = "echo_cancel_sink"
+                node.description = "Echo Cancelling sink"
+            }
+        }
+    }
+]
+"#;
+
+fn setup_noise_suppression(page_builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn_noise_suppression) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_noise_suppression")
+    else {
+        return;
+    };
+    let window = window.clone();
+    btn_noise_suppression.connect_clicked(move |_| {
+        info!("Multimedia tools: Mic & Camera button clicked");
+
+        let config = SelectionDialogConfig::new(
+            &gettext("Mic & Camera"),
+            &gettext(
+                "Clean up your microphone for streams and meetings. The PipeWire filters add new \
+                 input devices to pick in apps; NoiseTorch is a separate app with its own toggle.",
+            ),
+        )
+        .selection_type(SelectionType::Multi)
+        .selection_required(true)
+        .add_option(
+            SelectionOption::new(
+                "rnnoise",
+                &gettext("RNNoise Filter"),
+                &gettext(
+                    "Noise Canceling source built into PipeWire, on for every app that uses it",
+                ),
+                pipewire_conf_exists("99-input-denoising.conf"),
+            )
+            .in_group(&gettext("Microphone"))
+            .recommended(),
+        )
+        .add_option(
+            SelectionOption::new(
+                "echo_cancel",
+                &gettext("Echo Cancellation"),
+                &gettext(
+                    "Stops your speakers being picked up by the mic when not using headphones",
+                ),
+                pipewire_conf_exists("99-echo-cancel.conf"),
+            )
+            .in_group(&gettext("Microphone")),
+        )
+        .add_option(
+            SelectionOption::new(
+                "noisetorch",
+                "NoiseTorch",
+                &gettext("Standalone noise suppression app with a tray toggle"),
+                install_state::is_installed(Probe::Package("noisetorch")),
+            )
+            .in_group(&gettext("Microphone")),
+        )
+        .add_option(
+            SelectionOption::new(
+                "v4l2",
+                &gettext("Virtual Camera"),
+                &gettext("v4l2loopback module so OBS and other apps can act as a webcam"),
+                install_state::is_installed(Probe::Package("v4l2loopback-dkms")),
+            )
+            .in_group(&gettext("Camera")),
+        )
+        .confirm_label(&gettext("Install"));
+
+        let window_for_closure = window.clone();
+        show_selection_dialog(window.upcast_ref(), config, move |selected_ids| {
+            let selected = |id: &str| selected_ids.iter().any(|s| s == id);
+            let mut commands = CommandSequence::new();
+
+            if selected("rnnoise") {
+                commands = commands
+                    .then(
+                        Command::builder()
+                            .aur()
+                            .args(&[
+                                "-S",
+                                "--noconfirm",
+                                "--needed",
+                                "noise-suppression-for-voice",
+                            ])
+                            .description("Installing RNNoise plugin...")
+                            .build(),
+                    )
+                    .then(pipewire_conf_command(
+                        "99-input-denoising.conf",
+                        RNNOISE_CONF,
+                        "Adding the noise canceling source...",
+                    ));
+            }
+            if selected("echo_cancel") {
+                commands = commands.then(pipewire_conf_command(
+                    "99-echo-cancel.conf",
+                    ECHO_CANCEL_CONF,
+                    "Adding the echo cancelling source...",
+                ));
+            }
+            if selected("rnnoise") || selected("echo_cancel") {
+                commands = commands.then(
+                    Command::builder()
+                        .normal()
+                        .program("systemctl")
+                        .args(&[
+                            "--user",
+                            "restart",
+                            "pipewire",
+                            "pipewire-pulse",
+                            "wireplumber",
+                        ])
+                        .description("Restarting PipeWire...")
+                        .build(),
+                );
+            }
+            if selected("noisetorch") {
+                commands = commands.then(
+                    Command::builder()
+                        .aur()
+                        .args(&["-S", "--noconfirm", "--needed", "noisetorch"])
+                        .description("Installing NoiseTorch...")
+                        .build(),
+                );
+            }
+            if selected("v4l2") {
+                commands = v4l2loopback_commands(commands);
+            }
+
+            task_runner::run(
+                window_for_closure.upcast_ref(),
+                commands.build(),
+                "Mic & Camera Setup",
+            );
+        });
+    });
+}
+
+/// Whether the current user already has the PipeWire config fragment.
+fn pipewire_conf_exists(file_name: &str) -> bool {
+    dirs::config_dir().is_some_and(|dir| {
+        dir.join("pipewire/pipewire.conf.d")
+            .join(file_name)
+            .exists()
+    })
+}
+
+/// Write a PipeWire config fragment for the current user.
+fn pipewire_conf_command(file_name: &str, conf: &str, description: &str) -> Command {
+    Command::builder()
+        .normal()
+        .program("sh")
+        .args(&[
+            "-c",
+            &format!(
+                "mkdir -p \"{dir}\" && cat > \"{dir}/{file}\" << 'EOF'\n{conf}EOF",
+                dir = PIPEWIRE_CONF_DIR,
+                file = file_name,
+                conf = conf
+            ),
+        ])
+        .description(description)
+        .build()
+}
@@ -0,0 +1,1169 @@
+//! Network tools page handlers.
+//!
+//! Handles:
+//! - Encrypted DNS through systemd-resolved, with provider presets
+//! - Tailscale install, login and status
+//! - WireGuard (file or QR code) and OpenVPN imports into NetworkManager
+//! - Connecting and removing the imported VPN connections
+//! - Sharing folders over Samba and NFS, and removing the shares
+
+use crate::actions;
+use crate::core;
+use crate::core::firewall::{self, PortRule};
+use crate::core::network::{
+    self, DnsSetting, TailscaleStatus, VpnConnection, VpnKind, DNS_PROVIDERS,
+};
+use crate::core::services::ServiceState;
+use crate::core::sharing::{self, NfsExport, SambaShare};
+use crate::i18n::{gettext, gettext_f, n_};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{spawn_blocking, try_extract_widget};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button};
+use log::{info, warn};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// DNS, Tailscale, VPN and sharing state, read off the main thread.
+#[derive(Default)]
+struct State {
+    resolved: ServiceState,
+    dns: Option<DnsSetting>,
+    tailscaled: ServiceState,
+    tailscale: TailscaleStatus,
+    connections: Vec<VpnConnection>,
+    smb: ServiceState,
+    nfs: ServiceState,
+    samba_shares: Vec<SambaShare>,
+    nfs_exports: Vec<NfsExport>,
+    /// Suggested for the clients of a new NFS export.
+    subnet: Option<String>,
+}
+
+impl State {
+    fn read() -> Self {
+        let tailscaled = ServiceState::read(network::TAILSCALE_SERVICE);
+        Self {
+            resolved: ServiceState::read(network::RESOLVED_SERVICE),
+            dns: DnsSetting::read(),
+            tailscaled,
+            tailscale: if tailscaled.active {
+                TailscaleStatus::read()
+            } else {
+                TailscaleStatus::default()
+            },
+            connections: network::vpn_connections(),
+            smb: ServiceState::read(sharing::SAMBA_SERVICES[0]),
+            nfs: ServiceState::read(sharing::NFS_SERVICE),
+            samba_shares: sharing::samba_shares(),
+            nfs_exports: sharing::nfs_exports(),
+            subnet: sharing::local_subnet(),
+        }
+    }
+}
+
+struct NetworkPage {
+    window: ApplicationWindow,
+    dns_status_row: adw::ActionRow,
+    dns_provider_row: adw::ComboRow,
+    dns_strict_row: adw::SwitchRow,
+    tailscale_row: adw::ActionRow,
+    tailscale_install: Button,
+    tailscale_up: Button,
+    tailscale_down: Button,
+    connections_group: adw::PreferencesGroup,
+    /// Rows of the VPN connections, replaced on every render.
+    connection_rows: RefCell<Vec<adw::ActionRow>>,
+    samba_row: adw::ActionRow,
+    nfs_row: adw::ActionRow,
+    shares_group: adw::PreferencesGroup,
+    /// Rows of the shared folders, replaced on every render.
+    share_rows: RefCell<Vec<adw::ActionRow>>,
+    state: RefCell<State>,
+}
+
+/// Set up all handlers for the network tools page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let rows = (
+        try_extract_widget::<adw::ActionRow>(page_builder, "dns_status_row"),
+        try_extract_widget::<adw::ComboRow>(page_builder, "dns_provider_row"),
+        try_extract_widget::<adw::SwitchRow>(page_builder, "dns_strict_row"),
+        try_extract_widget::<adw::ActionRow>(page_builder, "tailscale_status_row"),
+        try_extract_widget::<adw::PreferencesGroup>(page_builder, "vpn_connections_group"),
+    );
+    let buttons = (
+        try_extract_widget::<Button>(page_builder, "btn_dns_apply"),
+        try_extract_widget::<Button>(page_builder, "btn_tailscale_install"),
+        try_extract_widget::<Button>(page_builder, "btn_tailscale_up"),
+        try_extract_widget::<Button>(page_builder, "btn_tailscale_down"),
+    );
+    let imports = (
+        try_extract_widget::<Button>(page_builder, "btn_wireguard_file"),
+        try_extract_widget::<Button>(page_builder, "btn_wireguard_qr"),
+        try_extract_widget::<Button>(page_builder, "btn_openvpn_import"),
+    );
+    let sharing = (
+        try_extract_widget::<adw::ActionRow>(page_builder, "samba_status_row"),
+        try_extract_widget::<adw::ActionRow>(page_builder, "nfs_status_row"),
+        try_extract_widget::<adw::PreferencesGroup>(page_builder, "shares_group"),
+        try_extract_widget::<Button>(page_builder, "btn_samba_share"),
+        try_extract_widget::<Button>(page_builder, "btn_nfs_export"),
+    );
+    let (
+        (
+            Some(dns_status_row),
+            Some(dns_provider_row),
+            Some(dns_strict_row),
+            Some(tailscale_row),
+            Some(connections_group),
+        ),
+        (
+            Some(dns_apply),
+            Some(tailscale_install),
+            Some(tailscale_up_button),
+            Some(tailscale_down_button),
+        ),
+        (Some(wireguard_file), Some(wireguard_qr), Some(openvpn_import)),
+        (Some(samba_row), Some(nfs_row), Some(shares_group), Some(samba_share), Some(nfs_export)),
+    ) = (rows, buttons, imports, sharing)
+    else {
+        return;
+    };
+
+    let page = Rc::new(NetworkPage {
+        window: window.clone(),
+        dns_status_row,
+        dns_provider_row,
+        dns_strict_row,
+        tailscale_row,
+        tailscale_install,
+        tailscale_up: tailscale_up_button,
+        tailscale_down: tailscale_down_button,
+        connections_group,
+        connection_rows: RefCell::new(Vec::new()),
+        samba_row,
+        nfs_row,
+        shares_group,
+        share_rows: RefCell::new(Vec::new()),
+        state: RefCell::new(State::default()),
+    });
+
+    let page_clone = page.clone();
+    dns_apply.connect_clicked(move |_| {
+        info!("Apply DNS clicked");
+        apply_dns(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    page.tailscale_install.connect_clicked(move |_| {
+        info!("Install Tailscale clicked");
+        install_tailscale(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    page.tailscale_up.connect_clicked(move |_| {
+        info!("Tailscale up clicked");
+        tailscale_up(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    page.tailscale_down.connect_clicked(move |_| {
+        info!("Tailscale down clicked");
+        tailscale_down(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    wireguard_file.connect_clicked(move |_| {
+        info!("Import WireGuard file clicked");
+        import_wireguard(&page_clone, false);
+    });
+
+    let page_clone = page.clone();
+    wireguard_qr.connect_clicked(move |_| {
+        info!("Import WireGuard QR code clicked");
+        import_wireguard(&page_clone, true);
+    });
+
+    let page_clone = page.clone();
+    openvpn_import.connect_clicked(move |_| {
+        info!("Import OpenVPN profile clicked");
+        import_openvpn(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    samba_share.connect_clicked(move |_| {
+        info!("Share folder over Samba clicked");
+        share_samba(&page_clone);
+    });
+
+    let page_clone = page.clone();
+    nfs_export.connect_clicked(move |_| {
+        info!("Export folder over NFS clicked");
+        export_nfs(&page_clone);
+    });
+
+    // Services and connections change when a task finishes
+    let page_clone = page.clone();
+    install_state::connect_refresh(move || load(&page_clone));
+}
+
+/// Read the state off the main thread, then show it.
+fn load(page: &Rc<NetworkPage>) {
+    let page = page.clone();
+    spawn_blocking(State::read, move |state| {
+        *page.state.borrow_mut() = state;
+        render(&page);
+    });
+}
+
+fn render(page: &Rc<NetworkPage>) {
+    let state = page.state.borrow();
+
+    page.dns_status_row.set_subtitle(&match state.dns {
+        Some(dns) => gettext_f(
+            "{state}, using {provider}",
+            &[
+                ("state", &gettext(state.resolved.summary())),
+                ("provider", dns.provider.name),
+            ],
+        ),
+        None => gettext_f(
+            "{state}, using the network's servers",
+            &[("state", &gettext(state.resolved.summary()))],
+        ),
+    });
+    let index = state
+        .dns
+        .and_then(|dns| DNS_PROVIDERS.iter().position(|p| *p == *dns.provider))
+        .map_or(0, |i| i + 1);
+    page.dns_provider_row.set_selected(index as u32);
+    page.dns_strict_row
+        .set_active(state.dns.is_some_and(|dns| dns.strict));
+
+    let installed = state.tailscaled.installed;
+    let connected = state.tailscale.ip.is_some();
+    page.tailscale_row
+        .set_subtitle(&match (&state.tailscale.ip, &state.tailscale.message) {
+            (Some(ip), _) => gettext_f("Connected as {ip}", &[("ip", ip)]),
+            _ if !state.tailscaled.active => gettext(state.tailscaled.summary()),
+            (None, Some(message)) => message.clone(),
+            (None, None) => gettext("Not connected"),
+        });
+    page.tailscale_install.set_visible(!installed);
+    page.tailscale_up.set_visible(installed && !connected);
+    page.tailscale_down.set_visible(connected);
+
+    for row in page.connection_rows.borrow_mut().drain(..) {
+        page.connections_group.remove(&row);
+    }
+    if state.connections.is_empty() {
+        let row = adw::ActionRow::builder()
+            .title(gettext("No VPN connections yet"))
+            .build();
+        page.connections_group.add(&row);
+        page.connection_rows.borrow_mut().push(row);
+    }
+    for connection in &state.connections {
+        let row = connection_row(page, connection);
+        page.connections_group.add(&row);
+        page.connection_rows.borrow_mut().push(row);
+    }
+
+    page.samba_row.set_subtitle(&gettext(state.smb.summary()));
+    page.nfs_row.set_subtitle(&gettext(state.nfs.summary()));
+
+    for row in page.share_rows.borrow_mut().drain(..) {
+        page.shares_group.remove(&row);
+    }
+    if state.samba_shares.is_empty() && state.nfs_exports.is_empty() {
+        let row = adw::ActionRow::builder()
+            .title(gettext("No shared folders yet"))
+            .build();
+        page.shares_group.add(&row);
+        page.share_rows.borrow_mut().push(row);
+    }
+    for share in &state.samba_shares {
+        let row = samba_share_row(page, share);
+        page.shares_group.add(&row);
+        page.share_rows.borrow_mut().push(row);
+    }
+    for export in &state.nfs_exports {
+        let row = nfs_export_row(page, export);
+        page.shares_group.add(&row);
+        page.share_rows.borrow_mut().push(row);
+    }
+}
+
+fn connection_row(page: &Rc<NetworkPage>, connection: &VpnConnection) -> adw::ActionRow {
+    let kind = match connection.kind {
+        VpnKind::WireGuard => "WireGuard",
+        VpnKind::Plugin => "VPN",
+    };
+    // Connection names come from imported files, not markup
+    let row = adw::ActionRow::builder()
+        .title(connection.name.as_str())
+        .subtitle(if connection.active {
+            gettext_f("{kind}, connected", &[("kind", kind)])
+        } else {
+            kind.to_string()
+        })
+        .use_markup(false)
+        .build();
+
+    let toggle_button = Button::builder()
+        .label(if connection.active {
+            gettext("Disconnect")
+        } else {
+            gettext("Connect")
+        })
+        .valign(gtk4::Align::Center)
+        .build();
+    let page_clone = page.clone();
+    let toggle = connection.clone();
+    toggle_button.connect_clicked(move |_| {
+        let verb = if toggle.active { "down" } else { "up" };
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("nmcli")
+                    .args(&["connection", verb, "uuid", &toggle.uuid])
+                    .description(&format!(
+                        "{} {}...",
+                        if toggle.active {
+                            "Disconnecting"
+                        } else {
+                            "Connecting"
+                        },
+                        toggle.name
+                    ))
+                    .build(),
+            )
+            .build();
+        task_runner::run(page_clone.window.upcast_ref(), commands, "VPN Connection");
+    });
+    row.add_suffix(&toggle_button);
+
+    let delete_button = Button::builder()
+        .icon_name("user-trash-symbolic")
+        .tooltip_text(gettext("Remove"))
+        .valign(gtk4::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+    let page_clone = page.clone();
+    let connection = connection.clone();
+    delete_button.connect_clicked(move |_| {
+        let window = page_clone.window.clone();
+        let connection = connection.clone();
+        show_warning_confirmation(
+            page_clone.window.upcast_ref(),
+            &gettext("Remove VPN Connection"),
+            &gettext_f(
+                "The connection <b>{name}</b> will be <span foreground=\"red\" weight=\"bold\">removed</span> \
+                 from NetworkManager. Import the config again to get it back.",
+                &[("name", &gtk4::glib::markup_escape_text(&connection.name))],
+            ),
+            move || {
+                let commands = CommandSequence::new()
+                    .then(
+                        Command::builder()
+                            .normal()
+                            .program("nmcli")
+                            .args(&["connection", "delete", "uuid", &connection.uuid])
+                            .description(&format!("Removing {}...", connection.name))
+                            .build(),
+                    )
+                    .build();
+                task_runner::run(window.upcast_ref(), commands, "VPN Connection");
+            },
+        );
+    });
+    row.add_suffix(&delete_button);
+
+    row
+}
+
+// ─── DNS ────────────────────────────────────────────────────────────────────
+
+fn apply_dns(page: &NetworkPage) {
+    let selected = page.dns_provider_row.selected() as usize;
+    let commands = match selected.checked_sub(1).and_then(|i| DNS_PROVIDERS.get(i)) {
+        Some(provider) => {
+            let setting = DnsSetting {
+                provider,
+                strict: page.dns_strict_row.is_active(),
+            };
+            info!("Setting DNS to {}", provider.name);
+
+            let staged = match core::staging::stage("xero-dns.conf", &setting.dropin()) {
+                Ok(path) => path,
+                Err(e) => {
+                    warn!("Failed to stage the DNS settings: {:#}", e);
+                    show_error(&page.window, &gettext("Couldn't prepare the DNS settings."));
+                    return;
+                }
+            };
+            let staged = staged.to_string_lossy();
+
+            CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("install")
+                        .args(&["-Dm644", &staged, network::DNS_DROPIN])
+                        .description(&format!("Setting {} as DNS provider...", provider.name))
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("systemctl")
+                        .args(&["enable", "--now", network::RESOLVED_SERVICE])
+                        .description("Enabling systemd-resolved...")
+                        .build(),
+                )
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("sh")
+                        .args(&["-c", network::RESOLV_CONF_SCRIPT])
+                        .description("Pointing /etc/resolv.conf at systemd-resolved...")
+                        .build(),
+                )
+        }
+        None => {
+            info!("Setting DNS back to the network's servers");
+            CommandSequence::new().then(
+                Command::builder()
+                    .privileged()
+                    .program("rm")
+                    .args(&["-f", network::DNS_DROPIN])
+                    .description("Removing the DNS provider...")
+                    .build(),
+            )
+        }
+    };
+
+    let commands = commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["try-restart", network::RESOLVED_SERVICE])
+                .description("Restarting systemd-resolved...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["try-restart", "NetworkManager.service"])
+                .description("Restarting NetworkManager...")
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Encrypted DNS");
+}
+
+// ─── Tailscale ──────────────────────────────────────────────────────────────
+
+fn install_tailscale(page: &NetworkPage) {
+    super::run_action(&page.window, &actions::network::TAILSCALE);
+}
+
+/// Log in and connect. The user becomes the operator, so the tray app and
+/// `tailscale` commands work without root afterwards.
+fn tailscale_up(page: &NetworkPage) {
+    let user = crate::config::env::get().user.clone();
+    let operator = format!("--operator={}", user);
+
+    let mut commands = CommandSequence::new();
+    if !page.state.borrow().tailscaled.active {
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", network::TAILSCALE_SERVICE])
+                .description("Starting the Tailscale daemon...")
+                .build(),
+        );
+    }
+    let commands = commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("tailscale")
+                .args(&["up", &operator])
+                .description("Connecting, open the login link below if one is shown...")
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Tailscale");
+}
+
+fn tailscale_down(page: &NetworkPage) {
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("tailscale")
+                .args(&["down"])
+                .description("Disconnecting from the tailnet...")
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Tailscale");
+}
+
+// ─── VPN import ─────────────────────────────────────────────────────────────
+
+/// Let the user pick a file matching `patterns`, then call `then` with it.
+fn pick_file(
+    window: &ApplicationWindow,
+    title: &str,
+    filter_name: &str,
+    patterns: &[&str],
+    then: impl FnOnce(PathBuf) + 'static,
+) {
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some(&gettext(filter_name)));
+    for pattern in patterns {
+        filter.add_pattern(pattern);
+    }
+    let filters = gtk4::gio::ListStore::new::<gtk4::FileFilter>();
+    filters.append(&filter);
+
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title(&gettext(title));
+    dialog.set_filters(Some(&filters));
+
+    let window = window.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(file) = dialog.open_future(Some(&window)).await else {
+            // User cancelled
+            return;
+        };
+        if let Some(path) = file.path() {
+            then(path);
+        }
+    });
+}
+
+/// Install `package` from the repositories first if it's missing.
+fn with_package(commands: CommandSequence, package: &str) -> CommandSequence {
+    if core::is_package_installed(package) {
+        return commands;
+    }
+    commands.then(
+        Command::builder()
+            .privileged()
+            .program("pacman")
+            .args(&["-S", "--noconfirm", "--needed", package])
+            .description(&format!("Installing {}...", package))
+            .build(),
+    )
+}
+
+fn import_wireguard(page: &NetworkPage, qr: bool) {
+    let (title, filter_name, patterns): (&str, &str, &[&str]) = if qr {
+        (
+            n_("Select the QR Code Image"),
+            n_("Images"),
+            &["*.png", "*.jpg", "*.jpeg", "*.webp"],
+        )
+    } else {
+        (
+            n_("Select the WireGuard Config"),
+            n_("WireGuard configs"),
+            &["*.conf"],
+        )
+    };
+
+    let window = page.window.clone();
+    pick_file(&page.window, title, filter_name, patterns, move |path| {
+        let name = network::wireguard_name(&path);
+        info!("Importing WireGuard config {:?} as {}", path, name);
+
+        let mut commands = CommandSequence::new();
+        if qr {
+            commands = with_package(commands, "zbar");
+        }
+        let commands = commands
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        network::WIREGUARD_IMPORT_SCRIPT,
+                        "sh",
+                        &path.to_string_lossy(),
+                        &name,
+                        if qr { "qr" } else { "file" },
+                    ])
+                    .description(&format!("Importing {}...", name))
+                    .build(),
+            )
+            .build();
+        task_runner::run(window.upcast_ref(), commands, "WireGuard Import");
+    });
+}
+
+fn import_openvpn(page: &NetworkPage) {
+    let window = page.window.clone();
+    pick_file(
+        &page.window,
+        n_("Select the OpenVPN Profile"),
+        n_("OpenVPN profiles"),
+        &["*.ovpn", "*.conf"],
+        move |path| {
+            info!("Importing OpenVPN profile {:?}", path);
+            let commands = with_package(CommandSequence::new(), "networkmanager-openvpn")
+                .then(
+                    Command::builder()
+                        .normal()
+                        .program("nmcli")
+                        .args(&[
+                            "connection",
+                            "import",
+                            "type",
+                            "openvpn",
+                            "file",
+                            &path.to_string_lossy(),
+                        ])
+                        .description("Importing the OpenVPN profile...")
+                        .build(),
+                )
+                .build();
+            task_runner::run(window.upcast_ref(), commands, "OpenVPN Import");
+        },
+    );
+}
+
+// ─── File sharing ───────────────────────────────────────────────────────────
+
+/// Let the user pick a folder, then call `then` with it.
+fn pick_folder(window: &ApplicationWindow, title: &str, then: impl FnOnce(PathBuf) + 'static) {
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title(&gettext(title));
+
+    let window = window.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let Ok(folder) = dialog.select_folder_future(Some(&window)).await else {
+            // User cancelled
+            return;
+        };
+        if let Some(path) = folder.path() {
+            then(path);
+        }
+    });
+}
+
+/// Ask for the details of a share in a dialog holding `rows`.
+fn show_share_form(
+    page: &NetworkPage,
+    heading: &str,
+    body: &str,
+    rows: &[&gtk4::Widget],
+    on_share: impl Fn() + 'static,
+) {
+    let list = gtk4::ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    for row in rows {
+        list.append(*row);
+    }
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext(heading))
+        .body(body)
+        .extra_child(&list)
+        .build();
+    dialog.add_responses(&[
+        ("cancel", gettext("Cancel").as_str()),
+        ("share", gettext("Share").as_str()),
+    ]);
+    dialog.set_response_appearance("share", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("share"));
+    dialog.set_close_response("cancel");
+    dialog.connect_response(Some("share"), move |_, _| on_share());
+    dialog.present(Some(&page.window));
+}
+
+/// Stage `contents` as `name`, to be installed as root by the task.
+fn stage_file(window: &ApplicationWindow, name: &str, contents: &str) -> Option<String> {
+    match core::staging::stage(name, contents) {
+        Ok(path) => Some(path.to_string_lossy().into_owned()),
+        Err(e) => {
+            warn!("Failed to stage {}: {:#}", name, e);
+            show_error(window, &gettext("Couldn't prepare the sharing settings."));
+            None
+        }
+    }
+}
+
+/// Open `rules` in the running firewall, if there is one.
+fn open_ports(commands: CommandSequence, rules: &[PortRule], comment: &str) -> CommandSequence {
+    match firewall::active() {
+        Some(fw) => {
+            info!("Opening {} ports in {}", comment, fw.name());
+            commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &fw.open_ports_script(rules, comment)])
+                    .description(&format!("Opening {} ports in the firewall...", comment))
+                    .build(),
+            )
+        }
+        None => {
+            info!("No active firewall, leaving ports alone");
+            commands
+        }
+    }
+}
+
+fn access(writable: bool) -> String {
+    if writable {
+        gettext("read and write")
+    } else {
+        gettext("read only")
+    }
+}
+
+/// Trash button asking before `remove` stops sharing `folder`.
+fn stop_sharing_button(
+    page: &Rc<NetworkPage>,
+    folder: &str,
+    remove: impl Fn(&NetworkPage) + 'static,
+) -> Button {
+    let button = Button::builder()
+        .icon_name("user-trash-symbolic")
+        .tooltip_text(gettext("Stop Sharing"))
+        .valign(gtk4::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+    let page = page.clone();
+    let folder = folder.to_string();
+    let remove = Rc::new(remove);
+    button.connect_clicked(move |_| {
+        let page_clone = page.clone();
+        let remove = remove.clone();
+        show_warning_confirmation(
+            page.window.upcast_ref(),
+            &gettext("Stop Sharing"),
+            &gettext_f(
+                "The folder <b>{folder}</b> will <span foreground=\"red\" weight=\"bold\">no longer \
+                 be shared</span>. The files in it are left alone.",
+                &[("folder", &gtk4::glib::markup_escape_text(&folder))],
+            ),
+            move || remove(&page_clone),
+        );
+    });
+    button
+}
+
+fn samba_share_row(page: &Rc<NetworkPage>, share: &SambaShare) -> adw::ActionRow {
+    // Folder names aren't markup
+    let row = adw::ActionRow::builder()
+        .title(share.name.as_str())
+        .subtitle(gettext_f(
+            "Samba, {path}, {access}",
+            &[("path", &share.path), ("access", &access(share.writable))],
+        ))
+        .use_markup(false)
+        .build();
+    let name = share.name.clone();
+    row.add_suffix(&stop_sharing_button(page, &share.path, move |page| {
+        remove_samba_share(page, &name)
+    }));
+    row
+}
+
+fn nfs_export_row(page: &Rc<NetworkPage>, export: &NfsExport) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(export.path.as_str())
+        .subtitle(gettext_f(
+            "NFS, {access} for {clients}",
+            &[
+                ("access", &access(export.writable)),
+                ("clients", &export.clients),
+            ],
+        ))
+        .use_markup(false)
+        .build();
+    let path = export.path.clone();
+    row.add_suffix(&stop_sharing_button(page, &export.path, move |page| {
+        remove_nfs_export(page, &path)
+    }));
+    row
+}
+
+fn share_samba(page: &Rc<NetworkPage>) {
+    let page_clone = page.clone();
+    pick_folder(
+        &page.window,
+        n_("Select the Folder to Share"),
+        move |path| show_samba_form(&page_clone, &path),
+    );
+}
+
+fn show_samba_form(page: &Rc<NetworkPage>, path: &Path) {
+    let user = crate::config::env::get().user.clone();
+    // smbpasswd needs a password the first time
+    let first = page.state.borrow().samba_shares.is_empty();
+
+    let name_row = adw::EntryRow::builder()
+        .title(gettext("Share Name"))
+        .text(sharing::share_name(path))
+        .build();
+    let writable_row = adw::SwitchRow::builder()
+        .title(gettext("Allow Changes"))
+        .build();
+    let password_row = adw::PasswordEntryRow::builder()
+        .title(gettext("Samba Password"))
+        .build();
+    let confirm_row = adw::PasswordEntryRow::builder()
+        .title(gettext("Confirm Password"))
+        .build();
+
+    let mut body = gettext_f(
+        "Other computers sign in as {user} with the Samba password.",
+        &[("user", &user)],
+    );
+    if !first {
+        body.push(' ');
+        body.push_str(&gettext("Leave it empty to keep the current one."));
+    }
+    let page_clone = page.clone();
+    let path = path.to_string_lossy().to_string();
+    let name_row_clone = name_row.clone();
+    let writable_row_clone = writable_row.clone();
+    let password_row_clone = password_row.clone();
+    let confirm_row_clone = confirm_row.clone();
+    show_share_form(
+        page,
+        n_("Share Folder"),
+        &body,
+        &[
+            name_row.upcast_ref(),
+            writable_row.upcast_ref(),
+            password_row.upcast_ref(),
+            confirm_row.upcast_ref(),
+        ],
+        move || {
+            let name = name_row_clone.text().to_string();
+            if !sharing::is_valid_share_name(&name) {
+                show_error(
+                    &page_clone.window,
+                    &gettext("Use up to 32 letters, digits, - and _ for the share name."),
+                );
+                return;
+            }
+            let password = password_row_clone.text().to_string();
+            if password != confirm_row_clone.text() {
+                show_error(&page_clone.window, &gettext("The passwords don't match."));
+                return;
+            }
+            if password.is_empty() && first {
+                show_error(
+                    &page_clone.window,
+                    &gettext("Set a Samba password for the first share."),
+                );
+                return;
+            }
+            let share = SambaShare {
+                name,
+                path: path.clone(),
+                writable: writable_row_clone.is_active(),
+                user: user.clone(),
+            };
+            apply_samba_share(&page_clone, share, &password);
+        },
+    );
+}
+
+/// Add or replace `share`, then install Samba, set the password and open
+/// the firewall as needed.
+fn apply_samba_share(page: &NetworkPage, share: SambaShare, password: &str) {
+    info!("Sharing {} over Samba as {}", share.path, share.name);
+
+    let mut shares = page.state.borrow().samba_shares.clone();
+    shares.retain(|s| s.name != share.name && s.path != share.path);
+    shares.push(share.clone());
+    let Some(tmp_shares) = stage_file(
+        &page.window,
+        "xero-shares.conf",
+        &sharing::render_samba(&shares),
+    ) else {
+        return;
+    };
+
+    let mut commands = with_package(CommandSequence::new(), "samba")
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", sharing::SAMBA_SETUP_SCRIPT])
+                .description("Preparing the Samba configuration...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("install")
+                .args(&["-Dm644", &tmp_shares, sharing::SAMBA_SHARES])
+                .description(&format!("Sharing {}...", share.path))
+                .build(),
+        );
+
+    if !password.is_empty() {
+        let staged = match sharing::stage_password(password) {
+            Ok(staged) => staged,
+            Err(e) => {
+                warn!("Failed to stage the Samba password: {:#}", e);
+                show_error(
+                    &page.window,
+                    &gettext("Couldn't prepare the Samba password."),
+                );
+                return;
+            }
+        };
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    sharing::SAMBA_PASSWORD_SCRIPT,
+                    "sh",
+                    &share.user,
+                    &staged.to_string_lossy(),
+                ])
+                .description(&format!("Setting the Samba password of {}...", share.user))
+                .build(),
+        );
+    }
+
+    let mut enable = vec!["enable", "--now"];
+    enable.extend(sharing::SAMBA_SERVICES);
+    let commands = commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&enable)
+                .description("Starting Samba...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["reload", sharing::SAMBA_SERVICES[0]])
+                .description("Reloading the shares...")
+                .build(),
+        );
+
+    let host = gtk4::glib::host_name();
+    let commands = open_ports(commands, &sharing::SAMBA_PORTS, "Samba")
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[&format!(
+                    "Open smb://{}/{} on Linux and macOS, or \\\\{}\\{} on Windows.",
+                    host, share.name, host, share.name
+                )])
+                .description("Folder shared")
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Share Folder");
+}
+
+fn remove_samba_share(page: &NetworkPage, name: &str) {
+    info!("Removing Samba share {}", name);
+
+    let mut shares = page.state.borrow().samba_shares.clone();
+    shares.retain(|s| s.name != name);
+    let Some(tmp_shares) = stage_file(
+        &page.window,
+        "xero-shares.conf",
+        &sharing::render_samba(&shares),
+    ) else {
+        return;
+    };
+
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("install")
+                .args(&["-Dm644", &tmp_shares, sharing::SAMBA_SHARES])
+                .description(&format!("Removing the share {}...", name))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["try-reload-or-restart", sharing::SAMBA_SERVICES[0]])
+                .description("Reloading the shares...")
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Stop Sharing");
+}
+
+fn export_nfs(page: &Rc<NetworkPage>) {
+    let page_clone = page.clone();
+    pick_folder(
+        &page.window,
+        n_("Select the Folder to Export"),
+        move |path| show_nfs_form(&page_clone, &path),
+    );
+}
+
+fn show_nfs_form(page: &Rc<NetworkPage>, path: &Path) {
+    let subnet = page
+        .state
+        .borrow()
+        .subnet
+        .clone()
+        .unwrap_or_else(|| "*".to_string());
+
+    let clients_row = adw::EntryRow::builder()
+        .title(gettext("Allowed Clients"))
+        .text(subnet)
+        .build();
+    let writable_row = adw::SwitchRow::builder()
+        .title(gettext("Allow Changes"))
+        .build();
+
+    let page_clone = page.clone();
+    let path = path.to_string_lossy().to_string();
+    let clients_row_clone = clients_row.clone();
+    let writable_row_clone = writable_row.clone();
+    show_share_form(
+        page,
+        n_("Export Folder"),
+        &gettext(
+            "Computers in this address range can mount the folder, e.g. 192.168.1.0/24, \
+             or * for any. Files are accessed with the user ids of the other computer.",
+        ),
+        &[clients_row.upcast_ref(), writable_row.upcast_ref()],
+        move || {
+            let clients = clients_row_clone.text().trim().to_string();
+            if clients.is_empty()
+                || clients.contains(|c: char| c.is_whitespace() || c == '(' || c == ')')
+            {
+                show_error(
+                    &page_clone.window,
+                    &gettext("Enter an address range like 192.168.1.0/24."),
+                );
+                return;
+            }
+            let export = NfsExport {
+                path: path.clone(),
+                clients,
+                writable: writable_row_clone.is_active(),
+            };
+            apply_nfs_export(&page_clone, export);
+        },
+    );
+}
+
+/// Add or replace `export`, then install and start the NFS server and open
+/// the firewall as needed.
+fn apply_nfs_export(page: &NetworkPage, export: NfsExport) {
+    info!("Exporting {} over NFS to {}", export.path, export.clients);
+
+    let mut exports = page.state.borrow().nfs_exports.clone();
+    exports.retain(|e| e.path != export.path);
+    exports.push(export.clone());
+    let Some(tmp_exports) =
+        stage_file(&page.window, "xero.exports", &sharing::render_nfs(&exports))
+    else {
+        return;
+    };
+
+    let commands = with_package(CommandSequence::new(), "nfs-utils")
+        .then(
+            Command::builder()
+                .privileged()
+                .program("install")
+                .args(&["-Dm644", &tmp_exports, sharing::NFS_EXPORTS])
+                .description(&format!("Exporting {}...", export.path))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", sharing::NFS_SERVICE])
+                .description("Starting the NFS server...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("exportfs")
+                .args(&["-ra"])
+                .description("Reloading the exports...")
+                .build(),
+        );
+
+    let commands = open_ports(commands, &sharing::NFS_PORTS, "NFS")
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[&format!(
+                    "Mount it from other computers with: mount -t nfs {}:\"{}\" /mnt",
+                    gtk4::glib::host_name(),
+                    export.path
+                )])
+                .description("Folder exported")
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Export Folder");
+}
+
+fn remove_nfs_export(page: &NetworkPage, path: &str) {
+    info!("Removing NFS export {}", path);
+
+    let mut exports = page.state.borrow().nfs_exports.clone();
+    exports.retain(|e| e.path != path);
+    let Some(tmp_exports) =
+        stage_file(&page.window, "xero.exports", &sharing::render_nfs(&exports))
+    else {
+        return;
+    };
+
+    let commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("install")
+                .args(&["-Dm644", &tmp_exports, sharing::NFS_EXPORTS])
+                .description(&format!("Removing the export of {}...", path))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("exportfs")
+                .args(&["-ra"])
+                .description("Reloading the exports...")
+                .build(),
+        )
+        .build();
+    task_runner::run(page.window.upcast_ref(), commands, "Stop Sharing");
+}
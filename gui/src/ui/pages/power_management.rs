@@ -0,0 +1,238 @@
+//! Power management page button handlers.
+//!
+//! Handles:
+//! - Laptop detection and status
+//! - Switching between power-profiles-daemon, TLP and tuned-ppd
+//! - Battery charge threshold for vendors with kernel support
+
+use crate::core::power::{self, InstalledDaemon, PowerDaemon};
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::install_state;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{
+    disable_action_row, get_combo_row_value, spawn_blocking, try_extract_widget,
+};
+use adw::prelude::*;
+use gtk4::{ApplicationWindow, Builder, Button, Label};
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Persisted charge threshold, re-applied at boot by systemd-tmpfiles.
+const THRESHOLD_TMPFILES: &str = "/etc/tmpfiles.d/xero-charge-threshold.conf";
+
+/// Set up all handlers for the power management page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let buttons: Vec<(PowerDaemon, Button)> = [
+        (PowerDaemon::PowerProfiles, "btn_power_ppd"),
+        (PowerDaemon::Tlp, "btn_power_tlp"),
+        (PowerDaemon::TunedPpd, "btn_power_tuned"),
+    ]
+    .into_iter()
+    .filter_map(|(daemon, id)| Some((daemon, try_extract_widget(page_builder, id)?)))
+    .collect();
+
+    // Filled by the refresh; the buttons stay insensitive until then.
+    let installed: Rc<RefCell<Vec<InstalledDaemon>>> = Rc::default();
+    for (daemon, button) in &buttons {
+        button.set_sensitive(false);
+        setup_daemon_button(*daemon, button, window, &installed);
+    }
+
+    setup_charge_threshold(page_builder, window);
+
+    // Status labels are informational; a missing one shouldn't stop the buttons
+    let label = |id: &str| try_extract_widget(page_builder, id).unwrap_or_else(|| Label::new(None));
+    let device_label = label("power_device_label");
+    let daemon_label = label("power_daemon_label");
+
+    // Refresh states along with the shared install state, e.g. after a switch.
+    install_state::connect_refresh(move || {
+        async_refresh_states(&buttons, &device_label, &daemon_label, &installed);
+    });
+}
+
+/// Build the full switch sequence for a power daemon.
+fn build_switch_commands(target: PowerDaemon, installed: &[InstalledDaemon]) -> CommandSequence {
+    let mut commands = power::remove_conflicts(CommandSequence::new(), target, installed);
+
+    let mut install_args = vec!["-S", "--noconfirm", "--needed"];
+    install_args.extend_from_slice(target.packages());
+    commands = commands.then(
+        Command::builder()
+            .aur()
+            .args(&install_args)
+            .description(&format!("Installing {}...", target.name()))
+            .build(),
+    );
+
+    // TLP manages radio devices itself; systemd-rfkill would fight it.
+    if target == PowerDaemon::Tlp {
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["mask", "systemd-rfkill.service", "systemd-rfkill.socket"])
+                .description("Masking systemd-rfkill (managed by TLP)...")
+                .build(),
+        );
+    }
+
+    let mut enable_args = vec!["enable", "--now"];
+    enable_args.extend_from_slice(target.services());
+    enable_args.extend_from_slice(target.extra_services());
+    commands.then(
+        Command::builder()
+            .privileged()
+            .program("systemctl")
+            .args(&enable_args)
+            .description(&format!("Enabling {} service...", target.name()))
+            .build(),
+    )
+}
+
+fn setup_daemon_button(
+    daemon: PowerDaemon,
+    button: &Button,
+    window: &ApplicationWindow,
+    installed: &Rc<RefCell<Vec<InstalledDaemon>>>,
+) {
+    let window = window.clone();
+    let installed = installed.clone();
+    button.connect_clicked(move |_| {
+        info!("Power daemon button clicked: {}", daemon.name());
+
+        let commands = build_switch_commands(daemon, &installed.borrow()).build();
+        task_runner::run(
+            window.upcast_ref(),
+            commands,
+            &gettext_f("Switch to {daemon}", &[("daemon", daemon.name())]),
+        );
+    });
+}
+
+/// Check daemon and device state off the main thread, then update the page.
+fn async_refresh_states(
+    buttons: &[(PowerDaemon, Button)],
+    device_label: &Label,
+    daemon_label: &Label,
+    installed: &Rc<RefCell<Vec<InstalledDaemon>>>,
+) {
+    let buttons = buttons.to_vec();
+    let device_label = device_label.clone();
+    let daemon_label = daemon_label.clone();
+    let installed = installed.clone();
+    spawn_blocking(
+        || {
+            (
+                power::is_laptop(),
+                power::vendor(),
+                power::installed_daemons(),
+            )
+        },
+        move |(laptop, vendor, daemons)| {
+            let active = daemons.first().map(|d| d.daemon);
+            *installed.borrow_mut() = daemons;
+
+            let kind = if laptop {
+                gettext("Laptop")
+            } else {
+                gettext("Desktop")
+            };
+            if vendor.is_empty() {
+                device_label.set_text(&kind);
+            } else {
+                device_label.set_text(&format!("{} ({})", kind, vendor));
+            }
+
+            match active {
+                Some(daemon) => daemon_label.set_text(daemon.name()),
+                None => daemon_label.set_text(&gettext("None")),
+            }
+
+            for (daemon, button) in &buttons {
+                let is_active = active == Some(*daemon);
+                button.set_sensitive(!is_active);
+                if is_active {
+                    button.set_label(&gettext("Active"));
+                    button.remove_css_class("suggested-action");
+                } else if active.is_some() {
+                    button.set_label(&gettext("Switch"));
+                    button.add_css_class("suggested-action");
+                } else {
+                    button.set_label(&gettext("Install"));
+                    button.add_css_class("suggested-action");
+                }
+            }
+        },
+    );
+}
+
+fn setup_charge_threshold(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_apply_threshold") else {
+        return;
+    };
+    let Some(group) = try_extract_widget::<adw::PreferencesGroup>(builder, "group_battery") else {
+        disable_action_row(&button);
+        return;
+    };
+    let Some(combo) = try_extract_widget::<adw::ComboRow>(builder, "combo_charge_threshold") else {
+        disable_action_row(&button);
+        return;
+    };
+
+    let paths = power::charge_threshold_paths();
+    group.set_visible(!paths.is_empty());
+    if paths.is_empty() {
+        return;
+    }
+
+    if let Some(current) = power::current_charge_threshold() {
+        let current = current.to_string();
+        if let Some(model) = combo.model() {
+            for i in 0..model.n_items() {
+                let item = model.item(i).and_downcast::<gtk4::StringObject>();
+                if item.is_some_and(|s| s.string() == current) {
+                    combo.set_selected(i);
+                }
+            }
+        }
+    }
+
+    let window = window.clone();
+    button.connect_clicked(move |_| {
+        info!("Apply charge threshold button clicked");
+
+        let Some(value) = get_combo_row_value(&combo) else {
+            return;
+        };
+
+        let mut apply = Vec::new();
+        let mut tmpfiles = Vec::new();
+        for path in &paths {
+            let path = path.to_string_lossy();
+            apply.push(format!("echo {} > {}", value, path));
+            tmpfiles.push(format!("w {} - - - - {}", path, value));
+        }
+
+        let script = format!(
+            "{} && printf '%s\\n' '{}' > {}",
+            apply.join(" && "),
+            tmpfiles.join("' '"),
+            THRESHOLD_TMPFILES
+        );
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &script])
+                    .description(&format!("Limiting battery charge to {}%...", value))
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window.upcast_ref(), commands, "Battery Charge Threshold");
+    });
+}
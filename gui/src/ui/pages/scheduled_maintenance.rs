@@ -0,0 +1,244 @@
+//! Scheduled maintenance page handlers.
+//!
+//! Each job is a systemd timer (`xero-maint-<job>.timer`) that triggers the
+//! shared `xero-maint@<job>.service`, which runs the headless `xero-maint`
+//! runner shipped in the toolkit's scripts directory.
+
+use crate::core;
+use crate::i18n::{gettext, gettext_f, n_};
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{is_service_enabled, try_extract_widget};
+use adw::prelude::*;
+use gtk4::glib::SignalHandlerId;
+use gtk4::{ApplicationWindow, Builder};
+use log::{info, warn};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// A recurring maintenance job.
+struct MaintenanceJob {
+    /// Job name passed to the runner and used in unit names.
+    id: &'static str,
+    switch_id: &'static str,
+    /// systemd `OnCalendar=` expression.
+    calendar: &'static str,
+    description: &'static str,
+    /// Package providing the tool the job relies on, installed on enable.
+    package: Option<&'static str>,
+}
+
+const MAINTENANCE_JOBS: &[MaintenanceJob] = &[
+    MaintenanceJob {
+        id: "mirrors",
+        switch_id: "switch_maint_mirrors",
+        calendar: "weekly",
+        description: n_("Refresh mirrorlists"),
+        package: Some("rate-mirrors"),
+    },
+    MaintenanceJob {
+        id: "paccache",
+        switch_id: "switch_maint_paccache",
+        calendar: "monthly",
+        description: n_("Trim package cache"),
+        package: Some("pacman-contrib"),
+    },
+    MaintenanceJob {
+        id: "flatpak",
+        switch_id: "switch_maint_flatpak",
+        calendar: "weekly",
+        description: n_("Update Flatpak apps"),
+        package: None,
+    },
+    MaintenanceJob {
+        id: "scrub",
+        switch_id: "switch_maint_scrub",
+        calendar: "monthly",
+        description: n_("Btrfs scrub"),
+        package: None,
+    },
+    MaintenanceJob {
+        id: "orphans",
+        switch_id: "switch_maint_orphans",
+        calendar: "weekly",
+        description: n_("Orphan package report"),
+        package: None,
+    },
+];
+
+const SERVICE_TEMPLATE: &str = "xero-maint@.service";
+const TIMER_TEMPLATE: &str = "xero-maint.timer.in";
+const UNIT_DIR: &str = "/etc/systemd/system";
+
+fn timer_name(job: &MaintenanceJob) -> String {
+    format!("xero-maint-{}.timer", job.id)
+}
+
+/// Timers of all maintenance jobs, enabled or not.
+pub fn timer_names() -> Vec<String> {
+    MAINTENANCE_JOBS.iter().map(timer_name).collect()
+}
+
+/// Set up all handlers for the scheduled maintenance page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    for job in MAINTENANCE_JOBS {
+        setup_job(page_builder, window, job);
+    }
+}
+
+fn setup_job(builder: &Builder, window: &ApplicationWindow, job: &'static MaintenanceJob) {
+    let Some(switch) = try_extract_widget::<adw::SwitchRow>(builder, job.switch_id) else {
+        return;
+    };
+
+    // Set the initial state before connecting so it doesn't trigger a run.
+    switch.set_active(is_service_enabled(&timer_name(job)));
+
+    let handler: Rc<RefCell<Option<SignalHandlerId>>> = Rc::default();
+    let handler_clone = handler.clone();
+    let window = window.clone();
+    let id = switch.connect_active_notify(move |sw| {
+        let enabling = sw.is_active();
+        info!(
+            "Maintenance job toggle: {} -> {}",
+            job.id,
+            if enabling { "enable" } else { "disable" }
+        );
+
+        let commands = if enabling {
+            match build_enable_commands(job) {
+                Some(commands) => commands,
+                None => {
+                    // Blocked, or turning it back off would unschedule the job
+                    if let Some(id) = handler_clone.borrow().as_ref() {
+                        sw.block_signal(id);
+                        sw.set_active(false);
+                        sw.unblock_signal(id);
+                    }
+                    return;
+                }
+            }
+        } else {
+            build_disable_commands(job)
+        };
+
+        let title = if enabling {
+            gettext_f("Schedule: {job}", &[("job", &gettext(job.description))])
+        } else {
+            gettext_f("Unschedule: {job}", &[("job", &gettext(job.description))])
+        };
+
+        task_runner::run(window.upcast_ref(), commands.build(), &title);
+    });
+    *handler.borrow_mut() = Some(id);
+}
+
+/// Render the timer from its template and build the install sequence.
+fn build_enable_commands(job: &MaintenanceJob) -> Option<CommandSequence> {
+    let systemd_dir = crate::config::paths::systemd();
+    let template_path = systemd_dir.join(TIMER_TEMPLATE);
+
+    let Ok(template) = std::fs::read_to_string(&template_path) else {
+        warn!("Failed to read timer template {:?}", template_path);
+        return None;
+    };
+
+    let timer = template
+        .replace("@JOB@", job.id)
+        .replace("@CALENDAR@", job.calendar)
+        .replace("@DESCRIPTION@", job.description);
+
+    let timer_name = timer_name(job);
+    let staged = match core::staging::stage(&timer_name, &timer) {
+        Ok(path) => path,
+        Err(e) => {
+            warn!("Failed to stage {}: {:#}", timer_name, e);
+            return None;
+        }
+    };
+    let staged = staged.to_string_lossy();
+
+    let mut commands = CommandSequence::new();
+
+    if let Some(pkg) = job.package {
+        if !core::is_package_installed(pkg) {
+            commands = commands.then(
+                Command::builder()
+                    .aur()
+                    .args(&["-S", "--noconfirm", "--needed", pkg])
+                    .description(&format!("Installing {}...", pkg))
+                    .build(),
+            );
+        }
+    }
+
+    let service_src = systemd_dir.join(SERVICE_TEMPLATE);
+    let service_dst = format!("{}/{}", UNIT_DIR, SERVICE_TEMPLATE);
+    let timer_dst = format!("{}/{}", UNIT_DIR, timer_name);
+
+    commands = commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("install")
+                .args(&["-m644", &service_src.to_string_lossy(), &service_dst])
+                .description("Installing maintenance service...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("install")
+                .args(&["-m644", &staged, &timer_dst])
+                .description(&format!("Installing {} timer...", job.calendar))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["daemon-reload"])
+                .description("Reloading systemd...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", &timer_name])
+                .description("Enabling timer...")
+                .build(),
+        );
+
+    Some(commands)
+}
+
+fn build_disable_commands(job: &MaintenanceJob) -> CommandSequence {
+    let timer_name = timer_name(job);
+    let timer_path = format!("{}/{}", UNIT_DIR, timer_name);
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["disable", "--now", &timer_name])
+                .description("Disabling timer...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("rm")
+                .args(&["-f", &timer_path])
+                .description("Removing timer unit...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["daemon-reload"])
+                .description("Reloading systemd...")
+                .build(),
+        )
+}
@@ -1,26 +1,45 @@
 //! Servicing and system tweaks page button handlers.
 
 use adw::prelude::*;
+use crate::actions;
 use crate::config;
 use crate::core;
+use crate::core::holds::{self, Holds};
+use crate::core::microcode::{self, CpuVendor};
+use crate::core::pacman_conf::{self, PacmanConf};
+use crate::core::repos::{self, Origins, ThirdPartyRepo};
+use crate::core::{changelog, orphans, self_update, snapshot};
+use crate::i18n::{gettext, gettext_f, ngettext_f};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::dialogs::selection::{
+    show_selection_dialog, SelectionDialogConfig, SelectionOption, SelectionType,
+};
 use crate::ui::dialogs::terminal;
+use crate::ui::dialogs::warning::{show_destructive_confirmation, show_warning_confirmation};
+use crate::ui::install_state::{self, Probe};
 use crate::ui::task_runner::{self, Command, CommandSequence};
-use crate::ui::utils::{extract_widget, is_package_installed, is_service_enabled, is_user_service_enabled};
+use crate::ui::toast;
+use crate::ui::utils::{
+    extract_action_widgets, is_package_installed, is_service_enabled, is_user_service_enabled,
+    spawn_blocking, try_extract_widget,
+};
 use gtk4::{
-    ApplicationWindow, Box as GtkBox, Builder, CheckButton, Frame, Label, Orientation,
-    ScrolledWindow, Separator, ToggleButton,
+    glib, ApplicationWindow, Box as GtkBox, Builder, CheckButton, Frame, Label, ListBox,
+    Orientation, ScrolledWindow, Separator, ToggleButton,
 };
-use log::info;
+use log::{info, warn};
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
     setup_clr_pacman(page_builder, window);
     setup_unlock_pacman(page_builder, window);
     setup_remove_orphans(page_builder, window);
+    setup_package_holds(page_builder, window);
     setup_plasma_x11(page_builder, window);
     setup_pacman_db_fix(page_builder, window);
-    setup_waydroid_guide(page_builder);
+    setup_waydroid(page_builder, window);
     setup_fix_gpgme(page_builder, window);
     setup_fix_arch_keyring(page_builder, window);
     setup_update_mirrorlist(page_builder, window);
@@ -28,385 +47,989 @@ pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &
     setup_cachyos_repos(page_builder, window);
     setup_chaotic_aur(page_builder, window);
     setup_xero_repo(page_builder, window);
+    setup_repo_removal(page_builder, window);
     setup_xpackagemanager(page_builder, window);
+    setup_update_channel(page_builder);
     setup_update_toolkit(page_builder, window);
+    setup_rollback_toolkit(page_builder, window);
     setup_optimization_services(page_builder, window);
 }
 
 fn setup_clr_pacman(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_clr_pacman = extract_widget::<gtk4::Button>(page_builder, "btn_clr_pacman");
+    let Some(btn_clr_pacman) = try_extract_widget::<gtk4::Button>(page_builder, "btn_clr_pacman")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_clr_pacman.connect_clicked(move |_| {
         info!("Servicing: Clear Pacman Cache button clicked");
-        let commands = CommandSequence::new()
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("pacman")
-                    .args(&["-Scc", "--noconfirm"])
-                    .description("Clearing Pacman cache...")
-                    .build(),
-            )
-            .build();
-        task_runner::run(window.upcast_ref(), commands, "Clear Pacman Cache");
+        super::run_action(&window, &actions::servicing::CLEAR_CACHE);
     });
 }
 
 fn setup_unlock_pacman(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_unlock_pacman = extract_widget::<gtk4::Button>(page_builder, "btn_unlock_pacman");
+    let Some(btn_unlock_pacman) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_unlock_pacman")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_unlock_pacman.connect_clicked(move |_| {
         info!("Servicing: Unlock Pacman DB button clicked");
-        let commands = CommandSequence::new()
-            .then(
-                Command::builder()
-                    .privileged()
-                    .program("rm")
-                    .args(&["-f", "/var/lib/pacman/db.lck"])
-                    .description("Removing Pacman lock file...")
-                    .build(),
-            )
-            .build();
-        task_runner::run(window.upcast_ref(), commands, "Unlock Pacman Database");
+        super::run_action(&window, &actions::servicing::UNLOCK_PACMAN);
     });
 }
 
-/// Query pacman for orphaned packages (installed as deps, no longer required).
-fn get_orphan_packages() -> Vec<String> {
-    std::process::Command::new("pacman")
-        .args(["-Qdtq"])
-        .output()
-        .ok()
-        .filter(|o| o.status.success())
-        .map(|o| {
-            String::from_utf8_lossy(&o.stdout)
-                .lines()
-                .filter(|l| !l.is_empty())
-                .map(|l| l.to_string())
-                .collect()
-        })
-        .unwrap_or_default()
-}
-
 fn setup_remove_orphans(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn = extract_widget::<gtk4::Button>(page_builder, "btn_remove_orphans");
+    let Some(btn) = try_extract_widget::<gtk4::Button>(page_builder, "btn_remove_orphans") else {
+        return;
+    };
     let window = window.clone();
 
-    btn.connect_clicked(move |_| {
+    btn.connect_clicked(move |btn| {
         info!("Servicing: Remove Orphans button clicked");
 
-        let orphans = get_orphan_packages();
-
-        if orphans.is_empty() {
-            // No orphans — show a simple info dialog
-            let dialog = adw::Window::new();
-            dialog.set_title(Some("Xero Toolkit - Remove Orphans"));
-            dialog.set_default_size(400, 200);
-            dialog.set_modal(true);
-            dialog.set_transient_for(Some(&window));
-
-            let toolbar = adw::ToolbarView::new();
-            let header = adw::HeaderBar::new();
-            toolbar.add_top_bar(&header);
-
-            let content = GtkBox::new(Orientation::Vertical, 16);
-            content.set_margin_top(24);
-            content.set_margin_bottom(24);
-            content.set_margin_start(24);
-            content.set_margin_end(24);
-            content.set_halign(gtk4::Align::Center);
-            content.set_valign(gtk4::Align::Center);
-
-            let icon = gtk4::Image::from_icon_name("emblem-ok-symbolic");
-            icon.set_pixel_size(48);
-            content.append(&icon);
-
-            let label = Label::new(Some("No orphaned packages found.\nYour system is clean!"));
-            label.set_halign(gtk4::Align::Center);
-            label.set_justify(gtk4::Justification::Center);
-            content.append(&label);
-
-            let ok_btn = gtk4::Button::with_label("OK");
-            ok_btn.add_css_class("suggested-action");
-            ok_btn.add_css_class("pill");
-            ok_btn.set_halign(gtk4::Align::Center);
-            let dialog_clone = dialog.clone();
-            ok_btn.connect_clicked(move |_| dialog_clone.close());
-            content.append(&ok_btn);
-
-            toolbar.set_content(Some(&content));
-            dialog.set_content(Some(&toolbar));
-            dialog.present();
-            return;
-        }
+        // Querying pacman can take a moment; keep the window responsive
+        btn.set_sensitive(false);
+        let btn = btn.clone();
+        let window = window.clone();
+        spawn_blocking(orphans::installed, move |packages| {
+            btn.set_sensitive(true);
+            show_orphans_dialog(&window, packages);
+        });
+    });
+}
 
-        // ── Build the orphan review dialog ───────────────────────────────
-        let dialog = adw::Window::new();
-        dialog.set_title(Some("Xero Toolkit - Remove Orphans"));
-        dialog.set_default_size(550, 500);
-        dialog.set_modal(true);
-        dialog.set_transient_for(Some(&window));
-
-        let toolbar = adw::ToolbarView::new();
-        let header = adw::HeaderBar::new();
-        toolbar.add_top_bar(&header);
-
-        let outer = GtkBox::new(Orientation::Vertical, 12);
-        outer.set_margin_top(12);
-        outer.set_margin_bottom(12);
-        outer.set_margin_start(12);
-        outer.set_margin_end(12);
-
-        // Title + description
-        let title_box = GtkBox::new(Orientation::Vertical, 4);
-        title_box.set_halign(gtk4::Align::Center);
-
-        let title = Label::new(Some("Remove Orphaned Packages"));
-        title.add_css_class("title-2");
-        title_box.append(&title);
-
-        let count_text = format!(
-            "Found {} orphaned package{}. Uncheck any you want to keep.",
-            orphans.len(),
-            if orphans.len() == 1 { "" } else { "s" }
-        );
-        let subtitle = Label::new(Some(&count_text));
-        subtitle.add_css_class("dim-label");
-        subtitle.set_wrap(true);
-        subtitle.set_halign(gtk4::Align::Center);
-        title_box.append(&subtitle);
-
-        outer.append(&title_box);
-
-        // Select All / Deselect All row
-        let toggle_row = GtkBox::new(Orientation::Horizontal, 8);
-        toggle_row.set_halign(gtk4::Align::End);
-        toggle_row.set_margin_end(24);
-
-        let btn_select_all = gtk4::Button::with_label("Select All");
-        btn_select_all.add_css_class("flat");
-        btn_select_all.add_css_class("caption");
-        toggle_row.append(&btn_select_all);
-
-        let btn_deselect_all = gtk4::Button::with_label("Deselect All");
-        btn_deselect_all.add_css_class("flat");
-        btn_deselect_all.add_css_class("caption");
-        toggle_row.append(&btn_deselect_all);
-
-        outer.append(&toggle_row);
-
-        // Scrollable package list inside a frame
-        let frame = Frame::new(None);
-        frame.add_css_class("view");
-        frame.set_hexpand(true);
-        frame.set_vexpand(true);
-        frame.set_margin_start(24);
-        frame.set_margin_end(24);
-        frame.set_margin_top(4);
-        frame.set_margin_bottom(8);
-
-        let scroll = ScrolledWindow::new();
-        scroll.set_hexpand(true);
-        scroll.set_vexpand(true);
-        scroll.set_min_content_height(250);
-
-        let list_box = GtkBox::new(Orientation::Vertical, 0);
-        list_box.set_margin_start(16);
-        list_box.set_margin_end(16);
-        list_box.set_margin_top(8);
-        list_box.set_margin_bottom(8);
-
-        let checkboxes: Rc<RefCell<Vec<(String, CheckButton)>>> =
-            Rc::new(RefCell::new(Vec::new()));
-
-        for (i, pkg) in orphans.iter().enumerate() {
-            let row = GtkBox::new(Orientation::Horizontal, 12);
-            row.set_margin_top(4);
-            row.set_margin_bottom(4);
-
-            let checkbox = CheckButton::new();
-            checkbox.set_active(true); // pre-checked for removal
-            row.append(&checkbox);
-
-            let label = Label::new(Some(pkg));
-            label.set_halign(gtk4::Align::Start);
-            label.set_hexpand(true);
-            label.add_css_class("monospace");
-            row.append(&label);
-
-            list_box.append(&row);
-            checkboxes.borrow_mut().push((pkg.clone(), checkbox));
-
-            if i < orphans.len() - 1 {
-                let sep = Separator::new(Orientation::Horizontal);
-                list_box.append(&sep);
-            }
-        }
+/// Look for orphaned packages and let the user review them, as the Remove
+/// Orphans button does.
+pub fn review_orphans(window: &ApplicationWindow) {
+    let window = window.clone();
+    spawn_blocking(orphans::installed, move |packages| {
+        show_orphans_dialog(&window, packages);
+    });
+}
 
-        scroll.set_child(Some(&list_box));
-        frame.set_child(Some(&scroll));
-        outer.append(&frame);
+/// A package in the orphan review dialog.
+struct OrphanRow {
+    name: String,
+    row: GtkBox,
+    note: Label,
+    check: CheckButton,
+    pin: ToggleButton,
+}
 
-        // Select All / Deselect All logic
-        let cbs = checkboxes.clone();
-        btn_select_all.connect_clicked(move |_| {
-            for (_, cb) in cbs.borrow().iter() {
-                cb.set_active(true);
-            }
+/// Let the user review the orphaned packages before removing them.
+///
+/// Every package that could go is listed, including the ones only other
+/// orphans need. Unchecking or pinning a package keeps it and whatever it
+/// depends on; pinned packages are saved to the keep list.
+fn show_orphans_dialog(window: &ApplicationWindow, packages: Vec<orphans::Package>) {
+    let candidates = orphans::resolve(&packages, &HashSet::new());
+    if candidates.is_empty() {
+        toast::show(&gettext(
+            "No orphaned packages found. Your system is clean!",
+        ));
+        return;
+    }
+    let pinned: HashSet<String> = config::user::load().keep_orphans.into_iter().collect();
+
+    // ── Build the orphan review dialog ───────────────────────────────
+    let dialog = adw::Window::new();
+    dialog.set_title(Some(&gettext("Xero Toolkit - Remove Orphans")));
+    dialog.set_default_size(550, 550);
+    dialog.set_modal(true);
+    dialog.set_transient_for(Some(window));
+
+    let toolbar = adw::ToolbarView::new();
+    let header = adw::HeaderBar::new();
+    toolbar.add_top_bar(&header);
+
+    let outer = GtkBox::new(Orientation::Vertical, 12);
+    outer.set_margin_top(12);
+    outer.set_margin_bottom(12);
+    outer.set_margin_start(12);
+    outer.set_margin_end(12);
+
+    // Title + description
+    let title_box = GtkBox::new(Orientation::Vertical, 4);
+    title_box.set_halign(gtk4::Align::Center);
+
+    let title = Label::new(Some(&gettext("Remove Orphaned Packages")));
+    title.add_css_class("title-2");
+    title_box.append(&title);
+
+    let cascaded = candidates.iter().filter(|o| o.cascaded).count();
+    let count_text = ngettext_f(
+        "Found {count} orphaned package, {cascaded} of them only needed by other orphans. \
+         Uncheck any you want to keep, or pin them to keep them for good.",
+        "Found {count} orphaned packages, {cascaded} of them only needed by other orphans. \
+         Uncheck any you want to keep, or pin them to keep them for good.",
+        candidates.len() as u32,
+        &[
+            ("count", &candidates.len().to_string()),
+            ("cascaded", &cascaded.to_string()),
+        ],
+    );
+    let subtitle = Label::new(Some(&count_text));
+    subtitle.add_css_class("dim-label");
+    subtitle.set_wrap(true);
+    subtitle.set_justify(gtk4::Justification::Center);
+    subtitle.set_halign(gtk4::Align::Center);
+    title_box.append(&subtitle);
+
+    outer.append(&title_box);
+
+    // Select All / Deselect All row
+    let toggle_row = GtkBox::new(Orientation::Horizontal, 8);
+    toggle_row.set_halign(gtk4::Align::End);
+    toggle_row.set_margin_end(24);
+
+    let btn_select_all = gtk4::Button::with_label(&gettext("Select All"));
+    btn_select_all.add_css_class("flat");
+    btn_select_all.add_css_class("caption");
+    toggle_row.append(&btn_select_all);
+
+    let btn_deselect_all = gtk4::Button::with_label(&gettext("Deselect All"));
+    btn_deselect_all.add_css_class("flat");
+    btn_deselect_all.add_css_class("caption");
+    toggle_row.append(&btn_deselect_all);
+
+    outer.append(&toggle_row);
+
+    // Scrollable package list inside a frame
+    let frame = Frame::new(None);
+    frame.add_css_class("view");
+    frame.set_hexpand(true);
+    frame.set_vexpand(true);
+    frame.set_margin_start(24);
+    frame.set_margin_end(24);
+    frame.set_margin_top(4);
+    frame.set_margin_bottom(8);
+
+    let scroll = ScrolledWindow::new();
+    scroll.set_hexpand(true);
+    scroll.set_vexpand(true);
+    scroll.set_min_content_height(250);
+
+    let list_box = GtkBox::new(Orientation::Vertical, 0);
+    list_box.set_margin_start(16);
+    list_box.set_margin_end(16);
+    list_box.set_margin_top(8);
+    list_box.set_margin_bottom(8);
+
+    let mut rows = Vec::new();
+    for (i, orphan) in candidates.iter().enumerate() {
+        let row = GtkBox::new(Orientation::Horizontal, 12);
+        row.set_margin_top(4);
+        row.set_margin_bottom(4);
+
+        let check = CheckButton::new();
+        check.set_active(true); // pre-checked for removal
+        row.append(&check);
+
+        let text = GtkBox::new(Orientation::Vertical, 2);
+        text.set_hexpand(true);
+        let label = Label::new(Some(&orphan.name));
+        label.set_halign(gtk4::Align::Start);
+        label.add_css_class("monospace");
+        text.append(&label);
+        let note = Label::new(None);
+        note.set_halign(gtk4::Align::Start);
+        note.add_css_class("caption");
+        note.add_css_class("dim-label");
+        text.append(&note);
+        row.append(&text);
+
+        let size = Label::new(Some(&glib::format_size(orphan.size)));
+        size.add_css_class("dim-label");
+        row.append(&size);
+
+        let pin = ToggleButton::new();
+        pin.set_icon_name("view-pin-symbolic");
+        pin.set_tooltip_text(Some(&gettext("Always keep")));
+        pin.set_valign(gtk4::Align::Center);
+        pin.add_css_class("flat");
+        pin.set_active(pinned.contains(&orphan.name));
+        row.append(&pin);
+
+        list_box.append(&row);
+        if i < candidates.len() - 1 {
+            let sep = Separator::new(Orientation::Horizontal);
+            list_box.append(&sep);
+        }
+
+        rows.push(OrphanRow {
+            name: orphan.name.clone(),
+            row,
+            note,
+            check,
+            pin,
         });
+    }
+    let rows = Rc::new(rows);
+
+    scroll.set_child(Some(&list_box));
+    frame.set_child(Some(&scroll));
+    outer.append(&frame);
+
+    // Select All / Deselect All logic
+    let rows_clone = rows.clone();
+    btn_select_all.connect_clicked(move |_| {
+        for row in rows_clone.iter() {
+            row.check.set_active(true);
+        }
+    });
+
+    let rows_clone = rows.clone();
+    btn_deselect_all.connect_clicked(move |_| {
+        for row in rows_clone.iter() {
+            row.check.set_active(false);
+        }
+    });
 
-        let cbs = checkboxes.clone();
-        btn_deselect_all.connect_clicked(move |_| {
-            for (_, cb) in cbs.borrow().iter() {
-                cb.set_active(false);
+    let remove_btn = gtk4::Button::with_label(&gettext("Remove"));
+    remove_btn.add_css_class("destructive-action");
+    remove_btn.add_css_class("pill");
+
+    // Work out what goes from what is kept, and show it on the rows
+    let packages = Rc::new(packages);
+    let removal: Rc<RefCell<Vec<orphans::Orphan>>> = Rc::new(RefCell::new(Vec::new()));
+    let update = {
+        let rows = rows.clone();
+        let removal = removal.clone();
+        let remove_btn = remove_btn.clone();
+        move || {
+            let keep: HashSet<String> = rows
+                .iter()
+                .filter(|row| row.pin.is_active() || !row.check.is_active())
+                .map(|row| row.name.clone())
+                .collect();
+            let set = orphans::resolve(&packages, &keep);
+
+            for row in rows.iter() {
+                row.check.set_sensitive(!row.pin.is_active());
+                let orphan = set.iter().find(|o| o.name == row.name);
+                let needed = orphan.is_none() && !keep.contains(&row.name);
+                row.row.set_sensitive(!needed);
+                let note = if row.pin.is_active() {
+                    gettext("On the keep list")
+                } else if needed {
+                    gettext("Needed by a package you keep")
+                } else if orphan.is_some_and(|o| o.cascaded) {
+                    gettext("Only needed by other orphans")
+                } else {
+                    String::new()
+                };
+                row.note.set_label(&note);
+                row.note.set_visible(!note.is_empty());
             }
-        });
 
-        // Update remove button label with count
-        let remove_btn = gtk4::Button::with_label(&format!("Remove {}", orphans.len()));
-        remove_btn.add_css_class("destructive-action");
-        remove_btn.add_css_class("pill");
-
-        let cbs = checkboxes.clone();
-        let remove_btn_clone = remove_btn.clone();
-        let update_count = move || {
-            let count = cbs.borrow().iter().filter(|(_, cb)| cb.is_active()).count();
-            if count > 0 {
-                remove_btn_clone.set_label(&format!("Remove {}", count));
-                remove_btn_clone.set_sensitive(true);
+            if set.is_empty() {
+                remove_btn.set_label(&gettext("Remove"));
+                remove_btn.set_sensitive(false);
             } else {
-                remove_btn_clone.set_label("Remove");
-                remove_btn_clone.set_sensitive(false);
+                let size: u64 = set.iter().map(|o| o.size).sum();
+                remove_btn.set_label(&gettext_f(
+                    "Remove {count} ({size})",
+                    &[
+                        ("count", &set.len().to_string()),
+                        ("size", &glib::format_size(size)),
+                    ],
+                ));
+                remove_btn.set_sensitive(true);
             }
-        };
-
-        // Connect each checkbox toggle to update the count
-        for (_, cb) in checkboxes.borrow().iter() {
-            let update = update_count.clone();
-            cb.connect_toggled(move |_| update());
+            *removal.borrow_mut() = set;
         }
+    };
+    update();
+
+    for row in rows.iter() {
+        let update_clone = update.clone();
+        row.check.connect_toggled(move |_| update_clone());
 
-        // Button row
-        let btn_row = GtkBox::new(Orientation::Horizontal, 8);
-        btn_row.set_halign(gtk4::Align::Center);
-        btn_row.set_margin_top(12);
-
-        let cancel_btn = gtk4::Button::with_label("Cancel");
-        cancel_btn.add_css_class("pill");
-        let dialog_clone = dialog.clone();
-        cancel_btn.connect_clicked(move |_| {
-            info!("Orphan removal cancelled");
-            dialog_clone.close();
+        let update_clone = update.clone();
+        let name = row.name.clone();
+        row.pin.connect_toggled(move |pin| {
+            let keep = pin.is_active();
+            info!(
+                "Orphans: {} {} the keep list",
+                if keep { "adding" } else { "removing" },
+                name
+            );
+            let result = config::user::update(|settings| {
+                settings.keep_orphans.retain(|n| *n != name);
+                if keep {
+                    settings.keep_orphans.push(name.clone());
+                    settings.keep_orphans.sort();
+                }
+            });
+            if let Err(e) = result {
+                warn!("Failed to save the orphan keep list: {:#}", e);
+            }
+            update_clone();
         });
+    }
 
-        btn_row.append(&cancel_btn);
-        btn_row.append(&remove_btn);
-        outer.append(&btn_row);
+    // Button row
+    let btn_row = GtkBox::new(Orientation::Horizontal, 8);
+    btn_row.set_halign(gtk4::Align::Center);
+    btn_row.set_margin_top(12);
+
+    let cancel_btn = gtk4::Button::with_label(&gettext("Cancel"));
+    cancel_btn.add_css_class("pill");
+    let dialog_clone = dialog.clone();
+    cancel_btn.connect_clicked(move |_| {
+        info!("Orphan removal cancelled");
+        dialog_clone.close();
+    });
 
-        // Remove button → collect checked packages and run removal
-        let dialog_clone = dialog.clone();
-        let window_clone = window.clone();
-        let cbs = checkboxes.clone();
-        remove_btn.connect_clicked(move |_| {
-            let selected: Vec<String> = cbs
-                .borrow()
-                .iter()
-                .filter(|(_, cb)| cb.is_active())
-                .map(|(pkg, _)| pkg.clone())
-                .collect();
+    btn_row.append(&cancel_btn);
+    btn_row.append(&remove_btn);
+    outer.append(&btn_row);
 
-            info!("Removing {} orphaned packages", selected.len());
-            dialog_clone.close();
+    // Remove button → remove exactly the previewed set
+    let dialog_clone = dialog.clone();
+    let window_clone = window.clone();
+    remove_btn.connect_clicked(move |_| {
+        let selected: Vec<String> = removal.borrow().iter().map(|o| o.name.clone()).collect();
 
-            if selected.is_empty() {
-                return;
-            }
+        info!("Removing {} orphaned packages", selected.len());
+        dialog_clone.close();
 
-            let mut args: Vec<&str> = vec!["-Rns", "--noconfirm"];
-            let refs: Vec<&str> = selected.iter().map(|s| s.as_str()).collect();
-            args.extend_from_slice(&refs);
+        if selected.is_empty() {
+            return;
+        }
 
-            let commands = CommandSequence::new()
-                .then(
-                    Command::builder()
-                        .aur()
-                        .args(&args)
-                        .description("Removing orphaned packages...")
-                        .build(),
-                )
-                .build();
+        // The set already holds the cascade, so -s isn't needed and nothing
+        // beyond the preview is removed
+        let mut args: Vec<&str> = vec!["-Rn", "--noconfirm"];
+        let refs: Vec<&str> = selected.iter().map(|s| s.as_str()).collect();
+        args.extend_from_slice(&refs);
 
-            task_runner::run(
-                window_clone.upcast_ref(),
-                commands,
-                "Remove Orphaned Packages",
-            );
-        });
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&args)
+                    .description("Removing orphaned packages...")
+                    .build(),
+            )
+            .build();
 
-        toolbar.set_content(Some(&outer));
-        dialog.set_content(Some(&toolbar));
-        dialog.present();
+        task_runner::run(
+            window_clone.upcast_ref(),
+            commands,
+            "Remove Orphaned Packages",
+        );
     });
+
+    toolbar.set_content(Some(&outer));
+    dialog.set_content(Some(&toolbar));
+    dialog.present();
 }
 
-fn setup_plasma_x11(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_plasma_x11 = extract_widget::<gtk4::Button>(page_builder, "btn_plasma_x11");
+fn setup_package_holds(page_builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn) = try_extract_widget::<gtk4::Button>(page_builder, "btn_package_holds") else {
+        return;
+    };
     let window = window.clone();
-    btn_plasma_x11.connect_clicked(move |_| {
-        info!("Servicing: Plasma X11 Session button clicked");
+
+    btn.connect_clicked(move |btn| {
+        info!("Servicing: Package Holds button clicked");
+
+        // The dry-run upgrade takes a moment
+        btn.set_sensitive(false);
+        let btn = btn.clone();
+        let window = window.clone();
+        spawn_blocking(HoldsState::load, move |state| {
+            btn.set_sensitive(true);
+            match state {
+                Ok(state) => show_holds_dialog(&window, state),
+                Err(e) => show_error(&window, &format!("{:#}", e)),
+            }
+        });
+    });
+}
+
+/// What the package holds dialog is built from.
+struct HoldsState {
+    conf: PacmanConf,
+    installed: Vec<String>,
+    groups: Vec<String>,
+    check: holds::UpgradeCheck,
+}
+
+impl HoldsState {
+    fn load() -> anyhow::Result<Self> {
+        let conf = PacmanConf::read()?;
+        let mut installed: Vec<String> = core::package::installed_packages().into_iter().collect();
+        installed.sort();
+        Ok(Self {
+            conf,
+            installed,
+            groups: holds::installed_groups(),
+            check: holds::check_upgrade(),
+        })
+    }
+}
+
+/// Let the user review and edit the packages and groups held back from
+/// upgrades, with what a dry-run upgrade says about them.
+fn show_holds_dialog(window: &ApplicationWindow, state: HoldsState) {
+    let holds = Rc::new(RefCell::new(Holds::from_conf(&state.conf)));
+
+    let dialog = adw::Window::new();
+    dialog.set_title(Some(&gettext("Xero Toolkit - Package Holds")));
+    dialog.set_default_size(550, 600);
+    dialog.set_modal(true);
+    dialog.set_transient_for(Some(window));
+
+    let toolbar = adw::ToolbarView::new();
+    let header = adw::HeaderBar::new();
+    toolbar.add_top_bar(&header);
+
+    let outer = GtkBox::new(Orientation::Vertical, 12);
+    outer.set_margin_top(12);
+    outer.set_margin_bottom(12);
+    outer.set_margin_start(24);
+    outer.set_margin_end(24);
+
+    let title = Label::new(Some(&gettext("Package Holds")));
+    title.add_css_class("title-2");
+    outer.append(&title);
+
+    let subtitle = Label::new(Some(&gettext(
+        "Held packages and groups are skipped by system upgrades. Arch doesn't support \
+         partial upgrades, so release holds once the problem they work around is fixed.",
+    )));
+    subtitle.add_css_class("dim-label");
+    subtitle.set_wrap(true);
+    subtitle.set_justify(gtk4::Justification::Center);
+    outer.append(&subtitle);
+
+    // What the dry-run upgrade ran into
+    if !state.check.problems.is_empty() {
+        let text = format!(
+            "{}\n{}",
+            gettext("The next upgrade can't resolve its dependencies:"),
+            state
+                .check
+                .problems
+                .iter()
+                .map(|p| format!("• {}", p))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        let warning = Label::new(Some(&text));
+        warning.add_css_class("error");
+        warning.set_wrap(true);
+        warning.set_xalign(0.0);
+        outer.append(&warning);
+    }
+    if !state.check.held_back.is_empty() {
+        let text = gettext_f(
+            "Held back: {packages}",
+            &[("packages", &state.check.held_back.join(", "))],
+        );
+        let held_back = Label::new(Some(&text));
+        held_back.add_css_class("warning");
+        held_back.set_wrap(true);
+        held_back.set_xalign(0.0);
+        outer.append(&held_back);
+    }
+
+    // Search over installed packages and groups
+    let search = gtk4::SearchEntry::new();
+    search.set_placeholder_text(Some(&gettext("Hold an installed package or group")));
+    outer.append(&search);
+
+    let results = ListBox::new();
+    results.set_selection_mode(gtk4::SelectionMode::None);
+    results.add_css_class("boxed-list");
+    results.set_visible(false);
+    outer.append(&results);
+
+    let held_list = ListBox::new();
+    held_list.set_selection_mode(gtk4::SelectionMode::None);
+    held_list.add_css_class("boxed-list");
+    let scroll = ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    scroll.set_min_content_height(200);
+    scroll.set_child(Some(&held_list));
+    outer.append(&scroll);
+    render_holds(&held_list, &holds);
+
+    let installed = Rc::new(state.installed);
+    let groups = Rc::new(state.groups);
+    let holds_clone = holds.clone();
+    let held_list_clone = held_list.clone();
+    search.connect_search_changed(move |entry| {
+        results.remove_all();
+        let query = entry.text().trim().to_lowercase();
+        results.set_visible(!query.is_empty());
+        if query.is_empty() {
+            return;
+        }
+
+        let current = holds_clone.borrow().clone();
+        let matches = groups
+            .iter()
+            .filter(|g| !current.groups.contains(g))
+            .map(|g| (g, true))
+            .chain(
+                installed
+                    .iter()
+                    .filter(|p| !current.packages.contains(p))
+                    .map(|p| (p, false)),
+            )
+            .filter(|(name, _)| name.to_lowercase().contains(&query))
+            .take(8);
+
+        for (name, group) in matches {
+            let row = adw::ActionRow::builder()
+                .title(name.as_str())
+                .subtitle(if group {
+                    gettext("Group")
+                } else {
+                    gettext("Package")
+                })
+                .activatable(true)
+                .build();
+            row.add_suffix(&gtk4::Image::from_icon_name("list-add-symbolic"));
+
+            let name = name.clone();
+            let holds = holds_clone.clone();
+            let held_list = held_list_clone.clone();
+            let entry = entry.clone();
+            row.connect_activated(move |_| {
+                info!("Holding {}", name);
+                {
+                    let mut holds = holds.borrow_mut();
+                    let list = if group {
+                        &mut holds.groups
+                    } else {
+                        &mut holds.packages
+                    };
+                    list.push(name.clone());
+                    list.sort();
+                }
+                render_holds(&held_list, &holds);
+                entry.set_text("");
+            });
+            results.append(&row);
+        }
+    });
+
+    // Button row
+    let btn_row = GtkBox::new(Orientation::Horizontal, 8);
+    btn_row.set_halign(gtk4::Align::Center);
+    btn_row.set_margin_top(12);
+
+    let cancel_btn = gtk4::Button::with_label(&gettext("Cancel"));
+    cancel_btn.add_css_class("pill");
+    let dialog_clone = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_clone.close());
+
+    let save_btn = gtk4::Button::with_label(&gettext("Save"));
+    save_btn.add_css_class("suggested-action");
+    save_btn.add_css_class("pill");
+
+    btn_row.append(&cancel_btn);
+    btn_row.append(&save_btn);
+    outer.append(&btn_row);
+
+    let dialog_clone = dialog.clone();
+    let window_clone = window.clone();
+    let original = state.conf;
+    save_btn.connect_clicked(move |_| {
+        dialog_clone.close();
+
+        let mut conf = original.clone();
+        holds.borrow().apply(&mut conf);
+        if conf == original {
+            info!("Package holds unchanged");
+            return;
+        }
+
+        let holds = holds.borrow();
+        info!(
+            "Saving {} held packages and {} held groups",
+            holds.packages.len(),
+            holds.groups.len()
+        );
         let commands = CommandSequence::new()
             .then(
                 Command::builder()
-                    .aur()
-                    .args(&["-S", "--noconfirm", "kwin-x11", "plasma-x11-session"])
-                    .description("Installing KDE Plasma X11 session components...")
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &pacman_conf::write_script(), "sh", &conf.to_string()])
+                    .description("Saving package holds to pacman.conf...")
                     .build(),
             )
             .build();
-        task_runner::run(window.upcast_ref(), commands, "Install KDE X11 Session");
+        task_runner::run(window_clone.upcast_ref(), commands, "Package Holds");
+    });
+
+    toolbar.set_content(Some(&outer));
+    dialog.set_content(Some(&toolbar));
+    dialog.present();
+}
+
+/// Fill `list` with a row per held package and group.
+fn render_holds(list: &ListBox, holds: &Rc<RefCell<Holds>>) {
+    list.remove_all();
+    let current = holds.borrow().clone();
+    if current.packages.is_empty() && current.groups.is_empty() {
+        list.append(
+            &adw::ActionRow::builder()
+                .title(gettext("Nothing is held"))
+                .subtitle(gettext("All packages are upgraded with the system"))
+                .build(),
+        );
+        return;
+    }
+
+    let entries = current
+        .groups
+        .iter()
+        .map(|g| (g, true))
+        .chain(current.packages.iter().map(|p| (p, false)));
+    for (name, group) in entries {
+        let row = adw::ActionRow::builder()
+            .title(name.as_str())
+            .subtitle(if group {
+                gettext("Held group")
+            } else {
+                gettext("Held package")
+            })
+            .build();
+        let release = gtk4::Button::from_icon_name("user-trash-symbolic");
+        release.set_tooltip_text(Some(&gettext("Release")));
+        release.set_valign(gtk4::Align::Center);
+        release.add_css_class("flat");
+        row.add_suffix(&release);
+
+        let name = name.clone();
+        let holds = holds.clone();
+        let list_clone = list.clone();
+        release.connect_clicked(move |_| {
+            info!("Releasing {}", name);
+            {
+                let mut holds = holds.borrow_mut();
+                holds.packages.retain(|p| *p != name);
+                holds.groups.retain(|g| *g != name);
+            }
+            render_holds(&list_clone, &holds);
+        });
+        list.append(&row);
+    }
+}
+
+fn setup_plasma_x11(page_builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn_plasma_x11) = try_extract_widget::<gtk4::Button>(page_builder, "btn_plasma_x11")
+    else {
+        return;
+    };
+    let window = window.clone();
+    btn_plasma_x11.connect_clicked(move |_| {
+        info!("Servicing: Plasma X11 Session button clicked");
+        super::run_action(&window, &actions::servicing::PLASMA_X11);
     });
 }
 
 fn setup_pacman_db_fix(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_pacman_db_fix = extract_widget::<gtk4::Button>(page_builder, "btn_pacman_db_fix");
+    let Some(btn_pacman_db_fix) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_pacman_db_fix")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_pacman_db_fix.connect_clicked(move |_| {
         info!("Servicing: Pacman DB Fix button clicked");
-        let commands = CommandSequence::new()
+        super::run_action(&window, &actions::servicing::FIX_PACMAN_DB);
+    });
+}
+
+/// Packages the WayDroid installer may add, removed again on uninstall.
+const WAYDROID_PACKAGES: &[&str] = &["waydroid", "waydroid-script-git", "binder_linux-dkms"];
+
+/// Whether the running kernel provides the binder IPC WayDroid needs, either
+/// built in (linux-zen) or as a loadable module.
+fn kernel_has_binder() -> bool {
+    let builtin = std::fs::read_to_string("/proc/filesystems")
+        .is_ok_and(|fs| fs.lines().any(|l| l.ends_with("binder")));
+    builtin
+        || std::process::Command::new("modprobe")
+            .args(["-n", "-q", "binder_linux"])
+            .status()
+            .is_ok_and(|s| s.success())
+}
+
+fn setup_waydroid(page_builder: &Builder, window: &ApplicationWindow) {
+    let ids = ["btn_waydroid", "btn_waydroid_uninstall"];
+    let Some([btn_waydroid, btn_waydroid_uninstall]) =
+        extract_action_widgets::<gtk4::Button, 2>(page_builder, ids)
+    else {
+        return;
+    };
+
+    let btn_uninstall_clone = btn_waydroid_uninstall.clone();
+    let probe = Probe::Package("waydroid");
+    install_state::bind_widget(&btn_waydroid, probe, move |is_installed| {
+        btn_uninstall_clone.set_visible(is_installed);
+    });
+
+    let window_clone = window.clone();
+    btn_waydroid.connect_clicked(move |btn| {
+        info!("Servicing: WayDroid button clicked");
+
+        btn.set_sensitive(false);
+        let btn = btn.clone();
+        let window = window_clone.clone();
+        spawn_blocking(
+            || (kernel_has_binder(), microcode::detect_vendor()),
+            move |(has_binder, cpu)| {
+                btn.set_sensitive(true);
+                show_waydroid_dialog(&window, has_binder, cpu);
+            },
+        );
+    });
+
+    let window_clone = window.clone();
+    btn_waydroid_uninstall.connect_clicked(move |_| {
+        info!("Servicing: Uninstall WayDroid button clicked");
+        let window = window_clone.clone();
+        show_warning_confirmation(
+            window_clone.upcast_ref(),
+            &gettext("Uninstall WayDroid"),
+            &gettext(
+                "This removes WayDroid, its Android image and <b>all apps and data</b> inside it.",
+            ),
+            move || {
+                task_runner::run(
+                    window.upcast_ref(),
+                    waydroid_uninstall_commands(),
+                    "Uninstall WayDroid",
+                );
+            },
+        );
+    });
+}
+
+/// Ask for the Android image and ARM translation, then install.
+fn show_waydroid_dialog(window: &ApplicationWindow, has_binder: bool, cpu: Option<CpuVendor>) {
+    // libndk is faster on AMD, libhoudini on Intel
+    let translation = match cpu {
+        Some(CpuVendor::Intel) => "libhoudini",
+        _ => "libndk",
+    };
+
+    let mut description = gettext(
+        "WayDroid runs a full Android system in a container. \
+         The Android image is downloaded on first setup (around 1 GB).",
+    );
+    if !has_binder {
+        description.push(' ');
+        description.push_str(&gettext(
+            "Your kernel lacks binder support, so binder_linux-dkms will be built for it.",
+        ));
+    }
+
+    let config = SelectionDialogConfig::new(&gettext("WayDroid Setup"), &description)
+        .selection_type(SelectionType::Multi)
+        .selection_required(false)
+        .add_option(
+            SelectionOption::new(
+                "gapps",
+                &gettext("Google Play"),
+                &gettext("Use the image with Google apps instead of plain LineageOS"),
+                false,
+            )
+            .in_group(&gettext("Android Image")),
+        )
+        .add_option(
+            SelectionOption::new(
+                "arm",
+                &gettext("ARM Translation"),
+                &gettext_f(
+                    "Run ARM-only apps using {layer}, picked for your CPU",
+                    &[("layer", translation)],
+                ),
+                false,
+            )
+            .in_group(&gettext("Extras"))
+            .recommended(),
+        )
+        .confirm_label(&gettext("Install"));
+
+    let window_clone = window.clone();
+    show_selection_dialog(window.upcast_ref(), config, move |selected_ids| {
+        let gapps = selected_ids.iter().any(|s| s == "gapps");
+        let arm = selected_ids
+            .iter()
+            .any(|s| s == "arm")
+            .then_some(translation);
+        task_runner::run(
+            window_clone.upcast_ref(),
+            waydroid_commands(has_binder, gapps, arm),
+            "WayDroid Setup",
+        );
+    });
+}
+
+/// Install WayDroid, download the image and start the container.
+/// `translation` is the waydroid_script ARM layer to add, if any.
+fn waydroid_commands(has_binder: bool, gapps: bool, translation: Option<&str>) -> CommandSequence {
+    let mut commands = CommandSequence::new();
+
+    if !has_binder {
+        commands = commands
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&["-S", "--noconfirm", "--needed", "binder_linux-dkms"])
+                    .description("Building the binder kernel module...")
+                    .build(),
+            )
             .then(
                 Command::builder()
                     .privileged()
                     .program("sh")
                     .args(&[
                         "-c",
-                        "find /var/lib/pacman/local/ -type f -name 'desc' -exec sed -i '/^%INSTALLED_DB%$/,+2d' {} \\;",
+                        "echo 'binder_linux' > /etc/modules-load.d/binder_linux.conf && \
+                         echo 'options binder_linux devices=binder,hwbinder,vndbinder' \
+                         > /etc/modprobe.d/binder_linux.conf && modprobe binder_linux",
                     ])
-                    .description("Fixing Pacman local database...")
+                    .description("Loading the binder module...")
+                    .build(),
+            );
+    }
+
+    commands = commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "waydroid"])
+                .description("Installing WayDroid...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("waydroid")
+                .args(&["init", "-f", "-s", if gapps { "GAPPS" } else { "VANILLA" }])
+                .description("Downloading the Android image...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "waydroid-container.service"])
+                .description("Enabling the WayDroid container service...")
+                .build(),
+        );
+
+    if let Some(translation) = translation {
+        commands = commands
+            .then(
+                Command::builder()
+                    .aur()
+                    .args(&["-S", "--noconfirm", "--needed", "waydroid-script-git"])
+                    .description("Installing waydroid_script...")
                     .build(),
             )
-            .build();
-        task_runner::run(window.upcast_ref(), commands, "Pacman DB Fix");
-    });
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("waydroid-extras")
+                    .args(&["install", translation])
+                    .description("Installing ARM translation...")
+                    .build(),
+            );
+    }
+
+    commands.build()
 }
 
-fn setup_waydroid_guide(page_builder: &Builder) {
-    let btn_waydroid_guide = extract_widget::<gtk4::Button>(page_builder, "btn_waydroid_guide");
-    btn_waydroid_guide.connect_clicked(move |_| {
-        info!("Servicing: WayDroid Guide button clicked - opening guide");
-        let _ = std::process::Command::new("xdg-open")
-            .arg("https://xerolinux.xyz/posts/waydroid-guide/")
-            .spawn();
-    });
+/// Stop WayDroid and remove its packages, images and per-user data.
+fn waydroid_uninstall_commands() -> CommandSequence {
+    let installed: Vec<&str> = WAYDROID_PACKAGES
+        .iter()
+        .copied()
+        .filter(|pkg| is_package_installed(pkg))
+        .collect();
+    let mut args = vec!["-Rns", "--noconfirm"];
+    args.extend(installed);
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", "waydroid session stop 2>/dev/null || true"])
+                .description("Stopping the WayDroid session...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "systemctl disable --now waydroid-container.service 2>/dev/null || true",
+                ])
+                .description("Stopping the WayDroid container service...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&args)
+                .description("Removing WayDroid packages...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "rm -rf /var/lib/waydroid /etc/modules-load.d/binder_linux.conf \
+                     /etc/modprobe.d/binder_linux.conf",
+                ])
+                .description("Removing Android images...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "rm -rf \"$HOME/.local/share/waydroid\" \
+                     \"$HOME\"/.local/share/applications/waydroid.*.desktop",
+                ])
+                .description("Removing Android apps and data...")
+                .build(),
+        )
+        .build()
 }
 
 fn setup_fix_gpgme(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_fix_gpgme = extract_widget::<gtk4::Button>(page_builder, "btn_fix_gpgme");
+    let Some(btn_fix_gpgme) = try_extract_widget::<gtk4::Button>(page_builder, "btn_fix_gpgme")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_fix_gpgme.connect_clicked(move |_| {
         info!("Servicing: Fix GPGME Database button clicked");
         terminal::show_terminal_dialog(
             window.upcast_ref(),
-            "Fix GPGME Database",
+            &gettext("Fix GPGME Database"),
             "pkexec",
             &["sh", "-c", "rm -rf /var/lib/pacman/sync && pacman -Syy"],
         );
@@ -414,126 +1037,101 @@ fn setup_fix_gpgme(page_builder: &Builder, window: &ApplicationWindow) {
 }
 
 fn setup_fix_arch_keyring(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_fix_arch_keyring = extract_widget::<gtk4::Button>(page_builder, "btn_fix_arch_keyring");
+    let Some(btn_fix_arch_keyring) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_fix_arch_keyring")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_fix_arch_keyring.connect_clicked(move |_| {
         info!("Servicing: Fix Arch Keyring button clicked");
-        let commands = CommandSequence::new()
-            .then(Command::builder()
-                .privileged()
-                .program("rm")
-                .args(&["-rf", "/etc/pacman.d/gnupg"])
-                .description("Removing existing GnuPG keyring...")
-                .build())
-            .then(Command::builder()
-                .privileged()
-                .program("pacman-key")
-                .args(&["--init"])
-                .description("Initializing new keyring...")
-                .build())
-            .then(Command::builder()
-                .privileged()
-                .program("pacman-key")
-                .args(&["--populate"])
-                .description("Populating keyring...")
-                .build())
-            .then(Command::builder()
-                .privileged()
-                .program("sh")
-                .args(&["-c", "echo 'keyserver hkp://keyserver.ubuntu.com:80' >> /etc/pacman.d/gnupg/gpg.conf"])
-                .description("Setting keyserver...")
-                .build())
-            .then(Command::builder()
-                .privileged()
-                .program("pacman")
-                .args(&["-Syy", "--noconfirm", "archlinux-keyring"])
-                .description("Reinstalling Arch Linux keyring...")
-                .build())
-            .build();
-        task_runner::run(window.upcast_ref(), commands, "Fix GnuPG Keyring");
+        super::run_action(&window, &actions::servicing::FIX_KEYRING);
     });
 }
 
 fn setup_update_mirrorlist(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_update_mirrorlist = extract_widget::<gtk4::Button>(page_builder, "btn_update_mirrorlist");
+    let Some(btn_update_mirrorlist) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_update_mirrorlist")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_update_mirrorlist.connect_clicked(move |_| {
         info!("Servicing: Update Mirrorlist button clicked");
-
-        let rate_mirrors_installed = core::is_package_installed("rate-mirrors");
-
-        let mirror_mappings: Vec<(&str, &str, &str)> = vec![
-            ("/etc/pacman.d/mirrorlist", "arch", "Arch"),
-            ("/etc/pacman.d/chaotic-mirrorlist", "chaotic-aur", "Chaotic-AUR"),
-            ("/etc/pacman.d/cachyos-mirrorlist", "cachyos", "CachyOS"),
-            ("/etc/pacman.d/endeavouros-mirrorlist", "endeavouros", "EndeavourOS"),
-            ("/etc/pacman.d/manjaro-mirrorlist", "manjaro", "Manjaro"),
-            ("/etc/pacman.d/rebornos-mirrorlist", "rebornos", "RebornOS"),
-            ("/etc/pacman.d/artix-mirrorlist", "artix", "Artix"),
-        ];
-
-        let mut commands = CommandSequence::new();
-
-        if !rate_mirrors_installed {
-            commands = commands.then(Command::builder()
-                .aur()
-                .args(&["-S", "--needed", "--noconfirm", "rate-mirrors"])
-                .description("Installing rate-mirrors utility...")
-                .build());
-        }
-
-        for (file_path, repo_id, repo_name) in mirror_mappings {
-            if std::path::Path::new(file_path).exists() {
-                let cmd = format!(
-                    "rate-mirrors --allow-root --protocol https {} | tee {}",
-                    repo_id, file_path
-                );
-                let description = format!("Updating {} mirrorlist...", repo_name);
-                commands = commands.then(Command::builder()
-                    .privileged()
-                    .program("sh")
-                    .args(&["-c", &cmd])
-                    .description(&description)
-                    .build());
-            }
-        }
-
-        task_runner::run(window.upcast_ref(), commands.build(), "Update System Mirrorlists");
+        super::run_action(&window, &actions::servicing::UPDATE_MIRRORS);
     });
 }
 
 fn setup_parallel_downloads(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_parallel_downloads = extract_widget::<gtk4::Button>(page_builder, "btn_parallel_downloads");
+    let Some(btn_parallel_downloads) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_parallel_downloads")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_parallel_downloads.connect_clicked(move |_| {
         info!("Servicing: Change Parallel Downloads button clicked");
         terminal::show_terminal_dialog(
             window.upcast_ref(),
-            "Change Parallel Downloads",
+            &gettext("Change Parallel Downloads"),
             "pkexec",
             &["pmpd"],
         );
     });
 }
 
+/// Grey out a repository install button whose download isn't pinned.
+fn disable_unpinned(button: &gtk4::Button) {
+    button.set_sensitive(false);
+    button.set_tooltip_text(Some(&gettext(
+        "Unavailable until this release pins the repository's setup files",
+    )));
+}
+
 fn setup_cachyos_repos(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_cachyos_repos = extract_widget::<gtk4::Button>(page_builder, "btn_cachyos_repos");
+    let Some(btn_cachyos_repos) =
+        try_extract_widget::<gtk4::Button>(page_builder, "btn_cachyos_repos")
+    else {
+        return;
+    };
+
+    // The setup script is only run against a pinned digest
+    let (url, hash) = match core::verify::get("cachyos-repo") {
+        Ok(pin) => match pin.sha256() {
+            Some(hash) => (pin.url.clone(), hash.to_string()),
+            None => {
+                warn!("The cachyos-repo pin isn't a sha256 checksum");
+                disable_unpinned(&btn_cachyos_repos);
+                return;
+            }
+        },
+        Err(e) => {
+            warn!("{}", e);
+            disable_unpinned(&btn_cachyos_repos);
+            return;
+        }
+    };
+
     let window = window.clone();
     btn_cachyos_repos.connect_clicked(move |_| {
         info!("Servicing: Install CachyOS Repos button clicked");
-        
+
         let commands = CommandSequence::new()
             .then(
                 Command::builder()
-                    .normal()
-                    .program("sh")
-                    .args(&[
-                        "-c",
-                        "curl -fsSL https://mirror.cachyos.org/cachyos-repo.tar.xz -o /tmp/cachyos-repo.tar.xz && cd /tmp && tar xvf cachyos-repo.tar.xz",
-                    ])
+                    .download(&url, "/tmp/cachyos-repo.tar.xz")
+                    .sha256(&hash)
                     .description("Downloading CachyOS repository files...")
                     .build(),
             )
+            .then(
+                Command::builder()
+                    .normal()
+                    .program("tar")
+                    .args(&["xvf", "/tmp/cachyos-repo.tar.xz", "-C", "/tmp"])
+                    .description("Extracting CachyOS repository files...")
+                    .build(),
+            )
             .then(
                 Command::builder()
                     .privileged()
@@ -568,11 +1166,27 @@ fn setup_cachyos_repos(page_builder: &Builder, window: &ApplicationWindow) {
 }
 
 fn setup_chaotic_aur(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_chaotic_aur = extract_widget::<gtk4::Button>(page_builder, "btn_chaotic_aur");
+    let Some(btn_chaotic_aur) = try_extract_widget::<gtk4::Button>(page_builder, "btn_chaotic_aur")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_chaotic_aur.connect_clicked(move |_| {
         info!("Servicing: Install Chaotic-AUR button clicked");
-        
+
+        let (keyring, mirrorlist) = match (
+            core::verify::get("chaotic-keyring"),
+            core::verify::get("chaotic-mirrorlist"),
+        ) {
+            (Ok(keyring), Ok(mirrorlist)) => (keyring, mirrorlist),
+            (Err(e), _) | (_, Err(e)) => {
+                show_error(&window, &e.to_string());
+                return;
+            }
+        };
+        let keyring_file = "/tmp/chaotic-keyring.pkg.tar.zst";
+        let mirrorlist_file = "/tmp/chaotic-mirrorlist.pkg.tar.zst";
+
         let commands = CommandSequence::new()
             .then(
                 Command::builder()
@@ -590,28 +1204,47 @@ fn setup_chaotic_aur(page_builder: &Builder, window: &ApplicationWindow) {
                     .description("Locally signing Chaotic-AUR key...")
                     .build(),
             )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &core::verify::fetch_script(&keyring, keyring_file)])
+                    .description("Downloading and verifying Chaotic-AUR keyring...")
+                    .build(),
+            )
             .then(
                 Command::builder()
                     .privileged()
                     .program("pacman")
-                    .args(&[
-                        "-U",
-                        "--noconfirm",
-                        "https://cdn-mirror.chaotic.cx/chaotic-aur/chaotic-keyring.pkg.tar.zst",
-                    ])
+                    .args(&["-U", "--noconfirm", keyring_file])
                     .description("Installing Chaotic-AUR keyring...")
                     .build(),
             )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &core::verify::fetch_script(&mirrorlist, mirrorlist_file)])
+                    .description("Downloading and verifying Chaotic-AUR mirrorlist...")
+                    .build(),
+            )
             .then(
                 Command::builder()
                     .privileged()
                     .program("pacman")
+                    .args(&["-U", "--noconfirm", mirrorlist_file])
+                    .description("Installing Chaotic-AUR mirrorlist...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
                     .args(&[
-                        "-U",
-                        "--noconfirm",
-                        "https://cdn-mirror.chaotic.cx/chaotic-aur/chaotic-mirrorlist.pkg.tar.zst",
+                        "-c",
+                        &format!("rm -f {}* {}*", keyring_file, mirrorlist_file),
                     ])
-                    .description("Installing Chaotic-AUR mirrorlist...")
+                    .description("Cleaning up temporary files...")
                     .build(),
             )
             .then(
@@ -640,21 +1273,54 @@ fn setup_chaotic_aur(page_builder: &Builder, window: &ApplicationWindow) {
 }
 
 fn setup_xero_repo(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_xero_repo = extract_widget::<gtk4::Button>(page_builder, "btn_xero_repo");
+    let Some(btn_xero_repo) = try_extract_widget::<gtk4::Button>(page_builder, "btn_xero_repo")
+    else {
+        return;
+    };
     let window = window.clone();
     btn_xero_repo.connect_clicked(move |_| {
         info!("Servicing: Add Xero Linux Repository button clicked");
-        
-        let commands = CommandSequence::new()
+        super::run_action(&window, &actions::servicing::XERO_REPO);
+    });
+}
+
+/// What removing a repository touches, read off the main thread.
+struct RepoRemoval {
+    repo: &'static ThirdPartyRepo,
+    conf: PacmanConf,
+    sections: Vec<String>,
+    packages: Vec<&'static str>,
+    origins: Origins,
+}
+
+impl RepoRemoval {
+    fn read(repo: &'static ThirdPartyRepo) -> anyhow::Result<Self> {
+        let conf = PacmanConf::read()?;
+        let sections = repo.sections(&conf);
+        let origins = repo.origins(&sections);
+        Ok(Self {
+            repo,
+            conf,
+            sections,
+            packages: repo.installed_packages(),
+            origins,
+        })
+    }
+
+    /// Steps removing the repository, reinstalling the packages also in
+    /// the other repositories first if `reinstall` is set.
+    fn commands(&self, reinstall: bool) -> CommandSequence {
+        let mut conf = self.conf.clone();
+        for section in &self.sections {
+            conf.remove_section(section);
+        }
+        let mut commands = CommandSequence::new()
             .then(
                 Command::builder()
                     .privileged()
                     .program("sh")
-                    .args(&[
-                        "-c",
-                        "grep -q '\\[xerolinux\\]' /etc/pacman.conf || echo -e '\\n[xerolinux]\\nSigLevel = Optional TrustAll\\nServer = https://repos.xerolinux.xyz/$repo/$arch' >> /etc/pacman.conf",
-                    ])
-                    .description("Adding Xero Linux repository to pacman.conf...")
+                    .args(&["-c", &pacman_conf::write_script(), "sh", &conf.to_string()])
+                    .description(&format!("Removing {} from pacman.conf...", self.repo.name))
                     .build(),
             )
             .then(
@@ -664,39 +1330,263 @@ fn setup_xero_repo(page_builder: &Builder, window: &ApplicationWindow) {
                     .args(&["-Syy"])
                     .description("Refreshing package databases...")
                     .build(),
-            )
-            .build();
+            );
+
+        if reinstall && !self.origins.elsewhere.is_empty() {
+            let mut args = vec!["-S", "--noconfirm"];
+            args.extend(self.origins.elsewhere.iter().map(String::as_str));
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&args)
+                    .description("Reinstalling packages from the other repositories...")
+                    .build(),
+            );
+        }
+        if !self.packages.is_empty() {
+            let mut args = vec!["-Rdd", "--noconfirm"];
+            args.extend(&self.packages);
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman")
+                    .args(&args)
+                    .description(&format!(
+                        "Removing {} keyring and mirrorlist...",
+                        self.repo.name
+                    ))
+                    .build(),
+            );
+        }
+        if !self.repo.keys.is_empty() {
+            let mut args = vec!["--delete"];
+            args.extend(self.repo.keys);
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("pacman-key")
+                    .args(&args)
+                    .description(&format!("Removing {} signing key...", self.repo.name))
+                    .build(),
+            );
+        }
+        commands.build()
+    }
+}
+
+/// A remove button's id, its repository and whether it's configured.
+type RepoRemovalButton = (&'static str, &'static ThirdPartyRepo, fn() -> bool);
+
+fn setup_repo_removal(page_builder: &Builder, window: &ApplicationWindow) {
+    let removals: [RepoRemovalButton; 3] = [
+        ("btn_cachyos_repos_remove", &repos::CACHYOS, || {
+            repos::CACHYOS.is_configured()
+        }),
+        ("btn_chaotic_aur_remove", &repos::CHAOTIC_AUR, || {
+            repos::CHAOTIC_AUR.is_configured()
+        }),
+        ("btn_xero_repo_remove", &repos::XEROLINUX, || {
+            repos::XEROLINUX.is_configured()
+        }),
+    ];
+
+    for (id, repo, is_configured) in removals {
+        let Some(button) = try_extract_widget::<gtk4::Button>(page_builder, id) else {
+            continue;
+        };
+
+        let button_clone = button.clone();
+        install_state::bind_widget(&button, Probe::Custom(is_configured), move |configured| {
+            button_clone.set_visible(configured);
+        });
 
-        task_runner::run(window.upcast_ref(), commands, "Add Xero Linux Repository");
+        let window = window.clone();
+        button.connect_clicked(move |button| {
+            info!("Servicing: Remove {} button clicked", repo.name);
+
+            // pacman.conf and the sync databases are read off the main thread
+            button.set_sensitive(false);
+            let button = button.clone();
+            let window = window.clone();
+            spawn_blocking(
+                move || RepoRemoval::read(repo),
+                move |removal| {
+                    button.set_sensitive(true);
+                    match removal {
+                        Ok(removal) if removal.sections.is_empty() => {
+                            toast::show(&format!("{} isn't in pacman.conf", repo.name));
+                            install_state::refresh();
+                        }
+                        Ok(removal) => offer_reinstall(&window, removal),
+                        Err(e) => show_error(&window, &format!("{:#}", e)),
+                    }
+                },
+            );
+        });
+    }
+}
+
+/// Ask whether the packages the repository provided that the other
+/// repositories also have should be reinstalled from there.
+fn offer_reinstall(window: &ApplicationWindow, removal: RepoRemoval) {
+    let count = removal.origins.elsewhere.len();
+    if count == 0 {
+        confirm_repo_removal(window, removal, false);
+        return;
+    }
+    info!(
+        "{} installed packages from {} are in the other repositories",
+        count, removal.repo.name
+    );
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(gettext("Packages From This Repository"))
+        .body(ngettext_f(
+            "{count} installed package came from {repo} and is also in your other \
+             repositories:\n\n{packages}\n\nReinstalling it from there keeps it \
+             updated. Kept as it is, it stays installed but no longer gets \
+             updates.",
+            "{count} installed packages came from {repo} and are also in your other \
+             repositories:\n\n{packages}\n\nReinstalling them from there keeps them \
+             updated. Kept as they are, they stay installed but no longer get \
+             updates.",
+            count as u32,
+            &[
+                ("count", &count.to_string()),
+                ("repo", removal.repo.name),
+                ("packages", &package_summary(&removal.origins.elsewhere)),
+            ],
+        ))
+        .build();
+    dialog.add_responses(&[
+        ("cancel", gettext("Cancel").as_str()),
+        ("keep", gettext("Keep As They Are").as_str()),
+        ("reinstall", gettext("Reinstall").as_str()),
+    ]);
+    dialog.set_response_appearance("reinstall", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("reinstall"));
+    dialog.set_close_response("cancel");
+
+    let window_clone = window.clone();
+    let pending = RefCell::new(Some(removal));
+    dialog.connect_response(None, move |_, response| {
+        if response == "cancel" {
+            return;
+        }
+        if let Some(removal) = pending.take() {
+            confirm_repo_removal(&window_clone, removal, response == "reinstall");
+        }
     });
+    dialog.present(Some(window));
+}
+
+fn confirm_repo_removal(window: &ApplicationWindow, removal: RepoRemoval, reinstall: bool) {
+    let name = removal.repo.name;
+    let mut message = gettext_f(
+        "Remove <b>{repo}</b> from pacman.conf?\n\nSections: {sections}",
+        &[("repo", name), ("sections", &removal.sections.join(", "))],
+    );
+    if !removal.packages.is_empty() {
+        message.push_str("\n\n");
+        message.push_str(&gettext_f(
+            "Its keyring and mirrorlist are removed too: {packages}.",
+            &[("packages", &removal.packages.join(", "))],
+        ));
+    }
+    if reinstall {
+        let count = removal.origins.elsewhere.len();
+        message.push_str("\n\n");
+        message.push_str(&ngettext_f(
+            "{count} package is reinstalled from your other repositories.",
+            "{count} packages are reinstalled from your other repositories.",
+            count as u32,
+            &[("count", &count.to_string())],
+        ));
+    }
+    let mut kept = removal.origins.foreign.clone();
+    if !reinstall {
+        kept.extend(removal.origins.elsewhere.iter().cloned());
+    }
+    if !kept.is_empty() {
+        kept.sort();
+        message.push_str("\n\n");
+        message.push_str(&ngettext_f(
+            "<span weight=\"bold\">{count} installed package stays as a foreign package</span> \
+             and no longer gets updates:\n{packages}",
+            "<span weight=\"bold\">{count} installed packages stay as foreign packages</span> \
+             and no longer get updates:\n{packages}",
+            kept.len() as u32,
+            &[
+                ("count", &kept.len().to_string()),
+                (
+                    "packages",
+                    &glib::markup_escape_text(&package_summary(&kept)),
+                ),
+            ],
+        ));
+    }
+
+    let window_clone = window.clone();
+    show_destructive_confirmation(
+        window.upcast_ref(),
+        &gettext_f("Remove {repo}", &[("repo", name)]),
+        &message,
+        move |snapshot_step| {
+            info!("Removing the {} repository", name);
+            task_runner::run(
+                window_clone.upcast_ref(),
+                snapshot::before(snapshot_step, removal.commands(reinstall)),
+                &gettext_f("Remove {repo} Repository", &[("repo", name)]),
+            );
+        },
+    );
+}
+
+/// The first few of `packages`, and how many more there are.
+fn package_summary(packages: &[String]) -> String {
+    const SHOWN: usize = 12;
+    let mut summary = packages
+        .iter()
+        .take(SHOWN)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if packages.len() > SHOWN {
+        summary.push(' ');
+        summary.push_str(&gettext_f(
+            "and {count} more",
+            &[("count", &(packages.len() - SHOWN).to_string())],
+        ));
+    }
+    summary
 }
 
 fn setup_xpackagemanager(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn_xpackagemanager = extract_widget::<gtk4::Button>(page_builder, "btn_xpackagemanager");
-    let btn_xpackagemanager_uninstall = extract_widget::<gtk4::Button>(page_builder, "btn_xpackagemanager_uninstall");
+    let ids = ["btn_xpackagemanager", "btn_xpackagemanager_uninstall"];
+    let Some([btn_xpackagemanager, btn_xpackagemanager_uninstall]) =
+        extract_action_widgets::<gtk4::Button, 2>(page_builder, ids)
+    else {
+        return;
+    };
 
     fn update_button_state(setup_btn: &gtk4::Button, uninstall_btn: &gtk4::Button, is_installed: bool) {
         if is_installed {
-            setup_btn.set_label("Launch");
+            setup_btn.set_label(&gettext("Launch"));
             setup_btn.add_css_class("suggested-action");
             uninstall_btn.set_visible(true);
         } else {
-            setup_btn.set_label("Install");
+            setup_btn.set_label(&gettext("Install"));
             setup_btn.remove_css_class("suggested-action");
             uninstall_btn.set_visible(false);
         }
     }
 
-    let is_installed = std::path::Path::new("/usr/bin/xpackagemanager").exists();
-    update_button_state(&btn_xpackagemanager, &btn_xpackagemanager_uninstall, is_installed);
-
     let btn_setup_clone = btn_xpackagemanager.clone();
     let btn_uninstall_clone = btn_xpackagemanager_uninstall.clone();
-    window.connect_is_active_notify(move |window| {
-        if window.is_active() {
-            let is_installed = std::path::Path::new("/usr/bin/xpackagemanager").exists();
-            update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
-        }
+    let probe = Probe::Path("/usr/bin/xpackagemanager");
+    install_state::bind_widget(&btn_xpackagemanager, probe, move |is_installed| {
+        update_button_state(&btn_setup_clone, &btn_uninstall_clone, is_installed);
     });
 
     let window_clone = window.clone();
@@ -926,225 +1816,116 @@ EOF"#,
     });
 }
 
-/// Get the latest remote commit hash from the toolkit GitHub repository.
-fn get_remote_commit() -> Option<String> {
-    std::process::Command::new("git")
-        .args(["ls-remote", config::links::TOOLKIT_REPO, "HEAD"])
-        .output()
-        .ok()
-        .filter(|o| o.status.success())
-        .and_then(|o| {
-            String::from_utf8_lossy(&o.stdout)
-                .split_whitespace()
-                .next()
-                .map(|s| s.to_string())
-        })
-}
+/// Working directory for toolkit updates.
+const UPDATE_DIR: &str = "/tmp/xero-toolkit-update";
 
-/// Get the locally stored commit hash from the last toolkit install/update.
-fn get_local_commit() -> Option<String> {
-    std::fs::read_to_string("/opt/xero-toolkit/.commit")
-        .ok()
-        .map(|s| s.trim().to_string())
-        .filter(|s| !s.is_empty())
+/// Shorten a commit hash for display; tags are shown as-is.
+fn short_version(version: &str) -> &str {
+    &version[..version.len().min(12)]
 }
 
-fn setup_update_toolkit(page_builder: &Builder, window: &ApplicationWindow) {
-    let btn = extract_widget::<gtk4::Button>(page_builder, "btn_update_toolkit");
-    let window = window.clone();
-
-    btn.connect_clicked(move |btn| {
-        info!("Servicing: Update Toolkit button clicked");
-
-        // Disable button while checking
-        btn.set_sensitive(false);
-        let btn_clone = btn.clone();
-
-        // Check for updates
-        let remote = get_remote_commit();
-        let local = get_local_commit();
+/// Commands that install binaries from `bin_dir` and support files from
+/// the update directory into the toolkit prefix.
+fn install_update_commands(commands: CommandSequence, bin_dir: &str) -> CommandSequence {
+    let mut commands = commands;
+    for binary in ["xero-toolkit", "xero-authd", "xero-auth"] {
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("install")
+                .args(&[
+                    "-Dm755",
+                    &format!("{}/{}", bin_dir, binary),
+                    &format!("{}/{}", self_update::INSTALL_DIR, binary),
+                ])
+                .description(&format!("Installing updated {} binary...", binary))
+                .build(),
+        );
+    }
 
-        btn_clone.set_sensitive(true);
+    commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    &format!(
+                        "cp -f {dir}/sources/scripts/* /opt/xero-toolkit/sources/scripts/ && \
+                         chmod 755 /opt/xero-toolkit/sources/scripts/* && \
+                         cp -f {dir}/sources/systemd/* /opt/xero-toolkit/sources/systemd/ && \
+                         install -Dm644 -t /opt/xero-toolkit/sources/pins/ {dir}/sources/pins/* && \
+                         if [ -d {dir}/sources/keys ]; then \
+                            install -Dm644 -t /opt/xero-toolkit/sources/keys/ {dir}/sources/keys/*.gpg; \
+                         fi",
+                        dir = UPDATE_DIR
+                    ),
+                ])
+                .description("Updating scripts, systemd units and pinned checksums...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    &format!(
+                        "if [ -d {dir}/extra-scripts/usr/local/bin ]; then \
+                            cp -f {dir}/extra-scripts/usr/local/bin/* /usr/local/bin/ 2>/dev/null; \
+                            chmod 755 /usr/local/bin/upd /usr/local/bin/grubup 2>/dev/null; \
+                         fi; true",
+                        dir = UPDATE_DIR
+                    ),
+                ])
+                .description("Updating extra scripts...")
+                .build(),
+        )
+}
 
-        // If we can't reach GitHub, warn the user
-        let remote_hash = match remote {
-            Some(hash) => hash,
-            None => {
-                let dialog = adw::Window::new();
-                dialog.set_title(Some("Xero Toolkit - Update"));
-                dialog.set_default_size(420, 200);
-                dialog.set_modal(true);
-                dialog.set_transient_for(Some(&window));
-
-                let toolbar = adw::ToolbarView::new();
-                let header = adw::HeaderBar::new();
-                toolbar.add_top_bar(&header);
-
-                let content = GtkBox::new(Orientation::Vertical, 16);
-                content.set_margin_top(24);
-                content.set_margin_bottom(24);
-                content.set_margin_start(24);
-                content.set_margin_end(24);
-                content.set_halign(gtk4::Align::Center);
-                content.set_valign(gtk4::Align::Center);
-
-                let icon = gtk4::Image::from_icon_name("network-error-symbolic");
-                icon.set_pixel_size(48);
-                content.append(&icon);
-
-                let label = Label::new(Some(
-                    "Could not reach GitHub to check for updates.\nPlease check your internet connection.",
-                ));
-                label.set_halign(gtk4::Align::Center);
-                label.set_justify(gtk4::Justification::Center);
-                content.append(&label);
-
-                let ok_btn = gtk4::Button::with_label("OK");
-                ok_btn.add_css_class("suggested-action");
-                ok_btn.add_css_class("pill");
-                ok_btn.set_halign(gtk4::Align::Center);
-                let dialog_clone = dialog.clone();
-                ok_btn.connect_clicked(move |_| dialog_clone.close());
-                content.append(&ok_btn);
-
-                toolbar.set_content(Some(&content));
-                dialog.set_content(Some(&toolbar));
-                dialog.present();
-                return;
+/// Build the full update sequence for a completed update check.
+fn build_update_commands(check: &self_update::UpdateCheck) -> CommandSequence {
+    let (commands, bin_dir) = match &check.source {
+        self_update::UpdateSource::Prebuilt(release) => {
+            let mut commands = CommandSequence::new().then(
+                Command::builder()
+                    .normal()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        &format!("rm -rf {dir} && mkdir -p {dir}", dir = UPDATE_DIR),
+                    ])
+                    .description("Preparing update directory...")
+                    .build(),
+            );
+            for (url, file) in self_update::prebuilt_downloads(release) {
+                commands = commands.then(
+                    Command::builder()
+                        .download(&url, &format!("{}/{}", UPDATE_DIR, file))
+                        .description(&format!("Downloading {}...", file))
+                        .build(),
+                );
             }
-        };
-
-        // Check if already up to date
-        let is_up_to_date = local
-            .as_ref()
-            .map(|l| l == &remote_hash)
-            .unwrap_or(false);
-
-        if is_up_to_date {
-            let dialog = adw::Window::new();
-            dialog.set_title(Some("Xero Toolkit - Update"));
-            dialog.set_default_size(420, 200);
-            dialog.set_modal(true);
-            dialog.set_transient_for(Some(&window));
-
-            let toolbar = adw::ToolbarView::new();
-            let header = adw::HeaderBar::new();
-            toolbar.add_top_bar(&header);
-
-            let content = GtkBox::new(Orientation::Vertical, 16);
-            content.set_margin_top(24);
-            content.set_margin_bottom(24);
-            content.set_margin_start(24);
-            content.set_margin_end(24);
-            content.set_halign(gtk4::Align::Center);
-            content.set_valign(gtk4::Align::Center);
-
-            let icon = gtk4::Image::from_icon_name("emblem-ok-symbolic");
-            icon.set_pixel_size(48);
-            content.append(&icon);
-
-            let label = Label::new(Some("CyberXero Toolkit is already up to date!"));
-            label.set_halign(gtk4::Align::Center);
-            label.set_justify(gtk4::Justification::Center);
-            content.append(&label);
-
-            let hash_label = Label::new(Some(&format!("Commit: {}", &remote_hash[..12])));
-            hash_label.add_css_class("dim-label");
-            hash_label.add_css_class("caption");
-            hash_label.set_halign(gtk4::Align::Center);
-            content.append(&hash_label);
-
-            let ok_btn = gtk4::Button::with_label("OK");
-            ok_btn.add_css_class("suggested-action");
-            ok_btn.add_css_class("pill");
-            ok_btn.set_halign(gtk4::Align::Center);
-            let dialog_clone = dialog.clone();
-            ok_btn.connect_clicked(move |_| dialog_clone.close());
-            content.append(&ok_btn);
-
-            toolbar.set_content(Some(&content));
-            dialog.set_content(Some(&toolbar));
-            dialog.present();
-            return;
-        }
-
-        // Updates available — show confirmation with commit info, then run update
-        let dialog = adw::Window::new();
-        dialog.set_title(Some("Xero Toolkit - Update Available"));
-        dialog.set_default_size(480, 280);
-        dialog.set_modal(true);
-        dialog.set_transient_for(Some(&window));
-
-        let toolbar = adw::ToolbarView::new();
-        let header = adw::HeaderBar::new();
-        toolbar.add_top_bar(&header);
-
-        let content = GtkBox::new(Orientation::Vertical, 16);
-        content.set_margin_top(24);
-        content.set_margin_bottom(24);
-        content.set_margin_start(24);
-        content.set_margin_end(24);
-        content.set_halign(gtk4::Align::Center);
-        content.set_valign(gtk4::Align::Center);
-
-        let icon = gtk4::Image::from_icon_name("software-update-available-symbolic");
-        icon.set_pixel_size(48);
-        content.append(&icon);
-
-        let title_label = Label::new(Some("A new version is available!"));
-        title_label.add_css_class("title-3");
-        title_label.set_halign(gtk4::Align::Center);
-        content.append(&title_label);
-
-        let info_text = match &local {
-            Some(l) => format!(
-                "Current: {}\nLatest:  {}",
-                &l[..l.len().min(12)],
-                &remote_hash[..12]
-            ),
-            None => format!("Latest: {}", &remote_hash[..12]),
-        };
-        let info_label = Label::new(Some(&info_text));
-        info_label.add_css_class("dim-label");
-        info_label.add_css_class("monospace");
-        info_label.set_halign(gtk4::Align::Center);
-        content.append(&info_label);
-
-        let note_label = Label::new(Some(
-            "This will download, build, and install the latest version.\nThe toolkit will need to be restarted after updating.",
-        ));
-        note_label.set_wrap(true);
-        note_label.set_halign(gtk4::Align::Center);
-        note_label.set_justify(gtk4::Justification::Center);
-        content.append(&note_label);
-
-        let button_box = GtkBox::new(Orientation::Horizontal, 12);
-        button_box.set_halign(gtk4::Align::Center);
-
-        let cancel_btn = gtk4::Button::with_label("Cancel");
-        cancel_btn.add_css_class("pill");
-        cancel_btn.set_width_request(120);
-        let dialog_cancel = dialog.clone();
-        cancel_btn.connect_clicked(move |_| dialog_cancel.close());
-        button_box.append(&cancel_btn);
-
-        let update_btn = gtk4::Button::with_label("Update Now");
-        update_btn.add_css_class("suggested-action");
-        update_btn.add_css_class("pill");
-        update_btn.set_width_request(120);
-
-        let window_clone = window.clone();
-        let dialog_update = dialog.clone();
-        let remote_hash_clone = remote_hash.clone();
-        update_btn.connect_clicked(move |_| {
-            dialog_update.close();
-
-            let repo_url = config::links::TOOLKIT_REPO;
-            let commit_store_cmd = format!(
-                "echo '{}' | tee /opt/xero-toolkit/.commit > /dev/null",
-                remote_hash_clone
+            commands = commands.then(
+                Command::builder()
+                    .normal()
+                    .program("sh")
+                    .args(&[
+                        "-c",
+                        &self_update::verify_prebuilt_script(release, UPDATE_DIR),
+                    ])
+                    .description(&format!("Verifying CyberXero Toolkit {}...", release.tag))
+                    .build(),
             );
-
+            (commands, UPDATE_DIR.to_string())
+        }
+        self_update::UpdateSource::Source { git_ref } => {
+            // Tags can be cloned directly; commits are whatever HEAD was at check time.
+            let branch = if check.channel == self_update::Channel::Stable {
+                format!("--branch {} ", git_ref)
+            } else {
+                String::new()
+            };
             let commands = CommandSequence::new()
                 .then(
                     Command::builder()
@@ -1153,112 +1934,346 @@ fn setup_update_toolkit(page_builder: &Builder, window: &ApplicationWindow) {
                         .args(&[
                             "-c",
                             &format!(
-                                "rm -rf /tmp/xero-toolkit-update && git clone --depth 1 {} /tmp/xero-toolkit-update",
-                                repo_url
+                                "rm -rf {dir} && git clone --depth 1 {branch}{repo} {dir}",
+                                dir = UPDATE_DIR,
+                                branch = branch,
+                                repo = config::links::TOOLKIT_REPO
                             ),
                         ])
-                        .description("Cloning latest CyberXero Toolkit from GitHub...")
+                        .description("Cloning CyberXero Toolkit from GitHub...")
                         .build(),
                 )
                 .then(
                     Command::builder()
                         .normal()
                         .program("sh")
-                        .args(&["-c", "cd /tmp/xero-toolkit-update && cargo build --release"])
+                        .args(&["-c", &format!("cd {} && cargo build --release", UPDATE_DIR)])
                         .description("Building CyberXero Toolkit (this may take a few minutes)...")
                         .build(),
-                )
-                .then(
-                    Command::builder()
-                        .privileged()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "install -Dm755 /tmp/xero-toolkit-update/target/release/xero-toolkit /opt/xero-toolkit/xero-toolkit",
-                        ])
-                        .description("Installing updated xero-toolkit binary...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .privileged()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "install -Dm755 /tmp/xero-toolkit-update/target/release/xero-authd /opt/xero-toolkit/xero-authd",
-                        ])
-                        .description("Installing updated xero-authd binary...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .privileged()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "install -Dm755 /tmp/xero-toolkit-update/target/release/xero-auth /opt/xero-toolkit/xero-auth",
-                        ])
-                        .description("Installing updated xero-auth binary...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .privileged()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "cp -f /tmp/xero-toolkit-update/sources/scripts/* /opt/xero-toolkit/sources/scripts/ && \
-                             chmod 755 /opt/xero-toolkit/sources/scripts/* && \
-                             cp -f /tmp/xero-toolkit-update/sources/systemd/* /opt/xero-toolkit/sources/systemd/",
-                        ])
-                        .description("Updating scripts and systemd units...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .privileged()
-                        .program("sh")
-                        .args(&[
-                            "-c",
-                            "if [ -d /tmp/xero-toolkit-update/extra-scripts/usr/local/bin ]; then \
-                                cp -f /tmp/xero-toolkit-update/extra-scripts/usr/local/bin/* /usr/local/bin/ 2>/dev/null; \
-                                chmod 755 /usr/local/bin/upd /usr/local/bin/grubup 2>/dev/null; \
-                             fi; true",
-                        ])
-                        .description("Updating extra scripts...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .privileged()
-                        .program("sh")
-                        .args(&["-c", &commit_store_cmd])
-                        .description("Recording update version...")
-                        .build(),
-                )
-                .then(
-                    Command::builder()
-                        .normal()
-                        .program("rm")
-                        .args(&["-rf", "/tmp/xero-toolkit-update"])
-                        .description("Cleaning up temporary files...")
-                        .build(),
-                )
-                .build();
+                );
+            (commands, format!("{}/target/release", UPDATE_DIR))
+        }
+    };
+
+    let commands = commands.then(
+        Command::builder()
+            .privileged()
+            .program("sh")
+            .args(&["-c", &self_update::backup_script()])
+            .description("Keeping current version for rollback...")
+            .build(),
+    );
+
+    install_update_commands(commands, &bin_dir)
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", &self_update::record_version_command(check)])
+                .description("Recording update version...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("rm")
+                .args(&["-rf", UPDATE_DIR])
+                .description("Cleaning up temporary files...")
+                .build(),
+        )
+}
 
-            task_runner::run(
-                window_clone.upcast_ref(),
-                commands,
-                "Update CyberXero Toolkit",
-            );
-        });
-        button_box.append(&update_btn);
+/// Scrollable list of the changes an update brings, filled in once the
+/// changelog has been fetched in the background.
+fn build_changelog_view(check: &self_update::UpdateCheck) -> ScrolledWindow {
+    let list = GtkBox::new(Orientation::Vertical, 10);
+    list.set_margin_top(12);
+    list.set_margin_bottom(12);
+    list.set_margin_start(12);
+    list.set_margin_end(12);
+
+    let loading = Label::new(Some(&gettext("Loading changes...")));
+    loading.add_css_class("dim-label");
+    list.append(&loading);
+
+    let scrolled = ScrolledWindow::new();
+    scrolled.set_hscrollbar_policy(gtk4::PolicyType::Never);
+    scrolled.set_min_content_height(200);
+    scrolled.set_min_content_width(460);
+    scrolled.set_vexpand(true);
+    scrolled.add_css_class("card");
+    scrolled.set_child(Some(&list));
+
+    let check = check.clone();
+    let fetch = move || changelog::fetch(&check);
+    spawn_blocking(fetch, move |result| {
+        list.remove(&loading);
+
+        let Some(log) = result else {
+            let label = Label::new(Some(&gettext("Could not load the changelog.")));
+            label.add_css_class("dim-label");
+            list.append(&label);
+            return;
+        };
 
-        content.append(&button_box);
-        toolbar.set_content(Some(&content));
-        dialog.set_content(Some(&toolbar));
-        dialog.present();
+        if log.entries.is_empty() {
+            let label = Label::new(Some(&gettext("No change details available.")));
+            label.add_css_class("dim-label");
+            list.append(&label);
+        }
+
+        for entry in &log.entries {
+            let row = GtkBox::new(Orientation::Vertical, 4);
+
+            let title = Label::new(Some(&format!("{}  {}", entry.version, entry.title)));
+            title.set_xalign(0.0);
+            title.set_wrap(true);
+            title.add_css_class("heading");
+            row.append(&title);
+
+            if !entry.body.is_empty() {
+                let body = Label::new(Some(&entry.body));
+                body.set_xalign(0.0);
+                body.set_wrap(true);
+                body.set_selectable(true);
+                body.add_css_class("dim-label");
+                row.append(&body);
+            }
+
+            list.append(&row);
+        }
+
+        if log.truncated {
+            let more = Label::new(Some(&gettext("Older changes may not be listed.")));
+            more.add_css_class("dim-label");
+            more.add_css_class("caption");
+            more.set_xalign(0.0);
+            list.append(&more);
+        }
+    });
+
+    scrolled
+}
+
+fn setup_rollback_toolkit(page_builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn) = try_extract_widget::<gtk4::Button>(page_builder, "btn_rollback_toolkit") else {
+        return;
+    };
+    btn.set_sensitive(false);
+
+    // The snapshot appears after an update and disappears after a rollback.
+    let btn_clone = btn.clone();
+    let probe = Probe::Custom(self_update::has_previous);
+    install_state::bind(probe, move |has_previous| {
+        btn_clone.set_sensitive(has_previous);
+    });
+
+    let window = window.clone();
+    btn.connect_clicked(move |_| {
+        info!("Servicing: Roll back toolkit button clicked");
+
+        let version = self_update::previous_version()
+            .map(|v| format!(" (<b>{}</b>)", short_version(&v)))
+            .unwrap_or_default();
+        let message = gettext_f(
+            "This restores the toolkit version installed before the last update{version}.\n\n\
+             Only one previous version is kept. The toolkit will need to be restarted afterwards.",
+            &[("version", &version)],
+        );
+
+        let window_clone = window.clone();
+        show_warning_confirmation(
+            window.upcast_ref(),
+            &gettext("Roll Back Last Update"),
+            &message,
+            move || {
+                let commands = CommandSequence::new()
+                    .then(
+                        Command::builder()
+                            .privileged()
+                            .program("sh")
+                            .args(&["-c", &self_update::rollback_script()])
+                            .description("Restoring previous CyberXero Toolkit version...")
+                            .build(),
+                    )
+                    .build();
+
+                task_runner::run(
+                    window_clone.upcast_ref(),
+                    commands,
+                    "Roll Back CyberXero Toolkit",
+                );
+            },
+        );
+    });
+}
+
+/// Keep the channel selector in sync with the saved preference.
+fn setup_update_channel(page_builder: &Builder) {
+    let Some(dropdown) =
+        try_extract_widget::<gtk4::DropDown>(page_builder, "dropdown_update_channel")
+    else {
+        return;
+    };
+
+    let current = self_update::channel();
+    if let Some(pos) = self_update::Channel::ALL.iter().position(|c| *c == current) {
+        dropdown.set_selected(pos as u32);
+    }
+
+    dropdown.connect_selected_notify(|dropdown| {
+        let Some(channel) = self_update::Channel::ALL.get(dropdown.selected() as usize) else {
+            return;
+        };
+        info!("Servicing: Update channel set to {}", channel.id());
+        if let Err(e) = self_update::set_channel(*channel) {
+            log::warn!("Failed to save update channel: {}", e);
+        }
+    });
+}
+
+fn setup_update_toolkit(page_builder: &Builder, window: &ApplicationWindow) {
+    let Some(btn) = try_extract_widget::<gtk4::Button>(page_builder, "btn_update_toolkit") else {
+        return;
+    };
+    let window = window.clone();
+
+    btn.connect_clicked(move |btn| {
+        info!("Servicing: Update Toolkit button clicked");
+
+        // Disable button while checking; the check goes over the network
+        btn.set_sensitive(false);
+        let btn = btn.clone();
+        let window = window.clone();
+        spawn_blocking(
+            || self_update::check(self_update::channel()),
+            move |result| {
+                btn.set_sensitive(true);
+                show_update_result(&window, result);
+            },
+        );
+    });
+}
+
+/// Report the result of an update check, offering the update if there is one.
+fn show_update_result(window: &ApplicationWindow, result: Option<self_update::UpdateCheck>) {
+    // If we can't reach GitHub, warn the user
+    let check = match result {
+        Some(check) => check,
+        None => {
+            toast::show(&gettext("Could not reach GitHub to check for updates"));
+            return;
+        }
+    };
+
+    // Check if already up to date
+    if check.is_up_to_date() {
+        toast::show(&gettext_f(
+            "CyberXero Toolkit is up to date ({channel}: {version})",
+            &[
+                ("channel", check.channel.name()),
+                ("version", short_version(&check.latest)),
+            ],
+        ));
+        return;
+    }
+
+    // Updates available — show confirmation with commit info, then run update
+    let dialog = adw::Window::new();
+    dialog.set_title(Some(&gettext("Xero Toolkit - Update Available")));
+    dialog.set_default_size(560, 520);
+    dialog.set_modal(true);
+    dialog.set_transient_for(Some(window));
+
+    let toolbar = adw::ToolbarView::new();
+    let header = adw::HeaderBar::new();
+    toolbar.add_top_bar(&header);
+
+    let content = GtkBox::new(Orientation::Vertical, 16);
+    content.set_margin_top(24);
+    content.set_margin_bottom(24);
+    content.set_margin_start(24);
+    content.set_margin_end(24);
+    content.set_halign(gtk4::Align::Center);
+    content.set_valign(gtk4::Align::Center);
+
+    let icon = gtk4::Image::from_icon_name("software-update-available-symbolic");
+    icon.set_pixel_size(48);
+    content.append(&icon);
+
+    let title_label = Label::new(Some(&gettext("A new version is available!")));
+    title_label.add_css_class("title-3");
+    title_label.set_halign(gtk4::Align::Center);
+    content.append(&title_label);
+
+    let info_text = match &check.installed {
+        Some(installed) => gettext_f(
+            "Current: {installed}\nLatest:  {latest}",
+            &[
+                ("installed", short_version(installed)),
+                ("latest", short_version(&check.latest)),
+            ],
+        ),
+        None => gettext_f(
+            "Latest: {latest}",
+            &[("latest", short_version(&check.latest))],
+        ),
+    };
+    let info_label = Label::new(Some(&info_text));
+    info_label.add_css_class("dim-label");
+    info_label.add_css_class("monospace");
+    info_label.set_halign(gtk4::Align::Center);
+    content.append(&info_label);
+
+    content.append(&build_changelog_view(&check));
+
+    let note_text = match &check.source {
+        self_update::UpdateSource::Prebuilt(_) => gettext(
+            "This will download, verify, and install the prebuilt release.\nThe toolkit will need to be restarted after updating.",
+        ),
+        self_update::UpdateSource::Source { .. } => gettext(
+            "This will download, build, and install the latest version.\nThe toolkit will need to be restarted after updating.",
+        ),
+    };
+    let note_label = Label::new(Some(&note_text));
+    note_label.set_wrap(true);
+    note_label.set_halign(gtk4::Align::Center);
+    note_label.set_justify(gtk4::Justification::Center);
+    content.append(&note_label);
+
+    let button_box = GtkBox::new(Orientation::Horizontal, 12);
+    button_box.set_halign(gtk4::Align::Center);
+
+    let cancel_btn = gtk4::Button::with_label(&gettext("Cancel"));
+    cancel_btn.add_css_class("pill");
+    cancel_btn.set_width_request(120);
+    let dialog_cancel = dialog.clone();
+    cancel_btn.connect_clicked(move |_| dialog_cancel.close());
+    button_box.append(&cancel_btn);
+
+    let update_btn = gtk4::Button::with_label(&gettext("Update Now"));
+    update_btn.add_css_class("suggested-action");
+    update_btn.add_css_class("pill");
+    update_btn.set_width_request(120);
+
+    let window_clone = window.clone();
+    let dialog_update = dialog.clone();
+    update_btn.connect_clicked(move |_| {
+        dialog_update.close();
+
+        let commands = build_update_commands(&check).build();
+        task_runner::run(
+            window_clone.upcast_ref(),
+            commands,
+            "Update CyberXero Toolkit",
+        );
     });
+    button_box.append(&update_btn);
+
+    content.append(&button_box);
+    toolbar.set_content(Some(&content));
+    dialog.set_content(Some(&toolbar));
+    dialog.present();
 }
 
 /// Service definition for the optimization toggles.
@@ -1298,7 +2313,9 @@ const OPTIMIZATION_SERVICES: &[ServiceDef] = &[
 
 fn setup_optimization_services(page_builder: &Builder, window: &ApplicationWindow) {
     for svc in OPTIMIZATION_SERVICES {
-        let button = extract_widget::<ToggleButton>(page_builder, svc.switch_id);
+        let Some(button) = try_extract_widget::<ToggleButton>(page_builder, svc.switch_id) else {
+            continue;
+        };
 
         // Set initial state based on whether the service is currently enabled
         let enabled = if svc.is_user {
@@ -1370,7 +2387,7 @@ fn setup_optimization_services(page_builder: &Builder, window: &ApplicationWindo
                 task_runner::run(
                     window.upcast_ref(),
                     seq.build(),
-                    &format!("Enable {}", service),
+                    &gettext_f("Enable {service}", &[("service", service)]),
                 );
             } else {
                 // Disable and stop the service
@@ -1401,7 +2418,7 @@ fn setup_optimization_services(page_builder: &Builder, window: &ApplicationWindo
                 task_runner::run(
                     window.upcast_ref(),
                     seq,
-                    &format!("Disable {}", service),
+                    &gettext_f("Disable {service}", &[("service", service)]),
                 );
             }
         });
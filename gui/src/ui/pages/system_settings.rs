@@ -0,0 +1,299 @@
+//! System settings page button handlers.
+//!
+//! Handles:
+//! - Hostname (hostnamectl)
+//! - Timezone selection with search (timedatectl)
+//! - NTP toggle
+//! - Locale generation and LANG (locale.gen + localectl)
+
+use crate::i18n::{gettext, n_};
+use crate::ui::dialogs::error::show_error;
+use crate::ui::task_runner::{self, Command, CommandSequence};
+use crate::ui::utils::{disable_action_row, get_combo_row_value, run_command, try_extract_widget};
+use adw::prelude::*;
+use gtk4::glib::{self, SignalHandlerId};
+use gtk4::{ApplicationWindow, Builder, Button, StringList};
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Supported locales shipped by glibc, one `<name> <charset>` per line.
+const SUPPORTED_LOCALES: &str = "/usr/share/i18n/SUPPORTED";
+
+/// Set up all handlers for the system settings page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    setup_hostname(page_builder, window);
+    setup_timezone(page_builder, window);
+    setup_ntp(page_builder, window);
+    setup_locale(page_builder, window);
+}
+
+/// Validate a hostname per RFC 1123 (single label, 1-63 chars).
+fn is_valid_hostname(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 63
+        && !name.starts_with('-')
+        && !name.ends_with('-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Fill a combo row's string list and select `current` if present.
+fn populate_combo(combo: &adw::ComboRow, items: &[String], current: Option<&str>) {
+    let Some(list) = combo.model().and_downcast::<StringList>() else {
+        return;
+    };
+
+    let refs: Vec<&str> = items.iter().map(|s| s.as_str()).collect();
+    list.splice(0, list.n_items(), &refs);
+
+    if let Some(pos) = current.and_then(|c| items.iter().position(|i| i == c)) {
+        combo.set_selected(pos as u32);
+    }
+}
+
+fn setup_hostname(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_apply_hostname") else {
+        return;
+    };
+    let Some(entry) = try_extract_widget::<adw::EntryRow>(builder, "entry_hostname") else {
+        disable_action_row(&button);
+        return;
+    };
+
+    let current = std::fs::read_to_string("/etc/hostname").unwrap_or_default();
+    entry.set_text(current.trim());
+
+    let window = window.clone();
+    button.connect_clicked(move |_| {
+        info!("Apply hostname button clicked");
+
+        let hostname = entry.text().trim().to_string();
+        if !is_valid_hostname(&hostname) {
+            show_error(
+                &window,
+                &gettext("Invalid hostname. Use 1-63 letters, digits or hyphens, not starting or ending with a hyphen."),
+            );
+            return;
+        }
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("hostnamectl")
+                    .args(&["set-hostname", &hostname])
+                    .description(&format!("Setting hostname to {}...", hostname))
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window.upcast_ref(), commands, "Set Hostname");
+    });
+}
+
+fn setup_timezone(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_apply_timezone") else {
+        return;
+    };
+    let Some(combo) = try_extract_widget::<adw::ComboRow>(builder, "combo_timezone") else {
+        disable_action_row(&button);
+        return;
+    };
+
+    // timedatectl can be slow on first call — load the list off the main thread.
+    let (tx, rx) = async_channel::bounded::<(Vec<String>, Option<String>)>(1);
+    std::thread::spawn(move || {
+        let zones = run_command("timedatectl", &["list-timezones"])
+            .map(|out| out.lines().map(str::to_string).collect())
+            .unwrap_or_default();
+        let current = run_command("timedatectl", &["show", "-p", "Timezone", "--value"]);
+        let _ = tx.send_blocking((zones, current));
+    });
+
+    let combo_clone = combo.clone();
+    gtk4::glib::MainContext::default().spawn_local(async move {
+        if let Ok((zones, current)) = rx.recv().await {
+            populate_combo(&combo_clone, &zones, current.as_deref());
+        }
+    });
+
+    let window = window.clone();
+    button.connect_clicked(move |_| {
+        info!("Apply timezone button clicked");
+
+        let Some(zone) = get_combo_row_value(&combo) else {
+            return;
+        };
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("timedatectl")
+                    .args(&["set-timezone", &zone])
+                    .description(&format!("Setting timezone to {}...", zone))
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window.upcast_ref(), commands, "Set Timezone");
+    });
+}
+
+/// Whether timedatectl reports network time synchronization on.
+fn ntp_enabled() -> bool {
+    run_command("timedatectl", &["show", "-p", "NTP", "--value"]).is_some_and(|v| v == "yes")
+}
+
+fn setup_ntp(builder: &Builder, window: &ApplicationWindow) {
+    let Some(switch) = try_extract_widget::<adw::SwitchRow>(builder, "switch_ntp") else {
+        return;
+    };
+
+    // Set the initial state before connecting so it doesn't trigger a run.
+    switch.set_active(ntp_enabled());
+
+    let handler: Rc<RefCell<Option<SignalHandlerId>>> = Rc::default();
+    let handler_clone = handler.clone();
+    let window = window.clone();
+    let id = switch.connect_active_notify(move |switch| {
+        let enable = switch.is_active();
+        info!("NTP switch toggled: {}", enable);
+
+        let value = if enable { "true" } else { "false" };
+        let description = if enable {
+            n_("Enabling network time synchronization...")
+        } else {
+            n_("Disabling network time synchronization...")
+        };
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("timedatectl")
+                    .args(&["set-ntp", value])
+                    .description(description)
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window.upcast_ref(), commands, "Network Time");
+        sync_ntp_when_done(switch, handler_clone.clone());
+    });
+    *handler.borrow_mut() = Some(id);
+}
+
+/// Once the task runner is done, put the switch back to the real NTP state,
+/// which it isn't in when the task failed or was cancelled. The handler is
+/// blocked meanwhile, so that doesn't start another run.
+fn sync_ntp_when_done(switch: &adw::SwitchRow, handler: Rc<RefCell<Option<SignalHandlerId>>>) {
+    let switch = switch.clone();
+    glib::timeout_add_seconds_local(1, move || {
+        if task_runner::is_running() {
+            return glib::ControlFlow::Continue;
+        }
+        let enabled = ntp_enabled();
+        if switch.is_active() != enabled {
+            info!("Network time is still {}, reverting the switch", enabled);
+            if let Some(id) = handler.borrow().as_ref() {
+                switch.block_signal(id);
+                switch.set_active(enabled);
+                switch.unblock_signal(id);
+            }
+        }
+        glib::ControlFlow::Break
+    });
+}
+
+/// Read available locale lines (e.g. `en_US.UTF-8 UTF-8`).
+fn read_supported_locales() -> Vec<String> {
+    std::fs::read_to_string(SUPPORTED_LOCALES)
+        .unwrap_or_default()
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Read the current LANG from /etc/locale.conf.
+fn read_current_lang() -> Option<String> {
+    std::fs::read_to_string("/etc/locale.conf")
+        .ok()?
+        .lines()
+        .find_map(|l| {
+            l.trim()
+                .strip_prefix("LANG=")
+                .map(|v| v.trim_matches('"').to_string())
+        })
+}
+
+fn setup_locale(builder: &Builder, window: &ApplicationWindow) {
+    let Some(button) = try_extract_widget::<Button>(builder, "btn_apply_locale") else {
+        return;
+    };
+    let Some(combo) = try_extract_widget::<adw::ComboRow>(builder, "combo_locale") else {
+        disable_action_row(&button);
+        return;
+    };
+
+    let locales = read_supported_locales();
+    let current = read_current_lang().and_then(|lang| {
+        locales
+            .iter()
+            .find(|l| l.split(' ').next() == Some(lang.as_str()))
+            .cloned()
+    });
+    populate_combo(&combo, &locales, current.as_deref());
+
+    let window = window.clone();
+    button.connect_clicked(move |_| {
+        info!("Apply locale button clicked");
+
+        let Some(line) = get_combo_row_value(&combo) else {
+            return;
+        };
+        // Only accept entries from the supported list; they go into a shell script.
+        if !read_supported_locales().contains(&line) {
+            return;
+        }
+        let Some(lang) = line.split(' ').next().map(str::to_string) else {
+            return;
+        };
+
+        let enable_script = format!(
+            "sed -i 's|^#\\s*{line}$|{line}|' /etc/locale.gen && \
+             (grep -qx '{line}' /etc/locale.gen || echo '{line}' >> /etc/locale.gen)",
+            line = line
+        );
+
+        let commands = CommandSequence::new()
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &enable_script])
+                    .description(&format!("Enabling {} in locale.gen...", lang))
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("locale-gen")
+                    .description("Generating locales...")
+                    .build(),
+            )
+            .then(
+                Command::builder()
+                    .privileged()
+                    .program("localectl")
+                    .args(&["set-locale", &format!("LANG={}", lang)])
+                    .description(&format!("Setting system language to {}...", lang))
+                    .build(),
+            )
+            .build();
+
+        task_runner::run(window.upcast_ref(), commands, "Set System Locale");
+    });
+}
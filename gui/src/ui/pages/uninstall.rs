@@ -0,0 +1,173 @@
+//! Uninstall manager page handlers.
+//!
+//! Lists what the toolkit's actions installed and enabled, one group per
+//! action, read from the task history and the file manifest by
+//! `core::uninstall`. Each group can
+//! be removed in one go, services first, then packages, apps and files.
+
+use crate::core::status::days_ago;
+use crate::core::uninstall::{self, Footprint, Item, Kind};
+use crate::i18n::{gettext, gettext_f};
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::install_state;
+use crate::ui::task_runner;
+use crate::ui::utils::{spawn_blocking, try_extract_widget};
+use adw::prelude::*;
+use gtk4::{glib, ApplicationWindow, Builder, Button};
+use log::info;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+struct UninstallPage {
+    window: ApplicationWindow,
+    footprints_box: gtk4::Box,
+    empty: adw::StatusPage,
+    /// Groups of the actions, replaced on every render.
+    groups: RefCell<Vec<adw::PreferencesGroup>>,
+}
+
+/// Set up all handlers for the uninstall manager page.
+pub fn setup_handlers(page_builder: &Builder, _main_builder: &Builder, window: &ApplicationWindow) {
+    let (Some(footprints_box), Some(empty)) = (
+        try_extract_widget::<gtk4::Box>(page_builder, "footprints_box"),
+        try_extract_widget::<adw::StatusPage>(page_builder, "uninstall_empty"),
+    ) else {
+        return;
+    };
+
+    let page = Rc::new(UninstallPage {
+        window: window.clone(),
+        footprints_box,
+        empty,
+        groups: RefCell::new(Vec::new()),
+    });
+
+    // Installs and removals both change what's listed
+    install_state::connect_refresh(move || load(&page));
+}
+
+/// Read the footprints off the main thread, then show them.
+fn load(page: &Rc<UninstallPage>) {
+    let page = page.clone();
+    spawn_blocking(uninstall::installed, move |footprints| {
+        render(&page, &footprints)
+    });
+}
+
+fn render(page: &Rc<UninstallPage>, footprints: &[Footprint]) {
+    for group in page.groups.borrow_mut().drain(..) {
+        page.footprints_box.remove(&group);
+    }
+
+    page.empty.set_visible(footprints.is_empty());
+    let now = crate::core::history::now();
+    for footprint in footprints {
+        let group = footprint_group(page, footprint, now);
+        page.footprints_box.append(&group);
+        page.groups.borrow_mut().push(group);
+    }
+}
+
+fn icon(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Unit | Kind::UserUnit => "system-run-symbolic",
+        Kind::Package => "package-x-generic-symbolic",
+        Kind::Flatpak => "application-x-executable-symbolic",
+        Kind::File => "folder-symbolic",
+    }
+}
+
+fn footprint_group(
+    page: &Rc<UninstallPage>,
+    footprint: &Footprint,
+    now: i64,
+) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::builder()
+        .title(glib::markup_escape_text(&gettext(&footprint.title)).as_str())
+        .description(gettext_f(
+            "Last run: {when}",
+            &[("when", &days_ago(footprint.last_run, now))],
+        ))
+        .build();
+
+    let remove_button = Button::builder()
+        .label(gettext("Remove All"))
+        .valign(gtk4::Align::Center)
+        .css_classes(vec!["destructive-action".to_string()])
+        .sensitive(footprint.removable().next().is_some())
+        .build();
+    let page_clone = page.clone();
+    let footprint_clone = footprint.clone();
+    remove_button.connect_clicked(move |_| {
+        info!("Remove all clicked for '{}'", footprint_clone.title);
+        confirm_removal(&page_clone, &footprint_clone);
+    });
+    group.set_header_suffix(Some(&remove_button));
+
+    for item in &footprint.items {
+        group.add(&item_row(item));
+    }
+    group
+}
+
+fn item_row(item: &Item) -> adw::ActionRow {
+    let mut subtitle = gettext(item.kind.label());
+    if item.modified {
+        subtitle.push_str(" · ");
+        subtitle.push_str(&gettext("changed since it was installed"));
+    }
+    if !item.needed_by.is_empty() {
+        subtitle.push_str(" · ");
+        subtitle.push_str(&gettext_f(
+            "kept, needed by {actions}",
+            &[("actions", &item.needed_by.join(", "))],
+        ));
+    }
+    let row = adw::ActionRow::builder()
+        .title(&item.name)
+        .subtitle(subtitle)
+        .use_markup(false)
+        .build();
+    row.add_prefix(&gtk4::Image::from_icon_name(icon(item.kind)));
+    if !item.needed_by.is_empty() {
+        row.add_css_class("dim-label");
+    }
+    row
+}
+
+fn confirm_removal(page: &Rc<UninstallPage>, footprint: &Footprint) {
+    let items: Vec<Item> = footprint.removable().cloned().collect();
+    let list = items
+        .iter()
+        .map(|i| format!("• {}", glib::markup_escape_text(&i.name)))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let message = gettext_f(
+        "This will remove what <span foreground=\"cyan\" weight=\"bold\">{title}</span> \
+         installed:\n\n{list}\n\n\
+         Packages are removed with their unneeded dependencies and \
+         configuration files.",
+        &[
+            (
+                "title",
+                &glib::markup_escape_text(&gettext(&footprint.title)),
+            ),
+            ("list", &list),
+        ],
+    );
+
+    let window = page.window.clone();
+    let title = gettext_f(
+        "Uninstall {title}",
+        &[("title", &gettext(&footprint.title))],
+    );
+    show_warning_confirmation(
+        page.window.upcast_ref(),
+        &gettext("Remove All"),
+        &message,
+        move || {
+            let items: Vec<&Item> = items.iter().collect();
+            task_runner::run(window.upcast_ref(), uninstall::removal(&items), &title);
+        },
+    );
+}
@@ -0,0 +1,41 @@
+//! Hardware-aware hiding of actions.
+//!
+//! Once a page is built, the rules in `core::relevance` are checked off the
+//! main thread and the widgets they name are hidden or get a note in their
+//! tooltip. A hidden button in a list row takes the row with it.
+
+use crate::core::relevance::{self, Fit, Machine};
+use crate::ui::utils::spawn_blocking;
+use adw::prelude::*;
+use gtk4::Builder;
+use log::info;
+
+/// Hide or annotate the widgets on `page_id` that don't fit this machine.
+pub fn apply_to_page(page_id: &str, page_builder: &Builder) {
+    if !relevance::RULES.iter().any(|rule| rule.page == page_id) {
+        return;
+    }
+
+    let page_id = page_id.to_string();
+    let builder = page_builder.clone();
+    spawn_blocking(
+        move || relevance::for_page(&page_id, Machine::get()),
+        move |fits| {
+            for (id, fit) in fits {
+                let Some(widget) = builder.object::<gtk4::Widget>(id) else {
+                    continue;
+                };
+                match fit {
+                    Fit::Hidden(reason) => {
+                        info!("Hiding {}: {}", id, reason);
+                        widget
+                            .ancestor(adw::ActionRow::static_type())
+                            .unwrap_or(widget)
+                            .set_visible(false);
+                    }
+                    Fit::Note(note) => widget.set_tooltip_text(Some(&note)),
+                }
+            }
+        },
+    );
+}
@@ -0,0 +1,204 @@
+//! Header bar search across pages and registered actions.
+//!
+//! Results show in a popover under the entry. Choosing one switches to its
+//! page and, for actions, focuses and briefly highlights the action's button.
+
+use crate::actions::{self, Action};
+use crate::i18n::gettext;
+use crate::ui::context::AppContext;
+use crate::ui::navigation::{PageConfig, PAGES};
+use crate::ui::utils::extract_widget;
+use adw::prelude::*;
+use gtk4::{glib, Builder, ListBox, Popover, SearchEntry};
+use log::{info, warn};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+const MAX_RESULTS: usize = 8;
+const HIGHLIGHT_CLASS: &str = "search-highlight";
+const HIGHLIGHT_DURATION: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Copy)]
+enum SearchResult {
+    Page(&'static PageConfig),
+    Action(&'static Action),
+}
+
+impl SearchResult {
+    fn page_id(&self) -> &'static str {
+        match self {
+            SearchResult::Page(page) => page.id,
+            SearchResult::Action(action) => action.page,
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            SearchResult::Page(page) => gettext(page.title),
+            SearchResult::Action(action) => gettext(action.title),
+        }
+    }
+
+    fn subtitle(&self) -> String {
+        match self {
+            SearchResult::Page(_) => gettext("Page"),
+            SearchResult::Action(action) => {
+                let page = PAGES
+                    .iter()
+                    .find(|p| p.id == action.page)
+                    .map_or(action.page, |p| p.title);
+                format!("{} · {}", gettext(page), gettext(action.description))
+            }
+        }
+    }
+}
+
+fn find_results(query: &str) -> Vec<SearchResult> {
+    let mut results: Vec<(u32, SearchResult)> = PAGES
+        .iter()
+        .filter_map(|page| {
+            actions::search::score(query, &gettext(page.title), &[page.title, page.id])
+                .map(|score| (score, SearchResult::Page(page)))
+        })
+        .collect();
+    results.extend(
+        actions::search::search(query)
+            .into_iter()
+            .map(|(score, action)| (score, SearchResult::Action(action))),
+    );
+    // Stable, so pages stay ahead of actions with the same score
+    results.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    results
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(_, result)| result)
+        .collect()
+}
+
+/// Set up the header bar search entry.
+pub fn setup_search(builder: &Builder, ctx: &AppContext) {
+    let entry = extract_widget::<SearchEntry>(builder, "action_search_entry");
+
+    let list = ListBox::builder()
+        .selection_mode(gtk4::SelectionMode::None)
+        .css_classes(vec!["boxed-list".to_string()])
+        .build();
+    let popover = Popover::builder()
+        .autohide(false)
+        .has_arrow(false)
+        .position(gtk4::PositionType::Bottom)
+        .child(&list)
+        .build();
+    popover.set_parent(&entry);
+
+    let results: Rc<RefCell<Vec<SearchResult>>> = Rc::new(RefCell::new(Vec::new()));
+
+    let list_clone = list.clone();
+    let popover_clone = popover.clone();
+    let results_clone = results.clone();
+    entry.connect_search_changed(move |entry| {
+        let found = find_results(&entry.text());
+
+        list_clone.remove_all();
+        for result in &found {
+            let row = adw::ActionRow::builder()
+                .title(result.title())
+                .subtitle(result.subtitle())
+                .use_markup(false)
+                .activatable(true)
+                .build();
+            list_clone.append(&row);
+        }
+
+        if found.is_empty() {
+            popover_clone.popdown();
+        } else {
+            popover_clone.popup();
+        }
+        *results_clone.borrow_mut() = found;
+    });
+
+    let ctx_clone = ctx.clone();
+    let entry_clone = entry.clone();
+    let results_clone = results.clone();
+    list.connect_row_activated(move |_, row| {
+        let result = results_clone.borrow().get(row.index() as usize).copied();
+        if let Some(result) = result {
+            entry_clone.set_text("");
+            open_result(&ctx_clone, result);
+        }
+    });
+
+    // Enter picks the best match
+    let ctx_clone = ctx.clone();
+    let results_clone = results.clone();
+    entry.connect_activate(move |entry| {
+        let result = results_clone.borrow().first().copied();
+        if let Some(result) = result {
+            entry.set_text("");
+            open_result(&ctx_clone, result);
+        }
+    });
+
+    let popover_clone = popover.clone();
+    entry.connect_stop_search(move |entry| {
+        entry.set_text("");
+        popover_clone.popdown();
+    });
+}
+
+fn open_result(ctx: &AppContext, result: SearchResult) {
+    info!("Search: opening '{}'", result.title());
+    if !ctx.navigate_to_page(result.page_id()) {
+        warn!(
+            "Search result points to unknown page '{}'",
+            result.page_id()
+        );
+        return;
+    }
+
+    let SearchResult::Action(action) = result else {
+        return;
+    };
+    let target = ctx
+        .ui
+        .stack
+        .child_by_name(action.page)
+        .and_then(|page| find_by_id(&page, action.widget));
+    match target {
+        Some(widget) => highlight(&widget),
+        None => warn!(
+            "Widget '{}' not found on page '{}'",
+            action.widget, action.page
+        ),
+    }
+}
+
+/// Find a descendant of `root` by its builder id.
+fn find_by_id(root: &gtk4::Widget, id: &str) -> Option<gtk4::Widget> {
+    if root.buildable_id().as_deref() == Some(id) {
+        return Some(root.clone());
+    }
+
+    let mut child = root.first_child();
+    while let Some(widget) = child {
+        if let Some(found) = find_by_id(&widget, id) {
+            return Some(found);
+        }
+        child = widget.next_sibling();
+    }
+    None
+}
+
+fn highlight(widget: &gtk4::Widget) {
+    widget.grab_focus();
+    widget.add_css_class(HIGHLIGHT_CLASS);
+
+    let widget = widget.downgrade();
+    glib::timeout_add_local_once(HIGHLIGHT_DURATION, move || {
+        if let Some(widget) = widget.upgrade() {
+            widget.remove_css_class(HIGHLIGHT_CLASS);
+        }
+    });
+}
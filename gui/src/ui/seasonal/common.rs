@@ -1,14 +1,18 @@
 //! Common utilities for seasonal effects.
 
+use crate::ui::seasonal::pace::Pace;
 use adw::prelude::*;
-use gtk4::{ApplicationWindow, DrawingArea, EventControllerMotion, Widget};
+use gtk4::{cairo, glib, ApplicationWindow, DrawingArea, EventControllerMotion, Widget};
 use std::cell::RefCell;
 use std::rc::Rc;
 
+/// Mouse coordinates shared between the motion controller and effects.
+type Position = Rc<RefCell<(f64, f64)>>;
+
 /// Mouse position context for seasonal effects.
 /// Provides mouse coordinates that effects can use.
 pub struct MouseContext {
-    position: Rc<RefCell<(f64, f64)>>,
+    position: Position,
 }
 
 impl MouseContext {
@@ -18,22 +22,91 @@ impl MouseContext {
     }
 }
 
-/// Set up mouse tracking for the window and return a MouseContext.
-pub fn setup_mouse_tracking(window: &ApplicationWindow) -> MouseContext {
-    let mouse_pos = Rc::new(RefCell::new((0.0f64, 0.0f64)));
+thread_local! {
+    /// Mouse position shared by all effects, tracked from the first apply.
+    static MOUSE_POSITION: RefCell<Option<Position>> = const { RefCell::new(None) };
+}
 
-    let motion = EventControllerMotion::new();
-    let mouse_pos_clone = mouse_pos.clone();
-    motion.connect_motion(move |_, x, y| {
-        *mouse_pos_clone.borrow_mut() = (x, y);
+/// Track the mouse over the window and return a MouseContext. The tracking
+/// is set up once and shared when effects are applied again.
+pub fn mouse_tracking(window: &ApplicationWindow) -> MouseContext {
+    let position = MOUSE_POSITION.with(|position| {
+        position
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                let mouse_pos = Rc::new(RefCell::new((0.0f64, 0.0f64)));
+
+                let motion = EventControllerMotion::new();
+                let mouse_pos_clone = mouse_pos.clone();
+                motion.connect_motion(move |_, x, y| {
+                    *mouse_pos_clone.borrow_mut() = (x, y);
+                });
+                window.add_controller(motion);
+
+                mouse_pos
+            })
+            .clone()
     });
-    window.add_controller(motion);
 
-    MouseContext {
-        position: mouse_pos,
-    }
+    MouseContext { position }
+}
+
+/// Create a full-window drawing area for an effect overlay. It doesn't take
+/// focus or input.
+pub fn new_overlay_area() -> Rc<DrawingArea> {
+    let drawing_area = Rc::new(DrawingArea::new());
+    drawing_area.set_hexpand(true);
+    drawing_area.set_vexpand(true);
+    drawing_area.set_can_focus(false);
+    drawing_area.set_sensitive(false);
+    drawing_area.set_halign(gtk4::Align::Fill);
+    drawing_area.set_valign(gtk4::Align::Fill);
+    drawing_area
 }
 
+/// Clear the previous frame.
+pub fn clear_frame(cr: &cairo::Context) {
+    let _ = cr.save();
+    cr.set_operator(cairo::Operator::Clear);
+    let _ = cr.paint();
+    cr.set_operator(cairo::Operator::Over);
+    let _ = cr.restore();
+}
+
+/// Seed for an effect's random numbers, from the current time.
+pub fn time_seed() -> u64 {
+    glib::DateTime::now_utc()
+        .map(|dt| dt.to_unix())
+        .unwrap_or(0) as u64
+}
+
+/// Redraw `drawing_area` until it's removed from the window, at the pace
+/// the window's focus and the power state allow.
+pub fn start_frame_timer(drawing_area: &DrawingArea) {
+    schedule_frame(drawing_area.downgrade(), Pace::Full);
+}
+
+fn schedule_frame(weak: glib::WeakRef<DrawingArea>, pace: Pace) {
+    glib::timeout_add_local_once(pace.interval(), move || {
+        let Some(area) = weak.upgrade() else {
+            return;
+        };
+        let Some(window) = area.root().and_downcast::<gtk4::Window>() else {
+            return;
+        };
+
+        let pace = Pace::current(&window);
+        if pace != Pace::Paused {
+            area.queue_draw();
+        }
+        schedule_frame(weak, pace);
+    });
+}
+
+/// Longest time step an effect advances in one frame, so particles don't
+/// jump after a pause. Long enough to keep full speed at the reduced pace.
+pub const MAX_FRAME_STEP: f64 = 0.25;
+
 /// Trait for effect states that need to handle window resizing.
 pub trait ResizableEffectState {
     /// Handle window resize by adjusting particle positions to fit new dimensions.
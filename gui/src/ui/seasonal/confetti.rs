@@ -0,0 +1,81 @@
+//! XeroLinux anniversary confetti effect overlay.
+//!
+//! Colorful confetti fluttering down on the anniversary of XeroLinux.
+
+use crate::config::seasonal_debug;
+use crate::i18n::n_;
+use crate::ui::seasonal::common::MouseContext;
+use crate::ui::seasonal::particles::{self, Emitter, Particle, ParticleSystem};
+use crate::ui::seasonal::SeasonalEffect;
+use gtk4::cairo;
+use gtk4::glib;
+use gtk4::{ApplicationWindow, DrawingArea};
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::rc::Rc;
+
+/// Month and day of the XeroLinux anniversary.
+const ANNIVERSARY: (i32, i32) = (6, 1);
+
+const CONFETTI: ParticleSystem = ParticleSystem {
+    count: 70,
+    emitter: Emitter::Falling,
+    gravity: 40.0,
+    drag: 0.5,
+    wind: 25.0,
+    palette: &[
+        (0.55, 0.35, 0.95),
+        (0.95, 0.3, 0.6),
+        (0.3, 0.8, 0.95),
+        (1.0, 0.8, 0.25),
+        (0.4, 0.9, 0.5),
+    ],
+    spawn: spawn_piece,
+    sprite: draw_piece,
+};
+
+/// XeroLinux anniversary confetti effect.
+pub struct ConfettiEffect;
+
+impl SeasonalEffect for ConfettiEffect {
+    fn id(&self) -> &'static str {
+        "confetti"
+    }
+
+    fn debug_env(&self) -> &'static str {
+        seasonal_debug::ENABLE_CONFETTI
+    }
+
+    fn in_season(&self) -> bool {
+        glib::DateTime::now_local().is_ok_and(|dt| (dt.month(), dt.day_of_month()) == ANNIVERSARY)
+    }
+
+    fn name(&self) -> &'static str {
+        n_("Confetti (XeroLinux anniversary)")
+    }
+
+    fn apply(
+        &self,
+        window: &ApplicationWindow,
+        _mouse_context: Option<&MouseContext>,
+    ) -> Option<Rc<DrawingArea>> {
+        particles::apply(window, &CONFETTI)
+    }
+}
+
+fn spawn_piece(p: &mut Particle, rng: &mut StdRng) {
+    p.vy = rng.random_range(40.0..80.0);
+    p.size = rng.random_range(3.0..6.0);
+    p.spin = rng.random_range(-4.0..4.0);
+    p.sway = rng.random_range(5.0..20.0);
+}
+
+fn draw_piece(cr: &cairo::Context, p: &Particle) {
+    let (r, g, b) = p.color;
+    // Flipping over: the piece narrows and darkens as it turns edge on
+    let flip = (p.phase * 2.0).cos();
+    let shade = 0.7 + 0.3 * flip.abs();
+    cr.set_source_rgba(r * shade, g * shade, b * shade, 0.9);
+    cr.rectangle(-p.size, -p.size * 0.5 * flip, p.size * 2.0, p.size * flip);
+    let _ = cr.fill();
+}
@@ -0,0 +1,87 @@
+//! New Year fireworks effect overlay.
+//!
+//! Bursts of glowing sparks that fall and fade out, on New Year's Day.
+
+use crate::config::seasonal_debug;
+use crate::i18n::n_;
+use crate::ui::seasonal::common::MouseContext;
+use crate::ui::seasonal::particles::{self, Emitter, Particle, ParticleSystem};
+use crate::ui::seasonal::SeasonalEffect;
+use gtk4::cairo;
+use gtk4::glib;
+use gtk4::{ApplicationWindow, DrawingArea};
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+const FIREWORKS: ParticleSystem = ParticleSystem {
+    count: 240,
+    emitter: Emitter::Bursts {
+        rate: 0.8,
+        size: 60,
+    },
+    gravity: 60.0,
+    drag: 0.8,
+    wind: 10.0,
+    palette: &[
+        (1.0, 0.35, 0.3),
+        (1.0, 0.8, 0.3),
+        (0.4, 0.9, 0.5),
+        (0.4, 0.7, 1.0),
+        (0.8, 0.5, 1.0),
+    ],
+    spawn: spawn_spark,
+    sprite: draw_spark,
+};
+
+/// New Year fireworks effect.
+pub struct FireworksEffect;
+
+impl SeasonalEffect for FireworksEffect {
+    fn id(&self) -> &'static str {
+        "fireworks"
+    }
+
+    fn debug_env(&self) -> &'static str {
+        seasonal_debug::ENABLE_FIREWORKS
+    }
+
+    fn in_season(&self) -> bool {
+        glib::DateTime::now_local().is_ok_and(|dt| dt.month() == 1 && dt.day_of_month() == 1)
+    }
+
+    fn name(&self) -> &'static str {
+        n_("Fireworks (New Year)")
+    }
+
+    fn apply(
+        &self,
+        window: &ApplicationWindow,
+        _mouse_context: Option<&MouseContext>,
+    ) -> Option<Rc<DrawingArea>> {
+        particles::apply(window, &FIREWORKS)
+    }
+}
+
+fn spawn_spark(p: &mut Particle, rng: &mut StdRng) {
+    let angle = rng.random_range(0.0..2.0 * PI);
+    let speed = rng.random_range(60.0..200.0);
+    p.vx = angle.cos() * speed;
+    p.vy = angle.sin() * speed;
+    p.size = rng.random_range(1.5..3.0);
+    p.lifetime = rng.random_range(1.2..2.2);
+}
+
+fn draw_spark(cr: &cairo::Context, p: &Particle) {
+    let (r, g, b) = p.color;
+    let radius = p.size * 2.5;
+    let glow = cairo::RadialGradient::new(0.0, 0.0, 0.0, 0.0, 0.0, radius);
+    glow.add_color_stop_rgba(0.0, 1.0, 1.0, 1.0, p.opacity());
+    glow.add_color_stop_rgba(0.3, r, g, b, p.opacity() * 0.8);
+    glow.add_color_stop_rgba(1.0, r, g, b, 0.0);
+
+    let _ = cr.set_source(&glow);
+    cr.arc(0.0, 0.0, radius, 0.0, 2.0 * PI);
+    let _ = cr.fill();
+}
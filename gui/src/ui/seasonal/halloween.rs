@@ -7,10 +7,12 @@
 //! - Mouse avoidance (bats scatter when the cursor approaches).
 
 use crate::config::seasonal_debug;
+use crate::i18n::n_;
 use crate::ui::seasonal::common::{
-    add_overlay_to_window, setup_resize_handler, MouseContext, ResizableEffectState,
+    add_overlay_to_window, clear_frame, new_overlay_area, setup_resize_handler, start_frame_timer,
+    time_seed, MouseContext, ResizableEffectState, MAX_FRAME_STEP,
 };
-use crate::ui::seasonal::SeasonalEffect;
+use crate::ui::seasonal::{self, SeasonalEffect};
 use gtk4::cairo;
 use gtk4::glib;
 use gtk4::prelude::*;
@@ -30,22 +32,20 @@ const MOUSE_AVOID_FORCE: f64 = 800.0;
 pub struct HalloweenEffect;
 
 impl SeasonalEffect for HalloweenEffect {
-    fn is_active(&self) -> bool {
-        // Check environment variable for debugging (overrides date check)
-        if let Some(enabled) = seasonal_debug::check_effect_env(seasonal_debug::ENABLE_HALLOWEEN) {
-            return enabled;
-        }
+    fn id(&self) -> &'static str {
+        "halloween"
+    }
 
-        // Default: check if it's October
-        if let Ok(dt) = glib::DateTime::now_utc() {
-            dt.month() == 10 // October
-        } else {
-            false
-        }
+    fn debug_env(&self) -> &'static str {
+        seasonal_debug::ENABLE_HALLOWEEN
+    }
+
+    fn in_season(&self) -> bool {
+        glib::DateTime::now_utc().is_ok_and(|dt| dt.month() == 10)
     }
 
     fn name(&self) -> &'static str {
-        "Bats (Halloween)"
+        n_("Bats (Halloween)")
     }
 
     fn apply(
@@ -55,14 +55,7 @@ impl SeasonalEffect for HalloweenEffect {
     ) -> Option<Rc<DrawingArea>> {
         use log::info;
 
-        let drawing_area = Rc::new(DrawingArea::new());
-        drawing_area.set_hexpand(true);
-        drawing_area.set_vexpand(true);
-        drawing_area.set_can_focus(false);
-        drawing_area.set_sensitive(false);
-        drawing_area.set_halign(gtk4::Align::Fill);
-        drawing_area.set_valign(gtk4::Align::Fill);
-        drawing_area.set_visible(crate::ui::seasonal::are_effects_enabled());
+        let drawing_area = new_overlay_area();
 
         let mouse_pos = if let Some(ctx) = mouse_context {
             ctx.position_internal()
@@ -74,11 +67,7 @@ impl SeasonalEffect for HalloweenEffect {
         let setup_state = Rc::clone(&state);
         let draw_mouse_pos = mouse_pos.clone();
 
-        let drawing_area_clone = drawing_area.clone();
-        glib::timeout_add_local(std::time::Duration::from_millis(16), move || {
-            drawing_area_clone.queue_draw();
-            glib::ControlFlow::Continue
-        });
+        start_frame_timer(&drawing_area);
 
         drawing_area.set_draw_func(move |_da, cr, width, height| {
             let mut state_ref = setup_state.borrow_mut();
@@ -93,11 +82,7 @@ impl SeasonalEffect for HalloweenEffect {
 
                 bat_state.update(width as f64, height as f64, now, mx, my);
 
-                let _ = cr.save();
-                cr.set_operator(cairo::Operator::Clear);
-                let _ = cr.paint();
-                cr.set_operator(cairo::Operator::Over);
-                let _ = cr.restore();
+                clear_frame(cr);
 
                 bat_state.draw_bats(cr);
                 bat_state.draw_fog(cr, width as f64, height as f64);
@@ -294,11 +279,9 @@ struct BatState {
 
 impl BatState {
     fn new(width: f64, height: f64) -> Self {
-        let seed = glib::DateTime::now_utc()
-            .map(|dt| dt.to_unix())
-            .unwrap_or(0) as u64;
+        let seed = time_seed();
 
-        let bats = (0..BAT_COUNT)
+        let bats = (0..seasonal::scaled_count(BAT_COUNT))
             .map(|i| Bat::new(width, height, seed.wrapping_add(i as u64 * 100)))
             .collect();
 
@@ -318,8 +301,8 @@ impl BatState {
 
         let dt_duration = now.duration_since(self.last_frame_time);
         let mut dt = dt_duration.as_secs_f64();
-        if dt > 0.1 {
-            dt = 0.1;
+        if dt > MAX_FRAME_STEP {
+            dt = MAX_FRAME_STEP;
         }
         self.last_frame_time = now;
 
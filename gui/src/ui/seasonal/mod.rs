@@ -2,79 +2,132 @@
 //!
 //! This module provides animated overlay effects that appear during specific
 //! times of the year (e.g., snow for December, Halloween effects for October).
+//! Effects without special behavior are described as a particle system in
+//! `particles`, which handles spawning, physics and drawing for them.
+//! Each effect can be turned off or forced on outside its season, and the
+//! particle density and frame rate are configurable, all from the
+//! Preferences dialog. Animations slow down while the window is unfocused
+//! or the machine is saving power, and pause while it's hidden. The
+//! `seasonal_debug` environment variables still override everything for
+//! testing.
 
 mod common;
+mod confetti;
+mod fireworks;
 mod halloween;
+mod pace;
+mod particles;
+mod petals;
 mod snow;
 
+use crate::config::seasonal_debug;
+use crate::config::user::{self, EffectMode, SeasonalSettings};
 use crate::ui::seasonal::common::MouseContext;
 use gtk4::prelude::*;
 use gtk4::{ApplicationWindow, DrawingArea};
-use log::info;
+use log::{info, warn};
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
+pub use confetti::ConfettiEffect;
+pub use fireworks::FireworksEffect;
 pub use halloween::HalloweenEffect;
+pub use petals::PetalsEffect;
 pub use snow::SnowEffect;
 
-/// Global state for whether seasonal effects are enabled.
-static EFFECTS_ENABLED: AtomicBool = AtomicBool::new(true);
-
-/// Global registry of active drawing areas for seasonal effects.
-/// SAFETY: GTK operations must be on the main thread, so this RefCell is safe to use.
-/// We use unsafe to implement Send+Sync, which is safe because GTK is single-threaded.
-struct DrawingAreaVec(RefCell<Vec<Rc<DrawingArea>>>);
-
-// SAFETY: Safe because GTK operations are single-threaded (main thread only).
-unsafe impl Send for DrawingAreaVec {}
-unsafe impl Sync for DrawingAreaVec {}
-
-static DRAWING_AREAS: std::sync::OnceLock<DrawingAreaVec> = std::sync::OnceLock::new();
+thread_local! {
+    /// Seasonal settings, loaded on first use.
+    static SETTINGS: RefCell<Option<SeasonalSettings>> = const { RefCell::new(None) };
+    /// Drawing areas of the effects currently shown.
+    static DRAWING_AREAS: RefCell<Vec<Rc<DrawingArea>>> = const { RefCell::new(Vec::new()) };
+}
 
-fn get_drawing_areas() -> &'static RefCell<Vec<Rc<DrawingArea>>> {
-    &DRAWING_AREAS
-        .get_or_init(|| DrawingAreaVec(RefCell::new(Vec::new())))
-        .0
+/// All effects, in the order they're listed in Preferences.
+pub fn effects() -> Vec<Box<dyn SeasonalEffect>> {
+    vec![
+        Box::new(SnowEffect),
+        Box::new(HalloweenEffect),
+        Box::new(FireworksEffect),
+        Box::new(PetalsEffect),
+        Box::new(ConfettiEffect),
+    ]
 }
 
-/// Check if seasonal effects are currently enabled.
-pub fn are_effects_enabled() -> bool {
-    EFFECTS_ENABLED.load(Ordering::Relaxed)
+/// Current seasonal settings.
+pub fn settings() -> SeasonalSettings {
+    SETTINGS.with(|settings| {
+        settings
+            .borrow_mut()
+            .get_or_insert_with(|| user::load().seasonal)
+            .clone()
+    })
 }
 
-/// Set whether seasonal effects are enabled and update visibility of drawing areas.
-pub fn set_effects_enabled(enabled: bool) {
-    EFFECTS_ENABLED.store(enabled, Ordering::Relaxed);
+/// Apply `change` to the seasonal settings, save them and show the effects
+/// that are now active.
+pub fn update_settings(window: &ApplicationWindow, change: impl FnOnce(&mut SeasonalSettings)) {
+    let mut seasonal = settings();
+    change(&mut seasonal);
+    SETTINGS.with(|settings| *settings.borrow_mut() = Some(seasonal.clone()));
 
-    let drawing_areas = get_drawing_areas();
-    for area in drawing_areas.borrow().iter() {
-        area.set_visible(enabled);
+    if let Err(e) = user::update(|s| s.seasonal = seasonal) {
+        warn!("Failed to save seasonal settings: {}", e);
     }
+    apply_seasonal_effects(window);
 }
 
-/// Check if any seasonal effect is currently active.
-pub fn has_active_effect() -> bool {
-    let effects: Vec<Box<dyn SeasonalEffect>> =
-        vec![Box::new(SnowEffect), Box::new(HalloweenEffect)];
+/// `count` scaled by the density setting, at least one.
+pub fn scaled_count(count: usize) -> usize {
+    (count * settings().density as usize / 100).max(1)
+}
 
-    effects.iter().any(|e| e.is_active())
+/// Time between animation frames, from the frame rate cap.
+pub fn frame_interval() -> Duration {
+    Duration::from_millis(1000 / u64::from(settings().max_fps.max(1)))
 }
 
-/// Register a drawing area so its visibility can be controlled by the toggle.
-pub fn register_drawing_area(area: Rc<DrawingArea>) {
-    let drawing_areas = get_drawing_areas();
-    drawing_areas.borrow_mut().push(area);
+/// Register a drawing area so it's removed when the effects are re-applied.
+fn register_drawing_area(area: Rc<DrawingArea>) {
+    DRAWING_AREAS.with(|areas| areas.borrow_mut().push(area));
+}
+
+/// Remove all effect overlays from the window.
+fn clear_drawing_areas() {
+    for area in DRAWING_AREAS.with(|areas| areas.take()) {
+        if let Some(overlay) = area.parent().and_downcast::<gtk4::Overlay>() {
+            overlay.remove_overlay(&*area);
+        }
+    }
 }
 
 /// Trait for seasonal effects that can be applied to application windows.
 pub trait SeasonalEffect {
-    /// Check if this effect should be active at the current time.
-    fn is_active(&self) -> bool;
+    /// Key of this effect in the settings.
+    fn id(&self) -> &'static str;
 
-    /// Get the name of this seasonal effect (for logging).
+    /// Get the name of this seasonal effect (for logging and Preferences).
     fn name(&self) -> &'static str;
 
+    /// Environment variable overriding whether this effect is shown.
+    fn debug_env(&self) -> &'static str;
+
+    /// Check if the current date falls in this effect's season.
+    fn in_season(&self) -> bool;
+
+    /// Check if this effect should be shown, from the debug environment
+    /// variable, the settings and the date, in that order.
+    fn is_active(&self) -> bool {
+        if let Some(enabled) = seasonal_debug::check_effect_env(self.debug_env()) {
+            return enabled;
+        }
+        match settings().mode(self.id()) {
+            EffectMode::Automatic => self.in_season(),
+            EffectMode::Always => true,
+            EffectMode::Off => false,
+        }
+    }
+
     /// Apply this effect to the given window.
     /// The mouse_context provides mouse position if the effect needs it.
     /// Returns the drawing area if the effect was successfully applied.
@@ -85,21 +138,21 @@ pub trait SeasonalEffect {
     ) -> Option<Rc<DrawingArea>>;
 }
 
-/// Apply any active seasonal effects to the window.
+/// Apply any active seasonal effects to the window, replacing the ones
+/// already shown.
 pub fn apply_seasonal_effects(window: &ApplicationWindow) {
-    if !are_effects_enabled() {
+    clear_drawing_areas();
+
+    if !settings().enabled {
         info!("Seasonal effects are disabled");
         return;
     }
 
     info!("Checking for active seasonal effects...");
 
-    let mouse_context = common::setup_mouse_tracking(window);
-
-    let effects: Vec<Box<dyn SeasonalEffect>> =
-        vec![Box::new(SnowEffect), Box::new(HalloweenEffect)];
+    let mouse_context = common::mouse_tracking(window);
 
-    for effect in effects {
+    for effect in effects() {
         if effect.is_active() {
             info!("Active seasonal effect detected: {}", effect.name());
             if let Some(drawing_area) = effect.apply(window, Some(&mouse_context)) {
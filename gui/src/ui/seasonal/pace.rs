@@ -0,0 +1,112 @@
+//! Frame pacing for seasonal effects.
+//!
+//! A full-window redraw at the frame rate cap costs real battery on laptops,
+//! so effects slow down to a few frames per second while the window is
+//! unfocused, the power-saver profile is active or the machine is on battery,
+//! and stop drawing while the window is hidden or minimized.
+
+use crate::core::power;
+use gtk4::gio;
+use gtk4::prelude::*;
+use log::debug;
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+/// Frame interval while throttled, about 5 FPS.
+const REDUCED_INTERVAL: Duration = Duration::from_millis(200);
+/// How often a paused effect checks whether it can draw again.
+const PAUSED_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long the battery state is trusted before sysfs is read again.
+const BATTERY_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+const PROFILES_BUS_NAME: &str = "net.hadess.PowerProfiles";
+const PROFILES_OBJECT_PATH: &str = "/net/hadess/PowerProfiles";
+const PROFILES_INTERFACE: &str = "net.hadess.PowerProfiles";
+
+thread_local! {
+    /// Last battery check and its result.
+    static BATTERY: Cell<Option<(Instant, bool)>> = const { Cell::new(None) };
+    /// power-profiles-daemon proxy, once connected.
+    static PROFILES: RefCell<Option<gio::DBusProxy>> = const { RefCell::new(None) };
+    /// Whether connecting to power-profiles-daemon was started.
+    static PROFILES_REQUESTED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// How fast an effect is redrawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pace {
+    /// At the configured frame rate.
+    Full,
+    /// At about 5 FPS.
+    Reduced,
+    /// Not drawn at all.
+    Paused,
+}
+
+impl Pace {
+    /// Pace for effects shown in `window` right now.
+    pub fn current(window: &gtk4::Window) -> Self {
+        if !window.is_visible() || window.is_suspended() {
+            Pace::Paused
+        } else if !window.is_active() || power_saving() {
+            Pace::Reduced
+        } else {
+            Pace::Full
+        }
+    }
+
+    /// Time until the next frame, or the next check while paused.
+    pub fn interval(self) -> Duration {
+        let full = crate::ui::seasonal::frame_interval();
+        match self {
+            Pace::Full => full,
+            Pace::Reduced => full.max(REDUCED_INTERVAL),
+            Pace::Paused => PAUSED_POLL_INTERVAL,
+        }
+    }
+}
+
+/// Whether the power-saver profile is active or the machine is on battery.
+fn power_saving() -> bool {
+    power_saver_profile() || on_battery()
+}
+
+fn on_battery() -> bool {
+    BATTERY.with(|battery| match battery.get() {
+        Some((checked, on_battery)) if checked.elapsed() < BATTERY_CHECK_INTERVAL => on_battery,
+        _ => {
+            let on_battery = power::on_battery();
+            battery.set(Some((Instant::now(), on_battery)));
+            on_battery
+        }
+    })
+}
+
+/// Whether power-profiles-daemon reports the power-saver profile. The proxy
+/// is connected in the background on first use and keeps the profile cached.
+fn power_saver_profile() -> bool {
+    if !PROFILES_REQUESTED.replace(true) {
+        gio::DBusProxy::for_bus(
+            gio::BusType::System,
+            gio::DBusProxyFlags::DO_NOT_AUTO_START,
+            None,
+            PROFILES_BUS_NAME,
+            PROFILES_OBJECT_PATH,
+            PROFILES_INTERFACE,
+            None::<&gio::Cancellable>,
+            |result| match result {
+                Ok(proxy) => PROFILES.with(|profiles| *profiles.borrow_mut() = Some(proxy)),
+                Err(e) => debug!("power-profiles-daemon not available: {}", e),
+            },
+        );
+    }
+
+    PROFILES.with(|profiles| {
+        profiles
+            .borrow()
+            .as_ref()
+            .and_then(|proxy| proxy.cached_property("ActiveProfile"))
+            .and_then(|value| value.get::<String>())
+            .is_some_and(|profile| profile == "power-saver")
+    })
+}
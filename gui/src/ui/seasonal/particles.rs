@@ -0,0 +1,370 @@
+//! Particle system shared by the simpler seasonal effects.
+//!
+//! An effect describes its particles with a [`ParticleSystem`]: how they're
+//! emitted, the gravity, drag and wind acting on them, and how each one is
+//! spawned and drawn. [`apply`] sets up the overlay, frame timer and resize
+//! handling, so a new effect only needs the description and a sprite.
+
+use crate::ui::seasonal;
+use crate::ui::seasonal::common::{
+    add_overlay_to_window, clear_frame, new_overlay_area, setup_resize_handler, start_frame_timer,
+    time_seed, ResizableEffectState, MAX_FRAME_STEP,
+};
+use gtk4::cairo;
+use gtk4::prelude::*;
+use gtk4::{ApplicationWindow, DrawingArea};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+/// How far outside the window particles may go before they're recycled.
+const EDGE_MARGIN: f64 = 20.0;
+
+/// Color as red, green and blue in 0.0..=1.0.
+pub type Rgb = (f64, f64, f64);
+
+/// How particles enter the window.
+#[derive(Clone, Copy, Debug)]
+pub enum Emitter {
+    /// Keep the particles on screen, starting anywhere and coming back in at
+    /// the top once they fall out.
+    Falling,
+    /// `rate` bursts per second of `size` particles each, from random points
+    /// in the upper half. Each burst has one color and its particles fade
+    /// out over their lifetime.
+    Bursts { rate: f64, size: usize },
+}
+
+/// Description of a particle effect.
+pub struct ParticleSystem {
+    /// Particles on screen, or the most alive at once for bursts, before the
+    /// density setting is applied.
+    pub count: usize,
+    pub emitter: Emitter,
+    /// Downward acceleration in px/s².
+    pub gravity: f64,
+    /// Share of the velocity lost per second. With gravity this sets how
+    /// fast particles fall.
+    pub drag: f64,
+    /// Strongest horizontal wind in px/s. The wind drifts randomly up to it.
+    pub wind: f64,
+    /// Colors picked at random for particles or bursts.
+    pub palette: &'static [Rgb],
+    /// Set the size, velocity, spin, sway and lifetime of a new particle.
+    pub spawn: fn(&mut Particle, &mut StdRng),
+    /// Draw a particle centred on the origin, already rotated.
+    pub sprite: fn(&cairo::Context, &Particle),
+}
+
+/// A single particle.
+#[derive(Clone, Debug, Default)]
+pub struct Particle {
+    pub x: f64,
+    pub y: f64,
+    pub vx: f64,
+    pub vy: f64,
+    pub size: f64,
+    pub rotation: f64,
+    /// Rotation speed in radians per second.
+    pub spin: f64,
+    /// Side to side sway in px/s.
+    pub sway: f64,
+    /// Sway phase, also free for sprites to animate with.
+    pub phase: f64,
+    pub color: Rgb,
+    /// Seconds to live from spawning. Ignored for falling particles.
+    pub lifetime: f64,
+    /// Seconds left to live.
+    pub life: f64,
+}
+
+impl Particle {
+    /// Opacity from the remaining life. Burst particles fade out over the
+    /// second half of their life; falling particles stay opaque.
+    pub fn opacity(&self) -> f64 {
+        if self.lifetime.is_infinite() {
+            1.0
+        } else {
+            (self.life / self.lifetime * 2.0).clamp(0.0, 1.0)
+        }
+    }
+}
+
+/// Add an overlay animating `system` to the window.
+pub fn apply(
+    window: &ApplicationWindow,
+    system: &'static ParticleSystem,
+) -> Option<Rc<DrawingArea>> {
+    let drawing_area = new_overlay_area();
+    let state = Rc::new(RefCell::new(None::<ParticleState>));
+    let draw_state = Rc::clone(&state);
+
+    start_frame_timer(&drawing_area);
+
+    drawing_area.set_draw_func(move |_da, cr, width, height| {
+        let mut state_ref = draw_state.borrow_mut();
+        let state = state_ref.get_or_insert_with(|| {
+            let capacity = seasonal::scaled_count(system.count);
+            ParticleState::new(system, capacity, width as f64, height as f64, time_seed())
+        });
+
+        state.update(width as f64, height as f64, Instant::now());
+        clear_frame(cr);
+        state.draw(cr);
+    });
+
+    setup_resize_handler(&drawing_area, state);
+
+    if add_overlay_to_window(window, &drawing_area) {
+        Some(drawing_area)
+    } else {
+        None
+    }
+}
+
+struct ParticleState {
+    system: &'static ParticleSystem,
+    capacity: usize,
+    particles: Vec<Particle>,
+    rng: StdRng,
+    wind: f64,
+    wind_target: f64,
+    /// Fraction of the next burst accumulated so far.
+    burst_progress: f64,
+    last_time: Instant,
+    current_width: f64,
+    current_height: f64,
+}
+
+impl ParticleState {
+    fn new(
+        system: &'static ParticleSystem,
+        capacity: usize,
+        width: f64,
+        height: f64,
+        seed: u64,
+    ) -> Self {
+        let mut state = Self {
+            system,
+            capacity,
+            particles: Vec::with_capacity(capacity),
+            rng: StdRng::seed_from_u64(seed),
+            wind: 0.0,
+            wind_target: 0.0,
+            burst_progress: 0.0,
+            last_time: Instant::now(),
+            current_width: width,
+            current_height: height,
+        };
+
+        if let Emitter::Falling = system.emitter {
+            for _ in 0..capacity {
+                let color = state.random_color();
+                let mut particle = state.spawn(color);
+                particle.x = state.rng.random_range(0.0..width.max(1.0));
+                particle.y = state.rng.random_range(0.0..height.max(1.0));
+                state.particles.push(particle);
+            }
+        }
+        state
+    }
+
+    fn random_color(&mut self) -> Rgb {
+        match self.system.palette.len() {
+            0 => (1.0, 1.0, 1.0),
+            n => self.system.palette[self.rng.random_range(0..n)],
+        }
+    }
+
+    fn spawn(&mut self, color: Rgb) -> Particle {
+        let mut particle = Particle {
+            color,
+            phase: self.rng.random_range(0.0..std::f64::consts::TAU),
+            ..Default::default()
+        };
+        (self.system.spawn)(&mut particle, &mut self.rng);
+        if let Emitter::Falling = self.system.emitter {
+            particle.lifetime = f64::INFINITY;
+        }
+        particle.life = particle.lifetime;
+        particle
+    }
+
+    fn update(&mut self, width: f64, height: f64, now: Instant) {
+        self.current_width = width;
+        self.current_height = height;
+
+        let dt = now
+            .duration_since(self.last_time)
+            .as_secs_f64()
+            .min(MAX_FRAME_STEP);
+        self.last_time = now;
+        self.step(dt);
+    }
+
+    /// Advance the simulation by `dt` seconds.
+    fn step(&mut self, dt: f64) {
+        let (width, height) = (self.current_width, self.current_height);
+
+        if self.rng.random::<f64>() > 0.98 {
+            self.wind_target = (self.rng.random::<f64>() * 2.0 - 1.0) * self.system.wind;
+        }
+        self.wind += (self.wind_target - self.wind) * dt;
+
+        let decay = (1.0 - self.system.drag * dt).max(0.0);
+        for p in &mut self.particles {
+            p.vy += self.system.gravity * dt;
+            p.vx *= decay;
+            p.vy *= decay;
+            p.phase += 2.0 * dt;
+            p.x += (p.vx + self.wind + p.phase.sin() * p.sway) * dt;
+            p.y += p.vy * dt;
+            p.rotation += p.spin * dt;
+            p.life -= dt;
+        }
+
+        match self.system.emitter {
+            Emitter::Falling => {
+                for i in 0..self.particles.len() {
+                    let p = &self.particles[i];
+                    if p.y > height + EDGE_MARGIN {
+                        let color = self.random_color();
+                        let mut particle = self.spawn(color);
+                        particle.x = self.rng.random_range(0.0..width.max(1.0));
+                        particle.y = self.rng.random_range(-EDGE_MARGIN..0.0);
+                        self.particles[i] = particle;
+                    } else if p.x < -EDGE_MARGIN {
+                        self.particles[i].x = width + EDGE_MARGIN;
+                    } else if p.x > width + EDGE_MARGIN {
+                        self.particles[i].x = -EDGE_MARGIN;
+                    }
+                }
+            }
+            Emitter::Bursts { rate, size } => {
+                self.particles
+                    .retain(|p| p.life > 0.0 && p.y < height + EDGE_MARGIN);
+
+                self.burst_progress += rate * dt;
+                while self.burst_progress >= 1.0 {
+                    self.burst_progress -= 1.0;
+                    self.burst(size.min(self.capacity), width, height);
+                }
+            }
+        }
+    }
+
+    fn burst(&mut self, size: usize, width: f64, height: f64) {
+        if self.particles.len() + size > self.capacity {
+            return;
+        }
+
+        let x = self.rng.random_range(0.1..0.9) * width;
+        let y = self.rng.random_range(0.1..0.5) * height;
+        let color = self.random_color();
+        for _ in 0..size {
+            let mut particle = self.spawn(color);
+            particle.x = x;
+            particle.y = y;
+            self.particles.push(particle);
+        }
+    }
+
+    fn draw(&self, cr: &cairo::Context) {
+        for p in &self.particles {
+            let _ = cr.save();
+            cr.translate(p.x, p.y);
+            cr.rotate(p.rotation);
+            (self.system.sprite)(cr, p);
+            let _ = cr.restore();
+        }
+    }
+}
+
+impl ResizableEffectState for ParticleState {
+    fn handle_resize(&mut self, new_width: f64, new_height: f64) {
+        if self.current_width <= 0.0 || self.current_height <= 0.0 {
+            self.current_width = new_width;
+            self.current_height = new_height;
+            return;
+        }
+
+        let scale_x = new_width / self.current_width;
+        let scale_y = new_height / self.current_height;
+        for p in &mut self.particles {
+            p.x *= scale_x;
+            p.y *= scale_y;
+        }
+
+        self.current_width = new_width;
+        self.current_height = new_height;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_test(p: &mut Particle, _rng: &mut StdRng) {
+        p.vy = 50.0;
+        p.lifetime = 1.0;
+    }
+
+    fn draw_test(_cr: &cairo::Context, _p: &Particle) {}
+
+    const FALLING: ParticleSystem = ParticleSystem {
+        count: 10,
+        emitter: Emitter::Falling,
+        gravity: 0.0,
+        drag: 0.0,
+        wind: 0.0,
+        palette: &[],
+        spawn: spawn_test,
+        sprite: draw_test,
+    };
+
+    const BURSTS: ParticleSystem = ParticleSystem {
+        count: 10,
+        emitter: Emitter::Bursts { rate: 1.0, size: 4 },
+        gravity: 0.0,
+        drag: 0.0,
+        wind: 0.0,
+        palette: &[(1.0, 0.0, 0.0)],
+        spawn: spawn_test,
+        sprite: draw_test,
+    };
+
+    #[test]
+    fn test_falling_particles_are_recycled() {
+        let mut state = ParticleState::new(&FALLING, 10, 100.0, 100.0, 1);
+        assert_eq!(state.particles.len(), 10);
+        assert!(state.particles.iter().all(|p| p.opacity() == 1.0));
+
+        for _ in 0..40 {
+            state.step(0.25);
+        }
+        assert_eq!(state.particles.len(), 10);
+        assert!(state.particles.iter().all(|p| p.y <= 100.0 + EDGE_MARGIN));
+    }
+
+    #[test]
+    fn test_bursts_respect_capacity_and_expire() {
+        let mut state = ParticleState::new(&BURSTS, 10, 100.0, 100.0, 1);
+        assert!(state.particles.is_empty());
+
+        // The timed burst and one more fit, a third would go over capacity
+        state.burst_progress = 0.99;
+        state.step(0.01);
+        state.burst(4, 100.0, 100.0);
+        state.burst(4, 100.0, 100.0);
+        assert_eq!(state.particles.len(), 8);
+        assert!(state.particles.iter().all(|p| p.color == (1.0, 0.0, 0.0)));
+
+        // Everything from the first bursts has expired a second later
+        state.step(0.5);
+        state.step(0.6);
+        assert!(state.particles.iter().all(|p| p.life > 0.0));
+        assert!(state.particles.len() <= 4);
+    }
+}
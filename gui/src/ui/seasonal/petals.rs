@@ -0,0 +1,80 @@
+//! Spring petals effect overlay.
+//!
+//! Blossom petals drifting down and tumbling in the wind, from the start of
+//! spring to the end of April.
+
+use crate::config::seasonal_debug;
+use crate::i18n::n_;
+use crate::ui::seasonal::common::MouseContext;
+use crate::ui::seasonal::particles::{self, Emitter, Particle, ParticleSystem};
+use crate::ui::seasonal::SeasonalEffect;
+use gtk4::cairo;
+use gtk4::glib;
+use gtk4::{ApplicationWindow, DrawingArea};
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+const PETALS: ParticleSystem = ParticleSystem {
+    count: 40,
+    emitter: Emitter::Falling,
+    gravity: 20.0,
+    drag: 0.4,
+    wind: 40.0,
+    palette: &[(1.0, 0.75, 0.82), (1.0, 0.85, 0.9), (0.98, 0.65, 0.75)],
+    spawn: spawn_petal,
+    sprite: draw_petal,
+};
+
+/// Spring petals effect.
+pub struct PetalsEffect;
+
+impl SeasonalEffect for PetalsEffect {
+    fn id(&self) -> &'static str {
+        "petals"
+    }
+
+    fn debug_env(&self) -> &'static str {
+        seasonal_debug::ENABLE_PETALS
+    }
+
+    fn in_season(&self) -> bool {
+        glib::DateTime::now_local().is_ok_and(|dt| match dt.month() {
+            3 => dt.day_of_month() >= 20,
+            4 => true,
+            _ => false,
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        n_("Petals (Spring)")
+    }
+
+    fn apply(
+        &self,
+        window: &ApplicationWindow,
+        _mouse_context: Option<&MouseContext>,
+    ) -> Option<Rc<DrawingArea>> {
+        particles::apply(window, &PETALS)
+    }
+}
+
+fn spawn_petal(p: &mut Particle, rng: &mut StdRng) {
+    p.vy = rng.random_range(20.0..50.0);
+    p.size = rng.random_range(4.0..8.0);
+    p.spin = rng.random_range(-1.5..1.5);
+    p.sway = rng.random_range(15.0..35.0);
+}
+
+fn draw_petal(cr: &cairo::Context, p: &Particle) {
+    let (r, g, b) = p.color;
+    // Narrowing with the phase makes the petal look like it's tumbling
+    let _ = cr.save();
+    cr.scale(p.size, p.size * (0.4 + 0.3 * p.phase.cos().abs()));
+    cr.arc(0.0, 0.0, 1.0, 0.0, 2.0 * PI);
+    let _ = cr.restore();
+
+    cr.set_source_rgba(r, g, b, 0.8);
+    let _ = cr.fill();
+}
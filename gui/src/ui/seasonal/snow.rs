@@ -3,10 +3,12 @@
 //! Adds a high-quality animated snow effect with parallax and soft-glow flakes.
 
 use crate::config::seasonal_debug;
+use crate::i18n::n_;
 use crate::ui::seasonal::common::{
-    add_overlay_to_window, setup_resize_handler, ResizableEffectState,
+    add_overlay_to_window, clear_frame, new_overlay_area, setup_resize_handler, start_frame_timer,
+    time_seed, ResizableEffectState, MAX_FRAME_STEP,
 };
-use crate::ui::seasonal::SeasonalEffect;
+use crate::ui::seasonal::{self, SeasonalEffect};
 use gtk4::cairo;
 use gtk4::glib;
 use gtk4::prelude::*;
@@ -24,22 +26,20 @@ const WIND_STRENGTH: f64 = 0.5;
 pub struct SnowEffect;
 
 impl SeasonalEffect for SnowEffect {
-    fn is_active(&self) -> bool {
-        // Check environment variable for debugging (overrides date check)
-        if let Some(enabled) = seasonal_debug::check_effect_env(seasonal_debug::ENABLE_SNOW) {
-            return enabled;
-        }
+    fn id(&self) -> &'static str {
+        "snow"
+    }
 
-        // Default: check if it's December
-        if let Ok(dt) = glib::DateTime::now_utc() {
-            dt.month() == 12
-        } else {
-            false
-        }
+    fn debug_env(&self) -> &'static str {
+        seasonal_debug::ENABLE_SNOW
+    }
+
+    fn in_season(&self) -> bool {
+        glib::DateTime::now_utc().is_ok_and(|dt| dt.month() == 12)
     }
 
     fn name(&self) -> &'static str {
-        "Snow (Christmas)"
+        n_("Snow (Christmas)")
     }
 
     fn apply(
@@ -47,23 +47,12 @@ impl SeasonalEffect for SnowEffect {
         window: &ApplicationWindow,
         _mouse_context: Option<&crate::ui::seasonal::common::MouseContext>,
     ) -> Option<Rc<DrawingArea>> {
-        let drawing_area = Rc::new(DrawingArea::new());
-        drawing_area.set_hexpand(true);
-        drawing_area.set_vexpand(true);
-        drawing_area.set_can_focus(false);
-        drawing_area.set_sensitive(false);
-        drawing_area.set_halign(gtk4::Align::Fill);
-        drawing_area.set_valign(gtk4::Align::Fill);
-        drawing_area.set_visible(crate::ui::seasonal::are_effects_enabled());
+        let drawing_area = new_overlay_area();
 
         let state = Rc::new(RefCell::new(None::<SnowState>));
         let setup_state = Rc::clone(&state);
 
-        let drawing_area_clone = drawing_area.clone();
-        glib::timeout_add_local(std::time::Duration::from_millis(16), move || {
-            drawing_area_clone.queue_draw();
-            glib::ControlFlow::Continue
-        });
+        start_frame_timer(&drawing_area);
 
         drawing_area.set_draw_func(move |_da, cr, width, height| {
             let mut state_ref = setup_state.borrow_mut();
@@ -76,11 +65,7 @@ impl SeasonalEffect for SnowEffect {
                 let now = std::time::Instant::now();
                 snow_state.update(width as f64, height as f64, now);
 
-                let _ = cr.save();
-                cr.set_operator(cairo::Operator::Clear);
-                let _ = cr.paint();
-                cr.set_operator(cairo::Operator::Over);
-                let _ = cr.restore();
+                clear_frame(cr);
 
                 snow_state.draw(cr, width as f64, height as f64);
             }
@@ -172,11 +157,9 @@ struct SnowState {
 
 impl SnowState {
     fn new(width: f64, height: f64) -> Self {
-        let seed = glib::DateTime::now_utc()
-            .map(|dt| dt.to_unix())
-            .unwrap_or(0) as u64;
+        let seed = time_seed();
         let mut rng = StdRng::seed_from_u64(seed);
-        let snowflakes = (0..SNOW_COUNT)
+        let snowflakes = (0..seasonal::scaled_count(SNOW_COUNT))
             .map(|_| Snowflake::new(width, height, &mut rng))
             .collect();
 
@@ -197,7 +180,10 @@ impl SnowState {
         self.current_width = width;
         self.current_height = height;
 
-        let dt = now.duration_since(self.last_time).as_secs_f64().min(0.1);
+        let dt = now
+            .duration_since(self.last_time)
+            .as_secs_f64()
+            .min(MAX_FRAME_STEP);
         self.last_time = now;
 
         if self.rng.random::<f64>() > 0.98 {
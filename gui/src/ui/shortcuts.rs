@@ -0,0 +1,122 @@
+//! Keyboard shortcuts for the main window.
+//!
+//! - Ctrl+1 to Ctrl+9 switch to the first nine pages
+//! - Ctrl+F focuses the header bar search
+//! - F5 refreshes the install states of page buttons
+//! - F9 toggles the sidebar
+//! - Ctrl+, opens Preferences
+//! - Ctrl+? shows the shortcuts dialog
+//!
+//! Esc in the task window is handled by the task runner.
+
+use crate::config;
+use crate::i18n::gettext;
+use crate::ui::context::AppContext;
+use crate::ui::navigation::PAGES;
+use crate::ui::utils::extract_widget;
+use adw::prelude::*;
+use adw::Application;
+use gtk4::{gio, glib, ApplicationWindow, Builder, SearchEntry};
+use log::info;
+
+/// Accelerator switching to the page at `index` in the sidebar, if it has one.
+pub fn page_accel(index: usize) -> Option<String> {
+    (index < 9).then(|| format!("<Control>{}", index + 1))
+}
+
+/// Human readable form of an accelerator, e.g. "Ctrl+1".
+pub fn accel_label(accel: &str) -> Option<String> {
+    let (key, modifiers) = gtk4::accelerator_parse(accel)?;
+    Some(gtk4::accelerator_get_label(key, modifiers).to_string())
+}
+
+/// Register the window actions and their accelerators.
+pub fn setup(app: &Application, builder: &Builder, window: &ApplicationWindow, ctx: &AppContext) {
+    let search_entry = extract_widget::<SearchEntry>(builder, "action_search_entry");
+
+    let ctx_clone = ctx.clone();
+    let open_page = gio::ActionEntry::builder("open-page")
+        .parameter_type(Some(glib::VariantTy::STRING))
+        .activate(move |_: &ApplicationWindow, _, parameter| {
+            if let Some(page) = parameter.and_then(|p| p.get::<String>()) {
+                ctx_clone.navigate_to_page(&page);
+            }
+        })
+        .build();
+
+    let search = gio::ActionEntry::builder("search")
+        .activate(move |_: &ApplicationWindow, _, _| {
+            search_entry.grab_focus();
+        })
+        .build();
+
+    let sidebar_toggle = ctx.ui.sidebar_toggle.clone();
+    let toggle_sidebar = gio::ActionEntry::builder("toggle-sidebar")
+        .activate(move |_: &ApplicationWindow, _, _| {
+            sidebar_toggle.set_active(!sidebar_toggle.is_active());
+        })
+        .build();
+
+    let refresh = gio::ActionEntry::builder("refresh")
+        .activate(|_: &ApplicationWindow, _, _| crate::ui::install_state::refresh())
+        .build();
+
+    let preferences = gio::ActionEntry::builder("preferences")
+        .activate(|window: &ApplicationWindow, _, _| {
+            crate::ui::dialogs::preferences::show_preferences_dialog(window)
+        })
+        .build();
+
+    let show_shortcuts = gio::ActionEntry::builder("show-shortcuts")
+        .activate(|window: &ApplicationWindow, _, _| show_shortcuts_dialog(window))
+        .build();
+
+    window.add_action_entries([
+        open_page,
+        search,
+        toggle_sidebar,
+        refresh,
+        preferences,
+        show_shortcuts,
+    ]);
+
+    for (index, page) in PAGES.iter().enumerate() {
+        if let Some(accel) = page_accel(index) {
+            app.set_accels_for_action(&format!("win.open-page::{}", page.id), &[&accel]);
+        }
+    }
+    app.set_accels_for_action("win.search", &["<Control>f"]);
+    app.set_accels_for_action("win.refresh", &["F5"]);
+    app.set_accels_for_action("win.toggle-sidebar", &["F9"]);
+    app.set_accels_for_action("win.preferences", &["<Control>comma"]);
+    app.set_accels_for_action("win.show-shortcuts", &["<Control>question"]);
+
+    info!("Keyboard shortcuts registered");
+}
+
+/// Show the shortcuts dialog, listing the page shortcuts by page title.
+fn show_shortcuts_dialog(window: &ApplicationWindow) {
+    let builder = Builder::from_resource(config::resources::dialogs::SHORTCUTS);
+    let dialog = extract_widget::<adw::ShortcutsDialog>(&builder, "shortcuts_dialog");
+    let pages_section = extract_widget::<adw::ShortcutsSection>(&builder, "pages_section");
+
+    for (index, page) in PAGES.iter().enumerate() {
+        if let Some(accel) = page_accel(index) {
+            pages_section.add(adw::ShortcutsItem::new(&gettext(page.title), &accel));
+        }
+    }
+
+    dialog.present(Some(window));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page_accel() {
+        assert_eq!(page_accel(0).as_deref(), Some("<Control>1"));
+        assert_eq!(page_accel(8).as_deref(), Some("<Control>9"));
+        assert_eq!(page_accel(9), None);
+    }
+}
@@ -4,12 +4,15 @@
 //! - Process spawning and management
 //! - Output capture (stdout/stderr)
 //! - Error handling and result processing
-//! - Command resolution (privilege escalation, AUR helpers)
+//! - In-process downloads with progress
 
-use super::command::{Command, CommandResult, CommandType, TaskStatus};
 use super::widgets::TaskRunnerWidgets;
 use crate::core;
-use crate::core::daemon::get_xero_auth_path;
+use crate::core::task_runner::{
+    resolve_command, shim_path, stop_daemon_if_needed, Command, CommandResult, CommandType,
+    DownloadSpec, TaskStatus, SUCCESS_MESSAGE,
+};
+use crate::i18n::{gettext, gettext_f};
 use gtk4::gio;
 use gtk4::glib;
 use log::{error, info, warn};
@@ -83,6 +86,9 @@ impl RunningContext {
 
                 self.widgets
                     .update_task_status(self.index, TaskStatus::Success);
+                if let Err(e) = core::journal::advance(self.index + 1) {
+                    warn!("Failed to update the task journal: {:#}", e);
+                }
                 execute_commands(
                     self.widgets.clone(),
                     self.commands.clone(),
@@ -103,15 +109,22 @@ impl RunningContext {
                     .update_task_status(self.index, TaskStatus::Failed);
 
                 // Include exit code in error message if available
-                let exit_msg = exit_code
-                    .map(|code| format!(" (exit code: {})", code))
-                    .unwrap_or_default();
-                let final_message = format!(
-                    "Operation failed at step {} of {}{}",
-                    self.index + 1,
-                    self.commands.len(),
-                    exit_msg
-                );
+                let step = (self.index + 1).to_string();
+                let total = self.commands.len().to_string();
+                let final_message = match exit_code {
+                    Some(code) => gettext_f(
+                        "Operation failed at step {step} of {total} (exit code: {code})",
+                        &[
+                            ("step", &step),
+                            ("total", &total),
+                            ("code", &code.to_string()),
+                        ],
+                    ),
+                    None => gettext_f(
+                        "Operation failed at step {step} of {total}",
+                        &[("step", &step), ("total", &total)],
+                    ),
+                };
 
                 finalize_execution(&self.widgets, false, &final_message);
             }
@@ -137,7 +150,7 @@ pub fn execute_commands(
     }
 
     if index >= commands.len() {
-        finalize_execution(&widgets, true, super::SUCCESS_MESSAGE);
+        finalize_execution(&widgets, true, SUCCESS_MESSAGE);
         return;
     }
 
@@ -147,18 +160,30 @@ pub fn execute_commands(
     widgets.update_task_status(index, TaskStatus::Running);
     widgets.set_title(&cmd.description);
 
+    if let CommandType::Download(spec) = &cmd.command_type {
+        let context = RunningContext::new(
+            widgets.clone(),
+            commands.clone(),
+            index,
+            cancelled.clone(),
+            current_process.clone(),
+        );
+        widgets.append_command_header(&cmd.description);
+        execute_download(context, spec.clone());
+        return;
+    }
+
     let (program, args) = match resolve_command(cmd) {
         Ok(result) => result,
         Err(err) => {
             error!("Failed to prepare command: {}", err);
-            let error_msg = format!("Failed to prepare command: {}\n", err);
-            widgets.append_colored(&error_msg, "error");
-            widgets.update_task_status(index, TaskStatus::Failed);
-            finalize_execution(
-                &widgets,
-                false,
-                &format!("Failed to prepare command: {}", err),
+            let error_msg = gettext_f(
+                "Failed to prepare command: {error}",
+                &[("error", &err.to_string())],
             );
+            widgets.append_colored(&format!("{}\n", error_msg), "error");
+            widgets.update_task_status(index, TaskStatus::Failed);
+            finalize_execution(&widgets, false, &error_msg);
             return;
         }
     };
@@ -186,12 +211,8 @@ pub fn execute_commands(
     process.args(&args);
 
     // Inject sudo shim to intercept sudo calls in scripts
-    let scripts_dir = crate::config::paths::scripts();
-    if scripts_dir.exists() {
-        if let Ok(path) = std::env::var("PATH") {
-            let new_path = format!("{}:{}", scripts_dir.display(), path);
-            process.env("PATH", new_path);
-        }
+    if let Some(path) = shim_path() {
+        process.env("PATH", path);
     }
 
     process.stdout(Stdio::piped());
@@ -201,14 +222,13 @@ pub fn execute_commands(
         Ok(child) => child,
         Err(err) => {
             error!("Failed to start command: {}", err);
-            let error_msg = format!("Failed to start operation: {}\n", err);
-            widgets.append_colored(&error_msg, "error");
-            widgets.update_task_status(index, TaskStatus::Failed);
-            finalize_execution(
-                &widgets,
-                false,
-                &format!("Failed to start operation: {}", err),
+            let error_msg = gettext_f(
+                "Failed to start operation: {error}",
+                &[("error", &err.to_string())],
             );
+            widgets.append_colored(&format!("{}\n", error_msg), "error");
+            widgets.update_task_status(index, TaskStatus::Failed);
+            finalize_execution(&widgets, false, &error_msg);
             return;
         }
     };
@@ -279,16 +299,17 @@ pub fn execute_commands(
     let widgets_stderr = widgets.clone();
     let result_arc_for_output = result_arc.clone();
     glib::timeout_add_local(std::time::Duration::from_millis(50), move || {
-        // Process stdout
-        while let Ok(text) = stdout_rx.try_recv() {
-            let cleaned_text = strip_ansi_escapes::strip_str(&text);
-            // Text already includes newline from buffer processing
+        // Append what arrived since the last tick at once, so a flood of
+        // lines doesn't mean a widget update each
+        let stdout: String = stdout_rx.try_iter().collect();
+        if !stdout.is_empty() {
+            // Text already includes newlines from buffer processing
+            let cleaned_text = strip_ansi_escapes::strip_str(&stdout);
             widgets_stdout.append_colored(&cleaned_text, "stdout");
         }
-        // Process stderr
-        while let Ok(text) = stderr_rx.try_recv() {
-            let cleaned_text = strip_ansi_escapes::strip_str(&text);
-            // Text already includes newline from buffer processing
+        let stderr: String = stderr_rx.try_iter().collect();
+        if !stderr.is_empty() {
+            let cleaned_text = strip_ansi_escapes::strip_str(&stderr);
             widgets_stderr.append_colored(&cleaned_text, "stderr");
         }
         // Stop if result is ready
@@ -350,64 +371,81 @@ pub fn execute_commands(
     });
 }
 
-/// Resolve command to executable program and arguments,
-/// handling privilege escalation (pkexec) and AUR helper detection.
-///
-/// # Returns
-///
-/// A tuple of `(program, args)` where `program` is the executable to run
-/// and `args` are the arguments to pass to it.
-///
-/// # Errors
-///
-/// Returns an error if the AUR helper is required but not available.
-fn resolve_command(command: &Command) -> Result<(String, Vec<String>), String> {
-    // Prepare PATH with scripts directory for sudo shim
-    let scripts_dir = crate::config::paths::scripts();
-    let shim_path_env = if scripts_dir.exists() {
-        if let Ok(path) = std::env::var("PATH") {
-            Some(format!("PATH={}:{}", scripts_dir.display(), path))
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-
-    match command.command_type {
-        CommandType::Normal => Ok((command.program.clone(), command.args.clone())),
-        CommandType::Privileged => {
-            // Use xero-auth client instead of pkexec for better session reuse
-            let mut args = Vec::new();
+/// Progress of a download running on a worker thread.
+enum DownloadMessage {
+    Progress(core::download::DownloadState),
+    Done(Result<(), String>),
+}
 
-            // Pass PATH via --env if available
-            if let Some(env) = &shim_path_env {
-                args.push("--env".to_string());
-                args.push(env.clone());
-            }
+/// Run a download step with the native downloader, showing progress on the
+/// task item. Cancelling the dialog aborts the transfer.
+fn execute_download(context: Rc<RunningContext>, spec: DownloadSpec) {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc};
+
+    info!("Downloading {} to {}", spec.url, spec.dest);
+    context
+        .widgets
+        .append_colored(&format!("{}\n", spec.url), "stdout");
+
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+
+    let thread_cancel = cancel_flag.clone();
+    std::thread::spawn(move || {
+        let dest = std::path::Path::new(&spec.dest);
+        let progress_tx = tx.clone();
+        let result = core::download::fetch_blocking(
+            &spec.url,
+            dest,
+            move |state| {
+                let _ = progress_tx.send(DownloadMessage::Progress(state));
+            },
+            thread_cancel,
+        )
+        .and_then(|()| match &spec.sha256 {
+            Some(hash) => crate::core::verify::check_sha256(dest, hash),
+            None => Ok(()),
+        })
+        .map_err(|e| format!("{:#}", e));
+        let _ = tx.send(DownloadMessage::Done(result));
+    });
 
-            args.push(command.program.clone());
-            args.extend(command.args.clone());
-            Ok((get_xero_auth_path().to_string_lossy().to_string(), args))
-        }
-        CommandType::Aur => {
-            let helper = core::aur_helper()
-                .ok_or_else(|| "AUR helper not available (paru or yay required)".to_string())?;
-            let mut args = Vec::with_capacity(command.args.len() + 2);
-            args.push("--sudo".to_string());
-            args.push(get_xero_auth_path().to_string_lossy().to_string());
-            args.extend(command.args.clone());
-            Ok((helper.to_string(), args))
+    glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        if *context.cancelled.borrow() {
+            cancel_flag.store(true, Ordering::Relaxed);
         }
-    }
-}
 
-/// Stop the daemon if needed.
-fn stop_daemon_if_needed() {
-    let rt = tokio::runtime::Runtime::new().unwrap();
-    if let Err(e) = rt.block_on(core::daemon::stop_daemon()) {
-        error!("Failed to stop daemon: {}", e);
-    }
+        while let Ok(message) = rx.try_recv() {
+            match message {
+                DownloadMessage::Progress(state) => {
+                    context.widgets.update_task_progress(context.index, &state);
+                }
+                DownloadMessage::Done(result) => {
+                    context.widgets.hide_task_progress(context.index);
+                    let result = match result {
+                        Ok(()) => {
+                            context.widgets.append_colored(
+                                &format!("{}\n", gettext("Download complete")),
+                                "stdout",
+                            );
+                            CommandResult::Success
+                        }
+                        Err(err) => {
+                            error!("Download failed: {}", err);
+                            context
+                                .widgets
+                                .append_colored(&format!("{}\n", err), "stderr");
+                            CommandResult::Failure { exit_code: None }
+                        }
+                    };
+                    context.set_exit_result(result);
+                    return glib::ControlFlow::Break;
+                }
+            }
+        }
+        glib::ControlFlow::Continue
+    });
 }
 
 /// Finalize dialog with success or failure message.
@@ -416,16 +454,26 @@ pub fn finalize_execution(widgets: &TaskRunnerWidgets, success: bool, message: &
 
     // Stop daemon before finalizing
     stop_daemon_if_needed();
+    crate::ui::elevation::set_active(false);
+    core::journal::finish();
+
+    // Point at the snapshot taken before a destructive action
+    let text = gettext(message);
+    let text = match crate::core::snapshot::take_created() {
+        Some(created) => format!("{}\n{}", text, created.note()),
+        None => text,
+    };
 
     // Print final message to terminal
     if success {
-        let success_msg = format!("\n{}\n", message);
+        let success_msg = format!("\n{}\n", text);
         widgets.append_colored(&success_msg, "stdout");
     } else {
-        let error_msg = format!("\n{}\n", message);
+        let error_msg = format!("\n{}\n", text);
         widgets.append_colored(&error_msg, "error");
     }
 
     super::ACTION_RUNNING.store(false, Ordering::SeqCst);
-    widgets.show_completion(success, message);
+    widgets.show_completion(success, &text);
+    super::record_history(widgets, success, message);
 }
@@ -4,8 +4,13 @@
 //! - Step-by-step execution status with visual progress tracking
 //! - Output capture (stdout/stderr) for better error reporting
 //! - Cancellation support (waits for current command to finish)
-//! - Automatic privilege escalation via pkexec
-//! - AUR helper integration (paru/yay)
+//! - Privilege escalation through a xero-auth session, pkexec or sudo
+//! - AUR helper integration (paru, yay or pikaur)
+//! - Native downloads with resume and per-task progress
+//! - Export of the steps as a shell script
+//!
+//! The command model, headless execution and script export live in
+//! `core::task_runner` and are re-exported here.
 //!
 //! ## Usage
 //!
@@ -51,83 +56,38 @@
 //! 3. Capture command output for error reporting
 //! 4. Show completion status with appropriate success/failure messages
 
-mod command;
 mod executor;
 mod widgets;
 
-use crate::ui::utils::extract_widget;
+use crate::core::conflicts::{self, Conflict};
+use crate::core::estimate::{Estimate, Targets};
+use crate::core::{history, journal, kernel, manifest, notifier};
+use crate::ui::dialogs::warning::show_warning_confirmation;
+use crate::ui::utils::{extract_widget, spawn_blocking};
+use adw::prelude::*;
 use gtk4::glib;
-use gtk4::prelude::*;
 use gtk4::{Button, Label, Separator, ToggleButton, Window};
 use log::{error, info, warn};
 use std::cell::RefCell;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 
 // Re-export public API
-pub use command::{Command, TaskStatus};
-
-use widgets::{TaskItem, TaskRunnerWidgets};
-
-/// Helper for building sequences of commands with a fluent API.
-///
-/// # Example
-///
-/// ```no_run
-/// use crate::ui::task_runner::{Command, CommandSequence};
-///
-/// let commands = CommandSequence::new()
-///     .then(Command::builder().aur()
-///         .args(&["-S", "package"])
-///         .description("Installing package")
-///         .build())
-///     .then(Command::builder().privileged()
-///         .program("systemctl")
-///         .args(&["enable", "--now", "service"])
-///         .description("Enabling service")
-///         .build())
-///     .build();
-/// ```
-#[derive(Debug, Default)]
-pub struct CommandSequence {
-    pub(super) commands: Vec<Command>,
-}
-
-impl CommandSequence {
-    /// Create a new empty command sequence.
-    pub fn new() -> Self {
-        Self {
-            commands: Vec::new(),
-        }
-    }
-
-    /// Add a command to the sequence.
-    ///
-    /// Can be chained to add multiple commands in order.
-    pub fn then(mut self, command: Command) -> Self {
-        self.commands.push(command);
-        self
-    }
+pub use crate::core::task_runner::{
+    headless, Command, CommandResult, CommandSequence, Restart, TaskStatus,
+};
 
-    /// Build the final command sequence.
-    pub fn build(self) -> Self {
-        self
-    }
+use crate::core::task_runner::{needs_daemon, restart_needed, script};
 
-    /// Check if the sequence is empty.
-    pub fn is_empty(&self) -> bool {
-        self.commands.is_empty()
-    }
-}
+use widgets::{TaskItem, TaskRunnerWidgets};
 
 /// Message displayed when waiting for current command to finish after cancellation.
-pub(super) const CANCEL_WAITING_MESSAGE: &str = "Waiting for current command to finish...";
+pub(super) const CANCEL_WAITING_MESSAGE: &str =
+    crate::i18n::n_("Waiting for current command to finish...");
 
 /// Message displayed when operation is canceled.
-pub(super) const CANCELLED_MESSAGE: &str = "Operation cancelled by user";
-
-/// Message displayed when all operations complete successfully.
-pub(super) const SUCCESS_MESSAGE: &str = "All operations completed successfully!";
+pub(super) const CANCELLED_MESSAGE: &str = crate::i18n::n_("Operation cancelled by user");
 
 /// Global flag to track if an action is currently running.
 static ACTION_RUNNING: AtomicBool = AtomicBool::new(false);
@@ -137,6 +97,12 @@ pub fn is_running() -> bool {
     ACTION_RUNNING.load(Ordering::SeqCst)
 }
 
+thread_local! {
+    /// Title, requested commands and start of the running sequence, for the
+    /// history and notifications.
+    static CURRENT_RUN: RefCell<Option<(String, Vec<Command>, Instant)>> = const { RefCell::new(None) };
+}
+
 /// Run commands with a progress dialog.
 ///
 /// Displays a modal dialog showing command execution progress with:
@@ -166,17 +132,222 @@ pub fn is_running() -> bool {
 ///     .build();
 /// run(&window, commands, "System Setup");
 /// ```
+///
+/// Sequences installing packages are sized first, and big ones only start
+/// once the user has seen the download and disk space they take. Installed
+/// packages that conflict with the ones a sequence installs are offered for
+/// replacement before it starts. Sequences installing DKMS modules get the
+/// headers of every installed kernel first.
 pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
     if commands.is_empty() {
         error!("No commands provided");
         return;
     }
 
+    // Picked on a page in Batch Mode, to run with the rest later
+    let Some(commands) = crate::ui::batch::offer(commands, title) else {
+        return;
+    };
+
     if is_running() {
         warn!("Action already running - ignoring request");
         return;
     }
 
+    let targets = Targets::of(commands.commands());
+    if targets.is_empty() {
+        start(parent, commands, title);
+        return;
+    }
+
+    // Sizing can take a moment; hold off other runs meanwhile
+    ACTION_RUNNING.store(true, Ordering::SeqCst);
+    let parent = parent.clone();
+    let title = title.to_string();
+    let steps = commands.commands().to_vec();
+    spawn_blocking(
+        move || {
+            (
+                conflicts::check(&steps),
+                kernel::check(&steps),
+                targets.estimate(),
+            )
+        },
+        move |(conflicts, headers, estimate)| {
+            ACTION_RUNNING.store(false, Ordering::SeqCst);
+            let commands = match headers.step() {
+                Some(step) => CommandSequence::new().then(step).append(commands).build(),
+                None => commands,
+            };
+            if headers.missing.is_empty() {
+                check_conflicts(&parent, commands, &title, conflicts, estimate);
+                return;
+            }
+            let parent_clone = parent.clone();
+            confirm_missing_headers(&parent, &headers.missing, move || {
+                check_conflicts(&parent_clone, commands, &title, conflicts, estimate);
+            });
+        },
+    );
+}
+
+fn check_conflicts(
+    parent: &Window,
+    commands: CommandSequence,
+    title: &str,
+    conflicts: Vec<Conflict>,
+    estimate: Option<Estimate>,
+) {
+    if conflicts.is_empty() {
+        proceed(parent, commands, title, estimate);
+    } else {
+        resolve_conflicts(parent, commands, title, conflicts, estimate);
+    }
+}
+
+/// Warn that DKMS can't build the sequence's modules for some kernels,
+/// since their headers aren't in the repos.
+fn confirm_missing_headers<F>(parent: &Window, kernels: &[String], on_confirm: F)
+where
+    F: FnOnce() + 'static,
+{
+    info!("No headers package found for {:?}", kernels);
+    let message = crate::i18n::gettext_f(
+        "No headers package was found for: <b>{kernels}</b>.\n\n\
+         This installs a kernel module built with DKMS, which can't be built \
+         for a kernel without its headers. It won't load while one of these \
+         kernels is running.",
+        &[("kernels", &glib::markup_escape_text(&kernels.join(", ")))],
+    );
+    show_warning_confirmation(
+        parent,
+        &crate::i18n::gettext("Kernel Headers Not Found"),
+        &message,
+        on_confirm,
+    );
+}
+
+/// Start the sequence, once confirmed if it's a big install.
+fn proceed(parent: &Window, commands: CommandSequence, title: &str, estimate: Option<Estimate>) {
+    match estimate {
+        Some(estimate) if estimate.is_large() => confirm_large(parent, commands, title, &estimate),
+        _ => start(parent, commands, title),
+    }
+}
+
+/// Ask whether installed packages in the way of the sequence should be
+/// replaced, rather than have pacman stop halfway through.
+fn resolve_conflicts(
+    parent: &Window,
+    commands: CommandSequence,
+    title: &str,
+    conflicts: Vec<Conflict>,
+    estimate: Option<Estimate>,
+) {
+    info!(
+        "{} conflicts with installed packages: {:?}",
+        title, conflicts
+    );
+    let list = conflicts
+        .iter()
+        .map(|c| {
+            format!(
+                "• {}: {} replaces {} ({})",
+                c.what,
+                c.wanted,
+                c.installed,
+                c.packages.join(", ")
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(crate::i18n::gettext("Conflicting Packages"))
+        .body(format!(
+            "{}\n\n{}",
+            crate::i18n::gettext(
+                "Only one of these can be installed at a time. Replacing removes \
+                 the installed packages before the rest of the sequence runs."
+            ),
+            list
+        ))
+        .build();
+    dialog.add_responses(&[
+        ("cancel", crate::i18n::gettext("Cancel").as_str()),
+        ("replace", crate::i18n::gettext("Replace").as_str()),
+    ]);
+    dialog.set_response_appearance("replace", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+
+    let parent_clone = parent.clone();
+    let title = title.to_string();
+    let pending = RefCell::new(Some((commands, estimate)));
+    dialog.connect_response(Some("replace"), move |_, _| {
+        let Some((commands, estimate)) = pending.take() else {
+            return;
+        };
+        let commands = CommandSequence::new()
+            .then(conflicts::resolution(&conflicts))
+            .append(commands)
+            .build();
+        proceed(&parent_clone, commands, &title, estimate);
+    });
+
+    dialog.present(Some(parent));
+}
+
+/// Show what a big install downloads and takes on disk, and start it once
+/// confirmed.
+fn confirm_large(parent: &Window, commands: CommandSequence, title: &str, estimate: &Estimate) {
+    info!(
+        "{} downloads {} bytes and installs {} bytes",
+        title, estimate.download, estimate.installed
+    );
+    let mut message = crate::i18n::ngettext_f(
+        "<b>{title}</b> downloads about <b>{download}</b> and takes <b>{installed}</b> of \
+         disk space, for {count} package.",
+        "<b>{title}</b> downloads about <b>{download}</b> and takes <b>{installed}</b> of \
+         disk space, for {count} packages.",
+        estimate.count as u32,
+        &[
+            (
+                "title",
+                &glib::markup_escape_text(&crate::i18n::gettext(title)),
+            ),
+            ("download", &glib::format_size(estimate.download)),
+            ("installed", &glib::format_size(estimate.installed)),
+            ("count", &estimate.count.to_string()),
+        ],
+    );
+    if estimate.partial {
+        message.push_str("\n\n");
+        message.push_str(&crate::i18n::gettext(
+            "Some packages can't be sized ahead of time, so it will be somewhat more.",
+        ));
+    }
+
+    let parent_clone = parent.clone();
+    let title = title.to_string();
+    show_warning_confirmation(
+        parent,
+        &crate::i18n::gettext("Large Download"),
+        &message,
+        move || start(&parent_clone, commands, &title),
+    );
+}
+
+/// Open the progress dialog and run the sequence.
+fn start(parent: &Window, commands: CommandSequence, title: &str) {
+    CURRENT_RUN.with(|run| {
+        *run.borrow_mut() = Some((
+            title.to_string(),
+            commands.commands().to_vec(),
+            Instant::now(),
+        ));
+    });
+
     ACTION_RUNNING.store(true, Ordering::SeqCst);
 
     let builder = gtk4::Builder::from_resource(crate::config::resources::dialogs::TASK_LIST);
@@ -187,15 +358,20 @@ pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
     let scrolled_window: gtk4::ScrolledWindow = extract_widget(&builder, "task_scrolled_window");
     let cancel_button: Button = extract_widget(&builder, "cancel_button");
     let close_button: Button = extract_widget(&builder, "close_button");
+    let export_button: Button = extract_widget(&builder, "export_button");
+    let full_log_button: Button = extract_widget(&builder, "full_log_button");
     let sidebar_toggle: ToggleButton = extract_widget(&builder, "sidebar_toggle_button");
     let sidebar_revealer: gtk4::Revealer = extract_widget(&builder, "sidebar_revealer");
     let output_text_view: gtk4::TextView = extract_widget(&builder, "output_text_view");
     let output_text_buffer = output_text_view.buffer();
 
     window.set_transient_for(Some(parent));
-    window.set_title(Some(title));
+    window.set_title(Some(&crate::i18n::gettext(title)));
 
-    let commands_vec = commands.commands;
+    let commands_vec = commands.into_commands();
+    if let Err(e) = journal::start(title, &commands_vec) {
+        warn!("Failed to write the task journal: {:#}", e);
+    }
 
     // Create task items for each command
     let mut task_items = Vec::new();
@@ -212,7 +388,10 @@ pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
     }
 
     // Initialize output buffer
-    output_text_buffer.set_text("Command outputs will appear here as tasks execute...\n\n");
+    output_text_buffer.set_text(&format!(
+        "{}\n\n",
+        crate::i18n::gettext("Command outputs will appear here as tasks execute...")
+    ));
 
     let widgets = Rc::new(TaskRunnerWidgets::new(
         window.clone(),
@@ -226,8 +405,15 @@ pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
         sidebar_revealer,
         output_text_view,
         output_text_buffer,
+        full_log_button,
     ));
 
+    // Output goes to the log file as it arrives; the view keeps only the tail
+    match history::create_log(title, history::now()) {
+        Ok((path, file)) => widgets.start_log(path, file),
+        Err(e) => warn!("Failed to create task log: {:#}", e),
+    }
+
     // Setup sidebar toggle binding and initialize collapsed
     widgets.setup_sidebar_toggle();
     widgets.init_sidebar_collapsed();
@@ -242,7 +428,7 @@ pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
     cancel_button.connect_clicked(move |_| {
         *cancelled_clone.borrow_mut() = true;
         widgets_clone.disable_cancel();
-        widgets_clone.set_title(CANCEL_WAITING_MESSAGE);
+        widgets_clone.set_title(&crate::i18n::gettext(CANCEL_WAITING_MESSAGE));
     });
 
     // Close button handler
@@ -251,6 +437,33 @@ pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
         widgets_clone.window.close();
     });
 
+    // Export button handler
+    let commands_clone = commands.clone();
+    let window_clone = window.clone();
+    let title_owned = title.to_string();
+    export_button.connect_clicked(move |_| {
+        export_script(&window_clone, &commands_clone, &title_owned);
+    });
+
+    // Esc cancels a running sequence, or closes the window once it's done
+    let key_controller = gtk4::EventControllerKey::new();
+    let cancel_clone = cancel_button.clone();
+    let close_clone = close_button.clone();
+    key_controller.connect_key_pressed(move |_, key, _, _| {
+        if key != gtk4::gdk::Key::Escape {
+            return glib::Propagation::Proceed;
+        }
+        if cancel_clone.is_visible() {
+            if cancel_clone.is_sensitive() {
+                cancel_clone.emit_clicked();
+            }
+        } else if close_clone.is_visible() {
+            close_clone.emit_clicked();
+        }
+        glib::Propagation::Stop
+    });
+    window.add_controller(key_controller);
+
     // Window close handler
     let cancelled_clone = cancelled.clone();
     window.connect_close_request(move |_| {
@@ -261,27 +474,265 @@ pub fn run(parent: &Window, commands: CommandSequence, title: &str) {
 
     window.present();
 
-    // Check if we need the daemon (any privileged or AUR commands)
-    let needs_daemon = commands.iter().any(|cmd| {
-        matches!(
-            cmd.command_type,
-            command::CommandType::Privileged | command::CommandType::Aur
-        )
-    });
-
     // Start daemon if needed
-    if needs_daemon {
+    if needs_daemon(&commands) {
         if let Err(e) = crate::core::daemon::start_daemon() {
             error!("Failed to start daemon: {}", e);
-            let error_msg = format!("Failed to start authentication daemon: {}\n", e);
-            widgets.append_colored(&error_msg, "error");
-            widgets.set_title(&format!("Failed to start authentication daemon: {}", e));
-            widgets.show_completion(false, "Failed to start authentication daemon");
+            let error_msg = crate::i18n::gettext_f(
+                "Failed to start authentication daemon: {error}",
+                &[("error", &e.to_string())],
+            );
+            widgets.append_colored(&format!("{}\n", error_msg), "error");
+            widgets.set_title(&error_msg);
+            widgets.show_completion(
+                false,
+                &crate::i18n::gettext("Failed to start authentication daemon"),
+            );
             return;
         }
         info!("Daemon ready for privileged commands");
+        crate::ui::elevation::set_active(true);
     }
 
     // Start executing commands
     executor::execute_commands(widgets, commands, 0, cancelled, current_process);
 }
+
+/// Close the output log of the finished sequence and add it to the history,
+/// record the files it created outside of pacman, send a notification if it
+/// ran for long, and ask for a relogin or reboot if a step needs one.
+pub(super) fn record_history(widgets: &TaskRunnerWidgets, success: bool, message: &str) {
+    let Some((title, commands, started)) = CURRENT_RUN.with(|run| run.borrow_mut().take()) else {
+        return;
+    };
+
+    let outcome = if success {
+        history::Outcome::Success
+    } else if message == CANCELLED_MESSAGE {
+        history::Outcome::Cancelled
+    } else {
+        history::Outcome::Failed
+    };
+    let timestamp = history::now();
+
+    let log = widgets.finish_log();
+
+    let notifier = crate::config::user::load().notifier;
+    if notifier::should_notify(&notifier, started.elapsed()) {
+        let title = title.clone();
+        std::thread::spawn(move || {
+            if let Err(e) = notifier::send(&notifier, &title, outcome) {
+                warn!("Failed to send task notification: {:#}", e);
+            }
+        });
+    }
+
+    let entry = history::Entry {
+        title: title.clone(),
+        timestamp,
+        outcome,
+        log,
+        commands: commands.clone(),
+    };
+    if let Err(e) = history::record(entry) {
+        warn!("Failed to record task history: {:#}", e);
+    }
+    crate::ui::activity::refresh();
+
+    if outcome != history::Outcome::Success {
+        crate::ui::install_state::refresh();
+        return;
+    }
+    if let Some(restart) = restart_needed(&commands) {
+        prompt_restart(&widgets.window, restart);
+    }
+    // Pages reading the manifest refresh once it's written
+    spawn_blocking(
+        move || manifest::record(&title, timestamp, &commands),
+        |result| {
+            if let Err(e) = result {
+                warn!("Failed to record installed files: {:#}", e);
+            }
+            crate::ui::install_state::refresh();
+        },
+    );
+}
+
+/// Offer to run the steps left of a sequence that was cut short when the
+/// toolkit last stopped.
+pub fn offer_resume(window: &Window) {
+    let Some(interrupted) = journal::load() else {
+        return;
+    };
+    let Some(step) = interrupted.interrupted_step() else {
+        journal::finish();
+        return;
+    };
+    let position = interrupted.completed + 1;
+    let total = interrupted.commands.len();
+    info!(
+        "'{}' was interrupted at step {} of {}",
+        interrupted.title, position, total
+    );
+
+    let body = format!(
+        "The toolkit stopped while running <b>{}</b>, at step {} of {}:\n<i>{}</i>\n\n\
+         That step may have been left half-done. Resuming runs it again, \
+         followed by the steps after it.",
+        glib::markup_escape_text(&crate::i18n::gettext(&interrupted.title)),
+        position,
+        total,
+        glib::markup_escape_text(&step.description)
+    );
+    let dialog = adw::AlertDialog::builder()
+        .heading(crate::i18n::gettext("Task Interrupted"))
+        .body(body)
+        .body_use_markup(true)
+        .build();
+    let resume_label = format!("{} {}", crate::i18n::gettext("Resume at Step"), position);
+    dialog.add_responses(&[
+        ("discard", crate::i18n::gettext("Discard").as_str()),
+        ("resume", resume_label.as_str()),
+    ]);
+    dialog.set_response_appearance("resume", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("resume"));
+    dialog.set_close_response("discard");
+
+    let parent = window.clone();
+    dialog.connect_response(None, move |_, response| {
+        journal::finish();
+        if response != "resume" {
+            info!("Discarded the interrupted '{}'", interrupted.title);
+            return;
+        }
+        let commands = interrupted
+            .remaining()
+            .iter()
+            .cloned()
+            .fold(CommandSequence::new(), CommandSequence::then)
+            .build();
+        // A title of its own, so hooks already among the steps don't run twice
+        let title = format!("{} (Resumed)", interrupted.title);
+        run(&parent, commands, &title);
+    });
+    dialog.present(Some(window));
+}
+
+/// Tell the user the finished sequence needs a relogin or reboot, and offer
+/// to do it right away.
+fn prompt_restart(window: &Window, restart: Restart) {
+    let (heading, body, response, label) = match restart {
+        Restart::Relogin => (
+            crate::i18n::n_("Log Out Required"),
+            crate::i18n::n_(
+                "Some changes, like new group memberships, only apply once you log out and back in.",
+            ),
+            "logout",
+            crate::i18n::n_("Log Out"),
+        ),
+        Restart::Reboot => (
+            crate::i18n::n_("Reboot Required"),
+            crate::i18n::n_(
+                "Some changes, like a new kernel or module options, only apply after a reboot.",
+            ),
+            "reboot",
+            crate::i18n::n_("Reboot Now"),
+        ),
+    };
+    info!("Sequence needs {:?}", restart);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading(crate::i18n::gettext(heading))
+        .body(crate::i18n::gettext(body))
+        .build();
+    dialog.add_responses(&[
+        ("later", crate::i18n::gettext("Later").as_str()),
+        (response, crate::i18n::gettext(label).as_str()),
+    ]);
+    dialog.set_response_appearance(response, adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("later"));
+    dialog.set_close_response("later");
+
+    dialog.connect_response(Some(response), move |_, _| {
+        let result = match restart {
+            Restart::Reboot => std::process::Command::new("systemctl")
+                .arg("reboot")
+                .spawn(),
+            Restart::Relogin => match std::env::var("XDG_SESSION_ID") {
+                Ok(session) => std::process::Command::new("loginctl")
+                    .args(["terminate-session", session.as_str()])
+                    .spawn(),
+                Err(_) => std::process::Command::new("loginctl")
+                    .arg("terminate-user")
+                    .arg(glib::user_name())
+                    .spawn(),
+            },
+        };
+        if let Err(e) = result {
+            error!("Failed to {:?}: {}", restart, e);
+        }
+    });
+
+    dialog.present(Some(window));
+}
+
+/// Ask for a file name and save the commands there as a shell script.
+fn export_script(window: &Window, commands: &[Command], title: &str) {
+    let script = script::to_shell_script(commands, title);
+    let file_name = format!(
+        "{}.sh",
+        title
+            .to_lowercase()
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect::<Vec<_>>()
+            .join("-")
+    );
+
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title(&crate::i18n::gettext("Export as Script"));
+    dialog.set_initial_name(Some(&file_name));
+
+    let window = window.clone();
+    glib::spawn_future_local(async move {
+        let Ok(file) = dialog.save_future(Some(&window)).await else {
+            // User cancelled
+            return;
+        };
+        let Some(path) = file.path() else {
+            return;
+        };
+
+        let result = std::fs::write(&path, script).and_then(|()| {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        });
+        let (heading, body) = match result {
+            Ok(()) => {
+                info!("Exported script to {}", path.display());
+                (
+                    crate::i18n::gettext("Script Exported"),
+                    crate::i18n::gettext_f(
+                        "Saved to {path}",
+                        &[("path", &path.display().to_string())],
+                    ),
+                )
+            }
+            Err(e) => {
+                error!("Failed to export script: {}", e);
+                (
+                    crate::i18n::gettext("Error"),
+                    crate::i18n::gettext_f(
+                        "Failed to export script: {error}",
+                        &[("error", &e.to_string())],
+                    ),
+                )
+            }
+        };
+        adw::AlertDialog::builder()
+            .heading(heading)
+            .body(body)
+            .build()
+            .present(Some(&window));
+    });
+}
@@ -3,12 +3,29 @@
 //! This module provides the UI components for displaying command execution progress,
 //! including task items, status icons, and scroll management.
 
-use super::command::TaskStatus;
+use super::TaskStatus;
+use crate::core::download::{format_bytes, format_speed, DownloadState};
 use adw::prelude::*;
 use gtk4::{
-    Box as GtkBox, Button, Image, Label, Revealer, ScrolledWindow, TextBuffer, TextView,
-    ToggleButton, Window,
+    Box as GtkBox, Button, Image, Label, ProgressBar, Revealer, ScrolledWindow, TextBuffer,
+    TextView, ToggleButton, Window,
 };
+use log::{info, warn};
+use std::cell::{Cell, RefCell};
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::path::PathBuf;
+
+/// Lines kept in the output view. The log file keeps all of them.
+const MAX_SCROLLBACK_LINES: i32 = 5000;
+
+/// Lines over the limit before the view is trimmed, so a busy command
+/// doesn't trim on every append.
+const TRIM_BATCH_LINES: i32 = 500;
+
+/// First line of the output view once older lines were dropped.
+const TRIMMED_NOTE: &str =
+    "[Earlier output was trimmed. The full log opens from the header bar.]\n";
 
 /// Container for all task runner dialog widgets.
 pub struct TaskRunnerWidgets {
@@ -25,6 +42,12 @@ pub struct TaskRunnerWidgets {
     pub sidebar_revealer: Revealer,
     pub output_text_view: TextView,
     pub output_text_buffer: TextBuffer,
+    pub full_log_button: Button,
+    /// Log file everything appended to the output view is written to, with
+    /// its path. The writer is dropped if writing fails.
+    log: RefCell<Option<LineWriter<File>>>,
+    log_path: RefCell<Option<PathBuf>>,
+    trimmed: Cell<bool>,
 }
 
 impl TaskRunnerWidgets {
@@ -42,6 +65,7 @@ impl TaskRunnerWidgets {
         sidebar_revealer: Revealer,
         output_text_view: TextView,
         output_text_buffer: TextBuffer,
+        full_log_button: Button,
     ) -> Self {
         let widgets = Self {
             window,
@@ -55,6 +79,10 @@ impl TaskRunnerWidgets {
             sidebar_revealer,
             output_text_view,
             output_text_buffer,
+            full_log_button,
+            log: RefCell::new(None),
+            log_path: RefCell::new(None),
+            trimmed: Cell::new(false),
         };
 
         // Set up color tags for output
@@ -113,11 +141,11 @@ impl TaskRunnerWidgets {
             .connect_reveal_child_notify(move |revealer| {
                 let is_revealed = revealer.reveals_child();
                 let tooltip = if is_revealed {
-                    "Hide command output"
+                    crate::i18n::gettext("Hide command output")
                 } else {
-                    "Show command output"
+                    crate::i18n::gettext("Show command output")
                 };
-                toggle.set_tooltip_text(Some(tooltip));
+                toggle.set_tooltip_text(Some(&tooltip));
 
                 // Only allow targeting when revealed so it doesn't block header bar when hidden
                 revealer_clone.set_can_target(is_revealed);
@@ -130,6 +158,8 @@ pub struct TaskItem {
     pub container: GtkBox,
     pub status_icon: Image,
     pub spinner_icon: Image,
+    /// Download progress, only shown while a download task runs.
+    pub progress_bar: ProgressBar,
 }
 
 impl TaskItem {
@@ -146,6 +176,16 @@ impl TaskItem {
         label.set_hexpand(true);
         label.set_wrap(true);
 
+        let progress_bar = ProgressBar::new();
+        progress_bar.set_show_text(true);
+        progress_bar.set_visible(false);
+
+        let text_box = GtkBox::new(gtk4::Orientation::Vertical, 6);
+        text_box.set_hexpand(true);
+        text_box.set_valign(gtk4::Align::Center);
+        text_box.append(&label);
+        text_box.append(&progress_bar);
+
         // Spinner icon for running state
         let spinner_icon = Image::new();
         spinner_icon.set_icon_name(Some("circle-noth-symbolic"));
@@ -158,7 +198,7 @@ impl TaskItem {
         status_icon.set_pixel_size(24);
         status_icon.set_visible(false);
 
-        container.append(&label);
+        container.append(&text_box);
         container.append(&spinner_icon);
         container.append(&status_icon);
 
@@ -166,6 +206,27 @@ impl TaskItem {
             container,
             status_icon,
             spinner_icon,
+            progress_bar,
+        }
+    }
+
+    /// Show download progress on this task item.
+    pub fn set_progress(&self, state: &DownloadState) {
+        self.progress_bar.set_visible(true);
+        if state.total > 0 {
+            self.progress_bar
+                .set_fraction(state.downloaded as f64 / state.total as f64);
+            self.progress_bar.set_text(Some(&format!(
+                "{} / {} ({})",
+                format_bytes(state.downloaded),
+                format_bytes(state.total),
+                format_speed(state.speed)
+            )));
+        } else {
+            // Size unknown, e.g. chunked responses
+            self.progress_bar.pulse();
+            self.progress_bar
+                .set_text(Some(&format_bytes(state.downloaded)));
         }
     }
 
@@ -238,6 +299,20 @@ impl TaskRunnerWidgets {
         }
     }
 
+    /// Show download progress for a specific task.
+    pub fn update_task_progress(&self, index: usize, state: &DownloadState) {
+        if let Some(task_item) = self.task_items.get(index) {
+            task_item.set_progress(state);
+        }
+    }
+
+    /// Hide the download progress of a specific task.
+    pub fn hide_task_progress(&self, index: usize) {
+        if let Some(task_item) = self.task_items.get(index) {
+            task_item.progress_bar.set_visible(false);
+        }
+    }
+
     /// Set the dialog title.
     pub fn set_title(&self, title: &str) {
         self.title_label.set_text(title);
@@ -274,6 +349,8 @@ impl TaskRunnerWidgets {
 
     /// Append text with a specific color tag.
     pub fn append_colored(&self, text: &str, tag_name: &str) {
+        self.write_log(text);
+
         // Get start position before insertion
         let start_offset = self.output_text_buffer.end_iter().offset();
 
@@ -289,9 +366,68 @@ impl TaskRunnerWidgets {
         if let Some(tag) = self.output_text_buffer.tag_table().lookup(tag_name) {
             self.output_text_buffer.apply_tag(&tag, &start, &end_fresh);
         }
+        self.trim_scrollback();
         self.scroll_to_bottom();
     }
 
+    /// Drop the oldest lines once the view holds too many, leaving a note
+    /// in their place.
+    fn trim_scrollback(&self) {
+        let buffer = &self.output_text_buffer;
+        let excess = buffer.line_count() - MAX_SCROLLBACK_LINES;
+        if excess < TRIM_BATCH_LINES {
+            return;
+        }
+        if !self.trimmed.replace(true) {
+            let mut start = buffer.start_iter();
+            buffer.insert_with_tags_by_name(&mut start, TRIMMED_NOTE, &["header"]);
+            self.full_log_button
+                .set_visible(self.log_path.borrow().is_some());
+        }
+        // Keep the note on the first line
+        if let (Some(mut start), Some(mut end)) =
+            (buffer.iter_at_line(1), buffer.iter_at_line(1 + excess))
+        {
+            buffer.delete(&mut start, &mut end);
+        }
+    }
+
+    /// Write the output to `file` at `path` from now on, and open it from
+    /// the full log button.
+    pub fn start_log(&self, path: PathBuf, file: File) {
+        let log = path.clone();
+        self.full_log_button.connect_clicked(move |_| {
+            info!("Opening task log {}", log.display());
+            if let Err(e) = crate::core::package::open_url(&log.to_string_lossy()) {
+                warn!("Failed to open {}: {}", log.display(), e);
+            }
+        });
+        *self.log.borrow_mut() = Some(LineWriter::new(file));
+        *self.log_path.borrow_mut() = Some(path);
+    }
+
+    /// Append `text` to the log file, if there is one.
+    fn write_log(&self, text: &str) {
+        let mut log = self.log.borrow_mut();
+        let Some(writer) = log.as_mut() else {
+            return;
+        };
+        if let Err(e) = writer.write_all(text.as_bytes()) {
+            warn!("Failed to write task log, stopping it: {}", e);
+            *log = None;
+        }
+    }
+
+    /// Flush and close the log file, returning its path.
+    pub fn finish_log(&self) -> Option<PathBuf> {
+        if let Some(mut writer) = self.log.borrow_mut().take() {
+            if let Err(e) = writer.flush() {
+                warn!("Failed to write task log: {}", e);
+            }
+        }
+        self.log_path.borrow().clone()
+    }
+
     /// Append a command header.
     pub fn append_command_header(&self, description: &str) {
         let header = format!("\n=== {} ===\n", description);
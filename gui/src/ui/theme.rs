@@ -0,0 +1,116 @@
+//! Color scheme, accent color and the toolkit stylesheet.
+//!
+//! The color scheme goes through the libadwaita style manager. The accent
+//! color and the toolkit's own CSS are separate providers on the display,
+//! so each can be swapped or removed without a restart.
+
+use crate::config;
+use crate::config::user::{self, Accent, AppearanceSettings, ColorScheme};
+use gtk4::gdk::Display;
+use gtk4::CssProvider;
+use log::{info, warn};
+use std::cell::RefCell;
+
+thread_local! {
+    /// Provider for `style.css`, once loaded.
+    static STYLESHEET: RefCell<Option<CssProvider>> = const { RefCell::new(None) };
+    /// Provider overriding the accent color, when one is chosen.
+    static ACCENT: RefCell<Option<CssProvider>> = const { RefCell::new(None) };
+}
+
+/// Apply the saved appearance settings. Called once at startup.
+pub fn setup() {
+    apply(&user::load().appearance);
+}
+
+/// Apply `change` to the appearance settings, save them and apply them.
+pub fn update_settings(change: impl FnOnce(&mut AppearanceSettings)) {
+    let mut appearance = user::load().appearance;
+    change(&mut appearance);
+    if let Err(e) = user::update(|s| s.appearance = appearance.clone()) {
+        warn!("Failed to save appearance settings: {}", e);
+    }
+    apply(&appearance);
+}
+
+/// Apply appearance settings to the default display.
+pub fn apply(appearance: &AppearanceSettings) {
+    let Some(display) = Display::default() else {
+        warn!("No default display found - UI theming may not work properly");
+        return;
+    };
+
+    adw::StyleManager::default().set_color_scheme(match appearance.color_scheme {
+        ColorScheme::System => adw::ColorScheme::Default,
+        ColorScheme::Light => adw::ColorScheme::ForceLight,
+        ColorScheme::Dark => adw::ColorScheme::ForceDark,
+    });
+
+    set_stylesheet(&display, appearance.custom_css);
+    set_accent(&display, appearance.accent);
+    info!("Applied appearance settings: {:?}", appearance);
+}
+
+/// libadwaita accent color for `accent`, `None` to follow the desktop.
+fn adw_accent(accent: Accent) -> Option<adw::AccentColor> {
+    Some(match accent {
+        Accent::System => return None,
+        Accent::Blue => adw::AccentColor::Blue,
+        Accent::Teal => adw::AccentColor::Teal,
+        Accent::Green => adw::AccentColor::Green,
+        Accent::Yellow => adw::AccentColor::Yellow,
+        Accent::Orange => adw::AccentColor::Orange,
+        Accent::Red => adw::AccentColor::Red,
+        Accent::Pink => adw::AccentColor::Pink,
+        Accent::Purple => adw::AccentColor::Purple,
+        Accent::Slate => adw::AccentColor::Slate,
+    })
+}
+
+fn set_stylesheet(display: &Display, enabled: bool) {
+    STYLESHEET.with(|stylesheet| {
+        let mut stylesheet = stylesheet.borrow_mut();
+        if !enabled {
+            if let Some(provider) = stylesheet.take() {
+                gtk4::style_context_remove_provider_for_display(display, &provider);
+            }
+        } else if stylesheet.is_none() {
+            let provider = CssProvider::new();
+            provider.load_from_resource(config::resources::CSS);
+            gtk4::style_context_add_provider_for_display(
+                display,
+                &provider,
+                gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+            *stylesheet = Some(provider);
+        }
+    });
+}
+
+fn set_accent(display: &Display, accent: Accent) {
+    ACCENT.with(|provider| {
+        if let Some(old) = provider.borrow_mut().take() {
+            gtk4::style_context_remove_provider_for_display(display, &old);
+        }
+
+        // libadwaita derives the standalone accent color from the background
+        // one, for light and dark styles alike
+        let Some(color) = adw_accent(accent) else {
+            return;
+        };
+        let css = format!(
+            ":root {{ --accent-bg-color: {}; }}",
+            color.to_rgba().to_str()
+        );
+
+        let accent_provider = CssProvider::new();
+        accent_provider.load_from_string(&css);
+        // Above the toolkit stylesheet in case it sets accent colors too
+        gtk4::style_context_add_provider_for_display(
+            display,
+            &accent_provider,
+            gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION + 1,
+        );
+        *provider.borrow_mut() = Some(accent_provider);
+    });
+}
@@ -0,0 +1,31 @@
+//! Toasts for short informational results.
+//!
+//! Use a toast for outcomes that need no decision, like "No orphaned
+//! packages found". Errors use `dialogs::error` and confirmations of
+//! destructive actions stay modal.
+
+use crate::ui::utils::extract_widget;
+use gtk4::Builder;
+use log::info;
+use std::cell::RefCell;
+
+thread_local! {
+    /// Toast overlay of the main window.
+    static OVERLAY: RefCell<Option<adw::ToastOverlay>> = const { RefCell::new(None) };
+}
+
+/// Remember the main window's toast overlay.
+pub fn setup(builder: &Builder) {
+    let overlay = extract_widget::<adw::ToastOverlay>(builder, "toast_overlay");
+    OVERLAY.with(|o| *o.borrow_mut() = Some(overlay));
+}
+
+/// Show `message` at the bottom of the main window.
+pub fn show(message: &str) {
+    info!("Toast: {}", message);
+    OVERLAY.with(|overlay| {
+        if let Some(overlay) = overlay.borrow().as_ref() {
+            overlay.add_toast(adw::Toast::new(message));
+        }
+    });
+}
@@ -0,0 +1,239 @@
+//! Background agent with a system tray icon.
+//!
+//! When `run_in_background` is set, closing the window only hides it and a
+//! StatusNotifierItem stays in the tray. The agent checks for updates every
+//! few hours and sends a notification when new ones show up. Started with
+//! `--background` (as the login autostart entry does when the setting is
+//! on), the toolkit goes straight to the tray without opening the window.
+
+use crate::config;
+use crate::core::updates::{self, Summary};
+use crate::i18n::gettext;
+use adw::prelude::*;
+use adw::Application;
+use gtk4::{gio, glib};
+use log::{info, warn};
+use std::cell::RefCell;
+
+/// Notification id, so a newer notification replaces the previous one.
+const NOTIFICATION_ID: &str = "updates";
+
+/// Requests from the tray menu, which runs on the ksni thread.
+enum TrayEvent {
+    Open,
+    CheckNow,
+    Quit,
+}
+
+struct XeroTray {
+    events: async_channel::Sender<TrayEvent>,
+    status: Option<String>,
+}
+
+impl ksni::Tray for XeroTray {
+    fn id(&self) -> String {
+        config::app_info::NAME.to_string()
+    }
+
+    fn title(&self) -> String {
+        "Xero Toolkit".to_string()
+    }
+
+    fn icon_name(&self) -> String {
+        "xero-toolkit".to_string()
+    }
+
+    fn tool_tip(&self) -> ksni::ToolTip {
+        ksni::ToolTip {
+            title: "Xero Toolkit".to_string(),
+            description: self
+                .status
+                .clone()
+                .unwrap_or_else(|| gettext("No updates found")),
+            ..Default::default()
+        }
+    }
+
+    fn activate(&mut self, _x: i32, _y: i32) {
+        let _ = self.events.try_send(TrayEvent::Open);
+    }
+
+    fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+        use ksni::menu::StandardItem;
+
+        let mut items = Vec::new();
+        if let Some(status) = &self.status {
+            items.push(
+                StandardItem {
+                    label: status.clone(),
+                    enabled: false,
+                    ..Default::default()
+                }
+                .into(),
+            );
+            items.push(ksni::MenuItem::Separator);
+        }
+        items.push(
+            StandardItem {
+                label: gettext("Open Xero Toolkit"),
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.events.try_send(TrayEvent::Open);
+                }),
+                ..Default::default()
+            }
+            .into(),
+        );
+        items.push(
+            StandardItem {
+                label: gettext("Check for Updates"),
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.events.try_send(TrayEvent::CheckNow);
+                }),
+                ..Default::default()
+            }
+            .into(),
+        );
+        items.push(
+            StandardItem {
+                label: gettext("Quit"),
+                activate: Box::new(|tray: &mut Self| {
+                    let _ = tray.events.try_send(TrayEvent::Quit);
+                }),
+                ..Default::default()
+            }
+            .into(),
+        );
+        items
+    }
+}
+
+/// A running agent.
+struct Agent {
+    handle: ksni::Handle<XeroTray>,
+    _hold: gio::ApplicationHoldGuard,
+    timer: glib::SourceId,
+    last: Summary,
+}
+
+thread_local! {
+    static AGENT: RefCell<Option<Agent>> = const { RefCell::new(None) };
+}
+
+/// Whether the tray agent is running.
+pub fn is_running() -> bool {
+    AGENT.with(|agent| agent.borrow().is_some())
+}
+
+/// Start the tray icon and the periodic update checks. Does nothing if
+/// the agent is already running.
+pub fn start(app: &Application) {
+    if is_running() {
+        return;
+    }
+    info!("Starting background agent");
+
+    let (sender, receiver) = async_channel::unbounded();
+    let service = ksni::TrayService::new(XeroTray {
+        events: sender,
+        status: None,
+    });
+    let handle = service.handle();
+    service.spawn();
+
+    let app_clone = app.clone();
+    glib::MainContext::default().spawn_local(async move {
+        while let Ok(event) = receiver.recv().await {
+            match event {
+                TrayEvent::Open => app_clone.activate(),
+                TrayEvent::CheckNow => check_updates(&app_clone),
+                TrayEvent::Quit => app_clone.quit(),
+            }
+        }
+    });
+
+    // Notifications open the window through this action
+    if app.lookup_action("show-window").is_none() {
+        let show_window = gio::ActionEntry::builder("show-window")
+            .activate(|app: &Application, _, _| app.activate())
+            .build();
+        app.add_action_entries([show_window]);
+    }
+
+    let app_clone = app.clone();
+    glib::timeout_add_seconds_local_once(config::tray::FIRST_CHECK_DELAY_SECS, move || {
+        check_updates(&app_clone)
+    });
+    let app_clone = app.clone();
+    let timer = glib::timeout_add_seconds_local(config::tray::CHECK_INTERVAL_SECS, move || {
+        check_updates(&app_clone);
+        glib::ControlFlow::Continue
+    });
+
+    AGENT.with(|agent| {
+        *agent.borrow_mut() = Some(Agent {
+            handle,
+            _hold: app.hold(),
+            timer,
+            last: Summary::default(),
+        })
+    });
+}
+
+/// Remove the tray icon and stop checking for updates.
+pub fn stop() {
+    if let Some(agent) = AGENT.with(|agent| agent.borrow_mut().take()) {
+        info!("Stopping background agent");
+        agent.timer.remove();
+        agent.handle.shutdown();
+    }
+}
+
+/// Check for updates off the main thread, then update the tray and notify
+/// if there's anything new since the last check.
+fn check_updates(app: &Application) {
+    if !is_running() {
+        return;
+    }
+    let (sender, receiver) = async_channel::bounded(1);
+    std::thread::spawn(move || {
+        let _ = sender.send_blocking(updates::check());
+    });
+
+    let app = app.clone();
+    glib::MainContext::default().spawn_local(async move {
+        let Ok(summary) = receiver.recv().await else {
+            warn!("Update check failed");
+            return;
+        };
+        let is_new = AGENT.with(|agent| {
+            let mut agent = agent.borrow_mut();
+            let Some(agent) = agent.as_mut() else {
+                return false;
+            };
+            let status = summary.message();
+            agent.handle.update(|tray| tray.status = status);
+            let is_new =
+                summary.packages > agent.last.packages || (summary.toolkit && !agent.last.toolkit);
+            agent.last = summary.clone();
+            is_new
+        });
+
+        if is_new {
+            notify(&app, &summary);
+        } else if summary.is_empty() {
+            app.withdraw_notification(NOTIFICATION_ID);
+        }
+    });
+}
+
+fn notify(app: &Application, summary: &Summary) {
+    let Some(message) = summary.message() else {
+        return;
+    };
+    info!("Notifying: {}", message);
+    let notification = gio::Notification::new(&message);
+    notification.set_body(Some(&gettext("Open Xero Toolkit to update your system.")));
+    notification.set_icon(&gio::ThemedIcon::new("xero-toolkit"));
+    notification.set_default_action("app.show-window");
+    app.send_notification(Some(NOTIFICATION_ID), &notification);
+}
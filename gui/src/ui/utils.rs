@@ -4,6 +4,7 @@ use adw::prelude::ComboRowExt;
 use gtk4::glib;
 use gtk4::prelude::*;
 use gtk4::{Builder, StringList};
+use log::warn;
 use std::process::Command;
 
 /// Helper to extract widgets from builder with consistent error handling.
@@ -13,6 +14,70 @@ pub fn extract_widget<T: IsA<glib::Object>>(builder: &Builder, name: &str) -> T
         .unwrap_or_else(|| panic!("Failed to get widget with id '{}'", name))
 }
 
+/// Like [`extract_widget`], but a missing id is logged and recorded in the
+/// diagnostics report instead of panicking. Page setup uses this so a .ui
+/// file drifting from the code costs one action, not the whole app.
+pub fn try_extract_widget<T: IsA<glib::Object>>(builder: &Builder, name: &str) -> Option<T> {
+    let widget = builder.object(name);
+    if widget.is_none() {
+        warn!("No widget with id '{}'; skipping its action", name);
+        crate::ui::diagnostics::record_missing_widget(name);
+    }
+    widget
+}
+
+/// Extract all widgets of one action. If any is missing, the rows of the
+/// ones found are disabled and `None` is returned so setup skips the action.
+pub fn extract_action_widgets<T, const N: usize>(
+    builder: &Builder,
+    names: [&str; N],
+) -> Option<[T; N]>
+where
+    T: IsA<gtk4::Widget> + IsA<glib::Object>,
+{
+    let widgets = names.map(|name| try_extract_widget::<T>(builder, name));
+    if widgets.iter().all(Option::is_some) {
+        return Some(widgets.map(Option::unwrap));
+    }
+    for widget in widgets.iter().flatten() {
+        disable_action_row(widget);
+    }
+    None
+}
+
+/// Disable the row holding `widget`, or the widget itself outside a row.
+pub fn disable_action_row(widget: &impl IsA<gtk4::Widget>) {
+    let row = widget
+        .ancestor(adw::ActionRow::static_type())
+        .unwrap_or_else(|| widget.clone().upcast());
+    row.set_sensitive(false);
+    row.set_tooltip_text(Some(&crate::i18n::gettext(
+        "Unavailable: part of this action is missing from the interface",
+    )));
+}
+
+/// Run blocking `work` on a worker thread and pass its result to `on_done`
+/// on the main loop, so a slow subprocess or network call never freezes the
+/// window.
+pub fn spawn_blocking<T, W, D>(work: W, on_done: D)
+where
+    T: Send + 'static,
+    W: FnOnce() -> T + Send + 'static,
+    D: FnOnce(T) + 'static,
+{
+    let (sender, receiver) = async_channel::bounded(1);
+    std::thread::spawn(move || {
+        let _ = sender.send_blocking(work());
+    });
+
+    glib::MainContext::default().spawn_local(async move {
+        match receiver.recv().await {
+            Ok(result) => on_done(result),
+            Err(_) => warn!("Background task ended without a result"),
+        }
+    });
+}
+
 /// Get the selected string value from an AdwComboRow.
 pub fn get_combo_row_value(combo: &adw::ComboRow) -> Option<String> {
     let model = combo.model()?;
@@ -78,9 +78,12 @@ check_aur_helper() {
     elif command -v yay &> /dev/null; then
         print_success "AUR helper found: yay"
         return
+    elif command -v pikaur &> /dev/null; then
+        print_success "AUR helper found: pikaur"
+        return
     fi
 
-    print_warning "No AUR helper (paru or yay) was found on this system."
+    print_warning "No AUR helper (paru, yay or pikaur) was found on this system."
     echo ""
     echo -e "  ${BOLD}1)${NC} Install paru  ${CYAN}(Rust-based, recommended)${NC}"
     echo -e "  ${BOLD}2)${NC} Install yay   ${CYAN}(Go-based, widely used)${NC}"
@@ -108,6 +111,7 @@ DEPS=(
     "rust"
     "cargo" 
     "pkgconf"
+    "gettext"
     "gtk4"
     "glib2"
     "libadwaita"
@@ -173,15 +177,36 @@ sudo install -Dm755 "target/release/xero-auth" "/opt/xero-toolkit/xero-auth" ||
 print_status "Installing scripts and systemd units..."
 sudo install -m755 sources/scripts/* "/opt/xero-toolkit/sources/scripts/" || die "Failed to install scripts"
 sudo install -m644 sources/systemd/* "/opt/xero-toolkit/sources/systemd/" || die "Failed to install systemd units"
+sudo install -Dm644 -t "/opt/xero-toolkit/sources/pins/" sources/pins/* || die "Failed to install pinned checksums"
+if [ -d sources/keys ]; then
+    sudo install -Dm644 -t "/opt/xero-toolkit/sources/keys/" sources/keys/*.gpg || die "Failed to install release signing key"
+fi
+
+# Compile translations
+print_status "Installing translations..."
+for po in po/*.po; do
+    [ -e "$po" ] || continue
+    lang="$(basename "$po" .po)"
+    sudo mkdir -p "/opt/xero-toolkit/locale/$lang/LC_MESSAGES"
+    sudo msgfmt -o "/opt/xero-toolkit/locale/$lang/LC_MESSAGES/xero-toolkit.mo" "$po" || print_warning "Failed to compile $po"
+done
 
 # Create symlink in /usr/bin
 print_status "Creating symlink..."
 sudo ln -sf "/opt/xero-toolkit/xero-toolkit" "/usr/bin/xero-toolkit" || die "Failed to create symlink"
+sudo ln -sf "/opt/xero-toolkit/xero-toolkit" "/usr/bin/xero-toolkit-cli" || die "Failed to create CLI symlink"
 
 # Install desktop file
 print_status "Installing desktop file..."
 sudo install -Dm644 "packaging/xero-toolkit.desktop" \
     "/usr/share/applications/xero-toolkit.desktop" || die "Failed to install desktop file"
+# Register the xero-toolkit:// link handler
+sudo update-desktop-database -q /usr/share/applications 2>/dev/null || true
+
+# Install D-Bus service file (starts the toolkit when its bus name is called)
+print_status "Installing D-Bus service..."
+sudo install -Dm644 "packaging/xyz.xerolinux.Toolkit.service" \
+    "/usr/share/dbus-1/services/xyz.xerolinux.Toolkit.service" || die "Failed to install D-Bus service"
 
 # Install icon
 print_status "Installing icon..."
@@ -0,0 +1,25 @@
+#!/bin/bash
+#
+# Refresh the sha256 pins in sources/pins/artifacts.pins from the
+# current upstream files. Review the diff before committing: a changed
+# hash means upstream changed the file.
+#
+
+set -euo pipefail
+
+PINS="$(cd "$(dirname "${BASH_SOURCE[0]}")/.." && pwd)/sources/pins/artifacts.pins"
+TMP="$(mktemp)"
+trap 'rm -f "$TMP" "$TMP.new"' EXIT
+
+while IFS= read -r line; do
+    read -r name method url _ <<< "$line" || true
+    if [[ "$method" == "sha256" && "$url" == https://* ]]; then
+        hash="$(curl -fsSL --retry 3 "$url" | sha256sum | cut -d' ' -f1)"
+        echo "$name: $hash" >&2
+        printf '%-19s %-7s %s  %s\n' "$name" "$method" "$url" "$hash"
+    else
+        printf '%s\n' "$line"
+    fi
+done < "$PINS" > "$TMP.new"
+
+mv "$TMP.new" "$PINS"
@@ -0,0 +1,29 @@
+#!/usr/bin/env bash
+# Regenerate xero-toolkit.pot from the sources listed in POTFILES.in and
+# merge new strings into the existing translations.
+set -euo pipefail
+
+cd "$(dirname "$0")"
+
+DOMAIN="xero-toolkit"
+UI_FILES=$(grep '\.ui$' POTFILES.in | sed 's|^|../|')
+RS_FILES=$(grep '\.rs$' POTFILES.in | sed 's|^|../|')
+
+# shellcheck disable=SC2086
+xgettext --from-code=UTF-8 --package-name="$DOMAIN" \
+    -o "$DOMAIN.pot" $UI_FILES
+
+# Rust has no xgettext parser; its string literals are close enough to C's.
+# Step descriptions and task_runner::run titles are translated where they're
+# shown, so they're picked up by position.
+# shellcheck disable=SC2086
+xgettext --from-code=UTF-8 --package-name="$DOMAIN" -L C -j \
+    --keyword=gettext --keyword=gettext_f --keyword=n_ \
+    --keyword=ngettext:1,2 --keyword=ngettext_f:1,2 \
+    --keyword=description --keyword=run:3 \
+    -o "$DOMAIN.pot" $RS_FILES
+
+for po in *.po; do
+    [ -e "$po" ] || continue
+    msgmerge --update --backup=none "$po" "$DOMAIN.pot"
+done
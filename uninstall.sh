@@ -23,11 +23,16 @@ sudo rm -rf /opt/xero-toolkit
 
 # Remove symlink
 print_status "Removing symlink..."
-sudo rm -f /usr/bin/xero-toolkit
+sudo rm -f /usr/bin/xero-toolkit /usr/bin/xero-toolkit-cli
 
 # Remove desktop file
 print_status "Removing desktop file..."
 sudo rm -f /usr/share/applications/xero-toolkit.desktop
+sudo update-desktop-database -q /usr/share/applications 2>/dev/null || true
+
+# Remove D-Bus service file
+print_status "Removing D-Bus service..."
+sudo rm -f /usr/share/dbus-1/services/xyz.xerolinux.Toolkit.service
 
 # Remove icon
 print_status "Removing icon..."
@@ -4,8 +4,19 @@ use crate::protocol::{ClientMessage, DaemonMessage};
 use crate::protocol_io::{read_message, write_message};
 use crate::shared::get_socket_path;
 use anyhow::{Context, Result};
+use std::path::Path;
+use std::time::Duration;
 use tokio::net::UnixStream;
 
+/// State of the daemon's root session.
+#[derive(Debug, Clone, Copy)]
+pub struct SessionStatus {
+    /// Commands run since the daemon started.
+    pub commands_run: u32,
+    /// Time left before the daemon exits if no command runs.
+    pub remaining: Duration,
+}
+
 /// Client for communicating with the xero-auth daemon.
 pub struct Client {
     stream: UnixStream,
@@ -14,10 +25,13 @@ pub struct Client {
 impl Client {
     /// Connect to the daemon.
     pub async fn new() -> Result<Self> {
-        let socket_path = get_socket_path(None)?;
+        Self::connect(&get_socket_path(None)?).await
+    }
 
-        use tokio::time::{timeout, Duration};
-        let stream = timeout(Duration::from_secs(5), UnixStream::connect(&socket_path))
+    /// Connect to a daemon listening on `socket_path`.
+    pub async fn connect(socket_path: &Path) -> Result<Self> {
+        use tokio::time::timeout;
+        let stream = timeout(Duration::from_secs(5), UnixStream::connect(socket_path))
             .await
             .context("Connection timeout")?
             .context("Failed to connect to daemon")?;
@@ -85,6 +99,9 @@ impl Client {
                 DaemonMessage::ErrorMessage(msg) => {
                     anyhow::bail!("Daemon error: {}", msg);
                 }
+                DaemonMessage::Rejected(rejection) => {
+                    anyhow::bail!("Daemon rejected the command: {}", rejection);
+                }
                 _ => {}
             }
         }
@@ -92,6 +109,24 @@ impl Client {
         Ok(exit_code.unwrap_or(-1))
     }
 
+    /// Ask the daemon about its session.
+    pub async fn status(&mut self) -> Result<SessionStatus> {
+        let (mut reader, mut writer) = self.stream.split();
+        write_message(&mut writer, &ClientMessage::Status).await?;
+
+        match read_message::<_, DaemonMessage>(&mut reader).await? {
+            Some(DaemonMessage::Status {
+                commands_run,
+                remaining_secs,
+            }) => Ok(SessionStatus {
+                commands_run,
+                remaining: Duration::from_secs(remaining_secs),
+            }),
+            Some(msg) => anyhow::bail!("Unexpected response to status: {:?}", msg),
+            None => anyhow::bail!("Connection closed before status"),
+        }
+    }
+
     /// Send a shutdown request to the daemon.
     pub async fn shutdown(&mut self) -> Result<()> {
         let (mut reader, mut writer) = self.stream.split();
@@ -1,8 +1,8 @@
 //! Daemon implementation that runs as root and executes commands.
 
 use crate::protocol::{ClientMessage, DaemonMessage};
-use crate::protocol_io::{read_message, write_message};
-use crate::shared::{get_socket_path, is_process_running};
+use crate::protocol_io::{read_message, write_message, FrameError};
+use crate::shared::{get_socket_path, is_process_running, IDLE_TIMEOUT};
 use crate::utils::read_buffer_with_line_processing;
 use anyhow::{Context, Result};
 use log::{error, info, warn};
@@ -10,11 +10,54 @@ use pty::fork::Fork;
 use std::ffi::CString;
 use std::os::unix::fs::PermissionsExt;
 use std::os::unix::process::CommandExt;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::net::{UnixListener, UnixStream};
 use tokio::sync::Mutex;
 
+/// How long output is gathered before it's sent to the client.
+const OUTPUT_FLUSH_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Most output sent in one message, in bytes, well under the frame limit.
+const MAX_OUTPUT_BATCH: usize = 64 * 1024;
+
+/// Activity of the root session, for the idle timeout and status requests.
+struct Session {
+    commands_run: AtomicU32,
+    running: AtomicU32,
+    last_active: std::sync::Mutex<Instant>,
+}
+
+impl Session {
+    fn new() -> Self {
+        Self {
+            commands_run: AtomicU32::new(0),
+            running: AtomicU32::new(0),
+            last_active: std::sync::Mutex::new(Instant::now()),
+        }
+    }
+
+    fn command_started(&self) {
+        self.commands_run.fetch_add(1, Ordering::SeqCst);
+        self.running.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn command_finished(&self) {
+        self.running.fetch_sub(1, Ordering::SeqCst);
+        *self.last_active.lock().unwrap() = Instant::now();
+    }
+
+    /// Time left before the session ends. The timeout only counts down
+    /// while no command is running.
+    fn remaining(&self) -> Duration {
+        if self.running.load(Ordering::SeqCst) > 0 {
+            return IDLE_TIMEOUT;
+        }
+        IDLE_TIMEOUT.saturating_sub(self.last_active.lock().unwrap().elapsed())
+    }
+}
+
 /// Run the authentication daemon.
 ///
 /// # Arguments
@@ -23,6 +66,8 @@ use tokio::sync::Mutex;
 ///   If provided, the socket will be created in that user's runtime directory.
 /// * `parent_pid` - Optional parent process ID to monitor. If provided, the daemon will
 ///   shut down if the parent process is no longer running.
+///
+/// The daemon also shuts down after [`IDLE_TIMEOUT`] without running a command.
 pub async fn run_daemon(effective_uid: Option<u32>, parent_pid: Option<u32>) -> Result<()> {
     let uid = unsafe { libc::getuid() };
     if uid != 0 {
@@ -51,6 +96,7 @@ pub async fn run_daemon(effective_uid: Option<u32>, parent_pid: Option<u32>) ->
     }
 
     let shutdown = Arc::new(AtomicBool::new(false));
+    let session = Arc::new(Session::new());
 
     if let Some(pid) = parent_pid {
         spawn_parent_monitor(shutdown.clone(), pid);
@@ -72,9 +118,10 @@ pub async fn run_daemon(effective_uid: Option<u32>, parent_pid: Option<u32>) ->
                     Ok((stream, _addr)) => {
                         info!("New client connection");
                         let shutdown_clone = shutdown.clone();
+                        let session_clone = session.clone();
                         let parent_pid_clone = parent_pid;
                         tokio::spawn(async move {
-                            if let Err(e) = handle_client(stream, shutdown_clone, parent_pid_clone).await {
+                            if let Err(e) = handle_client(stream, shutdown_clone, session_clone, parent_pid_clone).await {
                                 error!("Error handling client: {}", e);
                             }
                         });
@@ -91,6 +138,10 @@ pub async fn run_daemon(effective_uid: Option<u32>, parent_pid: Option<u32>) ->
                 if shutdown.load(Ordering::SeqCst) {
                     break;
                 }
+                if session.remaining().is_zero() {
+                    info!("Idle for {:?}, shutting down", IDLE_TIMEOUT);
+                    break;
+                }
                 continue;
             }
             _ = tokio::signal::ctrl_c() => {
@@ -184,6 +235,7 @@ fn spawn_signal_handler(shutdown: Arc<AtomicBool>) {
 async fn handle_client(
     mut stream: UnixStream,
     shutdown: Arc<AtomicBool>,
+    session: Arc<Session>,
     parent_pid: Option<u32>,
 ) -> Result<()> {
     let (mut reader, writer) = stream.split();
@@ -211,16 +263,44 @@ async fn handle_client(
             }
         }
 
-        let message = match read_message(&mut reader).await? {
-            Some(msg) => msg,
-            None => break, // EOF
+        let message = match read_message::<_, ClientMessage>(&mut reader).await {
+            Ok(Some(msg)) => msg,
+            Ok(None) => break, // EOF
+            Err(e) => {
+                let Some(frame_error) = e.downcast_ref::<FrameError>().copied() else {
+                    return Err(e);
+                };
+                warn!("Rejecting client message: {}", frame_error);
+                let mut w = writer_arc.lock().await;
+                write_message(&mut *w, &DaemonMessage::Rejected(frame_error.rejection())).await?;
+                // The rest of an oversized frame is still unread
+                if let FrameError::TooLarge(_) = frame_error {
+                    break;
+                }
+                continue;
+            }
         };
 
+        if let Err(rejection) = message.check_limits() {
+            warn!("Rejecting client message: {}", rejection);
+            let mut w = writer_arc.lock().await;
+            write_message(&mut *w, &DaemonMessage::Rejected(rejection)).await?;
+            continue;
+        }
+
         match message {
             ClientMessage::Ping => {
                 let mut w = writer_arc.lock().await;
                 write_message(&mut *w, &DaemonMessage::Pong).await?;
             }
+            ClientMessage::Status => {
+                let status = DaemonMessage::Status {
+                    commands_run: session.commands_run.load(Ordering::SeqCst),
+                    remaining_secs: session.remaining().as_secs(),
+                };
+                let mut w = writer_arc.lock().await;
+                write_message(&mut *w, &status).await?;
+            }
             ClientMessage::Shutdown => {
                 info!("Received shutdown request from client");
                 let mut w = writer_arc.lock().await;
@@ -234,7 +314,10 @@ async fn handle_client(
                 env,
                 working_dir,
             } => {
-                execute_command(&writer_arc, program, args, env, working_dir).await?;
+                session.command_started();
+                let result = execute_command(&writer_arc, program, args, env, working_dir).await;
+                session.command_finished();
+                result?;
             }
         }
     }
@@ -310,27 +393,40 @@ async fn read_pty_output(
 
     let writer_output = writer.clone();
     let output_task = async move {
-        while let Some(result) = rx.recv().await {
-            match result {
-                Ok(line) => {
-                    let msg = DaemonMessage::Output(line);
-                    let mut w = writer_output.lock().await;
-                    let _ = write_message(&mut *w, &msg).await;
-                }
-                Err(e) => {
-                    if e.kind() != std::io::ErrorKind::UnexpectedEof {
-                        warn!("Error reading from PTY: {}", e);
-                    }
-                    break;
+        let mut batch = String::new();
+        let mut read_error = None;
+        while read_error.is_none() {
+            // Wait for output, then gather what else arrives within the
+            // flush interval into one message
+            match rx.recv().await {
+                Some(Ok(text)) => batch.push_str(&text),
+                Some(Err(e)) => read_error = Some(e),
+                None => break,
+            }
+            let deadline = tokio::time::Instant::now() + OUTPUT_FLUSH_INTERVAL;
+            while read_error.is_none() && batch.len() < MAX_OUTPUT_BATCH {
+                match tokio::time::timeout_at(deadline, rx.recv()).await {
+                    Ok(Some(Ok(text))) => batch.push_str(&text),
+                    Ok(Some(Err(e))) => read_error = Some(e),
+                    Ok(None) | Err(_) => break,
                 }
             }
+
+            if !batch.is_empty() {
+                let msg = DaemonMessage::Output(std::mem::take(&mut batch));
+                let mut w = writer_output.lock().await;
+                let _ = write_message(&mut *w, &msg).await;
+            }
+        }
+        if let Some(e) = read_error {
+            if e.kind() != std::io::ErrorKind::UnexpectedEof {
+                warn!("Error reading from PTY: {}", e);
+            }
         }
     };
 
-    tokio::select! {
-        _ = read_handle => {},
-        _ = output_task => {},
-    }
+    // The output task ends once the reader is done and the last batch is sent
+    let _ = tokio::join!(read_handle, output_task);
 
     let exit_code = tokio::task::spawn_blocking(move || {
         let mut status: libc::c_int = 0;
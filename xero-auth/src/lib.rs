@@ -8,8 +8,10 @@ pub mod daemon;
 pub mod protocol;
 pub mod protocol_io;
 pub mod shared;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 pub mod utils;
 
-pub use client::Client;
+pub use client::{Client, SessionStatus};
 pub use daemon::run_daemon;
 pub use shared::{get_socket_path, is_daemon_running, wait_for_socket};
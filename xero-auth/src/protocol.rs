@@ -1,9 +1,24 @@
 //! Protocol definitions for communication between client and daemon.
+//!
+//! Messages are limited in size so a broken or hostile client can't make
+//! the root daemon allocate without bound. Anything over a limit, or not
+//! decoding as a message, gets a [`DaemonMessage::Rejected`] answer.
 
 use rkyv::{Archive, Deserialize, Serialize};
+use std::fmt;
+
+/// Longest frame either side accepts, in bytes.
+pub const MAX_FRAME_LEN: u64 = 4 * 1024 * 1024;
+
+/// Most arguments, and most environment entries, an `Execute` may carry.
+pub const MAX_ARGS: usize = 4096;
+
+/// Longest program, argument, environment entry or working directory, in
+/// bytes. The same as Linux's limit for a single argument.
+pub const MAX_ARG_LEN: usize = 128 * 1024;
 
 /// Message sent from client to daemon.
-#[derive(Debug, Archive, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
 pub enum ClientMessage {
     /// Execute a command with arguments.
     Execute {
@@ -14,12 +29,14 @@ pub enum ClientMessage {
     },
     /// Ping to check if daemon is alive.
     Ping,
+    /// Ask how many commands ran and how long the session stays open.
+    Status,
     /// Shutdown the daemon.
     Shutdown,
 }
 
 /// Message sent from daemon to client.
-#[derive(Debug, Archive, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
 pub enum DaemonMessage {
     /// Command output (stdout line).
     Output(String),
@@ -31,6 +48,123 @@ pub enum DaemonMessage {
     ErrorMessage(String),
     /// Pong response to ping.
     Pong,
+    /// Session status.
+    Status {
+        /// Commands run since the daemon started.
+        commands_run: u32,
+        /// Seconds left before the daemon exits if no command runs.
+        remaining_secs: u64,
+    },
     /// Shutdown acknowledged.
     ShutdownAck,
+    /// The request was refused and not run.
+    Rejected(Rejection),
+}
+
+/// Why the daemon refused a request.
+#[derive(Debug, Clone, PartialEq, Archive, Serialize, Deserialize)]
+pub enum Rejection {
+    /// The frame is longer than [`MAX_FRAME_LEN`]. The connection is closed,
+    /// as the next frame can't be found.
+    FrameTooLarge { len: u64 },
+    /// The frame isn't a valid message, e.g. a string isn't UTF-8.
+    Malformed,
+    /// More than [`MAX_ARGS`] arguments or environment entries.
+    TooManyArgs { count: u64 },
+    /// A string longer than [`MAX_ARG_LEN`].
+    ArgTooLong { len: u64 },
+    /// No program to run.
+    EmptyProgram,
+}
+
+impl fmt::Display for Rejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rejection::FrameTooLarge { len } => {
+                write!(f, "message of {} bytes is over {}", len, MAX_FRAME_LEN)
+            }
+            Rejection::Malformed => write!(f, "malformed message"),
+            Rejection::TooManyArgs { count } => {
+                write!(f, "{} arguments are over {}", count, MAX_ARGS)
+            }
+            Rejection::ArgTooLong { len } => {
+                write!(f, "argument of {} bytes is over {}", len, MAX_ARG_LEN)
+            }
+            Rejection::EmptyProgram => write!(f, "no program given"),
+        }
+    }
+}
+
+impl ClientMessage {
+    /// Check an `Execute` against the argument limits. Other messages
+    /// carry no data and always pass.
+    pub fn check_limits(&self) -> Result<(), Rejection> {
+        let ClientMessage::Execute {
+            program,
+            args,
+            env,
+            working_dir,
+        } = self
+        else {
+            return Ok(());
+        };
+
+        if program.is_empty() {
+            return Err(Rejection::EmptyProgram);
+        }
+        for list in [args, env] {
+            if list.len() > MAX_ARGS {
+                return Err(Rejection::TooManyArgs {
+                    count: list.len() as u64,
+                });
+            }
+        }
+        let strings = std::iter::once(program)
+            .chain(args)
+            .chain(env)
+            .chain(working_dir);
+        match strings.map(String::len).find(|len| *len > MAX_ARG_LEN) {
+            Some(len) => Err(Rejection::ArgTooLong { len: len as u64 }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn execute(program: &str, args: Vec<String>) -> ClientMessage {
+        ClientMessage::Execute {
+            program: program.to_string(),
+            args,
+            env: Vec::new(),
+            working_dir: None,
+        }
+    }
+
+    #[test]
+    fn limits_execute_arguments() {
+        assert_eq!(
+            execute("pacman", vec!["-Syu".into()]).check_limits(),
+            Ok(())
+        );
+        assert_eq!(
+            execute("", Vec::new()).check_limits(),
+            Err(Rejection::EmptyProgram)
+        );
+        assert_eq!(
+            execute("echo", vec![String::new(); MAX_ARGS + 1]).check_limits(),
+            Err(Rejection::TooManyArgs {
+                count: MAX_ARGS as u64 + 1
+            })
+        );
+        assert_eq!(
+            execute("echo", vec!["x".repeat(MAX_ARG_LEN + 1)]).check_limits(),
+            Err(Rejection::ArgTooLong {
+                len: MAX_ARG_LEN as u64 + 1
+            })
+        );
+        assert_eq!(ClientMessage::Ping.check_limits(), Ok(()));
+    }
 }
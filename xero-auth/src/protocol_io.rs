@@ -1,12 +1,43 @@
 //! I/O utilities for protocol message serialization/deserialization.
 
+use crate::protocol::{Rejection, MAX_FRAME_LEN};
 use anyhow::{Context, Result};
-use rkyv::api::high;
+use rkyv::api::high::{self, HighValidator};
+use rkyv::bytecheck::CheckBytes;
 use rkyv::rancor::Error;
 use rkyv::ser::allocator::ArenaHandle;
 use rkyv::util::AlignedVec;
+use std::fmt;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
+/// A frame [`read_message`] refused. Other errors are I/O errors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// The length prefix is over [`MAX_FRAME_LEN`]. The body is left
+    /// unread, so the stream can't be read any further.
+    TooLarge(u64),
+    /// The body was read but isn't a valid message.
+    Malformed,
+}
+
+impl FrameError {
+    /// The answer telling the client why its message was refused.
+    pub fn rejection(self) -> Rejection {
+        match self {
+            FrameError::TooLarge(len) => Rejection::FrameTooLarge { len },
+            FrameError::Malformed => Rejection::Malformed,
+        }
+    }
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rejection())
+    }
+}
+
+impl std::error::Error for FrameError {}
+
 /// Write a rkyv-serialized message to a writer.
 ///
 /// The format is: [8-byte length (u64, little-endian)][message bytes]
@@ -17,6 +48,9 @@ where
 {
     let bytes = high::to_bytes(message).context("Failed to serialize message")?;
     let len = bytes.len() as u64;
+    if len > MAX_FRAME_LEN {
+        anyhow::bail!(FrameError::TooLarge(len));
+    }
     let len_bytes = len.to_le_bytes();
     writer.write_all(&len_bytes).await?;
     writer.write_all(&bytes).await?;
@@ -25,13 +59,15 @@ where
 
 /// Read an rkyv-serialized message from a reader.
 ///
-/// Returns `None` on EOF, `Some(message)` on success.
-/// Uses unchecked deserialization since we control the data source.
+/// Returns `None` on EOF, `Some(message)` on success. Frames over
+/// [`MAX_FRAME_LEN`] and bytes that don't validate as a message, including
+/// strings that aren't UTF-8, fail with a [`FrameError`].
 pub async fn read_message<R, M>(reader: &mut R) -> Result<Option<M>>
 where
     R: AsyncReadExt + Unpin,
     M: rkyv::Archive,
-    M::Archived: rkyv::Deserialize<M, high::HighDeserializer<Error>>,
+    M::Archived: for<'a> CheckBytes<HighValidator<'a, Error>>
+        + rkyv::Deserialize<M, high::HighDeserializer<Error>>,
 {
     // Read length prefix (u64, little-endian)
     let mut len_bytes = [0u8; 8];
@@ -42,14 +78,49 @@ where
         }
         Err(e) => return Err(e.into()),
     }
-    let len = u64::from_le_bytes(len_bytes) as usize;
+    let len = u64::from_le_bytes(len_bytes);
+    if len > MAX_FRAME_LEN {
+        return Err(FrameError::TooLarge(len).into());
+    }
+
+    // Read the message bytes, aligned for validation
+    let mut buffer = AlignedVec::<16>::with_capacity(len as usize);
+    buffer.resize(len as usize, 0);
+    reader.read_exact(&mut buffer[..]).await?;
+
+    match high::from_bytes::<M, Error>(&buffer[..]) {
+        Ok(message) => Ok(Some(message)),
+        Err(_) => Err(FrameError::Malformed.into()),
+    }
+}
 
-    // Read the message bytes
-    let mut buffer = vec![0u8; len];
-    reader.read_exact(&mut buffer).await?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ClientMessage;
 
-    // Deserialize using unchecked high-level API (we control the data source)
-    let message: M = unsafe { high::from_bytes_unchecked(&buffer[..]) }
-        .context("Failed to deserialize message")?;
-    Ok(Some(message))
+    async fn read_frame(frame: &[u8]) -> Result<Option<ClientMessage>> {
+        let mut reader = frame;
+        read_message(&mut reader).await
+    }
+
+    #[tokio::test]
+    async fn refuses_oversized_and_garbage_frames() {
+        let huge = u64::MAX.to_le_bytes();
+        let error = read_frame(&huge).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<FrameError>(),
+            Some(&FrameError::TooLarge(u64::MAX))
+        );
+
+        let mut garbage = 8u64.to_le_bytes().to_vec();
+        garbage.extend([0xff; 8]);
+        let error = read_frame(&garbage).await.unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<FrameError>(),
+            Some(&FrameError::Malformed)
+        );
+
+        assert!(read_frame(&[]).await.unwrap().is_none());
+    }
 }
@@ -4,6 +4,9 @@ use anyhow::Result;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+/// How long the daemon keeps root without running a command.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
 /// Get the socket path for the daemon.
 ///
 /// # Arguments
@@ -0,0 +1,137 @@
+//! In-process mock daemon for tests, behind the `test-support` feature.
+//!
+//! [`MockDaemon`] listens on a socket in the temp directory and answers each
+//! client message through a responder, without root or a PTY. It records
+//! what it received, so tests can check the messages a client sent.
+
+use crate::client::Client;
+use crate::protocol::{ClientMessage, DaemonMessage};
+use crate::protocol_io::{read_message, write_message};
+use crate::shared::IDLE_TIMEOUT;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::task::JoinHandle;
+
+/// Answers to one client message, sent in order.
+pub type Responder = dyn Fn(&ClientMessage) -> Vec<DaemonMessage> + Send + Sync;
+
+/// A daemon answering on a temporary socket, stopped when dropped.
+pub struct MockDaemon {
+    socket_path: PathBuf,
+    received: Arc<Mutex<Vec<ClientMessage>>>,
+    task: JoinHandle<()>,
+}
+
+/// Answers the real daemon gives: `Execute` prints the program name and
+/// exits with 0, unless it's over a limit, and `Status` reports a fresh
+/// session.
+pub fn standard_responses(message: &ClientMessage) -> Vec<DaemonMessage> {
+    if let Err(rejection) = message.check_limits() {
+        return vec![DaemonMessage::Rejected(rejection)];
+    }
+    match message {
+        ClientMessage::Execute { program, .. } => vec![
+            DaemonMessage::Output(format!("{}\n", program)),
+            DaemonMessage::Completed { exit_code: 0 },
+        ],
+        ClientMessage::Ping => vec![DaemonMessage::Pong],
+        ClientMessage::Status => vec![DaemonMessage::Status {
+            commands_run: 0,
+            remaining_secs: IDLE_TIMEOUT.as_secs(),
+        }],
+        ClientMessage::Shutdown => vec![DaemonMessage::ShutdownAck],
+    }
+}
+
+impl MockDaemon {
+    /// Start a daemon giving [`standard_responses`].
+    pub async fn start() -> Result<Self> {
+        Self::with_responder(standard_responses).await
+    }
+
+    /// Start a daemon answering each message with `respond`.
+    pub async fn with_responder<F>(respond: F) -> Result<Self>
+    where
+        F: Fn(&ClientMessage) -> Vec<DaemonMessage> + Send + Sync + 'static,
+    {
+        static COUNT: AtomicU32 = AtomicU32::new(0);
+        let socket_path = std::env::temp_dir().join(format!(
+            "xero-authd-test-{}-{}.sock",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = UnixListener::bind(&socket_path).context("Failed to bind mock socket")?;
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let respond: Arc<Responder> = Arc::new(respond);
+        let received_clone = received.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _addr)) = listener.accept().await {
+                tokio::spawn(serve(stream, respond.clone(), received_clone.clone()));
+            }
+        });
+
+        Ok(Self {
+            socket_path,
+            received,
+            task,
+        })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    /// A client connected to this daemon.
+    pub async fn client(&self) -> Result<Client> {
+        Client::connect(&self.socket_path).await
+    }
+
+    /// Messages received so far, from all connections, in order.
+    pub fn received(&self) -> Vec<ClientMessage> {
+        self.received.lock().unwrap().clone()
+    }
+
+    /// Panic unless the messages received so far are `expected`.
+    pub fn assert_received(&self, expected: &[ClientMessage]) {
+        assert_eq!(self.received(), expected, "unexpected client messages");
+    }
+}
+
+impl Drop for MockDaemon {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_file(&self.socket_path);
+    }
+}
+
+async fn serve(
+    mut stream: UnixStream,
+    respond: Arc<Responder>,
+    received: Arc<Mutex<Vec<ClientMessage>>>,
+) {
+    let (mut reader, mut writer) = stream.split();
+    while let Ok(Some(message)) = read_message::<_, ClientMessage>(&mut reader).await {
+        let responses = respond(&message);
+        received.lock().unwrap().push(message);
+        for response in responses {
+            if write_message(&mut writer, &response).await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// Build an `Execute` message the way [`Client::execute`] sends it.
+pub fn execute(program: &str, args: &[&str]) -> ClientMessage {
+    ClientMessage::Execute {
+        program: program.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+        env: Vec::new(),
+        working_dir: None,
+    }
+}
@@ -0,0 +1,95 @@
+//! Client and framing tests against the mock daemon.
+
+use std::sync::{Arc, Mutex};
+use xero_auth::protocol::{ClientMessage, DaemonMessage, MAX_ARGS};
+use xero_auth::protocol_io::{read_message, write_message};
+use xero_auth::test_support::{execute, standard_responses, MockDaemon};
+
+#[tokio::test]
+async fn frames_round_trip() {
+    let (mut client, mut server) = tokio::io::duplex(1024);
+    let sent = execute("pacman", &["-Syu", "--noconfirm"]);
+    write_message(&mut client, &sent).await.unwrap();
+    drop(client);
+
+    let received: Option<ClientMessage> = read_message(&mut server).await.unwrap();
+    assert_eq!(received, Some(sent));
+    let eof: Option<ClientMessage> = read_message(&mut server).await.unwrap();
+    assert_eq!(eof, None);
+}
+
+#[tokio::test]
+async fn execute_streams_output_and_exit_code() {
+    let daemon = MockDaemon::with_responder(|message| match message {
+        ClientMessage::Execute { .. } => vec![
+            DaemonMessage::Output("building\n".to_string()),
+            DaemonMessage::Error("warning: no space\n".to_string()),
+            DaemonMessage::Completed { exit_code: 3 },
+        ],
+        other => standard_responses(other),
+    })
+    .await
+    .unwrap();
+
+    let output = Arc::new(Mutex::new(String::new()));
+    let errors = Arc::new(Mutex::new(String::new()));
+    let mut client = daemon.client().await.unwrap();
+    let exit_code = client
+        .execute(
+            "makepkg",
+            &["-si".to_string()],
+            Vec::new(),
+            None,
+            |text| output.lock().unwrap().push_str(text),
+            |text| errors.lock().unwrap().push_str(text),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(exit_code, 3);
+    assert_eq!(*output.lock().unwrap(), "building\n");
+    assert_eq!(*errors.lock().unwrap(), "warning: no space\n");
+    daemon.assert_received(&[execute("makepkg", &["-si"])]);
+}
+
+#[tokio::test]
+async fn daemon_errors_fail_execute() {
+    let daemon = MockDaemon::with_responder(|_| {
+        vec![DaemonMessage::ErrorMessage(
+            "Parent process is no longer running".to_string(),
+        )]
+    })
+    .await
+    .unwrap();
+
+    let mut client = daemon.client().await.unwrap();
+    let result = client
+        .execute("true", &[], Vec::new(), None, |_| {}, |_| {})
+        .await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn status_and_shutdown() {
+    let daemon = MockDaemon::start().await.unwrap();
+
+    let status = daemon.client().await.unwrap().status().await.unwrap();
+    assert_eq!(status.commands_run, 0);
+    assert_eq!(status.remaining, xero_auth::shared::IDLE_TIMEOUT);
+
+    daemon.client().await.unwrap().shutdown().await.unwrap();
+    daemon.assert_received(&[ClientMessage::Status, ClientMessage::Shutdown]);
+}
+
+#[tokio::test]
+async fn commands_over_the_limits_are_rejected() {
+    let daemon = MockDaemon::start().await.unwrap();
+
+    let args = vec![String::new(); MAX_ARGS + 1];
+    let mut client = daemon.client().await.unwrap();
+    let error = client
+        .execute("echo", &args, Vec::new(), None, |_| {}, |_| {})
+        .await
+        .unwrap_err();
+    assert!(error.to_string().contains("rejected"));
+}
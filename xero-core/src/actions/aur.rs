@@ -0,0 +1,52 @@
+//! Bootstrap of an AUR helper on systems without one.
+
+use crate::i18n::n_;
+use crate::task_runner::{Command, CommandSequence};
+
+/// Title of the paru install flow in the task runner.
+pub const INSTALL_PARU_TITLE: &str = n_("Install paru");
+
+const PARU_REPO: &str = "https://aur.archlinux.org/paru-bin.git";
+const BUILD_DIR: &str = "/tmp/xero-toolkit-paru-bin";
+
+/// Build and install paru from the AUR (`paru-bin`, so nothing has to be
+/// compiled). makepkg's own sudo calls go through the sudo shim.
+pub fn install_paru() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--needed", "--noconfirm", "base-devel", "git"])
+                .description("Installing build tools...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("rm")
+                .args(&["-rf", BUILD_DIR])
+                .description("Removing previous build files...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("git")
+                .args(&["clone", "--depth", "1", PARU_REPO, BUILD_DIR])
+                .description("Downloading paru build files...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("bash")
+                .args(&[
+                    "-c",
+                    &format!("cd {} && makepkg -si --noconfirm", BUILD_DIR),
+                ])
+                .description("Building and installing paru...")
+                .build(),
+        )
+        .build()
+}
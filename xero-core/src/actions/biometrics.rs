@@ -0,0 +1,176 @@
+//! Biometric login tool actions.
+
+use super::{Action, Requirement};
+use crate::i18n::n_;
+use crate::task_runner::{Command, CommandSequence};
+use log::info;
+
+/// Where the fingerprint GUI installs its launcher.
+pub const FINGERPRINT_BINARY: &str = "/usr/bin/xfprintd-gui";
+
+/// Where the Howdy GUI is installed.
+pub const HOWDY_BINARY: &str = "/usr/bin/xero-howdy-qt";
+
+pub static FINGERPRINT: Action = Action {
+    category: "install",
+    id: "fingerprint",
+    title: n_("Install XFPrintD GUI (Jailbroken Edition)"),
+    description: n_("Build the fingerprint enrollment GUI from source"),
+    requires: &[Requirement::AurHelper],
+    commands: fingerprint,
+    installed: Some(|| std::path::Path::new(FINGERPRINT_BINARY).exists()),
+    destructive: false,
+    page: "biometrics",
+    widget: "btn_fingerprint_setup",
+    keywords: &["fingerprint", "fprintd", "xfprintd", "biometrics"],
+};
+
+pub static HOWDY: Action = Action {
+    category: "install",
+    id: "howdy",
+    title: n_("Install Howdy Qt (Build from Source)"),
+    description: n_("Install Howdy facial recognition and build its GUI"),
+    requires: &[Requirement::AurHelper],
+    commands: howdy,
+    installed: Some(|| std::path::Path::new(HOWDY_BINARY).exists()),
+    destructive: false,
+    page: "biometrics",
+    widget: "btn_howdy_setup",
+    keywords: &["howdy", "face", "facial recognition", "biometrics"],
+};
+
+/// Builds and installs xfprintd-gui from the jailbroken fork.
+fn fingerprint() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "rust",
+                    "cargo",
+                    "gtk4",
+                    "libadwaita",
+                    "glib2",
+                    "pkgconf",
+                    "polkit",
+                    "fprintd",
+                    "base-devel",
+                ])
+                .description("Installing build dependencies...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "rm -rf /tmp/xfprintd-jailbreak && git clone https://github.com/MurderFromMars/xfprintd-gui.git /tmp/xfprintd-jailbreak",
+                ])
+                .description("Cloning XFPrintD GUI Jailbroken Edition...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("bash")
+                .args(&[
+                    "-c",
+                    "cd /tmp/xfprintd-jailbreak && bash /tmp/xfprintd-jailbreak/install.sh",
+                ])
+                .description("Building and installing Fingerprint GUI (Jailbroken Edition)...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("rm")
+                .args(&["-rf", "/tmp/xfprintd-jailbreak"])
+                .description("Cleaning up build directory...")
+                .build(),
+        )
+        .build()
+}
+
+/// Check if howdy is installed (either howdy-bin or howdy-git)
+fn is_howdy_installed() -> bool {
+    crate::is_package_installed("howdy-bin") || crate::is_package_installed("howdy-git")
+}
+
+/// Installs howdy-git unless a Howdy package is already there, then
+/// builds Howdy Qt from source.
+fn howdy() -> CommandSequence {
+    let mut commands = CommandSequence::new().then(
+        Command::builder()
+            .aur()
+            .args(&[
+                "-S",
+                "--noconfirm",
+                "--needed",
+                "rust",
+                "cargo",
+                "clang",
+                "qt6-base",
+                "qt6-declarative",
+            ])
+            .description("Installing build dependencies...")
+            .build(),
+    );
+
+    if !is_howdy_installed() {
+        info!("Installing howdy-git from AUR");
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "howdy-git"])
+                .description("Installing Howdy from AUR...")
+                .build(),
+        );
+    } else {
+        info!("Howdy already installed, skipping Howdy installation");
+    }
+
+    commands
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "rm -rf /tmp/xero-howdy-qt && git clone https://github.com/XeroLinuxDev/xero-howdy-qt.git /tmp/xero-howdy-qt",
+                ])
+                .description("Cloning Howdy Qt repository...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", "cd /tmp/xero-howdy-qt && cargo build --release"])
+                .description("Building Howdy Qt (this may take a few minutes)...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "install -Dm755 /tmp/xero-howdy-qt/target/release/xero-howdy-qt /usr/bin/xero-howdy-qt",
+                ])
+                .description("Installing Howdy Qt to system...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("rm")
+                .args(&["-rf", "/tmp/xero-howdy-qt"])
+                .description("Cleaning up build directory...")
+                .build(),
+        )
+        .build()
+}
@@ -0,0 +1,591 @@
+//! Container runtime actions.
+
+use super::{Action, Requirement};
+use crate::i18n::n_;
+use crate::task_runner::{Command, CommandSequence, Restart};
+use log::info;
+
+pub static DOCKER: Action = Action {
+    category: "install",
+    id: "docker",
+    title: n_("Docker Setup"),
+    description: n_("Install Docker and add your user to the docker group"),
+    requires: &[Requirement::AurHelper],
+    commands: docker,
+    installed: Some(|| crate::is_package_installed("docker")),
+    destructive: false,
+    page: "containers_vms",
+    widget: "btn_docker",
+    keywords: &["docker", "docker-compose", "docker-buildx", "containers"],
+};
+
+pub static INCUS: Action = Action {
+    category: "install",
+    id: "incus",
+    title: n_("Incus Setup"),
+    description: n_("Install Incus with a default storage pool and network bridge"),
+    requires: &[],
+    commands: incus,
+    installed: Some(|| crate::is_package_installed("incus")),
+    destructive: false,
+    page: "containers_vms",
+    widget: "btn_incus",
+    keywords: &["incus", "lxd", "lxc", "system containers"],
+};
+
+pub static LIBVIRT_DEFAULTS: Action = Action {
+    category: "servicing",
+    id: "libvirt-defaults",
+    title: n_("Fix Libvirt Defaults"),
+    description: n_("Start the default libvirt network and storage pool"),
+    requires: &[],
+    commands: libvirt_defaults,
+    installed: None,
+    destructive: false,
+    page: "containers_vms",
+    widget: "btn_kvm_defaults",
+    keywords: &["libvirt", "virsh", "default network", "storage pool", "kvm"],
+};
+
+pub static QUICKEMU: Action = Action {
+    category: "install",
+    id: "quickemu",
+    title: n_("Quickemu Setup"),
+    description: n_("Install Quickemu and Quickgui for quick desktop VMs"),
+    requires: &[Requirement::AurHelper],
+    commands: quickemu,
+    installed: Some(|| crate::is_package_installed("quickemu")),
+    destructive: false,
+    page: "containers_vms",
+    widget: "btn_quickemu",
+    keywords: &["quickemu", "quickget", "quickgui", "macos", "vm"],
+};
+
+pub static VM_GUEST_TOOLS: Action = Action {
+    category: "install",
+    id: "vm-guest-tools",
+    title: n_("VM Guest Utilities"),
+    description: n_("Install the guest agents for the hypervisor this system runs in"),
+    requires: &[],
+    commands: vm_guest_tools,
+    installed: Some(|| {
+        crate::virt::detect().is_some_and(|hv| {
+            hv.packages()
+                .iter()
+                .all(|pkg| crate::is_package_installed(pkg))
+        })
+    }),
+    destructive: false,
+    page: "containers_vms",
+    widget: "btn_vm_guest_tools",
+    keywords: &["guest", "spice", "vmware", "open-vm-tools", "hyperv"],
+};
+
+pub static VIRTUALBOX: Action = Action {
+    category: "install",
+    id: "virtualbox",
+    title: n_("VirtualBox Setup"),
+    description: n_("Install VirtualBox with the host modules for the running kernel"),
+    requires: &[Requirement::AurHelper],
+    commands: virtualbox,
+    installed: Some(|| crate::is_package_installed("virtualbox")),
+    destructive: false,
+    page: "containers_vms",
+    widget: "btn_vbox",
+    keywords: &["virtualbox", "vbox", "oracle", "vm"],
+};
+
+pub static DISTROBOX: Action = Action {
+    category: "install",
+    id: "distrobox",
+    title: n_("DistroBox Setup"),
+    description: n_("Install DistroBox and the BoxBuddy GUI"),
+    requires: &[Requirement::AurHelper, Requirement::Flatpak],
+    commands: distrobox,
+    installed: Some(|| crate::is_package_installed("distrobox")),
+    destructive: false,
+    page: "containers_vms",
+    widget: "btn_distrobox",
+    keywords: &["distrobox", "boxbuddy", "containers", "ubuntu", "fedora"],
+};
+
+pub static KVM: Action = Action {
+    category: "install",
+    id: "kvm",
+    title: n_("KVM / QEMU Setup"),
+    description: n_("Install QEMU, libvirt and virt-manager with nested virtualization"),
+    requires: &[Requirement::AurHelper],
+    commands: kvm,
+    installed: Some(|| crate::is_package_installed("virt-manager")),
+    destructive: false,
+    page: "containers_vms",
+    widget: "btn_kvm",
+    keywords: &["kvm", "qemu", "libvirt", "virt-manager", "vm"],
+};
+
+pub static IPA_SIDELOADER: Action = Action {
+    category: "install",
+    id: "ipa-sideloader",
+    title: n_("iOS iPA Sideloader Setup"),
+    description: n_("Install Plume Impactor to sideload iOS apps"),
+    requires: &[Requirement::Flatpak],
+    commands: ipa_sideloader,
+    installed: Some(|| crate::is_flatpak_installed(PLUME_FLATPAK)),
+    destructive: false,
+    page: "containers_vms",
+    widget: "btn_ipa_sideloader",
+    keywords: &["ipa", "ios", "iphone", "sideload", "plume"],
+};
+
+fn docker() -> CommandSequence {
+    let user = crate::config::env::get().user.clone();
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "docker",
+                    "docker-compose",
+                    "docker-buildx",
+                ])
+                .description("Installing Docker engine and tools...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "docker.service"])
+                .description("Enabling Docker service...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("groupadd")
+                .args(&["-f", "docker"])
+                .description("Ensuring docker group exists...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("usermod")
+                .args(&["-aG", "docker", &user])
+                .description("Adding your user to docker group...")
+                .requires(Restart::Relogin)
+                .build(),
+        )
+        .build()
+}
+
+/// Set up what virt-manager expects but a fresh libvirt lacks: socket access
+/// for the libvirt group, a running `default` NAT network and a `default`
+/// storage pool. Safe to run again, existing definitions are kept.
+pub fn libvirt_defaults() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sed")
+                .args(&[
+                    "-i",
+                    "-e",
+                    "s/^#\\?unix_sock_group = .*/unix_sock_group = \"libvirt\"/",
+                    "-e",
+                    "s/^#\\?unix_sock_rw_perms = .*/unix_sock_rw_perms = \"0770\"/",
+                    "/etc/libvirt/libvirtd.conf",
+                ])
+                .description("Allowing the libvirt group to use the libvirt socket...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["restart", "libvirtd.service"])
+                .description("Restarting libvirtd service...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "virsh net-info default > /dev/null 2>&1 || \
+                     virsh net-define /usr/share/libvirt/networks/default.xml; \
+                     virsh net-autostart default && \
+                     { virsh net-start default 2>/dev/null || virsh net-info default; }",
+                ])
+                .description("Starting the default NAT network...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "virsh pool-info default > /dev/null 2>&1 || \
+                     { virsh pool-define-as default dir --target /var/lib/libvirt/images && \
+                     virsh pool-build default; }; \
+                     virsh pool-autostart default && \
+                     { virsh pool-start default 2>/dev/null || virsh pool-info default; }",
+                ])
+                .description("Creating the default storage pool...")
+                .build(),
+        )
+        .build()
+}
+
+/// Incus storage driver for the filesystem `/var/lib` lives on. btrfs and
+/// zfs get native pools with snapshots and copy-on-write; anything else
+/// falls back to plain directories.
+fn incus_storage_backend(fstype: &str, has_zfs: bool) -> &'static str {
+    match fstype {
+        "zfs" => "zfs",
+        "btrfs" => "btrfs",
+        _ if has_zfs => "zfs",
+        _ => "dir",
+    }
+}
+
+fn incus() -> CommandSequence {
+    let user = crate::config::env::get().user.clone();
+    let fstype = std::process::Command::new("findmnt")
+        .args(["-no", "FSTYPE", "-T", "/var/lib"])
+        .output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default();
+    let has_zfs = std::path::Path::new("/usr/bin/zfs").exists();
+    let backend = incus_storage_backend(&fstype, has_zfs);
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "incus"])
+                .description("Installing Incus...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "grep -q '^root:1000000:' /etc/subuid || usermod -v 1000000-1000999999 root; \
+                     grep -q '^root:1000000:' /etc/subgid || usermod -w 1000000-1000999999 root",
+                ])
+                .description("Reserving subordinate IDs for unprivileged containers...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "incus.socket"])
+                .description("Enabling Incus socket...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("incus")
+                .args(&["admin", "init", "--auto", "--storage-backend", backend])
+                .description("Creating default storage pool and network...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("usermod")
+                .args(&["-aG", "incus-admin", &user])
+                .description("Adding your user to incus-admin group...")
+                .requires(Restart::Relogin)
+                .build(),
+        )
+        .build()
+}
+
+fn quickemu() -> CommandSequence {
+    let mut args = vec!["-S", "--noconfirm", "--needed"];
+    args.extend(crate::quickemu::PACKAGES);
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&args)
+                .description("Installing Quickemu and Quickgui...")
+                .build(),
+        )
+        .build()
+}
+
+/// Empty when not running in a supported VM, the page checks first.
+fn vm_guest_tools() -> CommandSequence {
+    let Some(hypervisor) = crate::virt::detect() else {
+        return CommandSequence::new().build();
+    };
+    let mut install = vec!["-S", "--noconfirm", "--needed"];
+    install.extend(hypervisor.packages());
+    let mut enable = vec!["enable", "--now"];
+    enable.extend(hypervisor.services());
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&install)
+                .description("Installing guest utilities...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&enable)
+                .description("Enabling guest services...")
+                .build(),
+        )
+        .build()
+}
+
+/// All possible VirtualBox host modules variants — used during uninstall
+/// to clean up whichever one was installed.
+pub const VBOX_HOST_VARIANTS: &[&str] = &[
+    "virtualbox-host-modules-arch",
+    "virtualbox-host-modules-lts",
+    "virtualbox-host-dkms",
+];
+
+/// Host modules package for VirtualBox, from the package owning the
+/// running kernel (see [`crate::kernel::running_package`]):
+///
+/// | Kernel package | Package                                     |
+/// |----------------|---------------------------------------------|
+/// | `linux`        | `virtualbox-host-modules-arch` (prebuilt)   |
+/// | `linux-lts`    | `virtualbox-host-modules-lts`  (prebuilt)   |
+/// | anything else  | `virtualbox-host-dkms`                      |
+///
+/// For dkms, the task runner installs the headers of every installed
+/// kernel along with it, and warns about kernels without any.
+fn detect_vbox_host_package() -> &'static str {
+    let kernel_pkg = crate::kernel::running_package();
+    info!("Running kernel package: {:?}", kernel_pkg);
+
+    match kernel_pkg.as_deref() {
+        Some("linux") => "virtualbox-host-modules-arch",
+        Some("linux-lts") => "virtualbox-host-modules-lts",
+        // Any other kernel (zen, cachyos, hardened, etc.) needs dkms
+        _ => "virtualbox-host-dkms",
+    }
+}
+
+/// Packages are listed explicitly instead of using `virtualbox-meta`
+/// (XeroLinux-specific) to avoid provider-conflict errors when
+/// --noconfirm auto-selects from multiple repos.
+fn virtualbox() -> CommandSequence {
+    let host_pkg = detect_vbox_host_package();
+    info!("Detected VBox host package: {}", host_pkg);
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "virtualbox",
+                    "virtualbox-guest-iso",
+                    host_pkg,
+                ])
+                .description("Installing VirtualBox...")
+                .build(),
+        )
+        .build()
+}
+
+/// BoxBuddy, the DistroBox GUI installed with it.
+pub const BOXBUDDY_FLATPAK: &str = "io.github.dvlv.boxbuddyrs";
+
+fn distrobox() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "distrobox"])
+                .description("Installing DistroBox...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&["install", "-y", BOXBUDDY_FLATPAK])
+                .description("Installing BoxBuddy GUI...")
+                .build(),
+        )
+        .build()
+}
+
+/// Explicit package list replacing `virt-manager-meta` (XeroLinux-specific).
+///
+/// | Package        | Purpose                                        |
+/// |----------------|------------------------------------------------|
+/// | qemu-desktop   | QEMU emulator (desktop profile, audio+display) |
+/// | libvirt        | Virtualization API daemon                      |
+/// | virt-manager   | GTK GUI for managing VMs                       |
+/// | virt-viewer    | Remote VM display client (SPICE/VNC)           |
+/// | edk2-ovmf      | UEFI firmware for VMs                          |
+/// | dnsmasq        | NAT/DHCP networking for libvirt                |
+/// | iptables-nft   | Firewall backend for libvirt networking        |
+/// | openbsd-netcat | Network utility (replaces gnu-netcat)          |
+/// | swtpm          | Software TPM 2.0 (needed for Windows 11 VMs)  |
+pub const KVM_PACKAGES: &[&str] = &[
+    "qemu-desktop",
+    "libvirt",
+    "virt-manager",
+    "virt-viewer",
+    "edk2-ovmf",
+    "dnsmasq",
+    "iptables-nft",
+    "openbsd-netcat",
+    "swtpm",
+];
+
+/// Detect CPU vendor and return the correct modprobe option for nested
+/// virtualisation. Intel → `kvm-intel`, AMD → `kvm-amd`.
+fn detect_kvm_nested_conf() -> (&'static str, &'static str) {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+
+    if cpuinfo.contains("GenuineIntel") {
+        ("kvm-intel", "options kvm-intel nested=1")
+    } else {
+        // AMD or fallback — kvm-amd also covers most other x86 cases
+        ("kvm-amd", "options kvm-amd nested=1")
+    }
+}
+
+fn kvm() -> CommandSequence {
+    let user = crate::config::env::get().user.clone();
+    let (kvm_module, kvm_option) = detect_kvm_nested_conf();
+    let conf_path = format!("/etc/modprobe.d/{}.conf", kvm_module);
+    let write_cmd = format!("echo '{}' > {}", kvm_option, conf_path);
+
+    let mut commands = CommandSequence::new();
+
+    // Resolve iptables / netcat conflicts safely.
+    // iptables (legacy) conflicts with iptables-nft; gnu-netcat conflicts
+    // with openbsd-netcat. Only act when the conflicting variant is present,
+    // exit 0 regardless so the sequence continues.
+    commands = commands.then(
+        Command::builder()
+            .privileged()
+            .program("sh")
+            .args(&[
+                "-c",
+                "pacman -Qi iptables &>/dev/null && \
+                 ! pacman -Qi iptables-nft &>/dev/null && \
+                 pacman -Rdd --noconfirm iptables || true; \
+                 pacman -Qi gnu-netcat &>/dev/null && \
+                 pacman -Rdd --noconfirm gnu-netcat || true",
+            ])
+            .description("Resolving package conflicts if needed...")
+            .build(),
+    );
+
+    // Install all packages explicitly (no meta-package).
+    commands = commands.then(
+        Command::builder()
+            .aur()
+            .args(&[
+                "-S",
+                "--noconfirm",
+                "--needed",
+                "qemu-desktop",
+                "libvirt",
+                "virt-manager",
+                "virt-viewer",
+                "edk2-ovmf",
+                "dnsmasq",
+                "iptables-nft",
+                "openbsd-netcat",
+                "swtpm",
+            ])
+            .description("Installing virtualization packages...")
+            .build(),
+    );
+
+    // Add user to libvirt group for unprivileged VM management.
+    commands = commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("usermod")
+                .args(&["-aG", "libvirt", &user])
+                .description("Adding your user to libvirt group...")
+                .requires(Restart::Relogin)
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", &write_cmd])
+                .description("Enabling nested virtualization...")
+                .requires(Restart::Reboot)
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "libvirtd.service"])
+                .description("Enabling libvirtd service...")
+                .build(),
+        )
+        // Restarts libvirtd, then brings up the default network and pool
+        // so the first VM doesn't fail with "network not started".
+        .append(libvirt_defaults());
+
+    commands.build()
+}
+
+/// Plume Impactor, the iOS iPA sideloader.
+pub const PLUME_FLATPAK: &str = "dev.khcrysalis.PlumeImpactor";
+
+fn ipa_sideloader() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&["install", "-y", "flathub", PLUME_FLATPAK])
+                .description("Installing Plume Impactor from Flathub...")
+                .build(),
+        )
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_incus_storage_backend() {
+        assert_eq!(incus_storage_backend("btrfs", false), "btrfs");
+        assert_eq!(incus_storage_backend("zfs", false), "zfs");
+        assert_eq!(incus_storage_backend("ext4", true), "zfs");
+        assert_eq!(incus_storage_backend("ext4", false), "dir");
+    }
+}
@@ -0,0 +1,231 @@
+//! User-defined actions loaded from drop-in TOML files.
+//!
+//! Each `*.toml` file in `~/.config/xero-toolkit/actions.d/` defines one
+//! action, shown on the Custom page and run through the task runner:
+//!
+//! ```toml
+//! title = "Refresh Mirrors"
+//! description = "Rank the fastest Arch mirrors"
+//! icon = "globe-symbolic"
+//!
+//! [[steps]]
+//! description = "Ranking mirrors..."
+//! privilege = "privileged"
+//! program = "rate-mirrors"
+//! args = ["--save", "/etc/pacman.d/mirrorlist", "arch"]
+//! ```
+//!
+//! `privilege` is `normal` (the default), `privileged` (runs through
+//! xero-auth) or `aur` (`args` go to the AUR helper and `program` is unused).
+
+use crate::task_runner::{Command, CommandSequence};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// How a step is run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Privilege {
+    #[default]
+    Normal,
+    Privileged,
+    Aur,
+}
+
+/// One command of a custom action.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    #[serde(default)]
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub privilege: Privilege,
+    /// Shown in the task list. Defaults to the command line.
+    pub description: Option<String>,
+}
+
+impl Step {
+    /// Check that the step can be built into a command.
+    pub(super) fn validate(&self) -> Result<()> {
+        if self.privilege != Privilege::Aur && self.program.trim().is_empty() {
+            bail!("no program set");
+        }
+        Ok(())
+    }
+
+    /// Build the task runner command for this step.
+    pub(super) fn to_command(&self) -> Command {
+        let builder = match self.privilege {
+            Privilege::Normal => Command::builder().normal(),
+            Privilege::Privileged => Command::builder().privileged(),
+            Privilege::Aur => Command::builder().aur(),
+        };
+        let args: Vec<&str> = self.args.iter().map(String::as_str).collect();
+        builder
+            .program(&self.program)
+            .args(&args)
+            .description(&self.description())
+            .build()
+    }
+
+    fn description(&self) -> String {
+        if let Some(description) = &self.description {
+            return description.clone();
+        }
+        let program = match self.privilege {
+            Privilege::Aur => "AUR helper",
+            _ => self.program.as_str(),
+        };
+        std::iter::once(program)
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// An action defined by a drop-in file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomAction {
+    /// File stem of the definition.
+    #[serde(skip)]
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Icon name for the row, e.g. `utilities-terminal-symbolic`.
+    pub icon: Option<String>,
+    pub steps: Vec<Step>,
+}
+
+impl CustomAction {
+    /// Parse and validate a definition.
+    pub fn parse(id: &str, text: &str) -> Result<Self> {
+        let mut action: CustomAction = toml::from_str(text)?;
+        action.id = id.to_string();
+
+        if action.title.trim().is_empty() {
+            bail!("title is empty");
+        }
+        if action.steps.is_empty() {
+            bail!("no steps defined");
+        }
+        for (index, step) in action.steps.iter().enumerate() {
+            step.validate()
+                .with_context(|| format!("step {}", index + 1))?;
+        }
+
+        Ok(action)
+    }
+
+    /// Build the command sequence for the task runner.
+    pub fn commands(&self) -> CommandSequence {
+        self.steps
+            .iter()
+            .fold(CommandSequence::new(), |sequence, step| {
+                sequence.then(step.to_command())
+            })
+            .build()
+    }
+
+    /// Whether any step needs an AUR helper.
+    pub fn needs_aur_helper(&self) -> bool {
+        self.steps.iter().any(|s| s.privilege == Privilege::Aur)
+    }
+}
+
+/// Directory holding the drop-in definitions.
+pub fn actions_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("xero-toolkit")
+        .join("actions.d")
+}
+
+fn load_file(path: &Path) -> Result<CustomAction> {
+    let id = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or_default();
+    let text = std::fs::read_to_string(path).context("Failed to read file")?;
+    CustomAction::parse(id, &text)
+}
+
+/// Load every definition in [`actions_dir`], sorted by file name.
+///
+/// Files that fail to parse are returned as errors naming the file, so
+/// one broken definition doesn't hide the others.
+pub fn load() -> Vec<Result<CustomAction>> {
+    let Ok(entries) = std::fs::read_dir(actions_dir()) else {
+        return Vec::new();
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            load_file(path).with_context(|| format!("Invalid action file {}", file_name))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_definition() {
+        let action = CustomAction::parse(
+            "mirrors",
+            r#"
+            title = "Refresh Mirrors"
+            icon = "globe-symbolic"
+
+            [[steps]]
+            privilege = "privileged"
+            program = "rate-mirrors"
+            args = ["--save", "/etc/pacman.d/mirrorlist", "arch"]
+
+            [[steps]]
+            privilege = "aur"
+            args = ["-Syu"]
+            description = "Updating AUR packages..."
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(action.id, "mirrors");
+        assert_eq!(action.description, "");
+        assert_eq!(action.steps[0].privilege, Privilege::Privileged);
+        assert_eq!(
+            action.steps[0].description(),
+            "rate-mirrors --save /etc/pacman.d/mirrorlist arch"
+        );
+        assert!(action.needs_aur_helper());
+    }
+
+    #[test]
+    fn rejects_invalid_definitions() {
+        assert!(CustomAction::parse("empty", "title = \"Empty\"\nsteps = []").is_err());
+        assert!(CustomAction::parse(
+            "no-program",
+            "title = \"No Program\"\n[[steps]]\nargs = [\"-a\"]"
+        )
+        .is_err());
+        assert!(CustomAction::parse(
+            "typo",
+            "title = \"Typo\"\n[[steps]]\nprogram = \"ls\"\nprivilage = \"aur\""
+        )
+        .is_err());
+    }
+}
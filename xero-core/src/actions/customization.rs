@@ -0,0 +1,228 @@
+//! Theme and desktop customization actions.
+
+use super::{Action, Requirement};
+use crate::desktop;
+use crate::i18n::n_;
+use crate::task_runner::{Command, CommandSequence};
+
+pub const SAVE_DESKTOP_FLATPAK: &str = "io.github.vikdevelop.SaveDesktop";
+
+pub static SAVE_DESKTOP: Action = Action {
+    category: "install",
+    id: "save-desktop",
+    title: n_("Save Desktop Tool Installation"),
+    description: n_("Install the Save Desktop tool from Flathub"),
+    requires: &[Requirement::Flatpak],
+    commands: save_desktop,
+    installed: Some(|| crate::is_flatpak_installed(SAVE_DESKTOP_FLATPAK)),
+    destructive: false,
+    page: "customization",
+    widget: "btn_save_desktop",
+    keywords: &["savedesktop", "backup", "desktop settings"],
+};
+
+pub static LAYAN: Action = Action {
+    category: "install",
+    id: "layan",
+    title: n_("Update Layan Theme"),
+    description: n_("Install the latest Layan KDE theme"),
+    requires: &[],
+    commands: layan,
+    installed: None,
+    destructive: false,
+    page: "customization",
+    widget: "btn_layan_patch",
+    keywords: &["layan", "kde", "plasma theme"],
+};
+
+pub static GNOME_TWEAKS: Action = Action {
+    category: "install",
+    id: "gnome-tweaks",
+    title: n_("GNOME Tweaks Installation"),
+    description: n_("Install GNOME Tweaks"),
+    requires: &[],
+    commands: gnome_tweaks,
+    installed: Some(|| crate::is_package_installed("gnome-tweaks")),
+    destructive: false,
+    page: "customization",
+    widget: "btn_gnome_tweaks",
+    keywords: &["gnome", "tweaks", "fonts"],
+};
+
+pub static EXTENSION_MANAGER: Action = Action {
+    category: "install",
+    id: "extension-manager",
+    title: n_("Extension Manager Installation"),
+    description: n_("Install Extension Manager from Flathub"),
+    requires: &[Requirement::Flatpak],
+    commands: extension_manager,
+    installed: Some(|| crate::is_flatpak_installed(desktop::EXTENSION_MANAGER_FLATPAK)),
+    destructive: false,
+    page: "customization",
+    widget: "btn_extension_manager",
+    keywords: &["gnome", "extensions", "shell extensions"],
+};
+
+pub static ADW_GTK3: Action = Action {
+    category: "install",
+    id: "adw-gtk3",
+    title: n_("adw-gtk3 Theme"),
+    description: n_("Install adw-gtk3 and apply it to GTK 3 apps"),
+    requires: &[],
+    commands: adw_gtk3,
+    installed: Some(|| crate::is_package_installed("adw-gtk-theme")),
+    destructive: false,
+    page: "customization",
+    widget: "btn_adw_gtk3",
+    keywords: &["adw-gtk-theme", "gtk3", "libadwaita", "theme"],
+};
+
+pub static DASH_TO_DOCK: Action = Action {
+    category: "install",
+    id: "dash-to-dock",
+    title: n_("Dash to Dock"),
+    description: n_("Install and enable the Dash to Dock GNOME extension"),
+    requires: &[],
+    commands: dash_to_dock,
+    installed: Some(|| crate::is_package_installed("gnome-shell-extension-dash-to-dock")),
+    destructive: false,
+    page: "customization",
+    widget: "btn_dash_to_dock",
+    keywords: &["gnome", "dock", "extension"],
+};
+
+fn save_desktop() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&["install", "-y", SAVE_DESKTOP_FLATPAK])
+                .description("Installing Save Desktop tool from Flathub...")
+                .build(),
+        )
+        .build()
+}
+
+fn layan() -> CommandSequence {
+    let home = crate::config::env::get().home.clone();
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("git")
+                .args(&[
+                    "clone",
+                    "--depth",
+                    "1",
+                    "https://github.com/vinceliuice/Layan-kde.git",
+                    &format!("{}/Layan-kde", home),
+                ])
+                .description("Downloading Layan KDE theme...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", &format!("cd {}/Layan-kde && sh install.sh", home)])
+                .description("Installing Layan KDE theme...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("rm")
+                .args(&["-rf", &format!("{}/Layan-kde", home)])
+                .description("Cleaning up KDE theme files...")
+                .build(),
+        )
+        .build()
+}
+
+fn gnome_tweaks() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "gnome-tweaks"])
+                .description("Installing GNOME Tweaks...")
+                .build(),
+        )
+        .build()
+}
+
+fn extension_manager() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&["install", "-y", desktop::EXTENSION_MANAGER_FLATPAK])
+                .description("Installing Extension Manager from Flathub...")
+                .build(),
+        )
+        .build()
+}
+
+fn adw_gtk3() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "adw-gtk-theme"])
+                .description("Installing the adw-gtk3 theme...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", desktop::ADW_GTK3_SCRIPT])
+                .description("Applying adw-gtk3 to GTK 3 apps...")
+                .build(),
+        )
+        .build()
+}
+
+fn dash_to_dock() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "gnome-shell-extension-dash-to-dock",
+                ])
+                .description("Installing Dash to Dock...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    desktop::ENABLE_EXTENSION_SCRIPT,
+                    "sh",
+                    desktop::DASH_TO_DOCK_UUID,
+                ])
+                .description("Enabling Dash to Dock...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&["Log out and back in for GNOME Shell to load Dash to Dock."])
+                .description("Dash to Dock enabled")
+                .build(),
+        )
+        .build()
+}
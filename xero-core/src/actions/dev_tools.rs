@@ -0,0 +1,45 @@
+//! Developer tool actions.
+
+use super::{Action, Requirement};
+use crate::i18n::n_;
+use crate::task_runner::{Command, CommandSequence};
+
+/// VS Code extension for opening folders in a dev container.
+pub const DEVCONTAINERS_EXTENSION: &str = "ms-vscode-remote.remote-containers";
+
+pub static DEVCONTAINERS: Action = Action {
+    category: "install",
+    id: "devcontainers",
+    title: n_("Dev Containers Setup"),
+    description: n_("Install the devcontainer CLI, and the VS Code extension if VS Code is there"),
+    requires: &[Requirement::AurHelper],
+    commands: devcontainers,
+    installed: Some(|| crate::is_package_installed("devcontainer-cli")),
+    destructive: false,
+    page: "dev_tools",
+    widget: "btn_devcontainers",
+    keywords: &["devcontainer", "dev containers", "docker", "vscode"],
+};
+
+fn devcontainers() -> CommandSequence {
+    let mut commands = CommandSequence::new().then(
+        Command::builder()
+            .aur()
+            .args(&["-S", "--noconfirm", "--needed", "devcontainer-cli"])
+            .description("Installing the devcontainer CLI...")
+            .build(),
+    );
+    // The extension is only on Microsoft's marketplace, so only their
+    // build can use it.
+    if crate::is_package_installed("visual-studio-code-bin") {
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("code")
+                .args(&["--install-extension", DEVCONTAINERS_EXTENSION])
+                .description("Installing the Dev Containers extension...")
+                .build(),
+        );
+    }
+    commands.build()
+}
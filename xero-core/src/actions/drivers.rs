@@ -0,0 +1,275 @@
+//! Driver and hardware tool actions.
+
+use super::{Action, Requirement};
+use crate::i18n::n_;
+use crate::task_runner::script::quote;
+use crate::task_runner::{Command, CommandSequence, Restart};
+use crate::verify;
+
+pub static TAILSCALE_VPN: Action = Action {
+    category: "install",
+    id: "tailscale-vpn",
+    title: n_("Install Tailscale VPN"),
+    description: n_("Install Tailscale with the XeroLinux setup script"),
+    requires: &[Requirement::Pin(TAILSCALE_SCRIPT)],
+    commands: tailscale_vpn,
+    installed: Some(|| crate::is_package_installed("tailscale")),
+    destructive: false,
+    page: "drivers",
+    widget: "btn_tailscale",
+    keywords: &["tailscale", "vpn", "wireguard", "mesh"],
+};
+
+pub static ASUS_ROG: Action = Action {
+    category: "install",
+    id: "asus-rog",
+    title: n_("Install ASUS ROG Tools"),
+    description: n_("Install asusctl, supergfxctl and ROG Control Center"),
+    requires: &[Requirement::AurHelper],
+    commands: asus_rog,
+    installed: Some(|| crate::is_package_installed("asusctl")),
+    destructive: false,
+    page: "drivers",
+    widget: "btn_asus_rog",
+    keywords: &["asusctl", "supergfxctl", "rog", "laptop"],
+};
+
+pub static COOLER_CONTROL: Action = Action {
+    category: "install",
+    id: "cooler-control",
+    title: n_("Install Cooler Control"),
+    description: n_("Install the Cooler Control daemon and liquidctl"),
+    requires: &[Requirement::AurHelper],
+    commands: cooler_control,
+    installed: Some(|| crate::is_package_installed("coolercontrol")),
+    destructive: false,
+    page: "drivers",
+    widget: "btn_cooler_control",
+    keywords: &["coolercontrol", "liquidctl", "fans", "aio", "pump"],
+};
+
+pub static ZENERGY: Action = Action {
+    category: "install",
+    id: "zenergy",
+    title: n_("Install Zenergy Driver"),
+    description: n_("Install the Zenergy AMD CPU power sensor driver"),
+    requires: &[Requirement::AurHelper],
+    commands: zenergy,
+    installed: Some(|| crate::is_package_installed("zenergy-dkms-git")),
+    destructive: false,
+    page: "drivers",
+    widget: "btn_zenergy",
+    keywords: &["zenergy", "amd", "power sensor", "hwmon"],
+};
+
+pub static NVIDIA_LEGACY: Action = Action {
+    category: "install",
+    id: "nvidia-legacy",
+    title: n_("Install Nvidia Legacy Drivers"),
+    description: n_("Install the 580xx drivers for GTX 900 and 1000 series GPUs"),
+    requires: &[Requirement::AurHelper],
+    commands: nvidia_legacy,
+    installed: Some(|| crate::is_package_installed("nvidia-580xx-dkms")),
+    destructive: false,
+    page: "drivers",
+    widget: "btn_nvidia_legacy",
+    keywords: &["nvidia-580xx", "gtx", "pascal", "maxwell", "legacy"],
+};
+
+pub static ROCM: Action = Action {
+    category: "install",
+    id: "rocm",
+    title: n_("Install AMD ROCm"),
+    description: n_("Install the AMD ROCm HIP and OpenCL SDKs"),
+    requires: &[Requirement::AurHelper],
+    commands: rocm,
+    installed: Some(|| crate::is_package_installed("rocm-hip-sdk")),
+    destructive: false,
+    page: "drivers",
+    widget: "btn_rocm",
+    keywords: &["rocm", "hip", "opencl", "amd", "compute"],
+};
+
+/// Pin of the XeroLinux Tailscale setup script.
+const TAILSCALE_SCRIPT: &str = "xero-tailscale-install";
+
+fn tailscale_vpn() -> CommandSequence {
+    let script = "/tmp/xero-tailscale-install.sh";
+    let fetch = match verify::get(TAILSCALE_SCRIPT) {
+        Ok(pin) => verify::fetch_script(&pin, script),
+        // Checked by the requirement, but a profile can still get here
+        Err(e) => format!("echo {} >&2; exit 1", quote(&e.to_string())),
+    };
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", &fetch])
+                .description("Downloading and verifying the Tailscale setup script...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("bash")
+                .args(&[script])
+                .description("Installing Tailscale VPN...")
+                .build(),
+        )
+        .build()
+}
+
+fn asus_rog() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "rog-control-center",
+                    "asusctl",
+                    "supergfxctl",
+                ])
+                .description("Installing ASUS ROG control tools...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "asusd", "supergfxd"])
+                .description("Enabling ASUS ROG services...")
+                .build(),
+        )
+        .build()
+}
+
+fn cooler_control() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "coolercontrol",
+                    "coolercontrold",
+                    "liquidctl",
+                ])
+                .description("Installing Cooler Control daemon and liquidctl...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "coolercontrold.service"])
+                .description("Enabling Cooler Control daemon service...")
+                .build(),
+        )
+        .build()
+}
+
+fn zenergy() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "zenergy-dkms-git"])
+                .description("Installing Zenergy Driver...")
+                .build(),
+        )
+        .build()
+}
+
+fn nvidia_legacy() -> CommandSequence {
+    let script_dir = crate::config::paths::scripts();
+    let grub_script = script_dir
+        .join("nvidia_grub.sh")
+        .to_string_lossy()
+        .into_owned();
+    let mkinitcpio_script = script_dir
+        .join("nvidia_mkinitcpio.sh")
+        .to_string_lossy()
+        .into_owned();
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "lib32-nvidia-580xx-utils",
+                    "lib32-opencl-nvidia-580xx",
+                    "nvidia-580xx-dkms",
+                    "nvidia-580xx-utils",
+                    "opencl-nvidia-580xx",
+                ])
+                .description("Installing Nvidia Legacy Drivers...")
+                .requires(Restart::Reboot)
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("bash")
+                .args(&[&grub_script])
+                .description("Configuring GRUB (nvidia-drm.modeset=1)...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("bash")
+                .args(&[&mkinitcpio_script])
+                .description("Configuring mkinitcpio modules...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&[
+                    "enable",
+                    "nvidia-suspend.service",
+                    "nvidia-hibernate.service",
+                    "nvidia-resume.service",
+                ])
+                .description("Enabling Nvidia power management services...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("mkinitcpio")
+                .args(&["-P"])
+                .description("Rebuilding initramfs...")
+                .build(),
+        )
+        .build()
+}
+
+fn rocm() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "rocm-hip-sdk",
+                    "rocm-opencl-sdk",
+                ])
+                .description("Installing AMD ROCm SDK...")
+                .build(),
+        )
+        .build()
+}
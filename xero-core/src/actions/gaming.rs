@@ -0,0 +1,577 @@
+//! Gaming tool actions.
+
+use super::{Action, Requirement};
+use crate::apps;
+use crate::firewall::{self, PortRule, Protocol};
+use crate::i18n::n_;
+use crate::power::{self, PowerDaemon};
+use crate::task_runner::{Command, CommandSequence, Restart};
+use log::info;
+
+pub static GAMING_SUITE: Action = Action {
+    category: "install",
+    id: "gaming-suite",
+    title: n_("Gaming Suite Installation"),
+    description: n_("Install the CachyOS gaming packages, or a curated Wine and Steam suite"),
+    requires: &[],
+    commands: gaming_suite,
+    installed: Some(|| crate::is_package_installed("steam")),
+    destructive: false,
+    page: "gaming_tools",
+    widget: "btn_gaming_meta",
+    keywords: &["steam", "wine", "proton", "lutris", "heroic", "mangohud"],
+};
+
+pub static LACT: Action = Action {
+    category: "install",
+    id: "lact",
+    title: n_("LACT GPU Tools"),
+    description: n_("Install LACT for GPU overclocking, fan curves and power limits"),
+    requires: &[Requirement::AurHelper],
+    commands: lact,
+    installed: Some(|| crate::is_package_installed("lact")),
+    destructive: false,
+    page: "gaming_tools",
+    widget: "btn_lact_oc",
+    keywords: &["lact", "overclock", "undervolt", "fan curve", "gpu"],
+};
+
+pub static FALCOND: Action = Action {
+    category: "install",
+    id: "falcond",
+    title: n_("Falcond Installation"),
+    description: n_("Install falcond, replacing conflicting power daemons with tuned-ppd"),
+    requires: &[],
+    commands: falcond,
+    installed: Some(|| crate::is_package_installed("falcond")),
+    destructive: false,
+    page: "gaming_tools",
+    widget: "btn_falcond",
+    keywords: &["falcond", "tuned-ppd", "performance", "game profiles"],
+};
+
+pub static GAMEMODE: Action = Action {
+    category: "install",
+    id: "gamemode",
+    title: n_("GameMode Setup"),
+    description: n_("Install and configure Feral GameMode"),
+    requires: &[],
+    commands: gamemode,
+    installed: Some(|| crate::is_package_installed("gamemode")),
+    destructive: false,
+    page: "gaming_tools",
+    widget: "btn_gamemode",
+    keywords: &["gamemode", "gamemoderun", "governor", "performance"],
+};
+
+pub static SUNSHINE: Action = Action {
+    category: "install",
+    id: "sunshine",
+    title: n_("Sunshine Setup"),
+    description: n_("Install the Sunshine game streaming host and open its ports"),
+    requires: &[Requirement::AurHelper],
+    commands: sunshine,
+    installed: Some(|| crate::is_package_installed("sunshine")),
+    destructive: false,
+    page: "gaming_tools",
+    widget: "btn_sunshine",
+    keywords: &["sunshine", "streaming", "moonlight", "remote play"],
+};
+
+pub static MOONLIGHT: Action = Action {
+    category: "install",
+    id: "moonlight",
+    title: n_("Moonlight Installation"),
+    description: n_("Install the Moonlight game streaming client from Flathub"),
+    requires: &[Requirement::Flatpak],
+    commands: moonlight,
+    installed: Some(|| crate::is_flatpak_installed("com.moonlight_stream.Moonlight")),
+    destructive: false,
+    page: "gaming_tools",
+    widget: "btn_moonlight",
+    keywords: &["moonlight", "streaming", "sunshine", "geforce now"],
+};
+
+pub static VKBASALT: Action = Action {
+    category: "install",
+    id: "vkbasalt",
+    title: n_("vkBasalt Setup"),
+    description: n_("Install vkBasalt with a sharpening config"),
+    requires: &[Requirement::AurHelper],
+    commands: vkbasalt,
+    installed: Some(|| crate::is_package_installed("vkbasalt")),
+    destructive: false,
+    page: "gaming_tools",
+    widget: "btn_vkbasalt",
+    keywords: &[
+        "vkbasalt",
+        "reshade",
+        "sharpening",
+        "cas",
+        "post-processing",
+    ],
+};
+
+/// CachyOS gaming meta packages when the repos have them, else a curated suite.
+fn gaming_suite() -> CommandSequence {
+    let mut commands = CommandSequence::new();
+
+    // Check if CachyOS gaming packages are available in repos
+    let cachy_meta_available = crate::is_package_in_repos("cachyos-gaming-meta");
+    let cachy_apps_available = crate::is_package_in_repos("cachyos-gaming-applications");
+
+    if cachy_meta_available && cachy_apps_available {
+        info!("CachyOS gaming packages found in repos, installing from repos");
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "cachyos-gaming-meta",
+                    "cachyos-gaming-applications",
+                ])
+                .description("Installing CachyOS gaming meta packages...")
+                .build(),
+        );
+    } else {
+        info!("CachyOS gaming packages not in repos, installing curated gaming suite");
+
+        // -- Step 1: Multilib runtime libraries & Wine/Proton stack --
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    // Audio
+                    "alsa-plugins",
+                    "lib32-alsa-plugins",
+                    // Media/codec libs
+                    "giflib",
+                    "lib32-giflib",
+                    "gst-plugins-base-libs",
+                    "lib32-gst-plugins-base-libs",
+                    "libjpeg-turbo",
+                    "lib32-libjpeg-turbo",
+                    "mpg123",
+                    "lib32-mpg123",
+                    "libxslt",
+                    "openal",
+                    "lib32-openal",
+                    // Video acceleration
+                    "libva",
+                    "lib32-libva",
+                    // OpenCL
+                    "opencl-icd-loader",
+                    "lib32-opencl-icd-loader",
+                    // Vulkan
+                    "vulkan-icd-loader",
+                    "lib32-vulkan-icd-loader",
+                    "vulkan-tools",
+                    // GTK (needed by some launchers/games)
+                    "lib32-gtk3",
+                    // GLFW
+                    "glfw",
+                    // Fonts (required by many Windows games)
+                    "ttf-liberation",
+                    "wqy-zenhei",
+                    // Wine & Proton tools
+                    "wine-staging",
+                    "winetricks",
+                    "protontricks",
+                    "umu-launcher",
+                    // Gaming tools & launchers
+                    "steam",
+                    "gamescope",
+                    "mangohud",
+                    "lib32-mangohud",
+                    "goverlay",
+                ])
+                .description("Installing gaming libraries, Wine, and tools from repos...")
+                .build(),
+        );
+
+        // -- Step 2: Launchers, from the preferred source --
+        for app in [&apps::LUTRIS, &apps::HEROIC] {
+            commands = commands.then(app.install(app.preferred()));
+        }
+
+        // -- Step 3: Splitlock mitigation disable (gaming perf optimization) --
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "echo 'kernel.split_lock_mitigate=0' > /etc/sysctl.d/99-splitlock.conf && sysctl --system",
+                ])
+                .description("Disabling split-lock mitigation for gaming performance...")
+                .build(),
+        );
+    }
+
+    commands.build()
+}
+
+fn lact() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "lact"])
+                .description("Installing LACT GPU control utility...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "lactd"])
+                .description("Enabling LACT background service...")
+                .build(),
+        )
+        .build()
+}
+
+/// Install falcond from the repos where possible, falling back to the AUR.
+fn falcond() -> CommandSequence {
+    let user = crate::config::env::get().user.clone();
+
+    // Remove any power daemon that conflicts with tuned-ppd
+    let mut commands = power::remove_conflicts(
+        CommandSequence::new(),
+        PowerDaemon::TunedPpd,
+        &power::installed_daemons(),
+    );
+
+    // Packages to install
+    let repo_candidates = ["falcond", "falcond-gui", "tuned-ppd"];
+
+    let mut pacman_packages: Vec<&str> = Vec::new();
+    let mut aur_packages: Vec<&str> = Vec::new();
+    let mut all_in_repos = true;
+
+    for pkg in repo_candidates {
+        // Skip if already installed
+        if crate::is_package_installed(pkg) {
+            info!("{} already installed, skipping", pkg);
+            continue;
+        }
+
+        // Check if available in repos
+        if crate::is_package_in_repos(pkg) {
+            info!("{} found in repos", pkg);
+            pacman_packages.push(pkg);
+        } else {
+            info!("{} not in repos, will use AUR", pkg);
+            aur_packages.push(pkg);
+            all_in_repos = false;
+        }
+    }
+
+    // If any package needs AUR, add falcond-profiles too (AUR-only)
+    if !all_in_repos && !crate::is_package_installed("falcond-profiles") {
+        info!("falcond-profiles not installed, adding to AUR list");
+        aur_packages.push("falcond-profiles");
+    }
+
+    // Install from repos first
+    if !pacman_packages.is_empty() {
+        let mut args = vec!["-S", "--noconfirm", "--needed"];
+        args.extend(pacman_packages.iter());
+
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&args)
+                .description("Installing Falcond packages from repos...")
+                .build(),
+        );
+    }
+
+    // Install remaining from AUR (only if needed)
+    if !aur_packages.is_empty() {
+        let mut args = vec!["-S", "--noconfirm", "--needed"];
+        args.extend(aur_packages.iter());
+
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&args)
+                .description("Installing Falcond packages from AUR...")
+                .build(),
+        );
+    }
+
+    // Post-install setup (always run to ensure proper configuration)
+    commands = commands
+        .then(
+            Command::builder()
+                .privileged()
+                .program("groupadd")
+                .args(&["-f", "falcond"])
+                .description("Ensuring falcond group exists...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("usermod")
+                .args(&["-aG", "falcond", &user])
+                .description("Adding your user to falcond group...")
+                .requires(Restart::Relogin)
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("mkdir")
+                .args(&["-p", "/usr/share/falcond/profiles/user"])
+                .description("Creating necessary user directory...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("chown")
+                .args(&[":falcond", "/usr/share/falcond/profiles/user"])
+                .description("Adding proper ownership permissions...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("chmod")
+                .args(&["2775", "/usr/share/falcond/profiles/user"])
+                .description("Adding proper executable permissions...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "falcond"])
+                .description("Enabling falcond background service...")
+                .build(),
+        );
+
+    commands.build()
+}
+
+/// System-wide GameMode config: renice and I/O priority for games, the
+/// performance governor while one runs, and no GPU tweaks since those need
+/// per-card values.
+const GAMEMODE_INI: &str = r#"[general]
+reaper_freq=5
+desiredgov=performance
+igpu_desiredgov=powersave
+softrealtime=auto
+renice=10
+ioprio=0
+inhibit_screensaver=1
+
+[gpu]
+apply_gpu_optimisations=0
+
+[custom]
+script_timeout=10
+"#;
+
+fn gamemode() -> CommandSequence {
+    let user = crate::config::env::get().user.clone();
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "gamemode",
+                    "lib32-gamemode",
+                ])
+                .description("Installing GameMode...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("usermod")
+                .args(&["-aG", "gamemode", &user])
+                .description("Adding your user to gamemode group...")
+                .requires(Restart::Relogin)
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    &format!("cat > /etc/gamemode.ini << 'EOF'\n{}EOF", GAMEMODE_INI),
+                ])
+                .description("Installing GameMode configuration...")
+                .build(),
+        )
+        // Output stays in the dialog so users can see which checks passed.
+        // The renice check only passes after logging back in with the
+        // new group.
+        .then(
+            Command::builder()
+                .normal()
+                .program("gamemoded")
+                .args(&["-t"])
+                .description("Running GameMode self-test...")
+                .build(),
+        )
+        .build()
+}
+
+/// Ports Sunshine listens on: HTTPS, HTTP, web UI and RTSP over TCP, then
+/// video, control, audio and mic over UDP.
+const SUNSHINE_PORTS: [PortRule; 7] = [
+    PortRule::tcp(47984),
+    PortRule::tcp(47989),
+    PortRule::tcp(47990),
+    PortRule::tcp(48010),
+    PortRule::range(47998, 48000, Protocol::Udp),
+    PortRule::udp(48002),
+    PortRule::udp(48010),
+];
+
+/// Sunshine's web UI, where clients are paired.
+const SUNSHINE_WEB_UI: &str = "https://localhost:47990";
+
+/// Install Sunshine, let it capture the screen under Wayland/KMS, open its
+/// ports and start it for the current user.
+fn sunshine() -> CommandSequence {
+    let mut commands = CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "sunshine"])
+                .description("Installing Sunshine from AUR...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "setcap cap_sys_admin+p \"$(readlink -f \"$(command -v sunshine)\")\"",
+                ])
+                .description("Allowing Sunshine to capture the screen...")
+                .build(),
+        );
+
+    match firewall::active() {
+        Some(fw) => {
+            info!("Opening Sunshine ports in {}", fw.name());
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &fw.open_ports_script(&SUNSHINE_PORTS, "Sunshine")])
+                    .description("Opening Sunshine ports in the firewall...")
+                    .build(),
+            );
+        }
+        None => info!("No active firewall, leaving ports alone"),
+    }
+
+    commands
+        .then(
+            Command::builder()
+                .normal()
+                .program("systemctl")
+                .args(&["--user", "enable", "--now", "sunshine"])
+                .description("Enabling Sunshine user service...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[&format!(
+                    "Open {} to set a password and pair Moonlight clients.",
+                    SUNSHINE_WEB_UI
+                )])
+                .description("Sunshine is ready")
+                .build(),
+        )
+        .build()
+}
+
+fn moonlight() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&["install", "-y", "com.moonlight_stream.Moonlight"])
+                .description("Installing Moonlight...")
+                .build(),
+        )
+        .build()
+}
+
+/// Starter vkBasalt config: contrast adaptive sharpening, toggled with Home.
+const VKBASALT_CONF: &str = "effects = cas
+casSharpness = 0.4
+toggleKey = Home
+enableOnLaunch = True
+";
+
+fn vkbasalt() -> CommandSequence {
+    // An existing config is the user's own and is left alone
+    let config_script = format!(
+        "conf=\"$HOME/.config/vkBasalt/vkBasalt.conf\"; \
+         if [ -f \"$conf\" ]; then echo \"Keeping existing $conf\"; \
+         else mkdir -p \"$(dirname \"$conf\")\" && cat > \"$conf\" << 'EOF'\n{}EOF\n\
+         echo \"Wrote $conf\"; fi",
+        VKBASALT_CONF
+    );
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "vkbasalt",
+                    "lib32-vkbasalt",
+                ])
+                .description("Installing vkBasalt...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", &config_script])
+                .description("Writing vkBasalt starter config...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[
+                    "Add ENABLE_VKBASALT=1 %command% to a game's Steam launch options to use it.",
+                ])
+                .description("vkBasalt is ready")
+                .build(),
+        )
+        .build()
+}
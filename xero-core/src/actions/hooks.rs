@@ -0,0 +1,175 @@
+//! User hooks run before and after built-in actions.
+//!
+//! `~/.config/xero-toolkit/hooks.toml` attaches steps to registered
+//! actions, matched by their id as listed by the CLI:
+//!
+//! ```toml
+//! [[hooks]]
+//! action = "update-mirrors"
+//!
+//! [[hooks.before]]
+//! description = "Creating snapshot..."
+//! privilege = "privileged"
+//! program = "timeshift"
+//! args = ["--create", "--comments", "Before mirrorlist update"]
+//!
+//! [[hooks.after]]
+//! program = "notify-send"
+//! args = ["Mirrorlists updated"]
+//! ```
+//!
+//! `before` steps run first and `after` steps run once the action's own
+//! steps succeed. Steps take the same fields as custom actions and show up
+//! as regular steps in the task list. Titles aren't matched, since they're
+//! translated and several flows share one.
+
+use super::custom::Step;
+use crate::task_runner::CommandSequence;
+use anyhow::{Context, Result};
+use log::{info, warn};
+use serde::Deserialize;
+use std::path::PathBuf;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HooksFile {
+    #[serde(default)]
+    hooks: Vec<Hook>,
+}
+
+/// Steps attached to one action.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Hook {
+    /// Id of the action, e.g. `update-mirrors`.
+    pub action: String,
+    #[serde(default)]
+    pub before: Vec<Step>,
+    #[serde(default)]
+    pub after: Vec<Step>,
+}
+
+/// Path of the hooks configuration.
+pub fn hooks_file() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("xero-toolkit")
+        .join("hooks.toml")
+}
+
+fn parse(text: &str) -> Result<Vec<Hook>> {
+    let file: HooksFile = toml::from_str(text)?;
+    for hook in &file.hooks {
+        for (index, step) in hook.before.iter().chain(&hook.after).enumerate() {
+            step.validate()
+                .with_context(|| format!("hook for '{}', step {}", hook.action, index + 1))?;
+        }
+    }
+    Ok(file.hooks)
+}
+
+/// Load the configured hooks. A missing file means no hooks; an invalid
+/// one is logged and ignored rather than blocking the action.
+pub fn load() -> Vec<Hook> {
+    let path = hooks_file();
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    match parse(&text) {
+        Ok(hooks) => hooks,
+        Err(e) => {
+            warn!("Ignoring invalid {}: {:#}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+fn apply_hooks(hooks: &[Hook], id: &str, commands: CommandSequence) -> CommandSequence {
+    let matching: Vec<&Hook> = hooks.iter().filter(|h| h.action == id).collect();
+    if matching.is_empty() {
+        return commands;
+    }
+
+    let before = matching.iter().flat_map(|h| &h.before);
+    let after = matching.iter().flat_map(|h| &h.after);
+    info!(
+        "Running {} before and {} after hook step(s) for '{}'",
+        before.clone().count(),
+        after.clone().count(),
+        id
+    );
+
+    before
+        .fold(CommandSequence::new(), |sequence, step| {
+            sequence.then(step.to_command())
+        })
+        .append(commands)
+        .append(after.fold(CommandSequence::new(), |sequence, step| {
+            sequence.then(step.to_command())
+        }))
+        .build()
+}
+
+/// Wrap `commands` with the hooks configured for the action with `id`.
+pub fn apply(id: &str, commands: CommandSequence) -> CommandSequence {
+    apply_hooks(&load(), id, commands)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hooks() {
+        let hooks = parse(
+            r#"
+            [[hooks]]
+            action = "docker"
+
+            [[hooks.after]]
+            program = "rsync"
+            args = ["-a", "/home/me/.config-backup", "/mnt/backup"]
+
+            [[hooks]]
+            action = "update-mirrors"
+            before = [{ program = "timeshift", args = ["--create"], privilege = "privileged" }]
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(hooks.len(), 2);
+        assert!(hooks[0].before.is_empty());
+        assert_eq!(hooks[0].after[0].program, "rsync");
+        assert_eq!(hooks[1].before[0].args, ["--create"]);
+    }
+
+    #[test]
+    fn wraps_only_matching_actions() {
+        let hooks = parse(
+            "[[hooks]]\naction = \"A\"\n\
+             [[hooks.before]]\nprogram = \"true\"\n\
+             [[hooks.after]]\nprogram = \"true\"",
+        )
+        .unwrap();
+        let sequence = || {
+            CommandSequence::new()
+                .then(
+                    crate::task_runner::Command::builder()
+                        .normal()
+                        .program("true")
+                        .description("Action step")
+                        .build(),
+                )
+                .build()
+        };
+
+        assert_eq!(apply_hooks(&hooks, "A", sequence()).len(), 3);
+        assert_eq!(apply_hooks(&hooks, "B", sequence()).len(), 1);
+    }
+
+    #[test]
+    fn rejects_steps_without_program() {
+        assert!(parse("[[hooks]]\naction = \"X\"\n[[hooks.before]]\nargs = [\"-a\"]").is_err());
+        assert!(parse("[[hooks]]\naction = \"X\"\nwhen = \"before\"").is_err());
+    }
+}
@@ -0,0 +1,74 @@
+//! Kernel and boot actions.
+
+use super::Action;
+use crate::i18n::n_;
+use crate::microcode;
+use crate::task_runner::{Command, CommandSequence, Restart};
+use log::warn;
+
+pub static FIX_MICROCODE: Action = Action {
+    category: "servicing",
+    id: "fix-microcode",
+    title: n_("Fix CPU Microcode"),
+    description: n_("Install the CPU's microcode and load it at boot"),
+    requires: &[],
+    commands: fix_microcode,
+    installed: None,
+    destructive: false,
+    page: "kernel_schedulers",
+    widget: "btn_fix_microcode",
+    keywords: &["microcode", "intel-ucode", "amd-ucode", "cpu"],
+};
+
+/// Empty when the CPU vendor isn't recognised, so nothing runs.
+fn fix_microcode() -> CommandSequence {
+    let Some(vendor) = microcode::detect_vendor() else {
+        warn!("Unknown CPU vendor, no microcode to install");
+        return CommandSequence::new().build();
+    };
+    let package = vendor.package();
+
+    let mut commands = CommandSequence::new().then(
+        Command::builder()
+            .privileged()
+            .program("pacman")
+            .args(&["-S", "--noconfirm", "--needed", package])
+            .description(&format!("Installing {} microcode...", vendor.name()))
+            .requires(Restart::Reboot)
+            .build(),
+    );
+
+    // GRUB picks up /boot/*-ucode.img on regeneration; otherwise the
+    // mkinitcpio microcode hook embeds it in the initramfs, once added.
+    commands = if microcode::uses_grub() {
+        commands.then(
+            Command::builder()
+                .privileged()
+                .program("grub-mkconfig")
+                .args(&["-o", "/boot/grub/grub.cfg"])
+                .description("Regenerating GRUB configuration...")
+                .build(),
+        )
+    } else {
+        let conf = std::fs::read_to_string(microcode::MKINITCPIO_CONF).unwrap_or_default();
+        if let Some(conf) = microcode::add_microcode_hook(&conf) {
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &microcode::mkinitcpio_write_script(), "sh", &conf])
+                    .description("Adding the microcode hook to mkinitcpio.conf...")
+                    .build(),
+            );
+        }
+        commands.then(
+            Command::builder()
+                .privileged()
+                .program("mkinitcpio")
+                .args(&["-P"])
+                .description("Rebuilding initramfs...")
+                .build(),
+        )
+    };
+    commands.build()
+}
@@ -0,0 +1,157 @@
+//! Local AI tool actions.
+//!
+//! The pages check free disk space before running these, see
+//! [`crate::ai::space_warning`].
+
+use super::{Action, Requirement};
+use crate::ai;
+use crate::hardware;
+use crate::i18n::n_;
+use crate::task_runner::{Command, CommandSequence};
+use log::info;
+
+pub static OLLAMA: Action = Action {
+    category: "install",
+    id: "ollama",
+    title: n_("Ollama Setup"),
+    description: n_("Install Ollama in the build matching the GPU and start it"),
+    requires: &[],
+    commands: ollama,
+    installed: Some(|| {
+        ai::OLLAMA_PACKAGES
+            .iter()
+            .any(|pkg| crate::is_package_installed(pkg))
+    }),
+    destructive: false,
+    page: "local_ai",
+    widget: "btn_ollama",
+    keywords: &["ollama", "llm", "ai", "cuda", "rocm"],
+};
+
+pub static WHISPER: Action = Action {
+    category: "install",
+    id: "whisper",
+    title: n_("whisper.cpp Setup"),
+    description: n_("Build whisper.cpp speech to text for the GPU"),
+    requires: &[Requirement::AurHelper],
+    commands: whisper,
+    installed: Some(|| {
+        ai::WHISPER_PACKAGES
+            .iter()
+            .any(|pkg| crate::is_package_installed(pkg))
+    }),
+    destructive: false,
+    page: "local_ai",
+    widget: "btn_whisper",
+    keywords: &["whisper", "speech to text", "transcription", "ai"],
+};
+
+pub static SD_WEBUI: Action = Action {
+    category: "install",
+    id: "sd-webui",
+    title: n_("Stable Diffusion Setup"),
+    description: n_("Download the Stable Diffusion web UI into the home folder"),
+    requires: &[],
+    commands: sd_webui,
+    installed: Some(|| ai::sd_webui_dir().join("webui.sh").exists()),
+    destructive: false,
+    page: "local_ai",
+    widget: "btn_sd_webui",
+    keywords: &[
+        "stable diffusion",
+        "automatic1111",
+        "image generation",
+        "ai",
+    ],
+};
+
+/// The GPU builds only carry the backend and depend on plain ollama, so
+/// both are named to keep the CPU fallback around.
+fn ollama() -> CommandSequence {
+    let package = ai::ollama_package(&hardware::gpus());
+    info!("Selected Ollama build: {}", package);
+
+    let mut install = vec!["-S", "--noconfirm", "--needed", "ollama"];
+    if package != "ollama" {
+        install.push(package);
+    }
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&install)
+                .description(&format!("Installing {}...", package))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "ollama.service"])
+                .description("Starting the Ollama service...")
+                .build(),
+        )
+        .build()
+}
+
+fn whisper() -> CommandSequence {
+    let package = ai::whisper_package(&hardware::gpus());
+    info!("Selected whisper.cpp build: {}", package);
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", package])
+                .description(&format!("Building {}...", package))
+                .build(),
+        )
+        .build()
+}
+
+/// Clones the web UI and points it at a Python 3.10 from uv. The PyTorch
+/// builds it pins don't support Arch's current Python. `$1` is the
+/// repository, `$2` the target folder.
+const SD_WEBUI_SETUP: &str = r#"set -e
+[ -d "$2/.git" ] || git clone --depth 1 "$1" "$2"
+uv python install 3.10
+printf '\npython_cmd="%s"\n' "$(uv python find 3.10)" >> "$2/webui-user.sh"
+"#;
+
+/// PyTorch and the first model are downloaded on the first run of
+/// webui.sh, which picks the CUDA or ROCm build itself.
+fn sd_webui() -> CommandSequence {
+    let dir = ai::sd_webui_dir();
+    let dir_str = dir.to_string_lossy().to_string();
+    let launch = dir.join("webui.sh").to_string_lossy().to_string();
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "git", "uv"])
+                .description("Installing git and uv...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&["-c", SD_WEBUI_SETUP, "sh", ai::SD_WEBUI_REPO, &dir_str])
+                .description("Downloading the Stable Diffusion web UI...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("echo")
+                .args(&[&format!(
+                    "Run {} to finish setup and start the web UI.",
+                    launch
+                )])
+                .description("Stable Diffusion web UI is ready")
+                .build(),
+        )
+        .build()
+}
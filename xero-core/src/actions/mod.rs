@@ -0,0 +1,236 @@
+//! Registry of toolkit actions.
+//!
+//! Install and repair flows are declared here once, independent of GTK, so
+//! the pages, the CLI and search all run the same steps. Each action has:
+//! - `category` and `id`: how the CLI addresses it (`install docker`)
+//! - `title`: shown as the task runner title
+//! - `requires`: checks that must pass before it can run
+//! - `commands`: builds the command sequence to run
+//! - `installed`: optional probe for whether it's already set up
+//! - `destructive`: whether it's confirmed first, with a snapshot offered
+//! - `page` and `widget`: the page and button it lives on, for search
+//! - `keywords`: extra search terms, such as package names
+//!
+//! Submodules group actions by area:
+//! - `aur`: Installing an AUR helper when none is present
+//! - `biometrics`: Fingerprint and face login tools
+//! - `containers`: Container runtimes
+//! - `custom`: User-defined actions from drop-in TOML files
+//! - `customization`: Themes and desktop tweaks
+//! - `dev_tools`: Developer tools
+//! - `drivers`: Driver and hardware tools
+//! - `gaming`: Gaming tools
+//! - `hooks`: User steps run before and after actions
+//! - `kernel`: Kernel and boot repairs
+//! - `local_ai`: Local AI tools
+//! - `multimedia`: Multimedia apps and servers
+//! - `network`: Network tools
+//! - `search`: Fuzzy matching over the registry
+//! - `servicing`: Pacman and system repairs
+
+pub mod aur;
+pub mod biometrics;
+pub mod containers;
+pub mod custom;
+pub mod customization;
+pub mod dev_tools;
+pub mod drivers;
+pub mod gaming;
+pub mod hooks;
+pub mod kernel;
+pub mod local_ai;
+pub mod multimedia;
+pub mod network;
+pub mod search;
+pub mod servicing;
+
+use crate::task_runner::{Command, CommandSequence};
+
+/// Something that must be available before an action can run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Requirement {
+    /// paru, yay or pikaur, for actions with AUR steps.
+    AurHelper,
+    /// flatpak, for actions installing Flatpak apps.
+    Flatpak,
+    /// A pin for the named download, for actions running upstream scripts.
+    Pin(&'static str),
+}
+
+impl Requirement {
+    /// Whether the requirement is met on this system.
+    pub fn is_met(&self) -> bool {
+        match self {
+            Requirement::AurHelper => crate::aur_helper().is_some() || crate::aur::init(),
+            Requirement::Flatpak => crate::system_check::flatpak_available(),
+            Requirement::Pin(name) => crate::verify::get(name).is_ok(),
+        }
+    }
+
+    /// Human readable description, for error messages.
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Requirement::AurHelper => crate::i18n::n_("an AUR helper (paru, yay or pikaur)"),
+            Requirement::Flatpak => "flatpak",
+            Requirement::Pin(_) => crate::i18n::n_("a pinned checksum for its download"),
+        }
+    }
+
+    /// Steps installing whatever provides the requirement. Pins only come
+    /// with toolkit updates, so there's nothing to run for them.
+    pub fn install_commands(&self) -> CommandSequence {
+        match self {
+            Requirement::Pin(_) => CommandSequence::new(),
+            Requirement::AurHelper => aur::install_paru(),
+            Requirement::Flatpak => CommandSequence::new()
+                .then(
+                    Command::builder()
+                        .privileged()
+                        .program("pacman")
+                        .args(&["-S", "--needed", "--noconfirm", "flatpak"])
+                        .description("Installing Flatpak...")
+                        .build(),
+                )
+                .build(),
+        }
+    }
+}
+
+/// An install or repair flow.
+pub struct Action {
+    pub category: &'static str,
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub requires: &'static [Requirement],
+    pub commands: fn() -> CommandSequence,
+    pub installed: Option<fn() -> bool>,
+    /// Hard to undo, so the GUI asks first and offers a snapshot.
+    pub destructive: bool,
+    /// Id of the page in `navigation::PAGES` that shows the action.
+    pub page: &'static str,
+    /// Builder id of the action's button on that page.
+    pub widget: &'static str,
+    pub keywords: &'static [&'static str],
+}
+
+impl Action {
+    /// Requirements that aren't met on this system.
+    pub fn missing_requirements(&self) -> Vec<Requirement> {
+        self.requires
+            .iter()
+            .copied()
+            .filter(|r| !r.is_met())
+            .collect()
+    }
+
+    /// Whether the action is already set up, if it can tell.
+    pub fn is_installed(&self) -> Option<bool> {
+        self.installed.map(|probe| probe())
+    }
+
+    /// Build the commands, wrapped in the user's hooks for this action.
+    pub fn commands_with_hooks(&self) -> CommandSequence {
+        hooks::apply(self.id, (self.commands)())
+    }
+
+    /// Error message for unmet requirements, if any.
+    pub fn requirements_error(&self) -> Option<String> {
+        let missing = self.missing_requirements();
+        if missing.is_empty() {
+            return None;
+        }
+        let list = missing
+            .iter()
+            .map(|r| crate::i18n::gettext(r.describe()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(crate::i18n::gettext_f(
+            "{action} requires {list}.",
+            &[
+                ("action", &crate::i18n::gettext(self.title)),
+                ("list", &list),
+            ],
+        ))
+    }
+}
+
+/// All registered actions, grouped by category.
+pub static ACTIONS: &[&Action] = &[
+    &containers::DOCKER,
+    &containers::INCUS,
+    &containers::LIBVIRT_DEFAULTS,
+    &containers::QUICKEMU,
+    &containers::VM_GUEST_TOOLS,
+    &containers::VIRTUALBOX,
+    &containers::DISTROBOX,
+    &containers::KVM,
+    &containers::IPA_SIDELOADER,
+    &servicing::PLASMA_X11,
+    &servicing::CLEAR_CACHE,
+    &servicing::UNLOCK_PACMAN,
+    &servicing::FIX_KEYRING,
+    &servicing::FIX_PACMAN_DB,
+    &servicing::UPDATE_MIRRORS,
+    &servicing::XERO_REPO,
+    &kernel::FIX_MICROCODE,
+    &drivers::TAILSCALE_VPN,
+    &drivers::ASUS_ROG,
+    &drivers::COOLER_CONTROL,
+    &drivers::ZENERGY,
+    &drivers::NVIDIA_LEGACY,
+    &drivers::ROCM,
+    &gaming::GAMING_SUITE,
+    &gaming::LACT,
+    &gaming::FALCOND,
+    &gaming::GAMEMODE,
+    &gaming::SUNSHINE,
+    &gaming::MOONLIGHT,
+    &gaming::VKBASALT,
+    &multimedia::KDENLIVE,
+    &multimedia::JELLYFIN,
+    &multimedia::GPU_SCREEN_RECORDER,
+    &local_ai::OLLAMA,
+    &local_ai::WHISPER,
+    &local_ai::SD_WEBUI,
+    &dev_tools::DEVCONTAINERS,
+    &biometrics::FINGERPRINT,
+    &biometrics::HOWDY,
+    &network::TAILSCALE,
+    &customization::SAVE_DESKTOP,
+    &customization::LAYAN,
+    &customization::GNOME_TWEAKS,
+    &customization::EXTENSION_MANAGER,
+    &customization::ADW_GTK3,
+    &customization::DASH_TO_DOCK,
+];
+
+/// Look up an action by category and id.
+pub fn find(category: &str, id: &str) -> Option<&'static Action> {
+    ACTIONS
+        .iter()
+        .copied()
+        .find(|a| a.category == category && a.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_and_widgets_are_unique() {
+        // Hooks match on the id alone
+        for (i, a) in ACTIONS.iter().enumerate() {
+            for b in &ACTIONS[i + 1..] {
+                assert!(a.id != b.id, "{} is registered twice", a.id);
+                assert!(
+                    (a.page, a.widget) != (b.page, b.widget),
+                    "{} and {} share {}",
+                    a.id,
+                    b.id,
+                    a.widget
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Multimedia app and server actions.
+
+use super::{Action, Requirement};
+use crate::i18n::n_;
+use crate::task_runner::{Command, CommandSequence};
+use log::info;
+
+pub static KDENLIVE: Action = Action {
+    category: "install",
+    id: "kdenlive",
+    title: n_("Kdenlive Installation"),
+    description: n_("Install the Kdenlive video editor"),
+    requires: &[Requirement::AurHelper],
+    commands: kdenlive,
+    installed: Some(|| crate::is_package_installed("kdenlive")),
+    destructive: false,
+    page: "multimedia_tools",
+    widget: "btn_kdenlive",
+    keywords: &["kdenlive", "video editor", "editing"],
+};
+
+pub static JELLYFIN: Action = Action {
+    category: "install",
+    id: "jellyfin",
+    title: n_("Jellyfin Server Setup"),
+    description: n_("Install and start the Jellyfin media server"),
+    requires: &[Requirement::AurHelper],
+    commands: jellyfin,
+    installed: Some(|| crate::is_package_installed("jellyfin-server")),
+    destructive: false,
+    page: "multimedia_tools",
+    widget: "btn_jellyfin",
+    keywords: &["jellyfin", "media server", "streaming", "plex"],
+};
+
+pub static GPU_SCREEN_RECORDER: Action = Action {
+    category: "install",
+    id: "gpu-screen-recorder",
+    title: n_("GPU Screen Recorder Setup"),
+    description: n_("Install GPU Screen Recorder, from the repos if it's there"),
+    requires: &[],
+    commands: gpu_screen_recorder,
+    installed: Some(|| crate::is_package_installed("gpu-screen-recorder-gtk")),
+    destructive: false,
+    page: "multimedia_tools",
+    widget: "btn_gpu_screen_recorder",
+    keywords: &["gpu-screen-recorder", "recording", "shadowplay", "capture"],
+};
+
+fn kdenlive() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "kdenlive"])
+                .description("Installing Kdenlive...")
+                .build(),
+        )
+        .build()
+}
+
+fn jellyfin() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&[
+                    "-S",
+                    "--noconfirm",
+                    "--needed",
+                    "jellyfin-server",
+                    "jellyfin-web",
+                    "jellyfin-ffmpeg",
+                ])
+                .description("Installing Jellyfin server and components...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "jellyfin.service"])
+                .description("Starting Jellyfin service...")
+                .build(),
+        )
+        .build()
+}
+
+/// Install from the official repos when available, else from the AUR.
+fn gpu_screen_recorder() -> CommandSequence {
+    let install = if crate::is_package_in_repos("gpu-screen-recorder-gtk") {
+        info!("gpu-screen-recorder-gtk found in official repos – installing via pacman");
+        Command::builder()
+            .privileged()
+            .program("pacman")
+            .args(&["-S", "--noconfirm", "--needed", "gpu-screen-recorder-gtk"])
+            .description("Installing GPU Screen Recorder GTK from official repos...")
+            .build()
+    } else {
+        info!("gpu-screen-recorder-gtk not in official repos – installing via AUR");
+        Command::builder()
+            .aur()
+            .args(&["-S", "--noconfirm", "--needed", "gpu-screen-recorder-gtk"])
+            .description("Installing GPU Screen Recorder GTK from AUR...")
+            .build()
+    };
+    CommandSequence::new().then(install).build()
+}
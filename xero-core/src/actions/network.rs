@@ -0,0 +1,41 @@
+//! Network tool actions.
+
+use super::Action;
+use crate::i18n::n_;
+use crate::network;
+use crate::task_runner::{Command, CommandSequence};
+
+pub static TAILSCALE: Action = Action {
+    category: "install",
+    id: "tailscale",
+    title: n_("Install Tailscale"),
+    description: n_("Install Tailscale from the repos and start its daemon"),
+    requires: &[],
+    commands: tailscale,
+    installed: Some(|| crate::is_package_installed("tailscale")),
+    destructive: false,
+    page: "network_tools",
+    widget: "btn_tailscale_install",
+    keywords: &["tailscale", "vpn", "tailscaled"],
+};
+
+fn tailscale() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", "tailscale"])
+                .description("Installing Tailscale...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", network::TAILSCALE_SERVICE])
+                .description("Starting the Tailscale daemon...")
+                .build(),
+        )
+        .build()
+}
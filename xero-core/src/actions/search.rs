@@ -0,0 +1,87 @@
+//! Fuzzy matching over the action registry.
+//!
+//! Every word of the query has to match somewhere: a word of the title
+//! (best), anywhere in the title, in the description or keywords, or as an
+//! in-order subsequence of the title (so "pcdb" finds "Pacman DB Fix").
+
+use super::{Action, ACTIONS};
+use crate::i18n::gettext;
+
+const WORD_PREFIX: u32 = 30;
+const TITLE: u32 = 20;
+const OTHER: u32 = 10;
+const SUBSEQUENCE: u32 = 5;
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|c| haystack.any(|h| h == c))
+}
+
+/// Score how well `query` matches an entry titled `title` with extra
+/// searchable `fields`. `None` if it doesn't match; higher is better.
+pub fn score(query: &str, title: &str, fields: &[&str]) -> Option<u32> {
+    let title = title.to_lowercase();
+    let fields: Vec<String> = fields.iter().map(|f| f.to_lowercase()).collect();
+
+    let mut total = 0;
+    let mut terms = 0;
+    for term in query.to_lowercase().split_whitespace() {
+        terms += 1;
+        total += if title.split_whitespace().any(|w| w.starts_with(term)) {
+            WORD_PREFIX
+        } else if title.contains(term) {
+            TITLE
+        } else if fields.iter().any(|f| f.contains(term)) {
+            OTHER
+        } else if is_subsequence(term, &title) {
+            SUBSEQUENCE
+        } else {
+            return None;
+        };
+    }
+
+    (terms > 0).then_some(total)
+}
+
+/// Registered actions matching `query` with their scores, best match first.
+pub fn search(query: &str) -> Vec<(u32, &'static Action)> {
+    let mut matches: Vec<(u32, &'static Action)> = ACTIONS
+        .iter()
+        .filter_map(|action| {
+            // The English title and description still match in a translated UI
+            let title = gettext(action.title);
+            let description = gettext(action.description);
+            let mut fields = vec![
+                description.as_str(),
+                action.title,
+                action.description,
+                action.id,
+            ];
+            fields.extend_from_slice(action.keywords);
+            score(query, &title, &fields).map(|s| (s, *action))
+        })
+        .collect();
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_title_matches_first() {
+        let prefix = score("key", "Fix GnuPG Keyring", &[]).unwrap();
+        let inner = score("ring", "Fix GnuPG Keyring", &[]).unwrap();
+        let field = score("gpg", "Fix GnuPG Keyring", &["pacman-key", "gpg"]).unwrap();
+        let fuzzy = score("fxkr", "Fix GnuPG Keyring", &[]).unwrap();
+        assert!(prefix > inner && inner > field && field > fuzzy);
+    }
+
+    #[test]
+    fn requires_every_term() {
+        assert!(score("keyring fix", "Fix GnuPG Keyring", &[]).is_some());
+        assert!(score("keyring docker", "Fix GnuPG Keyring", &[]).is_none());
+        assert!(score("", "Fix GnuPG Keyring", &[]).is_none());
+    }
+}
@@ -0,0 +1,299 @@
+//! Pacman and system repair actions.
+
+use super::{Action, Requirement};
+use crate::i18n::n_;
+use crate::task_runner::{Command, CommandSequence};
+
+pub static CLEAR_CACHE: Action = Action {
+    category: "servicing",
+    id: "clear-cache",
+    title: n_("Clear Pacman Cache"),
+    description: n_("Clear the pacman package cache"),
+    requires: &[],
+    commands: clear_cache,
+    installed: None,
+    destructive: false,
+    page: "servicing_system_tweaks",
+    widget: "btn_clr_pacman",
+    keywords: &["pacman", "paccache", "disk space"],
+};
+
+pub static UNLOCK_PACMAN: Action = Action {
+    category: "servicing",
+    id: "unlock-pacman",
+    title: n_("Unlock Pacman Database"),
+    description: n_("Remove a stale pacman database lock"),
+    requires: &[],
+    commands: unlock_pacman,
+    installed: None,
+    destructive: false,
+    page: "servicing_system_tweaks",
+    widget: "btn_unlock_pacman",
+    keywords: &["pacman", "db.lck", "lock"],
+};
+
+pub static FIX_KEYRING: Action = Action {
+    category: "servicing",
+    id: "fix-keyring",
+    title: n_("Fix GnuPG Keyring"),
+    description: n_("Recreate the pacman keyring"),
+    requires: &[],
+    commands: fix_keyring,
+    installed: None,
+    destructive: true,
+    page: "servicing_system_tweaks",
+    widget: "btn_fix_arch_keyring",
+    keywords: &["pacman-key", "archlinux-keyring", "gpg", "signature"],
+};
+
+pub static FIX_PACMAN_DB: Action = Action {
+    category: "servicing",
+    id: "fix-pacman-db",
+    title: n_("Pacman DB Fix"),
+    description: n_("Fix the pacman local database"),
+    requires: &[],
+    commands: fix_pacman_db,
+    installed: None,
+    destructive: false,
+    page: "servicing_system_tweaks",
+    widget: "btn_pacman_db_fix",
+    keywords: &["pacman", "database", "local db"],
+};
+
+pub static PLASMA_X11: Action = Action {
+    category: "install",
+    id: "plasma-x11",
+    title: n_("Install KDE X11 Session"),
+    description: n_("Install the KDE Plasma X11 session"),
+    requires: &[Requirement::AurHelper],
+    commands: plasma_x11,
+    installed: Some(|| crate::is_package_installed("plasma-x11-session")),
+    destructive: false,
+    page: "servicing_system_tweaks",
+    widget: "btn_plasma_x11",
+    keywords: &["plasma-x11-session", "kde", "xorg"],
+};
+
+pub static UPDATE_MIRRORS: Action = Action {
+    category: "servicing",
+    id: "update-mirrors",
+    title: n_("Update System Mirrorlists"),
+    description: n_("Rank mirrors by speed with rate-mirrors"),
+    requires: &[],
+    commands: update_mirrors,
+    installed: None,
+    destructive: false,
+    page: "servicing_system_tweaks",
+    widget: "btn_update_mirrorlist",
+    keywords: &["rate-mirrors", "mirrorlist", "reflector", "download speed"],
+};
+
+pub static XERO_REPO: Action = Action {
+    category: "servicing",
+    id: "xero-repo",
+    title: n_("Add Xero Linux Repository"),
+    description: n_("Add the XeroLinux repository to pacman.conf"),
+    requires: &[],
+    commands: xero_repo,
+    installed: None,
+    destructive: false,
+    page: "servicing_system_tweaks",
+    widget: "btn_xero_repo",
+    keywords: &["xerolinux", "repository", "pacman.conf"],
+};
+
+/// Countries offered as the starting point for mirror ranking, as
+/// `(code, name)`. rate-mirrors tests mirrors in and near this country first.
+pub const MIRROR_COUNTRIES: &[(&str, &str)] = &[
+    ("AU", "Australia"),
+    ("AT", "Austria"),
+    ("BR", "Brazil"),
+    ("CA", "Canada"),
+    ("CN", "China"),
+    ("CZ", "Czechia"),
+    ("DK", "Denmark"),
+    ("FI", "Finland"),
+    ("FR", "France"),
+    ("DE", "Germany"),
+    ("IN", "India"),
+    ("IT", "Italy"),
+    ("JP", "Japan"),
+    ("KR", "South Korea"),
+    ("NL", "Netherlands"),
+    ("NZ", "New Zealand"),
+    ("NO", "Norway"),
+    ("PL", "Poland"),
+    ("PT", "Portugal"),
+    ("RU", "Russia"),
+    ("SG", "Singapore"),
+    ("ZA", "South Africa"),
+    ("ES", "Spain"),
+    ("SE", "Sweden"),
+    ("CH", "Switzerland"),
+    ("GB", "United Kingdom"),
+    ("US", "United States"),
+];
+
+/// Mirrorlists rate-mirrors can refresh, as `(file, rate-mirrors target, name)`.
+const MIRRORLISTS: &[(&str, &str, &str)] = &[
+    ("/etc/pacman.d/mirrorlist", "arch", "Arch"),
+    ("/etc/pacman.d/chaotic-mirrorlist", "chaotic-aur", "Chaotic-AUR"),
+    ("/etc/pacman.d/cachyos-mirrorlist", "cachyos", "CachyOS"),
+    ("/etc/pacman.d/endeavouros-mirrorlist", "endeavouros", "EndeavourOS"),
+    ("/etc/pacman.d/manjaro-mirrorlist", "manjaro", "Manjaro"),
+    ("/etc/pacman.d/rebornos-mirrorlist", "rebornos", "RebornOS"),
+    ("/etc/pacman.d/artix-mirrorlist", "artix", "Artix"),
+];
+
+fn update_mirrors() -> CommandSequence {
+    let mut commands = CommandSequence::new();
+
+    if !crate::is_package_installed("rate-mirrors") {
+        commands = commands.then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--needed", "--noconfirm", "rate-mirrors"])
+                .description("Installing rate-mirrors utility...")
+                .build(),
+        );
+    }
+
+    // Start from the country picked during first-run setup, if any
+    let entry_country = crate::config::user::load()
+        .mirror_country
+        .map(|code| format!("--entry-country={} ", code))
+        .unwrap_or_default();
+
+    for (file_path, repo_id, repo_name) in MIRRORLISTS {
+        if std::path::Path::new(file_path).exists() {
+            let cmd = format!(
+                "rate-mirrors {}--allow-root --protocol https {} | tee {}",
+                entry_country, repo_id, file_path
+            );
+            let description = format!("Updating {} mirrorlist...", repo_name);
+            commands = commands.then(
+                Command::builder()
+                    .privileged()
+                    .program("sh")
+                    .args(&["-c", &cmd])
+                    .description(&description)
+                    .build(),
+            );
+        }
+    }
+
+    commands.build()
+}
+
+fn clear_cache() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-Scc", "--noconfirm"])
+                .description("Clearing Pacman cache...")
+                .build(),
+        )
+        .build()
+}
+
+fn unlock_pacman() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("rm")
+                .args(&["-f", "/var/lib/pacman/db.lck"])
+                .description("Removing Pacman lock file...")
+                .build(),
+        )
+        .build()
+}
+
+fn fix_keyring() -> CommandSequence {
+    CommandSequence::new()
+        .then(Command::builder()
+            .privileged()
+            .program("rm")
+            .args(&["-rf", "/etc/pacman.d/gnupg"])
+            .description("Removing existing GnuPG keyring...")
+            .build())
+        .then(Command::builder()
+            .privileged()
+            .program("pacman-key")
+            .args(&["--init"])
+            .description("Initializing new keyring...")
+            .build())
+        .then(Command::builder()
+            .privileged()
+            .program("pacman-key")
+            .args(&["--populate"])
+            .description("Populating keyring...")
+            .build())
+        .then(Command::builder()
+            .privileged()
+            .program("sh")
+            .args(&["-c", "echo 'keyserver hkp://keyserver.ubuntu.com:80' >> /etc/pacman.d/gnupg/gpg.conf"])
+            .description("Setting keyserver...")
+            .build())
+        .then(Command::builder()
+            .privileged()
+            .program("pacman")
+            .args(&["-Syy", "--noconfirm", "archlinux-keyring"])
+            .description("Reinstalling Arch Linux keyring...")
+            .build())
+        .build()
+}
+
+fn fix_pacman_db() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "find /var/lib/pacman/local/ -type f -name 'desc' -exec sed -i '/^%INSTALLED_DB%$/,+2d' {} \\;",
+                ])
+                .description("Fixing Pacman local database...")
+                .build(),
+        )
+        .build()
+}
+
+fn plasma_x11() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "kwin-x11", "plasma-x11-session"])
+                .description("Installing KDE Plasma X11 session components...")
+                .build(),
+        )
+        .build()
+}
+
+fn xero_repo() -> CommandSequence {
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "grep -q '\\[xerolinux\\]' /etc/pacman.conf || echo -e '\\n[xerolinux]\\nSigLevel = Optional TrustAll\\nServer = https://repos.xerolinux.xyz/$repo/$arch' >> /etc/pacman.conf",
+                ])
+                .description("Adding Xero Linux repository to pacman.conf...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-Syy"])
+                .description("Refreshing package databases...")
+                .build(),
+        )
+        .build()
+}
@@ -0,0 +1,120 @@
+//! Local AI tools.
+//!
+//! Picks the GPU build of each tool from the detected hardware and checks
+//! there's room for it first, since runtimes and models run to gigabytes.
+
+use super::hardware::GpuVendor;
+use crate::i18n::gettext_f;
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+
+/// Every Ollama build, for the install state and uninstall.
+pub const OLLAMA_PACKAGES: &[&str] = &["ollama", "ollama-cuda", "ollama-rocm", "ollama-vulkan"];
+
+/// Every whisper.cpp build in the AUR.
+pub const WHISPER_PACKAGES: &[&str] = &["whisper.cpp", "whisper.cpp-cuda", "whisper.cpp-vulkan"];
+
+pub const SD_WEBUI_REPO: &str = "https://github.com/AUTOMATIC1111/stable-diffusion-webui.git";
+
+/// Ollama build for the GPUs present. CUDA and ROCm are preferred over
+/// Vulkan where the vendor supports them.
+pub fn ollama_package(gpus: &[GpuVendor]) -> &'static str {
+    if gpus.contains(&GpuVendor::Nvidia) {
+        "ollama-cuda"
+    } else if gpus.contains(&GpuVendor::Amd) {
+        "ollama-rocm"
+    } else if gpus.contains(&GpuVendor::Intel) {
+        "ollama-vulkan"
+    } else {
+        "ollama"
+    }
+}
+
+/// whisper.cpp build for the GPUs present.
+pub fn whisper_package(gpus: &[GpuVendor]) -> &'static str {
+    if gpus.contains(&GpuVendor::Nvidia) {
+        "whisper.cpp-cuda"
+    } else if gpus.is_empty() {
+        "whisper.cpp"
+    } else {
+        "whisper.cpp-vulkan"
+    }
+}
+
+/// Space in GB to keep free for an Ollama build and a first model. ROCm
+/// pulls in several gigabytes of libraries on its own.
+pub fn ollama_required_gb(package: &str) -> u64 {
+    match package {
+        "ollama-rocm" => 25,
+        "ollama-cuda" => 12,
+        _ => 8,
+    }
+}
+
+/// whisper.cpp plus a medium model.
+pub const WHISPER_REQUIRED_GB: u64 = 3;
+
+/// The web UI's Python environment with PyTorch, plus a checkpoint.
+pub const SD_WEBUI_REQUIRED_GB: u64 = 15;
+
+/// Open WebUI's image.
+pub const OPEN_WEBUI_REQUIRED_GB: u64 = 5;
+
+/// Where the Stable Diffusion web UI is cloned, `~/stable-diffusion-webui`.
+pub fn sd_webui_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/root"))
+        .join("stable-diffusion-webui")
+}
+
+/// Free space in GB on the filesystem holding `path`, or its nearest
+/// existing parent.
+pub fn free_gb(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let c_path = CString::new(existing.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    Some(stat.f_bavail as u64 * stat.f_frsize as u64 / 1_000_000_000)
+}
+
+/// Warning for the confirmation dialog when `free` is below `required`.
+pub fn space_warning(name: &str, path: &Path, free: u64, required: u64) -> Option<String> {
+    (free < required).then(|| {
+        gettext_f(
+            "{name} needs about <b>{required} GB</b>, but only <b>{free} GB</b> is free on the \
+             drive holding {path}. The install may fail partway.",
+            &[
+                ("name", name),
+                ("required", &required.to_string()),
+                ("free", &free.to_string()),
+                ("path", &path.display().to_string()),
+            ],
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packages_for_gpus() {
+        use GpuVendor::*;
+        assert_eq!(ollama_package(&[Intel, Nvidia]), "ollama-cuda");
+        assert_eq!(ollama_package(&[Amd]), "ollama-rocm");
+        assert_eq!(ollama_package(&[]), "ollama");
+        assert_eq!(whisper_package(&[Amd]), "whisper.cpp-vulkan");
+        assert!(OLLAMA_PACKAGES.contains(&ollama_package(&[Intel])));
+    }
+
+    #[test]
+    fn test_space_warning() {
+        let home = Path::new("/home/user");
+        assert!(space_warning("Ollama", home, 30, 25).is_none());
+        let warning = space_warning("Ollama", home, 10, 25).unwrap();
+        assert!(warning.contains("<b>25 GB</b>"));
+    }
+}
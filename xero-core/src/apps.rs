@@ -0,0 +1,120 @@
+//! Apps offered both as native packages and on Flathub.
+//!
+//! Each app has a usual source the toolkit picks for it. The `app_source`
+//! setting overrides it for all of them, and dialogs that install one let
+//! the user choose again.
+
+use crate::config::user::{self, AppSource};
+use crate::package;
+use crate::task_runner::Command;
+
+/// An app with a native package and a Flatpak.
+pub struct App {
+    pub name: &'static str,
+    /// Package name, from the repos or the AUR.
+    pub package: &'static str,
+    /// Whether the package comes from the AUR.
+    pub aur: bool,
+    /// Flathub app id.
+    pub flatpak: &'static str,
+    /// Source used unless the user prefers another.
+    pub usual: AppSource,
+}
+
+pub const LUTRIS: App = App {
+    name: "Lutris",
+    package: "lutris",
+    aur: false,
+    flatpak: "net.lutris.Lutris",
+    usual: AppSource::Native,
+};
+
+pub const HEROIC: App = App {
+    name: "Heroic Games Launcher",
+    package: "heroic-games-launcher-bin",
+    aur: true,
+    flatpak: "com.heroicgameslauncher.hgl",
+    usual: AppSource::Native,
+};
+
+pub const BOTTLES: App = App {
+    name: "Bottles",
+    package: "bottles",
+    aur: true,
+    flatpak: "com.usebottles.bottles",
+    usual: AppSource::Flatpak,
+};
+
+pub const OBS: App = App {
+    name: "OBS-Studio",
+    package: "obs-studio",
+    aur: false,
+    flatpak: "com.obsproject.Studio",
+    usual: AppSource::Native,
+};
+
+pub const PODMAN_DESKTOP: App = App {
+    name: "Podman Desktop",
+    package: "podman-desktop",
+    aur: false,
+    flatpak: "io.podman_desktop.PodmanDesktop",
+    usual: AppSource::Flatpak,
+};
+
+impl App {
+    /// The source to offer first: the user's preference, else the usual one.
+    pub fn preferred(&self) -> AppSource {
+        user::load().app_source.unwrap_or(self.usual)
+    }
+
+    /// Where the installed app came from, if it is installed. Blocks on
+    /// pacman and flatpak.
+    pub fn installed(&self) -> Option<AppSource> {
+        if package::is_package_installed(self.package) {
+            Some(AppSource::Native)
+        } else if package::is_flatpak_installed(self.flatpak) {
+            Some(AppSource::Flatpak)
+        } else {
+            None
+        }
+    }
+
+    /// Step installing the app from `source`.
+    pub fn install(&self, source: AppSource) -> Command {
+        match source {
+            AppSource::Native if self.aur => Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", self.package])
+                .description(&format!("Installing {} from the AUR...", self.name)),
+            AppSource::Native => Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "--needed", self.package])
+                .description(&format!("Installing {}...", self.name)),
+            AppSource::Flatpak => Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&["install", "-y", "flathub", self.flatpak])
+                .description(&format!("Installing {} from Flathub...", self.name)),
+        }
+        .build()
+    }
+
+    /// Step removing the app installed from `source`.
+    pub fn remove(&self, source: AppSource) -> Command {
+        let (builder, args) = match source {
+            AppSource::Native => (
+                Command::builder().privileged().program("pacman"),
+                ["-Rns", "--noconfirm", self.package],
+            ),
+            AppSource::Flatpak => (
+                Command::builder().normal().program("flatpak"),
+                ["uninstall", "-y", self.flatpak],
+            ),
+        };
+        builder
+            .args(&args)
+            .description(&format!("Removing {}...", self.name))
+            .build()
+    }
+}
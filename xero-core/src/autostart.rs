@@ -1,7 +1,8 @@
 //! Autostart management for the toolkit.
 //!
 //! Handles enabling/disabling autostart by managing the desktop file
-//! in the user's autostart directory.
+//! in the user's autostart directory. With `run_in_background` set, the
+//! entry starts the toolkit in the tray instead of opening the window.
 
 use crate::config;
 use std::fs;
@@ -20,7 +21,11 @@ pub fn is_enabled() -> bool {
     get_autostart_path().exists() || config::paths::system_autostart().exists()
 }
 
-/// Enable autostart by creating a symlink to the desktop file in autostart directory
+/// Exec line of the autostart entry when running in the background.
+const BACKGROUND_EXEC: &str = "Exec=xero-toolkit --background";
+
+/// Enable autostart by creating a symlink to the desktop file in autostart
+/// directory, or a copy starting in the tray when running in the background
 pub fn enable() -> Result<(), std::io::Error> {
     let autostart_dir = dirs::config_dir()
         .unwrap_or_else(|| PathBuf::from("~/.config"))
@@ -37,7 +42,10 @@ pub fn enable() -> Result<(), std::io::Error> {
     }
 
     let source = config::paths::desktop_file();
-    if source.exists() {
+    if source.exists() && config::user::load().run_in_background {
+        let entry = fs::read_to_string(&source)?;
+        fs::write(target, background_entry(&entry))
+    } else if source.exists() {
         symlink(source, target)
     } else {
         Err(std::io::Error::new(
@@ -47,6 +55,32 @@ pub fn enable() -> Result<(), std::io::Error> {
     }
 }
 
+/// Rewrite the user's autostart entry, if there is one, after the
+/// background setting changed
+pub fn refresh() -> Result<(), std::io::Error> {
+    if get_autostart_path().symlink_metadata().is_ok() {
+        enable()
+    } else {
+        Ok(())
+    }
+}
+
+/// Desktop entry with its Exec line replaced by [`BACKGROUND_EXEC`]
+fn background_entry(entry: &str) -> String {
+    entry
+        .lines()
+        .map(|line| {
+            if line.starts_with("Exec=") {
+                BACKGROUND_EXEC
+            } else {
+                line
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
 /// Disable autostart by removing the desktop file
 pub fn disable() -> Result<(), std::io::Error> {
     let path = get_autostart_path();
@@ -0,0 +1,520 @@
+//! Encrypted backups with restic or borgmatic.
+//!
+//! The setup is kept in `~/.config/xero-toolkit/backup/`: the settings, a
+//! generated `backup.sh` that every action goes through (the page, the
+//! terminal and the systemd timer alike), and for borgmatic its config. The
+//! repository passphrase lives in the desktop keyring and is looked up with
+//! `secret-tool` when a command needs it, so it's never written to disk.
+
+use crate::i18n::n_;
+use crate::task_runner::script::quote;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Keyring attributes the passphrase is stored under.
+const SECRET_ATTRIBUTES: &str = "service xero-toolkit-backup";
+
+/// Snapshots kept by age when old ones are pruned after a backup.
+const KEEP_DAILY: u32 = 7;
+const KEEP_WEEKLY: u32 = 4;
+const KEEP_MONTHLY: u32 = 6;
+
+pub const SERVICE_NAME: &str = "xero-backup.service";
+pub const TIMER_NAME: &str = "xero-backup.timer";
+
+/// Backup program.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tool {
+    Restic,
+    Borgmatic,
+}
+
+impl Tool {
+    pub const ALL: [Tool; 2] = [Tool::Restic, Tool::Borgmatic];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Tool::Restic => "restic",
+            Tool::Borgmatic => "borgmatic",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|t| t.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Restic => "restic",
+            Tool::Borgmatic => "borgmatic",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Tool::Restic => n_("Single binary, backs up to disks, SFTP and any rclone remote"),
+            Tool::Borgmatic => n_("Borg with a config file, to disks or SSH servers running borg"),
+        }
+    }
+
+    /// Packages installed on setup. `libsecret` provides `secret-tool`.
+    pub fn packages(&self) -> &'static [&'static str] {
+        match self {
+            Tool::Restic => &["restic", "libsecret"],
+            Tool::Borgmatic => &["borgmatic", "libsecret"],
+        }
+    }
+
+    /// Destinations the tool can write to.
+    pub fn destinations(&self) -> &'static [Destination] {
+        match self {
+            Tool::Restic => &[Destination::Local, Destination::Sftp, Destination::Rclone],
+            Tool::Borgmatic => &[Destination::Local, Destination::Sftp],
+        }
+    }
+
+    /// Snapshots from the output of `backup.sh snapshots`, newest first.
+    pub fn parse_snapshots(&self, output: &str) -> Vec<Snapshot> {
+        let mut snapshots: Vec<Snapshot> = match self {
+            // `restic snapshots --compact`: a table with an 8 digit ID first
+            Tool::Restic => output
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.split_whitespace();
+                    let id = fields.next()?;
+                    if id.len() != 8 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return None;
+                    }
+                    let time = format!("{} {}", fields.next()?, fields.next()?);
+                    Some(Snapshot {
+                        id: id.to_string(),
+                        time,
+                    })
+                })
+                .collect(),
+            // `borg list` with a tab separated format
+            Tool::Borgmatic => output
+                .lines()
+                .filter_map(|line| {
+                    let (id, time) = line.split_once('\t')?;
+                    Some(Snapshot {
+                        id: id.to_string(),
+                        time: time.trim().to_string(),
+                    })
+                })
+                .collect(),
+        };
+        snapshots.reverse();
+        snapshots
+    }
+}
+
+/// Where the repository is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Destination {
+    Local,
+    Sftp,
+    Rclone,
+}
+
+impl Destination {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Destination::Local => "local",
+            Destination::Sftp => "sftp",
+            Destination::Rclone => "rclone",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        [Destination::Local, Destination::Sftp, Destination::Rclone]
+            .into_iter()
+            .find(|d| d.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Destination::Local => n_("Local Disk"),
+            Destination::Sftp => n_("SSH Server"),
+            Destination::Rclone => n_("rclone Remote"),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Destination::Local => n_("A folder on an internal or external drive"),
+            Destination::Sftp => n_("A server reachable with your SSH key, no password prompt"),
+            Destination::Rclone => n_("Any cloud storage set up with rclone config"),
+        }
+    }
+
+    /// Example location shown in the setup form.
+    pub fn placeholder(&self) -> &'static str {
+        match self {
+            Destination::Local => "/run/media/user/Backup/restic",
+            Destination::Sftp => "user@host:/srv/backup",
+            Destination::Rclone => "remote:backup",
+        }
+    }
+
+    /// Repository for `tool` at `location`, or `None` if `location` doesn't
+    /// fit the destination.
+    pub fn repository(&self, tool: Tool, location: &str) -> Option<String> {
+        let location = location.trim();
+        if location.is_empty() || location.contains('\n') {
+            return None;
+        }
+        match (self, tool) {
+            (Destination::Local, _) => location.starts_with('/').then(|| location.to_string()),
+            (Destination::Sftp, _) if !location.contains(':') => None,
+            (Destination::Sftp, Tool::Restic) => Some(format!("sftp:{}", location)),
+            (Destination::Sftp, Tool::Borgmatic) => Some(location.to_string()),
+            (Destination::Rclone, Tool::Restic) if location.contains(':') => {
+                Some(format!("rclone:{}", location))
+            }
+            (Destination::Rclone, _) => None,
+        }
+    }
+}
+
+/// How often the timer runs a backup.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Schedule {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl Schedule {
+    /// In the order of the schedule combo row.
+    pub const ALL: [Schedule; 3] = [Schedule::Off, Schedule::Daily, Schedule::Weekly];
+
+    /// systemd `OnCalendar=` expression, `None` when off.
+    pub fn calendar(&self) -> Option<&'static str> {
+        match self {
+            Schedule::Off => None,
+            Schedule::Daily => Some("daily"),
+            Schedule::Weekly => Some("weekly"),
+        }
+    }
+}
+
+/// A snapshot, or borg archive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    pub id: String,
+    pub time: String,
+}
+
+/// The backup setup.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Config {
+    pub tool: Tool,
+    pub repository: String,
+    /// Folders backed up, absolute.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub schedule: Schedule,
+}
+
+/// `~/.config/xero-toolkit/backup`.
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("xero-toolkit")
+        .join("backup")
+}
+
+fn config_file() -> PathBuf {
+    config_dir().join("config.toml")
+}
+
+/// The script every backup action runs through.
+pub fn script_path() -> PathBuf {
+    config_dir().join("backup.sh")
+}
+
+fn borgmatic_config() -> PathBuf {
+    config_dir().join("borgmatic.yaml")
+}
+
+fn user_unit_dir() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("systemd")
+        .join("user")
+}
+
+pub fn timer_path() -> PathBuf {
+    user_unit_dir().join(TIMER_NAME)
+}
+
+/// Folders suggested for a new setup: the usual home folders that exist.
+pub fn default_paths() -> Vec<String> {
+    [
+        dirs::document_dir(),
+        dirs::picture_dir(),
+        dirs::desktop_dir(),
+        dirs::audio_dir(),
+        dirs::video_dir(),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|dir| dir.is_dir() && Some(dir.as_path()) != dirs::home_dir().as_deref())
+    .map(|dir| dir.to_string_lossy().to_string())
+    .collect()
+}
+
+/// Quote a string for YAML.
+fn yaml_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+impl Config {
+    /// The saved setup, if backups were set up.
+    pub fn load() -> Option<Config> {
+        let text = std::fs::read_to_string(config_file()).ok()?;
+        toml::from_str(&text)
+            .map_err(|e| log::warn!("Ignoring invalid {}: {}", config_file().display(), e))
+            .ok()
+    }
+
+    /// Save the setup along with `backup.sh` and, for borgmatic, its config.
+    pub fn save(&self) -> Result<()> {
+        let dir = config_dir();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let text = toml::to_string_pretty(self).context("Failed to encode backup settings")?;
+        std::fs::write(config_file(), text)
+            .with_context(|| format!("Failed to write {}", config_file().display()))?;
+        std::fs::write(script_path(), self.script())
+            .with_context(|| format!("Failed to write {}", script_path().display()))?;
+        if self.tool == Tool::Borgmatic {
+            std::fs::write(borgmatic_config(), self.borgmatic_yaml())
+                .with_context(|| format!("Failed to write {}", borgmatic_config().display()))?;
+        }
+        Ok(())
+    }
+
+    /// `backup.sh`, taking `init`, `backup`, `snapshots` or
+    /// `restore <snapshot> <target>`. `init` leaves an existing repository
+    /// alone, so one from an earlier install can be picked up again.
+    fn script(&self) -> String {
+        let pass_command = format!("secret-tool lookup {}", SECRET_ATTRIBUTES);
+        let keep = format!(
+            "--keep-daily {} --keep-weekly {} --keep-monthly {}",
+            KEEP_DAILY, KEEP_WEEKLY, KEEP_MONTHLY
+        );
+        let paths: Vec<String> = self.paths.iter().map(|p| quote(p)).collect();
+        let (env, actions) = match self.tool {
+            Tool::Restic => (
+                format!(
+                    "export RESTIC_REPOSITORY={}\nexport RESTIC_PASSWORD_COMMAND={}\n",
+                    quote(&self.repository),
+                    quote(&pass_command)
+                ),
+                format!(
+                    r#"    init) restic cat config >/dev/null 2>&1 || restic init ;;
+    backup)
+        restic backup --exclude-caches --exclude "$HOME/.cache" {paths}
+        restic forget --prune {keep} ;;
+    snapshots) restic snapshots --compact ;;
+    restore) restic restore "$2" --target "$3" ;;
+"#,
+                    paths = paths.join(" "),
+                    keep = keep
+                ),
+            ),
+            Tool::Borgmatic => (
+                format!(
+                    "export BORG_REPO={}\nexport BORG_PASSCOMMAND={}\nconfig={}\n",
+                    quote(&self.repository),
+                    quote(&pass_command),
+                    quote(&borgmatic_config().to_string_lossy())
+                ),
+                r#"    init) borg info >/dev/null 2>&1 || borgmatic --config "$config" init --encryption repokey-blake2 ;;
+    backup) borgmatic --config "$config" --verbosity 1 --stats ;;
+    snapshots) borg list --format '{archive}{TAB}{time}{NL}' ;;
+    restore) cd "$3" && borg extract --list "::$2" ;;
+"#
+                .to_string(),
+            ),
+        };
+        format!(
+            "#!/bin/sh\n\
+             # Written by Xero Toolkit, changes are replaced from the Backup page.\n\
+             set -e\n\
+             {env}\n\
+             case \"$1\" in\n\
+             {actions}    *) echo \"Usage: $0 init|backup|snapshots|restore <snapshot> <target>\" >&2; exit 2 ;;\n\
+             esac\n",
+            env = env,
+            actions = actions
+        )
+    }
+
+    fn borgmatic_yaml(&self) -> String {
+        let mut yaml = String::from("# Written by Xero Toolkit from the Backup page.\n");
+        yaml.push_str("source_directories:\n");
+        for path in &self.paths {
+            yaml.push_str(&format!("    - {}\n", yaml_quote(path)));
+        }
+        yaml.push_str(&format!(
+            "repositories:\n    - path: {}\n      label: xero-toolkit\n",
+            yaml_quote(&self.repository)
+        ));
+        yaml.push_str(&format!(
+            "exclude_caches: true\nexclude_patterns:\n    - '~/.cache'\n\
+             encryption_passcommand: secret-tool lookup {}\n\
+             keep_daily: {}\nkeep_weekly: {}\nkeep_monthly: {}\n",
+            SECRET_ATTRIBUTES, KEEP_DAILY, KEEP_WEEKLY, KEEP_MONTHLY
+        ));
+        yaml
+    }
+
+    /// Write the user service and, if scheduled, its timer. An unscheduled
+    /// timer is disabled and removed by the caller.
+    pub fn write_units(&self) -> Result<()> {
+        let dir = user_unit_dir();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let service = format!(
+            "[Unit]\n\
+             Description=Xero Toolkit backup with {}\n\n\
+             [Service]\n\
+             Type=oneshot\n\
+             ExecStart=/bin/sh \"{}\" backup\n\
+             Nice=19\n\
+             IOSchedulingClass=idle\n",
+            self.tool.name(),
+            script_path().to_string_lossy().replace('%', "%%")
+        );
+        std::fs::write(dir.join(SERVICE_NAME), service)
+            .with_context(|| format!("Failed to write {}", SERVICE_NAME))?;
+
+        if let Some(calendar) = self.schedule.calendar() {
+            let timer = format!(
+                "[Unit]\n\
+                 Description=Scheduled Xero Toolkit backup\n\n\
+                 [Timer]\n\
+                 OnCalendar={}\n\
+                 Persistent=true\n\
+                 RandomizedDelaySec=30min\n\n\
+                 [Install]\n\
+                 WantedBy=timers.target\n",
+                calendar
+            );
+            std::fs::write(timer_path(), timer)
+                .with_context(|| format!("Failed to write {}", TIMER_NAME))?;
+        }
+        Ok(())
+    }
+
+    /// Snapshots in the repository, newest first. Runs the backup program,
+    /// which may reach a remote, so call it off the main thread.
+    pub fn snapshots(&self) -> Result<Vec<Snapshot>> {
+        let output = Command::new("sh")
+            .arg(script_path())
+            .arg("snapshots")
+            .output()
+            .context("Failed to run backup.sh")?;
+        if !output.status.success() {
+            anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        }
+        Ok(self
+            .tool
+            .parse_snapshots(&String::from_utf8_lossy(&output.stdout)))
+    }
+}
+
+/// Whether the backup timer is enabled.
+pub fn timer_enabled() -> bool {
+    Command::new("systemctl")
+        .args(["--user", "is-enabled", "--quiet", TIMER_NAME])
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Write `passphrase` to a file only the user can read, in the runtime
+/// directory, for [`STORE_PASSPHRASE_SCRIPT`] to move into the keyring.
+pub fn stage_passphrase(passphrase: &str) -> Result<PathBuf> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let dir = dirs::runtime_dir().unwrap_or_else(std::env::temp_dir);
+    let path = dir.join("xero-toolkit-backup-passphrase");
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    file.write_all(passphrase.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Script storing the passphrase staged in `$1` in the keyring, then
+/// deleting the file.
+pub const STORE_PASSPHRASE_SCRIPT: &str = r#"trap 'rm -f "$1"' EXIT
+secret-tool store --label='Xero Toolkit backup' service xero-toolkit-backup < "$1"
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repository() {
+        let local = Destination::Local;
+        assert_eq!(
+            local.repository(Tool::Restic, " /mnt/backup "),
+            Some("/mnt/backup".to_string())
+        );
+        assert_eq!(local.repository(Tool::Restic, "backup"), None);
+        assert_eq!(
+            Destination::Sftp.repository(Tool::Restic, "me@nas:/srv/backup"),
+            Some("sftp:me@nas:/srv/backup".to_string())
+        );
+        assert_eq!(
+            Destination::Sftp.repository(Tool::Borgmatic, "me@nas:/srv/backup"),
+            Some("me@nas:/srv/backup".to_string())
+        );
+        assert_eq!(
+            Destination::Rclone.repository(Tool::Borgmatic, "b2:x"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_snapshots() {
+        let restic = "ID        Time                 Host     Tags\n\
+                      -------------------------------------------\n\
+                      4bba301e  2024-05-01 12:00:00  desktop\n\
+                      9a1f0c22  2024-05-02 12:00:00  desktop\n\
+                      -------------------------------------------\n\
+                      2 snapshots\n";
+        let snapshots = Tool::Restic.parse_snapshots(restic);
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!(snapshots[0].id, "9a1f0c22");
+        assert_eq!(snapshots[0].time, "2024-05-02 12:00:00");
+
+        let borg = "desktop-2024-05-01\tWed, 2024-05-01 12:00:00\n";
+        assert_eq!(
+            Tool::Borgmatic.parse_snapshots(borg),
+            vec![Snapshot {
+                id: "desktop-2024-05-01".to_string(),
+                time: "Wed, 2024-05-01 12:00:00".to_string(),
+            }]
+        );
+    }
+}
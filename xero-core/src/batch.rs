@@ -0,0 +1,131 @@
+//! Several actions run as one sequence.
+//!
+//! Batch mode queues the sequences of the buttons picked on a page and runs
+//! them together. The package installs each sequence starts with are merged
+//! into one transaction up front; everything else runs afterwards in the
+//! order the actions were picked. Installs that come after other steps,
+//! such as adding a repository, stay where they are.
+
+use crate::task_runner::{Command, CommandSequence, CommandType};
+
+/// Flags a plain install step may carry.
+const INSTALL_FLAGS: &[&str] = &["--noconfirm", "--needed"];
+
+/// Packages of a pacman or AUR helper step that only installs packages,
+/// with no other flags that would change how they're installed.
+fn plain_install(command: &Command) -> Option<&[String]> {
+    let pacman = match command.command_type {
+        CommandType::Aur => true,
+        CommandType::Privileged => command.program == "pacman",
+        _ => false,
+    };
+    let (first, rest) = command.args.split_first()?;
+    if !pacman || first != "-S" {
+        return None;
+    }
+    rest.iter()
+        .all(|a| !a.starts_with('-') || INSTALL_FLAGS.contains(&a.as_str()))
+        .then_some(rest)
+}
+
+/// Combine `parts` into one sequence, merging their leading installs into a
+/// single step. It goes through the AUR helper if any of them did.
+pub fn combine(parts: Vec<CommandSequence>) -> CommandSequence {
+    let mut packages: Vec<String> = Vec::new();
+    let mut aur = false;
+    let mut requires = None;
+    let mut rest = CommandSequence::new();
+
+    for part in parts {
+        let mut leading = true;
+        for command in part.into_commands() {
+            let install = plain_install(&command).filter(|_| leading);
+            let Some(args) = install else {
+                leading = false;
+                rest = rest.then(command);
+                continue;
+            };
+            for package in args.iter().filter(|a| !a.starts_with('-')) {
+                if !packages.contains(package) {
+                    packages.push(package.clone());
+                }
+            }
+            aur |= command.command_type == CommandType::Aur;
+            requires = requires.max(command.requires);
+        }
+    }
+
+    if packages.is_empty() {
+        return rest.build();
+    }
+    let mut args = vec!["-S", "--noconfirm", "--needed"];
+    args.extend(packages.iter().map(String::as_str));
+    let builder = if aur {
+        Command::builder().aur()
+    } else {
+        Command::builder().privileged().program("pacman")
+    };
+    let mut builder = builder
+        .args(&args)
+        .description(&format!("Installing {} packages...", packages.len()));
+    if let Some(restart) = requires {
+        builder = builder.requires(restart);
+    }
+    CommandSequence::new()
+        .then(builder.build())
+        .append(rest)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn install(aur: bool, args: &[&str]) -> Command {
+        let builder = if aur {
+            Command::builder().aur()
+        } else {
+            Command::builder().privileged().program("pacman")
+        };
+        builder.args(args).description("Step").build()
+    }
+
+    fn enable(unit: &str) -> Command {
+        Command::builder()
+            .privileged()
+            .program("systemctl")
+            .args(&["enable", "--now", unit])
+            .description("Step")
+            .build()
+    }
+
+    #[test]
+    fn merges_leading_installs() {
+        let parts = vec![
+            CommandSequence::new()
+                .then(install(false, &["-S", "--noconfirm", "--needed", "docker"]))
+                .then(enable("docker.service"))
+                .build(),
+            CommandSequence::new()
+                .then(install(true, &["-S", "--noconfirm", "lact"]))
+                .then(enable("lactd.service"))
+                .then(install(false, &["-S", "--noconfirm", "late"]))
+                .build(),
+            CommandSequence::new()
+                .then(install(false, &["-S", "--noconfirm", "--asdeps", "dep"]))
+                .build(),
+        ];
+
+        let commands = combine(parts).into_commands();
+        assert_eq!(commands.len(), 5);
+        assert_eq!(commands[0].command_type, CommandType::Aur);
+        assert_eq!(
+            commands[0].args,
+            ["-S", "--noconfirm", "--needed", "docker", "lact"]
+        );
+        assert_eq!(commands[1].args, ["enable", "--now", "docker.service"]);
+        assert_eq!(commands[2].args, ["enable", "--now", "lactd.service"]);
+        assert_eq!(commands[3].args, ["-S", "--noconfirm", "late"]);
+        assert_eq!(commands[4].args[2], "--asdeps");
+    }
+}
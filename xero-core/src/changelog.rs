@@ -0,0 +1,187 @@
+//! Changelog between the installed toolkit and an available update.
+//!
+//! Uses GitHub's public Atom feeds so no API token or JSON parsing is
+//! needed: the commit feed for the git channel, the release feed for stable.
+
+use super::self_update::{Channel, UpdateCheck};
+use crate::{config, download};
+use log::warn;
+use regex::Regex;
+use std::path::PathBuf;
+
+/// One change shown to the user before updating.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangelogEntry {
+    /// Short commit hash or release tag.
+    pub version: String,
+    /// Commit subject or release title.
+    pub title: String,
+    /// Release notes as plain text; empty for commits.
+    pub body: String,
+}
+
+/// Changes between the installed and latest version, newest first.
+#[derive(Clone, Debug, Default)]
+pub struct Changelog {
+    pub entries: Vec<ChangelogEntry>,
+    /// The installed version wasn't found in the feed, so older changes
+    /// may be missing from `entries`.
+    pub truncated: bool,
+}
+
+/// Fetch the changelog for an update check. Blocks on the network.
+pub fn fetch(check: &UpdateCheck) -> Option<Changelog> {
+    let repo = config::links::TOOLKIT_REPO.trim_end_matches(".git");
+    let url = match check.channel {
+        Channel::Git => format!("{}/commits.atom", repo),
+        Channel::Stable => format!("{}/releases.atom", repo),
+    };
+
+    let dest = dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("xero-toolkit")
+        .join("changelog.atom");
+    let text = match download::fetch_text_blocking(&url, &dest) {
+        Ok(text) => text,
+        Err(e) => {
+            warn!("Failed to fetch changelog from {}: {:#}", url, e);
+            return None;
+        }
+    };
+
+    let feed = parse_feed(&text);
+    Some(changes_since(
+        feed,
+        check.channel,
+        check.installed.as_deref(),
+    ))
+}
+
+/// Raw Atom entry.
+struct FeedEntry {
+    id: String,
+    title: String,
+    content: String,
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Turn the escaped HTML of a release body into readable plain text.
+fn html_to_text(html: &str) -> String {
+    let tags = Regex::new(r"<[^>]+>").expect("valid regex");
+    let text = unescape(&tags.replace_all(&unescape(html), ""));
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn parse_feed(xml: &str) -> Vec<FeedEntry> {
+    let entry = Regex::new(r"(?s)<entry>(.*?)</entry>").expect("valid regex");
+    let id = Regex::new(r"(?s)<id>(.*?)</id>").expect("valid regex");
+    let title = Regex::new(r"(?s)<title[^>]*>(.*?)</title>").expect("valid regex");
+    let content = Regex::new(r"(?s)<content[^>]*>(.*?)</content>").expect("valid regex");
+
+    let field = |re: &Regex, text: &str| {
+        re.captures(text)
+            .map(|c| c[1].trim().to_string())
+            .unwrap_or_default()
+    };
+
+    entry
+        .captures_iter(xml)
+        .map(|c| FeedEntry {
+            id: field(&id, &c[1]),
+            title: unescape(&field(&title, &c[1])),
+            content: field(&content, &c[1]),
+        })
+        .collect()
+}
+
+/// Keep the feed entries newer than `installed`.
+///
+/// Commit ids end in `Commit/<sha>`, release ids in `/<tag>`.
+fn changes_since(feed: Vec<FeedEntry>, channel: Channel, installed: Option<&str>) -> Changelog {
+    let mut changelog = Changelog::default();
+
+    for entry in feed {
+        let version = entry.id.rsplit('/').next().unwrap_or_default().to_string();
+        if installed.is_some_and(|i| i == version) {
+            return changelog;
+        }
+
+        changelog.entries.push(match channel {
+            Channel::Git => ChangelogEntry {
+                version: version[..version.len().min(7)].to_string(),
+                title: entry.title.lines().next().unwrap_or_default().to_string(),
+                body: String::new(),
+            },
+            Channel::Stable => ChangelogEntry {
+                version,
+                title: entry.title,
+                body: html_to_text(&entry.content),
+            },
+        });
+    }
+
+    changelog.truncated = true;
+    changelog
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMITS: &str = r#"<feed>
+  <entry>
+    <id>tag:github.com,2008:Grit::Commit/ccc333</id>
+    <title>
+        Fix &quot;update&quot; dialog
+    </title>
+  </entry>
+  <entry>
+    <id>tag:github.com,2008:Grit::Commit/bbb222</id>
+    <title>Add power page</title>
+  </entry>
+  <entry>
+    <id>tag:github.com,2008:Grit::Commit/aaa111</id>
+    <title>Initial commit</title>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn stops_at_installed_commit() {
+        let log = changes_since(parse_feed(COMMITS), Channel::Git, Some("aaa111"));
+        assert!(!log.truncated);
+        assert_eq!(log.entries.len(), 2);
+        assert_eq!(log.entries[0].version, "ccc333");
+        assert_eq!(log.entries[0].title, "Fix \"update\" dialog");
+    }
+
+    #[test]
+    fn marks_unknown_installed_version_as_truncated() {
+        let log = changes_since(parse_feed(COMMITS), Channel::Git, Some("zzz999"));
+        assert!(log.truncated);
+        assert_eq!(log.entries.len(), 3);
+    }
+
+    #[test]
+    fn release_notes_become_plain_text() {
+        let feed = r#"<entry>
+  <id>tag:github.com,2008:Repository/1/v0.2.0</id>
+  <title>v0.2.0</title>
+  <content type="html">&lt;h2&gt;Changes&lt;/h2&gt;
+&lt;ul&gt;&lt;li&gt;Faster &amp;amp; safer updates&lt;/li&gt;&lt;/ul&gt;</content>
+</entry>"#;
+        let log = changes_since(parse_feed(feed), Channel::Stable, Some("v0.1.0"));
+        assert_eq!(log.entries[0].version, "v0.2.0");
+        assert_eq!(log.entries[0].body, "Changes\nFaster & safer updates");
+    }
+}
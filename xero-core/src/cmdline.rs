@@ -0,0 +1,116 @@
+//! Kernel command line parameters.
+//!
+//! Adds parameters through whichever boot setup is in use: GRUB's
+//! `/etc/default/grub`, `/etc/kernel/cmdline` for unified kernel images, or
+//! the `options` line of systemd-boot entries.
+
+use std::path::Path;
+
+/// Where the kernel command line is configured.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bootloader {
+    Grub,
+    /// `/etc/kernel/cmdline`, baked into unified kernel images by mkinitcpio.
+    KernelCmdline,
+    SystemdBoot,
+}
+
+const LOADER_ENTRIES: [&str; 2] = ["/boot/loader/entries", "/efi/loader/entries"];
+
+impl Bootloader {
+    /// Human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Bootloader::Grub => "GRUB",
+            Bootloader::KernelCmdline => "/etc/kernel/cmdline",
+            Bootloader::SystemdBoot => "systemd-boot",
+        }
+    }
+
+    /// Shell script adding each of `params` that isn't there yet, then
+    /// regenerating whatever the bootloader reads.
+    pub fn add_params_script(&self, params: &[&str]) -> String {
+        let (file, line, regenerate) = match self {
+            Bootloader::Grub => (
+                "/etc/default/grub",
+                "GRUB_CMDLINE_LINUX_DEFAULT=",
+                "; grub-mkconfig -o /boot/grub/grub.cfg",
+            ),
+            Bootloader::KernelCmdline => ("/etc/kernel/cmdline", "", "; mkinitcpio -P"),
+            // Entries are read at boot, nothing to regenerate
+            Bootloader::SystemdBoot => ("\"$f\"", "options", ""),
+        };
+
+        let mut edits = String::new();
+        for param in params {
+            let sed = match self {
+                // Insert after the opening quote
+                Bootloader::Grub => format!("s/^\\({}[\"']\\)/\\1{} /", line, param),
+                Bootloader::KernelCmdline => format!("1s/$/ {}/", param),
+                Bootloader::SystemdBoot => format!("/^{}/ s/$/ {}/", line, param),
+            };
+            edits.push_str(&format!(
+                "; grep -qw -- '{param}' {file} || sed -i \"{sed}\" {file}",
+                param = param,
+                file = file,
+                sed = sed.replace('"', "\\\"")
+            ));
+        }
+
+        match self {
+            Bootloader::SystemdBoot => format!(
+                "set -e; for f in {}; do [ -f \"$f\" ] || continue{}; done",
+                LOADER_ENTRIES.map(|d| format!("{}/*.conf", d)).join(" "),
+                edits
+            ),
+            _ => format!("set -e{}{}", edits, regenerate),
+        }
+    }
+}
+
+/// The boot setup in use, if it's one the toolkit can edit.
+pub fn detect() -> Option<Bootloader> {
+    if Path::new("/etc/default/grub").exists() && super::microcode::uses_grub() {
+        Some(Bootloader::Grub)
+    } else if Path::new("/etc/kernel/cmdline").exists() {
+        Some(Bootloader::KernelCmdline)
+    } else if LOADER_ENTRIES.iter().any(|d| Path::new(d).is_dir()) {
+        Some(Bootloader::SystemdBoot)
+    } else {
+        None
+    }
+}
+
+/// Whether `param` appears as a whole word in `cmdline`.
+pub fn has_param(cmdline: &str, param: &str) -> bool {
+    cmdline.split_whitespace().any(|p| p == param)
+}
+
+/// Command line the running kernel was booted with.
+pub fn current() -> String {
+    std::fs::read_to_string("/proc/cmdline").unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_param() {
+        let cmdline = "BOOT_IMAGE=/vmlinuz-linux root=UUID=abc rw amd_iommu=on iommu=pt";
+        assert!(has_param(cmdline, "iommu=pt"));
+        assert!(has_param(cmdline, "amd_iommu=on"));
+        assert!(!has_param(cmdline, "intel_iommu=on"));
+    }
+
+    #[test]
+    fn test_grub_script() {
+        let script = Bootloader::Grub.add_params_script(&["iommu=pt"]);
+        assert_eq!(
+            script,
+            "set -e; grep -qw -- 'iommu=pt' /etc/default/grub || \
+             sed -i \"s/^\\(GRUB_CMDLINE_LINUX_DEFAULT=[\\\"']\\)/\\1iommu=pt /\" \
+             /etc/default/grub; grub-mkconfig -o /boot/grub/grub.cfg"
+        );
+    }
+}
@@ -0,0 +1,352 @@
+//! Docker Compose stack templates.
+//!
+//! Each stack ships a compose file that reads its ports and paths from an
+//! `.env` file next to it. Deploying only writes `KEY=value` lines from the
+//! form, so the compose file on disk stays as shipped and users can edit
+//! either one later.
+
+use crate::i18n::n_;
+use anyhow::{bail, Context, Result};
+use log::info;
+use std::path::{Path, PathBuf};
+
+/// What a form field holds, which decides how it's validated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Host port to publish.
+    Port,
+    /// Host directory to bind mount. Created before the stack starts so
+    /// Docker doesn't create it owned by root.
+    Path,
+}
+
+/// A value the user can change before deploying.
+#[derive(Clone, Copy, Debug)]
+pub struct Field {
+    /// Variable name used in the compose file.
+    pub key: &'static str,
+    pub label: &'static str,
+    pub kind: FieldKind,
+    /// Default value. `{dir}` is replaced with the stack directory and a
+    /// leading `~` with the home directory.
+    pub default: &'static str,
+}
+
+/// A curated compose stack.
+#[derive(Clone, Copy, Debug)]
+pub struct Stack {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub compose: &'static str,
+    pub fields: &'static [Field],
+    /// Address of the web UI once running, `{KEY}` replaced by field values.
+    pub url: &'static str,
+}
+
+const fn port(key: &'static str, label: &'static str, default: &'static str) -> Field {
+    Field {
+        key,
+        label,
+        kind: FieldKind::Port,
+        default,
+    }
+}
+
+const fn path(key: &'static str, label: &'static str, default: &'static str) -> Field {
+    Field {
+        key,
+        label,
+        kind: FieldKind::Path,
+        default,
+    }
+}
+
+const PORTAINER_COMPOSE: &str = r#"services:
+  portainer:
+    image: portainer/portainer-ce:lts
+    container_name: portainer
+    restart: unless-stopped
+    ports:
+      - "${HTTPS_PORT}:9443"
+    volumes:
+      - /var/run/docker.sock:/var/run/docker.sock
+      - ${DATA_DIR}:/data
+"#;
+
+const JELLYFIN_COMPOSE: &str = r#"services:
+  jellyfin:
+    image: jellyfin/jellyfin:latest
+    container_name: jellyfin
+    restart: unless-stopped
+    user: "${PUID}:${PGID}"
+    ports:
+      - "${HTTP_PORT}:8096"
+    volumes:
+      - ${CONFIG_DIR}:/config
+      - ${CACHE_DIR}:/cache
+      - ${MEDIA_DIR}:/media:ro
+"#;
+
+// The master container creates the other Nextcloud containers itself and
+// only works with exactly this container and volume name.
+const NEXTCLOUD_AIO_COMPOSE: &str = r#"services:
+  nextcloud-aio-mastercontainer:
+    image: ghcr.io/nextcloud-releases/all-in-one:latest
+    container_name: nextcloud-aio-mastercontainer
+    init: true
+    restart: always
+    ports:
+      - "80:80"
+      - "${ADMIN_PORT}:8080"
+      - "8443:8443"
+    environment:
+      NEXTCLOUD_DATADIR: ${DATA_DIR}
+    volumes:
+      - nextcloud_aio_mastercontainer:/mnt/docker-aio-config
+      - /var/run/docker.sock:/var/run/docker.sock:ro
+volumes:
+  nextcloud_aio_mastercontainer:
+    name: nextcloud_aio_mastercontainer
+"#;
+
+// Host networking so device discovery (mDNS, UPnP, Bluetooth) works.
+const HOME_ASSISTANT_COMPOSE: &str = r#"services:
+  homeassistant:
+    image: ghcr.io/home-assistant/home-assistant:stable
+    container_name: homeassistant
+    restart: unless-stopped
+    privileged: true
+    network_mode: host
+    environment:
+      TZ: ${TZ}
+    volumes:
+      - ${CONFIG_DIR}:/config
+      - /etc/localtime:/etc/localtime:ro
+      - /run/dbus:/run/dbus:ro
+"#;
+
+// Host networking so it reaches Ollama, which only listens on localhost.
+const OPEN_WEBUI_COMPOSE: &str = r#"services:
+  open-webui:
+    image: ghcr.io/open-webui/open-webui:main
+    container_name: open-webui
+    restart: unless-stopped
+    network_mode: host
+    environment:
+      PORT: ${HTTP_PORT}
+      OLLAMA_BASE_URL: http://127.0.0.1:11434
+    volumes:
+      - ${DATA_DIR}:/app/backend/data
+"#;
+
+/// All stacks offered by the deploy dialog.
+pub const STACKS: &[Stack] = &[
+    Stack {
+        id: "portainer",
+        name: "Portainer",
+        description: n_("Web UI for managing containers, images and volumes"),
+        compose: PORTAINER_COMPOSE,
+        fields: &[
+            port("HTTPS_PORT", "Web UI port (HTTPS)", "9443"),
+            path("DATA_DIR", "Data folder", "{dir}/data"),
+        ],
+        url: "https://localhost:{HTTPS_PORT}",
+    },
+    Stack {
+        id: "jellyfin",
+        name: "Jellyfin",
+        description: n_("Media server for movies, shows and music"),
+        compose: JELLYFIN_COMPOSE,
+        fields: &[
+            port("HTTP_PORT", "Web UI port", "8096"),
+            path("CONFIG_DIR", "Config folder", "{dir}/config"),
+            path("CACHE_DIR", "Cache folder", "{dir}/cache"),
+            path("MEDIA_DIR", "Media library", "~/Videos"),
+        ],
+        url: "http://localhost:{HTTP_PORT}",
+    },
+    Stack {
+        id: "nextcloud-aio",
+        name: "Nextcloud AIO",
+        description: n_("Nextcloud with Office, Talk and backups, managed from one container"),
+        compose: NEXTCLOUD_AIO_COMPOSE,
+        fields: &[
+            port("ADMIN_PORT", "Setup interface port", "8080"),
+            path("DATA_DIR", "Nextcloud data folder", "{dir}/data"),
+        ],
+        url: "https://localhost:{ADMIN_PORT}",
+    },
+    Stack {
+        id: "home-assistant",
+        name: "Home Assistant",
+        description: n_("Home automation hub, on the host network for device discovery"),
+        compose: HOME_ASSISTANT_COMPOSE,
+        fields: &[path("CONFIG_DIR", "Config folder", "{dir}/config")],
+        url: "http://localhost:8123",
+    },
+    Stack {
+        id: "open-webui",
+        name: "Open WebUI",
+        description: n_("ChatGPT-style web interface for local Ollama models"),
+        compose: OPEN_WEBUI_COMPOSE,
+        fields: &[
+            port("HTTP_PORT", "Web UI port", "3000"),
+            path("DATA_DIR", "Data folder", "{dir}/data"),
+        ],
+        url: "http://localhost:{HTTP_PORT}",
+    },
+];
+
+/// Look up a stack by id.
+pub fn find(id: &str) -> Option<&'static Stack> {
+    STACKS.iter().find(|s| s.id == id)
+}
+
+/// Replace a leading `~` with `home`.
+fn expand_home(value: &str, home: &Path) -> PathBuf {
+    match value.strip_prefix('~') {
+        Some(rest) => home.join(rest.trim_start_matches('/')),
+        None => PathBuf::from(value),
+    }
+}
+
+fn home_dir() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("/root"))
+}
+
+/// Check a form value and return it the way it goes into `.env`.
+fn validate(field: &Field, value: &str, home: &Path) -> Result<String> {
+    let value = value.trim();
+    match field.kind {
+        FieldKind::Port => match value.parse::<u16>() {
+            Ok(port) if port > 0 => Ok(port.to_string()),
+            _ => bail!("{} must be a port number between 1 and 65535", field.label),
+        },
+        FieldKind::Path => {
+            if value.is_empty() || value.contains(['\n', '"', '$']) {
+                bail!("{} is not a usable folder path", field.label);
+            }
+            let path = expand_home(value, home);
+            if !path.is_absolute() {
+                bail!("{} must be an absolute path or start with ~", field.label);
+            }
+            Ok(path.display().to_string())
+        }
+    }
+}
+
+/// `.env` contents for validated `values`.
+fn render_env(values: &[(&str, String)]) -> String {
+    let mut env =
+        String::from("# Generated by Xero Toolkit, edit and run `docker compose up -d`\n");
+    for (key, value) in values {
+        env.push_str(&format!("{}=\"{}\"\n", key, value));
+    }
+    env
+}
+
+/// The system time zone, e.g. `Europe/Berlin`, from the `/etc/localtime` link.
+fn time_zone() -> String {
+    std::fs::read_link("/etc/localtime")
+        .ok()
+        .and_then(|target| {
+            let target = target.to_string_lossy().into_owned();
+            target
+                .split_once("zoneinfo/")
+                .map(|(_, zone)| zone.to_string())
+        })
+        .unwrap_or_else(|| "UTC".to_string())
+}
+
+impl Stack {
+    /// Directory the compose file is written to, `~/containers/<id>`.
+    pub fn dir(&self) -> PathBuf {
+        home_dir().join("containers").join(self.id)
+    }
+
+    /// Default for `field` with placeholders filled in, as shown in the form.
+    pub fn default_value(&self, field: &Field) -> String {
+        field
+            .default
+            .replace("{dir}", &self.dir().display().to_string())
+    }
+
+    /// Web UI address for the given form values.
+    pub fn url(&self, values: &[(&str, String)]) -> String {
+        values
+            .iter()
+            .fold(self.url.to_string(), |url, (key, value)| {
+                url.replace(&format!("{{{}}}", key), value)
+            })
+    }
+
+    /// Validate `values` (one per field, in order), then write the compose
+    /// file and `.env` and create the bind-mounted folders. Returns the
+    /// validated values.
+    pub fn write(&self, values: &[String]) -> Result<Vec<(&'static str, String)>> {
+        let home = home_dir();
+        let mut validated = Vec::with_capacity(self.fields.len() + 3);
+        for (field, value) in self.fields.iter().zip(values) {
+            validated.push((field.key, validate(field, value, &home)?));
+        }
+
+        // Always available to templates, so containers can run as the user
+        // and log in local time.
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        validated.push(("PUID", uid.to_string()));
+        validated.push(("PGID", gid.to_string()));
+        validated.push(("TZ", time_zone()));
+
+        let dir = self.dir();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+        for (field, (_, value)) in self.fields.iter().zip(&validated) {
+            if field.kind == FieldKind::Path {
+                std::fs::create_dir_all(value)
+                    .with_context(|| format!("Failed to create {}", value))?;
+            }
+        }
+
+        std::fs::write(dir.join("docker-compose.yml"), self.compose)
+            .context("Failed to write docker-compose.yml")?;
+        std::fs::write(dir.join(".env"), render_env(&validated)).context("Failed to write .env")?;
+
+        info!("Wrote {} stack to {}", self.name, dir.display());
+        Ok(validated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        let home = Path::new("/home/user");
+        let port = port("PORT", "Port", "80");
+        assert_eq!(validate(&port, " 8096 ", home).unwrap(), "8096");
+        assert!(validate(&port, "0", home).is_err());
+        assert!(validate(&port, "70000", home).is_err());
+
+        let dir = path("DIR", "Folder", "");
+        assert_eq!(
+            validate(&dir, "~/Videos", home).unwrap(),
+            "/home/user/Videos"
+        );
+        assert!(validate(&dir, "media", home).is_err());
+        assert!(validate(&dir, "/srv/$HOME", home).is_err());
+    }
+
+    #[test]
+    fn test_stack_templates() {
+        for stack in STACKS {
+            for field in stack.fields {
+                let var = format!("${{{}}}", field.key);
+                assert!(stack.compose.contains(&var), "{} uses {}", stack.id, var);
+            }
+        }
+        let env = render_env(&[("HTTP_PORT", "8096".to_string())]);
+        assert!(env.ends_with("HTTP_PORT=\"8096\"\n"));
+    }
+}
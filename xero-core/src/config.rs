@@ -0,0 +1,389 @@
+//! Paths, links and saved preferences shared by the GUI and the CLI.
+
+/// Application information constants.
+pub mod app_info {
+    pub const NAME: &str = "xero-toolkit";
+    pub const ID: &str = "xyz.xerolinux.xero-toolkit";
+    pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+}
+
+/// External links.
+pub mod links {
+    pub const DISCORD: &str = "https://discord.gg/2pdhYusbKV";
+    pub const YOUTUBE: &str = "https://www.youtube.com/@MurderFromMars";
+    pub const GITHUB: &str = "https://github.com/MurderFromMars";
+    pub const TOOLKIT_REPO: &str = "https://github.com/MurderFromMars/CyberXero-Toolkit.git";
+}
+
+/// Binary paths for system executables.
+pub mod paths {
+    use std::path::PathBuf;
+
+    /// Path to the xero-authd daemon binary.
+    pub const DAEMON: &str = "/opt/xero-toolkit/xero-authd";
+
+    /// Path to the xero-auth client binary.
+    pub const CLIENT: &str = "/opt/xero-toolkit/xero-auth";
+
+    /// Path to the sources directory (contains scripts and systemd).
+    #[allow(dead_code)]
+    pub const SOURCES: &str = "/opt/xero-toolkit/sources";
+
+    /// Path to the scripts directory.
+    pub const SCRIPTS: &str = "/opt/xero-toolkit/sources/scripts";
+
+    /// Path to the systemd units directory.
+    pub const SYSTEMD: &str = "/opt/xero-toolkit/sources/systemd";
+
+    /// Path to the pinned checksums of third-party downloads.
+    pub const PINS: &str = "/opt/xero-toolkit/sources/pins/artifacts.pins";
+
+    /// Path to the compiled translations.
+    pub const LOCALE: &str = "/opt/xero-toolkit/locale";
+
+    /// Path to the desktop file in system applications.
+    pub const DESKTOP_FILE: &str = "/usr/share/applications/xero-toolkit.desktop";
+
+    /// Path to the system-wide autostart desktop file.
+    pub const SYSTEM_AUTOSTART: &str = "/etc/xdg/autostart/xero-toolkit.desktop";
+
+    /// Get the daemon path as a PathBuf.
+    pub fn daemon() -> PathBuf {
+        PathBuf::from(DAEMON)
+    }
+
+    /// Get the client path as a PathBuf.
+    pub fn client() -> PathBuf {
+        PathBuf::from(CLIENT)
+    }
+
+    /// Get the sources path as a PathBuf.
+    #[allow(dead_code)]
+    pub fn sources() -> PathBuf {
+        PathBuf::from(SOURCES)
+    }
+
+    /// Get the scripts path as a PathBuf.
+    pub fn scripts() -> PathBuf {
+        PathBuf::from(SCRIPTS)
+    }
+
+    /// Get the systemd units path as a PathBuf.
+    pub fn systemd() -> PathBuf {
+        PathBuf::from(SYSTEMD)
+    }
+
+    /// Get the pinned checksums path as a PathBuf.
+    pub fn pins() -> PathBuf {
+        PathBuf::from(PINS)
+    }
+
+    /// Get the desktop file path as a PathBuf.
+    pub fn desktop_file() -> PathBuf {
+        PathBuf::from(DESKTOP_FILE)
+    }
+
+    /// Get the system autostart path as a PathBuf.
+    pub fn system_autostart() -> PathBuf {
+        PathBuf::from(SYSTEM_AUTOSTART)
+    }
+}
+
+/// Cached environment variables read at startup.
+pub mod env {
+    use std::sync::OnceLock;
+
+    static ENV: OnceLock<Env> = OnceLock::new();
+
+    /// Cached environment variables.
+    pub struct Env {
+        pub user: String,
+        pub home: String,
+    }
+
+    impl Env {
+        fn new() -> anyhow::Result<Self> {
+            Ok(Self {
+                user: std::env::var("USER")
+                    .map_err(|_| anyhow::anyhow!("USER environment variable is not set"))?,
+                home: std::env::var("HOME")
+                    .map_err(|_| anyhow::anyhow!("HOME environment variable is not set"))?,
+            })
+        }
+    }
+
+    /// Initialize environment variables. Must be called at application startup.
+    /// Returns an error if required environment variables (USER, HOME) are not set.
+    pub fn init() -> anyhow::Result<()> {
+        ENV.set(Env::new()?)
+            .map_err(|_| anyhow::anyhow!("Environment variables already initialized"))?;
+        Ok(())
+    }
+
+    /// Get the cached environment variables.
+    /// Panics if not initialized (call `init()` at application startup).
+    pub fn get() -> &'static Env {
+        ENV.get()
+            .expect("Environment variables not initialized. Call config::env::init() at startup.")
+    }
+}
+
+/// User preferences saved in `~/.config/xero-toolkit/settings.toml`.
+pub mod user {
+    use anyhow::Context;
+    use serde::{Deserialize, Serialize};
+    use std::path::PathBuf;
+
+    /// Saved preferences. Missing fields take their defaults.
+    #[derive(Debug, Default, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct Settings {
+        /// Pinned actions, as `category/id`.
+        pub favorites: Vec<String>,
+        /// Preferred AUR helper (`paru`, `yay` or `pikaur`); detected when unset.
+        pub aur_helper: Option<String>,
+        /// UI language, such as `de` or `pt_BR`; follows the system when unset.
+        pub language: Option<String>,
+        /// Keep running in the system tray after the window is closed, and
+        /// notify about available updates.
+        pub run_in_background: bool,
+        /// Country code rate-mirrors starts ranking mirrors from, e.g. `DE`.
+        pub mirror_country: Option<String>,
+        /// Whether the first-run setup has been completed or skipped.
+        pub setup_done: bool,
+        /// Seasonal overlay effects.
+        pub seasonal: SeasonalSettings,
+        /// Color scheme, accent color and custom styling.
+        pub appearance: AppearanceSettings,
+        /// Messages sent to a phone when long tasks finish.
+        pub notifier: NotifierSettings,
+        /// Packages the orphan cleanup leaves alone, though nothing needs them.
+        pub keep_orphans: Vec<String>,
+        /// Where apps offered both natively and on Flathub are installed
+        /// from; each app's usual source when unset.
+        pub app_source: Option<AppSource>,
+        /// Terminal emulator interactive commands open in; the built-in
+        /// terminal when unset.
+        pub terminal: Option<TerminalEmulator>,
+        /// How privileged steps get root.
+        pub privilege: PrivilegeBackend,
+    }
+
+    /// Native package or Flatpak, for apps offered both ways.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum AppSource {
+        /// From the Arch repos or the AUR.
+        Native,
+        /// From Flathub.
+        Flatpak,
+    }
+
+    impl AppSource {
+        pub fn name(&self) -> &'static str {
+            match self {
+                AppSource::Native => crate::i18n::n_("Native package"),
+                AppSource::Flatpak => "Flatpak",
+            }
+        }
+    }
+
+    /// Terminal emulators interactive commands can open in.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    pub enum TerminalEmulator {
+        Konsole,
+        Alacritty,
+        Kitty,
+        GnomeTerminal,
+        Foot,
+    }
+
+    /// How privileged steps are run as root.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum PrivilegeBackend {
+        /// A xero-auth session for the whole sequence, authorized once.
+        #[default]
+        Session,
+        /// pkexec for every step, asking each time.
+        Pkexec,
+        /// sudo for every step, asking through `SUDO_ASKPASS` when it's set.
+        Sudo,
+    }
+
+    /// Light or dark style.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum ColorScheme {
+        /// Follow the desktop's preference.
+        #[default]
+        System,
+        Light,
+        Dark,
+    }
+
+    /// Accent color, one of the libadwaita accent colors.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Accent {
+        /// Follow the desktop's accent color.
+        #[default]
+        System,
+        Blue,
+        Teal,
+        Green,
+        Yellow,
+        Orange,
+        Red,
+        Pink,
+        Purple,
+        Slate,
+    }
+
+    /// Appearance preferences.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct AppearanceSettings {
+        pub color_scheme: ColorScheme,
+        pub accent: Accent,
+        /// Load the toolkit's own stylesheet. Turning it off leaves only the
+        /// system theme.
+        pub custom_css: bool,
+    }
+
+    impl Default for AppearanceSettings {
+        fn default() -> Self {
+            Self {
+                color_scheme: ColorScheme::default(),
+                accent: Accent::default(),
+                custom_css: true,
+            }
+        }
+    }
+
+    /// Service that receives task notifications.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum NotifierService {
+        #[default]
+        Off,
+        /// An ntfy topic, e.g. `https://ntfy.sh/my-topic`.
+        Ntfy,
+        /// A Gotify server, with an application token.
+        Gotify,
+        /// Any URL taking a JSON POST.
+        Webhook,
+    }
+
+    /// Task notification preferences.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct NotifierSettings {
+        pub service: NotifierService,
+        /// Topic, server or webhook URL.
+        pub url: String,
+        /// Gotify application token.
+        pub token: String,
+        /// Only sequences running at least this long are reported.
+        pub min_minutes: u32,
+    }
+
+    impl Default for NotifierSettings {
+        fn default() -> Self {
+            Self {
+                service: NotifierService::default(),
+                url: String::new(),
+                token: String::new(),
+                min_minutes: 5,
+            }
+        }
+    }
+
+    /// When a seasonal effect is shown.
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum EffectMode {
+        /// During its season, e.g. snow in December.
+        #[default]
+        Automatic,
+        /// All year round.
+        Always,
+        /// Never.
+        Off,
+    }
+
+    /// Seasonal effect preferences.
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    #[serde(default)]
+    pub struct SeasonalSettings {
+        /// Show seasonal effects at all.
+        pub enabled: bool,
+        /// Per-effect mode, by effect id (`snow`, `halloween`). Effects not
+        /// listed are automatic.
+        pub effects: std::collections::BTreeMap<String, EffectMode>,
+        /// Particle count as a percentage of each effect's default.
+        pub density: u32,
+        /// Frame rate cap for the animations.
+        pub max_fps: u32,
+    }
+
+    impl Default for SeasonalSettings {
+        fn default() -> Self {
+            Self {
+                enabled: true,
+                effects: Default::default(),
+                density: 100,
+                max_fps: 60,
+            }
+        }
+    }
+
+    impl SeasonalSettings {
+        /// Mode of the effect with `id`.
+        pub fn mode(&self, id: &str) -> EffectMode {
+            self.effects.get(id).copied().unwrap_or_default()
+        }
+    }
+
+    /// Path of the settings file.
+    pub fn path() -> PathBuf {
+        dirs::config_dir()
+            .unwrap_or_else(|| PathBuf::from("~/.config"))
+            .join("xero-toolkit")
+            .join("settings.toml")
+    }
+
+    /// Load settings, falling back to defaults if the file is missing or invalid.
+    pub fn load() -> Settings {
+        let Ok(text) = std::fs::read_to_string(path()) else {
+            return Settings::default();
+        };
+        toml::from_str(&text).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid {}: {}", path().display(), e);
+            Settings::default()
+        })
+    }
+
+    /// Write settings, replacing the file atomically.
+    pub fn save(settings: &Settings) -> anyhow::Result<()> {
+        let path = path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        let text = toml::to_string_pretty(settings).context("Failed to encode settings")?;
+        let tmp = path.with_extension("toml.tmp");
+        std::fs::write(&tmp, text).with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Load the settings, apply `change` and save them.
+    pub fn update(change: impl FnOnce(&mut Settings)) -> anyhow::Result<()> {
+        let mut settings = load();
+        change(&mut settings);
+        save(&settings)
+    }
+}
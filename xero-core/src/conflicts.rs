@@ -0,0 +1,274 @@
+//! Package stacks that can't be installed side by side.
+//!
+//! pacman refuses to install a package that conflicts with an installed one
+//! when it runs non-interactively, which stops a sequence halfway. The
+//! known alternatives are grouped here, and a sequence is checked before it
+//! starts: when it installs one stack of a group while another is
+//! installed, the user picks whether to replace the installed one.
+
+use crate::estimate::Targets;
+use crate::manifest;
+use crate::package;
+use crate::task_runner::{Command, CommandType};
+use std::collections::HashSet;
+
+/// One way of providing something, as the packages that make it up.
+pub struct Stack {
+    pub name: &'static str,
+    pub packages: &'static [&'static str],
+}
+
+/// Stacks providing the same thing, of which only one can be installed.
+pub struct Group {
+    pub what: &'static str,
+    pub stacks: &'static [Stack],
+}
+
+pub static GROUPS: &[Group] = &[
+    Group {
+        what: "Docker CLI",
+        stacks: &[
+            Stack {
+                name: "Docker",
+                packages: &["docker"],
+            },
+            Stack {
+                name: "Podman's Docker shim",
+                packages: &["podman-docker"],
+            },
+        ],
+    },
+    Group {
+        what: "Power daemon",
+        stacks: &[
+            Stack {
+                name: "power-profiles-daemon",
+                packages: &["power-profiles-daemon"],
+            },
+            Stack {
+                name: "TLP",
+                packages: &["tlp", "tlp-rdw"],
+            },
+            Stack {
+                name: "tuned-ppd",
+                packages: &["tuned-ppd"],
+            },
+        ],
+    },
+    Group {
+        what: "Sound server",
+        stacks: &[
+            Stack {
+                name: "PipeWire",
+                packages: &["pipewire-pulse"],
+            },
+            Stack {
+                name: "PulseAudio",
+                packages: &["pulseaudio", "pulseaudio-bluetooth"],
+            },
+        ],
+    },
+    Group {
+        what: "NVIDIA kernel module",
+        stacks: &[
+            Stack {
+                name: "NVIDIA",
+                packages: &["nvidia", "nvidia-dkms", "nvidia-lts"],
+            },
+            Stack {
+                name: "NVIDIA Open",
+                packages: &["nvidia-open", "nvidia-open-dkms", "nvidia-open-lts"],
+            },
+            Stack {
+                name: "NVIDIA 580xx",
+                packages: &["nvidia-580xx-dkms"],
+            },
+            Stack {
+                name: "NVIDIA 470xx",
+                packages: &["nvidia-470xx-dkms"],
+            },
+        ],
+    },
+    Group {
+        what: "NVIDIA driver",
+        stacks: &[
+            Stack {
+                name: "NVIDIA",
+                packages: &[
+                    "nvidia-utils",
+                    "lib32-nvidia-utils",
+                    "opencl-nvidia",
+                    "lib32-opencl-nvidia",
+                ],
+            },
+            Stack {
+                name: "NVIDIA 580xx",
+                packages: &[
+                    "nvidia-580xx-utils",
+                    "lib32-nvidia-580xx-utils",
+                    "opencl-nvidia-580xx",
+                    "lib32-opencl-nvidia-580xx",
+                ],
+            },
+            Stack {
+                name: "NVIDIA 470xx",
+                packages: &[
+                    "nvidia-470xx-utils",
+                    "lib32-nvidia-470xx-utils",
+                    "opencl-nvidia-470xx",
+                    "lib32-opencl-nvidia-470xx",
+                ],
+            },
+        ],
+    },
+];
+
+/// An installed stack in the way of one a sequence installs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    /// What the stacks provide.
+    pub what: &'static str,
+    /// The stack being installed.
+    pub wanted: &'static str,
+    /// The installed stack.
+    pub installed: &'static str,
+    /// Installed packages of that stack.
+    pub packages: Vec<String>,
+}
+
+/// Packages the sequence removes itself with `pacman -R`, so they're not in
+/// the way.
+fn removed_by(commands: &[Command]) -> HashSet<String> {
+    let mut removed = HashSet::new();
+    for command in commands {
+        if command.command_type == CommandType::Aur {
+            if command.args.first().is_some_and(|a| a.starts_with("-R")) {
+                removed.extend(command.args.iter().skip(1).cloned());
+            }
+            continue;
+        }
+        for words in manifest::statements(command) {
+            let mut words = words.iter().skip_while(|w| *w == "sudo");
+            if words.next().is_some_and(|w| w == "pacman")
+                && words.next().is_some_and(|w| w.starts_with("-R"))
+            {
+                removed.extend(words.cloned());
+            }
+        }
+    }
+    removed
+}
+
+/// Conflicts between what `commands` install and the `installed` packages.
+pub fn find(commands: &[Command], installed: &HashSet<String>) -> Vec<Conflict> {
+    let targets = Targets::of(commands);
+    let removed = removed_by(commands);
+    let mut conflicts = Vec::new();
+
+    for group in GROUPS {
+        let wanted = |stack: &Stack| {
+            stack
+                .packages
+                .iter()
+                .any(|p| targets.packages.iter().any(|t| t == p))
+        };
+        let Some(target) = group.stacks.iter().find(|s| wanted(s)) else {
+            continue;
+        };
+        for stack in group.stacks.iter().filter(|s| !wanted(s)) {
+            let packages: Vec<String> = stack
+                .packages
+                .iter()
+                .filter(|p| installed.contains(**p) && !removed.contains(**p))
+                .map(|p| p.to_string())
+                .collect();
+            if !packages.is_empty() {
+                conflicts.push(Conflict {
+                    what: group.what,
+                    wanted: target.name,
+                    installed: stack.name,
+                    packages,
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+/// Conflicts of `commands` on this system. Blocks on pacman.
+pub fn check(commands: &[Command]) -> Vec<Conflict> {
+    if Targets::of(commands).packages.is_empty() {
+        return Vec::new();
+    }
+    find(commands, &package::installed_packages())
+}
+
+/// Step removing the installed side of `conflicts`. Dependency checks are
+/// skipped since the replacement, installed right after, provides the
+/// same things.
+pub fn resolution(conflicts: &[Conflict]) -> Command {
+    let mut args = vec!["-Rdd", "--noconfirm"];
+    for conflict in conflicts {
+        args.extend(conflict.packages.iter().map(String::as_str));
+    }
+    Command::builder()
+        .privileged()
+        .program("pacman")
+        .args(&args)
+        .description("Removing conflicting packages...")
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(packages: &[&str]) -> HashSet<String> {
+        packages.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn finds_installed_alternatives() {
+        let commands = [Command::builder()
+            .privileged()
+            .program("pacman")
+            .args(&["-S", "--needed", "--noconfirm", "podman", "podman-docker"])
+            .description("Step")
+            .build()];
+
+        assert!(find(&commands, &installed(&["podman"])).is_empty());
+        assert_eq!(
+            find(&commands, &installed(&["docker", "pulseaudio"])),
+            [Conflict {
+                what: "Docker CLI",
+                wanted: "Podman's Docker shim",
+                installed: "Docker",
+                packages: vec!["docker".to_string()],
+            }]
+        );
+    }
+
+    #[test]
+    fn skips_packages_the_sequence_removes() {
+        let commands = [
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", "pacman -Rdd --noconfirm tlp tlp-rdw || true"])
+                .description("Step")
+                .build(),
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--noconfirm", "power-profiles-daemon"])
+                .description("Step")
+                .build(),
+        ];
+
+        assert!(find(&commands, &installed(&["tlp", "tlp-rdw"])).is_empty());
+        assert_eq!(
+            find(&commands, &installed(&["tuned-ppd", "tuned"]))[0].packages,
+            ["tuned-ppd"]
+        );
+    }
+}
@@ -7,6 +7,7 @@ use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::time::Duration;
 use xero_auth::shared::is_daemon_running;
+use xero_auth::SessionStatus;
 
 /// Get the path to the xero-authd daemon binary.
 fn get_daemon_path() -> PathBuf {
@@ -85,3 +86,18 @@ pub async fn stop_daemon() -> Result<()> {
 
     Ok(())
 }
+
+/// Status of the running daemon's session, or `None` when no daemon runs.
+/// Blocks on the daemon's socket.
+pub fn session_status() -> Option<SessionStatus> {
+    use xero_auth::Client;
+
+    if !is_daemon_running() {
+        return None;
+    }
+
+    let rt = tokio::runtime::Runtime::new().ok()?;
+    rt.block_on(async { Client::new().await?.status().await })
+        .map_err(|e| warn!("Failed to get daemon status: {}", e))
+        .ok()
+}
@@ -0,0 +1,403 @@
+//! Decky Loader, the Steam plugin loader, installed step by step.
+//!
+//! Upstream installs it by piping a shell script from the network. The
+//! same steps run here as task runner commands instead, so each one shows
+//! up in the log and in exported scripts: the release is looked up through
+//! GitHub's release API, PluginLoader is downloaded and checked against the
+//! SHA256 digest GitHub lists for the asset, the systemd service is written
+//! from [`service_unit`], and Steam's CEF remote debugging flag is set so
+//! the loader can reach the client.
+
+use crate::task_runner::{Command, CommandSequence};
+use crate::{config, download};
+use anyhow::{anyhow, bail, Result};
+use log::warn;
+use regex::Regex;
+use std::path::PathBuf;
+
+const RELEASES_API: &str = "https://api.github.com/repos/SteamDeckHomebrew/decky-loader/releases";
+
+/// Asset of a release holding the Linux loader.
+const ASSET: &str = "PluginLoader";
+
+/// System service running the loader.
+pub const SERVICE: &str = "plugin_loader.service";
+
+const SERVICE_PATH: &str = "/etc/systemd/system/plugin_loader.service";
+
+/// Unit upstream installs, with `{homebrew}` and `{log_level}` filled in.
+const SERVICE_TEMPLATE: &str = "[Unit]
+Description=SteamDeck Plugin Loader
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+Type=simple
+User=root
+Restart=always
+KillMode=process
+TimeoutStopSec=45
+ExecStart={homebrew}/services/PluginLoader
+WorkingDirectory={homebrew}/services
+Environment=UNPRIVILEGED_PATH={homebrew}
+Environment=PRIVILEGED_PATH={homebrew}
+Environment=LOG_LEVEL={log_level}
+
+[Install]
+WantedBy=multi-user.target
+";
+
+/// Which releases to follow.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    /// Stable releases, for the stable Steam client.
+    Release,
+    /// Pre-releases, for the beta Steam client.
+    Prerelease,
+}
+
+impl Channel {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Channel::Release => "release",
+            Channel::Prerelease => "pre-release",
+        }
+    }
+
+    fn log_level(&self) -> &'static str {
+        match self {
+            Channel::Release => "INFO",
+            Channel::Prerelease => "DEBUG",
+        }
+    }
+}
+
+/// A release's PluginLoader with its expected digest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    pub tag: String,
+    pub prerelease: bool,
+    /// Download URL and SHA256 digest of PluginLoader; `None` when the
+    /// release doesn't list a digest for it.
+    pub loader: Option<(String, String)>,
+}
+
+/// `~/homebrew`, which holds the loader, its plugins and their settings.
+pub fn homebrew_dir() -> PathBuf {
+    PathBuf::from(&config::env::get().home).join("homebrew")
+}
+
+fn loader_path() -> PathBuf {
+    homebrew_dir().join("services").join(ASSET)
+}
+
+pub fn is_installed() -> bool {
+    loader_path().exists()
+}
+
+/// Version of the installed loader, as written by the installer.
+pub fn installed_version() -> Option<String> {
+    let path = homebrew_dir().join("services/.loader.version");
+    let version = std::fs::read_to_string(path).ok()?;
+    Some(version.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Directory PluginLoader is downloaded to before it's installed.
+fn download_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("xero-toolkit")
+        .join("decky")
+}
+
+/// Files that make Steam start with CEF remote debugging, which the loader
+/// connects through: one for native Steam and one for the Flatpak.
+fn cef_flags() -> [PathBuf; 2] {
+    let home = PathBuf::from(&config::env::get().home);
+    [
+        home.join(".steam/steam/.cef-enable-remote-debugging"),
+        home.join(".var/app/com.valvesoftware.Steam/data/Steam/.cef-enable-remote-debugging"),
+    ]
+}
+
+/// Releases in a GitHub release API response, newest first. The response
+/// is compact JSON with a fixed field order, so each release runs from its
+/// `tag_name` to the next one, and an asset's digest comes before its
+/// download URL with no nested object in between.
+fn parse_releases(json: &str) -> Vec<Release> {
+    let tag = Regex::new(r#""tag_name":"([^"]+)""#).expect("valid regex");
+    let prerelease = Regex::new(r#""prerelease":(true|false)"#).expect("valid regex");
+    let loader = Regex::new(&format!(
+        r#""digest":"sha256:([0-9a-f]{{64}})"[^{{}}]*?"browser_download_url":"(https://[^"]+/{})""#,
+        ASSET
+    ))
+    .expect("valid regex");
+
+    let starts: Vec<_> = tag.captures_iter(json).collect();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let start = c.get(0).map_or(0, |m| m.end());
+            let end = starts
+                .get(i + 1)
+                .and_then(|n| n.get(0))
+                .map_or(json.len(), |m| m.start());
+            let release = &json[start..end];
+            Release {
+                tag: c[1].to_string(),
+                prerelease: prerelease
+                    .captures(release)
+                    .is_some_and(|p| &p[1] == "true"),
+                loader: loader
+                    .captures(release)
+                    .map(|l| (l[2].to_string(), l[1].to_string())),
+            }
+        })
+        .collect()
+}
+
+/// The newest release on `channel`. Blocks on the network.
+pub fn latest(channel: Channel) -> Result<Release> {
+    let dest = download_dir().join("releases.json");
+    let json = download::fetch_text_blocking(RELEASES_API, &dest).map_err(|e| {
+        warn!("Failed to fetch {}: {:#}", RELEASES_API, e);
+        anyhow!("Couldn't reach GitHub to look up Decky Loader releases.")
+    })?;
+
+    let releases = parse_releases(&json);
+    let Some(release) = releases
+        .into_iter()
+        .find(|r| r.prerelease == (channel == Channel::Prerelease))
+    else {
+        bail!("No Decky Loader {} was found.", channel.name());
+    };
+    if release.loader.is_none() {
+        bail!(
+            "Decky Loader {} doesn't list a checksum for {}, so it can't be verified.",
+            release.tag,
+            ASSET
+        );
+    }
+    Ok(release)
+}
+
+/// The systemd unit for the loader in `homebrew`.
+pub fn service_unit(homebrew: &str, channel: Channel) -> String {
+    SERVICE_TEMPLATE
+        .replace("{homebrew}", homebrew)
+        .replace("{log_level}", channel.log_level())
+}
+
+fn stop_service() -> Command {
+    Command::builder()
+        .privileged()
+        .program("sh")
+        .args(&[
+            "-c",
+            &format!("systemctl disable --now {} 2>/dev/null; true", SERVICE),
+        ])
+        .description("Stopping Decky Loader service...")
+        .build()
+}
+
+/// Install or update to `release`, which must come from [`latest`].
+pub fn install(release: &Release, channel: Channel) -> CommandSequence {
+    let (url, sha256) = release
+        .loader
+        .as_ref()
+        .expect("releases from latest() have a verified loader");
+    let homebrew = homebrew_dir().display().to_string();
+    let staged = download_dir().join(ASSET).display().to_string();
+    let [cef_native, cef_flatpak] = cef_flags().map(|p| p.display().to_string());
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .download(url, &staged)
+                .sha256(sha256)
+                .description(&format!("Downloading Decky Loader {}...", release.tag))
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("mkdir")
+                .args(&[
+                    "-p",
+                    &format!("{}/services", homebrew),
+                    &format!("{}/plugins", homebrew),
+                ])
+                .description("Creating the homebrew folder...")
+                .build(),
+        )
+        .then(stop_service())
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    &format!(
+                        "set -e; install -m755 '{staged}' '{loader}'; \
+                         echo '{tag}' > '{homebrew}/services/.loader.version'; \
+                         rm -f '{staged}'",
+                        staged = staged,
+                        loader = loader_path().display(),
+                        tag = release.tag,
+                        homebrew = homebrew,
+                    ),
+                ])
+                .description("Installing PluginLoader...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    &format!(
+                        "cat > {} <<'EOF'\n{}EOF\nsystemctl daemon-reload",
+                        SERVICE_PATH,
+                        service_unit(&homebrew, channel)
+                    ),
+                ])
+                .description("Writing Decky Loader service...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    &format!(
+                        "for flag in '{}' '{}'; do \
+                         [ -d \"$(dirname \"$flag\")\" ] && touch \"$flag\"; done; true",
+                        cef_native, cef_flatpak
+                    ),
+                ])
+                .description("Enabling CEF remote debugging...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", SERVICE])
+                .description("Starting Decky Loader service...")
+                .build(),
+        )
+        .build()
+}
+
+/// Remove the loader and its service. `wipe` also deletes `~/homebrew`
+/// with all plugins and their settings; otherwise only the loader goes.
+pub fn remove(wipe: bool) -> CommandSequence {
+    let home = &config::env::get().home;
+    let homebrew = homebrew_dir().display().to_string();
+    let [cef_native, cef_flatpak] = cef_flags().map(|p| p.display().to_string());
+
+    let delete = if wipe {
+        Command::builder()
+            .privileged()
+            .program("rm")
+            .args(&["-rf", &homebrew])
+            .description("Deleting entire homebrew folder...")
+    } else {
+        Command::builder()
+            .privileged()
+            .program("rm")
+            .args(&["-f", &loader_path().display().to_string()])
+            .description("Removing Decky Loader binary...")
+    };
+
+    CommandSequence::new()
+        .then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["disable", "--now", SERVICE])
+                .description("Disabling and stopping Decky Loader service...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .privileged()
+                .program("bash")
+                .args(&[
+                    "-c",
+                    &format!(
+                        "rm -f {}; rm -f {}/.config/systemd/user/{}",
+                        SERVICE_PATH, home, SERVICE
+                    ),
+                ])
+                .description("Removing service files...")
+                .build(),
+        )
+        .then(
+            Command::builder()
+                .normal()
+                .program("bash")
+                .args(&[
+                    "-c",
+                    &format!(
+                        "rm -rf /tmp/plugin_loader /tmp/user_install_script.sh '{}'",
+                        download_dir().display()
+                    ),
+                ])
+                .description("Cleaning up temporary files...")
+                .build(),
+        )
+        .then(delete.build())
+        .then(
+            Command::builder()
+                .privileged()
+                .program("bash")
+                .args(&[
+                    "-c",
+                    &format!("rm -f '{}' '{}' 2>/dev/null; true", cef_native, cef_flatpak),
+                ])
+                .description("Disabling CEF remote debugging...")
+                .build(),
+        )
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_release_api_response() {
+        let digest = "a".repeat(64);
+        let json = format!(
+            concat!(
+                r#"[{{"id":2,"author":{{"login":"bot"}},"tag_name":"v3.2.0-pre1","prerelease":true,"#,
+                r#""assets":[{{"name":"PluginLoader","uploader":{{"login":"bot"}},"digest":null,"#,
+                r#""browser_download_url":"https://github.com/d/releases/download/v3.2.0-pre1/PluginLoader"}}]}},"#,
+                r#"{{"id":1,"author":{{"login":"bot"}},"tag_name":"v3.1.10","prerelease":false,"#,
+                r#""assets":[{{"name":"PluginLoader.exe","uploader":{{"login":"bot"}},"digest":"sha256:{b}","#,
+                r#""browser_download_url":"https://github.com/d/releases/download/v3.1.10/PluginLoader.exe"}},"#,
+                r#"{{"name":"PluginLoader","uploader":{{"login":"bot"}},"digest":"sha256:{a}","#,
+                r#""browser_download_url":"https://github.com/d/releases/download/v3.1.10/PluginLoader"}}]}}]"#
+            ),
+            a = digest,
+            b = "b".repeat(64),
+        );
+
+        let releases = parse_releases(&json);
+        assert_eq!(releases.len(), 2);
+        assert_eq!(releases[0].tag, "v3.2.0-pre1");
+        assert!(releases[0].prerelease);
+        assert_eq!(releases[0].loader, None);
+        assert_eq!(
+            releases[1].loader,
+            Some((
+                "https://github.com/d/releases/download/v3.1.10/PluginLoader".to_string(),
+                digest
+            ))
+        );
+    }
+}
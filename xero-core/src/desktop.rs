@@ -0,0 +1,71 @@
+//! Desktop environment detection.
+//!
+//! Much of the Customization page drives Plasma or GNOME tools directly, so
+//! it only offers the actions that work on the running desktop.
+
+/// Desktops with their own customization actions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Desktop {
+    Plasma,
+    Gnome,
+    Other,
+}
+
+impl Desktop {
+    /// The running desktop, from `XDG_CURRENT_DESKTOP`.
+    pub fn detect() -> Self {
+        Self::parse(&std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default())
+    }
+
+    /// Parse `XDG_CURRENT_DESKTOP`, a colon separated list such as
+    /// `ubuntu:GNOME`.
+    fn parse(value: &str) -> Self {
+        value
+            .split(':')
+            .find_map(|name| match name.trim().to_ascii_lowercase().as_str() {
+                "kde" => Some(Desktop::Plasma),
+                "gnome" | "gnome-classic" => Some(Desktop::Gnome),
+                _ => None,
+            })
+            .unwrap_or(Desktop::Other)
+    }
+}
+
+/// Extension Manager, for browsing and configuring GNOME Shell extensions.
+pub const EXTENSION_MANAGER_FLATPAK: &str = "com.mattjakeman.ExtensionManager";
+
+pub const DASH_TO_DOCK_UUID: &str = "dash-to-dock@micxgx.gmail.com";
+
+/// Script adding the extension `$1` to the enabled list. Newly installed
+/// extensions are unknown to a running shell, so `gnome-extensions enable`
+/// would fail; the setting is read at the next login.
+pub const ENABLE_EXTENSION_SCRIPT: &str = r#"current=$(gsettings get org.gnome.shell enabled-extensions)
+case "$current" in
+    *"'$1'"*) ;;
+    "@as []"|"[]") gsettings set org.gnome.shell enabled-extensions "['$1']" ;;
+    *) gsettings set org.gnome.shell enabled-extensions "${current%]}, '$1']" ;;
+esac
+"#;
+
+/// Script switching GTK 3 apps to adw-gtk3, light or dark to match the
+/// style in use, so they look like the libadwaita ones.
+pub const ADW_GTK3_SCRIPT: &str = r#"if gsettings get org.gnome.desktop.interface color-scheme | grep -q dark; then
+    theme=adw-gtk3-dark
+else
+    theme=adw-gtk3
+fi
+gsettings set org.gnome.desktop.interface gtk-theme "$theme"
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Desktop::parse("KDE"), Desktop::Plasma);
+        assert_eq!(Desktop::parse("ubuntu:GNOME"), Desktop::Gnome);
+        assert_eq!(Desktop::parse("XFCE"), Desktop::Other);
+        assert_eq!(Desktop::parse(""), Desktop::Other);
+    }
+}
@@ -0,0 +1,248 @@
+//! Developer tool choices and git identity.
+//!
+//! Editors come from the repos, the AUR or Flathub, and each toolchain is a
+//! set of packages plus the per-user setup it needs (a default Rust
+//! toolchain, pipx on `PATH`, fnm's shell hook).
+
+use crate::i18n::{gettext, gettext_f, n_};
+use anyhow::{bail, Result};
+use std::process::Command;
+
+/// Where an editor is installed from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Source {
+    Repo(&'static str),
+    Aur(&'static str),
+    Flatpak(&'static str),
+}
+
+/// A code editor build.
+#[derive(Clone, Copy, Debug)]
+pub struct Editor {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub source: Source,
+}
+
+pub const EDITORS: &[Editor] = &[
+    Editor {
+        id: "code",
+        name: "Code - OSS",
+        description: n_("Arch repo build of VS Code, Open VSX extensions"),
+        source: Source::Repo("code"),
+    },
+    Editor {
+        id: "vscode",
+        name: "Visual Studio Code",
+        description: n_("Microsoft build from the AUR, with the official marketplace"),
+        source: Source::Aur("visual-studio-code-bin"),
+    },
+    Editor {
+        id: "vscodium",
+        name: "VSCodium",
+        description: n_("Telemetry-free build, sandboxed from Flathub"),
+        source: Source::Flatpak("com.vscodium.codium"),
+    },
+    Editor {
+        id: "vscode-flatpak",
+        name: "Visual Studio Code (Flatpak)",
+        description: n_("Microsoft build, sandboxed from Flathub"),
+        source: Source::Flatpak("com.visualstudio.code"),
+    },
+];
+
+/// Packages of every repo and AUR editor, for the install state.
+pub const EDITOR_PACKAGES: &[&str] = &["code", "visual-studio-code-bin"];
+
+/// Flatpak ids of every Flathub editor.
+pub const EDITOR_FLATPAKS: &[&str] = &["com.vscodium.codium", "com.visualstudio.code"];
+
+/// A language toolchain.
+#[derive(Clone, Copy, Debug)]
+pub struct Toolchain {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub packages: &'static [&'static str],
+    /// Package whose presence means the toolchain is set up. Base packages
+    /// like gcc and python are on nearly every system, so it's the one the
+    /// toolchain adds.
+    pub marker: &'static str,
+}
+
+pub const TOOLCHAINS: &[Toolchain] = &[
+    Toolchain {
+        id: "rust",
+        name: "Rust",
+        description: n_("rustup with the stable toolchain"),
+        packages: &["rustup"],
+        marker: "rustup",
+    },
+    Toolchain {
+        id: "c",
+        name: "C / C++",
+        description: n_("GCC, Clang, CMake, Ninja and GDB"),
+        packages: &["gcc", "clang", "cmake", "ninja", "gdb"],
+        marker: "clang",
+    },
+    Toolchain {
+        id: "python",
+        name: "Python",
+        description: n_("Python with pipx for isolated CLI tools"),
+        packages: &["python", "python-pipx"],
+        marker: "python-pipx",
+    },
+    Toolchain {
+        id: "node",
+        name: "Node.js",
+        description: n_("fnm with the latest LTS release"),
+        packages: &["fnm"],
+        marker: "fnm",
+    },
+];
+
+/// Packages removed when uninstalling toolchains. gcc and python stay,
+/// too much of the system depends on them.
+pub const TOOLCHAIN_PACKAGES: &[&str] = &[
+    "rustup",
+    "clang",
+    "cmake",
+    "ninja",
+    "gdb",
+    "python-pipx",
+    "fnm",
+];
+
+/// Look up a toolchain by id.
+pub fn find_toolchain(id: &str) -> Option<&'static Toolchain> {
+    TOOLCHAINS.iter().find(|t| t.id == id)
+}
+
+/// Look up an editor by id.
+pub fn find_editor(id: &str) -> Option<&'static Editor> {
+    EDITORS.iter().find(|e| e.id == id)
+}
+
+/// Git credential helpers offered by the setup dialog, as
+/// `(helper, description)`.
+pub const CREDENTIAL_HELPERS: &[(&str, &str)] = &[
+    (
+        "libsecret",
+        n_("Desktop keyring (GNOME Keyring or KWallet)"),
+    ),
+    (
+        "cache --timeout=3600",
+        n_("Memory, forgotten after an hour"),
+    ),
+    ("", n_("Leave as it is")),
+];
+
+/// The global git identity and credential helper. An empty helper leaves
+/// the current one in place.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GitConfig {
+    pub name: String,
+    pub email: String,
+    pub credential_helper: String,
+}
+
+fn git_get(key: &str) -> String {
+    Command::new("git")
+        .args(["config", "--global", "--get", key])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .unwrap_or_default()
+}
+
+impl GitConfig {
+    /// Read the current values from `~/.gitconfig`.
+    pub fn read() -> Self {
+        Self {
+            name: git_get("user.name"),
+            email: git_get("user.email"),
+            credential_helper: git_get("credential.helper"),
+        }
+    }
+
+    /// Check the values before writing them.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            bail!(gettext("Enter the name to sign commits with"));
+        }
+        let email = self.email.trim();
+        let valid_email = email
+            .split_once('@')
+            .is_some_and(|(user, domain)| !user.is_empty() && domain.contains('.'))
+            && !email.contains(char::is_whitespace);
+        if !valid_email {
+            bail!(gettext_f(
+                "'{email}' is not a valid email address",
+                &[("email", email)],
+            ));
+        }
+        Ok(())
+    }
+
+    /// `git config --global` argument lists that apply these values.
+    pub fn commands(&self) -> Vec<Vec<String>> {
+        let set = |key: &str, value: &str| {
+            vec![
+                "config".to_string(),
+                "--global".to_string(),
+                key.to_string(),
+                value.to_string(),
+            ]
+        };
+        let mut commands = vec![
+            set("user.name", self.name.trim()),
+            set("user.email", self.email.trim()),
+            set("init.defaultBranch", "main"),
+        ];
+        if !self.credential_helper.is_empty() {
+            commands.push(set("credential.helper", &self.credential_helper));
+        }
+        commands
+    }
+}
+
+/// Whether a global git identity is set.
+pub fn git_configured() -> bool {
+    !git_get("user.name").is_empty() && !git_get("user.email").is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_git_config() {
+        let mut config = GitConfig {
+            name: "Ada Lovelace".into(),
+            email: "ada@example.org".into(),
+            credential_helper: "libsecret".into(),
+        };
+        assert!(config.validate().is_ok());
+        let commands = config.commands();
+        assert_eq!(
+            commands[0],
+            ["config", "--global", "user.name", "Ada Lovelace"]
+        );
+        assert_eq!(commands[3][2], "credential.helper");
+
+        config.email = "ada@localhost".into();
+        assert!(config.validate().is_err());
+        config.email = "ada lovelace@example.org".into();
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_toolchain_packages() {
+        for toolchain in TOOLCHAINS {
+            assert!(toolchain.packages.contains(&toolchain.marker));
+            assert!(TOOLCHAIN_PACKAGES.contains(&toolchain.marker));
+        }
+    }
+}
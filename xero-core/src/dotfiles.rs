@@ -0,0 +1,237 @@
+//! Dotfiles kept in a bare git repository.
+//!
+//! The repository lives in `~/.dotfiles` with the home folder as its work
+//! tree, so tracked files stay where programs expect them and no symlinks
+//! are needed. Untracked files are hidden from `git status`, only the paths
+//! picked by the user are ever committed.
+
+use crate::i18n::n_;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Paths offered for tracking, relative to the home folder.
+pub const CANDIDATES: &[(&str, &str)] = &[
+    (".bashrc", "Bash"),
+    (".zshrc", "ZSH"),
+    (".gitconfig", "Git"),
+    (".config/fish", "Fish"),
+    (".config/starship.toml", n_("Starship prompt")),
+    (".config/fastfetch", "Fastfetch"),
+    (".config/nvim", "Neovim"),
+    (".config/kitty", "Kitty"),
+    (".config/alacritty", "Alacritty"),
+    (".config/konsolerc", "Konsole"),
+    (".local/share/konsole", n_("Konsole profiles")),
+    (".config/kdeglobals", n_("Plasma colors and fonts")),
+    (".config/kwinrc", "KWin"),
+    (
+        ".config/plasma-org.kde.plasma.desktop-appletsrc",
+        n_("Plasma panels and widgets"),
+    ),
+];
+
+fn home() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("/root"))
+}
+
+/// The bare repository, `~/.dotfiles`.
+pub fn git_dir() -> PathBuf {
+    home().join(".dotfiles")
+}
+
+/// Arguments pointing git at the bare repository and the home folder.
+pub fn git_args() -> [String; 2] {
+    [
+        format!("--git-dir={}", git_dir().display()),
+        format!("--work-tree={}", home().display()),
+    ]
+}
+
+/// Run git on the repository, returning its output on success.
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git")
+        .args(git_args())
+        .args(args)
+        .output()
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// State of the repository.
+#[derive(Clone, Debug, Default)]
+pub struct Status {
+    pub initialized: bool,
+    pub remote: Option<String>,
+    /// Tracked files, relative to the home folder.
+    pub files: Vec<String>,
+    /// Tracked files with uncommitted changes.
+    pub changed: usize,
+    /// When the last commit was made, e.g. "2 days ago".
+    pub last_commit: Option<String>,
+    /// Whether git has a name and email to commit with.
+    pub identity: bool,
+}
+
+impl Status {
+    pub fn read() -> Self {
+        let identity = ["user.name", "user.email"].iter().all(|key| {
+            Command::new("git")
+                .args(["config", "--get", key])
+                .output()
+                .is_ok_and(|o| o.status.success())
+        });
+        if !git_dir().join("HEAD").is_file() {
+            return Self {
+                identity,
+                ..Self::default()
+            };
+        }
+        Self {
+            initialized: true,
+            remote: git(&["remote", "get-url", "origin"]).filter(|r| !r.is_empty()),
+            files: git(&["ls-files"])
+                .map(|o| o.lines().map(str::to_string).collect())
+                .unwrap_or_default(),
+            changed: git(&["status", "--porcelain"])
+                .map(|o| o.lines().count())
+                .unwrap_or(0),
+            last_commit: git(&["log", "-1", "--format=%cr"]).filter(|c| !c.is_empty()),
+            identity,
+        }
+    }
+}
+
+/// Whether each of the [`CANDIDATES`] exists in the home folder.
+pub fn present() -> Vec<bool> {
+    let home = home();
+    CANDIDATES
+        .iter()
+        .map(|(path, _)| home.join(path).exists())
+        .collect()
+}
+
+/// Whether `path`, or anything under it, is in `files`.
+pub fn is_tracked(files: &[String], path: &str) -> bool {
+    files.iter().any(|file| {
+        file == path
+            || file
+                .strip_prefix(path)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Whether `url` looks like a git remote: https, ssh, or the scp-like
+/// `git@host:user/repo.git`.
+pub fn is_valid_remote(url: &str) -> bool {
+    if url.is_empty() || url.contains(char::is_whitespace) {
+        return false;
+    }
+    url.starts_with("https://")
+        || url.starts_with("ssh://")
+        || url.split_once('@').is_some_and(|(user, rest)| {
+            !user.is_empty() && !user.contains('/') && rest.contains(':')
+        })
+}
+
+/// Defines `dot`, git on the bare repository.
+const DOT: &str = "dot() { git --git-dir=\"$HOME/.dotfiles\" --work-tree=\"$HOME\" \"$@\"; }\n";
+
+/// Script creating the repository and a `dotfiles` alias for the shell.
+pub fn init_script() -> String {
+    format!(
+        r#"set -e
+{dot}git init --bare "$HOME/.dotfiles"
+dot config status.showUntrackedFiles no
+for rc in "$HOME/.bashrc" "$HOME/.zshrc"; do
+    if [ -f "$rc" ] && ! grep -q "alias dotfiles=" "$rc"; then
+        printf '\n%s\n' "alias dotfiles='git --git-dir=\$HOME/.dotfiles --work-tree=\$HOME'" >> "$rc"
+    fi
+done
+"#,
+        dot = DOT
+    )
+}
+
+/// Script pointing `origin` at `$1`.
+pub fn remote_script() -> String {
+    format!(
+        r#"set -e
+{dot}if dot remote get-url origin >/dev/null 2>&1; then
+    dot remote set-url origin "$1"
+else
+    dot remote add origin "$1"
+fi
+"#,
+        dot = DOT
+    )
+}
+
+/// Script committing every change to tracked files, if there are any.
+pub fn commit_script() -> String {
+    format!(
+        r#"set -e
+{dot}dot add -u
+if dot diff --cached --quiet; then
+    echo 'Nothing to commit.'
+else
+    dot commit -m "Update dotfiles from $(uname -n)"
+fi
+"#,
+        dot = DOT
+    )
+}
+
+/// Script cloning `$1` and checking it out over the home folder, for a
+/// fresh install. Files that would be overwritten are moved to
+/// `~/.dotfiles-backup/` first.
+pub fn restore_script() -> String {
+    format!(
+        r#"set -e
+{dot}git clone --bare "$1" "$HOME/.dotfiles"
+dot config status.showUntrackedFiles no
+backup="$HOME/.dotfiles-backup/$(date +%Y.%m.%d-%H.%M.%S)"
+dot ls-tree -r --name-only HEAD | while IFS= read -r file; do
+    if [ -e "$HOME/$file" ]; then
+        mkdir -p "$backup/$(dirname "$file")"
+        mv "$HOME/$file" "$backup/$file"
+    fi
+done
+dot checkout -f
+if [ -d "$backup" ]; then
+    echo "Files that were in the way are in $backup"
+fi
+"#,
+        dot = DOT
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_tracked() {
+        let files = vec![
+            ".zshrc".to_string(),
+            ".config/nvim/init.lua".to_string(),
+            ".config/kwinrc".to_string(),
+        ];
+        assert!(is_tracked(&files, ".zshrc"));
+        assert!(is_tracked(&files, ".config/nvim"));
+        assert!(!is_tracked(&files, ".config/kwin"));
+        assert!(!is_tracked(&files, ".bashrc"));
+    }
+
+    #[test]
+    fn test_is_valid_remote() {
+        assert!(is_valid_remote("https://github.com/user/dotfiles.git"));
+        assert!(is_valid_remote("git@github.com:user/dotfiles.git"));
+        assert!(is_valid_remote("ssh://git@host:2222/dotfiles"));
+        assert!(!is_valid_remote("github.com/user/dotfiles"));
+        assert!(!is_valid_remote("https://host/a b"));
+        assert!(!is_valid_remote(""));
+    }
+}
@@ -240,6 +240,204 @@ where
     Ok(())
 }
 
+/// Attempts made by [`fetch`] before giving up.
+const FETCH_ATTEMPTS: u32 = 5;
+
+/// Download `url` to `dest`, for files the toolkit installs or runs.
+///
+/// Data goes to `<dest>.part` first and is renamed once complete, so a
+/// failed attempt never leaves a truncated file at `dest`. Dropped
+/// connections are retried with backoff, resuming from the partial file
+/// when the server supports ranges. Proxies are taken from the standard
+/// `http_proxy`/`https_proxy`/`no_proxy` environment variables.
+pub async fn fetch<F>(
+    url: &str,
+    dest: &std::path::Path,
+    progress_callback: F,
+    cancel_flag: Arc<AtomicBool>,
+) -> Result<()>
+where
+    F: Fn(DownloadState),
+{
+    info!("Fetching {} to {}", url, dest.display());
+
+    if let Some(parent) = dest.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    // GitHub's API refuses requests without a user agent
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("xero-toolkit/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(Duration::from_secs(30))
+        .read_timeout(Duration::from_secs(60))
+        .build()
+        .context("Failed to build HTTP client")?;
+
+    let part = std::path::PathBuf::from(format!("{}.part", dest.display()));
+    let mut last_error = None;
+
+    for attempt in 1..=FETCH_ATTEMPTS {
+        if cancel_flag.load(Ordering::Relaxed) {
+            let _ = tokio::fs::remove_file(&part).await;
+            anyhow::bail!("Download cancelled");
+        }
+
+        match fetch_attempt(&client, url, &part, &progress_callback, &cancel_flag).await {
+            Ok(()) => {
+                tokio::fs::rename(&part, dest)
+                    .await
+                    .with_context(|| format!("Failed to move download to {}", dest.display()))?;
+                info!("Fetched {}", dest.display());
+                return Ok(());
+            }
+            Err(e) if cancel_flag.load(Ordering::Relaxed) => {
+                let _ = tokio::fs::remove_file(&part).await;
+                return Err(e);
+            }
+            Err(e) => {
+                info!(
+                    "Download attempt {}/{} failed: {:#}",
+                    attempt, FETCH_ATTEMPTS, e
+                );
+                last_error = Some(e);
+                if attempt < FETCH_ATTEMPTS {
+                    tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+                }
+            }
+        }
+    }
+
+    Err(last_error
+        .unwrap_or_else(|| anyhow::anyhow!("Download failed"))
+        .context(format!(
+            "Failed to download {} after {} attempts",
+            url, FETCH_ATTEMPTS
+        )))
+}
+
+/// One request of [`fetch`], appending to `part` if it already has data.
+async fn fetch_attempt<F>(
+    client: &reqwest::Client,
+    url: &str,
+    part: &std::path::Path,
+    progress_callback: &F,
+    cancel_flag: &AtomicBool,
+) -> Result<()>
+where
+    F: Fn(DownloadState),
+{
+    use futures_util::StreamExt;
+    use reqwest::header::RANGE;
+    use reqwest::StatusCode;
+    use tokio::io::AsyncWriteExt;
+
+    let mut downloaded = tokio::fs::metadata(part)
+        .await
+        .map(|m| m.len())
+        .unwrap_or(0);
+
+    let mut request = client.get(url);
+    if downloaded > 0 {
+        info!("Resuming download from byte {}", downloaded);
+        request = request.header(RANGE, format!("bytes={}-", downloaded));
+    }
+
+    let response = request.send().await.context("Connection failed")?;
+    let status = response.status();
+
+    // The partial file is already complete.
+    if status == StatusCode::RANGE_NOT_SATISFIABLE && downloaded > 0 {
+        return Ok(());
+    }
+    if !status.is_success() {
+        anyhow::bail!("Server responded with {}", status);
+    }
+
+    // A plain 200 means the server ignored the range: start over.
+    let resumed = status == StatusCode::PARTIAL_CONTENT;
+    if !resumed {
+        downloaded = 0;
+    }
+    let total = response.content_length().map_or(0, |len| downloaded + len);
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(resumed)
+        .truncate(!resumed)
+        .open(part)
+        .await
+        .with_context(|| format!("Failed to open {}", part.display()))?;
+
+    let mut stream = response.bytes_stream();
+    let mut last_update = Instant::now();
+    let mut last_downloaded = downloaded;
+
+    while let Some(chunk) = stream.next().await {
+        if cancel_flag.load(Ordering::Relaxed) {
+            anyhow::bail!("Download cancelled");
+        }
+
+        let chunk = chunk.context("Connection interrupted")?;
+        file.write_all(&chunk)
+            .await
+            .context("Failed to write download")?;
+        downloaded += chunk.len() as u64;
+
+        let elapsed = last_update.elapsed();
+        if elapsed >= Duration::from_millis(250) {
+            progress_callback(DownloadState {
+                downloaded,
+                total,
+                speed: (downloaded - last_downloaded) as f64 / elapsed.as_secs_f64(),
+            });
+            last_update = Instant::now();
+            last_downloaded = downloaded;
+        }
+    }
+
+    file.flush().await.context("Failed to write download")?;
+
+    if total > 0 && downloaded < total {
+        anyhow::bail!("Connection closed after {} of {} bytes", downloaded, total);
+    }
+
+    progress_callback(DownloadState {
+        downloaded,
+        total,
+        speed: 0.0,
+    });
+    Ok(())
+}
+
+/// Blocking wrapper around [`fetch`] for use from worker threads.
+pub fn fetch_blocking<F>(
+    url: &str,
+    dest: &std::path::Path,
+    progress_callback: F,
+    cancel_flag: Arc<AtomicBool>,
+) -> Result<()>
+where
+    F: Fn(DownloadState),
+{
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to start download runtime")?
+        .block_on(fetch(url, dest, progress_callback, cancel_flag))
+}
+
+/// Fetch a small document, such as a feed or an API response, through
+/// [`fetch`] into `dest` and return its text. A partial file left by an
+/// earlier failure is discarded, since the document may have changed since.
+pub fn fetch_text_blocking(url: &str, dest: &std::path::Path) -> Result<String> {
+    let _ = std::fs::remove_file(format!("{}.part", dest.display()));
+    fetch_blocking(url, dest, |_| {}, Arc::new(AtomicBool::new(false)))?;
+    std::fs::read_to_string(dest).with_context(|| format!("Failed to read {}", dest.display()))
+}
+
 /// Format bytes to human-readable string
 pub fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
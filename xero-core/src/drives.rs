@@ -0,0 +1,507 @@
+//! Disk and partition detection and fstab entries for extra drives.
+//!
+//! Used to mount secondary drives, mainly NTFS ones shared with Windows,
+//! at a fixed place on boot with permissions the desktop user can write to,
+//! and to list every disk on the Disks page.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Mount points that belong to the running system and are never offered.
+const SYSTEM_MOUNTS: [&str; 6] = ["/", "/boot", "/boot/efi", "/efi", "/home", "/var"];
+
+/// A partition with a filesystem, as reported by `lsblk`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Partition {
+    /// Device path, e.g. `/dev/nvme1n1p2`.
+    pub device: String,
+    pub fstype: String,
+    pub uuid: String,
+    pub label: String,
+    /// Human readable size, e.g. `931.5G`.
+    pub size: String,
+    pub mountpoint: Option<String>,
+    /// Human readable free space, only known while mounted.
+    pub avail: String,
+    /// Percentage of the filesystem in use, only known while mounted.
+    pub used_percent: Option<u8>,
+}
+
+impl Partition {
+    pub fn is_ntfs(&self) -> bool {
+        self.fstype == "ntfs" || self.fstype == "ntfs3"
+    }
+
+    /// Whether the running system is mounted from this partition.
+    pub fn is_system(&self) -> bool {
+        self.mountpoint
+            .as_deref()
+            .is_some_and(|m| SYSTEM_MOUNTS.contains(&m) || m == "[SWAP]")
+    }
+
+    /// Whether this holds files, rather than swap, an encrypted volume or
+    /// a member of a RAID or LVM set.
+    pub fn is_mountable(&self) -> bool {
+        !matches!(
+            self.fstype.as_str(),
+            "swap" | "crypto_LUKS" | "LVM2_member" | "linux_raid_member" | "zfs_member"
+        )
+    }
+
+    /// Label if it has one, else the device path.
+    pub fn display_name(&self) -> &str {
+        if self.label.is_empty() {
+            &self.device
+        } else {
+            &self.label
+        }
+    }
+
+    /// Where the partition gets mounted: `/mnt/<label>`, or the UUID when
+    /// the label has nothing usable in a path.
+    pub fn mount_dir(&self) -> PathBuf {
+        let name: String = self
+            .label
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            .collect();
+        let name = name.trim_start_matches('.');
+        PathBuf::from("/mnt").join(if name.is_empty() { &self.uuid } else { name })
+    }
+
+    /// fstab line mounting an NTFS partition with the in-kernel ntfs3 driver,
+    /// owned by `uid`/`gid` so Steam can write to it. `nofail` keeps the
+    /// system booting when the drive is missing.
+    pub fn ntfs_fstab_entry(&self, uid: u32, gid: u32) -> String {
+        format!(
+            "UUID={} {} ntfs3 uid={},gid={},umask=022,noatime,windows_names,nofail 0 0",
+            self.uuid,
+            self.mount_dir().display(),
+            uid,
+            gid
+        )
+    }
+
+    /// fstab line mounting the partition at `dir` with options suited to
+    /// its filesystem. Windows filesystems are owned by `uid`/`gid`, since
+    /// they have no permissions of their own. With `automount` it's only
+    /// mounted when first opened, which suits drives that aren't always
+    /// connected.
+    pub fn fstab_entry(&self, dir: &Path, uid: u32, gid: u32, automount: bool) -> String {
+        let (fstype, options) = match self.fstype.as_str() {
+            "ntfs" | "ntfs3" => (
+                "ntfs3",
+                format!("uid={},gid={},umask=022,noatime,windows_names", uid, gid),
+            ),
+            "vfat" | "exfat" => (
+                self.fstype.as_str(),
+                format!("uid={},gid={},umask=022,noatime", uid, gid),
+            ),
+            fstype => (fstype, "defaults,noatime".to_string()),
+        };
+        let pass = if self.fstype.starts_with("ext") { 2 } else { 0 };
+        format!(
+            "UUID={} {} {} {}{},nofail 0 {}",
+            self.uuid,
+            dir.display(),
+            fstype,
+            options,
+            if automount {
+                ",noauto,x-systemd.automount"
+            } else {
+                ""
+            },
+            pass
+        )
+    }
+
+    /// Where the partition ends up mounted: the mount point of its existing
+    /// fstab entry, else [`Partition::mount_dir`].
+    pub fn target_dir(&self) -> PathBuf {
+        fstab_dir(&self.uuid).unwrap_or_else(|| self.mount_dir())
+    }
+
+    /// Shell script that adds [`Partition::ntfs_fstab_entry`] to fstab unless
+    /// the UUID is already there, then remounts the partition.
+    pub fn ntfs_mount_script(&self, uid: u32, gid: u32) -> String {
+        self.fstab_mount_script(&self.ntfs_fstab_entry(uid, gid), &self.mount_dir())
+    }
+
+    /// Shell script that adds `entry` to fstab unless the UUID is already
+    /// there, then remounts the partition at `dir`, or wherever the existing
+    /// entry puts it. NTFS volumes Windows left hibernated or dirty, as Fast
+    /// Startup does, are refused rather than forced, since that can lose
+    /// what Windows hasn't written back yet.
+    pub fn fstab_mount_script(&self, entry: &str, dir: &Path) -> String {
+        format!(
+            "set -e; \
+             umount '{device}' 2>/dev/null || true; \
+             {ntfs_check}\
+             dir=$(awk '$1 == \"UUID={uuid}\" {{ print $2; exit }}' /etc/fstab); \
+             if [ -z \"$dir\" ]; then \
+             dir='{dir}'; \
+             cp /etc/fstab /etc/fstab.bak; \
+             echo '{entry}' >> /etc/fstab; \
+             fi; \
+             dir=$(printf '%b' \"$dir\"); \
+             mkdir -p \"$dir\"; \
+             systemctl daemon-reload; \
+             mount \"$dir\"",
+            device = self.device,
+            ntfs_check = if self.is_ntfs() {
+                format!(
+                    "if command -v ntfs-3g.probe >/dev/null; then \
+                     status=0; ntfs-3g.probe --readwrite '{}' || status=$?; \
+                     if [ $status -ge 13 ] && [ $status -le 15 ]; then \
+                     echo \"Windows didn't shut this drive down cleanly. Turn off Fast Startup \
+                     in Windows and shut it down fully, or run chkdsk /f on the drive, then \
+                     try again.\" >&2; \
+                     exit 1; \
+                     fi; \
+                     fi; ",
+                    self.device
+                )
+            } else {
+                String::new()
+            },
+            dir = dir.display(),
+            uuid = self.uuid,
+            entry = entry,
+        )
+    }
+}
+
+/// Whether `dir` can be used as a mount point in fstab: an absolute path of
+/// plain characters, which needs no escaping, outside the running system.
+pub fn is_valid_mount_point(dir: &str) -> bool {
+    dir.len() > 1
+        && dir.starts_with('/')
+        && dir
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '-' | '_' | '.'))
+        && !dir.split('/').any(|part| part == "..")
+        && !SYSTEM_MOUNTS.contains(&dir.trim_end_matches('/'))
+}
+
+/// A disk and the filesystems on it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Disk {
+    /// Device path, e.g. `/dev/nvme0n1`.
+    pub device: String,
+    pub model: String,
+    /// Human readable size, e.g. `931.5G`.
+    pub size: String,
+    /// How it's connected, e.g. `nvme`, `sata` or `usb`.
+    pub transport: String,
+    pub removable: bool,
+    /// Partitions, and the volumes opened from them, that hold a filesystem.
+    pub partitions: Vec<Partition>,
+}
+
+impl Disk {
+    /// Model if known, else the device path.
+    pub fn display_name(&self) -> &str {
+        if self.model.is_empty() {
+            &self.device
+        } else {
+            &self.model
+        }
+    }
+}
+
+/// Script printing the SMART health and attributes of disk `$1`. smartctl
+/// sets bits of its exit status for logged errors too, so the script only
+/// fails when smartctl couldn't read the disk or says it's failing.
+pub const SMART_SCRIPT: &str = r#"smartctl -H -A "$1"
+status=$?
+if [ $((status & 8)) -ne 0 ]; then
+    echo "The drive reports that it is failing. Back up its data now." >&2
+fi
+[ $((status & 11)) -eq 0 ]
+"#;
+
+/// Whether fstab already mounts the partition with `uuid`.
+pub fn in_fstab(uuid: &str) -> bool {
+    fstab_dir(uuid).is_some()
+}
+
+/// Mount point of the fstab entry for the partition with `uuid`.
+pub fn fstab_dir(uuid: &str) -> Option<PathBuf> {
+    let fstab = std::fs::read_to_string("/etc/fstab").ok()?;
+    parse_fstab_dir(&fstab, uuid)
+}
+
+fn parse_fstab_dir(fstab: &str, uuid: &str) -> Option<PathBuf> {
+    let source = format!("UUID={}", uuid);
+    fstab.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        (fields.next() == Some(&source))
+            .then(|| fields.next())
+            .flatten()
+            .map(|dir| PathBuf::from(unescape_octal(dir)))
+    })
+}
+
+/// Undo fstab's `\NNN` octal escapes, e.g. `\040` for a space.
+fn unescape_octal(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'\\' && tail.len() >= 3 {
+            if let Some(byte) = std::str::from_utf8(&tail[..3])
+                .ok()
+                .and_then(|octal| u8::from_str_radix(octal, 8).ok())
+            {
+                bytes.push(byte);
+                rest = &tail[3..];
+                continue;
+            }
+        }
+        bytes.push(b);
+        rest = tail;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Undo `lsblk`'s `\xNN` escapes.
+fn unescape(value: &str) -> String {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut rest = value.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'\\' && tail.first() == Some(&b'x') && tail.len() >= 3 {
+            if let Some(byte) = std::str::from_utf8(&tail[1..3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            {
+                bytes.push(byte);
+                rest = &tail[3..];
+                continue;
+            }
+        }
+        bytes.push(b);
+        rest = tail;
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// `KEY="value"` pairs of a line of `lsblk -P` output.
+fn parse_fields(line: &str) -> Option<Vec<(String, String)>> {
+    let mut fields = Vec::new();
+    let mut rest = line;
+    while let Some((key, tail)) = rest.split_once("=\"") {
+        let (value, tail) = tail.split_once('"')?;
+        fields.push((key.trim().to_string(), unescape(value)));
+        rest = tail;
+    }
+    Some(fields)
+}
+
+fn partition_from(fields: &[(String, String)]) -> Partition {
+    let mut partition = Partition::default();
+    for (key, value) in fields {
+        let value = value.clone();
+        match key.as_str() {
+            "NAME" => partition.device = value,
+            "FSTYPE" => partition.fstype = value,
+            "UUID" => partition.uuid = value,
+            "LABEL" => partition.label = value,
+            "SIZE" => partition.size = value,
+            "MOUNTPOINT" if !value.is_empty() => partition.mountpoint = Some(value),
+            "FSAVAIL" => partition.avail = value,
+            "FSUSE%" => partition.used_percent = value.trim_end_matches('%').parse().ok(),
+            _ => {}
+        }
+    }
+    partition
+}
+
+/// Partitions with a filesystem and UUID from `lsblk -P` output.
+fn parse_lsblk(output: &str) -> Vec<Partition> {
+    output
+        .lines()
+        .filter_map(parse_fields)
+        .map(|fields| partition_from(&fields))
+        .filter(|p| !p.fstype.is_empty() && !p.uuid.is_empty())
+        .collect()
+}
+
+/// Disks from `lsblk -P` output, which lists every device after its parent.
+/// Volumes go with the disk holding their parent, so an opened LUKS volume
+/// shows up on the disk of its encrypted partition.
+fn parse_disks(output: &str) -> Vec<Disk> {
+    let mut disks: Vec<Disk> = Vec::new();
+    for fields in output.lines().filter_map(parse_fields) {
+        let get = |key: &str| {
+            fields
+                .iter()
+                .find(|(k, _)| k == key)
+                .map_or("", |(_, v)| v.as_str())
+        };
+        let partition = partition_from(&fields);
+
+        if get("TYPE") == "disk" {
+            if partition.device.starts_with("/dev/zram") {
+                continue;
+            }
+            let mut disk = Disk {
+                device: partition.device.clone(),
+                model: get("MODEL").trim().to_string(),
+                size: partition.size.clone(),
+                transport: get("TRAN").to_string(),
+                removable: get("RM") == "1",
+                partitions: Vec::new(),
+            };
+            // A filesystem right on the disk, as on some USB sticks
+            if !partition.fstype.is_empty() {
+                disk.partitions.push(partition);
+            }
+            disks.push(disk);
+            continue;
+        }
+
+        let parent = get("PKNAME");
+        let Some(disk) = disks
+            .iter_mut()
+            .find(|d| d.device == parent || d.partitions.iter().any(|p| p.device == parent))
+        else {
+            continue;
+        };
+        if !partition.fstype.is_empty() {
+            disk.partitions.push(partition);
+        }
+    }
+    disks
+}
+
+/// All disks with their filesystems, including usage of the mounted ones.
+/// Blocks on `lsblk`.
+pub fn disks() -> Vec<Disk> {
+    Command::new("lsblk")
+        .args([
+            "-Ppno",
+            "NAME,TYPE,PKNAME,MODEL,TRAN,RM,SIZE,FSTYPE,UUID,LABEL,MOUNTPOINT,FSAVAIL,FSUSE%",
+        ])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_disks(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+}
+
+/// All partitions with a filesystem. Blocks on `lsblk`.
+pub fn partitions() -> Vec<Partition> {
+    Command::new("lsblk")
+        .args(["-Ppno", "NAME,FSTYPE,UUID,LABEL,SIZE,MOUNTPOINT"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_lsblk(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+}
+
+/// NTFS partitions that aren't part of the running system.
+pub fn ntfs_partitions() -> Vec<Partition> {
+    partitions()
+        .into_iter()
+        .filter(|p| p.is_ntfs() && !p.is_system())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_lsblk() {
+        let output = r#"NAME="/dev/sda" FSTYPE="" UUID="" LABEL="" SIZE="931.5G" MOUNTPOINT=""
+NAME="/dev/sda1" FSTYPE="ntfs" UUID="01D9A2B3C4D5E6F7" LABEL="Steam\x20Games" SIZE="931.5G" MOUNTPOINT="/run/media/user/Steam Games"
+NAME="/dev/nvme0n1p2" FSTYPE="btrfs" UUID="5f1c-aa" LABEL="" SIZE="476G" MOUNTPOINT="/"
+"#;
+        let partitions = parse_lsblk(output);
+        assert_eq!(partitions.len(), 2);
+
+        let ntfs = &partitions[0];
+        assert_eq!(ntfs.device, "/dev/sda1");
+        assert_eq!(ntfs.label, "Steam Games");
+        assert!(ntfs.is_ntfs());
+        assert!(!ntfs.is_system());
+        assert!(partitions[1].is_system());
+    }
+
+    #[test]
+    fn test_ntfs_fstab_entry() {
+        let partition = Partition {
+            uuid: "01D9A2B3C4D5E6F7".into(),
+            label: "Steam Games".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            partition.ntfs_fstab_entry(1000, 1000),
+            "UUID=01D9A2B3C4D5E6F7 /mnt/SteamGames ntfs3 \
+             uid=1000,gid=1000,umask=022,noatime,windows_names,nofail 0 0"
+        );
+
+        let unlabeled = Partition {
+            uuid: "ABCD".into(),
+            label: "..".into(),
+            ..Default::default()
+        };
+        assert_eq!(unlabeled.mount_dir(), PathBuf::from("/mnt/ABCD"));
+    }
+
+    #[test]
+    fn test_parse_disks() {
+        let output = r#"NAME="/dev/nvme0n1" TYPE="disk" PKNAME="" MODEL="Samsung SSD 980" TRAN="nvme" RM="0" SIZE="476.9G" FSTYPE="" UUID="" LABEL="" MOUNTPOINT="" FSAVAIL="" FSUSE%=""
+NAME="/dev/nvme0n1p1" TYPE="part" PKNAME="/dev/nvme0n1" MODEL="" TRAN="nvme" RM="0" SIZE="1G" FSTYPE="vfat" UUID="AB-CD" LABEL="" MOUNTPOINT="/boot" FSAVAIL="800M" FSUSE%="20%"
+NAME="/dev/nvme0n1p2" TYPE="part" PKNAME="/dev/nvme0n1" MODEL="" TRAN="nvme" RM="0" SIZE="475.9G" FSTYPE="crypto_LUKS" UUID="11" LABEL="" MOUNTPOINT="" FSAVAIL="" FSUSE%=""
+NAME="/dev/mapper/root" TYPE="crypt" PKNAME="/dev/nvme0n1p2" MODEL="" TRAN="" RM="0" SIZE="475.9G" FSTYPE="btrfs" UUID="22" LABEL="" MOUNTPOINT="/" FSAVAIL="300G" FSUSE%="37%"
+NAME="/dev/zram0" TYPE="disk" PKNAME="" MODEL="" TRAN="" RM="0" SIZE="8G" FSTYPE="swap" UUID="" LABEL="" MOUNTPOINT="[SWAP]" FSAVAIL="" FSUSE%=""
+NAME="/dev/sda" TYPE="disk" PKNAME="" MODEL="Flash Drive" TRAN="usb" RM="1" SIZE="29.3G" FSTYPE="exfat" UUID="33" LABEL="STICK" MOUNTPOINT="" FSAVAIL="" FSUSE%=""
+"#;
+        let disks = parse_disks(output);
+        assert_eq!(disks.len(), 2);
+
+        let nvme = &disks[0];
+        assert_eq!(nvme.display_name(), "Samsung SSD 980");
+        assert_eq!(nvme.partitions.len(), 3);
+        assert!(!nvme.partitions[1].is_mountable());
+        let root = &nvme.partitions[2];
+        assert_eq!(root.device, "/dev/mapper/root");
+        assert_eq!(root.used_percent, Some(37));
+        assert!(root.is_system());
+
+        let stick = &disks[1];
+        assert!(stick.removable);
+        assert_eq!(stick.partitions[0].label, "STICK");
+        assert_eq!(stick.partitions[0].used_percent, None);
+    }
+
+    #[test]
+    fn test_fstab_entry() {
+        let partition = Partition {
+            fstype: "ext4".into(),
+            uuid: "ABCD".into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            partition.fstab_entry(Path::new("/mnt/data"), 1000, 1000, true),
+            "UUID=ABCD /mnt/data ext4 defaults,noatime,noauto,x-systemd.automount,nofail 0 2"
+        );
+        assert!(is_valid_mount_point("/mnt/data"));
+        assert!(!is_valid_mount_point("/home/"));
+        assert!(!is_valid_mount_point("/mnt/my data"));
+        assert!(!is_valid_mount_point("/mnt/../etc"));
+    }
+
+    #[test]
+    fn test_parse_fstab_dir() {
+        let fstab = "# <file system> <dir> <type> <options> <dump> <pass>\n\
+                     UUID=5f1c-aa / btrfs defaults 0 0\n\
+                     UUID=01D9A2B3C4D5E6F7 /mnt/Steam\\040Games ntfs3 nofail 0 0\n";
+        assert_eq!(
+            parse_fstab_dir(fstab, "01D9A2B3C4D5E6F7"),
+            Some(PathBuf::from("/mnt/Steam Games"))
+        );
+        assert_eq!(parse_fstab_dir(fstab, "5f1c"), None);
+    }
+}
@@ -0,0 +1,318 @@
+//! Download and disk space estimates for install sequences.
+//!
+//! The packages a sequence installs are read from its pacman, AUR helper
+//! and `flatpak install` steps. Repository packages are sized with a
+//! `pacman -Sp` dry run, which also pulls in their missing dependencies;
+//! Flatpak apps with `flatpak remote-info`. AUR packages, Flatpak runtimes
+//! and anything a script installs can't be sized ahead of time, so the
+//! estimate is a lower bound when [`Estimate::partial`] is set.
+
+use crate::package::{self, parse_size};
+use crate::task_runner::{Command, CommandType};
+use std::collections::HashMap;
+use std::process::{Command as Process, Stdio};
+
+/// Estimates at least this big are worth a confirmation before installing.
+pub const LARGE: u64 = 500 * 1000 * 1000;
+
+/// Packages and apps a sequence installs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Targets {
+    /// Repository or AUR package names.
+    pub packages: Vec<String>,
+    /// Flatpak app ids, with the remote if the step names one.
+    pub flatpaks: Vec<(Option<String>, String)>,
+}
+
+/// Sizes of what a sequence installs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Estimate {
+    /// Bytes to download.
+    pub download: u64,
+    /// Bytes used on disk once installed.
+    pub installed: u64,
+    /// Packages and apps counted, dependencies included.
+    pub count: usize,
+    /// Some targets couldn't be sized, so the real numbers are higher.
+    pub partial: bool,
+}
+
+impl Estimate {
+    /// Whether the download or the disk space is at least [`LARGE`].
+    pub fn is_large(&self) -> bool {
+        self.download.max(self.installed) >= LARGE
+    }
+}
+
+/// Words after the flags, the targets of a pacman or flatpak call.
+fn operands(args: &[String]) -> impl Iterator<Item = &String> {
+    args.iter().skip(1).filter(|a| !a.starts_with('-'))
+}
+
+impl Targets {
+    /// Targets of the install steps in `commands`.
+    pub fn of(commands: &[Command]) -> Self {
+        let mut targets = Self::default();
+        for command in commands {
+            let Some(first) = command.args.first() else {
+                continue;
+            };
+            let pacman = match command.command_type {
+                CommandType::Aur => true,
+                CommandType::Privileged => command.program == "pacman",
+                _ => false,
+            };
+            // -S with targets installs; -Sy, -Syu and -Sc don't take any
+            if pacman && first.starts_with("-S") && !first.contains(['c', 'u', 'y']) {
+                targets.packages.extend(operands(&command.args).cloned());
+            } else if command.program == "flatpak" && first == "install" {
+                let words: Vec<&String> = operands(&command.args).collect();
+                let remote = match words.as_slice() {
+                    [remote, _, ..] if !remote.contains('.') => Some((*remote).clone()),
+                    _ => None,
+                };
+                let skip = usize::from(remote.is_some());
+                for id in &words[skip..] {
+                    targets.flatpaks.push((remote.clone(), (*id).clone()));
+                }
+            }
+        }
+        targets
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packages.is_empty() && self.flatpaks.is_empty()
+    }
+
+    /// Size up the targets. Blocks on pacman and flatpak, which may go to
+    /// the network for Flatpak metadata. `None` when nothing could be sized.
+    pub fn estimate(&self) -> Option<Estimate> {
+        let mut estimate = Estimate::default();
+
+        let (sizes, missing) = repo_sizes(&self.packages);
+        estimate.partial |= missing;
+        estimate.count += sizes.len();
+        for (download, installed) in sizes.values() {
+            estimate.download += download;
+            estimate.installed += installed;
+        }
+
+        let installed_flatpaks = package::installed_flatpaks();
+        for (remote, id) in &self.flatpaks {
+            if installed_flatpaks.contains(id) {
+                continue;
+            }
+            // Runtimes come on top and aren't listed
+            estimate.partial = true;
+            match flatpak_size(remote.as_deref().unwrap_or("flathub"), id) {
+                Some((download, installed)) => {
+                    estimate.count += 1;
+                    estimate.download += download;
+                    estimate.installed += installed;
+                }
+                None => log::debug!("No size for Flatpak {}", id),
+            }
+        }
+
+        (estimate.count > 0).then_some(estimate)
+    }
+}
+
+fn stdout(program: &str, args: &[&str]) -> Option<(String, String, bool)> {
+    let output = Process::new(program)
+        .args(args)
+        .env("LC_ALL", "C")
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    Some((
+        String::from_utf8_lossy(&output.stdout).into_owned(),
+        String::from_utf8_lossy(&output.stderr).into_owned(),
+        output.status.success(),
+    ))
+}
+
+/// Download and installed size of the repository packages missing from the
+/// system, by name, and whether some targets weren't in the repositories.
+fn repo_sizes(packages: &[String]) -> (HashMap<String, (u64, u64)>, bool) {
+    let mut names: Vec<&str> = packages.iter().map(String::as_str).collect();
+    let mut missing = false;
+
+    // AUR targets make the dry run fail; drop them and try again once
+    let mut downloads = None;
+    for _ in 0..2 {
+        if names.is_empty() {
+            break;
+        }
+        let mut args = vec!["-Sp", "--needed", "--noconfirm", "--print-format", "%n %s"];
+        args.extend(&names);
+        let Some((out, err, success)) = stdout("pacman", &args) else {
+            break;
+        };
+        if success {
+            downloads = Some(parse_downloads(&out));
+            break;
+        }
+        let not_found = parse_not_found(&err);
+        if not_found.is_empty() {
+            break;
+        }
+        missing = true;
+        names.retain(|n| !not_found.iter().any(|f| f == n));
+    }
+    let Some(downloads) = downloads else {
+        return (HashMap::new(), missing || !packages.is_empty());
+    };
+    if downloads.is_empty() {
+        return (HashMap::new(), missing);
+    }
+
+    let mut args = vec!["-Si"];
+    args.extend(downloads.iter().map(|(name, _)| name.as_str()));
+    let installed = stdout("pacman", &args)
+        .map(|(out, _, _)| parse_installed_sizes(&out))
+        .unwrap_or_default();
+
+    let sizes = downloads
+        .into_iter()
+        .map(|(name, download)| {
+            let size = installed.get(&name).copied().unwrap_or(0);
+            (name, (download, size))
+        })
+        .collect();
+    (sizes, missing)
+}
+
+/// `%n %s` lines of `pacman -Sp --print-format`.
+fn parse_downloads(output: &str) -> Vec<(String, u64)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (name, size) = line.trim().split_once(' ')?;
+            Some((name.to_string(), size.parse().ok()?))
+        })
+        .collect()
+}
+
+fn parse_not_found(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .filter_map(|line| line.strip_prefix("error: target not found: "))
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+/// Installed sizes by name from `pacman -Si`, first repository wins.
+fn parse_installed_sizes(output: &str) -> HashMap<String, u64> {
+    let mut sizes = HashMap::new();
+    let mut name = None;
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(" : ") else {
+            continue;
+        };
+        match key.trim() {
+            "Name" => name = Some(value.trim().to_string()),
+            "Installed Size" => {
+                if let (Some(name), Some(size)) = (name.take(), parse_size(value.trim())) {
+                    sizes.entry(name).or_insert(size);
+                }
+            }
+            _ => {}
+        }
+    }
+    sizes
+}
+
+fn flatpak_size(remote: &str, id: &str) -> Option<(u64, u64)> {
+    let (out, _, success) = stdout("flatpak", &["remote-info", remote, id])?;
+    if !success {
+        return None;
+    }
+    parse_remote_info(&out)
+}
+
+/// Download and installed size from `flatpak remote-info`.
+fn parse_remote_info(output: &str) -> Option<(u64, u64)> {
+    let field = |key: &str| {
+        output
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix(key)?.strip_prefix(':'))
+            .find_map(|value| parse_si_size(value.trim()))
+    };
+    Some((field("Download")?, field("Installed")?))
+}
+
+/// Parse a size as GLib formats it, such as `72.8 MB`, into bytes.
+fn parse_si_size(text: &str) -> Option<u64> {
+    let (number, unit) = text.split_once(' ')?;
+    let number: f64 = number.parse().ok()?;
+    let power = ["bytes", "kB", "MB", "GB", "TB"]
+        .iter()
+        .position(|u| *u == unit.trim())?;
+    Some((number * 1000f64.powi(power as i32)) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_install_targets() {
+        let commands = [
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-Syu", "--noconfirm"])
+                .description("Step")
+                .build(),
+            Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", "--needed", "steam", "gamemode"])
+                .description("Step")
+                .build(),
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&["install", "-y", "flathub", "com.obsproject.Studio"])
+                .description("Step")
+                .build(),
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-Rns", "--noconfirm", "old"])
+                .description("Step")
+                .build(),
+        ];
+        let targets = Targets::of(&commands);
+        assert_eq!(targets.packages, ["steam", "gamemode"]);
+        assert_eq!(
+            targets.flatpaks,
+            [(
+                Some("flathub".to_string()),
+                "com.obsproject.Studio".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(
+            parse_downloads("steam 4567\nlib32-mesa 1000\n"),
+            [
+                ("steam".to_string(), 4567),
+                ("lib32-mesa".to_string(), 1000)
+            ]
+        );
+        assert_eq!(
+            parse_not_found("error: target not found: heroic-games-launcher-bin\n"),
+            ["heroic-games-launcher-bin"]
+        );
+        let si = "Repository      : multilib\nName            : steam\n\
+                  Installed Size  : 4.00 MiB\n\n\
+                  Repository      : other\nName            : steam\n\
+                  Installed Size  : 1.00 MiB\n";
+        assert_eq!(parse_installed_sizes(si)["steam"], 4 * 1024 * 1024);
+        let info = "        ID: com.obsproject.Studio\n  Download: 72.8 MB\n Installed: 1.2 GB\n";
+        assert_eq!(parse_remote_info(info), Some((72_800_000, 1_200_000_000)));
+    }
+}
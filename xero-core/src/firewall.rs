@@ -0,0 +1,152 @@
+//! Firewall detection and port rules.
+//!
+//! Features that listen on the network open their ports through whichever
+//! of firewalld or ufw is running, and leave things alone when neither is.
+
+use std::process::Command;
+
+/// Supported firewall frontends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Firewall {
+    Firewalld,
+    Ufw,
+}
+
+/// Transport protocol of a port rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "tcp",
+            Protocol::Udp => "udp",
+        }
+    }
+}
+
+/// A port or an inclusive range of ports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PortRule {
+    pub first: u16,
+    pub last: u16,
+    pub protocol: Protocol,
+}
+
+impl PortRule {
+    pub const fn tcp(port: u16) -> Self {
+        Self::range(port, port, Protocol::Tcp)
+    }
+
+    pub const fn udp(port: u16) -> Self {
+        Self::range(port, port, Protocol::Udp)
+    }
+
+    pub const fn range(first: u16, last: u16, protocol: Protocol) -> Self {
+        Self {
+            first,
+            last,
+            protocol,
+        }
+    }
+
+    /// Ports joined with `separator` when this is a range.
+    fn ports(&self, separator: char) -> String {
+        if self.first == self.last {
+            self.first.to_string()
+        } else {
+            format!("{}{}{}", self.first, separator, self.last)
+        }
+    }
+}
+
+impl Firewall {
+    pub const ALL: [Firewall; 2] = [Firewall::Firewalld, Firewall::Ufw];
+
+    /// Human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Firewall::Firewalld => "firewalld",
+            Firewall::Ufw => "ufw",
+        }
+    }
+
+    fn service(&self) -> &'static str {
+        match self {
+            Firewall::Firewalld => "firewalld.service",
+            Firewall::Ufw => "ufw.service",
+        }
+    }
+
+    /// Whether this firewall's service is running.
+    pub fn is_active(&self) -> bool {
+        Command::new("systemctl")
+            .args(["is-active", "--quiet", self.service()])
+            .status()
+            .is_ok_and(|s| s.success())
+    }
+
+    /// Shell script that opens `rules` permanently. `comment` labels the
+    /// rules where the firewall supports it.
+    pub fn open_ports_script(&self, rules: &[PortRule], comment: &str) -> String {
+        match self {
+            Firewall::Firewalld => {
+                let ports: Vec<String> = rules
+                    .iter()
+                    .map(|r| format!("--add-port={}/{}", r.ports('-'), r.protocol.as_str()))
+                    .collect();
+                format!(
+                    "firewall-cmd --permanent {} && firewall-cmd --reload",
+                    ports.join(" ")
+                )
+            }
+            Firewall::Ufw => rules
+                .iter()
+                .map(|r| {
+                    format!(
+                        "ufw allow {}/{} comment '{}'",
+                        r.ports(':'),
+                        r.protocol.as_str(),
+                        comment.replace('\'', "")
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" && "),
+        }
+    }
+}
+
+/// The running firewall, if any. Blocks on systemctl.
+pub fn active() -> Option<Firewall> {
+    Firewall::ALL.into_iter().find(|f| f.is_active())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: [PortRule; 2] = [
+        PortRule::tcp(47990),
+        PortRule::range(47998, 48000, Protocol::Udp),
+    ];
+
+    #[test]
+    fn test_firewalld_script() {
+        assert_eq!(
+            Firewall::Firewalld.open_ports_script(&RULES, "Sunshine"),
+            "firewall-cmd --permanent --add-port=47990/tcp --add-port=47998-48000/udp \
+             && firewall-cmd --reload"
+        );
+    }
+
+    #[test]
+    fn test_ufw_script() {
+        assert_eq!(
+            Firewall::Ufw.open_ports_script(&RULES, "Sunshine"),
+            "ufw allow 47990/tcp comment 'Sunshine' && ufw allow 47998:48000/udp comment 'Sunshine'"
+        );
+    }
+}
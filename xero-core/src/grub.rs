@@ -0,0 +1,253 @@
+//! GRUB themes from the xero-grubs collection.
+//!
+//! Themes are copied to `/boot/grub/themes/` and selected with `GRUB_THEME`
+//! in `/etc/default/grub`. The file is backed up before every change, which
+//! keeps the previous theme around for a one-click revert.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const DEFAULT_GRUB: &str = "/etc/default/grub";
+
+/// Copy of `/etc/default/grub` from before the last theme change.
+pub const BACKUP: &str = "/etc/default/grub.xero-toolkit.bak";
+
+pub const THEMES_DIR: &str = "/boot/grub/themes";
+
+/// Image names checked, in order, for a theme's thumbnail. Most themes
+/// have no screenshot, but their background makes a fair stand-in.
+const PREVIEW_NAMES: &[&str] = &[
+    "preview.png",
+    "preview.jpg",
+    "screenshot.png",
+    "screenshot.jpg",
+    "background.png",
+    "background.jpg",
+];
+
+/// A theme in the collection.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub name: String,
+    /// Folder holding `theme.txt`.
+    pub dir: PathBuf,
+    pub preview: Option<PathBuf>,
+}
+
+impl Theme {
+    /// `GRUB_THEME` value once the theme is installed.
+    pub fn theme_txt(&self) -> String {
+        format!("{}/{}/theme.txt", THEMES_DIR, self.name)
+    }
+}
+
+/// Where the collection is unpacked, `~/xero-grubs`.
+pub fn themes_repo() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/root"))
+        .join("xero-grubs")
+}
+
+/// Unpack a snapshot of the collection into `repo`.
+pub fn extract_themes(archive: &Path, repo: &Path) -> Result<()> {
+    std::fs::create_dir_all(repo)
+        .with_context(|| format!("Failed to create {}", repo.display()))?;
+    let status = Command::new("tar")
+        .arg("-xzf")
+        .arg(archive)
+        .arg("-C")
+        .arg(repo)
+        .arg("--strip-components=1")
+        .status()
+        .context("Failed to run tar")?;
+    if !status.success() {
+        bail!("Failed to unpack {}", archive.display());
+    }
+    let _ = std::fs::remove_file(archive);
+    Ok(())
+}
+
+fn find_preview(dir: &Path) -> Option<PathBuf> {
+    PREVIEW_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Themes under `repo`, a folder with a `theme.txt` each, sorted by name.
+pub fn list_themes(repo: &Path) -> Vec<Theme> {
+    fn walk(dir: &Path, depth: usize, themes: &mut Vec<Theme>) {
+        if dir.join("theme.txt").is_file() {
+            if let Some(name) = dir.file_name().and_then(|n| n.to_str()) {
+                themes.push(Theme {
+                    name: name.to_string(),
+                    dir: dir.to_path_buf(),
+                    preview: find_preview(dir),
+                });
+            }
+            return;
+        }
+        if depth == 0 {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if path.is_dir() && !hidden {
+                walk(&path, depth - 1, themes);
+            }
+        }
+    }
+
+    let mut themes = Vec::new();
+    walk(repo, 3, &mut themes);
+    themes.sort_by(|a, b| a.name.cmp(&b.name));
+    themes.dedup_by(|a, b| a.name == b.name);
+    themes
+}
+
+/// Value of the last active `KEY=value` line, the one that wins when the
+/// file is sourced, without quotes.
+pub fn get_var(contents: &str, key: &str) -> Option<String> {
+    contents
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix(key)?.strip_prefix('='))
+        .next_back()
+        .map(|value| value.trim().trim_matches(['"', '\'']).to_string())
+}
+
+/// Set `key` to `value`, replacing the first active or commented-out line
+/// for it, or appending one.
+pub fn set_var(contents: &str, key: &str, value: &str) -> String {
+    let assignment = format!("{}=\"{}\"", key, value);
+    let mut replaced = false;
+    let mut lines: Vec<String> = contents
+        .lines()
+        .map(|line| {
+            let bare = line.trim_start().trim_start_matches('#').trim_start();
+            if !replaced && bare.strip_prefix(key).is_some_and(|r| r.starts_with('=')) {
+                replaced = true;
+                assignment.clone()
+            } else {
+                line.to_string()
+            }
+        })
+        .collect();
+    if !replaced {
+        lines.push(assignment);
+    }
+    lines.join("\n") + "\n"
+}
+
+/// `/etc/default/grub` contents selecting `theme`. A console-only
+/// terminal output hides any theme, so it's commented out.
+pub fn with_theme(contents: &str, theme: &Theme) -> String {
+    let mut contents = set_var(contents, "GRUB_THEME", &theme.theme_txt());
+    if get_var(&contents, "GRUB_TERMINAL_OUTPUT").as_deref() == Some("console") {
+        contents = contents
+            .lines()
+            .map(|line| {
+                if line.starts_with("GRUB_TERMINAL_OUTPUT=") {
+                    format!("#{}", line)
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+            + "\n";
+    }
+    contents
+}
+
+/// Theme name from a `GRUB_THEME` path, the folder holding `theme.txt`.
+fn theme_name(theme_txt: &str) -> Option<String> {
+    Path::new(theme_txt)
+        .parent()?
+        .file_name()?
+        .to_str()
+        .map(str::to_string)
+}
+
+/// Name of the theme in use, if any.
+pub fn current_theme() -> Option<String> {
+    let contents = std::fs::read_to_string(DEFAULT_GRUB).ok()?;
+    theme_name(&get_var(&contents, "GRUB_THEME")?)
+}
+
+/// Theme a revert returns to. `None` when there's no backup, `Some(None)`
+/// when GRUB had no theme before.
+pub fn previous_theme() -> Option<Option<String>> {
+    let contents = std::fs::read_to_string(BACKUP).ok()?;
+    Some(get_var(&contents, "GRUB_THEME").and_then(|t| theme_name(&t)))
+}
+
+/// Root script installing a theme. `$1` is the theme folder, `$2` its name
+/// and `$3` the new `/etc/default/grub`.
+pub fn apply_script() -> String {
+    format!(
+        "set -e\n\
+         mkdir -p {themes}\n\
+         rm -rf \"{themes}/$2\"\n\
+         cp -r \"$1\" \"{themes}/$2\"\n\
+         cp -f {grub} {backup}\n\
+         printf '%s' \"$3\" > {grub}\n\
+         grub-mkconfig -o /boot/grub/grub.cfg\n",
+        themes = THEMES_DIR,
+        grub = DEFAULT_GRUB,
+        backup = BACKUP
+    )
+}
+
+/// Root script restoring the backup. Installed theme files stay.
+pub fn revert_script() -> String {
+    format!(
+        "set -e\n\
+         cp -f {backup} {grub}\n\
+         rm -f {backup}\n\
+         grub-mkconfig -o /boot/grub/grub.cfg\n",
+        grub = DEFAULT_GRUB,
+        backup = BACKUP
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_theme() {
+        let theme = Theme {
+            name: "XeroDark".into(),
+            dir: PathBuf::from("/home/a/xero-grubs/XeroDark"),
+            preview: None,
+        };
+        let contents = "GRUB_DEFAULT=0\n#GRUB_THEME=\"/path/to/gfxtheme\"\n\
+                        GRUB_TERMINAL_OUTPUT=console\n";
+        let updated = with_theme(contents, &theme);
+        assert_eq!(
+            updated,
+            "GRUB_DEFAULT=0\n\
+             GRUB_THEME=\"/boot/grub/themes/XeroDark/theme.txt\"\n\
+             #GRUB_TERMINAL_OUTPUT=console\n"
+        );
+        let theme_txt = get_var(&updated, "GRUB_THEME").unwrap();
+        assert_eq!(theme_name(&theme_txt).as_deref(), Some("XeroDark"));
+
+        // Appended when there's no line for it at all
+        assert!(
+            set_var("GRUB_DEFAULT=0", "GRUB_THEME", "/t.txt").ends_with("GRUB_THEME=\"/t.txt\"\n")
+        );
+    }
+
+    #[test]
+    fn test_get_var() {
+        let contents = "GRUB_TIMEOUT='5'\n#GRUB_THEME=\"/old\"\nGRUB_TIMEOUT_STYLE=menu\n";
+        assert_eq!(get_var(contents, "GRUB_TIMEOUT").as_deref(), Some("5"));
+        assert_eq!(get_var(contents, "GRUB_THEME"), None);
+    }
+}
@@ -0,0 +1,95 @@
+//! GPU detection.
+//!
+//! Actions that depend on the graphics hardware, such as encoder plugins or
+//! compute runtimes, branch on the vendors found here rather than asking.
+
+use log::debug;
+
+/// GPU vendors with their own driver and acceleration stacks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GpuVendor {
+    Nvidia,
+    Amd,
+    Intel,
+}
+
+impl GpuVendor {
+    /// Human readable vendor name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            GpuVendor::Nvidia => "NVIDIA",
+            GpuVendor::Amd => "AMD",
+            GpuVendor::Intel => "Intel",
+        }
+    }
+
+    /// VA-API driver package for hardware video decoding and encoding.
+    pub fn vaapi_package(&self) -> &'static str {
+        match self {
+            GpuVendor::Nvidia => "libva-nvidia-driver",
+            GpuVendor::Amd => "libva-mesa-driver",
+            GpuVendor::Intel => "intel-media-driver",
+        }
+    }
+}
+
+/// Parse a PCI vendor id such as `0x10de` from sysfs.
+pub fn parse_pci_vendor(id: &str) -> Option<GpuVendor> {
+    match id.trim().to_ascii_lowercase().as_str() {
+        "0x10de" => Some(GpuVendor::Nvidia),
+        "0x1002" => Some(GpuVendor::Amd),
+        "0x8086" => Some(GpuVendor::Intel),
+        _ => None,
+    }
+}
+
+/// Vendors of all GPUs in the system, each listed once, in DRM card order.
+pub fn gpus() -> Vec<GpuVendor> {
+    let mut vendors = Vec::new();
+    let Ok(dir) = std::fs::read_dir("/sys/class/drm") else {
+        return vendors;
+    };
+
+    let mut cards: Vec<_> = dir
+        .flatten()
+        .filter(|e| {
+            // card0, card1, ... but not connectors like card0-DP-1
+            e.file_name()
+                .to_str()
+                .and_then(|n| n.strip_prefix("card"))
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(|e| e.path())
+        .collect();
+    cards.sort();
+
+    for card in cards {
+        let id = std::fs::read_to_string(card.join("device/vendor")).unwrap_or_default();
+        if let Some(vendor) = parse_pci_vendor(&id) {
+            if !vendors.contains(&vendor) {
+                vendors.push(vendor);
+            }
+        }
+    }
+
+    debug!("Detected GPUs: {:?}", vendors);
+    vendors
+}
+
+/// Whether any GPU is from `vendor`.
+pub fn has_gpu(vendor: GpuVendor) -> bool {
+    gpus().contains(&vendor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pci_vendor() {
+        assert_eq!(parse_pci_vendor("0x10de\n"), Some(GpuVendor::Nvidia));
+        assert_eq!(parse_pci_vendor("0x1002"), Some(GpuVendor::Amd));
+        assert_eq!(parse_pci_vendor("0x8086"), Some(GpuVendor::Intel));
+        assert_eq!(parse_pci_vendor("0x1af4"), None);
+    }
+}
@@ -0,0 +1,212 @@
+//! Record of the sequences run by the task runner.
+//!
+//! Each finished sequence is saved to
+//! `~/.local/state/xero-toolkit/history.toml` with its outcome, the commands
+//! it ran and the path of its output log, giving an audit trail of what the
+//! toolkit did. Only the newest [`MAX_ENTRIES`] are kept, along with their
+//! logs.
+
+use crate::task_runner::Command;
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of entries kept in the history.
+pub const MAX_ENTRIES: usize = 50;
+
+/// How a sequence ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Success,
+    Failed,
+    Cancelled,
+}
+
+/// One finished sequence.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Entry {
+    /// Task runner title.
+    pub title: String,
+    /// When the sequence finished, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    pub outcome: Outcome,
+    /// Saved output of the task runner, if it could be written.
+    pub log: Option<PathBuf>,
+    /// Commands as requested, without hook steps, so they can be run again.
+    pub commands: Vec<Command>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryFile {
+    #[serde(default)]
+    entries: Vec<Entry>,
+}
+
+/// Directory holding the history file and the output logs.
+pub fn state_dir() -> PathBuf {
+    dirs::state_dir()
+        .or_else(dirs::data_local_dir)
+        .unwrap_or_else(|| PathBuf::from("~/.local/state"))
+        .join("xero-toolkit")
+}
+
+fn history_file() -> PathBuf {
+    state_dir().join("history.toml")
+}
+
+/// Current time in seconds since the Unix epoch.
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// All recorded entries, oldest first. A missing or invalid history is
+/// treated as empty.
+pub fn load() -> Vec<Entry> {
+    let path = history_file();
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    match toml::from_str::<HistoryFile>(&text) {
+        Ok(file) => file.entries,
+        Err(e) => {
+            warn!("Ignoring invalid {}: {}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+/// The newest `count` entries, newest first.
+pub fn recent(count: usize) -> Vec<Entry> {
+    load().into_iter().rev().take(count).collect()
+}
+
+/// Add `entry`, dropping the oldest entries beyond `max`. Returns the
+/// dropped entries.
+fn push(entries: &mut Vec<Entry>, entry: Entry, max: usize) -> Vec<Entry> {
+    entries.push(entry);
+    let excess = entries.len().saturating_sub(max);
+    entries.drain(..excess).collect()
+}
+
+/// Append `entry` to the history, removing the logs of entries that no
+/// longer fit.
+pub fn record(entry: Entry) -> Result<()> {
+    let mut entries = load();
+    for dropped in push(&mut entries, entry, MAX_ENTRIES) {
+        if let Some(log) = dropped.log {
+            let _ = std::fs::remove_file(log);
+        }
+    }
+
+    let path = history_file();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let text = toml::to_string(&HistoryFile { entries }).context("Failed to encode history")?;
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, text).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// File name for the log of `title` started at `timestamp`.
+fn log_name(title: &str, timestamp: i64) -> String {
+    let slug: String = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    format!("{}-{}.log", timestamp, slug)
+}
+
+/// Create the log the output of a sequence is written to as it runs, and
+/// return its path with the open file.
+pub fn create_log(title: &str, timestamp: i64) -> Result<(PathBuf, std::fs::File)> {
+    let dir = state_dir().join("logs");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(log_name(title, timestamp));
+    let file = std::fs::File::create(&path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    Ok((path, file))
+}
+
+/// Whether the log of an entry still exists.
+pub fn has_log(entry: &Entry) -> bool {
+    entry.log.as_deref().is_some_and(Path::exists)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, timestamp: i64) -> Entry {
+        Entry {
+            title: title.to_string(),
+            timestamp,
+            outcome: Outcome::Success,
+            log: None,
+            commands: vec![Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-Scc", "--noconfirm"])
+                .description("Clearing cache")
+                .build()],
+        }
+    }
+
+    #[test]
+    fn round_trips_entries() {
+        let mut failed = entry("Arch ISO", 1_700_000_000);
+        failed.outcome = Outcome::Failed;
+        failed.log = Some(PathBuf::from("/tmp/arch-iso.log"));
+        failed.commands.push(
+            Command::builder()
+                .download("https://example.com/arch.iso", "/tmp/arch.iso")
+                .sha256("abc")
+                .description("Downloading")
+                .build(),
+        );
+        let file = HistoryFile {
+            entries: vec![entry("Clear Pacman Cache", 1_600_000_000), failed],
+        };
+
+        let text = toml::to_string(&file).unwrap();
+        let parsed: HistoryFile = toml::from_str(&text).unwrap();
+
+        assert_eq!(parsed.entries.len(), 2);
+        assert_eq!(parsed.entries[1].outcome, Outcome::Failed);
+        assert_eq!(parsed.entries[1].commands[1], file.entries[1].commands[1]);
+        assert_eq!(parsed.entries[0].log, None);
+    }
+
+    #[test]
+    fn drops_oldest_entries() {
+        let mut entries = vec![entry("a", 1), entry("b", 2)];
+        assert!(push(&mut entries, entry("c", 3), 3).is_empty());
+
+        let dropped = push(&mut entries, entry("d", 4), 3);
+        assert_eq!(dropped[0].title, "a");
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].title, "b");
+    }
+
+    #[test]
+    fn names_logs_after_title() {
+        assert_eq!(
+            log_name("Config/Rice Reset", 42),
+            "42-config-rice-reset.log"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Package holds, pacman's `IgnorePkg` and `IgnoreGroup` lists.
+//!
+//! Held packages are skipped by system upgrades, which is how a package
+//! stays on an older version. Holding a package others depend on can stop
+//! an upgrade from resolving, so [`check_upgrade`] asks pacman for a dry
+//! run against the last synced databases and reports what it ran into.
+
+use crate::pacman_conf::PacmanConf;
+use std::collections::BTreeSet;
+
+/// Held packages and groups from the `[options]` section.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Holds {
+    pub packages: Vec<String>,
+    pub groups: Vec<String>,
+}
+
+impl Holds {
+    /// Read the holds from a parsed pacman.conf.
+    pub fn from_conf(conf: &PacmanConf) -> Self {
+        Self {
+            packages: conf.list("options", "IgnorePkg"),
+            groups: conf.list("options", "IgnoreGroup"),
+        }
+    }
+
+    /// Write the holds into a parsed pacman.conf.
+    pub fn apply(&self, conf: &mut PacmanConf) {
+        conf.set_list("options", "IgnorePkg", &self.packages);
+        conf.set_list("options", "IgnoreGroup", &self.groups);
+    }
+}
+
+/// What a dry-run upgrade says about the holds.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct UpgradeCheck {
+    /// Held packages with a newer version, e.g. `linux (6.1-1 => 6.2-1)`.
+    pub held_back: Vec<String>,
+    /// Dependency errors that stop the upgrade.
+    pub problems: Vec<String>,
+}
+
+/// Groups with at least one installed package, from `pacman -Qg`.
+pub fn installed_groups() -> Vec<String> {
+    std::process::Command::new("pacman")
+        .arg("-Qg")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split_whitespace().next())
+                .map(str::to_string)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Dry-run a system upgrade with `pacman -Sup`, which needs no root and
+/// doesn't sync the databases. Blocks on pacman.
+pub fn check_upgrade() -> UpgradeCheck {
+    match std::process::Command::new("pacman")
+        .args(["-Sup", "--noconfirm"])
+        .env("LC_ALL", "C")
+        .output()
+    {
+        Ok(output) => parse_upgrade(
+            &String::from_utf8_lossy(&output.stderr),
+            output.status.success(),
+        ),
+        Err(e) => {
+            log::warn!("Failed to run pacman: {}", e);
+            UpgradeCheck::default()
+        }
+    }
+}
+
+fn parse_upgrade(stderr: &str, success: bool) -> UpgradeCheck {
+    let mut check = UpgradeCheck::default();
+    for line in stderr.lines() {
+        if let Some(rest) = line.strip_prefix("warning: ") {
+            if let Some((name, versions)) = rest.split_once(": ignoring package upgrade ") {
+                check.held_back.push(format!("{} {}", name, versions));
+            }
+        } else if let Some(problem) = line.strip_prefix(":: ") {
+            if !success {
+                check.problems.push(problem.to_string());
+            }
+        }
+    }
+    check
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_pacman_conf() {
+        let mut conf = PacmanConf::parse("[options]\nIgnorePkg = linux\n#IgnoreGroup =\n");
+        let mut holds = Holds::from_conf(&conf);
+        assert_eq!(holds.packages, ["linux"]);
+        assert!(holds.groups.is_empty());
+
+        holds.groups.push("gnome".to_string());
+        holds.apply(&mut conf);
+        assert_eq!(Holds::from_conf(&conf), holds);
+    }
+
+    #[test]
+    fn reads_dry_run_output() {
+        let stderr = "\
+warning: linux: ignoring package upgrade (6.1.1-1 => 6.2.1-1)
+error: failed to prepare transaction (could not satisfy dependencies)
+:: installing linux-headers (6.2.1-1) breaks dependency 'linux-headers=6.1.1' required by nvidia
+";
+        let check = parse_upgrade(stderr, false);
+        assert_eq!(check.held_back, ["linux (6.1.1-1 => 6.2.1-1)"]);
+        assert_eq!(check.problems.len(), 1);
+        assert!(check.problems[0].contains("breaks dependency"));
+
+        assert!(parse_upgrade(stderr, true).problems.is_empty());
+    }
+}
@@ -0,0 +1,192 @@
+//! Hardware video acceleration checks.
+//!
+//! Runs `vainfo`, `vdpauinfo` and `nvidia-smi` and reports which APIs
+//! work, so a missing VA-API driver shows up as a named package instead of
+//! as high CPU usage during video playback.
+
+use super::hardware::{self, GpuVendor};
+use log::debug;
+use std::process::Command;
+
+/// Codecs, containers and the tools [`check`] uses.
+pub const CODEC_PACKAGES: &[&str] = &[
+    "gstreamer",
+    "gst-plugins-base",
+    "gst-plugins-good",
+    "gst-plugins-bad",
+    "gst-plugins-ugly",
+    "gst-plugin-va",
+    "gst-libav",
+    "ffmpeg",
+    "libva-utils",
+    "vdpauinfo",
+];
+
+/// Video acceleration APIs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Api {
+    Vaapi,
+    Vdpau,
+    Nvenc,
+}
+
+impl Api {
+    /// Human readable API name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Api::Vaapi => "VA-API",
+            Api::Vdpau => "VDPAU",
+            Api::Nvenc => "NVENC / NVDEC",
+        }
+    }
+}
+
+/// Result of checking one API.
+#[derive(Clone, Debug)]
+pub struct ApiStatus {
+    pub api: Api,
+    pub working: bool,
+    /// Driver and capabilities when working, the reason otherwise.
+    pub detail: String,
+}
+
+/// Results of a [`check`].
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub gpus: Vec<GpuVendor>,
+    pub apis: Vec<ApiStatus>,
+    /// Driver packages that would make a failing API work.
+    pub missing_packages: Vec<&'static str>,
+}
+
+/// stdout of `program`, `None` if it isn't installed or failed.
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+}
+
+/// Driver name and decode/encode profile counts from `vainfo` output.
+fn parse_vainfo(output: &str) -> Option<String> {
+    let driver = output
+        .lines()
+        .find_map(|l| l.split_once("Driver version:"))
+        .map(|(_, v)| v.trim().to_string())?;
+    let decode = output
+        .lines()
+        .filter(|l| l.contains("VAEntrypointVLD"))
+        .count();
+    let encode = output
+        .lines()
+        .filter(|l| l.contains("VAEntrypointEnc"))
+        .count();
+    (decode + encode > 0)
+        .then(|| format!("{} ({} decode, {} encode profiles)", driver, decode, encode))
+}
+
+/// Implementation name from `vdpauinfo` output.
+fn parse_vdpauinfo(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|l| l.trim().strip_prefix("Information string:"))
+        .map(|info| info.trim().to_string())
+        .filter(|info| !info.is_empty())
+}
+
+fn check_vaapi() -> ApiStatus {
+    let detail = match run("vainfo", &[]) {
+        Some(output) => parse_vainfo(&output).ok_or("No usable VA-API driver"),
+        None => Err("vainfo failed or isn't installed"),
+    };
+    status(Api::Vaapi, detail)
+}
+
+fn check_vdpau() -> ApiStatus {
+    let detail = match run("vdpauinfo", &[]) {
+        Some(output) => parse_vdpauinfo(&output).ok_or("No usable VDPAU driver"),
+        None => Err("vdpauinfo failed or isn't installed"),
+    };
+    status(Api::Vdpau, detail)
+}
+
+fn check_nvenc() -> ApiStatus {
+    let query = ["--query-gpu=name,driver_version", "--format=csv,noheader"];
+    let detail = run("nvidia-smi", &query)
+        .and_then(|o| {
+            o.lines()
+                .next()
+                .map(|l| l.trim().replace(", ", ", driver "))
+        })
+        .ok_or("nvidia-smi failed, is the NVIDIA driver loaded?");
+    status(Api::Nvenc, detail)
+}
+
+fn status(api: Api, detail: Result<String, &str>) -> ApiStatus {
+    ApiStatus {
+        api,
+        working: detail.is_ok(),
+        detail: detail.unwrap_or_else(str::to_string),
+    }
+}
+
+/// Check every API relevant to the GPUs in the system. Runs external tools
+/// and pacman, so call off the main thread.
+pub fn check() -> Report {
+    let gpus = hardware::gpus();
+    let mut apis = vec![check_vaapi(), check_vdpau()];
+    if gpus.contains(&GpuVendor::Nvidia) {
+        apis.push(check_nvenc());
+    }
+
+    let vaapi_working = apis[0].working;
+    let missing_packages = gpus
+        .iter()
+        .map(|g| g.vaapi_package())
+        .filter(|pkg| !vaapi_working && !super::is_package_installed(pkg))
+        .collect();
+
+    debug!("Hardware acceleration check: {:?}", apis);
+    Report {
+        gpus,
+        apis,
+        missing_packages,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vainfo() {
+        let output = "\
+vainfo: VA-API version: 1.22 (libva 2.22.0)
+vainfo: Driver version: Mesa Gallium driver 25.0.1 for AMD Radeon RX 6700 XT
+vainfo: Supported profile and entrypoints
+      VAProfileH264Main               :\tVAEntrypointVLD
+      VAProfileH264Main               :\tVAEntrypointEncSlice
+      VAProfileHEVCMain               :\tVAEntrypointVLD
+";
+        assert_eq!(
+            parse_vainfo(output).as_deref(),
+            Some("Mesa Gallium driver 25.0.1 for AMD Radeon RX 6700 XT (2 decode, 1 encode profiles)")
+        );
+        assert_eq!(
+            parse_vainfo("vainfo: Supported profile and entrypoints\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_vdpauinfo() {
+        let output = "display: :0   screen: 0\nAPI version: 1\nInformation string: G3DVL VDPAU Driver Shared Library version 1.0\n";
+        assert_eq!(
+            parse_vdpauinfo(output).as_deref(),
+            Some("G3DVL VDPAU Driver Shared Library version 1.0")
+        );
+        assert_eq!(parse_vdpauinfo("Failed to open VDPAU backend\n"), None);
+    }
+}
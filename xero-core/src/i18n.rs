@@ -0,0 +1,80 @@
+//! Translations through gettext.
+//!
+//! UI files mark their strings `translatable="yes"`, which GtkBuilder looks
+//! up in the text domain set here. Rust strings go through [`gettext`], or
+//! [`gettext_f`] when they have placeholders; task descriptions are
+//! translated by the command builder and task titles by the task runner.
+//! Catalogs are compiled from `po/` into `/opt/xero-toolkit/locale` by the
+//! installer.
+//!
+//! The language follows the system locale unless `language` is set in
+//! `~/.config/xero-toolkit/settings.toml`.
+
+use crate::config;
+use gettextrs::{bind_textdomain_codeset, bindtextdomain, setlocale, textdomain, LocaleCategory};
+use log::{info, warn};
+
+pub use gettextrs::{gettext, ngettext};
+
+/// Marks a string for extraction where it can't be translated yet, such as
+/// in a `const`. Translate it with [`gettext`] where it's shown.
+pub const fn n_(msgid: &'static str) -> &'static str {
+    msgid
+}
+
+/// Set up the locale and the text domain. Call before any UI is built.
+pub fn init() {
+    if let Some(language) = config::user::load().language {
+        info!("Using configured language '{}'", language);
+        // Takes precedence over LANG and LC_MESSAGES in gettext lookups
+        std::env::set_var("LANGUAGE", &language);
+    }
+
+    setlocale(LocaleCategory::LcAll, "");
+
+    let domain = config::app_info::NAME;
+    if let Err(e) = bindtextdomain(domain, config::paths::LOCALE) {
+        warn!("Failed to bind text domain {}: {}", domain, e);
+        return;
+    }
+    if let Err(e) = bind_textdomain_codeset(domain, "UTF-8") {
+        warn!("Failed to set text domain codeset: {}", e);
+    }
+    if let Err(e) = textdomain(domain) {
+        warn!("Failed to set text domain {}: {}", domain, e);
+    }
+}
+
+/// Translate `msgid` and fill in its `{name}` placeholders from `args`,
+/// so translators can move them around the sentence.
+pub fn gettext_f(msgid: &str, args: &[(&str, &str)]) -> String {
+    fill(gettext(msgid), args)
+}
+
+/// Like [`gettext_f`], picking the singular or plural form for `n`.
+pub fn ngettext_f(singular: &str, plural: &str, n: u32, args: &[(&str, &str)]) -> String {
+    fill(ngettext(singular, plural, n), args)
+}
+
+fn fill(mut text: String, args: &[(&str, &str)]) -> String {
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_named_placeholders() {
+        assert_eq!(
+            gettext_f(
+                "Move {app} to {dir}",
+                &[("app", "Steam"), ("dir", "/mnt/games")]
+            ),
+            "Move Steam to /mnt/games"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Icon and cursor themes offered on the Customization page.
+//!
+//! A theme is applied for Plasma through its own tools, which notify running
+//! apps, and for GTK apps through the GNOME interface settings.
+
+use std::process::Command;
+
+/// What a theme changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    Icons,
+    Cursors,
+}
+
+impl Kind {
+    /// Theme Plasma falls back to, applied before its theme is removed.
+    pub fn default_theme(&self) -> &'static str {
+        match self {
+            Kind::Icons => "breeze",
+            Kind::Cursors => "breeze_cursors",
+        }
+    }
+
+    /// The theme in use, from Plasma's settings or else from GTK's.
+    pub fn current(&self) -> Option<String> {
+        let (file, group, key, gsettings_key) = match self {
+            Kind::Icons => ("kdeglobals", "Icons", "Theme", "icon-theme"),
+            Kind::Cursors => ("kcminputrc", "Mouse", "cursorTheme", "cursor-theme"),
+        };
+        let read = |program: &str, args: &[&str]| {
+            let output = Command::new(program).args(args).output().ok()?;
+            let value = String::from_utf8_lossy(&output.stdout)
+                .trim()
+                .trim_matches('\'')
+                .to_string();
+            (output.status.success() && !value.is_empty()).then_some(value)
+        };
+        read(
+            "kreadconfig6",
+            &["--file", file, "--group", group, "--key", key],
+        )
+        .or_else(|| {
+            read(
+                "gsettings",
+                &["get", "org.gnome.desktop.interface", gsettings_key],
+            )
+        })
+    }
+
+    /// Script applying the theme named `$1`. Missing tools are skipped, so
+    /// it works on Plasma, GNOME and in between.
+    pub fn apply_script(&self) -> &'static str {
+        match self {
+            Kind::Icons => {
+                r#"kwriteconfig6 --file kdeglobals --group Icons --key Theme "$1" 2>/dev/null || true
+if [ -x /usr/lib/plasma-changeicons ]; then /usr/lib/plasma-changeicons "$1" || true; fi
+gsettings set org.gnome.desktop.interface icon-theme "$1" 2>/dev/null || true
+"#
+            }
+            Kind::Cursors => {
+                r#"if command -v plasma-apply-cursortheme >/dev/null; then plasma-apply-cursortheme "$1" || true; fi
+gsettings set org.gnome.desktop.interface cursor-theme "$1" 2>/dev/null || true
+"#
+            }
+        }
+    }
+}
+
+/// An icon or cursor theme package.
+#[derive(Clone, Copy, Debug)]
+pub struct IconTheme {
+    pub name: &'static str,
+    pub kind: Kind,
+    pub package: &'static str,
+    /// Built from the AUR rather than the repositories.
+    pub aur: bool,
+    /// Theme folders the package installs, with a label each. The first is
+    /// the one applied by default.
+    pub variants: &'static [(&'static str, &'static str)],
+    /// Stock themes the desktop depends on are never removed.
+    pub removable: bool,
+}
+
+pub const THEMES: &[IconTheme] = &[
+    IconTheme {
+        name: "Papirus",
+        kind: Kind::Icons,
+        package: "papirus-icon-theme",
+        aur: false,
+        variants: &[
+            ("Papirus", "Papirus"),
+            ("Papirus-Dark", "Dark"),
+            ("Papirus-Light", "Light"),
+        ],
+        removable: true,
+    },
+    IconTheme {
+        name: "Tela",
+        kind: Kind::Icons,
+        package: "tela-icon-theme",
+        aur: true,
+        variants: &[("Tela", "Tela"), ("Tela-dark", "Dark")],
+        removable: true,
+    },
+    IconTheme {
+        name: "Breeze",
+        kind: Kind::Icons,
+        package: "breeze-icons",
+        aur: false,
+        variants: &[("breeze", "Breeze"), ("breeze-dark", "Dark")],
+        removable: false,
+    },
+    IconTheme {
+        name: "Bibata",
+        kind: Kind::Cursors,
+        package: "bibata-cursor-theme-bin",
+        aur: true,
+        variants: &[
+            ("Bibata-Modern-Classic", "Modern Classic"),
+            ("Bibata-Modern-Ice", "Modern Ice"),
+            ("Bibata-Modern-Amber", "Modern Amber"),
+        ],
+        removable: true,
+    },
+    IconTheme {
+        name: "Capitaine",
+        kind: Kind::Cursors,
+        package: "capitaine-cursors",
+        aur: false,
+        variants: &[
+            ("capitaine-cursors", "Dark"),
+            ("capitaine-cursors-light", "Light"),
+        ],
+        removable: true,
+    },
+    IconTheme {
+        name: "Breeze",
+        kind: Kind::Cursors,
+        package: "breeze",
+        aur: false,
+        variants: &[("breeze_cursors", "Breeze")],
+        removable: false,
+    },
+];
+
+impl IconTheme {
+    /// Whether `theme` is one of this package's variants.
+    pub fn provides(&self, theme: &str) -> bool {
+        self.variants.iter().any(|(name, _)| *name == theme)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults_are_stock() {
+        for kind in [Kind::Icons, Kind::Cursors] {
+            let stock = THEMES
+                .iter()
+                .find(|t| t.kind == kind && t.provides(kind.default_theme()))
+                .unwrap();
+            assert!(!stock.removable);
+        }
+    }
+}
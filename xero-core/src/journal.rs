@@ -0,0 +1,126 @@
+//! Journal of the running sequence, for resuming after a crash.
+//!
+//! While the task runner works through a sequence, its title, its steps
+//! and how many of them finished are kept in
+//! `~/.local/state/xero-toolkit/running.toml`. The file is removed when the
+//! sequence ends, however it ends. Finding it at launch means the toolkit
+//! was killed or crashed mid-task, and the steps left can be run again.
+
+use crate::history;
+use crate::task_runner::Command;
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A sequence that started and hasn't ended.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Journal {
+    /// Task runner title.
+    pub title: String,
+    /// When the sequence started, in seconds since the Unix epoch.
+    pub started: i64,
+    /// Every step, hook steps included.
+    pub commands: Vec<Command>,
+    /// Number of steps that finished successfully.
+    pub completed: usize,
+}
+
+impl Journal {
+    /// Steps that didn't finish.
+    pub fn remaining(&self) -> &[Command] {
+        &self.commands[self.completed.min(self.commands.len())..]
+    }
+
+    /// The step that was running, if any.
+    pub fn interrupted_step(&self) -> Option<&Command> {
+        self.commands.get(self.completed)
+    }
+}
+
+fn journal_file() -> PathBuf {
+    history::state_dir().join("running.toml")
+}
+
+fn save(journal: &Journal) -> Result<()> {
+    let path = journal_file();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let text = toml::to_string(journal).context("Failed to encode the task journal")?;
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, text).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Record that `commands` started running as `title`.
+pub fn start(title: &str, commands: &[Command]) -> Result<()> {
+    save(&Journal {
+        title: title.to_string(),
+        started: history::now(),
+        commands: commands.to_vec(),
+        completed: 0,
+    })
+}
+
+/// Record that the first `completed` steps finished.
+pub fn advance(completed: usize) -> Result<()> {
+    let Some(mut journal) = load() else {
+        return Ok(());
+    };
+    journal.completed = completed;
+    save(&journal)
+}
+
+/// Remove the journal, once the sequence ended or won't be resumed.
+pub fn finish() {
+    let path = journal_file();
+    if let Err(e) = std::fs::remove_file(&path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// The sequence that was running when the toolkit last stopped, if it
+/// didn't end. An invalid journal is ignored.
+pub fn load() -> Option<Journal> {
+    let path = journal_file();
+    let text = std::fs::read_to_string(&path).ok()?;
+    toml::from_str(&text)
+        .map_err(|e| warn!("Ignoring invalid {}: {}", path.display(), e))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_steps_start_at_the_interrupted_one() {
+        let step = |program: &str| {
+            Command::builder()
+                .privileged()
+                .program(program)
+                .description(program)
+                .build()
+        };
+        let mut journal = Journal {
+            title: "KVM / QEMU Setup".to_string(),
+            started: 0,
+            commands: vec![step("pacman"), step("systemctl"), step("usermod")],
+            completed: 1,
+        };
+        let text = toml::to_string(&journal).unwrap();
+        let parsed: Journal = toml::from_str(&text).unwrap();
+        assert_eq!(parsed.remaining(), &journal.commands[1..]);
+        assert_eq!(parsed.interrupted_step().unwrap().program, "systemctl");
+
+        journal.completed = 3;
+        assert!(journal.remaining().is_empty());
+        assert!(journal.interrupted_step().is_none());
+    }
+}
@@ -0,0 +1,38 @@
+//! JSON string encoding for the CLI's events and webhook bodies.
+//!
+//! The few JSON documents the toolkit writes are built by hand, so this is
+//! all that's needed rather than a serializer.
+
+/// Quote and escape a string as a JSON string literal.
+pub fn string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!(string("plain"), "\"plain\"");
+        assert_eq!(
+            string("say \"hi\"\\\n\tdone\r"),
+            "\"say \\\"hi\\\"\\\\\\n\\tdone\\r\""
+        );
+        assert_eq!(string("\u{1b}[0m"), "\"\\u001b[0m\"");
+    }
+}
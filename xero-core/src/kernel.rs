@@ -0,0 +1,196 @@
+//! Installed kernels and the headers DKMS modules are built against.
+//!
+//! A kernel's package is the one owning its image in `/usr/lib/modules`, as
+//! pacman reports it. That works for any flavor, such as
+//! `linux-cachyos-bore` or `linux-hardened`, where guessing from the
+//! `uname -r` suffix doesn't. Its headers package is named after it.
+//!
+//! DKMS builds a module for every installed kernel that has its headers.
+//! Sequences installing a `-dkms` package get the headers of all of them,
+//! so the module keeps working after booting another kernel.
+
+use crate::estimate::Targets;
+use crate::package;
+use crate::task_runner::Command;
+use log::warn;
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Where kernel images and modules are installed, one directory per release.
+const MODULES_DIR: &str = "/usr/lib/modules";
+
+/// Headers a DKMS install needs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Headers {
+    /// Headers packages to install, the running kernel's first.
+    pub packages: Vec<String>,
+    /// Kernels whose headers package wasn't found, by package name, or by
+    /// release for a kernel no package owns.
+    pub missing: Vec<String>,
+}
+
+impl Headers {
+    /// Step installing [`Headers::packages`], if there are any.
+    pub fn step(&self) -> Option<Command> {
+        if self.packages.is_empty() {
+            return None;
+        }
+        let mut args = vec!["-S", "--noconfirm", "--needed"];
+        args.extend(self.packages.iter().map(String::as_str));
+        Some(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&args)
+                .description("Installing kernel headers...")
+                .build(),
+        )
+    }
+}
+
+/// Release of the running kernel, as `uname -r` prints it.
+pub fn running_release() -> Option<String> {
+    std::fs::read_to_string("/proc/sys/kernel/osrelease")
+        .map(|release| release.trim().to_string())
+        .ok()
+        .filter(|release| !release.is_empty())
+}
+
+fn image(release: &str) -> String {
+    format!("{}/{}/vmlinuz", MODULES_DIR, release)
+}
+
+/// Packages owning `paths`, asked in the C locale so the output parses the
+/// same everywhere. Paths no package owns are left out.
+fn owners(paths: &[String]) -> Vec<String> {
+    if paths.is_empty() {
+        return Vec::new();
+    }
+    // Exits non-zero when any path is unowned, but still lists the others
+    let output = match std::process::Command::new("pacman")
+        .arg("-Qqo")
+        .args(paths)
+        .env("LC_ALL", "C")
+        .output()
+    {
+        Ok(output) => output,
+        Err(e) => {
+            warn!("Failed to run pacman -Qo: {}", e);
+            return Vec::new();
+        }
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Package of the running kernel, e.g. `linux-zen`. `None` for kernels
+/// not installed through pacman.
+pub fn running_package() -> Option<String> {
+    let release = running_release()?;
+    owners(&[image(&release)]).into_iter().next()
+}
+
+/// Packages of all installed kernels.
+pub fn installed_packages() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(MODULES_DIR) else {
+        return Vec::new();
+    };
+    // Directories left behind by removed kernels have no image
+    let images: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .map(|release| image(&release))
+        .filter(|path| Path::new(path).exists())
+        .collect();
+    owners(&images)
+}
+
+/// Name of the headers package of the kernel package `kernel`.
+pub fn headers_name(kernel: &str) -> String {
+    format!("{}-headers", kernel)
+}
+
+/// Headers for the `running` kernel and the other `kernels`. `running` is
+/// the package name, or the release when no package owns it. Headers that
+/// are `installed` are skipped, and those that aren't `available` in the
+/// repos are missing.
+fn plan(
+    running: &str,
+    kernels: &[String],
+    installed: &HashSet<String>,
+    available: impl Fn(&str) -> bool,
+) -> Headers {
+    let mut headers = Headers::default();
+    let mut kernels: Vec<&str> = kernels.iter().map(String::as_str).collect();
+    kernels.retain(|k| *k != running);
+    kernels.insert(0, running);
+
+    for kernel in kernels {
+        let name = headers_name(kernel);
+        if installed.contains(&name) {
+            continue;
+        }
+        if installed.contains(kernel) && available(&name) {
+            headers.packages.push(name);
+        } else {
+            headers.missing.push(kernel.to_string());
+        }
+    }
+    headers
+}
+
+/// Headers missing for the running kernel and every other installed one.
+/// Blocks on pacman.
+pub fn headers_for_running_kernel() -> Headers {
+    let running = running_package()
+        .or_else(running_release)
+        .unwrap_or_else(|| "the running kernel".to_string());
+    plan(
+        &running,
+        &installed_packages(),
+        &package::installed_packages(),
+        package::is_package_in_repos,
+    )
+}
+
+/// Whether `package` is built with DKMS, like `xone-dkms-git`.
+fn is_dkms(package: &str) -> bool {
+    package.ends_with("-dkms") || package.contains("-dkms-")
+}
+
+/// Headers `commands` need, if they install a DKMS module. Blocks on pacman.
+pub fn check(commands: &[Command]) -> Headers {
+    if !Targets::of(commands).packages.iter().any(|p| is_dkms(p)) {
+        return Headers::default();
+    }
+    headers_for_running_kernel()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plans_headers_for_every_installed_kernel() {
+        let installed: HashSet<String> = ["linux", "linux-zen", "linux-lts", "linux-lts-headers"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        let kernels = ["linux", "linux-lts", "linux-zen"].map(String::from);
+        let headers = plan("linux-zen", &kernels, &installed, |p| p != "linux-headers");
+        assert_eq!(headers.packages, ["linux-zen-headers"]);
+        assert_eq!(headers.missing, ["linux"]);
+
+        // A self-built kernel has no package, so no headers either
+        let headers = plan("6.12.8-custom", &[], &installed, |_| true);
+        assert!(headers.packages.is_empty());
+        assert_eq!(headers.missing, ["6.12.8-custom"]);
+
+        assert!(is_dkms("xone-dkms-git") && is_dkms("v4l2loopback-dkms"));
+        assert!(!is_dkms("dkms"));
+    }
+}
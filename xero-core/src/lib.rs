@@ -0,0 +1,143 @@
+//! Core functionality and business logic of Xero Toolkit.
+//!
+//! Nothing here depends on GTK, so the GUI, its command line interface and
+//! other tools can share the same logic, and it can be tested on its own.
+//!
+//! This crate contains:
+//! - `actions`: Registry of install and repair actions
+//! - `ai`: GPU builds and disk space checks for local AI tools
+//! - `apps`: Apps offered both as native packages and on Flathub
+//! - `aur`: AUR helper detection and management
+//! - `autostart`: Starting the toolkit on login
+//! - `backup`: Encrypted restic or borgmatic backups and their schedule
+//! - `batch`: Several actions combined into one sequence
+//! - `changelog`: Changes between the installed toolkit and an update
+//! - `cmdline`: Kernel command line parameters
+//! - `compose`: Docker Compose stack templates
+//! - `config`: Paths, links and saved user preferences
+//! - `conflicts`: Package stacks that can't be installed side by side
+//! - `daemon`: Daemon management for xero-auth
+//! - `decky`: Decky Loader releases, install and removal
+//! - `desktop`: Desktop environment detection
+//! - `devtools`: Developer tool choices and git identity
+//! - `dotfiles`: Dotfiles kept in a bare git repository
+//! - `drives`: Disk and partition detection and fstab entries
+//! - `download`: File download functionality
+//! - `estimate`: Download and disk space estimates for install sequences
+//! - `firewall`: Firewall detection and port rules
+//! - `grub`: GRUB themes and `/etc/default/grub` edits
+//! - `hardware`: GPU detection
+//! - `history`: Record of sequences run by the task runner
+//! - `holds`: Packages and groups held back from upgrades
+//! - `hwaccel`: Hardware video acceleration checks
+//! - `i18n`: Translations through gettext
+//! - `icon_themes`: Icon and cursor themes and how they're applied
+//! - `journal`: Journal of the running sequence, for resuming after a crash
+//! - `json`: JSON string encoding for events and webhooks
+//! - `kernel`: Installed kernels and the headers DKMS modules need
+//! - `manifest`: Files the toolkit installs outside of pacman, with checksums
+//! - `microcode`: CPU microcode detection
+//! - `network`: Encrypted DNS, VPN connections and Tailscale status
+//! - `notifier`: ntfy, Gotify and webhook messages when long tasks finish
+//! - `nvidia`: NVIDIA kernel modules and the kernels they're built for
+//! - `orphans`: Orphaned packages and the dependencies removed with them
+//! - `package`: Package and flatpak checking utilities
+//! - `pacman_conf`: Structured edits of `/etc/pacman.conf`
+//! - `podman`: Rootless Podman and quadlet generation
+//! - `power`: Power daemon conflicts and battery detection
+//! - `prime`: Hybrid graphics mode switching and PRIME offload
+//! - `profile`: Setup profiles exported and replayed on a reinstall
+//! - `proton`: Proton-GE releases and installed builds for Steam
+//! - `quickemu`: Quickemu guests and the VMs created with quickget
+//! - `relevance`: Actions hidden or annotated for the hardware they need
+//! - `repos`: Third-party repositories and their removal
+//! - `report`: Prefilled issue reports for errors
+//! - `reset`: Config reset from `/etc/skel`, by scope
+//! - `rice`: Plasma global themes and saved rice profiles
+//! - `self_update`: Toolkit update channels and prebuilt releases
+//! - `services`: systemd service state
+//! - `sharing`: Samba shares and NFS exports
+//! - `shell`: Shell Setup frameworks, prompts and plugins
+//! - `snapshot`: Snapper or Timeshift snapshots before destructive actions
+//! - `staging`: Private files handed to privileged commands
+//! - `status`: Orphans, free space, maintenance runs and failed units
+//! - `system_check`: System dependency and distribution validation
+//! - `task_runner`: Commands, command sequences and headless execution
+//! - `terminal`: External terminal emulators for interactive commands
+//! - `uninstall`: What the toolkit installed, grouped by action, and its removal
+//! - `updates`: Pending package and toolkit updates
+//! - `verify`: Pinned checksums and signatures for third-party downloads
+//! - `vfio`: GPU passthrough readiness checks and configuration
+//! - `virt`: Hypervisor detection for systems running as a VM guest
+//! - `vm`: Windows 11 virtual machines for libvirt
+
+pub mod actions;
+pub mod ai;
+pub mod apps;
+pub mod aur;
+pub mod autostart;
+pub mod backup;
+pub mod batch;
+pub mod changelog;
+pub mod cmdline;
+pub mod compose;
+pub mod config;
+pub mod conflicts;
+pub mod daemon;
+pub mod decky;
+pub mod desktop;
+pub mod devtools;
+pub mod dotfiles;
+pub mod download;
+pub mod drives;
+pub mod estimate;
+pub mod firewall;
+pub mod grub;
+pub mod hardware;
+pub mod history;
+pub mod holds;
+pub mod hwaccel;
+pub mod i18n;
+pub mod icon_themes;
+pub mod journal;
+pub mod json;
+pub mod kernel;
+pub mod manifest;
+pub mod microcode;
+pub mod network;
+pub mod notifier;
+pub mod nvidia;
+pub mod orphans;
+pub mod package;
+pub mod pacman_conf;
+pub mod podman;
+pub mod power;
+pub mod prime;
+pub mod profile;
+pub mod proton;
+pub mod quickemu;
+pub mod relevance;
+pub mod repos;
+pub mod report;
+pub mod reset;
+pub mod rice;
+pub mod self_update;
+pub mod services;
+pub mod sharing;
+pub mod shell;
+pub mod snapshot;
+pub mod staging;
+pub mod status;
+pub mod system_check;
+pub mod task_runner;
+pub mod terminal;
+pub mod uninstall;
+pub mod updates;
+pub mod verify;
+pub mod vfio;
+pub mod virt;
+pub mod vm;
+
+// Re-export commonly used items
+pub use aur::get as aur_helper;
+pub use package::{is_flatpak_installed, is_package_installed, is_package_in_repos};
@@ -0,0 +1,431 @@
+//! Files the toolkit installs outside of pacman.
+//!
+//! Scripts in `/usr/local`, tools unpacked into `/opt`, binaries built from
+//! source and polkit policies aren't owned by any package, so nothing else
+//! knows they came from the toolkit. When a sequence succeeds, the paths
+//! its steps wrote to are checked, and the ones pacman doesn't own are
+//! recorded in `~/.local/state/xero-toolkit/manifest.toml` with a SHA256
+//! digest. The uninstall manager lists them by action, and the dashboard
+//! points out the ones changed since.
+
+use crate::history::state_dir;
+use crate::task_runner::{Command, CommandType};
+use anyhow::{Context, Result};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+/// Directories whose contents are tracked, with how many path components
+/// below them make up one artifact. A tool under `/opt` is its whole
+/// directory.
+const TRACKED: &[(&str, usize)] = &[
+    ("/opt/", 1),
+    ("/usr/local/", 2),
+    ("/usr/bin/", 1),
+    ("/usr/share/polkit-1/actions/", 1),
+    ("/etc/polkit-1/rules.d/", 1),
+];
+
+/// Where the toolkit itself lives; never recorded.
+const OWN_DIR: &str = "/opt/xero-toolkit";
+
+/// A file or directory an action created.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Artifact {
+    pub path: String,
+    /// Task runner title of the action that created it.
+    pub action: String,
+    /// When it was recorded, in seconds since the Unix epoch.
+    pub timestamp: i64,
+    /// Digest of the file, or of all files in the directory. Missing when
+    /// the contents couldn't be read.
+    pub sha256: Option<String>,
+}
+
+/// How an artifact compares to when it was recorded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum State {
+    Intact,
+    Modified,
+    Missing,
+}
+
+impl Artifact {
+    /// Check the artifact against its recorded digest. Blocks on sha256sum.
+    pub fn state(&self) -> State {
+        let path = Path::new(&self.path);
+        if !path.exists() {
+            return State::Missing;
+        }
+        match (&self.sha256, digest(path)) {
+            (Some(recorded), Some(current)) if *recorded != current => State::Modified,
+            _ => State::Intact,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ManifestFile {
+    #[serde(default)]
+    artifacts: Vec<Artifact>,
+}
+
+fn manifest_file() -> PathBuf {
+    state_dir().join("manifest.toml")
+}
+
+/// All recorded artifacts, oldest first. A missing or invalid manifest is
+/// treated as empty. The manifest is the user's to edit, so entries outside
+/// the tracked directories are dropped rather than offered for removal.
+pub fn load() -> Vec<Artifact> {
+    let path = manifest_file();
+    let Ok(text) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    match toml::from_str::<ManifestFile>(&text) {
+        Ok(file) => file
+            .artifacts
+            .into_iter()
+            .filter(|a| {
+                let tracked = is_tracked(&a.path);
+                if !tracked {
+                    warn!("Ignoring untracked path {} in the manifest", a.path);
+                }
+                tracked
+            })
+            .collect(),
+        Err(e) => {
+            warn!("Ignoring invalid {}: {}", path.display(), e);
+            Vec::new()
+        }
+    }
+}
+
+fn save(artifacts: Vec<Artifact>) -> Result<()> {
+    let path = manifest_file();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+    }
+    let text = toml::to_string(&ManifestFile { artifacts }).context("Failed to encode manifest")?;
+    let tmp = path.with_extension("toml.tmp");
+    std::fs::write(&tmp, text).with_context(|| format!("Failed to write {}", tmp.display()))?;
+    std::fs::rename(&tmp, &path)
+        .with_context(|| format!("Failed to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Record what a successful sequence created outside of pacman. Artifacts
+/// recorded before are replaced, and ones that are gone are dropped.
+/// Blocks on pacman and sha256sum.
+pub fn record(action: &str, timestamp: i64, commands: &[Command]) -> Result<()> {
+    let created: Vec<Artifact> = paths_of(commands)
+        .into_iter()
+        .filter(|path| Path::new(path).exists() && !owned_by_package(path))
+        .map(|path| Artifact {
+            sha256: digest(Path::new(&path)),
+            path,
+            action: action.to_string(),
+            timestamp,
+        })
+        .collect();
+
+    let mut artifacts = load();
+    let before = artifacts.len();
+    artifacts.retain(|a| Path::new(&a.path).exists());
+    if created.is_empty() && artifacts.len() == before {
+        return Ok(());
+    }
+    artifacts.retain(|a| !created.iter().any(|c| c.path == a.path));
+    artifacts.extend(created);
+    save(artifacts)
+}
+
+fn owned_by_package(path: &str) -> bool {
+    std::process::Command::new("pacman")
+        .args(["-Qqo", path])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// SHA256 of a file, or of the sorted checksums of all files under a
+/// directory.
+fn digest(path: &Path) -> Option<String> {
+    let output = if path.is_dir() {
+        std::process::Command::new("sh")
+            .args([
+                "-c",
+                "find \"$1\" -type f -print0 | LC_ALL=C sort -z | xargs -0r sha256sum | sha256sum",
+                "sh",
+            ])
+            .arg(path)
+            .output()
+    } else {
+        std::process::Command::new("sha256sum")
+            .arg("--")
+            .arg(path)
+            .output()
+    }
+    .ok()
+    .filter(|o| o.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(str::to_string)
+}
+
+/// The tracked artifact `word` points into, if any.
+fn tracked_path(word: &str) -> Option<String> {
+    let word = word.trim_matches(|c: char| "\"'`;,()<>|&".contains(c));
+    if word.contains(['$', '*', '?', '{']) || word.split('/').any(|part| part == "..") {
+        return None;
+    }
+    let (prefix, depth) = TRACKED.iter().find(|(p, _)| word.starts_with(p))?;
+    let rest: Vec<&str> = word[prefix.len()..]
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .take(*depth)
+        .collect();
+    if rest.len() < *depth {
+        return None;
+    }
+    let path = format!("{}{}", prefix, rest.join("/"));
+    (path != OWN_DIR).then_some(path)
+}
+
+/// Whether `path` is a whole artifact in one of the tracked directories.
+pub fn is_tracked(path: &str) -> bool {
+    tracked_path(path).as_deref() == Some(path)
+}
+
+/// The `sh -c` script a command runs, if it's one.
+fn script_of(command: &Command) -> Option<&str> {
+    match (command.program.as_str(), command.args.as_slice()) {
+        ("sh" | "bash", [flag, script, ..]) if flag == "-c" => Some(script),
+        _ => None,
+    }
+}
+
+/// Statements of a script, as text. Here-document bodies are data, not
+/// statements, so they're left out.
+fn script_statements(script: &str) -> Vec<&str> {
+    let mut statements = Vec::new();
+    let mut heredoc: Option<&str> = None;
+    for line in script.lines() {
+        if let Some(end) = heredoc {
+            if line.trim() == end {
+                heredoc = None;
+            }
+            continue;
+        }
+        heredoc = heredoc_delimiter(line);
+        statements.extend(
+            line.split(';')
+                .flat_map(|line| line.split("&&"))
+                .flat_map(|line| line.split("||")),
+        );
+    }
+    statements
+}
+
+/// Delimiter of the here-document a line starts, e.g. `EOF` for `<< 'EOF'`.
+fn heredoc_delimiter(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once("<<")?;
+    if rest.starts_with('<') {
+        return None;
+    }
+    let word = rest.trim_start_matches('-').split_whitespace().next()?;
+    Some(word.trim_matches(['\'', '"']))
+}
+
+fn words_of(statement: &str) -> Vec<String> {
+    statement
+        .split(|c: char| c.is_whitespace() || c == '<' || c == '>')
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Shell statements a command runs: the command itself, or each statement
+/// of a `sh -c` script, split into words.
+pub(crate) fn statements(command: &Command) -> Vec<Vec<String>> {
+    match script_of(command) {
+        Some(script) => script_statements(script)
+            .into_iter()
+            .map(words_of)
+            .collect(),
+        None => {
+            let mut words = vec![command.program.clone()];
+            words.extend(command.args.iter().cloned());
+            vec![words]
+        }
+    }
+}
+
+/// Files a statement redirects its output to.
+fn redirect_targets(statement: &str) -> impl Iterator<Item = &str> {
+    statement
+        .split('>')
+        .skip(1)
+        .filter(|rest| !rest.starts_with('&'))
+        .filter_map(|rest| rest.split_whitespace().next())
+}
+
+/// Paths a statement writes to: the destination of `install`, `cp`, `mv`
+/// and `ln`, directories `mkdir` makes or archives are unpacked into, and
+/// files `tee` and downloads write.
+fn destinations(words: &[String]) -> Vec<String> {
+    let mut words = words
+        .iter()
+        .map(String::as_str)
+        .skip_while(|w| *w == "sudo");
+    let Some(program) = words.next() else {
+        return Vec::new();
+    };
+    let args: Vec<&str> = words.collect();
+    let value_of = |flag: &str| {
+        args.windows(2)
+            .find(|pair| pair[0] == flag)
+            .map(|pair| pair[1].to_string())
+    };
+    let operands: Vec<&str> = args
+        .iter()
+        .copied()
+        .filter(|a| !a.starts_with('-'))
+        .collect();
+    // A destination directory gets the sources under their own names
+    let into = |dir: &str, sources: &[&str]| -> Vec<String> {
+        sources
+            .iter()
+            .filter_map(|source| source.trim_end_matches('/').rsplit('/').next())
+            .map(|name| format!("{}/{}", dir.trim_end_matches('/'), name))
+            .collect()
+    };
+    let makes_dirs = args
+        .iter()
+        .any(|a| a.starts_with('-') && !a.starts_with("--") && a.contains('d'));
+
+    match program {
+        "mkdir" | "tee" => operands.iter().map(|o| o.to_string()).collect(),
+        "install" if makes_dirs => operands.iter().map(|o| o.to_string()).collect(),
+        "install" | "cp" | "mv" | "ln" => match value_of("-t") {
+            Some(dir) => {
+                let sources: Vec<&str> = operands.into_iter().filter(|o| *o != dir).collect();
+                into(&dir, &sources)
+            }
+            None => match operands.split_last() {
+                Some((dest, sources)) if dest.ends_with('/') => into(dest, sources),
+                Some((dest, _)) => vec![dest.to_string()],
+                None => Vec::new(),
+            },
+        },
+        "curl" => value_of("-o").into_iter().collect(),
+        "wget" => value_of("-O").into_iter().collect(),
+        "tar" => value_of("-C").into_iter().collect(),
+        "unzip" => value_of("-d").into_iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Tracked paths the steps of a sequence write to, in order, without
+/// repeats. Paths a step only reads, runs or removes don't count.
+pub fn paths_of(commands: &[Command]) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    let mut add = |word: &str| {
+        if let Some(path) = tracked_path(word) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    };
+
+    for command in commands {
+        if let CommandType::Download(spec) = &command.command_type {
+            add(&spec.dest);
+            continue;
+        }
+        match script_of(command) {
+            Some(script) => {
+                for statement in script_statements(script) {
+                    redirect_targets(statement).for_each(&mut add);
+                    destinations(&words_of(statement))
+                        .iter()
+                        .for_each(|path| add(path));
+                }
+            }
+            None => {
+                for words in statements(command) {
+                    destinations(&words).iter().for_each(|path| add(path));
+                }
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_created_paths() {
+        let commands = [
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "install -Dm755 tool /usr/local/bin/upd && \
+                     cat > /usr/share/polkit-1/actions/org.tool.policy << 'EOF'\n\
+                     <annotate>/opt/tool/bin/tool</annotate>\nEOF\n\
+                     /opt/other/setup --prefix /usr/local/lib/other; \
+                     ln -sf /opt/other/bin/other /usr/bin/",
+                ])
+                .description("Step")
+                .build(),
+            Command::builder()
+                .privileged()
+                .program("rm")
+                .args(&["-rf", "/opt/old"])
+                .description("Step")
+                .build(),
+            Command::builder()
+                .privileged()
+                .program("cp")
+                .args(&[
+                    "-r",
+                    "/opt/xero-toolkit/sources/x",
+                    "/opt/$NAME",
+                    "/usr/local/",
+                ])
+                .description("Step")
+                .build(),
+            Command::builder()
+                .download("https://example.com/x", "/usr/bin/x")
+                .description("Step")
+                .build(),
+        ];
+        assert_eq!(
+            paths_of(&commands),
+            [
+                "/usr/local/bin/upd",
+                "/usr/share/polkit-1/actions/org.tool.policy",
+                "/usr/bin/other",
+                "/usr/bin/x",
+            ]
+        );
+    }
+
+    #[test]
+    fn tracks_only_whole_artifacts() {
+        assert!(is_tracked("/opt/tool"));
+        assert!(is_tracked("/usr/local/bin/upd"));
+        assert!(!is_tracked("/opt/tool/bin/tool"));
+        assert!(!is_tracked(OWN_DIR));
+        assert!(!is_tracked("/home/user"));
+        assert!(!is_tracked("/usr/bin/../lib"));
+    }
+}
@@ -0,0 +1,233 @@
+//! CPU microcode detection.
+//!
+//! Checks that the microcode package matching the CPU vendor is installed
+//! and that the boot configuration actually loads it. Missing microcode is
+//! common after manual installs and otherwise invisible to the user.
+
+use log::debug;
+use std::path::Path;
+
+pub const MKINITCPIO_CONF: &str = "/etc/mkinitcpio.conf";
+
+/// CPU vendors that ship microcode updates through Arch packages.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CpuVendor {
+    Intel,
+    Amd,
+}
+
+impl CpuVendor {
+    /// Microcode package name for this vendor.
+    pub fn package(&self) -> &'static str {
+        match self {
+            CpuVendor::Intel => "intel-ucode",
+            CpuVendor::Amd => "amd-ucode",
+        }
+    }
+
+    /// Human readable vendor name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CpuVendor::Intel => "Intel",
+            CpuVendor::Amd => "AMD",
+        }
+    }
+}
+
+/// Result of a microcode check.
+#[derive(Clone, Debug)]
+pub struct MicrocodeStatus {
+    pub vendor: Option<CpuVendor>,
+    pub installed: bool,
+    pub loaded: bool,
+}
+
+impl MicrocodeStatus {
+    /// Whether a fix sequence should be offered.
+    pub fn needs_fix(&self) -> bool {
+        self.vendor.is_some() && !(self.installed && self.loaded)
+    }
+}
+
+/// Parse the CPU vendor from `/proc/cpuinfo` contents.
+pub fn parse_vendor(cpuinfo: &str) -> Option<CpuVendor> {
+    cpuinfo
+        .lines()
+        .find(|line| line.starts_with("vendor_id"))
+        .and_then(|line| line.split(':').nth(1))
+        .and_then(|vendor| match vendor.trim() {
+            "GenuineIntel" => Some(CpuVendor::Intel),
+            "AuthenticAMD" => Some(CpuVendor::Amd),
+            _ => None,
+        })
+}
+
+/// Detect the CPU vendor of the running system.
+pub fn detect_vendor() -> Option<CpuVendor> {
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    parse_vendor(&cpuinfo)
+}
+
+/// Check whether the `microcode` hook is present in a mkinitcpio config.
+/// Since mkinitcpio v38 this embeds microcode into the initramfs directly.
+fn has_microcode_hook(mkinitcpio_conf: &str) -> bool {
+    mkinitcpio_conf
+        .lines()
+        .map(str::trim)
+        .filter(|line| line.starts_with("HOOKS="))
+        .any(|line| {
+            line.trim_start_matches("HOOKS=")
+                .trim_matches(|c| c == '(' || c == ')')
+                .split_whitespace()
+                .any(|hook| hook == "microcode")
+        })
+}
+
+/// Add the `microcode` hook to the active `HOOKS=` line of a mkinitcpio
+/// config, after `autodetect` as in the stock config. `None` when it's
+/// already there or there's no `HOOKS=` line.
+pub fn add_microcode_hook(mkinitcpio_conf: &str) -> Option<String> {
+    if has_microcode_hook(mkinitcpio_conf) {
+        return None;
+    }
+    let mut added = false;
+    let lines: Vec<String> = mkinitcpio_conf
+        .lines()
+        .map(|line| {
+            let Some(list) = line.trim().strip_prefix("HOOKS=") else {
+                return line.to_string();
+            };
+            if added {
+                return line.to_string();
+            }
+            added = true;
+            let mut hooks: Vec<&str> = list
+                .trim_matches(|c| c == '(' || c == ')')
+                .split_whitespace()
+                .collect();
+            let at = ["autodetect", "systemd", "udev", "base"]
+                .iter()
+                .find_map(|after| hooks.iter().position(|h| h == after))
+                .map_or(0, |i| i + 1);
+            hooks.insert(at, "microcode");
+            format!("HOOKS=({})", hooks.join(" "))
+        })
+        .collect();
+    added.then(|| lines.join("\n") + "\n")
+}
+
+/// Root script backing up `/etc/mkinitcpio.conf` and replacing it with `$1`.
+pub fn mkinitcpio_write_script() -> String {
+    format!(
+        "set -e\n\
+         cp -f {conf} {conf}.xero-toolkit.bak\n\
+         printf '%s' \"$1\" > {conf}\n",
+        conf = MKINITCPIO_CONF
+    )
+}
+
+/// Check whether the bootloader configuration references the microcode image.
+fn bootloader_loads(image: &str) -> bool {
+    let grub_cfg = std::fs::read_to_string("/boot/grub/grub.cfg").unwrap_or_default();
+    if grub_cfg.contains(image) {
+        debug!("Microcode image {} referenced in grub.cfg", image);
+        return true;
+    }
+
+    // systemd-boot entries may live on /boot or /efi
+    for entries in ["/boot/loader/entries", "/efi/loader/entries"] {
+        let Ok(dir) = std::fs::read_dir(entries) else {
+            continue;
+        };
+        for entry in dir.flatten() {
+            let content = std::fs::read_to_string(entry.path()).unwrap_or_default();
+            if content.contains(image) {
+                debug!("Microcode image {} referenced in {:?}", image, entry.path());
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Check whether microcode is loaded at boot, either through the bootloader
+/// or embedded in the initramfs.
+fn is_loaded(vendor: CpuVendor) -> bool {
+    let image = format!("{}.img", vendor.package());
+    if bootloader_loads(&image) {
+        return true;
+    }
+
+    let mkinitcpio = std::fs::read_to_string(MKINITCPIO_CONF).unwrap_or_default();
+    has_microcode_hook(&mkinitcpio)
+}
+
+/// Check the microcode state of the running system. Blocks on pacman, so
+/// call off the main thread.
+pub fn check() -> MicrocodeStatus {
+    let vendor = detect_vendor();
+    let (installed, loaded) = match vendor {
+        Some(v) => (super::is_package_installed(v.package()), is_loaded(v)),
+        None => (false, false),
+    };
+
+    debug!(
+        "Microcode check: vendor={:?} installed={} loaded={}",
+        vendor, installed, loaded
+    );
+
+    MicrocodeStatus {
+        vendor,
+        installed,
+        loaded,
+    }
+}
+
+/// Whether GRUB is the active bootloader (used to pick the regeneration step).
+pub fn uses_grub() -> bool {
+    Path::new("/boot/grub/grub.cfg").exists()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vendor() {
+        assert_eq!(
+            parse_vendor("processor\t: 0\nvendor_id\t: GenuineIntel\n"),
+            Some(CpuVendor::Intel)
+        );
+        assert_eq!(
+            parse_vendor("vendor_id\t: AuthenticAMD\n"),
+            Some(CpuVendor::Amd)
+        );
+        assert_eq!(parse_vendor("vendor_id\t: ARM\n"), None);
+    }
+
+    #[test]
+    fn test_has_microcode_hook() {
+        assert!(has_microcode_hook(
+            "HOOKS=(base udev autodetect microcode modconf block filesystems fsck)"
+        ));
+        assert!(!has_microcode_hook(
+            "#HOOKS=(base microcode)\nHOOKS=(base udev)"
+        ));
+    }
+
+    #[test]
+    fn test_add_microcode_hook() {
+        assert_eq!(
+            add_microcode_hook("#HOOKS=(base)\nHOOKS=(base udev autodetect modconf block)\n")
+                .as_deref(),
+            Some("#HOOKS=(base)\nHOOKS=(base udev autodetect microcode modconf block)\n")
+        );
+        assert_eq!(
+            add_microcode_hook("HOOKS=(base systemd keyboard)").as_deref(),
+            Some("HOOKS=(base systemd microcode keyboard)\n")
+        );
+        assert_eq!(add_microcode_hook("HOOKS=(base microcode)"), None);
+        assert_eq!(add_microcode_hook("MODULES=()"), None);
+    }
+}
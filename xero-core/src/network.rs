@@ -0,0 +1,334 @@
+//! Encrypted DNS, VPN connections and Tailscale for the Network Tools page.
+//!
+//! DNS goes through systemd-resolved with a drop-in the toolkit owns, so
+//! removing it restores the servers handed out by the network. VPN configs
+//! are imported into NetworkManager, which then connects them like any
+//! other connection.
+
+use std::path::Path;
+use std::process::Command;
+
+/// resolved drop-in written for a DNS provider.
+pub const DNS_DROPIN: &str = "/etc/systemd/resolved.conf.d/xero-dns.conf";
+
+pub const RESOLVED_SERVICE: &str = "systemd-resolved.service";
+pub const TAILSCALE_SERVICE: &str = "tailscaled.service";
+
+/// A DNS provider offering DNS over TLS.
+#[derive(Debug)]
+pub struct DnsProvider {
+    pub id: &'static str,
+    pub name: &'static str,
+    /// Servers as `address#tls-name`, the form resolved expects.
+    pub servers: &'static [&'static str],
+}
+
+impl PartialEq for DnsProvider {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for DnsProvider {}
+
+pub const DNS_PROVIDERS: &[DnsProvider] = &[
+    DnsProvider {
+        id: "cloudflare",
+        name: "Cloudflare",
+        servers: &[
+            "1.1.1.1#cloudflare-dns.com",
+            "1.0.0.1#cloudflare-dns.com",
+            "2606:4700:4700::1111#cloudflare-dns.com",
+            "2606:4700:4700::1001#cloudflare-dns.com",
+        ],
+    },
+    DnsProvider {
+        id: "quad9",
+        name: "Quad9",
+        servers: &[
+            "9.9.9.9#dns.quad9.net",
+            "149.112.112.112#dns.quad9.net",
+            "2620:fe::fe#dns.quad9.net",
+            "2620:fe::9#dns.quad9.net",
+        ],
+    },
+    DnsProvider {
+        id: "google",
+        name: "Google",
+        servers: &[
+            "8.8.8.8#dns.google",
+            "8.8.4.4#dns.google",
+            "2001:4860:4860::8888#dns.google",
+            "2001:4860:4860::8844#dns.google",
+        ],
+    },
+    DnsProvider {
+        id: "adguard",
+        name: "AdGuard DNS (blocks ads)",
+        servers: &[
+            "94.140.14.14#dns.adguard-dns.com",
+            "94.140.15.15#dns.adguard-dns.com",
+            "2a10:50c0::ad1:ff#dns.adguard-dns.com",
+            "2a10:50c0::ad2:ff#dns.adguard-dns.com",
+        ],
+    },
+    DnsProvider {
+        id: "mullvad",
+        name: "Mullvad DNS",
+        servers: &[
+            "194.242.2.2#dns.mullvad.net",
+            "2a07:e340::2#dns.mullvad.net",
+        ],
+    },
+];
+
+/// The provider set in the drop-in, and whether encryption is required.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DnsSetting {
+    pub provider: &'static DnsProvider,
+    pub strict: bool,
+}
+
+impl DnsSetting {
+    /// The current setting, `None` when the network's servers are used.
+    pub fn read() -> Option<Self> {
+        Self::parse(&std::fs::read_to_string(DNS_DROPIN).ok()?)
+    }
+
+    fn parse(dropin: &str) -> Option<Self> {
+        let mut servers = None;
+        let mut strict = false;
+        for line in dropin.lines() {
+            match line.split_once('=') {
+                Some(("DNS", value)) => servers = Some(value.trim()),
+                Some(("DNSOverTLS", value)) => strict = value.trim() == "yes",
+                _ => {}
+            }
+        }
+        let servers = servers?;
+        DNS_PROVIDERS
+            .iter()
+            .find(|p| p.servers.join(" ") == servers)
+            .map(|provider| Self { provider, strict })
+    }
+
+    /// The drop-in for this setting. Queries for every domain go to the
+    /// provider, not only those without a per-connection server.
+    pub fn dropin(&self) -> String {
+        format!(
+            "# Written by Xero Toolkit from the Network Tools page.\n\
+             [Resolve]\n\
+             DNS={}\n\
+             Domains=~.\n\
+             DNSOverTLS={}\n",
+            self.provider.servers.join(" "),
+            if self.strict { "yes" } else { "opportunistic" }
+        )
+    }
+}
+
+/// Script pointing `/etc/resolv.conf` at resolved's stub, so every program
+/// uses it. A file that was there before is kept next to it.
+pub const RESOLV_CONF_SCRIPT: &str = r#"stub=/run/systemd/resolve/stub-resolv.conf
+if [ "$(readlink /etc/resolv.conf)" != "$stub" ]; then
+    if [ -e /etc/resolv.conf ]; then cp -P /etc/resolv.conf /etc/resolv.conf.xero-backup; fi
+    ln -sf "$stub" /etc/resolv.conf
+fi
+"#;
+
+/// Kind of a VPN connection in NetworkManager.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VpnKind {
+    WireGuard,
+    /// A plugin VPN, such as OpenVPN.
+    Plugin,
+}
+
+/// A VPN connection known to NetworkManager.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VpnConnection {
+    pub name: String,
+    pub uuid: String,
+    pub kind: VpnKind,
+    pub active: bool,
+}
+
+/// Split a line of `nmcli --terse` output, which escapes `:` and `\` in
+/// values with a backslash.
+fn split_terse(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    fields.last_mut().unwrap().push(next);
+                }
+            }
+            ':' => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+fn parse_connections(output: &str) -> Vec<VpnConnection> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let [name, uuid, kind, device] = <[String; 4]>::try_from(split_terse(line)).ok()?;
+            let kind = match kind.as_str() {
+                "wireguard" => VpnKind::WireGuard,
+                "vpn" => VpnKind::Plugin,
+                _ => return None,
+            };
+            Some(VpnConnection {
+                name,
+                uuid,
+                kind,
+                active: !device.is_empty(),
+            })
+        })
+        .collect()
+}
+
+/// VPN connections in NetworkManager. Blocks on nmcli.
+pub fn vpn_connections() -> Vec<VpnConnection> {
+    Command::new("nmcli")
+        .args([
+            "--terse",
+            "--fields",
+            "NAME,UUID,TYPE,DEVICE",
+            "connection",
+            "show",
+        ])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| parse_connections(&String::from_utf8_lossy(&o.stdout)))
+        .unwrap_or_default()
+}
+
+/// Interface name for a WireGuard config imported from `path`. NetworkManager
+/// names the interface after the file, which has to be a valid one.
+pub fn wireguard_name(path: &Path) -> String {
+    let name: String = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || "_=+.-".contains(*c))
+        .take(15)
+        .collect();
+    if name.is_empty() {
+        "wg0".to_string()
+    } else {
+        name
+    }
+}
+
+/// Script importing the WireGuard config `$1` as connection `$2`. With
+/// `qr` as `$3`, `$1` is an image of a QR code holding the config. The
+/// private key is only ever copied to a private runtime folder.
+pub const WIREGUARD_IMPORT_SCRIPT: &str = r#"set -e
+dir="${XDG_RUNTIME_DIR:-/tmp}/xero-toolkit-wireguard"
+mkdir -p -m 700 "$dir"
+trap 'rm -rf "$dir"' EXIT
+conf="$dir/$2.conf"
+if [ "$3" = qr ]; then
+    zbarimg --quiet --raw "$1" > "$conf"
+else
+    cp "$1" "$conf"
+fi
+if ! grep -q '^\[Interface\]' "$conf"; then
+    echo "This doesn't hold a WireGuard config." >&2
+    exit 1
+fi
+nmcli connection import type wireguard file "$conf"
+"#;
+
+/// Tailscale's connection, read from its CLI.
+#[derive(Clone, Debug, Default)]
+pub struct TailscaleStatus {
+    /// The tailnet address when connected.
+    pub ip: Option<String>,
+    /// Why it isn't connected, e.g. "Logged out."
+    pub message: Option<String>,
+}
+
+impl TailscaleStatus {
+    /// Blocks on the tailscale CLI. Only meaningful with tailscaled running.
+    pub fn read() -> Self {
+        let run = |args: &[&str]| {
+            Command::new("tailscale").args(args).output().ok().map(|o| {
+                (
+                    o.status.success(),
+                    String::from_utf8_lossy(&o.stdout).trim().to_string(),
+                )
+            })
+        };
+        match run(&["ip", "-4"]) {
+            Some((true, ip)) if !ip.is_empty() => Self {
+                ip: ip.lines().next().map(str::to_string),
+                message: None,
+            },
+            _ => Self {
+                ip: None,
+                message: run(&["status", "--peers=false"])
+                    .and_then(|(_, out)| out.lines().next().map(str::to_string))
+                    .filter(|line| !line.is_empty()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dns_dropin_round_trip() {
+        for provider in DNS_PROVIDERS {
+            for strict in [false, true] {
+                let setting = DnsSetting { provider, strict };
+                assert_eq!(DnsSetting::parse(&setting.dropin()), Some(setting));
+            }
+        }
+        assert_eq!(DnsSetting::parse("[Resolve]\nDNS=192.168.1.1\n"), None);
+    }
+
+    #[test]
+    fn test_parse_connections() {
+        let output = "Home Wi\\:Fi:1111:802-11-wireless:wlan0\n\
+                      wg-home:2222:wireguard:wg-home\n\
+                      Work VPN:3333:vpn:\n";
+        assert_eq!(
+            parse_connections(output),
+            vec![
+                VpnConnection {
+                    name: "wg-home".to_string(),
+                    uuid: "2222".to_string(),
+                    kind: VpnKind::WireGuard,
+                    active: true,
+                },
+                VpnConnection {
+                    name: "Work VPN".to_string(),
+                    uuid: "3333".to_string(),
+                    kind: VpnKind::Plugin,
+                    active: false,
+                },
+            ]
+        );
+        assert_eq!(split_terse("a\\:b:c"), vec!["a:b", "c"]);
+    }
+
+    #[test]
+    fn test_wireguard_name() {
+        assert_eq!(wireguard_name(Path::new("/tmp/my vpn (1).conf")), "myvpn1");
+        assert_eq!(
+            wireguard_name(Path::new("/tmp/a-very-long-tunnel-name.conf")),
+            "a-very-long-tun"
+        );
+        assert_eq!(wireguard_name(Path::new("/tmp/@@.conf")), "wg0");
+    }
+}
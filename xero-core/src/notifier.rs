@@ -0,0 +1,188 @@
+//! Phone notifications for long task runner sequences.
+//!
+//! When a sequence runs longer than the configured number of minutes, its
+//! title and outcome are sent to an ntfy topic, a Gotify server or a
+//! generic webhook, so nobody has to watch a kernel build to know it's done.
+
+use crate::config::user::{NotifierService, NotifierSettings};
+use crate::history::Outcome;
+use crate::json::string;
+use anyhow::{Context, Result};
+use std::time::Duration;
+
+/// Sender name shown in ntfy and Gotify.
+const APP_NAME: &str = "Xero Toolkit";
+
+/// An HTTP POST to the notification service.
+#[derive(Debug, PartialEq, Eq)]
+struct Request {
+    url: String,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+/// Whether a sequence that ran for `elapsed` should be reported.
+pub fn should_notify(settings: &NotifierSettings, elapsed: Duration) -> bool {
+    settings.service != NotifierService::Off
+        && !settings.url.trim().is_empty()
+        && elapsed.as_secs() >= u64::from(settings.min_minutes) * 60
+}
+
+fn message(title: &str, outcome: Outcome) -> String {
+    match outcome {
+        Outcome::Success => format!("{} finished successfully.", title),
+        Outcome::Failed => format!("{} failed.", title),
+        Outcome::Cancelled => format!("{} was cancelled.", title),
+    }
+}
+
+fn outcome_name(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Success => "success",
+        Outcome::Failed => "failed",
+        Outcome::Cancelled => "cancelled",
+    }
+}
+
+fn request(settings: &NotifierSettings, title: &str, outcome: Outcome) -> Option<Request> {
+    let url = settings.url.trim();
+    let message = message(title, outcome);
+    let json = ("Content-Type", "application/json".to_string());
+    match settings.service {
+        NotifierService::Off => None,
+        // Headers must be ASCII, so the task title goes in the body
+        NotifierService::Ntfy => Some(Request {
+            url: url.to_string(),
+            headers: vec![
+                ("Title", APP_NAME.to_string()),
+                (
+                    "Tags",
+                    if outcome == Outcome::Success {
+                        "white_check_mark"
+                    } else {
+                        "x"
+                    }
+                    .to_string(),
+                ),
+            ],
+            body: message,
+        }),
+        NotifierService::Gotify => Some(Request {
+            url: format!("{}/message", url.trim_end_matches('/')),
+            headers: vec![json, ("X-Gotify-Key", settings.token.trim().to_string())],
+            body: format!(
+                "{{\"title\":{},\"message\":{},\"priority\":5}}",
+                string(APP_NAME),
+                string(&message)
+            ),
+        }),
+        NotifierService::Webhook => Some(Request {
+            url: url.to_string(),
+            headers: vec![json],
+            body: format!(
+                "{{\"title\":{},\"outcome\":{},\"message\":{}}}",
+                string(title),
+                string(outcome_name(outcome)),
+                string(&message)
+            ),
+        }),
+    }
+}
+
+/// Report that `title` ended with `outcome`. Blocks on the network.
+pub fn send(settings: &NotifierSettings, title: &str, outcome: Outcome) -> Result<()> {
+    let Some(request) = request(settings, title, outcome) else {
+        return Ok(());
+    };
+
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .context("Failed to start notification runtime")?
+        .block_on(async {
+            let client = reqwest::Client::builder()
+                .timeout(Duration::from_secs(15))
+                .build()
+                .context("Failed to build HTTP client")?;
+            let mut post = client.post(&request.url).body(request.body);
+            for (name, value) in request.headers {
+                post = post.header(name, value);
+            }
+            post.send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .with_context(|| format!("Failed to notify {}", request.url))?;
+            Ok(())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(service: NotifierService, url: &str) -> NotifierSettings {
+        NotifierSettings {
+            service,
+            url: url.to_string(),
+            token: "secret".to_string(),
+            min_minutes: 5,
+        }
+    }
+
+    #[test]
+    fn test_should_notify() {
+        let ntfy = settings(NotifierService::Ntfy, "https://ntfy.sh/xero");
+        assert!(should_notify(&ntfy, Duration::from_secs(300)));
+        assert!(!should_notify(&ntfy, Duration::from_secs(299)));
+        assert!(!should_notify(
+            &settings(NotifierService::Off, "https://ntfy.sh/xero"),
+            Duration::from_secs(3600)
+        ));
+        assert!(!should_notify(
+            &settings(NotifierService::Webhook, " "),
+            Duration::from_secs(3600)
+        ));
+    }
+
+    #[test]
+    fn test_requests() {
+        let ntfy = request(
+            &settings(NotifierService::Ntfy, "https://ntfy.sh/xero"),
+            "Steam AiO",
+            Outcome::Failed,
+        )
+        .unwrap();
+        assert_eq!(ntfy.url, "https://ntfy.sh/xero");
+        assert_eq!(ntfy.body, "Steam AiO failed.");
+        assert_eq!(ntfy.headers[1], ("Tags", "x".to_string()));
+
+        let gotify = request(
+            &settings(NotifierService::Gotify, "https://push.example.com/"),
+            "Kernel \"zen\"",
+            Outcome::Success,
+        )
+        .unwrap();
+        assert_eq!(gotify.url, "https://push.example.com/message");
+        assert_eq!(
+            gotify.body,
+            r#"{"title":"Xero Toolkit","message":"Kernel \"zen\" finished successfully.","priority":5}"#
+        );
+
+        let webhook = request(
+            &settings(NotifierService::Webhook, "https://example.com/hook"),
+            "Update",
+            Outcome::Success,
+        )
+        .unwrap();
+        assert!(webhook.body.contains(r#""outcome":"success""#));
+
+        assert_eq!(
+            request(
+                &settings(NotifierService::Off, ""),
+                "Update",
+                Outcome::Success
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! NVIDIA kernel modules and the kernels they're built for.
+//!
+//! The NVIDIA module comes either built with DKMS, for every kernel with
+//! headers, or prebuilt for one kernel: `nvidia` and `nvidia-open` for
+//! `linux`, `-lts` packages for `linux-lts`, and CachyOS's
+//! `<kernel>-nvidia` and `<kernel>-nvidia-open`. Booting a kernel without a
+//! module for it ends at a black screen, so kernel installs and removals
+//! are checked against the installed module first.
+
+use crate::package;
+use std::collections::HashSet;
+
+/// What a kernel install or removal needs for the NVIDIA module.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Advice {
+    /// Nothing: no NVIDIA module, or DKMS builds it for any kernel.
+    Nothing,
+    /// Install the prebuilt module package for the new kernel with it.
+    Add(String),
+    /// No prebuilt module exists for the new kernel. The prebuilt packages
+    /// have to be replaced with the DKMS module.
+    SwitchToDkms {
+        remove: Vec<String>,
+        install: &'static str,
+    },
+    /// Remove the prebuilt module package for the removed kernel with it,
+    /// since it depends on that kernel.
+    Remove(String),
+}
+
+/// Whether `package` is a prebuilt NVIDIA module package. CachyOS's are
+/// named after a kernel in `installed`, which tells them apart from
+/// packages like `linux-firmware-nvidia` that only share the pattern.
+fn is_prebuilt(package: &str, installed: &HashSet<String>) -> bool {
+    if matches!(
+        package,
+        "nvidia" | "nvidia-open" | "nvidia-lts" | "nvidia-open-lts"
+    ) {
+        return true;
+    }
+    let kernel = package
+        .strip_suffix("-nvidia-open")
+        .or_else(|| package.strip_suffix("-nvidia"));
+    kernel.is_some_and(|kernel| {
+        kernel.starts_with("linux-")
+            && !kernel.starts_with("linux-firmware")
+            && installed.contains(kernel)
+    })
+}
+
+/// Whether `package` is an NVIDIA module built with DKMS.
+fn is_dkms(package: &str) -> bool {
+    package.starts_with("nvidia") && package.ends_with("-dkms")
+}
+
+/// Prebuilt module package for `kernel`, open or proprietary.
+fn prebuilt_for(kernel: &str, open: bool) -> String {
+    let flavor = if open { "nvidia-open" } else { "nvidia" };
+    match kernel {
+        "linux" => flavor.to_string(),
+        "linux-lts" => format!("{}-lts", flavor),
+        _ => format!("{}-{}", kernel, flavor),
+    }
+}
+
+/// What installing (`installing`) or removing `kernel` needs, given the
+/// `installed` packages and whether a package is `available` in the repos.
+fn advise(
+    kernel: &str,
+    installing: bool,
+    installed: &HashSet<String>,
+    available: impl Fn(&str) -> bool,
+) -> Advice {
+    let mut prebuilt: Vec<String> = installed
+        .iter()
+        .filter(|p| is_prebuilt(p, installed))
+        .cloned()
+        .collect();
+    prebuilt.sort();
+    if prebuilt.is_empty() || installed.iter().any(|p| is_dkms(p)) {
+        return Advice::Nothing;
+    }
+    let open = prebuilt.iter().any(|p| p.contains("nvidia-open"));
+    let module = prebuilt_for(kernel, open);
+
+    if !installing {
+        return if installed.contains(&module) {
+            Advice::Remove(module)
+        } else {
+            Advice::Nothing
+        };
+    }
+    if installed.contains(&module) {
+        Advice::Nothing
+    } else if available(&module) {
+        Advice::Add(module)
+    } else {
+        Advice::SwitchToDkms {
+            remove: prebuilt,
+            install: if open {
+                "nvidia-open-dkms"
+            } else {
+                "nvidia-dkms"
+            },
+        }
+    }
+}
+
+/// What installing `kernel` needs for the NVIDIA module. Blocks on pacman.
+pub fn for_install(kernel: &str) -> Advice {
+    advise(
+        kernel,
+        true,
+        &package::installed_packages(),
+        package::is_package_in_repos,
+    )
+}
+
+/// What removing `kernel` needs for the NVIDIA module. Blocks on pacman.
+pub fn for_removal(kernel: &str) -> Advice {
+    advise(kernel, false, &package::installed_packages(), |_| false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(packages: &[&str]) -> HashSet<String> {
+        packages.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn prebuilt_modules_follow_the_kernel() {
+        let prebuilt = installed(&["linux", "nvidia-open", "nvidia-utils", "opencl-nvidia"]);
+        assert_eq!(
+            advise("linux-lts", true, &prebuilt, |_| true),
+            Advice::Add("nvidia-open-lts".to_string())
+        );
+        assert_eq!(
+            advise("linux-cachyos", true, &prebuilt, |_| false),
+            Advice::SwitchToDkms {
+                remove: vec!["nvidia-open".to_string()],
+                install: "nvidia-open-dkms",
+            }
+        );
+        assert_eq!(
+            advise("linux", false, &prebuilt, |_| false),
+            Advice::Remove("nvidia-open".to_string())
+        );
+
+        let dkms = installed(&["linux", "nvidia-dkms", "nvidia-utils"]);
+        assert_eq!(advise("linux-zen", true, &dkms, |_| false), Advice::Nothing);
+        assert_eq!(
+            advise("linux-zen", true, &installed(&["linux"]), |_| true),
+            Advice::Nothing
+        );
+
+        let firmware = installed(&["linux", "linux-firmware", "linux-firmware-nvidia"]);
+        assert_eq!(
+            advise("linux-cachyos", true, &firmware, |_| false),
+            Advice::Nothing
+        );
+        let cachyos = installed(&["linux-cachyos", "linux-cachyos-nvidia", "nvidia-utils"]);
+        assert_eq!(
+            advise("linux-cachyos-lts", true, &cachyos, |_| true),
+            Advice::Add("linux-cachyos-lts-nvidia".to_string())
+        );
+    }
+}
@@ -0,0 +1,195 @@
+//! Orphaned packages, including the ones only other orphans need.
+//!
+//! `pacman -Qdt` lists the packages nothing needs anymore, but removing
+//! them with `-Rns` frees up their own dependencies too. The whole set is
+//! worked out here from `pacman -Qi`, so it can be reviewed with sizes
+//! before anything is removed. Packages on the keep list in the settings
+//! are never offered, and neither is anything they depend on.
+
+use crate::config::user;
+use crate::package::parse_size;
+use std::collections::HashSet;
+
+/// An installed package, as far as orphan detection cares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub name: String,
+    /// Installed size in bytes.
+    pub size: u64,
+    /// Installed as a dependency of another package.
+    pub dependency: bool,
+    /// Packages requiring or optionally requiring this one.
+    pub required_by: Vec<String>,
+}
+
+/// A package that would be removed with the orphans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Orphan {
+    pub name: String,
+    /// Installed size in bytes.
+    pub size: u64,
+    /// Only an orphan once the other orphans are gone.
+    pub cascaded: bool,
+}
+
+/// Read all installed packages. Blocks on pacman.
+pub fn installed() -> Vec<Package> {
+    std::process::Command::new("pacman")
+        .arg("-Qi")
+        .env("LC_ALL", "C")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Orphans that would be removed now, leaving out the saved keep list.
+pub fn find() -> Vec<Orphan> {
+    let keep = user::load().keep_orphans.into_iter().collect();
+    resolve(&installed(), &keep)
+}
+
+/// Packages `-Rns` would remove along with the orphans, keeping the ones in
+/// `keep` and everything they depend on. Direct orphans come first.
+pub fn resolve(packages: &[Package], keep: &HashSet<String>) -> Vec<Orphan> {
+    let mut removed: HashSet<&str> = HashSet::new();
+    let mut orphans = Vec::new();
+
+    // Each round frees the packages only needed by the ones found before
+    loop {
+        let mut round: Vec<&Package> = packages
+            .iter()
+            .filter(|p| p.dependency && !keep.contains(&p.name))
+            .filter(|p| !removed.contains(p.name.as_str()))
+            .filter(|p| p.required_by.iter().all(|r| removed.contains(r.as_str())))
+            .collect();
+        if round.is_empty() {
+            break;
+        }
+        round.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let cascaded = !removed.is_empty();
+        for package in round {
+            removed.insert(&package.name);
+            orphans.push(Orphan {
+                name: package.name.clone(),
+                size: package.size,
+                cascaded,
+            });
+        }
+    }
+
+    orphans
+}
+
+/// Parse `pacman -Qi` output in the C locale.
+fn parse(output: &str) -> Vec<Package> {
+    let mut packages = Vec::new();
+    for block in output.split("\n\n") {
+        let mut name = None;
+        let mut package = Package {
+            name: String::new(),
+            size: 0,
+            dependency: false,
+            required_by: Vec::new(),
+        };
+
+        // Long lists continue on indented lines
+        let mut field = "";
+        for line in block.lines() {
+            let value = match line.split_once(" : ") {
+                Some((key, value)) if !line.starts_with(' ') => {
+                    field = key.trim();
+                    value.trim()
+                }
+                _ => line.trim(),
+            };
+            match field {
+                "Name" => name = Some(value.to_string()),
+                "Installed Size" => package.size = parse_size(value).unwrap_or(0),
+                "Install Reason" => package.dependency = value.contains("dependency"),
+                "Required By" | "Optional For" => package.required_by.extend(
+                    value
+                        .split_whitespace()
+                        .filter(|word| *word != "None")
+                        .map(str::to_string),
+                ),
+                _ => {}
+            }
+        }
+
+        if let Some(name) = name {
+            package.name = name;
+            packages.push(package);
+        }
+    }
+    packages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QI: &str = "\
+Name            : app
+Installed Size  : 2.00 MiB
+Install Reason  : Explicitly installed
+
+Name            : lib-a
+Installed Size  : 1.50 KiB
+Install Reason  : Installed as a dependency for another package
+Required By     : None
+Optional For    : None
+
+Name            : lib-b
+Installed Size  : 1024.00 B
+Install Reason  : Installed as a dependency for another package
+Required By     : lib-a  lib-c
+                  lib-d
+Optional For    : None
+
+Name            : lib-c
+Installed Size  : 0.00 B
+Install Reason  : Installed as a dependency for another package
+Required By     : None
+Optional For    : app
+";
+
+    #[test]
+    fn parses_pacman_output() {
+        let packages = parse(QI);
+        assert_eq!(packages.len(), 4);
+        assert!(!packages[0].dependency);
+        assert_eq!(packages[0].size, 2 * 1024 * 1024);
+        assert_eq!(packages[1].size, 1536);
+        assert_eq!(packages[2].required_by, ["lib-a", "lib-c", "lib-d"]);
+        assert_eq!(packages[3].required_by, ["app"]);
+    }
+
+    #[test]
+    fn follows_orphans_of_orphans() {
+        let mut packages = parse(QI);
+        // lib-d is gone, so lib-b is only needed by lib-a and lib-c
+        packages[2].required_by.retain(|r| r != "lib-d");
+        packages[3].required_by.clear();
+
+        let names = |orphans: Vec<Orphan>| -> Vec<(String, bool)> {
+            orphans.into_iter().map(|o| (o.name, o.cascaded)).collect()
+        };
+        assert_eq!(
+            names(resolve(&packages, &HashSet::new())),
+            [
+                ("lib-a".to_string(), false),
+                ("lib-c".to_string(), false),
+                ("lib-b".to_string(), true),
+            ]
+        );
+
+        let keep = HashSet::from(["lib-c".to_string()]);
+        assert_eq!(
+            names(resolve(&packages, &keep)),
+            [("lib-a".to_string(), false)]
+        );
+    }
+}
@@ -6,6 +6,7 @@
 use super::aur;
 use anyhow::Result;
 use log::debug;
+use std::collections::HashSet;
 
 /// Check if a package is installed using AUR helper or pacman.
 pub fn is_package_installed(package: &str) -> bool {
@@ -13,12 +14,12 @@ pub fn is_package_installed(package: &str) -> bool {
 
     // Use the cached AUR helper if available (avoids re-scanning PATH)
     if let Some(helper) = aur::get() {
-        if let Ok(output) = std::process::Command::new(helper)
+        if let Ok(output) = std::process::Command::new(helper.name())
             .args(["-Q", package])
             .output()
         {
             if output.status.success() {
-                debug!("Package '{}' found via {}", package, helper);
+                debug!("Package '{}' found via {}", package, helper.name());
                 return true;
             }
             // AUR helper -Q failed → package not installed, no need for pacman fallback
@@ -92,6 +93,60 @@ pub fn is_flatpak_installed(package: &str) -> bool {
     installed
 }
 
+/// Names of all installed pacman packages, from a single `pacman -Qq`.
+pub fn installed_packages() -> HashSet<String> {
+    output_lines("pacman", &["-Qq"])
+}
+
+/// Ids of all installed Flatpak apps and runtimes, from a single `flatpak list`.
+pub fn installed_flatpaks() -> HashSet<String> {
+    output_lines("flatpak", &["list", "--columns=application"])
+}
+
+/// Trimmed, non-empty stdout lines of a successful command.
+fn output_lines(program: &str, args: &[&str]) -> HashSet<String> {
+    std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_lines(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default()
+}
+
+/// Parse a size as pacman prints it, such as `1.50 MiB`, into bytes.
+pub(crate) fn parse_size(text: &str) -> Option<u64> {
+    let (number, unit) = text.split_once(' ')?;
+    let number: f64 = number.parse().ok()?;
+    let power = ["B", "KiB", "MiB", "GiB", "TiB"]
+        .iter()
+        .position(|u| *u == unit.trim())?;
+    Some((number * 1024f64.powi(power as i32)) as u64)
+}
+
+fn parse_lines(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Check if a Flatpak remote such as `flathub` is configured.
+pub fn has_flatpak_remote(name: &str) -> bool {
+    std::process::Command::new("flatpak")
+        .args(["remotes", "--columns=name"])
+        .output()
+        .map(|output| {
+            output.status.success()
+                && String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .any(|line| line.trim() == name)
+        })
+        .unwrap_or(false)
+}
+
 /// Open a URL in the default browser.
 pub fn open_url(url: &str) -> Result<()> {
     debug!("Opening URL: {}", url);
@@ -103,6 +158,13 @@ pub fn open_url(url: &str) -> Result<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_lines() {
+        let names = parse_lines("linux\n  mesa \n\nsteam\n");
+        assert_eq!(names.len(), 3);
+        assert!(names.contains("mesa"));
+    }
+
     #[test]
     fn test_is_package_installed_nonexistent() {
         // A package that definitely doesn't exist
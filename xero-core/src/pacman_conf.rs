@@ -0,0 +1,219 @@
+//! Structured edits of `/etc/pacman.conf`.
+//!
+//! The file is kept line by line, so comments, spacing and the order of
+//! repositories survive an edit; only the lines for the option being
+//! changed are rewritten. Writing needs root, so changes go through
+//! [`write_script`] in a privileged step.
+
+use anyhow::{Context, Result};
+use std::ops::Range;
+
+pub const PACMAN_CONF: &str = "/etc/pacman.conf";
+
+/// Copy of `/etc/pacman.conf` from before the last change.
+pub const BACKUP: &str = "/etc/pacman.conf.xero-toolkit.bak";
+
+/// The lines of a pacman.conf, grouped into `[sections]` when read.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PacmanConf {
+    lines: Vec<String>,
+}
+
+/// `key` and value of an active `Key = value` or bare `Key` line.
+fn option(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+        return None;
+    }
+    match line.split_once('=') {
+        Some((key, value)) => Some((key.trim(), value.trim())),
+        None => Some((line, "")),
+    }
+}
+
+/// Section name of a `[name]` line.
+fn header(line: &str) -> Option<&str> {
+    line.trim().strip_prefix('[')?.strip_suffix(']')
+}
+
+impl PacmanConf {
+    pub fn parse(text: &str) -> Self {
+        Self {
+            lines: text.lines().map(str::to_string).collect(),
+        }
+    }
+
+    /// Read `/etc/pacman.conf`.
+    pub fn read() -> Result<Self> {
+        let text = std::fs::read_to_string(PACMAN_CONF)
+            .with_context(|| format!("Failed to read {}", PACMAN_CONF))?;
+        Ok(Self::parse(&text))
+    }
+
+    /// Names of the sections, `options` and the repositories, in order.
+    pub fn sections(&self) -> Vec<&str> {
+        self.lines.iter().filter_map(|l| header(l)).collect()
+    }
+
+    /// Lines of `section` after its header, up to the next one.
+    fn section(&self, name: &str) -> Option<Range<usize>> {
+        let start = self.lines.iter().position(|l| header(l) == Some(name))? + 1;
+        let end = self.lines[start..]
+            .iter()
+            .position(|l| header(l).is_some())
+            .map_or(self.lines.len(), |i| start + i);
+        Some(start..end)
+    }
+
+    /// Words of a list option such as `IgnorePkg`, from all of its lines
+    /// in `section`.
+    pub fn list(&self, section: &str, key: &str) -> Vec<String> {
+        let Some(range) = self.section(section) else {
+            return Vec::new();
+        };
+        self.lines[range]
+            .iter()
+            .filter_map(|l| option(l))
+            .filter(|(k, _)| *k == key)
+            .flat_map(|(_, value)| value.split_whitespace().map(str::to_string))
+            .collect()
+    }
+
+    /// Set a list option in `section` to `values`, on a single line. The
+    /// line goes where the option was, or below its commented-out example,
+    /// or at the end of the section. An empty list removes the option.
+    pub fn set_list(&mut self, section: &str, key: &str, values: &[String]) {
+        let range = match self.section(section) {
+            Some(range) => range,
+            None => {
+                self.lines.push(String::new());
+                self.lines.push(format!("[{}]", section));
+                self.lines.len()..self.lines.len()
+            }
+        };
+
+        let active: Vec<usize> = range
+            .clone()
+            .filter(|&i| option(&self.lines[i]).is_some_and(|(k, _)| k == key))
+            .collect();
+        let commented = range.clone().find(|&i| {
+            let bare = self.lines[i].trim().trim_start_matches('#');
+            option(bare).is_some_and(|(k, _)| k == key)
+        });
+        let end = range
+            .clone()
+            .rev()
+            .find(|&i| !self.lines[i].trim().is_empty())
+            .map_or(range.start, |i| i + 1);
+
+        let at = match (active.first(), commented) {
+            (Some(&i), _) => i,
+            (None, Some(i)) => i + 1,
+            (None, None) => end,
+        };
+        for &i in active.iter().rev() {
+            self.lines.remove(i);
+        }
+        if !values.is_empty() {
+            self.lines
+                .insert(at, format!("{} = {}", key, values.join(" ")));
+        }
+    }
+
+    /// Remove `section` with its header. Blank lines go with it, so the
+    /// sections around it stay one blank line apart. Returns whether it
+    /// was there.
+    pub fn remove_section(&mut self, name: &str) -> bool {
+        let Some(range) = self.section(name) else {
+            return false;
+        };
+        let mut start = range.start - 1;
+        if range.end == self.lines.len() {
+            while start > 0 && self.lines[start - 1].trim().is_empty() {
+                start -= 1;
+            }
+        }
+        self.lines.drain(start..range.end);
+        true
+    }
+}
+
+impl std::fmt::Display for PacmanConf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Root script backing up `/etc/pacman.conf` and replacing it with `$1`.
+pub fn write_script() -> String {
+    format!(
+        "set -e\n\
+         cp -f {conf} {backup}\n\
+         printf '%s' \"$1\" > {conf}\n",
+        conf = PACMAN_CONF,
+        backup = BACKUP
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONF: &str = "\
+# General options
+[options]
+HoldPkg     = pacman glibc
+#IgnorePkg   =
+#IgnoreGroup =
+Color
+
+[core]
+Include = /etc/pacman.d/mirrorlist
+IgnorePkg = not-options
+";
+
+    #[test]
+    fn reads_options_and_sections() {
+        let conf = PacmanConf::parse(CONF);
+        assert_eq!(conf.sections(), ["options", "core"]);
+        assert_eq!(conf.list("options", "HoldPkg"), ["pacman", "glibc"]);
+        assert!(conf.list("options", "IgnorePkg").is_empty());
+        assert_eq!(conf.to_string(), CONF);
+    }
+
+    #[test]
+    fn sets_and_clears_lists() {
+        let mut conf = PacmanConf::parse(CONF);
+        let held = vec!["linux".to_string(), "mesa".to_string()];
+        conf.set_list("options", "IgnorePkg", &held);
+        assert!(conf
+            .to_string()
+            .contains("#IgnorePkg   =\nIgnorePkg = linux mesa\n#IgnoreGroup"));
+        assert_eq!(conf.list("options", "IgnorePkg"), held);
+        assert_eq!(conf.list("core", "IgnorePkg"), ["not-options"]);
+
+        conf.set_list("options", "IgnorePkg", &held[..1]);
+        assert_eq!(conf.list("options", "IgnorePkg"), ["linux"]);
+
+        conf.set_list("options", "IgnorePkg", &[]);
+        assert_eq!(conf.to_string(), CONF);
+    }
+
+    #[test]
+    fn removes_sections() {
+        let mut conf = PacmanConf::parse(&format!(
+            "{}\n[chaotic-aur]\nInclude = /etc/pacman.d/chaotic-mirrorlist\n",
+            CONF
+        ));
+        assert!(conf.remove_section("chaotic-aur"));
+        assert_eq!(conf.to_string(), CONF);
+        assert!(!conf.remove_section("chaotic-aur"));
+
+        assert!(conf.remove_section("options"));
+        assert_eq!(conf.sections(), ["core"]);
+        assert!(conf.to_string().starts_with("# General options\n[core]\n"));
+    }
+}
@@ -0,0 +1,278 @@
+//! Rootless Podman and quadlet generation.
+//!
+//! Rootless containers need subordinate UID/GID ranges for the user. Quadlets
+//! are `.container` files under `~/.config/containers/systemd/` that systemd
+//! turns into user services, replacing `podman generate systemd`.
+
+use anyhow::{bail, Context, Result};
+use log::info;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Subordinate ID range given to the user, the one `useradd` picks for the
+/// first user.
+pub const SUBID_RANGE: &str = "100000-165535";
+
+/// Environment variables every image sets, left out of generated quadlets.
+const IMAGE_ENV: &[&str] = &["PATH", "HOSTNAME", "HOME", "TERM", "container"];
+
+/// `podman inspect` template printing one `key<TAB>value` line per setting.
+const INSPECT_FORMAT: &str = "image\t{{.ImageName}}\n\
+    restart\t{{.HostConfig.RestartPolicy.Name}}\n\
+    {{range .Config.Env}}env\t{{.}}\n{{end}}\
+    {{range $p, $b := .HostConfig.PortBindings}}{{range $b}}\
+    port\t{{.HostIP}}|{{.HostPort}}|{{$p}}\n{{end}}{{end}}\
+    {{range .Mounts}}mount\t{{.Type}}|{{.Name}}|{{.Source}}|{{.Destination}}|{{.RW}}\n{{end}}\
+    {{range .Config.Cmd}}cmd\t{{.}}\n{{end}}";
+
+/// Whether `user` has an entry in a subuid or subgid file.
+fn has_subids(contents: &str, user: &str) -> bool {
+    contents.lines().any(|l| l.split(':').next() == Some(user))
+}
+
+/// Whether `user` has both subordinate UID and GID ranges.
+pub fn rootless_ready(user: &str) -> bool {
+    ["/etc/subuid", "/etc/subgid"].iter().all(|file| {
+        std::fs::read_to_string(file)
+            .map(|c| has_subids(&c, user))
+            .unwrap_or(false)
+    })
+}
+
+/// Whether the user's Podman API socket is enabled.
+pub fn user_socket_enabled() -> bool {
+    Command::new("systemctl")
+        .args(["--user", "is-enabled", "--quiet", "podman.socket"])
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+/// Enable and start, or disable and stop, the user's Podman API socket.
+pub fn set_user_socket(enabled: bool) -> Result<()> {
+    let verb = if enabled { "enable" } else { "disable" };
+    let status = Command::new("systemctl")
+        .args(["--user", verb, "--now", "podman.socket"])
+        .status()
+        .context("Failed to run systemctl")?;
+    if !status.success() {
+        bail!("systemctl --user {} podman.socket failed", verb);
+    }
+    info!("User podman.socket {}d", verb);
+    Ok(())
+}
+
+/// Names of the user's containers, running or not.
+pub fn containers() -> Vec<String> {
+    Command::new("podman")
+        .args(["ps", "--all", "--format", "{{.Names}}"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| {
+            String::from_utf8_lossy(&o.stdout)
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Quadlet file for the container `name`.
+pub fn quadlet_path(name: &str) -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("/root/.config"))
+        .join("containers/systemd")
+        .join(format!("{}.container", name))
+}
+
+/// Settings of a container that carry over to a quadlet.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Container {
+    pub name: String,
+    pub image: String,
+    /// `PublishPort=` values.
+    pub ports: Vec<String>,
+    /// `Volume=` values.
+    pub volumes: Vec<String>,
+    /// `KEY=value` pairs set on the container.
+    pub env: Vec<String>,
+    /// Command and arguments after the image.
+    pub exec: Vec<String>,
+    /// Podman restart policy, empty or `no` when unset.
+    pub restart: String,
+}
+
+/// Parse the output of `podman inspect --format INSPECT_FORMAT`.
+fn parse_inspect(name: &str, output: &str) -> Container {
+    let mut container = Container {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    for (key, value) in output.lines().filter_map(|l| l.split_once('\t')) {
+        match key {
+            "image" => container.image = value.to_string(),
+            "restart" => container.restart = value.to_string(),
+            "env" => {
+                let var = value.split('=').next().unwrap_or_default();
+                if !IMAGE_ENV.contains(&var) {
+                    container.env.push(value.to_string());
+                }
+            }
+            "port" => {
+                let parts: Vec<&str> = value.split('|').collect();
+                if let [ip, host_port, container_port] = parts[..] {
+                    let container_port = container_port.trim_end_matches("/tcp");
+                    container.ports.push(match ip {
+                        "" | "0.0.0.0" => format!("{}:{}", host_port, container_port),
+                        _ => format!("{}:{}:{}", ip, host_port, container_port),
+                    });
+                }
+            }
+            "mount" => {
+                let parts: Vec<&str> = value.split('|').collect();
+                if let [kind, volume, source, dest, rw] = parts[..] {
+                    let source = if kind == "volume" { volume } else { source };
+                    let ro = if rw == "false" { ":ro" } else { "" };
+                    container.volumes.push(format!("{}:{}{}", source, dest, ro));
+                }
+            }
+            "cmd" => container.exec.push(value.to_string()),
+            _ => {}
+        }
+    }
+    container
+}
+
+/// Read a container's settings.
+pub fn inspect(name: &str) -> Result<Container> {
+    let output = Command::new("podman")
+        .args([
+            "inspect",
+            "--type",
+            "container",
+            "--format",
+            INSPECT_FORMAT,
+            name,
+        ])
+        .output()
+        .context("Failed to run podman inspect")?;
+    if !output.status.success() {
+        bail!(
+            "podman inspect {} failed: {}",
+            name,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(parse_inspect(name, &stdout))
+}
+
+/// Quote a value for a systemd unit file if it needs it. `%` starts a
+/// specifier in units, so it's doubled either way.
+fn quote(value: &str) -> String {
+    let value = value.replace('%', "%%");
+    if value.is_empty() || value.contains(|c: char| c.is_whitespace() || c == '"' || c == '\\') {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value
+    }
+}
+
+impl Container {
+    /// Quadlet unit running this container as a user service.
+    pub fn quadlet(&self) -> String {
+        let mut unit = format!(
+            "# Generated by Xero Toolkit from the {name} container\n\
+             [Unit]\nDescription={name} container\n\n\
+             [Container]\nContainerName={name}\nImage={image}\n",
+            name = self.name,
+            image = self.image
+        );
+        for port in &self.ports {
+            unit.push_str(&format!("PublishPort={}\n", port));
+        }
+        for volume in &self.volumes {
+            unit.push_str(&format!("Volume={}\n", quote(volume)));
+        }
+        for var in &self.env {
+            unit.push_str(&format!("Environment={}\n", quote(var)));
+        }
+        if !self.exec.is_empty() {
+            let args: Vec<String> = self.exec.iter().map(|a| quote(a)).collect();
+            unit.push_str(&format!("Exec={}\n", args.join(" ")));
+        }
+
+        let restart = match self.restart.as_str() {
+            "always" | "unless-stopped" => Some("always"),
+            "on-failure" => Some("on-failure"),
+            _ => None,
+        };
+        if let Some(restart) = restart {
+            unit.push_str(&format!("\n[Service]\nRestart={}\n", restart));
+        }
+        unit.push_str("\n[Install]\nWantedBy=default.target\n");
+        unit
+    }
+
+    /// Name of the user service systemd generates from the quadlet.
+    pub fn service(&self) -> String {
+        format!("{}.service", self.name)
+    }
+
+    /// Where the quadlet is written.
+    pub fn unit_path(&self) -> PathBuf {
+        quadlet_path(&self.name)
+    }
+
+    /// Write the quadlet, returning its path.
+    pub fn write_quadlet(&self) -> Result<PathBuf> {
+        let path = self.unit_path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&path, self.quadlet())
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        info!("Wrote quadlet {}", path.display());
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_subids() {
+        let subuid = "root:1000000:1000000000\nalice:100000:65536\n";
+        assert!(has_subids(subuid, "alice"));
+        assert!(!has_subids(subuid, "ali"));
+    }
+
+    #[test]
+    fn test_quadlet() {
+        let output = "image\tdocker.io/library/nginx:latest\n\
+                      restart\tunless-stopped\n\
+                      env\tPATH=/usr/bin\n\
+                      env\tGREETING=hello world\n\
+                      port\t|8080|80/tcp\n\
+                      port\t127.0.0.1|5353|53/udp\n\
+                      mount\tvolume|web|/home/a/.local/share/containers/storage/volumes/web/_data|/data|true\n\
+                      mount\tbind||/srv/site|/usr/share/nginx/html|false\n\
+                      cmd\tnginx\ncmd\t-g\ncmd\tdaemon off;\n";
+        let container = parse_inspect("web", output);
+        assert_eq!(container.ports, ["8080:80", "127.0.0.1:5353:53/udp"]);
+        assert_eq!(
+            container.volumes,
+            ["web:/data", "/srv/site:/usr/share/nginx/html:ro"]
+        );
+
+        let unit = container.quadlet();
+        assert!(unit.contains("Image=docker.io/library/nginx:latest\n"));
+        assert!(unit.contains("Environment=\"GREETING=hello world\"\n"));
+        assert!(!unit.contains("PATH="));
+        assert!(unit.contains("Exec=nginx -g \"daemon off;\"\n"));
+        assert!(unit.contains("[Service]\nRestart=always\n"));
+    }
+}
@@ -0,0 +1,207 @@
+//! Power management daemon and battery detection.
+//!
+//! power-profiles-daemon, TLP and tuned-ppd all manage the same knobs and
+//! conflict with each other. Everything that installs one of them should go
+//! through [`remove_conflicts`] so the others get removed first.
+
+use super::is_package_installed;
+use crate::package;
+use crate::task_runner::{Command, CommandSequence};
+use log::{debug, info};
+use std::path::PathBuf;
+
+/// Mutually exclusive power management daemons.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PowerDaemon {
+    PowerProfiles,
+    Tlp,
+    TunedPpd,
+}
+
+impl PowerDaemon {
+    pub const ALL: [PowerDaemon; 3] = [
+        PowerDaemon::PowerProfiles,
+        PowerDaemon::Tlp,
+        PowerDaemon::TunedPpd,
+    ];
+
+    /// Human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerDaemon::PowerProfiles => "power-profiles-daemon",
+            PowerDaemon::Tlp => "TLP",
+            PowerDaemon::TunedPpd => "tuned-ppd",
+        }
+    }
+
+    /// Packages that make up this daemon. The first one is used to detect it.
+    pub fn packages(&self) -> &'static [&'static str] {
+        match self {
+            PowerDaemon::PowerProfiles => &["power-profiles-daemon"],
+            PowerDaemon::Tlp => &["tlp", "tlp-rdw"],
+            PowerDaemon::TunedPpd => &["tuned-ppd", "tuned"],
+        }
+    }
+
+    /// The daemon's own systemd units, enabled after installation and
+    /// disabled when it's removed.
+    pub fn services(&self) -> &'static [&'static str] {
+        match self {
+            PowerDaemon::PowerProfiles => &["power-profiles-daemon.service"],
+            PowerDaemon::Tlp => &["tlp.service"],
+            PowerDaemon::TunedPpd => &["tuned.service", "tuned-ppd.service"],
+        }
+    }
+
+    /// Units of other packages the daemon needs enabled. The rest of the
+    /// system uses them too, so removing the daemon leaves them alone.
+    pub fn extra_services(&self) -> &'static [&'static str] {
+        match self {
+            PowerDaemon::Tlp => &["NetworkManager-dispatcher.service"],
+            PowerDaemon::PowerProfiles | PowerDaemon::TunedPpd => &[],
+        }
+    }
+
+    /// Whether this daemon is currently installed.
+    pub fn is_installed(&self) -> bool {
+        is_package_installed(self.packages()[0])
+    }
+}
+
+/// An installed daemon, with those of its packages that are installed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InstalledDaemon {
+    pub daemon: PowerDaemon,
+    pub packages: Vec<&'static str>,
+}
+
+/// The installed daemons, read from pacman once. Blocks, so call off the
+/// main thread.
+pub fn installed_daemons() -> Vec<InstalledDaemon> {
+    let installed = package::installed_packages();
+    PowerDaemon::ALL
+        .into_iter()
+        .filter(|d| installed.contains(d.packages()[0]))
+        .map(|daemon| InstalledDaemon {
+            daemon,
+            packages: daemon
+                .packages()
+                .iter()
+                .copied()
+                .filter(|p| installed.contains(*p))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Append commands that remove every `installed` daemon conflicting with
+/// `target`. Shared by every handler that installs a power daemon.
+pub fn remove_conflicts(
+    mut commands: CommandSequence,
+    target: PowerDaemon,
+    installed: &[InstalledDaemon],
+) -> CommandSequence {
+    for InstalledDaemon { daemon, packages } in installed {
+        if *daemon == target {
+            continue;
+        }
+        info!(
+            "{} installed, removing first (conflicts with {})",
+            daemon.name(),
+            target.name()
+        );
+
+        let mut disable_args = vec!["disable", "--now"];
+        disable_args.extend_from_slice(daemon.services());
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&disable_args)
+                .description(&format!("Stopping {}...", daemon.name()))
+                .build(),
+        );
+
+        // -Rdd: the replacement is installed right after and may provide the
+        // same virtual package, so dependents must not block the removal.
+        let remove = format!("pacman -Rdd --noconfirm {} || true", packages.join(" "));
+
+        commands = commands.then(
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&["-c", &remove])
+                .description(&format!(
+                    "Removing {} (conflicts with {})...",
+                    daemon.name(),
+                    target.name()
+                ))
+                .build(),
+        );
+    }
+
+    commands
+}
+
+/// Paths of all batteries reported by the kernel.
+fn batteries() -> Vec<PathBuf> {
+    std::fs::read_dir("/sys/class/power_supply")
+        .map(|dir| {
+            dir.flatten()
+                .map(|e| e.path())
+                .filter(|p| {
+                    p.file_name()
+                        .and_then(|n| n.to_str())
+                        .is_some_and(|n| n.starts_with("BAT"))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether the machine is running on battery power.
+pub fn on_battery() -> bool {
+    batteries()
+        .iter()
+        .any(|b| std::fs::read_to_string(b.join("status")).is_ok_and(|s| s.trim() == "Discharging"))
+}
+
+/// Whether this machine is a laptop (has a battery or a portable chassis).
+pub fn is_laptop() -> bool {
+    if !batteries().is_empty() {
+        return true;
+    }
+
+    // SMBIOS chassis types: notebook, portable, laptop, sub-notebook,
+    // convertible, detachable.
+    let chassis = std::fs::read_to_string("/sys/class/dmi/id/chassis_type").unwrap_or_default();
+    matches!(chassis.trim(), "8" | "9" | "10" | "14" | "31" | "32")
+}
+
+/// Hardware vendor as reported by DMI.
+pub fn vendor() -> String {
+    std::fs::read_to_string("/sys/class/dmi/id/sys_vendor")
+        .map(|v| v.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Sysfs files for the battery charge end threshold. Only vendors with
+/// kernel support (ThinkPad, ASUS, Huawei, Framework, ...) expose these.
+pub fn charge_threshold_paths() -> Vec<PathBuf> {
+    let paths: Vec<PathBuf> = batteries()
+        .into_iter()
+        .map(|b| b.join("charge_control_end_threshold"))
+        .filter(|p| p.exists())
+        .collect();
+
+    debug!("Charge threshold paths: {:?}", paths);
+    paths
+}
+
+/// Current charge end threshold of the first battery that supports it.
+pub fn current_charge_threshold() -> Option<u8> {
+    charge_threshold_paths()
+        .first()
+        .and_then(|p| std::fs::read_to_string(p).ok())
+        .and_then(|v| v.trim().parse().ok())
+}
@@ -0,0 +1,276 @@
+//! Hybrid graphics switching for laptops with an NVIDIA GPU.
+//!
+//! Optimus laptops pair an integrated Intel or AMD GPU with an NVIDIA one.
+//! envycontrol or supergfxctl decides which of them is powered and drives
+//! the display. A switch only takes effect after a reboot, so the previous
+//! mode is kept in a rollback script that can be run from a TTY if the
+//! desktop doesn't come back.
+
+use super::hardware::GpuVendor;
+use crate::i18n::n_;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Script that switches back to the mode used before the last change.
+pub const ROLLBACK_SCRIPT: &str = "/usr/local/bin/xero-gpu-rollback";
+
+/// Environment that sends a single program to the NVIDIA GPU, the same as
+/// `prime-run` sets.
+pub const OFFLOAD_ENV: &[(&str, &str)] = &[
+    ("__NV_PRIME_RENDER_OFFLOAD", "1"),
+    ("__VK_LAYER_NV_optimus", "NVIDIA_only"),
+    ("__GLX_VENDOR_LIBRARY_NAME", "nvidia"),
+];
+
+/// Whether the GPUs form an Optimus setup, an NVIDIA GPU next to one from
+/// another vendor.
+pub fn is_hybrid(gpus: &[GpuVendor]) -> bool {
+    gpus.contains(&GpuVendor::Nvidia) && gpus.len() > 1
+}
+
+/// GPU modes offered by both switchers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// NVIDIA GPU powered off, longest battery life.
+    Integrated,
+    /// Integrated GPU drives the display, programs are offloaded on demand.
+    Hybrid,
+    /// NVIDIA GPU drives everything.
+    Dedicated,
+}
+
+impl Mode {
+    pub const ALL: [Mode; 3] = [Mode::Integrated, Mode::Hybrid, Mode::Dedicated];
+
+    /// Stable id, used in the rollback script.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Mode::Integrated => "integrated",
+            Mode::Hybrid => "hybrid",
+            Mode::Dedicated => "dedicated",
+        }
+    }
+
+    /// Human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Mode::Integrated => "Integrated",
+            Mode::Hybrid => "Hybrid",
+            Mode::Dedicated => "Dedicated (NVIDIA)",
+        }
+    }
+
+    /// Parse a mode as printed by either switcher, or an id.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "integrated" => Some(Mode::Integrated),
+            "hybrid" => Some(Mode::Hybrid),
+            "nvidia" | "asusmuxdgpu" | "dedicated" => Some(Mode::Dedicated),
+            _ => None,
+        }
+    }
+}
+
+/// Tools that switch the GPU mode. They write conflicting modprobe and
+/// Xorg configuration, so only one should be installed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Switcher {
+    EnvyControl,
+    Supergfxctl,
+}
+
+impl Switcher {
+    pub const ALL: [Switcher; 2] = [Switcher::EnvyControl, Switcher::Supergfxctl];
+
+    /// Package and program name.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Switcher::EnvyControl => "envycontrol",
+            Switcher::Supergfxctl => "supergfxctl",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.id() == id)
+    }
+
+    /// Human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Switcher::EnvyControl => "EnvyControl",
+            Switcher::Supergfxctl => "supergfxctl",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Switcher::EnvyControl => n_("Works on most Optimus laptops, no daemon"),
+            Switcher::Supergfxctl => n_("Daemon from asus-linux, supports the ASUS MUX switch"),
+        }
+    }
+
+    /// Service the switcher needs running.
+    pub fn service(&self) -> Option<&'static str> {
+        match self {
+            Switcher::EnvyControl => None,
+            Switcher::Supergfxctl => Some("supergfxd.service"),
+        }
+    }
+
+    /// envycontrol edits files under /etc itself, supergfxctl asks its
+    /// daemon.
+    pub fn needs_root(&self) -> bool {
+        *self == Switcher::EnvyControl
+    }
+
+    /// Arguments that switch to `mode`.
+    pub fn switch_args(&self, mode: Mode) -> [&'static str; 2] {
+        match self {
+            Switcher::EnvyControl => {
+                let id = match mode {
+                    Mode::Integrated => "integrated",
+                    Mode::Hybrid => "hybrid",
+                    Mode::Dedicated => "nvidia",
+                };
+                ["--switch", id]
+            }
+            Switcher::Supergfxctl => {
+                let id = match mode {
+                    Mode::Integrated => "Integrated",
+                    Mode::Hybrid => "Hybrid",
+                    Mode::Dedicated => "AsusMuxDgpu",
+                };
+                ["--mode", id]
+            }
+        }
+    }
+
+    pub fn installed(&self) -> bool {
+        super::is_package_installed(self.id())
+    }
+
+    fn query(&self, arg: &str) -> Option<String> {
+        let output = Command::new(self.id()).arg(arg).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    /// The mode currently set. Both tools print it last on their output.
+    pub fn current_mode(&self) -> Option<Mode> {
+        let arg = match self {
+            Switcher::EnvyControl => "--query",
+            Switcher::Supergfxctl => "--get",
+        };
+        Mode::parse(self.query(arg)?.split_whitespace().last()?)
+    }
+
+    /// Modes this laptop supports. supergfxctl only offers Dedicated on
+    /// ASUS laptops with a MUX switch.
+    pub fn supported_modes(&self) -> Vec<Mode> {
+        match self {
+            Switcher::EnvyControl => Mode::ALL.to_vec(),
+            Switcher::Supergfxctl => self
+                .query("--supported")
+                .map(|output| parse_supported(&output))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Parse `supergfxctl --supported` output such as
+/// `[Integrated, Hybrid, AsusMuxDgpu]`.
+fn parse_supported(output: &str) -> Vec<Mode> {
+    output
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter_map(Mode::parse)
+        .collect()
+}
+
+/// The installed switcher, if any.
+pub fn installed_switcher() -> Option<Switcher> {
+    Switcher::ALL.into_iter().find(|s| s.installed())
+}
+
+/// Rollback script that returns to `previous` and reboots. It removes
+/// itself once run, so there's only ever one step to undo.
+pub fn rollback_script(switcher: Switcher, previous: Mode) -> String {
+    format!(
+        "#!/bin/sh\n\
+         # Generated by Xero Toolkit\n\
+         # previous-mode: {id} {mode}\n\
+         # Switches back to the GPU mode used before the last change. Run it\n\
+         # with sudo from a TTY (Ctrl+Alt+F3) if the desktop doesn't come up.\n\
+         set -e\n\
+         {id} {args}\n\
+         rm -f \"$0\"\n\
+         systemctl reboot\n",
+        id = switcher.id(),
+        mode = previous.id(),
+        args = switcher.switch_args(previous).join(" ")
+    )
+}
+
+/// Switcher and mode recorded in a rollback script.
+fn parse_rollback(contents: &str) -> Option<(Switcher, Mode)> {
+    let line = contents
+        .lines()
+        .find_map(|l| l.strip_prefix("# previous-mode: "))?;
+    let (switcher, mode) = line.split_once(' ')?;
+    Some((Switcher::from_id(switcher)?, Mode::parse(mode)?))
+}
+
+/// The mode the rollback script returns to, if one is armed.
+pub fn rollback_target() -> Option<(Switcher, Mode)> {
+    parse_rollback(&std::fs::read_to_string(ROLLBACK_SCRIPT).ok()?)
+}
+
+/// `KEY=value` lines of the offload environment.
+pub fn offload_env() -> String {
+    OFFLOAD_ENV
+        .iter()
+        .map(|(key, value)| format!("{}={}\n", key, value))
+        .collect()
+}
+
+/// Where the offload environment is saved for launchers that read
+/// environment files, `~/.config/xero-toolkit/prime-offload.env`.
+pub fn offload_env_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("/root/.config"))
+        .join("xero-toolkit/prime-offload.env")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modes() {
+        use GpuVendor::*;
+        assert!(is_hybrid(&[Intel, Nvidia]));
+        assert!(!is_hybrid(&[Nvidia]));
+        assert!(!is_hybrid(&[Intel, Amd]));
+
+        assert_eq!(Mode::parse("nvidia\n"), Some(Mode::Dedicated));
+        assert_eq!(
+            parse_supported("[Integrated, Hybrid, Vfio, AsusMuxDgpu]"),
+            Mode::ALL
+        );
+    }
+
+    #[test]
+    fn test_rollback_script() {
+        let script = rollback_script(Switcher::EnvyControl, Mode::Hybrid);
+        assert!(script.contains("\nenvycontrol --switch hybrid\n"));
+        assert_eq!(
+            parse_rollback(&script),
+            Some((Switcher::EnvyControl, Mode::Hybrid))
+        );
+
+        let script = rollback_script(Switcher::Supergfxctl, Mode::Dedicated);
+        assert!(script.contains("\nsupergfxctl --mode AsusMuxDgpu\n"));
+        assert_eq!(parse_rollback("#!/bin/sh\n"), None);
+    }
+}
@@ -0,0 +1,216 @@
+//! Setup profiles for re-provisioning a reinstalled machine.
+//!
+//! A profile lists what the toolkit set up on one computer: registered
+//! actions that report being installed, by `category/id`, and other
+//! sequences that finished successfully, by their commands. Importing it
+//! elsewhere replays the chosen steps as one task runner sequence.
+
+use crate::actions::{self, Action};
+use crate::history::{self, Entry, Outcome};
+use crate::task_runner::{Command, CommandSequence, CommandType};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Task runner title of an imported profile, left out of new profiles.
+pub const RESTORE_TITLE: &str = "Restore Setup";
+
+/// File name suggested when exporting.
+pub const FILE_NAME: &str = "xero-setup.toml";
+
+/// A saved setup.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Profile {
+    /// When it was exported, in seconds since the Unix epoch.
+    pub created: i64,
+    /// Computer it was exported from.
+    pub hostname: String,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+}
+
+/// One thing to set up again.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Step {
+    pub title: String,
+    /// Registered action as `category/id`, built fresh on import.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    /// Commands of a recorded sequence, replayed as they are.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub commands: Vec<Command>,
+}
+
+impl Step {
+    fn from_action(action: &Action) -> Self {
+        Self {
+            title: action.title.to_string(),
+            action: Some(format!("{}/{}", action.category, action.id)),
+            commands: Vec::new(),
+        }
+    }
+
+    /// The registered action, if the step names one this version knows.
+    pub fn registered_action(&self) -> Option<&'static Action> {
+        let (category, id) = self.action.as_deref()?.split_once('/')?;
+        actions::find(category, id)
+    }
+
+    /// Whether this version can run the step.
+    pub fn is_known(&self) -> bool {
+        self.action.is_none() || self.registered_action().is_some()
+    }
+
+    /// Commands to run for the step, including whatever its action
+    /// requires. `None` for an action this version doesn't know.
+    pub fn commands(&self) -> Option<CommandSequence> {
+        if self.action.is_none() {
+            return Some(
+                self.commands
+                    .iter()
+                    .cloned()
+                    .fold(CommandSequence::new(), CommandSequence::then),
+            );
+        }
+        let action = self.registered_action()?;
+        let sequence = action
+            .missing_requirements()
+            .iter()
+            .fold(CommandSequence::new(), |sequence, requirement| {
+                sequence.append(requirement.install_commands())
+            });
+        Some(sequence.append(action.commands_with_hooks()))
+    }
+}
+
+impl Profile {
+    /// Profile of this computer, for the user to pick from. Blocks on the
+    /// install probes of the registered actions.
+    pub fn current() -> Self {
+        let installed: Vec<&Action> = actions::ACTIONS
+            .iter()
+            .copied()
+            .filter(|action| action.is_installed() == Some(true))
+            .collect();
+        let hostname = std::fs::read_to_string("/etc/hostname").unwrap_or_default();
+        Self {
+            created: history::now(),
+            hostname: hostname.trim().to_string(),
+            steps: steps(&installed, &history::load()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&text).with_context(|| format!("{} is not a setup profile", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = toml::to_string(self).context("Failed to encode the profile")?;
+        std::fs::write(path, text).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Steps for the `installed` actions, then the successful `entries` that
+/// can be replayed, each title once with its newest commands.
+fn steps(installed: &[&Action], entries: &[Entry]) -> Vec<Step> {
+    let mut titles: HashSet<&str> = actions::ACTIONS.iter().map(|a| a.title).collect();
+    titles.insert(RESTORE_TITLE);
+
+    let mut recorded: Vec<Step> = Vec::new();
+    for entry in entries.iter().rev() {
+        if entry.outcome != Outcome::Success
+            || entry.commands.is_empty()
+            || !titles.insert(&entry.title)
+            || uses_staged_files(&entry.commands)
+        {
+            continue;
+        }
+        recorded.push(Step {
+            title: entry.title.clone(),
+            action: None,
+            commands: entry.commands.clone(),
+        });
+    }
+
+    installed
+        .iter()
+        .map(|action| Step::from_action(action))
+        .chain(recorded.into_iter().rev())
+        .collect()
+}
+
+/// Whether the commands read files the toolkit wrote to `/tmp` before
+/// running them. Those are gone on another computer, unless one of the
+/// commands downloads them again.
+fn uses_staged_files(commands: &[Command]) -> bool {
+    let downloaded: Vec<&str> = commands
+        .iter()
+        .filter_map(|c| match &c.command_type {
+            CommandType::Download(spec) => Some(spec.dest.as_str()),
+            _ => None,
+        })
+        .collect();
+    commands
+        .iter()
+        .filter(|c| !matches!(c.command_type, CommandType::Download(_)))
+        .flat_map(|c| &c.args)
+        .any(|arg| arg.starts_with("/tmp/") && !downloaded.contains(&arg.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, outcome: Outcome, args: &[&str]) -> Entry {
+        Entry {
+            title: title.to_string(),
+            timestamp: 0,
+            outcome,
+            log: None,
+            commands: vec![Command::builder()
+                .privileged()
+                .program("install")
+                .args(args)
+                .description("Installing")
+                .build()],
+        }
+    }
+
+    #[test]
+    fn test_steps_from_history() {
+        let entries = vec![
+            entry("Steam", Outcome::Success, &["old"]),
+            entry("Steam", Outcome::Success, &["new"]),
+            entry("Gaming Mode", Outcome::Failed, &["x"]),
+            entry("Samba", Outcome::Success, &["/tmp/xero-shares.conf"]),
+            entry("Docker Setup", Outcome::Success, &["x"]),
+            entry(RESTORE_TITLE, Outcome::Success, &["x"]),
+            entry("Zsh", Outcome::Success, &["x"]),
+        ];
+        let docker = actions::find("install", "docker").unwrap();
+        let steps = steps(&[docker], &entries);
+
+        let titles: Vec<&str> = steps.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Docker Setup", "Steam", "Zsh"]);
+        assert_eq!(steps[0].action.as_deref(), Some("install/docker"));
+        assert_eq!(steps[1].commands[0].args, ["new"]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let profile = Profile {
+            created: 1_760_000_000,
+            hostname: "xero".to_string(),
+            steps: steps(&[], &[entry("Steam", Outcome::Success, &["-S"])]),
+        };
+        let parsed: Profile = toml::from_str(&toml::to_string(&profile).unwrap()).unwrap();
+        assert_eq!(parsed.steps, profile.steps);
+        assert!(toml::from_str::<Profile>("created = 0\nhostname = \"\"\n")
+            .unwrap()
+            .steps
+            .is_empty());
+    }
+}
@@ -0,0 +1,175 @@
+//! Proton-GE builds for Steam.
+//!
+//! Releases are listed from the tags of GloriousEggroll's repository and
+//! installed by extracting the release archive into Steam's
+//! `compatibilitytools.d`, the same place ProtonUp-Qt puts them.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+const REPO: &str = "https://github.com/GloriousEggroll/proton-ge-custom";
+
+/// Prefix of release tags and of the directories they extract to.
+const TAG_PREFIX: &str = "GE-Proton";
+
+/// Steam's directory for custom compatibility tools.
+pub fn compat_dir() -> PathBuf {
+    PathBuf::from(&crate::config::env::get().home).join(".steam/root/compatibilitytools.d")
+}
+
+/// Directory release archives are downloaded to before extracting.
+pub fn download_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("/tmp"))
+        .join("xero-toolkit")
+        .join("proton")
+}
+
+/// A Proton-GE release, e.g. `GE-Proton9-20`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    pub tag: String,
+}
+
+impl Release {
+    /// File name of the release archive.
+    pub fn archive_name(&self) -> String {
+        format!("{}.tar.gz", self.tag)
+    }
+
+    /// URL of the release archive.
+    pub fn archive_url(&self) -> String {
+        format!(
+            "{}/releases/download/{}/{}",
+            REPO,
+            self.tag,
+            self.archive_name()
+        )
+    }
+
+    /// File name of the archive's SHA-512 checksum.
+    pub fn checksum_name(&self) -> String {
+        format!("{}.sha512sum", self.tag)
+    }
+
+    /// URL of the archive's SHA-512 checksum.
+    pub fn checksum_url(&self) -> String {
+        format!(
+            "{}/releases/download/{}/{}",
+            REPO,
+            self.tag,
+            self.checksum_name()
+        )
+    }
+
+    /// Whether this release is extracted in [`compat_dir`].
+    pub fn is_installed(&self) -> bool {
+        compat_dir().join(&self.tag).is_dir()
+    }
+}
+
+/// Major and minor version of a `GE-ProtonX-Y` tag.
+fn parse_version(tag: &str) -> Option<(u32, u32)> {
+    let (major, minor) = tag.strip_prefix(TAG_PREFIX)?.split_once('-')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
+
+/// Sort tags newest first, dropping anything that isn't a `GE-ProtonX-Y` tag.
+fn sort_tags<'a>(tags: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut versions: Vec<((u32, u32), &str)> = tags
+        .filter_map(|t| parse_version(t).map(|v| (v, t)))
+        .collect();
+    versions.sort_by_key(|v| std::cmp::Reverse(v.0));
+    versions.dedup_by_key(|(v, _)| *v);
+    versions.into_iter().map(|(_, t)| t.to_string()).collect()
+}
+
+/// Release tags from `git ls-remote --tags` output, newest first.
+fn parse_releases(refs: &str) -> Vec<Release> {
+    let tags = refs
+        .lines()
+        .filter_map(|l| l.split_whitespace().nth(1))
+        .filter_map(|r| r.strip_prefix("refs/tags/"));
+    sort_tags(tags)
+        .into_iter()
+        .map(|tag| Release { tag })
+        .collect()
+}
+
+/// The `limit` newest releases. Blocks on the network; returns `None` if
+/// the repository could not be reached.
+pub fn available(limit: usize) -> Option<Vec<Release>> {
+    let output = Command::new("git")
+        .args(["ls-remote", "--tags", "--refs", REPO])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+
+    let mut releases = parse_releases(&String::from_utf8_lossy(&output.stdout));
+    releases.truncate(limit);
+    Some(releases)
+}
+
+/// Proton-GE builds in [`compat_dir`], newest first.
+pub fn installed() -> Vec<String> {
+    let names: Vec<String> = std::fs::read_dir(compat_dir())
+        .map(|dir| {
+            dir.flatten()
+                .filter(|e| e.path().is_dir())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    sort_tags(names.iter().map(String::as_str))
+}
+
+/// Shell script that checks the downloaded archive of `release` in
+/// [`download_dir`] against its checksum, then extracts it into
+/// [`compat_dir`] and removes the download. Fails (and so aborts the
+/// sequence) if the checksum doesn't match.
+pub fn install_script(release: &Release) -> String {
+    format!(
+        "set -e; cd '{dir}' && \
+         sha512sum --check --strict '{checksum}' && \
+         mkdir -p '{compat}' && \
+         tar -xzf '{archive}' -C '{compat}' && \
+         rm -f '{archive}' '{checksum}'",
+        dir = download_dir().display(),
+        checksum = release.checksum_name(),
+        archive = release.archive_name(),
+        compat = compat_dir().display(),
+    )
+}
+
+/// Path of an installed build, if `name` is one of [`installed`].
+pub fn installed_path(name: &str) -> Option<PathBuf> {
+    installed()
+        .iter()
+        .any(|n| n == name)
+        .then(|| compat_dir().join(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("GE-Proton9-20"), Some((9, 20)));
+        assert_eq!(parse_version("GE-Proton10-1"), Some((10, 1)));
+        assert_eq!(parse_version("Proton-6.21-GE-2"), None);
+        assert_eq!(parse_version("GE-Proton9-20-rc"), None);
+    }
+
+    #[test]
+    fn test_parse_releases() {
+        let refs = "\
+abc\trefs/tags/GE-Proton9-2
+def\trefs/tags/Proton-6.21-GE-2
+123\trefs/tags/GE-Proton10-1
+456\trefs/tags/GE-Proton9-20
+";
+        let tags: Vec<String> = parse_releases(refs).into_iter().map(|r| r.tag).collect();
+        assert_eq!(tags, ["GE-Proton10-1", "GE-Proton9-20", "GE-Proton9-2"]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Quickemu virtual machines.
+//!
+//! `quickget` downloads an OS and writes `<os>-<release>.conf` next to a
+//! directory holding the disk. The toolkit keeps these in `~/VMs` and lists
+//! every config there so VMs can be started from the page.
+
+use crate::i18n::n_;
+use anyhow::{Context, Result};
+use log::info;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Packages installed by the Quickemu action.
+pub const PACKAGES: &[&str] = &["quickemu", "quickgui-bin"];
+
+/// A guest `quickget` can create with one click.
+#[derive(Clone, Copy, Debug)]
+pub struct Guest {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// OS and release passed to `quickget`.
+    pub quickget: &'static [&'static str],
+}
+
+/// Guests offered by the create dialog.
+pub const GUESTS: &[Guest] = &[
+    Guest {
+        id: "windows-11",
+        name: "Windows 11",
+        description: n_("Downloaded from Microsoft, with TPM and virtio drivers"),
+        quickget: &["windows", "11"],
+    },
+    Guest {
+        id: "macos-sonoma",
+        name: "macOS Sonoma",
+        description: n_("Recovery image from Apple, installed over the network"),
+        quickget: &["macos", "sonoma"],
+    },
+    Guest {
+        id: "ubuntu-24.04",
+        name: "Ubuntu 24.04 LTS",
+        description: n_("Desktop ISO from the Ubuntu mirrors"),
+        quickget: &["ubuntu", "24.04"],
+    },
+];
+
+impl Guest {
+    /// Name of the config `quickget` writes for this guest.
+    pub fn conf_name(&self) -> String {
+        format!("{}.conf", self.quickget.join("-"))
+    }
+}
+
+/// Look up a guest by id.
+pub fn find(id: &str) -> Option<&'static Guest> {
+    GUESTS.iter().find(|g| g.id == id)
+}
+
+/// Directory VMs are created in, `~/VMs`.
+pub fn vms_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("/root"))
+        .join("VMs")
+}
+
+/// A VM created by `quickget`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuickVm {
+    /// Config file name without `.conf`, e.g. `ubuntu-24.04`.
+    pub name: String,
+    pub conf: PathBuf,
+    /// The config's `guest_os`, e.g. `linux`, `windows` or `macos`.
+    pub guest_os: String,
+}
+
+/// `guest_os` from a Quickemu config, `None` if it isn't one.
+fn parse_guest_os(conf: &str) -> Option<String> {
+    conf.lines()
+        .find_map(|l| l.trim().strip_prefix("guest_os="))
+        .map(|v| v.trim_matches('"').to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// Quickemu configs in `dir`, sorted by name.
+pub fn list_vms(dir: &Path) -> Vec<QuickVm> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut vms: Vec<QuickVm> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "conf"))
+        .filter_map(|conf| {
+            let guest_os = parse_guest_os(&std::fs::read_to_string(&conf).ok()?)?;
+            let name = conf.file_stem()?.to_string_lossy().into_owned();
+            Some(QuickVm {
+                name,
+                conf,
+                guest_os,
+            })
+        })
+        .collect();
+    vms.sort_by(|a, b| a.name.cmp(&b.name));
+    vms
+}
+
+impl QuickVm {
+    /// Start the VM. Disk paths in the config are relative, so quickemu
+    /// runs from the config's directory.
+    pub fn launch(&self) -> Result<()> {
+        let dir = self.conf.parent().unwrap_or(Path::new("."));
+        info!("Launching Quickemu VM {}", self.name);
+        Command::new("quickemu")
+            .arg("--vm")
+            .arg(&self.conf)
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to launch {}", self.name))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conf_names() {
+        assert_eq!(find("windows-11").unwrap().conf_name(), "windows-11.conf");
+        assert_eq!(
+            find("ubuntu-24.04").unwrap().conf_name(),
+            "ubuntu-24.04.conf"
+        );
+        for guest in GUESTS {
+            assert_eq!(guest.conf_name(), format!("{}.conf", guest.id));
+        }
+    }
+
+    #[test]
+    fn test_parse_guest_os() {
+        let conf = "#!/usr/bin/quickemu --vm\nguest_os=\"linux\"\n\
+                    disk_img=\"ubuntu-24.04/disk.qcow2\"\n";
+        assert_eq!(parse_guest_os(conf).as_deref(), Some("linux"));
+        assert_eq!(parse_guest_os("[Unit]\nDescription=foo\n"), None);
+    }
+}
@@ -0,0 +1,165 @@
+//! Which actions make sense on this machine.
+//!
+//! Some buttons only apply to certain hardware: face unlock needs a
+//! camera, GPU tuning needs a GPU it supports, a VirtualBox host is no use
+//! inside a VM and battery tools are for laptops. The rules here are
+//! checked against a [`Machine`] detected once, and pages hide or annotate
+//! the widgets they name.
+
+use crate::hardware::{self, GpuVendor};
+use crate::package;
+use crate::power::{self, PowerDaemon};
+use crate::virt::{self, Hypervisor};
+use std::sync::OnceLock;
+
+/// Hardware facts the rules look at.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Machine {
+    pub gpus: Vec<GpuVendor>,
+    /// A video capture device is present.
+    pub camera: bool,
+    /// The hypervisor when running as a VM guest.
+    pub hypervisor: Option<Hypervisor>,
+    pub laptop: bool,
+}
+
+impl Machine {
+    /// Detect the machine. Blocks on `systemd-detect-virt`.
+    pub fn detect() -> Self {
+        Self {
+            gpus: hardware::gpus(),
+            camera: has_camera(),
+            hypervisor: virt::detect(),
+            laptop: power::is_laptop(),
+        }
+    }
+
+    /// The machine as detected on first use.
+    pub fn get() -> &'static Self {
+        static MACHINE: OnceLock<Machine> = OnceLock::new();
+        MACHINE.get_or_init(Self::detect)
+    }
+}
+
+fn has_camera() -> bool {
+    std::fs::read_dir("/sys/class/video4linux").is_ok_and(|mut dir| dir.next().is_some())
+}
+
+/// What to do with a widget on this machine.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fit {
+    /// Hide it, for the given reason.
+    Hidden(String),
+    /// Keep it, with a note on why it's a good fit.
+    Note(String),
+}
+
+/// A widget that only applies to some machines.
+pub struct Rule {
+    /// Id of the page in `navigation::PAGES`.
+    pub page: &'static str,
+    /// Builder id of the widget.
+    pub widget: &'static str,
+    /// How the widget fits, `None` when nothing changes. May probe the
+    /// system further, so it's called off the main thread.
+    pub check: fn(&Machine) -> Option<Fit>,
+}
+
+pub static RULES: &[Rule] = &[
+    Rule {
+        page: "biometrics",
+        widget: "frame_howdy",
+        check: |m| (!m.camera).then(|| Fit::Hidden("No camera found for face unlock".to_string())),
+    },
+    Rule {
+        page: "gaming_tools",
+        widget: "btn_lact_oc",
+        check: |m| {
+            m.gpus
+                .is_empty()
+                .then(|| Fit::Hidden("No AMD, NVIDIA or Intel GPU found for LACT".to_string()))
+        },
+    },
+    Rule {
+        page: "containers_vms",
+        widget: "btn_vbox",
+        check: |m| {
+            let hypervisor = m
+                .hypervisor
+                .filter(|_| !package::is_package_installed("virtualbox"));
+            hypervisor.map(|h| {
+                Fit::Hidden(format!(
+                    "Running in a {} VM, which needs guest utilities instead",
+                    h.name()
+                ))
+            })
+        },
+    },
+    Rule {
+        page: "containers_vms",
+        widget: "btn_vm_guest_tools",
+        check: |m| {
+            m.hypervisor.map(|h| {
+                Fit::Note(format!(
+                    "Running in a {} VM: installs its guest utilities",
+                    h.name()
+                ))
+            })
+        },
+    },
+    Rule {
+        page: "power_management",
+        widget: "btn_power_tlp",
+        check: |m| {
+            // Keep it switchable where it was set up anyway
+            (!m.laptop && !PowerDaemon::Tlp.is_installed())
+                .then(|| Fit::Hidden("TLP is for laptops".to_string()))
+        },
+    },
+];
+
+/// Widgets on `page` that change on `machine`, with how.
+pub fn for_page(page: &str, machine: &Machine) -> Vec<(&'static str, Fit)> {
+    RULES
+        .iter()
+        .filter(|rule| rule.page == page)
+        .filter_map(|rule| Some((rule.widget, (rule.check)(machine)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_rules_for_the_machine() {
+        let laptop = Machine {
+            gpus: vec![GpuVendor::Amd],
+            camera: true,
+            hypervisor: None,
+            laptop: true,
+        };
+        assert!(for_page("biometrics", &laptop).is_empty());
+        assert!(for_page("containers_vms", &laptop).is_empty());
+
+        let vm = Machine {
+            hypervisor: Some(Hypervisor::Kvm),
+            ..Machine::default()
+        };
+        assert!(matches!(
+            for_page("biometrics", &vm).as_slice(),
+            [("frame_howdy", Fit::Hidden(_))]
+        ));
+        assert!(matches!(
+            for_page("gaming_tools", &vm).as_slice(),
+            [("btn_lact_oc", Fit::Hidden(_))]
+        ));
+        assert!(matches!(
+            for_page("containers_vms", &vm).as_slice(),
+            [
+                ("btn_vbox", Fit::Hidden(_)),
+                ("btn_vm_guest_tools", Fit::Note(_))
+            ]
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+//! Prefilled GitHub issue reports for errors.
+//!
+//! The issue body carries the toolkit version, the distribution, the
+//! failing action and the most recent log lines, so reports arrive with
+//! enough context to act on.
+
+use crate::config;
+
+/// Log lines included in a report.
+pub const LOG_LINES: usize = 30;
+
+/// Longest issue URL to open. Browsers and GitHub reject much longer ones,
+/// so the oldest log lines are dropped to fit.
+const MAX_URL_LEN: usize = 8000;
+
+/// Distribution name from `/etc/os-release`.
+pub fn distro() -> String {
+    std::fs::read_to_string("/etc/os-release")
+        .ok()
+        .and_then(|contents| pretty_name(&contents))
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+fn pretty_name(os_release: &str) -> Option<String> {
+    os_release.lines().find_map(|line| {
+        let value = line.strip_prefix("PRETTY_NAME=")?;
+        Some(value.trim_matches('"').to_string())
+    })
+}
+
+/// URL of a new issue on the toolkit repository describing `message`.
+pub fn issue_url(message: &str, action: Option<&str>, logs: &[String]) -> String {
+    let repo = config::links::TOOLKIT_REPO.trim_end_matches(".git");
+    let summary: String = message
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .take(80)
+        .collect();
+    let title = match action {
+        Some(action) => format!("{}: {}", action, summary),
+        None => summary,
+    };
+
+    let distro = distro();
+    let mut logs = logs;
+    loop {
+        let body = issue_body(message, action, &distro, logs);
+        let url = format!(
+            "{}/issues/new?title={}&body={}",
+            repo,
+            encode(&title),
+            encode(&body)
+        );
+        if url.len() <= MAX_URL_LEN || logs.is_empty() {
+            return url;
+        }
+        logs = &logs[1..];
+    }
+}
+
+fn issue_body(message: &str, action: Option<&str>, distro: &str, logs: &[String]) -> String {
+    format!(
+        "**Error**\n\n```\n{}\n```\n\n\
+         **Environment**\n\n\
+         - Toolkit version: {}\n\
+         - Distribution: {}\n\
+         - Action: {}\n\n\
+         **Recent log**\n\n```\n{}\n```\n\n\
+         **What were you doing when it happened?**\n\n",
+        message,
+        config::app_info::VERSION,
+        distro,
+        action.unwrap_or("none"),
+        logs.join("\n")
+    )
+}
+
+/// Percent-encode everything but unreserved characters.
+fn encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pretty_name() {
+        let os_release = "NAME=\"Arch Linux\"\nPRETTY_NAME=\"XeroLinux\"\nID=arch\n";
+        assert_eq!(pretty_name(os_release).as_deref(), Some("XeroLinux"));
+        assert_eq!(pretty_name("ID=arch\n"), None);
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode("a b/c"), "a%20b%2Fc");
+        assert_eq!(encode("ü"), "%C3%BC");
+    }
+
+    #[test]
+    fn test_issue_url_drops_old_log_lines() {
+        let logs: Vec<String> = (0..500)
+            .map(|i| format!("line {} {}", i, "x".repeat(40)))
+            .collect();
+        let url = issue_url("Install failed", Some("install_steam"), &logs);
+        assert!(url.len() <= MAX_URL_LEN);
+        assert!(url.contains("title=install_steam%3A%20Install%20failed"));
+        assert!(url.contains("line%20499"));
+        assert!(!url.contains("line%200%20"));
+    }
+}
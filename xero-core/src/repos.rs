@@ -0,0 +1,166 @@
+//! Third-party repositories the toolkit can add, and their removal.
+//!
+//! Removing one takes its sections out of `/etc/pacman.conf`, along with
+//! its keyring and mirrorlist packages and the signing keys trusted when
+//! it was added. Installed packages it provided stay behind. Those also in
+//! the other repositories can be reinstalled from there; the rest become
+//! foreign packages that no longer get updates.
+
+use crate::package;
+use crate::pacman_conf::PacmanConf;
+use log::warn;
+use std::collections::BTreeSet;
+use std::process::Command;
+
+/// A repository added from the Servicing page.
+pub struct ThirdPartyRepo {
+    pub name: &'static str,
+    /// Whether a pacman.conf section belongs to the repository.
+    is_section: fn(&str) -> bool,
+    /// Keyring and mirrorlist packages it installs.
+    pub packages: &'static [&'static str],
+    /// Signing keys locally trusted for it.
+    pub keys: &'static [&'static str],
+}
+
+pub const CACHYOS: ThirdPartyRepo = ThirdPartyRepo {
+    name: "CachyOS",
+    // cachyos, cachyos-v3, cachyos-core-znver4, cachyos-testing, ...
+    is_section: |section| section == "cachyos" || section.starts_with("cachyos-"),
+    packages: &[
+        "cachyos-keyring",
+        "cachyos-mirrorlist",
+        "cachyos-v3-mirrorlist",
+        "cachyos-v4-mirrorlist",
+    ],
+    keys: &["F3B607488DB35A47"],
+};
+
+pub const CHAOTIC_AUR: ThirdPartyRepo = ThirdPartyRepo {
+    name: "Chaotic-AUR",
+    is_section: |section| section == "chaotic-aur",
+    packages: &["chaotic-keyring", "chaotic-mirrorlist"],
+    keys: &["3056513887B78AEB"],
+};
+
+pub const XEROLINUX: ThirdPartyRepo = ThirdPartyRepo {
+    name: "XeroLinux",
+    is_section: |section| section == "xerolinux",
+    packages: &[],
+    keys: &[],
+};
+
+/// Installed packages a repository provides.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Origins {
+    /// Also in the other repositories, so they can be reinstalled from
+    /// there.
+    pub elsewhere: Vec<String>,
+    /// Only in this repository.
+    pub foreign: Vec<String>,
+}
+
+impl Origins {
+    pub fn is_empty(&self) -> bool {
+        self.elsewhere.is_empty() && self.foreign.is_empty()
+    }
+}
+
+impl ThirdPartyRepo {
+    /// The repository's sections in `conf`.
+    pub fn sections(&self, conf: &PacmanConf) -> Vec<String> {
+        conf.sections()
+            .into_iter()
+            .filter(|s| (self.is_section)(s))
+            .map(String::from)
+            .collect()
+    }
+
+    /// Whether `/etc/pacman.conf` has any of the repository's sections.
+    pub fn is_configured(&self) -> bool {
+        PacmanConf::read().is_ok_and(|conf| !self.sections(&conf).is_empty())
+    }
+
+    /// Keyring and mirrorlist packages that are installed.
+    pub fn installed_packages(&self) -> Vec<&'static str> {
+        let installed = package::installed_packages();
+        self.packages
+            .iter()
+            .copied()
+            .filter(|p| installed.contains(*p))
+            .collect()
+    }
+
+    /// Installed packages from the repository's `sections`. Blocks on
+    /// pacman.
+    pub fn origins(&self, sections: &[String]) -> Origins {
+        let output = match Command::new("pacman")
+            .arg("-Sl")
+            .env("LC_ALL", "C")
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            Ok(_) | Err(_) => {
+                warn!("Failed to list the sync databases");
+                return Origins::default();
+            }
+        };
+        origins(
+            &String::from_utf8_lossy(&output.stdout),
+            sections,
+            self.packages,
+        )
+    }
+}
+
+/// Installed packages listed for the `removed` sections in `pacman -Sl`
+/// output, leaving out the repository's own `skip` packages.
+fn origins(list: &str, removed: &[String], skip: &[&str]) -> Origins {
+    let mut installed = BTreeSet::new();
+    let mut elsewhere = BTreeSet::new();
+    for line in list.lines() {
+        let mut words = line.split_whitespace();
+        let (Some(repo), Some(name)) = (words.next(), words.next()) else {
+            continue;
+        };
+        if !removed.iter().any(|r| r == repo) {
+            elsewhere.insert(name);
+        } else if line.contains("[installed") && !skip.contains(&name) {
+            installed.insert(name);
+        }
+    }
+
+    let (elsewhere, foreign) = installed
+        .into_iter()
+        .map(String::from)
+        .partition(|name| elsewhere.contains(name.as_str()));
+    Origins { elsewhere, foreign }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorts_installed_packages_by_where_else_they_are() {
+        let list = "\
+core pacman 7.0.0.r6-2
+cachyos-v3 pacman 7.0.0.r7-1 [installed]
+cachyos-v3 cachyos-mirrorlist 22-1 [installed]
+cachyos-v3 linux-cachyos 6.17.2-1 [installed: 6.17.1-1]
+cachyos-v3 paru 2.1.0-1
+extra mesa 1:25.2.4-1 [installed]
+";
+        let removed = vec!["cachyos-v3".to_string()];
+        let origins = origins(list, &removed, CACHYOS.packages);
+        assert_eq!(origins.elsewhere, ["pacman"]);
+        assert_eq!(origins.foreign, ["linux-cachyos"]);
+
+        let conf = PacmanConf::parse(
+            "[options]\n[core]\n[cachyos-v3]\n[cachyos-core-v3]\n[chaotic-aur]\n",
+        );
+        assert_eq!(CACHYOS.sections(&conf), ["cachyos-v3", "cachyos-core-v3"]);
+        assert_eq!(CHAOTIC_AUR.sections(&conf), ["chaotic-aur"]);
+        assert!(XEROLINUX.sections(&conf).is_empty());
+    }
+}
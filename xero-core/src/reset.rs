@@ -0,0 +1,270 @@
+//! Config reset from `/etc/skel`.
+//!
+//! The reset copies the default configs from `/etc/skel` over the user's.
+//! It can be limited to Plasma, GTK apps or the shell. Entries in
+//! `.config` and `.local/share` are picked one by one, anything else at the
+//! top of `/etc/skel` is one entry. The files a reset would overwrite are
+//! listed before it runs and backed up first.
+
+use crate::i18n::n_;
+use crate::task_runner::{Command, CommandSequence, Restart};
+use std::path::{Path, PathBuf};
+
+pub const SKEL: &str = "/etc/skel";
+
+/// Folders whose children are reset one by one.
+const NESTED: &[&str] = &[".config", ".local/share"];
+
+/// Name prefixes of Plasma, KWin and KDE app settings.
+const PLASMA: &[&str] = &[
+    "plasma",
+    "kde",
+    "kwin",
+    "kglobalshortcuts",
+    "khotkeys",
+    "ksplash",
+    "kscreenlocker",
+    "krunner",
+    "kactivitymanagerd",
+    "kcminput",
+    "kxkb",
+    "konsole",
+    "dolphin",
+    "Kvantum",
+    "latte",
+    "aurorae",
+    "color-schemes",
+    "kxmlgui5",
+];
+
+const GTK: &[&str] = &["gtk-2.0", "gtk-3.0", "gtk-4.0", ".gtkrc-2.0", "xsettingsd"];
+
+const SHELL: &[&str] = &[
+    ".bashrc",
+    ".bash_profile",
+    ".bash_logout",
+    ".profile",
+    ".zshrc",
+    ".zprofile",
+    ".p10k.zsh",
+    "fish",
+    "starship.toml",
+    "oh-my-posh",
+];
+
+/// Part of the configs a reset covers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scope {
+    Plasma,
+    Gtk,
+    Shell,
+    Everything,
+}
+
+impl Scope {
+    pub const ALL: [Scope; 4] = [Scope::Plasma, Scope::Gtk, Scope::Shell, Scope::Everything];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Scope::Plasma => "plasma",
+            Scope::Gtk => "gtk",
+            Scope::Shell => "shell",
+            Scope::Everything => "everything",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.id() == id)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scope::Plasma => n_("Plasma Only"),
+            Scope::Gtk => n_("GTK Apps"),
+            Scope::Shell => n_("Shell"),
+            Scope::Everything => n_("Everything"),
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Scope::Plasma => n_("Plasma, KWin, Konsole and Dolphin settings"),
+            Scope::Gtk => n_("GTK 2, 3 and 4 themes and settings"),
+            Scope::Shell => n_("bash, zsh and fish configs and the prompt"),
+            Scope::Everything => n_("Every default config in /etc/skel"),
+        }
+    }
+
+    /// Whether the scope covers `entry`, a path relative to `/etc/skel`.
+    fn covers(&self, entry: &str) -> bool {
+        let name = entry.rsplit('/').next().unwrap_or(entry);
+        match self {
+            Scope::Plasma => PLASMA.iter().any(|p| name.starts_with(p)),
+            Scope::Gtk => GTK.contains(&name),
+            Scope::Shell => SHELL.contains(&name),
+            Scope::Everything => true,
+        }
+    }
+}
+
+/// What a reset copies and what it overwrites.
+#[derive(Clone, Debug, Default)]
+pub struct Plan {
+    /// Entries copied from `/etc/skel`, relative to it.
+    pub entries: Vec<String>,
+    /// Files in the home folder the copy replaces, relative to it.
+    pub overwritten: Vec<String>,
+}
+
+fn home() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("/root"))
+}
+
+fn children(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Entries of `skel`, relative to it.
+fn entries(skel: &Path) -> Vec<String> {
+    let mut entries = Vec::new();
+    for name in children(skel) {
+        if name == ".local" {
+            let nested = children(&skel.join(".local"))
+                .into_iter()
+                .map(|n| format!(".local/{}", n));
+            entries.extend(nested.filter(|e| !NESTED.contains(&e.as_str())));
+        } else if !NESTED.contains(&name.as_str()) {
+            entries.push(name);
+        }
+    }
+    for dir in NESTED {
+        let nested = children(&skel.join(dir))
+            .into_iter()
+            .map(|n| format!("{}/{}", dir, n));
+        entries.extend(nested);
+    }
+    entries
+}
+
+/// The `entries` covered by any of `scopes`.
+fn select(entries: Vec<String>, scopes: &[Scope]) -> Vec<String> {
+    entries
+        .into_iter()
+        .filter(|e| scopes.iter().any(|s| s.covers(e)))
+        .collect()
+}
+
+/// Files under `entry` in `skel` that also exist in `home`.
+fn overwritten(skel: &Path, home: &Path, entry: &str, out: &mut Vec<String>) {
+    let source = skel.join(entry);
+    if source.is_dir() && !source.is_symlink() {
+        for name in children(&source) {
+            overwritten(skel, home, &format!("{}/{}", entry, name), out);
+        }
+    } else if home.join(entry).symlink_metadata().is_ok() {
+        out.push(entry.to_string());
+    }
+}
+
+/// Plan a reset of `scopes`. Blocks on reading `/etc/skel` and the home
+/// folder.
+pub fn plan(scopes: &[Scope]) -> Plan {
+    let skel = Path::new(SKEL);
+    let home = home();
+    let entries = select(entries(skel), scopes);
+    let mut files = Vec::new();
+    for entry in &entries {
+        overwritten(skel, &home, entry, &mut files);
+    }
+    Plan {
+        entries,
+        overwritten: files,
+    }
+}
+
+/// Back up what `plan` overwrites to `~/.reset-backup-<date>`, then copy
+/// its entries from `/etc/skel`. Changes show up after logging in again.
+pub fn commands(plan: &Plan) -> CommandSequence {
+    let mut commands = CommandSequence::new();
+    if !plan.overwritten.is_empty() {
+        let mut args = vec![
+            "-c",
+            "set -e; backup=\"$HOME/.reset-backup-$(date +%Y.%m.%d-%H.%M.%S)\"; \
+             mkdir -p \"$backup\"; cd \"$HOME\"; cp -a --parents -- \"$@\" \"$backup\"; \
+             echo \"Backed up to $backup\"",
+            "sh",
+        ];
+        args.extend(plan.overwritten.iter().map(String::as_str));
+        commands = commands.then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&args)
+                .description("Backing up configuration...")
+                .build(),
+        );
+    }
+
+    let mut args = vec![
+        "-c",
+        "set -e; cd /etc/skel; cp -Rf --parents -- \"$@\" \"$HOME\"",
+        "sh",
+    ];
+    args.extend(plan.entries.iter().map(String::as_str));
+    commands
+        .then(
+            Command::builder()
+                .normal()
+                .program("sh")
+                .args(&args)
+                .description("Restoring default configuration...")
+                .requires(Restart::Relogin)
+                .build(),
+        )
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selects_entries_by_scope() {
+        let entries: Vec<String> = [
+            ".bashrc",
+            ".zshrc",
+            ".config/kdeglobals",
+            ".config/plasma-org.kde.plasma.desktop-appletsrc",
+            ".config/gtk-3.0",
+            ".config/fish",
+            ".config/kitty",
+            ".local/share/konsole",
+        ]
+        .iter()
+        .map(|e| e.to_string())
+        .collect();
+
+        assert_eq!(
+            select(entries.clone(), &[Scope::Plasma]),
+            [
+                ".config/kdeglobals",
+                ".config/plasma-org.kde.plasma.desktop-appletsrc",
+                ".local/share/konsole"
+            ]
+        );
+        assert_eq!(
+            select(entries.clone(), &[Scope::Gtk, Scope::Shell]),
+            [".bashrc", ".zshrc", ".config/gtk-3.0", ".config/fish"]
+        );
+        assert_eq!(select(entries.clone(), &[Scope::Everything]), entries);
+    }
+}
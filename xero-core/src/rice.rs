@@ -0,0 +1,202 @@
+//! Plasma global themes and saved rice profiles.
+//!
+//! A profile is a copy of the Plasma, KWin and Konsole settings under
+//! `~/.local/share/xero-toolkit/rice-profiles/<name>/`, taken before a
+//! theme is applied so the previous look can be restored later.
+
+use crate::i18n::n_;
+use anyhow::{bail, Context, Result};
+use log::info;
+use std::path::{Path, PathBuf};
+
+/// How a global theme is applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Apply {
+    /// Look-and-feel package id for `plasma-apply-lookandfeel`.
+    LookAndFeel(&'static str),
+    /// Interactive installer script, run in a terminal.
+    Script(&'static str),
+}
+
+/// A curated global theme.
+#[derive(Clone, Copy, Debug)]
+pub struct GlobalTheme {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub apply: Apply,
+}
+
+pub const THEMES: &[GlobalTheme] = &[
+    GlobalTheme {
+        id: "cyberxero",
+        name: "CyberXero",
+        description: n_("Dynamic tiling rice with custom KWin effects"),
+        apply: Apply::Script("/usr/local/bin/cyberxero-theme"),
+    },
+    GlobalTheme {
+        id: "layan",
+        name: "Layan",
+        description: n_("Flat theme with translucent panels"),
+        apply: Apply::LookAndFeel("com.github.vinceliuice.Layan"),
+    },
+    GlobalTheme {
+        id: "breeze",
+        name: "Breeze",
+        description: n_("Stock Plasma look"),
+        apply: Apply::LookAndFeel("org.kde.breeze.desktop"),
+    },
+    GlobalTheme {
+        id: "breeze-dark",
+        name: "Breeze Dark",
+        description: n_("Stock Plasma look, dark variant"),
+        apply: Apply::LookAndFeel("org.kde.breezedark.desktop"),
+    },
+];
+
+/// Files under `~/.config` saved in a profile.
+const CONFIG_FILES: &[&str] = &[
+    "kdeglobals",
+    "plasmarc",
+    "plasmashellrc",
+    "plasma-org.kde.plasma.desktop-appletsrc",
+    "kwinrc",
+    "kwinrulesrc",
+    "kglobalshortcutsrc",
+    "ksplashrc",
+    "kscreenlockerrc",
+    "konsolerc",
+    "kdedefaults",
+];
+
+/// Folders under `~/.local/share` saved in a profile: Konsole profiles and
+/// color schemes.
+const DATA_DIRS: &[&str] = &["konsole", "color-schemes"];
+
+fn home() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("/root"))
+}
+
+impl GlobalTheme {
+    /// Whether the theme can be applied. Layan only ships its
+    /// look-and-feel once installed from the Customization page.
+    pub fn available(&self) -> bool {
+        match self.apply {
+            Apply::Script(path) => Path::new(path).exists(),
+            Apply::LookAndFeel(id) => [
+                PathBuf::from("/usr/share/plasma/look-and-feel"),
+                home().join(".local/share/plasma/look-and-feel"),
+            ]
+            .iter()
+            .any(|dir| dir.join(id).is_dir()),
+        }
+    }
+}
+
+/// Where profiles are kept.
+pub fn profiles_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| home().join(".local/share"))
+        .join("xero-toolkit/rice-profiles")
+}
+
+/// Names of the saved profiles, newest first.
+pub fn list_profiles() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(profiles_dir()) else {
+        return Vec::new();
+    };
+    let mut profiles: Vec<_> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .map(|e| {
+            let modified = e.metadata().and_then(|m| m.modified()).ok();
+            (modified, e.file_name().to_string_lossy().into_owned())
+        })
+        .collect();
+    profiles.sort_by(|a, b| b.cmp(a));
+    profiles.into_iter().map(|(_, name)| name).collect()
+}
+
+/// Check a profile name, which becomes a folder name.
+pub fn validate_name(name: &str) -> Result<()> {
+    let name = name.trim();
+    if name.is_empty() {
+        bail!("Enter a name for the profile");
+    }
+    if name.starts_with('.') || name.contains('/') {
+        bail!("'{}' can't be used as a profile name", name);
+    }
+    Ok(())
+}
+
+fn copy_recursive(from: &Path, to: &Path) -> std::io::Result<()> {
+    if from.is_dir() {
+        std::fs::create_dir_all(to)?;
+        for entry in std::fs::read_dir(from)?.flatten() {
+            copy_recursive(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else {
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::copy(from, to)?;
+    }
+    Ok(())
+}
+
+/// Copy the current settings into the profile `name`, replacing it if it
+/// exists.
+pub fn save_profile(name: &str) -> Result<PathBuf> {
+    validate_name(name)?;
+    let dir = profiles_dir().join(name.trim());
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)
+            .with_context(|| format!("Failed to replace {}", dir.display()))?;
+    }
+
+    let home = home();
+    let sources = CONFIG_FILES
+        .iter()
+        .map(|f| (home.join(".config").join(f), dir.join("config").join(f)))
+        .chain(
+            DATA_DIRS
+                .iter()
+                .map(|d| (home.join(".local/share").join(d), dir.join("share").join(d))),
+        );
+    for (from, to) in sources.filter(|(from, _)| from.exists()) {
+        copy_recursive(&from, &to).with_context(|| format!("Failed to save {}", from.display()))?;
+    }
+
+    info!("Saved rice profile {}", dir.display());
+    Ok(dir)
+}
+
+/// Delete the profile `name`.
+pub fn delete_profile(name: &str) -> Result<()> {
+    validate_name(name)?;
+    let dir = profiles_dir().join(name);
+    std::fs::remove_dir_all(&dir).with_context(|| format!("Failed to delete {}", dir.display()))
+}
+
+/// Script restoring the profile folder `$1`. Plasma writes its panel
+/// layout on exit, so it's stopped before the files are copied back.
+pub const RESTORE_SCRIPT: &str = r#"set -e
+kquitapp6 plasmashell 2>/dev/null || true
+[ -d "$1/config" ] && cp -rf "$1/config/." "$HOME/.config/"
+[ -d "$1/share" ] && cp -rf "$1/share/." "$HOME/.local/share/"
+qdbus6 org.kde.KWin /KWin reconfigure 2>/dev/null || true
+setsid plasmashell >/dev/null 2>&1 &
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("Before Layan").is_ok());
+        assert!(validate_name("  ").is_err());
+        assert!(validate_name("../kwinrc").is_err());
+        assert!(validate_name(".hidden").is_err());
+    }
+}
@@ -0,0 +1,369 @@
+//! Toolkit self-update: release channels and update source resolution.
+//!
+//! The stable channel installs signed prebuilt binaries from GitHub releases.
+//! The git channel tracks the latest commit and always builds from source.
+//! Stable falls back to a source build of the release tag when no usable
+//! prebuilt archive exists (unsupported arch, missing asset or signing key).
+
+use crate::config;
+use log::{info, warn};
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Install prefix of the toolkit.
+pub const INSTALL_DIR: &str = "/opt/xero-toolkit";
+
+/// File recording the commit of the last git channel install.
+const COMMIT_FILE: &str = "/opt/xero-toolkit/.commit";
+
+/// File recording the tag of the last stable channel install.
+const RELEASE_FILE: &str = "/opt/xero-toolkit/.release";
+
+/// Public key used to verify release checksums, shipped with the toolkit.
+const SIGNING_KEY: &str = "/opt/xero-toolkit/sources/keys/release-signing.gpg";
+
+/// Copy of the install kept from before the last update, for rollback.
+pub const PREVIOUS_DIR: &str = "/opt/xero-toolkit/previous";
+
+/// Everything an update replaces, relative to [`INSTALL_DIR`].
+const UPDATED_FILES: &[&str] = &[
+    "xero-toolkit",
+    "xero-authd",
+    "xero-auth",
+    "sources",
+    ".commit",
+    ".release",
+];
+
+/// Architecture prebuilt archives are published for.
+const PREBUILT_ARCH: &str = "x86_64";
+
+/// Update channel the toolkit follows.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    Stable,
+    Git,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 2] = [Channel::Stable, Channel::Git];
+
+    /// Identifier stored in the user's config.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Channel::Stable => "stable",
+            Channel::Git => "git",
+        }
+    }
+
+    /// Human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Channel::Stable => "Stable",
+            Channel::Git => "Git (latest commit)",
+        }
+    }
+
+    fn from_id(id: &str) -> Option<Channel> {
+        Channel::ALL.into_iter().find(|c| c.id() == id.trim())
+    }
+}
+
+fn channel_file() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("~/.config"))
+        .join("xero-toolkit")
+        .join("update-channel")
+}
+
+/// The channel selected by the user, stable by default.
+pub fn channel() -> Channel {
+    std::fs::read_to_string(channel_file())
+        .ok()
+        .and_then(|s| Channel::from_id(&s))
+        .unwrap_or(Channel::Stable)
+}
+
+/// Persist the selected channel.
+pub fn set_channel(channel: Channel) -> std::io::Result<()> {
+    let path = channel_file();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, channel.id())
+}
+
+/// Where an update comes from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UpdateSource {
+    /// Verified prebuilt archive of a release.
+    Prebuilt(Release),
+    /// Source build of a git ref (commit hash or tag).
+    Source { git_ref: String },
+}
+
+/// Download locations of a published release.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    pub tag: String,
+}
+
+impl Release {
+    fn asset_base(&self) -> String {
+        let repo = config::links::TOOLKIT_REPO.trim_end_matches(".git");
+        format!("{}/releases/download/{}", repo, self.tag)
+    }
+
+    /// File name of the prebuilt archive.
+    pub fn archive_name(&self) -> String {
+        format!("xero-toolkit-{}-{}.tar.gz", self.tag, PREBUILT_ARCH)
+    }
+
+    pub fn archive_url(&self) -> String {
+        format!("{}/{}", self.asset_base(), self.archive_name())
+    }
+
+    /// Checksum list covering every asset of the release.
+    pub fn checksums_url(&self) -> String {
+        format!("{}/SHA256SUMS", self.asset_base())
+    }
+
+    /// Detached signature of the checksum list.
+    pub fn signature_url(&self) -> String {
+        format!("{}/SHA256SUMS.sig", self.asset_base())
+    }
+}
+
+/// Result of an update check.
+#[derive(Clone, Debug)]
+pub struct UpdateCheck {
+    pub channel: Channel,
+    /// Installed version (commit hash or tag), if known.
+    pub installed: Option<String>,
+    /// Latest version on the channel (commit hash or tag).
+    pub latest: String,
+    pub source: UpdateSource,
+}
+
+impl UpdateCheck {
+    pub fn is_up_to_date(&self) -> bool {
+        self.installed.as_deref() == Some(self.latest.as_str())
+    }
+}
+
+/// Check the channel for updates. Blocks on the network; returns `None`
+/// if the remote could not be reached.
+pub fn check(channel: Channel) -> Option<UpdateCheck> {
+    match channel {
+        Channel::Git => {
+            let commit = remote_commit()?;
+            Some(UpdateCheck {
+                channel,
+                installed: read_trimmed(COMMIT_FILE),
+                latest: commit.clone(),
+                source: UpdateSource::Source { git_ref: commit },
+            })
+        }
+        Channel::Stable => {
+            let refs = git_ls_remote(&["--tags", "--refs", config::links::TOOLKIT_REPO])?;
+            let Some(tag) = latest_tag(&refs) else {
+                info!("No releases published yet, following git instead");
+                return check(Channel::Git);
+            };
+            let release = Release { tag: tag.clone() };
+            let source = if prebuilt_usable(&release) {
+                UpdateSource::Prebuilt(release)
+            } else {
+                UpdateSource::Source {
+                    git_ref: tag.clone(),
+                }
+            };
+            Some(UpdateCheck {
+                channel,
+                installed: installed_release(),
+                latest: tag,
+                source,
+            })
+        }
+    }
+}
+
+/// Command that records the installed version for the channel.
+pub fn record_version_command(check: &UpdateCheck) -> String {
+    let file = match check.channel {
+        Channel::Git => COMMIT_FILE,
+        Channel::Stable => RELEASE_FILE,
+    };
+    format!("echo '{}' > {}", check.latest, file)
+}
+
+/// Script that snapshots the current install into [`PREVIOUS_DIR`],
+/// replacing any older snapshot. Run before installing an update.
+pub fn backup_script() -> String {
+    let mut script = format!(
+        "set -e; rm -rf {prev} && mkdir -p {prev}",
+        prev = PREVIOUS_DIR
+    );
+    for file in UPDATED_FILES {
+        script.push_str(&format!(
+            "; if [ -e {dir}/{file} ]; then cp -a {dir}/{file} {prev}/; fi",
+            dir = INSTALL_DIR,
+            file = file,
+            prev = PREVIOUS_DIR
+        ));
+    }
+    script
+}
+
+/// Script that restores the snapshot in [`PREVIOUS_DIR`] and removes it.
+/// Files the snapshot doesn't have (e.g. a version marker written by a
+/// different channel) are removed so update checks see the old version.
+pub fn rollback_script() -> String {
+    let mut script = format!("set -e; [ -x {}/xero-toolkit ]", PREVIOUS_DIR);
+    for file in UPDATED_FILES {
+        script.push_str(&format!(
+            "; rm -rf {dir}/{file}; if [ -e {prev}/{file} ]; then cp -a {prev}/{file} {dir}/; fi",
+            dir = INSTALL_DIR,
+            file = file,
+            prev = PREVIOUS_DIR
+        ));
+    }
+    script.push_str(&format!("; rm -rf {}", PREVIOUS_DIR));
+    script
+}
+
+/// Whether a rollback snapshot exists.
+pub fn has_previous() -> bool {
+    std::path::Path::new(PREVIOUS_DIR)
+        .join("xero-toolkit")
+        .exists()
+}
+
+/// Version recorded in the rollback snapshot, if any.
+pub fn previous_version() -> Option<String> {
+    read_trimmed(&format!("{}/.release", PREVIOUS_DIR))
+        .or_else(|| read_trimmed(&format!("{}/.commit", PREVIOUS_DIR)))
+}
+
+fn read_trimmed(path: &str) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// Installed release tag, falling back to the running binary's version.
+fn installed_release() -> Option<String> {
+    read_trimmed(RELEASE_FILE).or_else(|| Some(format!("v{}", env!("CARGO_PKG_VERSION"))))
+}
+
+/// Latest commit hash on the toolkit repository.
+fn remote_commit() -> Option<String> {
+    git_ls_remote(&[config::links::TOOLKIT_REPO, "HEAD"])?
+        .split_whitespace()
+        .next()
+        .map(|s| s.to_string())
+}
+
+fn git_ls_remote(args: &[&str]) -> Option<String> {
+    Command::new("git")
+        .arg("ls-remote")
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+}
+
+fn parse_version(tag: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = tag.strip_prefix('v')?.split('.');
+    let version = (
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+        parts.next()?.parse().ok()?,
+    );
+    parts.next().is_none().then_some(version)
+}
+
+/// Pick the highest release tag from `git ls-remote --tags` output.
+/// Pre-release tags (anything not plain `vX.Y.Z`) are ignored.
+fn latest_tag(refs: &str) -> Option<String> {
+    refs.lines()
+        .filter_map(|l| l.split_whitespace().nth(1))
+        .filter_map(|r| r.strip_prefix("refs/tags/"))
+        .filter_map(|t| parse_version(t).map(|v| (v, t)))
+        .max_by_key(|(v, _)| *v)
+        .map(|(_, t)| t.to_string())
+}
+
+/// Whether the prebuilt archive of `release` can be used on this machine.
+fn prebuilt_usable(release: &Release) -> bool {
+    if std::env::consts::ARCH != PREBUILT_ARCH {
+        info!("No prebuilt binaries for {}", std::env::consts::ARCH);
+        return false;
+    }
+    if !std::path::Path::new(SIGNING_KEY).exists() {
+        warn!("Release signing key missing, prebuilt binaries can't be verified");
+        return false;
+    }
+
+    let available = Command::new("curl")
+        .args(["-fsIL", "--max-time", "10", &release.archive_url()])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false);
+    if !available {
+        info!("No prebuilt archive published for {}", release.tag);
+    }
+    available
+}
+
+/// Files to download into the update directory for a prebuilt install,
+/// as `(url, file name)` pairs.
+pub fn prebuilt_downloads(release: &Release) -> [(String, String); 3] {
+    [
+        (release.archive_url(), release.archive_name()),
+        (release.checksums_url(), "SHA256SUMS".to_string()),
+        (release.signature_url(), "SHA256SUMS.sig".to_string()),
+    ]
+}
+
+/// Shell script that verifies the files from [`prebuilt_downloads`] in
+/// `dir`, then extracts the archive. Fails (and so aborts the sequence) if
+/// the signature or checksum doesn't match.
+pub fn verify_prebuilt_script(release: &Release, dir: &str) -> String {
+    format!(
+        "set -e; cd {dir} && \
+         gpgv --keyring {key} SHA256SUMS.sig SHA256SUMS && \
+         sha256sum --check --ignore-missing --strict SHA256SUMS && \
+         grep -q ' \\*\\?{archive}$' SHA256SUMS && \
+         tar -xzf {archive}",
+        dir = dir,
+        archive = release.archive_name(),
+        key = SIGNING_KEY,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_highest_release_tag() {
+        let refs = "a\trefs/tags/v0.9.0\n\
+                    b\trefs/tags/v0.10.2\n\
+                    c\trefs/tags/v0.10.10\n\
+                    d\trefs/tags/v1.0.0-rc1\n\
+                    e\trefs/tags/nightly\n";
+        assert_eq!(latest_tag(refs).as_deref(), Some("v0.10.10"));
+        assert_eq!(latest_tag(""), None);
+    }
+
+    #[test]
+    fn parses_only_plain_versions() {
+        assert_eq!(parse_version("v1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version("1.2.3"), None);
+        assert_eq!(parse_version("v1.2"), None);
+        assert_eq!(parse_version("v1.2.3.4"), None);
+    }
+}
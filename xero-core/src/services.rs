@@ -0,0 +1,84 @@
+//! systemd service state.
+//!
+//! Pages built around a daemon show whether it's installed, enabled and
+//! running, read with a single `systemctl show`.
+
+use std::process::Command;
+
+use crate::i18n::n_;
+
+/// State of a system unit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ServiceState {
+    /// The unit file exists.
+    pub installed: bool,
+    /// Started at boot.
+    pub enabled: bool,
+    /// Running now.
+    pub active: bool,
+}
+
+impl ServiceState {
+    /// State of `unit`, e.g. `tailscaled.service`. Blocks on systemctl.
+    pub fn read(unit: &str) -> Self {
+        Command::new("systemctl")
+            .args(["show", "-p", "LoadState,UnitFileState,ActiveState", unit])
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| Self::parse(&String::from_utf8_lossy(&o.stdout)))
+            .unwrap_or_default()
+    }
+
+    /// Parse `key=value` lines from `systemctl show`.
+    fn parse(output: &str) -> Self {
+        let mut state = Self::default();
+        for line in output.lines() {
+            match line.split_once('=') {
+                Some(("LoadState", value)) => state.installed = value == "loaded",
+                Some(("UnitFileState", value)) => {
+                    state.enabled = matches!(value, "enabled" | "enabled-runtime" | "alias")
+                }
+                Some(("ActiveState", value)) => {
+                    state.active = matches!(value, "active" | "reloading" | "activating")
+                }
+                _ => {}
+            }
+        }
+        state
+    }
+
+    /// One line for a row subtitle.
+    pub fn summary(&self) -> &'static str {
+        match (self.installed, self.enabled, self.active) {
+            (false, _, _) => n_("Not installed"),
+            (_, true, true) => n_("Running, starts at boot"),
+            (_, false, true) => n_("Running, doesn't start at boot"),
+            (_, true, false) => n_("Stopped, starts at boot"),
+            (_, false, false) => n_("Stopped"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let state =
+            ServiceState::parse("LoadState=loaded\nActiveState=active\nUnitFileState=enabled\n");
+        assert_eq!(
+            state,
+            ServiceState {
+                installed: true,
+                enabled: true,
+                active: true,
+            }
+        );
+        let missing =
+            ServiceState::parse("LoadState=not-found\nActiveState=inactive\nUnitFileState=\n");
+        assert_eq!(missing, ServiceState::default());
+        assert_eq!(missing.summary(), "Not installed");
+    }
+}
@@ -0,0 +1,293 @@
+//! Samba and NFS folder sharing for the Network Tools page.
+//!
+//! The toolkit keeps its shares in files of its own, pulled into smb.conf
+//! with an `include` line and read by nfs-server from `/etc/exports.d`, so
+//! shares set up by hand are never rewritten.
+
+use crate::firewall::{PortRule, Protocol};
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Samba shares written by the toolkit.
+pub const SAMBA_SHARES: &str = "/etc/samba/xero-shares.conf";
+/// NFS exports written by the toolkit.
+pub const NFS_EXPORTS: &str = "/etc/exports.d/xero.exports";
+
+pub const SAMBA_SERVICES: [&str; 2] = ["smb.service", "nmb.service"];
+pub const NFS_SERVICE: &str = "nfs-server.service";
+
+/// SMB plus the NetBIOS name and datagram services, so the computer shows
+/// up when browsing the network from Windows.
+pub const SAMBA_PORTS: [PortRule; 3] = [
+    PortRule::tcp(139),
+    PortRule::tcp(445),
+    PortRule::range(137, 138, Protocol::Udp),
+];
+/// NFSv4 only needs its one port.
+pub const NFS_PORTS: [PortRule; 1] = [PortRule::tcp(2049)];
+
+/// Section names smb.conf gives a meaning of their own.
+const RESERVED_NAMES: [&str; 3] = ["global", "homes", "printers"];
+
+/// A folder shared over Samba.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SambaShare {
+    pub name: String,
+    pub path: String,
+    pub writable: bool,
+    /// The account allowed to connect.
+    pub user: String,
+}
+
+/// Shares in the toolkit's file, empty when there is none.
+pub fn samba_shares() -> Vec<SambaShare> {
+    std::fs::read_to_string(SAMBA_SHARES)
+        .map(|text| parse_samba(&text))
+        .unwrap_or_default()
+}
+
+fn parse_samba(text: &str) -> Vec<SambaShare> {
+    let mut shares: Vec<SambaShare> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            shares.push(SambaShare {
+                name: name.to_string(),
+                path: String::new(),
+                writable: false,
+                user: String::new(),
+            });
+            continue;
+        }
+        let (Some(share), Some((key, value))) = (shares.last_mut(), line.split_once('=')) else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "path" => share.path = value.to_string(),
+            "read only" => share.writable = value == "no",
+            "valid users" => share.user = value.to_string(),
+            _ => {}
+        }
+    }
+    shares.retain(|share| !share.path.is_empty());
+    shares
+}
+
+/// The toolkit's share file holding `shares`.
+pub fn render_samba(shares: &[SambaShare]) -> String {
+    let mut text = String::from("# Written by Xero Toolkit from the Network Tools page.\n");
+    for share in shares {
+        text.push_str(&format!(
+            "\n[{}]\n   path = {}\n   read only = {}\n   valid users = {}\n   \
+             force user = {}\n   browseable = yes\n",
+            share.name,
+            share.path,
+            if share.writable { "no" } else { "yes" },
+            share.user,
+            share.user
+        ));
+    }
+    text
+}
+
+/// Whether `name` can be used for a share: short, plain characters and not
+/// one of smb.conf's special sections.
+pub fn is_valid_share_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        && !RESERVED_NAMES.contains(&name.to_ascii_lowercase().as_str())
+}
+
+/// Share name suggested for `path`, made from its folder name.
+pub fn share_name(path: &Path) -> String {
+    let name: String = path
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c == ' ' { '_' } else { c })
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .take(32)
+        .collect();
+    if is_valid_share_name(&name) {
+        name
+    } else {
+        "Shared".to_string()
+    }
+}
+
+/// Script creating a minimal smb.conf when the package didn't ship one,
+/// and including the toolkit's shares from it.
+pub const SAMBA_SETUP_SCRIPT: &str = r#"set -e
+conf=/etc/samba/smb.conf
+shares=/etc/samba/xero-shares.conf
+if [ ! -e "$conf" ]; then
+    mkdir -p /etc/samba
+    cat > "$conf" <<'EOF'
+[global]
+   workgroup = WORKGROUP
+   server string = %h
+   server role = standalone server
+   map to guest = Never
+EOF
+fi
+touch "$shares"
+if ! grep -qxF "include = $shares" "$conf"; then
+    printf '\ninclude = %s\n' "$shares" >> "$conf"
+fi
+"#;
+
+/// Write the Samba password to a private runtime file, twice as smbpasswd
+/// wants it, so it never shows up in a command line.
+pub fn stage_password(password: &str) -> Result<PathBuf> {
+    crate::staging::stage(
+        "xero-toolkit-samba-password",
+        &format!("{}\n{}\n", password, password),
+    )
+}
+
+/// Script setting the Samba password of user `$1` from the file staged in
+/// `$2`, then deleting the file.
+pub const SAMBA_PASSWORD_SCRIPT: &str = r#"trap 'rm -f "$2"' EXIT
+smbpasswd -s -a "$1" < "$2"
+"#;
+
+/// A folder exported over NFS.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NfsExport {
+    pub path: String,
+    /// Hosts allowed to mount it, e.g. `192.168.1.0/24`.
+    pub clients: String,
+    pub writable: bool,
+}
+
+/// Exports in the toolkit's file, empty when there is none.
+pub fn nfs_exports() -> Vec<NfsExport> {
+    std::fs::read_to_string(NFS_EXPORTS)
+        .map(|text| parse_nfs(&text))
+        .unwrap_or_default()
+}
+
+fn parse_nfs(text: &str) -> Vec<NfsExport> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let (path, rest) = match line.strip_prefix('"') {
+                Some(quoted) => quoted.split_once('"')?,
+                None => line.split_once(char::is_whitespace)?,
+            };
+            let (clients, options) = rest.trim().split_once('(')?;
+            let options = options.strip_suffix(')')?;
+            Some(NfsExport {
+                path: path.to_string(),
+                clients: clients.to_string(),
+                writable: options.split(',').any(|o| o == "rw"),
+            })
+        })
+        .collect()
+}
+
+/// The toolkit's exports file holding `exports`.
+pub fn render_nfs(exports: &[NfsExport]) -> String {
+    let mut text = String::from("# Written by Xero Toolkit from the Network Tools page.\n");
+    for export in exports {
+        text.push_str(&format!(
+            "\"{}\" {}({},sync,no_subtree_check)\n",
+            export.path,
+            export.clients,
+            if export.writable { "rw" } else { "ro" }
+        ));
+    }
+    text
+}
+
+/// Addresses of the local network, e.g. `192.168.1.0/24`, for the allowed
+/// NFS clients. Blocks on `ip`.
+pub fn local_subnet() -> Option<String> {
+    let output = Command::new("ip")
+        .args(["-o", "-4", "route", "show", "scope", "link"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    parse_subnet(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_subnet(routes: &str) -> Option<String> {
+    routes
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .find(|net| net.contains('/') && !net.starts_with("169.254."))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samba_round_trip() {
+        let shares = vec![
+            SambaShare {
+                name: "Music".to_string(),
+                path: "/home/xero/My Music".to_string(),
+                writable: false,
+                user: "xero".to_string(),
+            },
+            SambaShare {
+                name: "Public".to_string(),
+                path: "/home/xero/Public".to_string(),
+                writable: true,
+                user: "xero".to_string(),
+            },
+        ];
+        assert_eq!(parse_samba(&render_samba(&shares)), shares);
+        assert_eq!(parse_samba(&render_samba(&[])), vec![]);
+    }
+
+    #[test]
+    fn test_share_names() {
+        assert_eq!(share_name(Path::new("/home/xero/My Music")), "My_Music");
+        assert_eq!(share_name(Path::new("/home/xero/Homes")), "Shared");
+        assert_eq!(share_name(Path::new("/")), "Shared");
+        assert!(!is_valid_share_name("a b"));
+        assert!(is_valid_share_name("Public"));
+    }
+
+    #[test]
+    fn test_nfs_round_trip() {
+        let exports = vec![
+            NfsExport {
+                path: "/srv/media files".to_string(),
+                clients: "192.168.1.0/24".to_string(),
+                writable: true,
+            },
+            NfsExport {
+                path: "/srv/iso".to_string(),
+                clients: "*".to_string(),
+                writable: false,
+            },
+        ];
+        assert_eq!(parse_nfs(&render_nfs(&exports)), exports);
+        assert_eq!(
+            parse_nfs("/srv/plain 10.0.0.0/8(ro)\n"),
+            vec![NfsExport {
+                path: "/srv/plain".to_string(),
+                clients: "10.0.0.0/8".to_string(),
+                writable: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parse_subnet() {
+        let routes = "169.254.0.0/16 dev wlan0 scope link metric 1000\n\
+                      192.168.1.0/24 dev wlan0 proto kernel scope link src 192.168.1.20\n";
+        assert_eq!(parse_subnet(routes).as_deref(), Some("192.168.1.0/24"));
+        assert_eq!(parse_subnet(""), None);
+    }
+}
@@ -0,0 +1,517 @@
+//! Shell setups offered by the Shell Setup wizard.
+//!
+//! A setup is a framework, a prompt and a set of plugins. Its config is
+//! generated here and written over `~/.zshrc`, or to a `conf.d` file for
+//! fish. The first setup backs up the original `.zshrc` and login shell,
+//! which a revert puts back. Running a setup again reuses what's installed,
+//! and the plugins can be updated on their own.
+
+use crate::i18n::n_;
+use crate::verify;
+use anyhow::Result;
+use std::path::PathBuf;
+
+/// Pin of the Oh My Zsh installer.
+const OH_MY_ZSH_PIN: &str = "ohmyzsh-install";
+
+/// How the shell and its plugins are managed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Framework {
+    OhMyZsh,
+    Zinit,
+    Fish,
+    /// Plain zsh with the Starship prompt, no plugin manager.
+    StarshipOnly,
+}
+
+impl Framework {
+    pub const ALL: [Framework; 4] = [
+        Framework::OhMyZsh,
+        Framework::Zinit,
+        Framework::Fish,
+        Framework::StarshipOnly,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Framework::OhMyZsh => "oh-my-zsh",
+            Framework::Zinit => "zinit",
+            Framework::Fish => "fish",
+            Framework::StarshipOnly => "starship",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.id() == id)
+    }
+
+    /// Human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Framework::OhMyZsh => "Oh My Zsh",
+            Framework::Zinit => "Zinit",
+            Framework::Fish => "Fish + Fisher",
+            Framework::StarshipOnly => "Starship Only",
+        }
+    }
+
+    /// Whether the framework can be set up. The Oh My Zsh installer is
+    /// fetched from upstream, so it needs a pin.
+    pub fn is_available(&self) -> bool {
+        match self {
+            Framework::OhMyZsh => verify::get(OH_MY_ZSH_PIN).is_ok(),
+            _ => true,
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Framework::OhMyZsh => n_("ZSH with the popular framework and its bundled plugins"),
+            Framework::Zinit => n_("ZSH with a fast plugin manager that fetches plugins itself"),
+            Framework::Fish => n_("Friendly shell with autosuggestions built in"),
+            Framework::StarshipOnly => n_("Plain ZSH with the Starship prompt, nothing else"),
+        }
+    }
+
+    /// Login shell the setup switches to.
+    pub fn shell(&self) -> &'static str {
+        match self {
+            Framework::Fish => "/usr/bin/fish",
+            _ => "/bin/zsh",
+        }
+    }
+
+    /// Prompts that work with the framework. Powerlevel10k is zsh only.
+    pub fn prompts(&self) -> Vec<Prompt> {
+        match self {
+            Framework::OhMyZsh | Framework::Zinit => Prompt::ALL.to_vec(),
+            Framework::Fish => vec![Prompt::Starship, Prompt::OhMyPosh],
+            Framework::StarshipOnly => vec![Prompt::Starship],
+        }
+    }
+
+    pub fn plugins(&self) -> &'static [Plugin] {
+        match self {
+            Framework::Fish => FISH_PLUGINS,
+            _ => ZSH_PLUGINS,
+        }
+    }
+
+    /// Where the generated config goes.
+    pub fn config_path(&self) -> PathBuf {
+        match self {
+            Framework::Fish => home().join(".config/fish/conf.d/xero-shell.fish"),
+            _ => home().join(".zshrc"),
+        }
+    }
+}
+
+/// The prompt theme.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prompt {
+    Powerlevel10k,
+    OhMyPosh,
+    Starship,
+}
+
+impl Prompt {
+    pub const ALL: [Prompt; 3] = [Prompt::Powerlevel10k, Prompt::OhMyPosh, Prompt::Starship];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Prompt::Powerlevel10k => "powerlevel10k",
+            Prompt::OhMyPosh => "oh-my-posh",
+            Prompt::Starship => "starship",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|p| p.id() == id)
+    }
+
+    /// Human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Prompt::Powerlevel10k => "Powerlevel10k",
+            Prompt::OhMyPosh => "Oh My Posh",
+            Prompt::Starship => "Starship",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Prompt::Powerlevel10k => n_("Fast and highly configurable, set up with p10k configure"),
+            Prompt::OhMyPosh => n_("Themeable prompt shared across shells"),
+            Prompt::Starship => n_("Minimal prompt configured in starship.toml"),
+        }
+    }
+
+    /// Package providing the prompt, and whether it's from the AUR.
+    fn package(&self) -> (&'static str, bool) {
+        match self {
+            Prompt::Powerlevel10k => ("zsh-theme-powerlevel10k", false),
+            Prompt::OhMyPosh => ("oh-my-posh-bin", true),
+            Prompt::Starship => ("starship", false),
+        }
+    }
+
+    fn init(&self, framework: Framework) -> &'static str {
+        match (self, framework) {
+            (Prompt::OhMyPosh, Framework::Fish) => "oh-my-posh init fish | source",
+            (Prompt::Starship, Framework::Fish) => "starship init fish | source",
+            (Prompt::Powerlevel10k, _) => {
+                "source /usr/share/zsh-theme-powerlevel10k/powerlevel10k.zsh-theme\n\
+                 [[ -f ~/.p10k.zsh ]] && source ~/.p10k.zsh"
+            }
+            (Prompt::OhMyPosh, _) => "eval \"$(oh-my-posh init zsh)\"",
+            (Prompt::Starship, _) => "eval \"$(starship init zsh)\"",
+        }
+    }
+}
+
+/// A shell plugin.
+#[derive(Clone, Copy, Debug)]
+pub struct Plugin {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    /// GitHub repository, loaded by zinit or installed by fisher.
+    pub repo: &'static str,
+    /// Packages needed when the framework doesn't fetch the plugin itself,
+    /// or that the plugin depends on.
+    pub packages: &'static [&'static str],
+    /// Line loading the packaged plugin, `None` for completions that zsh
+    /// finds on its own.
+    pub source: Option<&'static str>,
+    pub recommended: bool,
+}
+
+/// zsh plugins, in the order they must be loaded. Syntax highlighting goes
+/// after anything that adds widgets, and before history substring search.
+pub const ZSH_PLUGINS: &[Plugin] = &[
+    Plugin {
+        id: "autosuggestions",
+        name: "Autosuggestions",
+        description: n_("Suggest commands from history as you type"),
+        repo: "zsh-users/zsh-autosuggestions",
+        packages: &["zsh-autosuggestions"],
+        source: Some("/usr/share/zsh/plugins/zsh-autosuggestions/zsh-autosuggestions.zsh"),
+        recommended: true,
+    },
+    Plugin {
+        id: "completions",
+        name: "Completions",
+        description: n_("Extra completion definitions for common tools"),
+        repo: "zsh-users/zsh-completions",
+        packages: &["zsh-completions"],
+        source: None,
+        recommended: false,
+    },
+    Plugin {
+        id: "syntax-highlighting",
+        name: "Syntax Highlighting",
+        description: n_("Color commands while they're typed"),
+        repo: "zsh-users/zsh-syntax-highlighting",
+        packages: &["zsh-syntax-highlighting"],
+        source: Some("/usr/share/zsh/plugins/zsh-syntax-highlighting/zsh-syntax-highlighting.zsh"),
+        recommended: true,
+    },
+    Plugin {
+        id: "history-substring-search",
+        name: "History Substring Search",
+        description: n_("Search history for what's typed with the arrow keys"),
+        repo: "zsh-users/zsh-history-substring-search",
+        packages: &["zsh-history-substring-search"],
+        source: Some(
+            "/usr/share/zsh/plugins/zsh-history-substring-search/zsh-history-substring-search.zsh",
+        ),
+        recommended: false,
+    },
+];
+
+/// fish plugins, installed with fisher.
+pub const FISH_PLUGINS: &[Plugin] = &[
+    Plugin {
+        id: "autopair",
+        name: "Autopair",
+        description: n_("Close brackets and quotes as they're typed"),
+        repo: "jorgebucaran/autopair.fish",
+        packages: &[],
+        source: None,
+        recommended: true,
+    },
+    Plugin {
+        id: "fzf",
+        name: "fzf.fish",
+        description: n_("Fuzzy search for files, history and git log"),
+        repo: "PatrickF1/fzf.fish",
+        packages: &["fzf", "fd"],
+        source: None,
+        recommended: false,
+    },
+    Plugin {
+        id: "z",
+        name: "z",
+        description: n_("Jump to frequently used folders"),
+        repo: "jethrokuan/z",
+        packages: &[],
+        source: None,
+        recommended: false,
+    },
+];
+
+/// A complete setup picked in the wizard.
+#[derive(Clone, Debug)]
+pub struct Setup {
+    pub framework: Framework,
+    pub prompt: Prompt,
+    pub plugins: Vec<&'static Plugin>,
+}
+
+impl Setup {
+    /// Packages to install, split into repository and AUR ones.
+    pub fn packages(&self) -> (Vec<&'static str>, Vec<&'static str>) {
+        let mut repo = match self.framework {
+            Framework::Fish => vec!["fish", "fisher"],
+            _ => vec!["zsh"],
+        };
+        // Nerd Font glyphs for the prompts
+        repo.push("ttf-meslo-nerd");
+        let mut aur = Vec::new();
+
+        let (prompt, from_aur) = self.prompt.package();
+        if from_aur {
+            aur.push(prompt);
+        } else {
+            repo.push(prompt);
+        }
+        for plugin in &self.plugins {
+            // zinit clones plugins from GitHub, their packages aren't needed
+            if self.framework != Framework::Zinit {
+                repo.extend(plugin.packages);
+            }
+        }
+        (repo, aur)
+    }
+
+    /// The generated config file.
+    pub fn config(&self) -> String {
+        let mut out = String::from("# Generated by Xero Toolkit Shell Setup\n");
+        if self.framework == Framework::Fish {
+            out.push_str("if status is-interactive\n    ");
+            out.push_str(self.prompt.init(self.framework));
+            out.push_str("\nend\n");
+            return out;
+        }
+
+        out.push_str(
+            "HISTFILE=~/.zsh_history\n\
+             HISTSIZE=10000\n\
+             SAVEHIST=10000\n\
+             setopt share_history hist_ignore_dups\n\n",
+        );
+        match self.framework {
+            Framework::OhMyZsh => out.push_str(
+                "export ZSH=\"$HOME/.oh-my-zsh\"\n\
+                 ZSH_THEME=\"\"\n\
+                 plugins=(git)\n\
+                 source \"$ZSH/oh-my-zsh.sh\"\n",
+            ),
+            Framework::Zinit => {
+                out.push_str(ZINIT_BOOTSTRAP);
+                for plugin in &self.plugins {
+                    out.push_str(&format!("zinit light {}\n", plugin.repo));
+                }
+                out.push_str("autoload -Uz compinit && compinit\n");
+            }
+            _ => out.push_str("autoload -Uz compinit && compinit\n"),
+        }
+        if self.framework != Framework::Zinit {
+            for source in self.plugins.iter().filter_map(|p| p.source) {
+                out.push_str(&format!("source {}\n", source));
+            }
+        }
+
+        out.push('\n');
+        out.push_str(self.prompt.init(self.framework));
+        out.push_str(
+            "\n\n# Personal additions, kept across setups\n\
+             [[ -f ~/.zshrc.user ]] && source ~/.zshrc.user\n",
+        );
+        out
+    }
+
+    /// fisher repositories to install, empty for zsh setups.
+    pub fn fisher_plugins(&self) -> Vec<&'static str> {
+        match self.framework {
+            Framework::Fish => self.plugins.iter().map(|p| p.repo).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Clones zinit on the first start of the shell, then loads it.
+const ZINIT_BOOTSTRAP: &str = r#"ZINIT_HOME="${XDG_DATA_HOME:-$HOME/.local/share}/zinit/zinit.git"
+if [[ ! -d "$ZINIT_HOME" ]]; then
+    mkdir -p "$(dirname "$ZINIT_HOME")"
+    git clone https://github.com/zdharma-continuum/zinit.git "$ZINIT_HOME"
+fi
+source "$ZINIT_HOME/zinit.zsh"
+"#;
+
+fn home() -> PathBuf {
+    dirs::home_dir().unwrap_or_else(|| PathBuf::from("/root"))
+}
+
+/// Backup of the `.zshrc` and login shell from before the first setup.
+pub fn backup_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| home().join(".local/share"))
+        .join("xero-toolkit/shell-backup")
+}
+
+/// Login shell recorded in the backup, if there is one.
+pub fn previous_shell() -> Option<String> {
+    let shell = std::fs::read_to_string(backup_dir().join("login-shell")).ok()?;
+    let shell = shell.trim();
+    (!shell.is_empty()).then(|| shell.to_string())
+}
+
+/// Script backing up `~/.zshrc` and the login shell into `$1`, once. Later
+/// setups keep the first backup, so a revert returns to the original.
+pub const BACKUP_SCRIPT: &str = r#"set -e
+if [ ! -d "$1" ]; then
+    mkdir -p "$1"
+    if [ -f "$HOME/.zshrc" ]; then cp -a "$HOME/.zshrc" "$1/zshrc"; fi
+    getent passwd "$USER" | cut -d: -f7 > "$1/login-shell"
+fi
+"#;
+
+/// Script writing `$2` to the config file `$1`.
+pub const WRITE_CONFIG_SCRIPT: &str = r#"set -e
+mkdir -p "$(dirname "$1")"
+printf '%s' "$2" > "$1"
+"#;
+
+/// Script installing Oh My Zsh, or updating it when it's there already.
+/// The installer is checked against its pin before it runs. It leaves
+/// `.zshrc` alone, so the only backup is the one [`BACKUP_SCRIPT`] made;
+/// the generated config replaces it afterwards.
+pub fn oh_my_zsh_script() -> Result<String> {
+    let pin = verify::get(OH_MY_ZSH_PIN)?;
+    let installer = "/tmp/ohmyzsh-install.sh";
+    Ok(format!(
+        r#"if [ ! -d "$HOME/.oh-my-zsh" ]; then
+    {fetch} && sh {installer} --unattended --keep-zshrc
+elif [ -d "$HOME/.oh-my-zsh/.git" ]; then
+    git -C "$HOME/.oh-my-zsh" pull --ff-only
+fi
+"#,
+        fetch = verify::fetch_script(&pin, installer),
+        installer = installer
+    ))
+}
+
+/// Whether anything [`UPDATE_SCRIPT`] updates is installed.
+pub fn has_plugins() -> bool {
+    let data = dirs::data_dir().unwrap_or_else(|| home().join(".local/share"));
+    home().join(".oh-my-zsh").is_dir()
+        || data.join("zinit/zinit.git").is_dir()
+        || home().join(".config/fish/fish_plugins").is_file()
+}
+
+/// Script pulling Oh My Zsh and plugins or themes cloned into its `custom`
+/// folder, updating zinit and its plugins, and fisher's plugins. Packaged
+/// plugins update with the system. Fails if any update did, after trying
+/// the rest.
+pub const UPDATE_SCRIPT: &str = r#"status=0
+omz="$HOME/.oh-my-zsh"
+if [ -d "$omz/.git" ]; then
+    echo "Updating Oh My Zsh..."
+    git -C "$omz" pull --ff-only || status=1
+fi
+for repo in "$omz"/custom/plugins/*/ "$omz"/custom/themes/*/; do
+    if [ -d "${repo}.git" ]; then
+        echo "Updating $(basename "$repo")..."
+        git -C "$repo" pull --ff-only || status=1
+    fi
+done
+zinit_home="${XDG_DATA_HOME:-$HOME/.local/share}/zinit/zinit.git"
+if [ -d "$zinit_home" ]; then
+    echo "Updating zinit plugins..."
+    zsh -c "source '$zinit_home/zinit.zsh' && zinit self-update && zinit update --all" || status=1
+fi
+if [ -f "$HOME/.config/fish/fish_plugins" ] && command -v fish >/dev/null; then
+    echo "Updating fish plugins..."
+    fish -c 'fisher update' || status=1
+fi
+exit $status
+"#;
+
+/// Script pointing the XeroLinux Konsole profile at the shell `$1`. The
+/// profile names its shell itself, ignoring the login shell.
+pub const KONSOLE_PROFILE_SCRIPT: &str = r#"profile="$HOME/.local/share/konsole/XeroLinux.profile"
+if [ -f "$profile" ]; then
+    sed -i "s|^Command=.*|Command=$1|" "$profile"
+fi
+"#;
+
+/// Script restoring the backup in `$1` and removing generated configs. The
+/// login shell is reset separately, that needs root.
+pub const REVERT_SCRIPT: &str = r#"set -e
+if [ -f "$1/zshrc" ]; then
+    cp -af "$1/zshrc" "$HOME/.zshrc"
+else
+    rm -f "$HOME/.zshrc"
+fi
+rm -f "$HOME/.config/fish/conf.d/xero-shell.fish"
+rm -rf "$1"
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zsh_config() {
+        let setup = Setup {
+            framework: Framework::OhMyZsh,
+            prompt: Prompt::Starship,
+            plugins: ZSH_PLUGINS.iter().collect(),
+        };
+        let config = setup.config();
+        assert!(config.contains("source \"$ZSH/oh-my-zsh.sh\"\n"));
+        assert!(config.contains("eval \"$(starship init zsh)\""));
+        let highlighting = config.find("zsh-syntax-highlighting.zsh").unwrap();
+        let substring = config.find("zsh-history-substring-search.zsh").unwrap();
+        assert!(highlighting < substring);
+
+        let (repo, aur) = setup.packages();
+        assert!(repo.contains(&"zsh-autosuggestions") && repo.contains(&"starship"));
+        assert!(aur.is_empty());
+    }
+
+    #[test]
+    fn test_zinit_and_fish() {
+        let setup = Setup {
+            framework: Framework::Zinit,
+            prompt: Prompt::OhMyPosh,
+            plugins: vec![&ZSH_PLUGINS[0]],
+        };
+        assert!(setup
+            .config()
+            .contains("zinit light zsh-users/zsh-autosuggestions\n"));
+        assert!(!setup.config().contains("source /usr/share/zsh/plugins"));
+        assert_eq!(setup.packages().1, vec!["oh-my-posh-bin"]);
+
+        let setup = Setup {
+            framework: Framework::Fish,
+            prompt: Prompt::Starship,
+            plugins: FISH_PLUGINS.iter().collect(),
+        };
+        assert!(setup.config().contains("starship init fish | source"));
+        assert!(setup.packages().0.contains(&"fd"));
+        assert_eq!(setup.fisher_plugins().len(), 3);
+        assert!(!Framework::Fish.prompts().contains(&Prompt::Powerlevel10k));
+    }
+}
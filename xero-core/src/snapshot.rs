@@ -0,0 +1,178 @@
+//! Filesystem snapshots taken before destructive actions.
+//!
+//! When Snapper (with a `root` config) or Timeshift is set up, the
+//! confirmation of a destructive action offers to take a snapshot first.
+//! The snapshot step records the new snapshot's id in the runtime
+//! directory, and the task runner shows it once the sequence ends, so the
+//! system can be rolled back to it later. Without a runtime directory the
+//! snapshot is still taken, but its id isn't shown.
+
+use crate::aur::is_executable_in_path;
+use crate::task_runner::{Command, CommandSequence};
+use std::path::{Path, PathBuf};
+
+/// Snapper takes the snapshot and prints its number. The id is recorded in
+/// `$1` unless it's empty.
+const SNAPPER_SCRIPT: &str = r#"set -e
+[ -z "$1" ] || rm -f "$1"
+id=$(snapper -c root create --description "$2" --cleanup-algorithm number --print-number)
+echo "Created snapshot $id"
+[ -z "$1" ] || echo "snapper $id" > "$1"
+"#;
+
+/// Timeshift names snapshots by date and reports the name when tagging it.
+const TIMESHIFT_SCRIPT: &str = r#"set -e
+[ -z "$1" ] || rm -f "$1"
+out=$(timeshift --create --scripted --comments "$2")
+echo "$out"
+id=$(printf '%s\n' "$out" | sed -n "s/^Tagged snapshot '\([^']*\)'.*/\1/p" | head -n 1)
+[ -n "$id" ] || { echo "Couldn't find the new snapshot's name" >&2; exit 1; }
+[ -z "$1" ] || echo "timeshift $id" > "$1"
+"#;
+
+/// A snapshot tool that is set up on this system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    Snapper,
+    Timeshift,
+}
+
+impl Tool {
+    fn id(&self) -> &'static str {
+        match self {
+            Tool::Snapper => "snapper",
+            Tool::Timeshift => "timeshift",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Snapper => "Snapper",
+            Tool::Timeshift => "Timeshift",
+        }
+    }
+
+    /// The configured tool, Snapper first.
+    pub fn detect() -> Option<Tool> {
+        if is_executable_in_path("snapper") && Path::new("/etc/snapper/configs/root").exists() {
+            Some(Tool::Snapper)
+        } else if is_executable_in_path("timeshift")
+            && Path::new("/etc/timeshift/timeshift.json").exists()
+        {
+            Some(Tool::Timeshift)
+        } else {
+            None
+        }
+    }
+
+    /// Step taking a snapshot described as `comment` and recording its id.
+    pub fn create(&self, comment: &str) -> Command {
+        let script = match self {
+            Tool::Snapper => SNAPPER_SCRIPT,
+            Tool::Timeshift => TIMESHIFT_SCRIPT,
+        };
+        Command::builder()
+            .privileged()
+            .program("sh")
+            .args(&[
+                "-c",
+                script,
+                "sh",
+                &id_file()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+                comment,
+            ])
+            .description(&format!("Creating {} snapshot...", self.name()))
+            .build()
+    }
+
+    fn from_id(id: &str) -> Option<Tool> {
+        [Tool::Snapper, Tool::Timeshift]
+            .into_iter()
+            .find(|t| t.id() == id)
+    }
+}
+
+/// A snapshot taken during the last sequence.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Created {
+    pub tool: Tool,
+    pub id: String,
+}
+
+impl Created {
+    /// Command restoring the snapshot.
+    pub fn rollback_command(&self) -> String {
+        match self.tool {
+            Tool::Snapper => format!("snapper rollback {}", self.id),
+            Tool::Timeshift => format!("timeshift --restore --snapshot '{}'", self.id),
+        }
+    }
+
+    /// Line for the completion screen.
+    pub fn note(&self) -> String {
+        crate::i18n::gettext_f(
+            "Snapshot {id} ({tool}), restore with: {command}",
+            &[
+                ("id", &self.id),
+                ("tool", self.tool.name()),
+                ("command", &self.rollback_command()),
+            ],
+        )
+    }
+}
+
+/// Where the snapshot step records the id. Only the user's runtime
+/// directory will do, since root writes the file.
+fn id_file() -> Option<PathBuf> {
+    dirs::runtime_dir().map(|dir| dir.join("xero-toolkit-snapshot"))
+}
+
+fn parse(record: &str) -> Option<Created> {
+    let (tool, id) = record.trim().split_once(' ')?;
+    Some(Created {
+        tool: Tool::from_id(tool)?,
+        id: id.to_string(),
+    })
+    .filter(|c| !c.id.is_empty())
+}
+
+/// The snapshot recorded since the last call, if any.
+pub fn take_created() -> Option<Created> {
+    let path = id_file()?;
+    let record = std::fs::read_to_string(&path).ok()?;
+    let _ = std::fs::remove_file(&path);
+    parse(&record)
+}
+
+/// `commands` with `snapshot` as their first step, when there is one.
+pub fn before(snapshot: Option<Command>, commands: CommandSequence) -> CommandSequence {
+    match snapshot {
+        Some(step) => CommandSequence::new().then(step).append(commands).build(),
+        None => commands,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_recorded_snapshots() {
+        assert_eq!(
+            parse("snapper 42\n"),
+            Some(Created {
+                tool: Tool::Snapper,
+                id: "42".to_string()
+            })
+        );
+        let created = parse("timeshift 2026-10-16_12-00-01").unwrap();
+        assert_eq!(
+            created.rollback_command(),
+            "timeshift --restore --snapshot '2026-10-16_12-00-01'"
+        );
+        assert_eq!(parse("btrbk 1"), None);
+        assert_eq!(parse("snapper "), None);
+    }
+}
@@ -0,0 +1,28 @@
+//! Files rendered as the user and installed by a privileged command.
+//!
+//! Staging them under a predictable name in `/tmp` would let another user
+//! put their own file or a symlink there before root installs it. They go
+//! to the user's runtime directory instead, readable by the user only.
+
+use anyhow::{Context, Result};
+use std::io::Write;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::PathBuf;
+
+/// Write `contents` to `name` in the runtime directory with mode 0600 and
+/// return its path. Fails when there's no runtime directory.
+pub fn stage(name: &str, contents: &str) -> Result<PathBuf> {
+    let dir = dirs::runtime_dir().context("XDG_RUNTIME_DIR is not set")?;
+    let path = dir.join(name);
+    // Left over from an earlier run; it would keep its old mode
+    let _ = std::fs::remove_file(&path);
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&path)
+        .with_context(|| format!("Failed to create {}", path.display()))?;
+    file.write_all(contents.as_bytes())
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
@@ -0,0 +1,150 @@
+//! System status for the dashboard on the main page.
+//!
+//! Everything here is cheap enough to read again after every task; the
+//! slower update check lives in `updates`.
+
+use crate::i18n::{gettext, ngettext_f};
+use crate::{manifest, orphans};
+use std::process::Command;
+
+/// Numbers shown on the dashboard cards.
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    pub orphans: usize,
+    /// Free and total bytes of the root filesystem.
+    pub root_space: Option<(u64, u64)>,
+    /// Unix time the most recent maintenance timer fired.
+    pub last_maintenance: Option<i64>,
+    pub failed_units: Vec<String>,
+    /// Files the toolkit installed outside of pacman that changed since.
+    pub modified_files: Vec<String>,
+}
+
+impl Snapshot {
+    /// Read the status, with `timers` being the maintenance timers to look
+    /// at. Blocks on pacman, systemctl and sha256sum.
+    pub fn read(timers: &[String]) -> Self {
+        Self {
+            orphans: orphans::find().len(),
+            root_space: root_space(),
+            last_maintenance: last_trigger(timers),
+            failed_units: failed_units(),
+            modified_files: manifest::load()
+                .into_iter()
+                .filter(|a| a.state() == manifest::State::Modified)
+                .map(|a| a.path)
+                .collect(),
+        }
+    }
+}
+
+fn stdout(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+}
+
+fn root_space() -> Option<(u64, u64)> {
+    parse_df(&stdout("df", &["-B1", "--output=avail,size", "/"])?)
+}
+
+/// Free and total bytes from `df --output=avail,size`.
+fn parse_df(output: &str) -> Option<(u64, u64)> {
+    let mut values = output.lines().nth(1)?.split_whitespace();
+    Some((values.next()?.parse().ok()?, values.next()?.parse().ok()?))
+}
+
+/// When any of `timers` last fired.
+fn last_trigger(timers: &[String]) -> Option<i64> {
+    if timers.is_empty() {
+        return None;
+    }
+    let mut args = vec![
+        "show",
+        "--timestamp=unix",
+        "--property=LastTriggerUSec",
+        "--value",
+    ];
+    args.extend(timers.iter().map(String::as_str));
+    parse_triggers(&stdout("systemctl", &args)?)
+}
+
+/// Newest of the `@<unix time>` values, skipping timers that never fired.
+fn parse_triggers(output: &str) -> Option<i64> {
+    output
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix('@')?.parse().ok())
+        .max()
+}
+
+fn failed_units() -> Vec<String> {
+    stdout(
+        "systemctl",
+        &[
+            "list-units",
+            "--failed",
+            "--plain",
+            "--no-legend",
+            "--no-pager",
+        ],
+    )
+    .map(|output| parse_failed(&output))
+    .unwrap_or_default()
+}
+
+fn parse_failed(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect()
+}
+
+/// How long ago `timestamp` was, in days, e.g. "Yesterday".
+pub fn days_ago(timestamp: i64, now: i64) -> String {
+    match (now - timestamp).max(0) / 86_400 {
+        0 => gettext("Today"),
+        1 => gettext("Yesterday"),
+        days => ngettext_f(
+            "{days} day ago",
+            "{days} days ago",
+            days as u32,
+            &[("days", &days.to_string())],
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsers() {
+        assert_eq!(
+            parse_df("      Avail    1B-blocks\n 52428800 104857600\n"),
+            Some((52_428_800, 104_857_600))
+        );
+        assert_eq!(parse_df(""), None);
+
+        assert_eq!(
+            parse_triggers("@1760000000\n\nn/a\n\n@1760500000\n"),
+            Some(1_760_500_000)
+        );
+        assert_eq!(parse_triggers("n/a\n"), None);
+
+        assert_eq!(
+            parse_failed("bluetooth.service loaded failed failed Bluetooth service\n"),
+            vec!["bluetooth.service"]
+        );
+    }
+
+    #[test]
+    fn test_days_ago() {
+        assert_eq!(days_ago(1_000, 1_000 + 3_600), "Today");
+        assert_eq!(days_ago(0, 86_400 + 1), "Yesterday");
+        assert_eq!(days_ago(0, 86_400 * 5), "5 days ago");
+    }
+}
@@ -1,8 +1,5 @@
 //! System dependency checks and validation.
 
-use crate::ui::utils::extract_widget;
-use gtk4::prelude::*;
-use gtk4::{ApplicationWindow, Builder, Button, Label};
 use log::{error, info, warn};
 
 /// Result of dependency check containing missing dependencies.
@@ -25,7 +22,7 @@ impl DependencyCheckResult {
             missing.push("flatpak");
         }
         if self.aur_helper_missing {
-            missing.push("paru or yay");
+            missing.push("paru, yay or pikaur");
         }
         missing
     }
@@ -47,7 +44,7 @@ impl DependencyCheckResult {
             hints.push("Install flatpak: <tt>sudo pacman -S flatpak</tt>");
         }
         if self.aur_helper_missing {
-            hints.push("AUR Helper repositories:\n• Paru: <a href=\"https://github.com/Morganamilo/paru\">https://github.com/Morganamilo/paru</a>\n• Yay: <a href=\"https://github.com/Jguer/yay\">https://github.com/Jguer/yay</a>");
+            hints.push("AUR Helper repositories:\n• Paru: <a href=\"https://github.com/Morganamilo/paru\">https://github.com/Morganamilo/paru</a>\n• Yay: <a href=\"https://github.com/Jguer/yay\">https://github.com/Jguer/yay</a>\n• Pikaur: <a href=\"https://github.com/actionless/pikaur\">https://github.com/actionless/pikaur</a>");
         }
 
         if hints.is_empty() {
@@ -58,6 +55,12 @@ impl DependencyCheckResult {
     }
 }
 
+/// Whether flatpak is on PATH. Cheap enough for the UI thread, unlike
+/// [`check_flatpak`] which runs it.
+pub fn flatpak_available() -> bool {
+    super::aur::is_executable_in_path("flatpak")
+}
+
 /// Check if flatpak is installed and available.
 fn check_flatpak() -> bool {
     info!("Checking for flatpak availability");
@@ -81,28 +84,23 @@ fn check_flatpak() -> bool {
     }
 }
 
-/// Check if an AUR helper (paru or yay) is installed.
+/// Check if a supported AUR helper is installed.
 fn check_aur_helper() -> bool {
     info!("Checking for AUR helper availability");
 
-    if let Ok(output) = std::process::Command::new("paru").arg("--version").output() {
-        if output.status.success() {
-            let version = String::from_utf8_lossy(&output.stdout);
-            info!("paru found: {}", version.trim());
-            return true;
+    match crate::aur::detect() {
+        Some(helper) => {
+            info!("AUR helper found: {}", helper.name());
+            true
         }
-    }
-
-    if let Ok(output) = std::process::Command::new("yay").arg("--version").output() {
-        if output.status.success() {
-            let version = String::from_utf8_lossy(&output.stdout);
-            info!("yay found: {}", version.trim());
-            return true;
+        None => {
+            warn!(
+                "No AUR helper ({}) found in PATH",
+                crate::aur::supported_list()
+            );
+            false
         }
     }
-
-    warn!("No AUR helper (paru or yay) found in PATH");
-    false
 }
 
 /// Perform all dependency checks and return results.
@@ -126,37 +124,3 @@ pub fn check_dependencies() -> DependencyCheckResult {
 
     result
 }
-
-/// Show dependency error dialog and prevent app from continuing.
-pub fn show_dependency_error_dialog(
-    main_window: &ApplicationWindow,
-    check_result: &DependencyCheckResult,
-) {
-    error!("Showing dependency error dialog");
-
-    // Load error dialog from UI file
-    let builder = Builder::from_resource(crate::config::resources::dialogs::DEPENDENCY_ERROR);
-
-    let error_window: gtk4::Window = extract_widget(&builder, "dependency_error_window");
-
-    let missing_deps_label: Label = extract_widget(&builder, "missing_deps_label");
-
-    let install_hint_label: Label = extract_widget(&builder, "install_hint_label");
-
-    let exit_button: Button = extract_widget(&builder, "exit_button");
-
-    missing_deps_label.set_label(&check_result.format_missing_list());
-
-    install_hint_label.set_label(&check_result.generate_install_hint());
-
-    error_window.set_transient_for(Some(main_window));
-
-    let main_window_clone = main_window.clone();
-    exit_button.connect_clicked(move |_| {
-        error!("User clicked exit on dependency error dialog");
-        main_window_clone.close();
-        std::process::exit(1);
-    });
-
-    error_window.present();
-}
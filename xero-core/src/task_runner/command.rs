@@ -3,15 +3,41 @@
 //! This module provides the core data structures for representing commands
 //! and their execution results in the task runner system.
 
+use serde::{Deserialize, Serialize};
+
 /// Type of command to execute.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CommandType {
     /// Normal command (no special handling)
     Normal,
     /// Command that needs privilege escalation (pkexec)
     Privileged,
-    /// AUR helper command (paru/yay)
+    /// AUR helper command (paru, yay or pikaur)
     Aur,
+    /// File download handled in-process by the native downloader
+    Download(DownloadSpec),
+}
+
+/// What has to happen after a command for its change to take effect.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Restart {
+    /// Log out and back in, e.g. for new group memberships
+    Relogin,
+    /// Reboot, e.g. for a new kernel or module options
+    Reboot,
+}
+
+/// A file to fetch with the native downloader.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownloadSpec {
+    /// URL to download from
+    pub url: String,
+    /// Destination path; parent directories are created as needed
+    pub dest: String,
+    /// Expected SHA256 digest; the step fails if the file doesn't match
+    pub sha256: Option<String>,
 }
 
 /// Status of a task in the UI.
@@ -45,7 +71,7 @@ pub enum CommandResult {
 ///
 /// Commands can be of different types (normal, privileged, AUR) and include
 /// the program name, arguments, and a user-facing description.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Command {
     /// The type of command, determining how it should be executed
     pub command_type: CommandType,
@@ -55,6 +81,9 @@ pub struct Command {
     pub args: Vec<String>,
     /// Human-readable description shown in the UI
     pub description: String,
+    /// Relogin or reboot needed once the command has run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires: Option<Restart>,
 }
 
 /// Builder for constructing `Command` objects with a fluent API.
@@ -62,7 +91,7 @@ pub struct Command {
 /// # Examples
 ///
 /// ```no_run
-/// use crate::ui::task_runner::Command;
+/// use xero_core::task_runner::Command;
 ///
 /// // Privileged command
 /// let cmd = Command::builder()
@@ -93,6 +122,7 @@ pub struct CommandBuilder {
     program: Option<String>,
     args: Vec<String>,
     description: Option<String>,
+    requires: Option<Restart>,
 }
 
 impl CommandBuilder {
@@ -110,9 +140,26 @@ impl CommandBuilder {
         self
     }
 
-    /// Set the human-readable description shown in the UI.
+    /// Require the downloaded file to match a SHA256 digest.
+    ///
+    /// Only meaningful for download commands; ignored otherwise.
+    pub fn sha256(mut self, hash: &str) -> Self {
+        if let CommandType::Download(spec) = &mut self.command_type {
+            spec.sha256 = Some(hash.to_string());
+        }
+        self
+    }
+
+    /// Set the human-readable description shown in the UI, translated.
     pub fn description(mut self, description: &str) -> Self {
-        self.description = Some(description.to_string());
+        self.description = Some(crate::i18n::gettext(description));
+        self
+    }
+
+    /// Mark the command as needing a relogin or reboot to take effect. The
+    /// task runner asks for it once the sequence is done.
+    pub fn requires(mut self, restart: Restart) -> Self {
+        self.requires = Some(restart);
         self
     }
 
@@ -122,8 +169,9 @@ impl CommandBuilder {
     ///
     /// Panics if required fields (program for normal/privileged, description) are missing.
     pub fn build(self) -> Command {
-        let program = match self.command_type {
+        let program = match &self.command_type {
             CommandType::Aur => "aur".to_string(),
+            CommandType::Download(_) => "download".to_string(),
             _ => self
                 .program
                 .expect("program is required for normal and privileged commands"),
@@ -136,6 +184,7 @@ impl CommandBuilder {
             program,
             args: self.args,
             description,
+            requires: self.requires,
         }
     }
 }
@@ -148,7 +197,7 @@ impl Command {
     /// # Example
     ///
     /// ```no_run
-    /// use crate::ui::task_runner::Command;
+    /// use xero_core::task_runner::Command;
     ///
     /// let cmd = Command::builder()
     ///     .privileged()
@@ -169,7 +218,7 @@ impl Command {
 /// # Example
 ///
 /// ```no_run
-/// use crate::ui::task_runner::Command;
+/// use xero_core::task_runner::Command;
 ///
 /// // Privileged command
 /// let cmd = Command::builder()
@@ -193,6 +242,12 @@ impl Command {
 ///     .args(&["install", "-y", "app.id"])
 ///     .description("Installing Flatpak app")
 ///     .build();
+///
+/// // Download (program is automatically set)
+/// let cmd = Command::builder()
+///     .download("https://example.com/file.tar.gz", "/tmp/file.tar.gz")
+///     .description("Downloading file")
+///     .build();
 /// ```
 #[derive(Debug)]
 pub struct CommandBuilderType;
@@ -205,6 +260,7 @@ impl CommandBuilderType {
             program: None,
             args: Vec::new(),
             description: None,
+            requires: None,
         }
     }
 
@@ -215,16 +271,34 @@ impl CommandBuilderType {
             program: None,
             args: Vec::new(),
             description: None,
+            requires: None,
         }
     }
 
-    /// Create a builder for an AUR helper command (paru/yay).
+    /// Create a builder for an AUR helper command (paru, yay or pikaur).
     pub fn aur(self) -> CommandBuilder {
         CommandBuilder {
             command_type: CommandType::Aur,
             program: None,
             args: Vec::new(),
             description: None,
+            requires: None,
+        }
+    }
+
+    /// Create a builder for a download of `url` to `dest`, run in-process
+    /// with progress shown on the task. Runs as the current user.
+    pub fn download(self, url: &str, dest: &str) -> CommandBuilder {
+        CommandBuilder {
+            command_type: CommandType::Download(DownloadSpec {
+                url: url.to_string(),
+                dest: dest.to_string(),
+                sha256: None,
+            }),
+            program: None,
+            args: Vec::new(),
+            description: None,
+            requires: None,
         }
     }
 }
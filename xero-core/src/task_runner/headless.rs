@@ -0,0 +1,214 @@
+//! Headless execution of command sequences, without GTK.
+//!
+//! Runs the same commands as the task runner dialog, resolved the same way
+//! (xero-auth, pkexec or sudo for privileged steps, the AUR helper for AUR
+//! steps), and
+//! reports progress to a [`Reporter`] instead of widgets. Used by the CLI.
+
+use super::command::{Command, CommandResult, CommandType, DownloadSpec};
+use super::{resolve_command, stop_daemon_if_needed, CommandSequence};
+use crate::download::DownloadState;
+use log::{error, info, warn};
+use std::process::Stdio;
+use std::sync::atomic::AtomicBool;
+use std::sync::{mpsc, Arc};
+use xero_auth::utils::read_buffer_with_line_processing;
+
+/// Receives progress of a headless run.
+pub trait Reporter {
+    /// A step is about to run. `index` is zero-based.
+    fn step_started(&mut self, index: usize, total: usize, command: &Command);
+
+    /// A chunk of output from the running step, usually one line.
+    fn output(&mut self, text: &str, stderr: bool);
+
+    /// Progress of a download step.
+    fn download_progress(&mut self, _state: &DownloadState) {}
+
+    /// A step finished.
+    fn step_finished(&mut self, index: usize, result: &CommandResult);
+
+    /// The whole sequence finished.
+    fn finished(&mut self, success: bool, message: &str);
+}
+
+/// Run a command sequence to completion, stopping at the first failure.
+///
+/// Returns whether every step succeeded.
+pub fn run_headless(commands: CommandSequence, reporter: &mut dyn Reporter) -> bool {
+    let commands = commands.commands;
+
+    if super::needs_daemon(&commands) {
+        if let Err(e) = crate::daemon::start_daemon() {
+            error!("Failed to start daemon: {}", e);
+            reporter.finished(
+                false,
+                &format!("Failed to start authentication daemon: {}", e),
+            );
+            return false;
+        }
+        info!("Daemon ready for privileged commands");
+    }
+
+    let total = commands.len();
+    let mut failure = None;
+    for (index, command) in commands.iter().enumerate() {
+        reporter.step_started(index, total, command);
+
+        let result = match &command.command_type {
+            CommandType::Download(spec) => run_download(spec, reporter),
+            _ => run_process(command, reporter),
+        };
+        reporter.step_finished(index, &result);
+
+        if let CommandResult::Failure { exit_code } = result {
+            let exit_msg = exit_code
+                .map(|code| format!(" (exit code: {})", code))
+                .unwrap_or_default();
+            failure = Some(format!(
+                "Operation failed at step {} of {}{}",
+                index + 1,
+                total,
+                exit_msg
+            ));
+            break;
+        }
+    }
+
+    stop_daemon_if_needed();
+
+    match failure {
+        Some(message) => {
+            reporter.finished(false, &message);
+            false
+        }
+        None => {
+            reporter.finished(true, super::SUCCESS_MESSAGE);
+            true
+        }
+    }
+}
+
+/// Output chunk from one of a child's pipes.
+enum Output {
+    Stdout(String),
+    Stderr(String),
+}
+
+fn run_process(command: &Command, reporter: &mut dyn Reporter) -> CommandResult {
+    let (program, args) = match resolve_command(command) {
+        Ok(result) => result,
+        Err(err) => {
+            reporter.output(&format!("Failed to prepare command: {}\n", err), true);
+            return CommandResult::Failure { exit_code: None };
+        }
+    };
+
+    info!("Executing: {} {:?}", program, args);
+
+    let mut process = std::process::Command::new(&program);
+    process.args(&args);
+
+    // Inject sudo shim to intercept sudo calls in scripts
+    if let Some(path) = super::shim_path() {
+        process.env("PATH", path);
+    }
+
+    process.stdin(Stdio::null());
+    process.stdout(Stdio::piped());
+    process.stderr(Stdio::piped());
+
+    let mut child = match process.spawn() {
+        Ok(child) => child,
+        Err(err) => {
+            reporter.output(&format!("Failed to start operation: {}\n", err), true);
+            return CommandResult::Failure { exit_code: None };
+        }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut readers = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        let tx = tx.clone();
+        readers.push(std::thread::spawn(move || {
+            read_buffer_with_line_processing(
+                stdout,
+                |text| tx.send(Output::Stdout(text)).is_ok(),
+                |e| warn!("Error reading stdout: {}", e),
+            );
+        }));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        let tx = tx.clone();
+        readers.push(std::thread::spawn(move || {
+            read_buffer_with_line_processing(
+                stderr,
+                |text| tx.send(Output::Stderr(text)).is_ok(),
+                |e| warn!("Error reading stderr: {}", e),
+            );
+        }));
+    }
+    drop(tx);
+
+    // Ends once both pipes are closed
+    for output in rx {
+        match output {
+            Output::Stdout(text) => reporter.output(&strip_ansi_escapes::strip_str(&text), false),
+            Output::Stderr(text) => reporter.output(&strip_ansi_escapes::strip_str(&text), true),
+        }
+    }
+    for reader in readers {
+        if let Err(e) = reader.join() {
+            warn!("Error joining output reader thread: {:?}", e);
+        }
+    }
+
+    match child.wait() {
+        Ok(status) if status.success() => CommandResult::Success,
+        Ok(status) => CommandResult::Failure {
+            exit_code: status.code(),
+        },
+        Err(e) => {
+            error!("Error waiting for process: {}", e);
+            CommandResult::Failure { exit_code: None }
+        }
+    }
+}
+
+fn run_download(spec: &DownloadSpec, reporter: &mut dyn Reporter) -> CommandResult {
+    reporter.output(&format!("{}\n", spec.url), false);
+
+    let (tx, rx) = mpsc::channel();
+    let spec_clone = spec.clone();
+    let worker = std::thread::spawn(move || {
+        let dest = std::path::Path::new(&spec_clone.dest);
+        crate::download::fetch_blocking(
+            &spec_clone.url,
+            dest,
+            move |state| {
+                let _ = tx.send(state);
+            },
+            Arc::new(AtomicBool::new(false)),
+        )
+        .and_then(|()| match &spec_clone.sha256 {
+            Some(hash) => crate::verify::check_sha256(dest, hash),
+            None => Ok(()),
+        })
+    });
+
+    for state in rx {
+        reporter.download_progress(&state);
+    }
+
+    match worker.join() {
+        Ok(Ok(())) => {
+            reporter.output("Download complete\n", false);
+            CommandResult::Success
+        }
+        Ok(Err(e)) => {
+            reporter.output(&format!("{:#}\n", e), true);
+            CommandResult::Failure { exit_code: None }
+        }
+        Err(_) => CommandResult::Failure { exit_code: None },
+    }
+}
@@ -0,0 +1,209 @@
+//! Command model shared by the task runner dialog, the CLI and scripts.
+//!
+//! - `command`: Commands and their results
+//! - `headless`: Execution without GTK, reporting to a `Reporter`
+//! - `script`: Export of the steps as a shell script
+//!
+//! The GTK dialog running these lives in the GUI crate.
+
+mod command;
+pub mod headless;
+pub mod script;
+
+pub use command::{Command, CommandResult, CommandType, DownloadSpec, Restart, TaskStatus};
+
+use crate::config::user::PrivilegeBackend;
+use crate::daemon::get_xero_auth_path;
+use log::error;
+
+/// Message displayed when all operations complete successfully.
+pub const SUCCESS_MESSAGE: &str = crate::i18n::n_("All operations completed successfully!");
+
+/// Helper for building sequences of commands with a fluent API.
+///
+/// # Example
+///
+/// ```no_run
+/// use xero_core::task_runner::{Command, CommandSequence};
+///
+/// let commands = CommandSequence::new()
+///     .then(Command::builder().aur()
+///         .args(&["-S", "package"])
+///         .description("Installing package")
+///         .build())
+///     .then(Command::builder().privileged()
+///         .program("systemctl")
+///         .args(&["enable", "--now", "service"])
+///         .description("Enabling service")
+///         .build())
+///     .build();
+/// ```
+#[derive(Debug, Default)]
+pub struct CommandSequence {
+    commands: Vec<Command>,
+}
+
+impl CommandSequence {
+    /// Create a new empty command sequence.
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    /// Add a command to the sequence.
+    ///
+    /// Can be chained to add multiple commands in order.
+    pub fn then(mut self, command: Command) -> Self {
+        self.commands.push(command);
+        self
+    }
+
+    /// Add all commands of another sequence after this one's.
+    pub fn append(mut self, other: CommandSequence) -> Self {
+        self.commands.extend(other.commands);
+        self
+    }
+
+    /// Render the sequence as a bash script, the same as Export does.
+    pub fn to_shell_script(&self, title: &str) -> String {
+        script::to_shell_script(&self.commands, title)
+    }
+
+    /// Build the final command sequence.
+    pub fn build(self) -> Self {
+        self
+    }
+
+    /// Check if the sequence is empty.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Number of commands in the sequence.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// The commands in the sequence, in order.
+    pub fn commands(&self) -> &[Command] {
+        &self.commands
+    }
+
+    /// Take the commands out of the sequence.
+    pub fn into_commands(self) -> Vec<Command> {
+        self.commands
+    }
+}
+
+/// The strongest relogin or reboot the commands need, if any.
+pub fn restart_needed(commands: &[Command]) -> Option<Restart> {
+    commands.iter().filter_map(|c| c.requires).max()
+}
+
+/// The backend privileged and AUR steps get root through.
+pub fn privilege_backend() -> PrivilegeBackend {
+    crate::config::user::load().privilege
+}
+
+/// Whether any command needs the xero-auth daemon: privileged or AUR steps,
+/// with the session backend.
+pub fn needs_daemon(commands: &[Command]) -> bool {
+    privilege_backend() == PrivilegeBackend::Session
+        && commands
+            .iter()
+            .any(|cmd| matches!(cmd.command_type, CommandType::Privileged | CommandType::Aur))
+}
+
+/// PATH with the scripts directory first, so the sudo shim there forwards
+/// `sudo` calls in scripts to xero-auth. Only the session backend has a
+/// daemon to forward them to.
+pub fn shim_path() -> Option<String> {
+    if privilege_backend() != PrivilegeBackend::Session {
+        return None;
+    }
+    let scripts_dir = crate::config::paths::scripts();
+    if !scripts_dir.exists() {
+        return None;
+    }
+    let path = std::env::var("PATH").ok()?;
+    Some(format!("{}:{}", scripts_dir.display(), path))
+}
+
+/// Program escalating a single command with `backend`.
+fn escalation_program(backend: PrivilegeBackend) -> String {
+    match backend {
+        PrivilegeBackend::Session => get_xero_auth_path().to_string_lossy().to_string(),
+        PrivilegeBackend::Pkexec => "pkexec".to_string(),
+        PrivilegeBackend::Sudo => "sudo".to_string(),
+    }
+}
+
+/// Resolve command to executable program and arguments,
+/// handling privilege escalation and AUR helper detection.
+///
+/// Privileged steps go through xero-auth, pkexec or sudo, depending on the
+/// `privilege` setting. sudo asks through `SUDO_ASKPASS` when it's set and
+/// fails instead of prompting otherwise, as there is no terminal to ask in.
+///
+/// # Returns
+///
+/// A tuple of `(program, args)` where `program` is the executable to run
+/// and `args` are the arguments to pass to it.
+///
+/// # Errors
+///
+/// Returns an error if the AUR helper is required but not available.
+pub fn resolve_command(command: &Command) -> Result<(String, Vec<String>), String> {
+    let backend = privilege_backend();
+    match &command.command_type {
+        CommandType::Normal => Ok((command.program.clone(), command.args.clone())),
+        CommandType::Privileged => {
+            let mut args = Vec::new();
+            match backend {
+                PrivilegeBackend::Session => {
+                    // Pass PATH via --env so scripts find the sudo shim
+                    if let Some(path) = shim_path() {
+                        args.push("--env".to_string());
+                        args.push(format!("PATH={}", path));
+                    }
+                }
+                PrivilegeBackend::Pkexec => {}
+                PrivilegeBackend::Sudo => {
+                    let prompt = if std::env::var_os("SUDO_ASKPASS").is_some() {
+                        "-A"
+                    } else {
+                        "-n"
+                    };
+                    args.push(prompt.to_string());
+                    args.push("--".to_string());
+                }
+            }
+
+            args.push(command.program.clone());
+            args.extend(command.args.clone());
+            Ok((escalation_program(backend), args))
+        }
+        CommandType::Aur => {
+            let helper = crate::aur_helper().ok_or_else(|| {
+                format!(
+                    "AUR helper not available ({} required)",
+                    crate::aur::supported_list()
+                )
+            })?;
+            Ok((
+                helper.name().to_string(),
+                helper.command_args(&escalation_program(backend), &command.args),
+            ))
+        }
+        CommandType::Download(_) => Err("downloads don't run as a process".to_string()),
+    }
+}
+
+/// Stop the daemon if needed.
+pub fn stop_daemon_if_needed() {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    if let Err(e) = rt.block_on(crate::daemon::stop_daemon()) {
+        error!("Failed to stop daemon: {}", e);
+    }
+}
@@ -0,0 +1,140 @@
+//! Export of command sequences as standalone shell scripts.
+//!
+//! The script runs the same steps as the task runner, with `sudo` for
+//! privileged steps and the user's AUR helper for AUR steps, so it can be
+//! audited or run on another machine without the toolkit.
+
+use super::command::{Command, CommandType, Restart};
+
+/// Quote a word for POSIX shells. Plain words are left as they are.
+pub(crate) fn quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+fn quoted_line<'a>(words: impl IntoIterator<Item = &'a str>) -> String {
+    words.into_iter().map(quote).collect::<Vec<_>>().join(" ")
+}
+
+/// Shell line(s) running one command.
+fn command_lines(command: &Command) -> String {
+    let args = command.args.iter().map(String::as_str);
+    match &command.command_type {
+        CommandType::Normal => quoted_line(std::iter::once(command.program.as_str()).chain(args)),
+        CommandType::Privileged => {
+            quoted_line(["sudo", command.program.as_str()].into_iter().chain(args))
+        }
+        CommandType::Aur => format!("\"$AUR_HELPER\" {}", quoted_line(args)),
+        CommandType::Download(spec) => {
+            let mut lines = format!(
+                "mkdir -p \"$(dirname {dest})\"\ncurl -fL --retry 3 -o {dest} {url}",
+                dest = quote(&spec.dest),
+                url = quote(&spec.url)
+            );
+            if let Some(hash) = &spec.sha256 {
+                lines.push_str(&format!(
+                    "\necho {} | sha256sum --check --strict",
+                    quote(&format!("{}  {}", hash, spec.dest))
+                ));
+            }
+            lines
+        }
+    }
+}
+
+/// Render `commands` as an annotated bash script titled `title`.
+pub fn to_shell_script(commands: &[Command], title: &str) -> String {
+    let mut script = format!(
+        "#!/usr/bin/env bash\n\
+         # {}\n\
+         #\n\
+         # Exported from {} {}. Review the steps before running.\n\
+         set -euo pipefail\n",
+        title,
+        crate::config::app_info::NAME,
+        crate::config::app_info::VERSION
+    );
+
+    if commands
+        .iter()
+        .any(|c| matches!(c.command_type, CommandType::Aur))
+    {
+        script.push_str(
+            "\nAUR_HELPER=\"${AUR_HELPER:-$(command -v paru || command -v yay || command -v pikaur || true)}\"\n\
+             if [ -z \"$AUR_HELPER\" ]; then\n    \
+                 echo 'An AUR helper (paru, yay or pikaur) is required' >&2\n    \
+                 exit 1\n\
+             fi\n",
+        );
+    }
+
+    let total = commands.len();
+    for (index, command) in commands.iter().enumerate() {
+        script.push_str(&format!(
+            "\n# [{}/{}] {}\n{}\n",
+            index + 1,
+            total,
+            command.description,
+            command_lines(command)
+        ));
+    }
+
+    match super::restart_needed(commands) {
+        Some(Restart::Relogin) => script.push_str(
+            "
+echo 'Log out and back in for the changes to take effect.'
+",
+        ),
+        Some(Restart::Reboot) => script.push_str(
+            "
+echo 'Reboot for the changes to take effect.'
+",
+        ),
+        None => {}
+    }
+
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_only_when_needed() {
+        assert_eq!(quote("pacman"), "pacman");
+        assert_eq!(quote("/var/lib/pacman/db.lck"), "/var/lib/pacman/db.lck");
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("a b"), "'a b'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn prefixes_steps_by_type() {
+        let commands = [
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&["enable", "--now", "docker.service"])
+                .description("Enabling Docker service...")
+                .build(),
+            Command::builder()
+                .aur()
+                .args(&["-S", "--needed", "docker"])
+                .description("Installing Docker...")
+                .build(),
+        ];
+        let script = to_shell_script(&commands, "Docker Setup");
+        assert!(script.contains(
+            "# [1/2] Enabling Docker service...\nsudo systemctl enable --now docker.service\n"
+        ));
+        assert!(script.contains("\"$AUR_HELPER\" -S --needed docker\n"));
+    }
+}
@@ -0,0 +1,145 @@
+//! External terminal emulators for interactive commands.
+//!
+//! Interactive commands run in the GUI's built-in terminal unless the
+//! `terminal` setting names an emulator. When that one isn't installed,
+//! the others are tried in [`CHAIN`] order, and the built-in terminal is
+//! used when none of them are. The command runs in a small wrapper that
+//! records its exit code and keeps the window open until Enter is pressed,
+//! so the output can be read and the caller learns how it ended.
+
+use crate::aur::is_executable_in_path;
+use crate::config::user::TerminalEmulator;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Emulators in the order they're tried.
+pub const CHAIN: &[TerminalEmulator] = &[
+    TerminalEmulator::Konsole,
+    TerminalEmulator::Alacritty,
+    TerminalEmulator::Kitty,
+    TerminalEmulator::GnomeTerminal,
+    TerminalEmulator::Foot,
+];
+
+/// Runs the command given after the status file, writes its exit code to
+/// that file and waits for Enter.
+const WRAPPER: &str = r#"status_file=$1
+shift
+"$@"
+status=$?
+echo "$status" > "$status_file"
+printf '\n[Process completed] Command exited with code %s. Press Enter to close.' "$status"
+read -r _"#;
+
+impl TerminalEmulator {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TerminalEmulator::Konsole => "Konsole",
+            TerminalEmulator::Alacritty => "Alacritty",
+            TerminalEmulator::Kitty => "kitty",
+            TerminalEmulator::GnomeTerminal => "GNOME Terminal",
+            TerminalEmulator::Foot => "foot",
+        }
+    }
+
+    pub fn program(&self) -> &'static str {
+        match self {
+            TerminalEmulator::Konsole => "konsole",
+            TerminalEmulator::Alacritty => "alacritty",
+            TerminalEmulator::Kitty => "kitty",
+            TerminalEmulator::GnomeTerminal => "gnome-terminal",
+            TerminalEmulator::Foot => "foot",
+        }
+    }
+
+    pub fn is_available(&self) -> bool {
+        is_executable_in_path(self.program())
+    }
+
+    /// Command line opening a window titled `title` that runs `argv`. It
+    /// only returns once the window is closed.
+    pub fn argv(&self, title: &str, argv: &[String]) -> Vec<String> {
+        let options: Vec<String> = match self {
+            TerminalEmulator::Konsole => vec![
+                "--nofork".into(),
+                "-p".into(),
+                format!("tabtitle={}", title),
+                "-e".into(),
+            ],
+            TerminalEmulator::Alacritty => vec!["--title".into(), title.into(), "-e".into()],
+            TerminalEmulator::Kitty | TerminalEmulator::Foot => {
+                vec!["--title".into(), title.into()]
+            }
+            TerminalEmulator::GnomeTerminal => {
+                vec!["--wait".into(), "--title".into(), title.into(), "--".into()]
+            }
+        };
+        std::iter::once(self.program().to_string())
+            .chain(options)
+            .chain(argv.iter().cloned())
+            .collect()
+    }
+}
+
+/// The emulator to use: `preferred` if installed, else the first installed
+/// one in [`CHAIN`]. `None` means the built-in terminal.
+pub fn pick(preferred: TerminalEmulator) -> Option<TerminalEmulator> {
+    std::iter::once(preferred)
+        .chain(CHAIN.iter().copied())
+        .find(TerminalEmulator::is_available)
+}
+
+/// A fresh path for [`wrapped`] to write an exit code to.
+pub fn status_file() -> PathBuf {
+    static COUNT: AtomicU32 = AtomicU32::new(0);
+    std::env::temp_dir().join(format!(
+        "xero-terminal-{}-{}",
+        std::process::id(),
+        COUNT.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// `command` with `args`, wrapped to write its exit code to `status_file`.
+pub fn wrapped(command: &str, args: &[&str], status_file: &Path) -> Vec<String> {
+    ["sh", "-c", WRAPPER, "sh"]
+        .into_iter()
+        .map(String::from)
+        .chain(std::iter::once(status_file.display().to_string()))
+        .chain(std::iter::once(command.to_string()))
+        .chain(args.iter().map(|a| a.to_string()))
+        .collect()
+}
+
+/// Exit code the wrapper wrote to `status_file`, removing the file. `None`
+/// when the window was closed before the command finished or it never ran.
+pub fn read_status(status_file: &Path) -> Option<i32> {
+    let status = std::fs::read_to_string(status_file).ok();
+    let _ = std::fs::remove_file(status_file);
+    status?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_commands_for_each_emulator() {
+        let argv = wrapped("pkexec", &["pmpd"], Path::new("/tmp/status"));
+        assert_eq!(argv[..2], ["sh", "-c"]);
+        assert_eq!(argv[3..], ["sh", "/tmp/status", "pkexec", "pmpd"]);
+
+        let inner = vec!["upd".to_string()];
+        assert_eq!(
+            TerminalEmulator::GnomeTerminal.argv("Update", &inner),
+            ["gnome-terminal", "--wait", "--title", "Update", "--", "upd"]
+        );
+        assert_eq!(
+            TerminalEmulator::Konsole.argv("Update", &inner),
+            ["konsole", "--nofork", "-p", "tabtitle=Update", "-e", "upd"]
+        );
+        assert_eq!(
+            TerminalEmulator::Foot.argv("Update", &inner),
+            ["foot", "--title", "Update", "upd"]
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! What the toolkit has installed, grouped by action, and how to remove it.
+//!
+//! Each successful sequence in the [`history`](crate::history) is read for
+//! the packages and Flatpak apps it installed and the systemd units it
+//! enabled; the files it created outside of pacman come from the
+//! [`manifest`]. Only what is still on the system is offered for removal.
+//! Sequences run in a terminal leave no history, so their files are missed.
+
+use crate::estimate::Targets;
+use crate::history::{Entry, Outcome};
+use crate::i18n::n_;
+use crate::manifest::{self, Artifact};
+use crate::orphans;
+use crate::package;
+use crate::services::ServiceState;
+use crate::task_runner::{Command, CommandSequence};
+use std::collections::{HashMap, HashSet};
+
+/// Kind of thing an action left behind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Kind {
+    Unit,
+    UserUnit,
+    Package,
+    Flatpak,
+    File,
+}
+
+impl Kind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Kind::Unit => n_("System service"),
+            Kind::UserUnit => n_("User service"),
+            Kind::Package => n_("Package"),
+            Kind::Flatpak => n_("Flatpak app"),
+            Kind::File => n_("File"),
+        }
+    }
+}
+
+/// One thing an action installed or enabled.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Item {
+    pub kind: Kind,
+    /// Package name, Flatpak id, unit name or absolute path.
+    pub name: String,
+    /// Installed packages outside the group that depend on this package, so
+    /// it can't be removed with the rest.
+    pub needed_by: Vec<String>,
+    /// A file whose contents changed since the toolkit installed it.
+    pub modified: bool,
+}
+
+/// Everything a single action left behind.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Footprint {
+    /// Task runner title of the action.
+    pub title: String,
+    /// When the action last succeeded, in seconds since the Unix epoch.
+    pub last_run: i64,
+    pub items: Vec<Item>,
+}
+
+impl Footprint {
+    /// Items that can be removed together.
+    pub fn removable(&self) -> impl Iterator<Item = &Item> {
+        self.items.iter().filter(|i| i.needed_by.is_empty())
+    }
+}
+
+/// Units a statement enables.
+fn scan(words: &[String], items: &mut Vec<Item>) {
+    let words: Vec<&str> = words
+        .iter()
+        .map(String::as_str)
+        .skip_while(|w| *w == "sudo")
+        .collect();
+    if words.first() != Some(&"systemctl") || !words.contains(&"enable") {
+        return;
+    }
+    let kind = if words.contains(&"--user") {
+        Kind::UserUnit
+    } else {
+        Kind::Unit
+    };
+    let units = words
+        .iter()
+        .skip(1)
+        .filter(|w| !w.starts_with('-') && **w != "enable");
+    for unit in units {
+        items.push(item(kind, unit));
+    }
+}
+
+fn item(kind: Kind, name: &str) -> Item {
+    Item {
+        kind,
+        name: name.to_string(),
+        needed_by: Vec::new(),
+        modified: false,
+    }
+}
+
+/// Packages, apps and units the commands of one sequence install, in
+/// order, without repeats. Files come from the [`manifest`].
+pub fn items_of(commands: &[Command]) -> Vec<Item> {
+    let targets = Targets::of(commands);
+    let mut items: Vec<Item> = targets
+        .packages
+        .iter()
+        .map(|name| item(Kind::Package, name))
+        .chain(
+            targets
+                .flatpaks
+                .iter()
+                .map(|(_, id)| item(Kind::Flatpak, id)),
+        )
+        .collect();
+
+    for words in commands.iter().flat_map(manifest::statements) {
+        scan(&words, &mut items);
+    }
+
+    let mut seen = HashSet::new();
+    items.retain(|i| seen.insert((i.kind, i.name.clone())));
+    items
+}
+
+/// Footprints of the successful entries and the artifacts, one per title,
+/// newest first. An item is listed under the newest action that installed
+/// it.
+pub fn footprints(entries: &[Entry], artifacts: &[Artifact]) -> Vec<Footprint> {
+    let mut footprints: Vec<Footprint> = Vec::new();
+    let mut claimed = HashSet::new();
+
+    for entry in entries.iter().rev() {
+        if entry.outcome != Outcome::Success {
+            continue;
+        }
+        let items: Vec<Item> = items_of(&entry.commands)
+            .into_iter()
+            .filter(|i| claimed.insert((i.kind, i.name.clone())))
+            .collect();
+        add(&mut footprints, &entry.title, entry.timestamp, items);
+    }
+    // Artifacts outlive the history, which only keeps the newest entries
+    for artifact in artifacts {
+        let file = item(Kind::File, &artifact.path);
+        add(
+            &mut footprints,
+            &artifact.action,
+            artifact.timestamp,
+            vec![file],
+        );
+    }
+
+    footprints.retain(|f| !f.items.is_empty());
+    footprints.sort_by_key(|f| std::cmp::Reverse(f.last_run));
+    footprints
+}
+
+fn add(footprints: &mut Vec<Footprint>, title: &str, timestamp: i64, items: Vec<Item>) {
+    match footprints.iter_mut().find(|f| f.title == title) {
+        Some(footprint) => {
+            footprint.last_run = footprint.last_run.max(timestamp);
+            footprint.items.extend(items);
+        }
+        None => footprints.push(Footprint {
+            title: title.to_string(),
+            last_run: timestamp,
+            items,
+        }),
+    }
+}
+
+/// Footprints from the history and the manifest, trimmed to what is still
+/// installed or enabled. Blocks on pacman, flatpak, systemctl and
+/// sha256sum.
+pub fn installed() -> Vec<Footprint> {
+    let artifacts = manifest::load();
+    let mut footprints = footprints(&crate::history::load(), &artifacts);
+    if footprints.is_empty() {
+        return footprints;
+    }
+
+    let packages: HashMap<String, Vec<String>> = orphans::installed()
+        .into_iter()
+        .map(|p| (p.name, p.required_by))
+        .collect();
+    let flatpaks = package::installed_flatpaks();
+    let files: HashMap<&str, manifest::State> = artifacts
+        .iter()
+        .map(|a| (a.path.as_str(), a.state()))
+        .collect();
+
+    for footprint in &mut footprints {
+        footprint.items.retain_mut(|i| match i.kind {
+            Kind::Package => packages.contains_key(&i.name),
+            Kind::Flatpak => flatpaks.contains(&i.name),
+            Kind::Unit => ServiceState::read(&i.name).enabled,
+            Kind::UserUnit => user_unit_enabled(&i.name),
+            Kind::File => {
+                let state = files[i.name.as_str()];
+                i.modified = state == manifest::State::Modified;
+                state != manifest::State::Missing
+            }
+        });
+
+        let group: HashSet<String> = footprint
+            .items
+            .iter()
+            .filter(|i| i.kind == Kind::Package)
+            .map(|i| i.name.clone())
+            .collect();
+        for item in footprint
+            .items
+            .iter_mut()
+            .filter(|i| i.kind == Kind::Package)
+        {
+            item.needed_by = packages[&item.name]
+                .iter()
+                .filter(|r| !group.contains(*r) && packages.contains_key(*r))
+                .cloned()
+                .collect();
+        }
+        footprint.items.sort_by_key(|i| i.kind);
+    }
+
+    footprints.retain(|f| !f.items.is_empty());
+    footprints
+}
+
+fn user_unit_enabled(unit: &str) -> bool {
+    std::process::Command::new("systemctl")
+        .args(["--user", "is-enabled", unit])
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// Steps removing `items`: services are stopped first, then packages,
+/// Flatpak apps and files are removed. Files are removed as root, so only
+/// whole artifacts in the directories the [`manifest`] tracks are.
+pub fn removal(items: &[&Item]) -> CommandSequence {
+    let names = |kind: Kind| -> Vec<&str> {
+        items
+            .iter()
+            .filter(|i| i.kind == kind)
+            .map(|i| i.name.as_str())
+            .collect()
+    };
+    let mut sequence = CommandSequence::new();
+
+    let units = names(Kind::Unit);
+    if !units.is_empty() {
+        let mut args = vec!["disable", "--now"];
+        args.extend(&units);
+        sequence = sequence.then(
+            Command::builder()
+                .privileged()
+                .program("systemctl")
+                .args(&args)
+                .description("Disabling services...")
+                .build(),
+        );
+    }
+    let units = names(Kind::UserUnit);
+    if !units.is_empty() {
+        let mut args = vec!["--user", "disable", "--now"];
+        args.extend(&units);
+        sequence = sequence.then(
+            Command::builder()
+                .normal()
+                .program("systemctl")
+                .args(&args)
+                .description("Disabling user services...")
+                .build(),
+        );
+    }
+
+    let packages = names(Kind::Package);
+    if !packages.is_empty() {
+        let mut args = vec!["-Rns", "--noconfirm"];
+        args.extend(&packages);
+        sequence = sequence.then(
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&args)
+                .description("Removing packages...")
+                .build(),
+        );
+    }
+
+    let flatpaks = names(Kind::Flatpak);
+    if !flatpaks.is_empty() {
+        let mut args = vec!["uninstall", "-y"];
+        args.extend(&flatpaks);
+        sequence = sequence.then(
+            Command::builder()
+                .normal()
+                .program("flatpak")
+                .args(&args)
+                .description("Removing Flatpak apps...")
+                .build(),
+        );
+    }
+
+    let mut files = names(Kind::File);
+    files.retain(|path| manifest::is_tracked(path));
+    if !files.is_empty() {
+        let mut args = vec!["-rf", "--"];
+        args.extend(&files);
+        sequence = sequence.then(
+            Command::builder()
+                .privileged()
+                .program("rm")
+                .args(&args)
+                .description("Removing files...")
+                .build(),
+        );
+    }
+
+    sequence.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(title: &str, timestamp: i64, outcome: Outcome, commands: Vec<Command>) -> Entry {
+        Entry {
+            title: title.to_string(),
+            timestamp,
+            outcome,
+            log: None,
+            commands,
+        }
+    }
+
+    fn names(footprint: &Footprint) -> Vec<(Kind, &str)> {
+        footprint
+            .items
+            .iter()
+            .map(|i| (i.kind, i.name.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn reads_what_commands_install() {
+        let commands = vec![
+            Command::builder()
+                .privileged()
+                .program("pacman")
+                .args(&["-S", "--needed", "--noconfirm", "docker"])
+                .description("Step")
+                .build(),
+            Command::builder()
+                .privileged()
+                .program("sh")
+                .args(&[
+                    "-c",
+                    "systemctl daemon-reload && systemctl enable --now docker.service",
+                ])
+                .description("Step")
+                .build(),
+            Command::builder()
+                .normal()
+                .program("systemctl")
+                .args(&["--user", "enable", "--now", "podman.socket"])
+                .description("Step")
+                .build(),
+        ];
+        let entries = vec![
+            entry("Docker Setup", 1, Outcome::Success, commands),
+            entry("Failed Setup", 2, Outcome::Failed, vec![]),
+        ];
+        let artifacts = vec![Artifact {
+            path: "/usr/local/bin/upd".to_string(),
+            action: "Update Toolkit".to_string(),
+            timestamp: 3,
+            sha256: None,
+        }];
+
+        let footprints = footprints(&entries, &artifacts);
+        assert_eq!(footprints.len(), 2);
+        assert_eq!(names(&footprints[0]), [(Kind::File, "/usr/local/bin/upd")]);
+        assert_eq!(
+            names(&footprints[1]),
+            [
+                (Kind::Package, "docker"),
+                (Kind::Unit, "docker.service"),
+                (Kind::UserUnit, "podman.socket"),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_by_title_and_newest_run() {
+        let install = |package: &str| {
+            vec![Command::builder()
+                .aur()
+                .args(&["-S", "--noconfirm", package])
+                .description("Step")
+                .build()]
+        };
+        let entries = vec![
+            entry("Shell Setup", 1, Outcome::Success, install("zsh")),
+            entry("Theme", 2, Outcome::Success, install("zsh")),
+            entry("Shell Setup", 3, Outcome::Success, install("starship")),
+        ];
+
+        let footprints = footprints(&entries, &[]);
+        assert_eq!(footprints.len(), 2);
+        assert_eq!(footprints[0].title, "Shell Setup");
+        assert_eq!(footprints[0].last_run, 3);
+        assert_eq!(names(&footprints[0]), [(Kind::Package, "starship")]);
+        assert_eq!(names(&footprints[1]), [(Kind::Package, "zsh")]);
+    }
+}
@@ -0,0 +1,110 @@
+//! Pending package and toolkit updates, for the background agent.
+//!
+//! Repository updates come from `checkupdates` (pacman-contrib), which
+//! syncs a private copy of the databases so it's safe to run unprivileged.
+//! AUR updates come from the AUR helper's `-Qua`.
+
+use crate::i18n::{gettext, ngettext_f};
+use crate::{aur, self_update};
+use log::{debug, warn};
+use std::process::Command;
+
+/// Updates found by [`check`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// Number of repository and AUR packages with a newer version.
+    pub packages: usize,
+    /// Whether a newer toolkit is available on the update channel.
+    pub toolkit: bool,
+}
+
+impl Summary {
+    pub fn is_empty(&self) -> bool {
+        self.packages == 0 && !self.toolkit
+    }
+
+    /// Short description, e.g. "12 updates available".
+    pub fn message(&self) -> Option<String> {
+        let count = self.packages.to_string();
+        let args = [("count", count.as_str())];
+        match (self.packages as u32, self.toolkit) {
+            (0, false) => None,
+            (0, true) => Some(gettext("A toolkit update is available")),
+            (n, false) => Some(ngettext_f(
+                "{count} update available",
+                "{count} updates available",
+                n,
+                &args,
+            )),
+            (n, true) => Some(ngettext_f(
+                "{count} update and a toolkit update available",
+                "{count} updates and a toolkit update available",
+                n,
+                &args,
+            )),
+        }
+    }
+}
+
+/// Check for updates. Blocks on the network; run it off the main thread.
+pub fn check() -> Summary {
+    let repo = count_updates_from("checkupdates", &[]);
+    let aur = aur::get()
+        .or_else(aur::detect)
+        .map_or(0, |helper| count_updates_from(helper.name(), &["-Qua"]));
+    let toolkit = self_update::check(self_update::channel()).is_some_and(|c| !c.is_up_to_date());
+
+    let summary = Summary {
+        packages: repo + aur,
+        toolkit,
+    };
+    debug!("Update check: {:?}", summary);
+    summary
+}
+
+/// Number of updates listed by `program`. Both `checkupdates` and `-Qua`
+/// exit non-zero when there's nothing to update, so only output counts.
+fn count_updates_from(program: &str, args: &[&str]) -> usize {
+    match Command::new(program).args(args).output() {
+        Ok(output) => count_updates(&String::from_utf8_lossy(&output.stdout)),
+        Err(e) => {
+            warn!("Failed to run {}: {}", program, e);
+            0
+        }
+    }
+}
+
+/// Count `name old -> new` lines, skipping blank and warning lines.
+fn count_updates(output: &str) -> usize {
+    output.lines().filter(|line| line.contains(" -> ")).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_updates() {
+        let output = "linux 6.9.1-1 -> 6.9.2-1\n\
+                      :: warning: database file for 'extra' does not exist\n\
+                      mesa 1:24.1.0-1 -> 1:24.1.1-1\n\n";
+        assert_eq!(count_updates(output), 2);
+        assert_eq!(count_updates(""), 0);
+    }
+
+    #[test]
+    fn test_summary_message() {
+        let summary = |packages, toolkit| Summary { packages, toolkit }.message();
+        assert_eq!(summary(0, false), None);
+        assert_eq!(summary(1, false).as_deref(), Some("1 update available"));
+        assert_eq!(summary(12, false).as_deref(), Some("12 updates available"));
+        assert_eq!(
+            summary(3, true).as_deref(),
+            Some("3 updates and a toolkit update available")
+        );
+        assert_eq!(
+            summary(0, true).as_deref(),
+            Some("A toolkit update is available")
+        );
+    }
+}
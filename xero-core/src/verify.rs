@@ -0,0 +1,206 @@
+//! Verified downloads of third-party artifacts.
+//!
+//! Scripts, tarballs and packages fetched from outside the distro repos are
+//! pinned in a manifest shipped under the toolkit's sources directory, so
+//! pins get refreshed through the toolkit's own update channel. Anything
+//! without a pin is refused rather than downloaded and run unverified.
+//!
+//! Manifest lines are `<name> <method> <url> <expected>`, where method is:
+//! - `sha256`: `expected` is the hex digest of the file
+//! - `gpg`: `expected` is the primary key fingerprint that must have signed
+//!   `<url>.sig`, checked against pacman's keyring
+
+use crate::config;
+use anyhow::{bail, Context, Result};
+use log::warn;
+use std::path::Path;
+
+/// How a pinned artifact is verified.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Verification {
+    Sha256(String),
+    Gpg { fingerprint: String },
+}
+
+/// A downloadable artifact with its expected checksum or signer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pin {
+    pub name: String,
+    pub url: String,
+    pub verification: Verification,
+}
+
+impl Pin {
+    /// Expected digest, for pins verified by checksum.
+    pub fn sha256(&self) -> Option<&str> {
+        match &self.verification {
+            Verification::Sha256(hash) => Some(hash),
+            Verification::Gpg { .. } => None,
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<Pin> {
+    let mut fields = line.split_whitespace();
+    let name = fields.next()?;
+    let method = fields.next()?;
+    let url = fields.next()?;
+    let expected = fields.next()?;
+    if fields.next().is_some() || !url.starts_with("https://") {
+        return None;
+    }
+
+    let verification = match method {
+        "sha256" if expected.len() == 64 && expected.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Verification::Sha256(expected.to_ascii_lowercase())
+        }
+        "gpg" if expected.len() == 40 && expected.chars().all(|c| c.is_ascii_hexdigit()) => {
+            Verification::Gpg {
+                fingerprint: expected.to_ascii_uppercase(),
+            }
+        }
+        _ => return None,
+    };
+
+    Some(Pin {
+        name: name.to_string(),
+        url: url.to_string(),
+        verification,
+    })
+}
+
+/// Parse a pin manifest. Malformed lines are skipped, which leaves their
+/// artifact unpinned and therefore refused.
+fn parse(text: &str) -> Vec<Pin> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .filter_map(|l| {
+            let pin = parse_line(l);
+            if pin.is_none() {
+                warn!("Ignoring malformed pin: {}", l);
+            }
+            pin
+        })
+        .collect()
+}
+
+/// Look up the pin for an artifact.
+pub fn get(name: &str) -> Result<Pin> {
+    let path = config::paths::pins();
+    let text = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read pinned checksums from {}", path.display()))?;
+
+    match parse(&text).into_iter().find(|p| p.name == name) {
+        Some(pin) => Ok(pin),
+        None => bail!(
+            "No pinned checksum for '{}', refusing to run an unverified download.\n\n\
+             Update the toolkit to get current checksums.",
+            name
+        ),
+    }
+}
+
+/// Check a downloaded file against its expected SHA256 digest, deleting it
+/// on mismatch so it can't be run by accident.
+pub fn check_sha256(path: &Path, expected: &str) -> Result<()> {
+    let output = std::process::Command::new("sha256sum")
+        .arg(path)
+        .output()
+        .context("Failed to run sha256sum")?;
+    if !output.status.success() {
+        bail!("Failed to checksum {}", path.display());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let actual = stdout.split_whitespace().next().unwrap_or_default();
+    if !actual.eq_ignore_ascii_case(expected) {
+        let _ = std::fs::remove_file(path);
+        bail!(
+            "Checksum mismatch for {}\nexpected {}\ngot      {}",
+            path.display(),
+            expected,
+            actual
+        );
+    }
+    Ok(())
+}
+
+/// Shell script that downloads `pin` to `dest` and verifies it. On a
+/// mismatch the file is deleted and the script fails, so a sequence using
+/// it stops before anything runs the payload.
+pub fn fetch_script(pin: &Pin, dest: &str) -> String {
+    let fail = format!(
+        "{{ rm -f {dest} {dest}.sig; echo 'Verification failed for {name}' >&2; exit 1; }}",
+        dest = dest,
+        name = pin.name
+    );
+    let download = format!(
+        "curl -fL --retry 3 --connect-timeout 60 -o {} {}",
+        dest, pin.url
+    );
+
+    match &pin.verification {
+        Verification::Sha256(hash) => format!(
+            "{download} && {{ echo '{hash}  {dest}' | sha256sum --check --strict --quiet || {fail}; }}",
+            download = download,
+            hash = hash,
+            dest = dest,
+            fail = fail
+        ),
+        // VALIDSIG ends with the primary key fingerprint, so subkey
+        // signatures still match the pinned key.
+        Verification::Gpg { fingerprint } => format!(
+            "{download} && curl -fL --retry 3 -o {dest}.sig {url}.sig && \
+             {{ gpg --homedir /etc/pacman.d/gnupg --status-fd 1 --verify {dest}.sig {dest} 2>/dev/null \
+             | grep -q '^\\[GNUPG:\\] VALIDSIG .* {fingerprint}$' || {fail}; }}",
+            download = download,
+            dest = dest,
+            url = pin.url,
+            fingerprint = fingerprint,
+            fail = fail
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pins_and_skips_bad_lines() {
+        let text = "# comment\n\
+            a sha256 https://example.com/a.sh 9F86D081884C7D659A2FEAA0C55AD015A3BF4F1B2B0B822CD15D6C15B0F00A08\n\
+            b gpg https://example.com/b.pkg.tar.zst ef925ea60f33d0cb85c44ad13056513887b78aeb\n\
+            c sha256 https://example.com/c.sh -\n\
+            d sha256 http://example.com/d.sh 9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08\n";
+        let pins = parse(text);
+        assert_eq!(pins.len(), 2);
+        assert_eq!(
+            pins[0].verification,
+            Verification::Sha256(
+                "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08".into()
+            )
+        );
+        assert_eq!(
+            pins[1].verification,
+            Verification::Gpg {
+                fingerprint: "EF925EA60F33D0CB85C44AD13056513887B78AEB".into()
+            }
+        );
+    }
+
+    #[test]
+    fn every_shipped_pin_parses() {
+        let text = include_str!("../../sources/pins/artifacts.pins");
+        let lines: Vec<&str> = text
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('#'))
+            .collect();
+        assert!(!lines.is_empty());
+        for line in lines {
+            assert!(parse_line(line).is_some(), "malformed pin: {}", line);
+        }
+    }
+}
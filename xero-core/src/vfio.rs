@@ -0,0 +1,430 @@
+//! GPU passthrough (VFIO) readiness checks and configuration.
+//!
+//! Reads IOMMU groups from sysfs and the kernel log to tell whether a GPU
+//! can be handed to a VM, and generates the modprobe config and libvirt
+//! hooks that do the handover. The hooks follow the usual single GPU
+//! passthrough recipe: stop the desktop, detach the GPU before the VM
+//! starts, and give it back when the VM shuts down.
+
+use super::cmdline;
+use super::microcode::CpuVendor;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+/// Hook libvirt runs for every QEMU domain event.
+pub const QEMU_HOOK_PATH: &str = "/etc/libvirt/hooks/qemu";
+pub const STARTUP_HOOK_PATH: &str = "/etc/libvirt/hooks/vfio-startup.sh";
+pub const TEARDOWN_HOOK_PATH: &str = "/etc/libvirt/hooks/vfio-teardown.sh";
+
+/// Groups the user needs for VMs with passed-through devices.
+pub const USER_GROUPS: [&str; 3] = ["libvirt", "kvm", "input"];
+
+/// Kernel modules loaded early when a GPU is reserved at boot.
+pub const INITRAMFS_MODULES: [&str; 3] = ["vfio_pci", "vfio", "vfio_iommu_type1"];
+
+/// Host drivers that must wait for vfio-pci to claim reserved devices.
+const HOST_DRIVERS: [&str; 7] = [
+    "amdgpu",
+    "radeon",
+    "nouveau",
+    "nvidia",
+    "i915",
+    "xe",
+    "snd_hda_intel",
+];
+
+/// Runs the startup and teardown hooks only for domains passing through a
+/// PCI device, so other VMs don't take the desktop down.
+pub const QEMU_HOOK: &str = r#"#!/bin/bash
+# Installed by Xero Toolkit: hand the GPU to VMs that pass through PCI devices
+grep -q "<hostdev mode='subsystem' type='pci'" || exit 0
+case "$2/$3" in
+  prepare/begin) /etc/libvirt/hooks/vfio-startup.sh ;;
+  release/end) /etc/libvirt/hooks/vfio-teardown.sh ;;
+esac
+"#;
+
+/// A PCI device in an IOMMU group.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PciDevice {
+    /// Full address, e.g. `0000:01:00.0`.
+    pub address: String,
+    /// PCI class, e.g. `0x030000`.
+    pub class: String,
+    /// `vendor:device`, e.g. `10de:2484`, the form vfio-pci `ids=` takes.
+    pub ids: String,
+    pub description: String,
+    /// Kernel driver bound to the device, if any.
+    pub driver: Option<String>,
+    /// Whether the firmware used this GPU to boot, i.e. the host display.
+    pub boot_vga: bool,
+}
+
+impl PciDevice {
+    pub fn is_gpu(&self) -> bool {
+        self.class.starts_with("0x03")
+    }
+
+    /// GPUs come with HDMI audio and sometimes USB-C functions, which are
+    /// fine to pass through alongside them, as are PCI bridges.
+    fn is_gpu_companion(&self) -> bool {
+        self.is_gpu()
+            || self.class.starts_with("0x0403")
+            || self.class.starts_with("0x0c03")
+            || self.class.starts_with("0x0c80")
+            || self.class.starts_with("0x0604")
+    }
+
+    /// libvirt node device name, e.g. `pci_0000_01_00_0`.
+    pub fn nodedev_name(&self) -> String {
+        format!("pci_{}", self.address.replace([':', '.'], "_"))
+    }
+}
+
+/// An IOMMU group. Devices can only be passed to a VM a whole group at a
+/// time.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IommuGroup {
+    pub id: u32,
+    pub devices: Vec<PciDevice>,
+}
+
+impl IommuGroup {
+    pub fn gpu(&self) -> Option<&PciDevice> {
+        self.devices.iter().find(|d| d.is_gpu())
+    }
+
+    /// Whether the group holds nothing but the GPU and its own functions.
+    pub fn is_isolated(&self) -> bool {
+        self.devices.iter().all(|d| d.is_gpu_companion())
+    }
+
+    /// Devices to detach from the host, bridges excluded.
+    pub fn passthrough_devices(&self) -> impl Iterator<Item = &PciDevice> {
+        self.devices
+            .iter()
+            .filter(|d| !d.class.starts_with("0x0604"))
+    }
+
+    /// Kernel modules driving the GPU, in unload order.
+    fn driver_modules(&self) -> Vec<&str> {
+        match self.gpu().and_then(|g| g.driver.as_deref()) {
+            Some("nvidia") => vec!["nvidia_drm", "nvidia_modeset", "nvidia_uvm", "nvidia"],
+            Some(driver) => vec![driver],
+            None => Vec::new(),
+        }
+    }
+
+    /// `modprobe.d` config reserving the group's devices for vfio-pci at
+    /// boot, ahead of the regular GPU drivers.
+    pub fn modprobe_conf(&self) -> String {
+        let mut ids: Vec<&str> = self.passthrough_devices().map(|d| d.ids.as_str()).collect();
+        ids.dedup();
+        let mut conf = format!("options vfio-pci ids={}\n", ids.join(","));
+        for driver in HOST_DRIVERS {
+            conf.push_str(&format!("softdep {} pre: vfio-pci\n", driver));
+        }
+        conf
+    }
+
+    /// Hook run before a passthrough VM starts: stop the desktop, release
+    /// the consoles and framebuffer, unload the driver and detach the group.
+    pub fn startup_hook(&self) -> String {
+        let mut script = String::from(
+            "#!/bin/bash\n\
+             # Installed by Xero Toolkit: detach the GPU for a passthrough VM\n\
+             set -x\n\
+             systemctl stop display-manager.service\n\
+             for vt in /sys/class/vtconsole/vtcon*/bind; do echo 0 > \"$vt\"; done\n\
+             echo efi-framebuffer.0 > /sys/bus/platform/drivers/efi-framebuffer/unbind 2>/dev/null\n\
+             sleep 2\n",
+        );
+        let modules = self.driver_modules();
+        if !modules.is_empty() {
+            script.push_str(&format!("modprobe -r {}\n", modules.join(" ")));
+        }
+        for device in self.passthrough_devices() {
+            script.push_str(&format!("virsh nodedev-detach {}\n", device.nodedev_name()));
+        }
+        script.push_str("modprobe vfio-pci\n");
+        script
+    }
+
+    /// Hook run after a passthrough VM stops, undoing [`Self::startup_hook`].
+    pub fn teardown_hook(&self) -> String {
+        let mut script = String::from(
+            "#!/bin/bash\n\
+             # Installed by Xero Toolkit: give the GPU back to the host\n\
+             set -x\n",
+        );
+        for device in self.passthrough_devices() {
+            script.push_str(&format!(
+                "virsh nodedev-reattach {}\n",
+                device.nodedev_name()
+            ));
+        }
+        script.push_str("modprobe -r vfio_pci vfio_iommu_type1 vfio\n");
+        let mut modules = self.driver_modules();
+        modules.reverse();
+        if !modules.is_empty() {
+            script.push_str(&format!("modprobe -a {}\n", modules.join(" ")));
+        }
+        script.push_str(
+            "for vt in /sys/class/vtconsole/vtcon*/bind; do echo 1 > \"$vt\"; done\n\
+             echo efi-framebuffer.0 > /sys/bus/platform/drivers/efi-framebuffer/bind 2>/dev/null\n\
+             systemctl start display-manager.service\n",
+        );
+        script
+    }
+}
+
+/// Kernel parameters enabling the IOMMU in passthrough mode.
+pub fn iommu_params(cpu: Option<CpuVendor>) -> [&'static str; 2] {
+    match cpu {
+        Some(CpuVendor::Amd) => ["amd_iommu=on", "iommu=pt"],
+        _ => ["intel_iommu=on", "iommu=pt"],
+    }
+}
+
+/// Device descriptions by address from `lspci -Dnn` output.
+fn parse_lspci(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (address, rest) = line.split_once(' ')?;
+            // "VGA compatible controller [0300]: NVIDIA Corporation ... [10de:2484] (rev a1)"
+            let (_, description) = rest.split_once(": ")?;
+            let description = match description.rfind(" [") {
+                Some(end) => &description[..end],
+                None => description,
+            };
+            Some((address.to_string(), description.to_string()))
+        })
+        .collect()
+}
+
+/// First kernel log line showing the IOMMU came up, if any.
+fn iommu_log_line(log: &str) -> Option<String> {
+    const MARKERS: [&str; 4] = [
+        "DMAR: IOMMU enabled",
+        "AMD-Vi: Interrupt remapping enabled",
+        "AMD-Vi: AMD IOMMUv2",
+        "iommu: Default domain type",
+    ];
+    log.lines()
+        .find(|l| MARKERS.iter().any(|m| l.contains(m)))
+        .map(|l| match l.find("] ") {
+            // Drop the timestamp
+            Some(i) if l.starts_with('[') => l[i + 2..].trim().to_string(),
+            _ => l.trim().to_string(),
+        })
+}
+
+fn read_sysfs(path: &Path) -> String {
+    std::fs::read_to_string(path)
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+}
+
+/// All IOMMU groups, empty when the IOMMU is off. Blocks on `lspci`.
+pub fn iommu_groups() -> Vec<IommuGroup> {
+    let Ok(dir) = std::fs::read_dir("/sys/kernel/iommu_groups") else {
+        return Vec::new();
+    };
+    let descriptions = command_output("lspci", &["-Dnn"])
+        .map(|o| parse_lspci(&o))
+        .unwrap_or_default();
+
+    let mut groups: Vec<IommuGroup> = dir
+        .flatten()
+        .filter_map(|group| {
+            let id = group.file_name().to_str()?.parse().ok()?;
+            let mut devices: Vec<PciDevice> = std::fs::read_dir(group.path().join("devices"))
+                .ok()?
+                .flatten()
+                .map(|entry| {
+                    let path = entry.path();
+                    let address = entry.file_name().to_string_lossy().into_owned();
+                    let vendor = read_sysfs(&path.join("vendor"));
+                    let device = read_sysfs(&path.join("device"));
+                    PciDevice {
+                        class: read_sysfs(&path.join("class")),
+                        ids: format!(
+                            "{}:{}",
+                            vendor.trim_start_matches("0x"),
+                            device.trim_start_matches("0x")
+                        ),
+                        description: descriptions.get(&address).cloned().unwrap_or_default(),
+                        driver: std::fs::read_link(path.join("driver"))
+                            .ok()
+                            .and_then(|d| d.file_name().map(|n| n.to_string_lossy().into_owned())),
+                        boot_vga: read_sysfs(&path.join("boot_vga")) == "1",
+                        address,
+                    }
+                })
+                .collect();
+            devices.sort_by(|a, b| a.address.cmp(&b.address));
+            Some(IommuGroup { id, devices })
+        })
+        .collect();
+    groups.sort_by_key(|g| g.id);
+    groups
+}
+
+/// Result of [`check`].
+#[derive(Clone, Debug)]
+pub struct Readiness {
+    pub cpu: Option<CpuVendor>,
+    /// VT-x / AMD-V is available to the kernel.
+    pub virtualization: bool,
+    /// The IOMMU parameters are on the running kernel's command line.
+    pub iommu_param: bool,
+    /// Kernel log line confirming the IOMMU, when the log is readable.
+    pub iommu_log: Option<String>,
+    pub groups: Vec<IommuGroup>,
+    /// Groups in [`USER_GROUPS`] the user isn't in yet.
+    pub missing_user_groups: Vec<&'static str>,
+    pub hooks_installed: bool,
+}
+
+impl Readiness {
+    /// Whether the IOMMU is active, going by the groups the kernel created.
+    pub fn iommu_active(&self) -> bool {
+        !self.groups.is_empty()
+    }
+
+    /// Groups containing a GPU.
+    pub fn gpu_groups(&self) -> impl Iterator<Item = &IommuGroup> {
+        self.groups.iter().filter(|g| g.gpu().is_some())
+    }
+
+    /// The GPU to pass through: the only one, or the first that isn't
+    /// driving the host display.
+    pub fn target(&self) -> Option<&IommuGroup> {
+        let gpus: Vec<&IommuGroup> = self.gpu_groups().collect();
+        match gpus.as_slice() {
+            [only] => Some(only),
+            _ => gpus
+                .iter()
+                .copied()
+                .find(|g| g.gpu().is_some_and(|d| !d.boot_vga)),
+        }
+    }
+
+    /// Whether the target GPU is the host's only display, so passing it
+    /// through needs the hooks rather than reserving it at boot.
+    pub fn single_gpu(&self) -> bool {
+        self.gpu_groups().count() == 1
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.virtualization
+            && self.iommu_active()
+            && self.target().is_some_and(|g| g.is_isolated())
+            && self.missing_user_groups.is_empty()
+            && (!self.single_gpu() || self.hooks_installed)
+    }
+}
+
+/// Check everything passthrough needs. Runs external tools, so call off
+/// the main thread.
+pub fn check() -> Readiness {
+    let cpu = super::microcode::detect_vendor();
+    let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+    let virtualization = cpuinfo
+        .lines()
+        .filter(|l| l.starts_with("flags"))
+        .any(|l| l.split_whitespace().any(|f| f == "vmx" || f == "svm"));
+
+    let current = cmdline::current();
+    let iommu_param = iommu_params(cpu)
+        .iter()
+        .all(|p| cmdline::has_param(&current, p));
+
+    // dmesg is often restricted to root; the journal may still be readable
+    let iommu_log = command_output("dmesg", &[])
+        .or_else(|| command_output("journalctl", &["-k", "-b", "--no-pager", "-q"]))
+        .and_then(|log| iommu_log_line(&log));
+
+    let user_groups = command_output("id", &["-nG"]).unwrap_or_default();
+    let missing_user_groups = USER_GROUPS
+        .into_iter()
+        .filter(|g| !user_groups.split_whitespace().any(|ug| ug == *g))
+        .collect();
+
+    Readiness {
+        cpu,
+        virtualization,
+        iommu_param,
+        iommu_log,
+        groups: iommu_groups(),
+        missing_user_groups,
+        hooks_installed: Path::new(STARTUP_HOOK_PATH).exists(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(address: &str, class: &str, ids: &str) -> PciDevice {
+        PciDevice {
+            address: address.into(),
+            class: class.into(),
+            ids: ids.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_lspci() {
+        let output = "0000:01:00.0 VGA compatible controller [0300]: NVIDIA Corporation GA104 [GeForce RTX 3070] [10de:2484] (rev a1)\n";
+        assert_eq!(
+            parse_lspci(output).get("0000:01:00.0").map(String::as_str),
+            Some("NVIDIA Corporation GA104 [GeForce RTX 3070]")
+        );
+    }
+
+    #[test]
+    fn test_iommu_log_line() {
+        let log = "[    0.000000] Linux version 6.12\n[    0.045000] DMAR: IOMMU enabled\n";
+        assert_eq!(iommu_log_line(log).as_deref(), Some("DMAR: IOMMU enabled"));
+        assert_eq!(iommu_log_line("[    0.0] nothing here\n"), None);
+    }
+
+    #[test]
+    fn test_group_config() {
+        let mut gpu = device("0000:01:00.0", "0x030000", "10de:2484");
+        gpu.driver = Some("nvidia".into());
+        let group = IommuGroup {
+            id: 14,
+            devices: vec![
+                device("0000:00:01.0", "0x060400", "8086:1901"),
+                gpu,
+                device("0000:01:00.1", "0x040300", "10de:228b"),
+            ],
+        };
+        assert!(group.is_isolated());
+        assert!(group
+            .modprobe_conf()
+            .starts_with("options vfio-pci ids=10de:2484,10de:228b\n"));
+
+        let startup = group.startup_hook();
+        assert!(startup.contains("modprobe -r nvidia_drm nvidia_modeset nvidia_uvm nvidia\n"));
+        assert!(startup.contains("virsh nodedev-detach pci_0000_01_00_1\n"));
+        assert!(!startup.contains("pci_0000_00_01_0"));
+        assert!(group
+            .teardown_hook()
+            .contains("modprobe -a nvidia nvidia_uvm nvidia_modeset nvidia_drm\n"));
+    }
+}
@@ -0,0 +1,85 @@
+//! Hypervisor detection for systems running as a VM guest.
+//!
+//! `systemd-detect-virt` names the hypervisor, which decides the guest
+//! agents that give clipboard sharing, display resizing and clean shutdown.
+
+use std::process::Command;
+
+/// Hypervisors with guest agents in the Arch repos.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Hypervisor {
+    VirtualBox,
+    /// KVM or plain QEMU, usually through libvirt.
+    Kvm,
+    Vmware,
+    HyperV,
+}
+
+impl Hypervisor {
+    /// Map a `systemd-detect-virt` id.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id.trim() {
+            "oracle" => Some(Hypervisor::VirtualBox),
+            "kvm" | "qemu" => Some(Hypervisor::Kvm),
+            "vmware" => Some(Hypervisor::Vmware),
+            "microsoft" => Some(Hypervisor::HyperV),
+            _ => None,
+        }
+    }
+
+    /// Human readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hypervisor::VirtualBox => "VirtualBox",
+            Hypervisor::Kvm => "KVM / QEMU",
+            Hypervisor::Vmware => "VMware",
+            Hypervisor::HyperV => "Hyper-V",
+        }
+    }
+
+    /// Guest agent packages.
+    pub fn packages(&self) -> &'static [&'static str] {
+        match self {
+            Hypervisor::VirtualBox => &["virtualbox-guest-utils"],
+            Hypervisor::Kvm => &["qemu-guest-agent", "spice-vdagent"],
+            Hypervisor::Vmware => &["open-vm-tools", "gtkmm3"],
+            Hypervisor::HyperV => &["hyperv"],
+        }
+    }
+
+    /// Services to enable once the packages are installed.
+    pub fn services(&self) -> &'static [&'static str] {
+        match self {
+            Hypervisor::VirtualBox => &["vboxservice.service"],
+            Hypervisor::Kvm => &["qemu-guest-agent.service", "spice-vdagentd.socket"],
+            Hypervisor::Vmware => &["vmtoolsd.service", "vmware-vmblock-fuse.service"],
+            Hypervisor::HyperV => &["hv_kvp_daemon.service", "hv_vss_daemon.service"],
+        }
+    }
+}
+
+/// The hypervisor this system runs under, `None` on bare metal or an
+/// unsupported hypervisor.
+pub fn detect() -> Option<Hypervisor> {
+    let output = Command::new("systemd-detect-virt")
+        .arg("--vm")
+        .output()
+        .ok()?;
+    Hypervisor::from_id(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_id() {
+        assert_eq!(
+            Hypervisor::from_id("oracle\n"),
+            Some(Hypervisor::VirtualBox)
+        );
+        assert_eq!(Hypervisor::from_id("qemu"), Some(Hypervisor::Kvm));
+        assert_eq!(Hypervisor::from_id("microsoft"), Some(Hypervisor::HyperV));
+        assert_eq!(Hypervisor::from_id("none"), None);
+    }
+}
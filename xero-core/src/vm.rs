@@ -0,0 +1,174 @@
+//! Windows 11 virtual machines for libvirt.
+//!
+//! Builds the `virt-install` call for a VM that meets the Windows 11
+//! requirements (TPM 2.0, UEFI with Secure Boot) with virtio disk and
+//! network, sized from the host's memory and cores.
+
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+
+/// Stable virtio-win driver ISO published by the Fedora virt team.
+pub const VIRTIO_WIN_URL: &str =
+    "https://fedorapeople.org/groups/virt/virtio-win/direct-downloads/stable-virtio/virtio-win.iso";
+
+/// The default storage pool. ISOs are placed here because the QEMU process
+/// can't read files inside home directories.
+pub const IMAGES_DIR: &str = "/var/lib/libvirt/images";
+
+/// Smallest values Windows 11 setup accepts.
+const MIN_MEMORY_MIB: u32 = 4096;
+const MIN_VCPUS: u32 = 2;
+const MIN_DISK_GB: u32 = 64;
+
+/// A Windows 11 VM to create.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Win11Vm {
+    pub name: String,
+    /// Windows installation ISO as picked by the user.
+    pub iso: PathBuf,
+    pub memory_mib: u32,
+    pub vcpus: u32,
+    pub disk_gb: u32,
+}
+
+impl Win11Vm {
+    /// A VM using half the host's memory and cores, within what Windows
+    /// needs and what's useful for a desktop guest.
+    pub fn with_defaults(iso: PathBuf, host_memory_mib: u64, host_cpus: usize) -> Self {
+        let memory = (host_memory_mib / 2 / 1024 * 1024).clamp(MIN_MEMORY_MIB as u64, 16384);
+        Self {
+            name: "win11".to_string(),
+            iso,
+            memory_mib: memory as u32,
+            vcpus: (host_cpus as u32 / 2).clamp(MIN_VCPUS, 8),
+            disk_gb: 80,
+        }
+    }
+
+    /// Check the values are ones Windows setup and libvirt accept.
+    pub fn validate(&self) -> Result<()> {
+        if self.name.is_empty()
+            || !self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            bail!("The VM name may only contain letters, numbers, '-', '_' and '.'");
+        }
+        if self.memory_mib < MIN_MEMORY_MIB {
+            bail!("Windows 11 needs at least {} MiB of memory", MIN_MEMORY_MIB);
+        }
+        if self.vcpus < MIN_VCPUS {
+            bail!("Windows 11 needs at least {} CPU cores", MIN_VCPUS);
+        }
+        if self.disk_gb < MIN_DISK_GB {
+            bail!("Windows 11 needs a disk of at least {} GB", MIN_DISK_GB);
+        }
+        Ok(())
+    }
+
+    /// Where the Windows ISO is copied so QEMU can read it.
+    pub fn pool_iso(&self) -> PathBuf {
+        if self.iso.starts_with(IMAGES_DIR) {
+            return self.iso.clone();
+        }
+        let file = self
+            .iso
+            .file_name()
+            .map(|f| f.to_os_string())
+            .unwrap_or_else(|| "windows.iso".into());
+        Path::new(IMAGES_DIR).join(file)
+    }
+
+    pub fn virtio_iso() -> PathBuf {
+        Path::new(IMAGES_DIR).join("virtio-win.iso")
+    }
+
+    /// Arguments for `virt-install`. The VM boots the installer with the
+    /// virtio drivers attached as a second CD, ready for "Load driver".
+    pub fn virt_install_args(&self) -> Vec<String> {
+        let cdrom = |path: PathBuf| format!("path={},device=cdrom,bus=sata", path.display());
+        [
+            "--connect",
+            "qemu:///system",
+            "--name",
+            &self.name,
+            "--osinfo",
+            "win11",
+            "--memory",
+            &self.memory_mib.to_string(),
+            "--vcpus",
+            &self.vcpus.to_string(),
+            "--cpu",
+            "host-passthrough",
+            "--disk",
+            &format!("size={},bus=virtio,format=qcow2", self.disk_gb),
+            "--disk",
+            &cdrom(self.pool_iso()),
+            "--disk",
+            &cdrom(Self::virtio_iso()),
+            "--network",
+            "network=default,model=virtio",
+            "--tpm",
+            "backend.type=emulator,backend.version=2.0,model=tpm-crb",
+            "--boot",
+            "uefi,firmware.feature0.name=secure-boot,firmware.feature0.enabled=yes,\
+             firmware.feature1.name=enrolled-keys,firmware.feature1.enabled=yes,\
+             cdrom,hd",
+            "--graphics",
+            "spice",
+            "--noautoconsole",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+}
+
+/// Total memory of the host in MiB.
+pub fn host_memory_mib() -> u64 {
+    std::fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|info| {
+            info.lines()
+                .find_map(|l| l.strip_prefix("MemTotal:"))
+                .and_then(|v| v.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+        })
+        .map(|kb| kb / 1024)
+        .unwrap_or(8192)
+}
+
+/// Logical CPUs of the host.
+pub fn host_cpus() -> usize {
+    std::thread::available_parallelism().map_or(4, |n| n.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_defaults() {
+        let vm = Win11Vm::with_defaults("/home/user/Win11.iso".into(), 31_900, 16);
+        assert_eq!((vm.memory_mib, vm.vcpus), (15360, 8));
+        assert!(vm.validate().is_ok());
+
+        let small = Win11Vm::with_defaults("/tmp/w.iso".into(), 6_000, 2);
+        assert_eq!((small.memory_mib, small.vcpus), (4096, 2));
+
+        let bad = Win11Vm {
+            name: "my vm".into(),
+            ..small
+        };
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn test_virt_install_args() {
+        let vm = Win11Vm::with_defaults("/home/user/Win11.iso".into(), 16_000, 8);
+        let args = vm.virt_install_args().join(" ");
+        assert!(args.contains("--disk path=/var/lib/libvirt/images/Win11.iso,device=cdrom"));
+        assert!(args.contains("--tpm backend.type=emulator,backend.version=2.0"));
+        assert!(args.contains("firmware.feature0.name=secure-boot"));
+    }
+}